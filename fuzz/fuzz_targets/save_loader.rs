@@ -0,0 +1,18 @@
+//! Feeds arbitrary bytes to the save loader. Any save it accepts must be safe to build the menus
+//! and stats from.
+
+#![no_main]
+
+use dispair::common::savestore::parse_save;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(json) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(player_state) = parse_save(json) {
+        let _ = player_state.preview_upgrade("42");
+        let _ = player_state.is_fresh();
+    }
+});
@@ -0,0 +1,28 @@
+//! Feeds arbitrary bytes to the upgrade tree parser, and prices every upgrade in any tree it
+//! accepts.
+
+#![no_main]
+
+use dispair::common::upgrades::upgrade::{UpgradeNode, parse_upgrade_tree};
+use libfuzzer_sys::fuzz_target;
+
+fn price_all(nodes: &[UpgradeNode]) {
+    for node in nodes {
+        let _ = node.get_raw_title();
+        let _ = node.bulk_cost(0, node.limit.min(100));
+
+        if let Some(children) = &node.children {
+            price_all(children);
+        }
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(json) = std::str::from_utf8(data) else {
+        return;
+    };
+
+    if let Ok(upgrade_tree) = parse_upgrade_tree(json) {
+        price_all(&upgrade_tree);
+    }
+});
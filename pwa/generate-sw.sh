@@ -0,0 +1,24 @@
+#!/bin/sh
+# Generates sw.js in trunk's staging directory from pwa/sw.template.js.
+#
+# Trunk gives the wasm, js and other assets hashed names, so the list of files is enough to tell
+# builds apart. The cache name is a hash of the files' contents, which makes a new build replace
+# the old cache as soon as the new service worker activates.
+set -eu
+
+staging="${TRUNK_STAGING_DIR:?run this from a trunk hook}"
+template="$(dirname "$0")/sw.template.js"
+
+cd "$staging"
+
+files=$(find . -type f ! -name sw.js | sed 's|^\./||' | sort)
+
+version=$(printf '%s\n' $files | xargs cat | sha256sum | cut -c1-16)
+
+precache=$(printf '    "./%s",\n' $files)
+
+{
+    printf 'const CACHE_NAME = "dispair-%s";\n' "$version"
+    printf 'const PRECACHE = [\n    "./",\n%s\n];\n\n' "$precache"
+    cat "$template"
+} > sw.js
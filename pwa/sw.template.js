@@ -0,0 +1,69 @@
+// CACHE_NAME and PRECACHE are written above this by generate-sw.sh.
+
+const CACHE_PREFIX = "dispair-";
+
+// hosts that are only worth reaching while online, such as analytics
+const NETWORK_ONLY_HOSTS = ["i.posthog.com"];
+
+self.addEventListener("install", (event) => {
+    event.waitUntil(
+        caches
+            .open(CACHE_NAME)
+            .then((cache) => cache.addAll(PRECACHE))
+            .then(() => self.skipWaiting()),
+    );
+});
+
+self.addEventListener("activate", (event) => {
+    // drop the caches of older builds
+    event.waitUntil(
+        caches
+            .keys()
+            .then((keys) =>
+                Promise.all(
+                    keys
+                        .filter((key) => key.startsWith(CACHE_PREFIX) && key !== CACHE_NAME)
+                        .map((key) => caches.delete(key)),
+                ),
+            )
+            .then(() => self.clients.claim()),
+    );
+});
+
+self.addEventListener("fetch", (event) => {
+    const request = event.request;
+    const url = new URL(request.url);
+
+    if (
+        request.method !== "GET" ||
+        NETWORK_ONLY_HOSTS.some((host) => url.hostname.endsWith(host))
+    ) {
+        return;
+    }
+
+    if (request.mode === "navigate") {
+        // pages come from the network when possible, so a new build is picked up straight away
+        event.respondWith(
+            fetch(request).catch(() =>
+                caches.match("./", { ignoreSearch: true }),
+            ),
+        );
+        return;
+    }
+
+    // assets have hashed names, so a cached copy is never out of date. anything else, like the
+    // web font, is cached the first time it's fetched.
+    event.respondWith(
+        caches.match(request).then(
+            (cached) =>
+                cached ||
+                fetch(request).then((response) => {
+                    if (response.ok || response.type === "opaque") {
+                        const copy = response.clone();
+                        caches.open(CACHE_NAME).then((cache) => cache.put(request, copy));
+                    }
+                    return response;
+                }),
+        ),
+    );
+});
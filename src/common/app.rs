@@ -0,0 +1,480 @@
+//! This module provides `AppCore`, the app's flow between the main menu, the game and the error
+//! screen. The terminal and WASM apps are thin shells around it that pass their events on and
+//! draw it, with `Platform` covering the things they do their own way.
+
+use ratatui::{Frame, text::Line};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use crate::common::Goto;
+use crate::common::apperror::{AppError, ErrorChoice};
+use crate::common::game::Game;
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::mainmenu::{MainMenu, MenuChoice};
+use crate::common::popups::errorscreen::ErrorScreen;
+use crate::common::popups::helpoverlay::render_help;
+use crate::common::render::apply_theme;
+use crate::common::settings::setting::Theme;
+use crate::common::sound::SoundWrangler;
+use crate::common::upgrades::upgrade::PlayerState;
+use crate::prelude::{KeyCode, KeyEvent};
+
+/// What each platform does its own way: where progress is saved, and how it sets up new games.
+pub trait Platform {
+    /// Saves the player's progress.
+    ///
+    /// # Errors
+    ///
+    /// Errors with `AppError::Save` if the progress couldn't be saved.
+    fn save(&mut self, player_state: &PlayerState) -> Result<(), AppError>;
+
+    /// Loads the player's saved progress.
+    ///
+    /// # Errors
+    ///
+    /// Errors with `AppError::Load` if there's no save, or it couldn't be read.
+    fn load(&mut self) -> Result<PlayerState, AppError>;
+
+    /// Checks whether there's a save to continue from.
+    ///
+    /// # Errors
+    ///
+    /// Errors with `AppError::Load` if there's a save, but it's damaged.
+    fn check_save(&mut self) -> Result<bool, AppError>;
+
+    /// Sets up a game that's about to start, returning the seed of a run to go straight into if
+    /// the game was launched with one.
+    fn prepare_game(&mut self, _player_state: &mut PlayerState) -> Option<u64> {
+        None
+    }
+
+    /// Hears how a save went, returning the failure if it should go on the error screen.
+    /// Platforms that retry failed saves in the background can keep the failure to themselves.
+    ///
+    /// # Errors
+    ///
+    /// Errors with the failure the player should be shown.
+    fn on_saved(&mut self, result: Result<(), AppError>) -> Result<(), AppError> {
+        result
+    }
+
+    /// Whether saves are failing, so new runs wait for the player to say they'll go without.
+    fn is_save_failing(&self) -> bool {
+        false
+    }
+
+    /// Whether the menu should keep looking for a save while there isn't one, for platforms
+    /// whose saves are read in the background.
+    fn should_recheck_save(&self) -> bool {
+        false
+    }
+
+    /// The theme the main menu is drawn in, before a save's settings are loaded.
+    fn get_menu_theme(&self) -> Theme {
+        Theme::default()
+    }
+
+    /// Called when the player is back on the main menu after a game, once it's been saved.
+    fn on_menu(&mut self) {}
+}
+
+/// A run that's waiting for the last one to be saved before it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeldStart {
+    /// The game is about to leave its current view for a new run.
+    Run,
+    /// A new game was picked from the main menu.
+    NewGame,
+}
+
+/// The app's state, shared by both platforms.
+pub struct AppCore<P: Platform> {
+    pub platform: P,
+    game: Option<Game>,
+    /// The latest progress while there's no game running, to save or start again from.
+    player_state: Option<PlayerState>,
+    pub menu: MainMenu,
+    /// The error screen, while something that went wrong is being shown.
+    error_screen: Option<ErrorScreen>,
+    /// The sound for the game, once the platform allows it to play.
+    pub sound_wrangler: Option<Rc<RefCell<SoundWrangler>>>,
+    /// A run held back until the last one is saved, while the player is warned about it.
+    held_start: Option<HeldStart>,
+    /// Whether the player chose to start the held run without waiting for the save.
+    start_anyway: bool,
+    /// Whether the help overlay is open over the warning about the held run.
+    show_held_help: bool,
+}
+
+impl<P: Platform> AppCore<P> {
+    /// Creates the app on the main menu, offering `choices`.
+    #[must_use]
+    pub fn new(platform: P, choices: Vec<MenuChoice>) -> Self {
+        let mut out = Self {
+            platform,
+            game: None,
+            player_state: None,
+            menu: MainMenu::new(choices),
+            error_screen: None,
+            sound_wrangler: None,
+            held_start: None,
+            start_anyway: false,
+            show_held_help: false,
+        };
+
+        out.check_save();
+
+        out
+    }
+
+    #[must_use]
+    pub fn get_game(&self) -> Option<&Game> {
+        self.game.as_ref()
+    }
+
+    pub fn get_game_mut(&mut self) -> Option<&mut Game> {
+        self.game.as_mut()
+    }
+
+    /// Whether a run is being held back until the last one is saved.
+    #[must_use]
+    pub fn is_holding_start(&self) -> bool {
+        self.held_start.is_some()
+    }
+
+    /// Whether the main menu is what the player is looking at.
+    #[must_use]
+    pub fn is_on_menu(&self) -> bool {
+        self.game.is_none() && self.error_screen.is_none()
+    }
+
+    /// Handles a key press, returning whether the player asked to quit.
+    pub fn handle_key_event(&mut self, key_event: &KeyEvent) -> bool {
+        if let Some(error_screen) = &mut self.error_screen {
+            if let Some(choice) = error_screen.handle_key_event(key_event) {
+                self.handle_error_choice(choice);
+            }
+        } else if self.held_start.is_some() {
+            let action = KeyContext::HeldStart.get_action(&key_event.code);
+
+            if self.show_held_help {
+                if action == Some(Action::Help) || key_event.code == KeyCode::Esc {
+                    self.show_held_help = false;
+                }
+            } else if let Some(action) = action {
+                self.handle_held_action(action);
+            }
+        } else if let Some(game) = &mut self.game {
+            game.handle_key_event(key_event);
+        } else {
+            match self.menu.handle_key_event(key_event) {
+                Some(MenuChoice::NewGame) if self.platform.is_save_failing() => {
+                    self.held_start = Some(HeldStart::NewGame);
+                }
+                Some(MenuChoice::NewGame) => self.start_game(PlayerState::default()),
+                Some(MenuChoice::Continue) => self.continue_game(),
+                Some(MenuChoice::Quit) => return true,
+                None => {}
+            }
+        }
+
+        false
+    }
+
+    /// Loads the save and starts the game from it, or shows why it couldn't be loaded.
+    fn continue_game(&mut self) {
+        match self.platform.load() {
+            Ok(player_state) => self.start_game(player_state),
+            Err(err) => self.show_error(err),
+        }
+    }
+
+    /// Starts the game from `player_state`, set up the way the platform was launched.
+    fn start_game(&mut self, mut player_state: PlayerState) {
+        let seed = self.platform.prepare_game(&mut player_state);
+        // kept first, so a game that fails to start can be tried again from the same progress
+        self.player_state = Some(player_state.clone());
+
+        let mut game = match Game::new(
+            player_state,
+            self.sound_wrangler.clone().unwrap_or_default(),
+        ) {
+            Ok(game) => game,
+            Err(err) => {
+                self.show_error(err);
+                return;
+            }
+        };
+
+        // challenges go straight into a run on the shared map
+        if let Some(seed) = seed {
+            game.go_to(&Goto::Retry(seed));
+        }
+
+        self.game = Some(game);
+    }
+
+    /// Saves the latest progress, from the game if one is running.
+    pub fn save(&mut self) {
+        let player_state = self
+            .game
+            .as_ref()
+            .map(Game::get_player_state)
+            .or_else(|| self.player_state.clone());
+
+        if let Some(player_state) = player_state {
+            let result = self.platform.save(&player_state);
+            self.report_saved(result);
+        }
+    }
+
+    /// Lets the platform know how a save went, showing the failure if it's left to the player.
+    fn report_saved(&mut self, result: Result<(), AppError>) {
+        if let Err(err) = self.platform.on_saved(result) {
+            self.show_error(err);
+        }
+    }
+
+    /// Handles an action on the warning about the held run.
+    fn handle_held_action(&mut self, action: Action) {
+        match action {
+            Action::Confirm => self.start_held(),
+            Action::Back => self.cancel_held_start(),
+            Action::Help => self.show_held_help = true,
+            _ => {}
+        }
+    }
+
+    /// Starts the run that was waiting for the last one to be saved.
+    fn start_held(&mut self) {
+        self.show_held_help = false;
+
+        match self.held_start.take() {
+            Some(HeldStart::Run) => self.start_anyway = true,
+            Some(HeldStart::NewGame) => self.start_game(PlayerState::default()),
+            None => {}
+        }
+    }
+
+    /// Gives up on the held run, going back to the main menu if it was being started from the
+    /// game.
+    fn cancel_held_start(&mut self) {
+        self.show_held_help = false;
+
+        if let Some(HeldStart::Run) = self.held_start.take()
+            && let Some(game) = self.game.take()
+        {
+            self.player_state = Some(game.get_player_state());
+        }
+    }
+
+    fn show_error(&mut self, error: AppError) {
+        self.error_screen = Some(ErrorScreen::new(error));
+    }
+
+    /// Recovers from the error on the error screen the way the player chose.
+    fn handle_error_choice(&mut self, choice: ErrorChoice) {
+        let Some(error_screen) = self.error_screen.take() else {
+            return;
+        };
+
+        match (error_screen.error, choice) {
+            (AppError::Load(_), ErrorChoice::Retry) => self.continue_game(),
+            (AppError::Save(_), ErrorChoice::Retry) => {
+                self.save();
+                self.check_save();
+            }
+            // a running game opens the upgrades again on its next tick
+            (AppError::UpgradeTree(_), ErrorChoice::Retry) if self.game.is_none() => {
+                self.start_game(self.player_state.clone().unwrap_or_default());
+            }
+            (_, ErrorChoice::StartFresh) => self.start_game(PlayerState::default()),
+            (_, ErrorChoice::Menu) => self.game = None,
+            _ => {}
+        }
+    }
+
+    /// Moves the game on, and goes back to the main menu once the player leaves it.
+    pub fn on_tick(&mut self) {
+        if self.held_start.is_some() && !self.platform.is_save_failing() {
+            self.start_held();
+        }
+
+        // everything waits while the player decides what to do about an error or an unsaved run
+        if self.error_screen.is_some() || self.held_start.is_some() {
+            return;
+        }
+
+        let Some(game) = &mut self.game else {
+            if !self.menu.save_exists && self.platform.should_recheck_save() {
+                self.check_save();
+            }
+            return;
+        };
+
+        let start_anyway = std::mem::take(&mut self.start_anyway);
+        if game.is_starting_run() && self.platform.is_save_failing() && !start_anyway {
+            self.held_start = Some(HeldStart::Run);
+            return;
+        }
+
+        game.on_tick();
+        let saved = game.take_saved();
+        let error = game.take_error();
+        let left = *game.get_goto() == Goto::Menu;
+
+        if saved {
+            self.report_saved(Ok(()));
+        }
+
+        match error {
+            Some(err @ AppError::Save(_)) => self.report_saved(Err(err)),
+            Some(err) => self.show_error(err),
+            None if left => self.leave_game(),
+            None => {}
+        }
+    }
+
+    /// Goes back to the main menu from the game, saving on the way.
+    fn leave_game(&mut self) {
+        if let Some(game) = self.game.take() {
+            self.player_state = Some(game.get_player_state());
+        }
+        self.save();
+        self.check_save();
+        self.platform.on_menu();
+    }
+
+    pub fn on_frame(&mut self) {
+        if let Some(game) = &mut self.game {
+            game.on_frame();
+        }
+    }
+
+    /// Updates whether there's a save to continue from, showing the error screen if there's one
+    /// but it's damaged. A missing save isn't an error, it just means there's nothing to
+    /// continue.
+    pub fn check_save(&mut self) {
+        match self.platform.check_save() {
+            Ok(exists) => self.menu.save_exists = exists,
+            Err(err) => {
+                self.menu.save_exists = false;
+
+                // an error already showing, like a failed save, is what the player needs to see
+                if self.error_screen.is_none() {
+                    tracing::warn!(%err, "save is damaged");
+                    self.show_error(err);
+                }
+            }
+        }
+    }
+
+    /// Draws the game, or the main menu with `footer` under its options, and the error screen
+    /// over either.
+    pub fn render(&mut self, frame: &mut Frame, footer: Option<Line>) {
+        if let Some(game) = &mut self.game {
+            game.render(frame);
+        } else {
+            self.menu.render(frame, footer);
+            apply_theme(frame.buffer_mut(), self.platform.get_menu_theme());
+        }
+
+        if let Some(error_screen) = &mut self.error_screen {
+            error_screen.render(frame);
+        }
+    }
+
+    /// Draws the help over the warning about the held run, if the player has opened it.
+    pub fn render_held_help(&self, frame: &mut Frame) {
+        if self.held_start.is_some() && self.show_held_help {
+            render_help(frame, KeyContext::HeldStart);
+        }
+    }
+}
+
+#[cfg(all(test, not(target_family = "wasm")))]
+mod tests {
+    use crate::common::app::{AppCore, Platform};
+    use crate::common::apperror::AppError;
+    use crate::common::keybinds::{Action, KeyContext};
+    use crate::common::mainmenu::MenuChoice;
+    use crate::common::upgrades::upgrade::PlayerState;
+    use crate::prelude::KeyEvent;
+
+    /// Keeps the save in memory, and can be told to fail every save.
+    #[derive(Default)]
+    struct MemoryPlatform {
+        save: Option<PlayerState>,
+        failing: bool,
+    }
+
+    impl Platform for MemoryPlatform {
+        fn save(&mut self, player_state: &PlayerState) -> Result<(), AppError> {
+            if self.failing {
+                return Err(AppError::Save("the disk is full".into()));
+            }
+            self.save = Some(player_state.clone());
+            Ok(())
+        }
+
+        fn load(&mut self) -> Result<PlayerState, AppError> {
+            self.save
+                .clone()
+                .ok_or_else(|| AppError::Load("there's no save".into()))
+        }
+
+        fn check_save(&mut self) -> Result<bool, AppError> {
+            Ok(self.save.is_some())
+        }
+
+        fn is_save_failing(&self) -> bool {
+            self.failing
+        }
+    }
+
+    /// Presses the key bound to `action` in `context`.
+    fn press(app: &mut AppCore<MemoryPlatform>, context: KeyContext, action: Action) -> bool {
+        let code = context.get_key(action).unwrap();
+        app.handle_key_event(&KeyEvent::from(code))
+    }
+
+    fn new_core(platform: MemoryPlatform) -> AppCore<MemoryPlatform> {
+        let mut core = AppCore::new(platform, vec![MenuChoice::NewGame, MenuChoice::Continue]);
+        // finishes typing out the title
+        press(&mut core, KeyContext::MainMenu, Action::Confirm);
+        core
+    }
+
+    #[test]
+    fn new_game_starts_from_the_menu() {
+        let mut core = new_core(MemoryPlatform::default());
+
+        assert!(!press(&mut core, KeyContext::MainMenu, Action::Confirm));
+        assert!(core.get_game().is_some());
+    }
+
+    #[test]
+    fn new_game_waits_while_saves_are_failing() {
+        let mut core = new_core(MemoryPlatform {
+            failing: true,
+            ..MemoryPlatform::default()
+        });
+
+        press(&mut core, KeyContext::MainMenu, Action::Confirm);
+        assert!(core.is_holding_start() && core.get_game().is_none());
+
+        // the help opens over the warning, and keys go to it until it's closed
+        press(&mut core, KeyContext::HeldStart, Action::Help);
+        press(&mut core, KeyContext::HeldStart, Action::Confirm);
+        assert!(core.is_holding_start() && core.show_held_help);
+        press(&mut core, KeyContext::HeldStart, Action::Help);
+
+        press(&mut core, KeyContext::HeldStart, Action::Back);
+        assert!(!core.is_holding_start() && core.is_on_menu());
+
+        press(&mut core, KeyContext::MainMenu, Action::Confirm);
+        core.platform.failing = false;
+        core.on_tick();
+        assert!(core.get_game().is_some());
+    }
+}
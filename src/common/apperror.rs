@@ -0,0 +1,75 @@
+//! This module defines `AppError`, the failures in the app's flow that the player is told about
+//! on the error screen, rather than the game panicking or quietly starting over.
+
+use std::fmt::{self, Display};
+
+/// Something that went wrong outside of a run, which the player can choose how to recover from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppError {
+    /// There is a save, but it couldn't be read.
+    Load(String),
+    /// Progress couldn't be saved.
+    Save(String),
+    /// The upgrade tree built into the game couldn't be read.
+    UpgradeTree(String),
+}
+
+/// A way to recover from an `AppError`, picked on the error screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorChoice {
+    /// Tries whatever failed again.
+    Retry,
+    /// Throws away the save and starts a new game.
+    StartFresh,
+    /// Gives up and goes back to the main menu.
+    Menu,
+}
+
+impl AppError {
+    #[must_use]
+    pub fn get_title(&self) -> &'static str {
+        match self {
+            AppError::Load(_) => "your save couldn't be loaded",
+            AppError::Save(_) => "your progress couldn't be saved",
+            AppError::UpgradeTree(_) => "the upgrades couldn't be read",
+        }
+    }
+
+    /// Returns the ways the player can recover from this error, in the order they're offered.
+    #[must_use]
+    pub fn get_choices(&self) -> &'static [ErrorChoice] {
+        match self {
+            AppError::Load(_) => &[
+                ErrorChoice::Retry,
+                ErrorChoice::StartFresh,
+                ErrorChoice::Menu,
+            ],
+            AppError::Save(_) | AppError::UpgradeTree(_) => {
+                &[ErrorChoice::Retry, ErrorChoice::Menu]
+            }
+        }
+    }
+
+    /// Returns the label for `choice`, which can depend on what failed.
+    #[must_use]
+    pub fn get_choice_label(&self, choice: ErrorChoice) -> &'static str {
+        match (self, choice) {
+            (_, ErrorChoice::Retry) => "try again",
+            (_, ErrorChoice::StartFresh) => "start a new game, replacing the save",
+            (AppError::Save(_), ErrorChoice::Menu) => "back to the menu without saving",
+            (_, ErrorChoice::Menu) => "back to the menu",
+        }
+    }
+}
+
+impl Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AppError::Load(reason) | AppError::Save(reason) | AppError::UpgradeTree(reason) => {
+                write!(f, "{}: {reason}", self.get_title())
+            }
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
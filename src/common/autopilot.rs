@@ -0,0 +1,124 @@
+//! This module provides the autopilot, which steers the character during a run. It scores each
+//! step by a potential field: enemies push the character away, pickups pull it in, and the map
+//! edges push it back towards open ground. It's used for soak testing and as an idle mode.
+
+use crate::common::coords::{Direction, Position};
+use crate::prelude::KeyCode;
+
+/// Steers the character by following a potential field over enemies and pickups.
+#[derive(Debug, Clone, Default)]
+pub struct AutoPilot;
+
+impl AutoPilot {
+    pub const TOGGLE_KEY: KeyCode = KeyCode::Char('p');
+
+    /// How far away enemies are felt.
+    const ENEMY_RANGE: f64 = 8.;
+    /// How far away pickups are felt.
+    const PICKUP_RANGE: f64 = 25.;
+    const ENEMY_WEIGHT: f64 = 4.;
+    const PICKUP_WEIGHT: f64 = 1.;
+    /// How many cells from the map edge the character starts being pushed away from it.
+    const EDGE_MARGIN: f64 = 2.;
+    const EDGE_WEIGHT: f64 = 1.;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Picks the step with the best score from `position`, or `None` if staying put scores best.
+    #[must_use]
+    pub fn choose_direction(
+        &self,
+        position: &Position,
+        enemies: &[Position],
+        pickups: &[Position],
+        width: i32,
+        height: i32,
+    ) -> Option<Direction> {
+        let score_at =
+            |candidate: &Position| Self::get_score(candidate, enemies, pickups, width, height);
+
+        let mut best = None;
+        let mut best_score = score_at(position);
+
+        for direction in [
+            Direction::UP,
+            Direction::DOWN,
+            Direction::LEFT,
+            Direction::RIGHT,
+        ] {
+            let candidate = direction.step_from(position);
+            let (x, y) = candidate.get();
+
+            if x < 0 || y < 0 || x >= width || y >= height || enemies.contains(&candidate) {
+                continue;
+            }
+
+            let score = score_at(&candidate);
+            if score > best_score {
+                best_score = score;
+                best = Some(direction);
+            }
+        }
+
+        best
+    }
+
+    /// Scores how good a spot is to stand on. Higher is better.
+    fn get_score(
+        position: &Position,
+        enemies: &[Position],
+        pickups: &[Position],
+        width: i32,
+        height: i32,
+    ) -> f64 {
+        let repulsion: f64 = enemies
+            .iter()
+            .map(|enemy| get_distance(position, enemy))
+            .filter(|distance| *distance <= Self::ENEMY_RANGE)
+            .map(|distance| Self::ENEMY_WEIGHT / (distance + 0.5).powi(2))
+            .sum();
+
+        let attraction: f64 = pickups
+            .iter()
+            .map(|pickup| get_distance(position, pickup))
+            .filter(|distance| *distance <= Self::PICKUP_RANGE)
+            .map(|distance| Self::PICKUP_WEIGHT / (distance + 1.))
+            .sum();
+
+        let (x, y) = position.get();
+        let edge_distance = f64::from(x.min(y).min(width - 1 - x).min(height - 1 - y));
+        let edge_push = (Self::EDGE_MARGIN - edge_distance).max(0.) * Self::EDGE_WEIGHT;
+
+        attraction - repulsion - edge_push
+    }
+}
+
+fn get_distance(a: &Position, b: &Position) -> f64 {
+    let (dx, dy) = a.get_distance(b);
+
+    f64::from(dx).hypot(f64::from(dy))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::autopilot::AutoPilot;
+    use crate::common::coords::{Direction, Position};
+
+    #[test]
+    fn steers_away_from_enemies_and_towards_pickups() {
+        let autopilot = AutoPilot::new();
+        let position = Position(10, 10);
+
+        let fleeing = autopilot.choose_direction(&position, &[Position(11, 10)], &[], 20, 20);
+        assert!(matches!(fleeing, Some(Direction::LEFT)));
+
+        let collecting = autopilot.choose_direction(&position, &[], &[Position(10, 15)], 20, 20);
+        assert!(matches!(collecting, Some(Direction::DOWN)));
+
+        let idle = autopilot.choose_direction(&position, &[], &[], 20, 20);
+        assert!(idle.is_none());
+    }
+}
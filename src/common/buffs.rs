@@ -0,0 +1,157 @@
+//! This module provides timed buffs: short boosts the character gets during a run, e.g. from the
+//! merchant, which run out after a set time.
+
+use ratatui::style::Style;
+use ratatui::text::Span;
+
+use crate::common::TICK_RATE;
+use crate::common::statsheet::{StatKey, StatModifier, StatSource};
+use crate::prelude::Duration;
+
+/// A kind of timed buff.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BuffKind {
+    /// Raised attack speed.
+    Frenzy,
+}
+
+impl BuffKind {
+    #[must_use]
+    pub fn get_name(self) -> &'static str {
+        match self {
+            BuffKind::Frenzy => "FRENZY",
+        }
+    }
+
+    /// How much the buff multiplies attack speed by.
+    #[must_use]
+    pub fn get_attack_speed_mult(self) -> f64 {
+        match self {
+            BuffKind::Frenzy => 1.5,
+        }
+    }
+
+    fn get_style(self) -> Style {
+        match self {
+            BuffKind::Frenzy => Style::new().black().on_light_red(),
+        }
+    }
+}
+
+/// A buff the character has, with the ticks it has left.
+#[derive(Debug, Clone)]
+pub struct TimedBuff {
+    pub kind: BuffKind,
+    remaining_ticks: u64,
+}
+
+impl TimedBuff {
+    #[must_use]
+    pub fn new(kind: BuffKind, duration: Duration) -> Self {
+        Self {
+            kind,
+            remaining_ticks: (duration.as_secs_f64() * TICK_RATE).round() as u64,
+        }
+    }
+
+    /// Returns how long the buff has left, counted in run time so pauses don't wear it down.
+    #[must_use]
+    pub fn get_remaining(&self) -> Duration {
+        Duration::from_secs_f64(self.remaining_ticks as f64 / TICK_RATE)
+    }
+
+    /// Builds the countdown chip shown in the HUD.
+    #[must_use]
+    pub fn get_chip(&self) -> Span<'static> {
+        Span::styled(
+            format!(
+                " {} {}s ",
+                self.kind.get_name(),
+                self.get_remaining().as_secs_f64().ceil()
+            ),
+            self.kind.get_style(),
+        )
+    }
+}
+
+/// The timed buffs the character has. Getting a buff already held refreshes it rather than
+/// stacking it.
+#[derive(Debug, Clone, Default)]
+pub struct Buffs {
+    buffs: Vec<TimedBuff>,
+}
+
+impl Buffs {
+    /// Gives a buff for `duration`, or tops up one already held if that leaves it longer.
+    pub fn add(&mut self, kind: BuffKind, duration: Duration) {
+        let buff = TimedBuff::new(kind, duration);
+
+        match self.buffs.iter_mut().find(|held| held.kind == kind) {
+            Some(held) => held.remaining_ticks = held.remaining_ticks.max(buff.remaining_ticks),
+            None => self.buffs.push(buff),
+        }
+    }
+
+    /// Counts every buff down by a tick and returns the kinds that just ran out.
+    pub fn tick(&mut self) -> Vec<BuffKind> {
+        for buff in &mut self.buffs {
+            buff.remaining_ticks = buff.remaining_ticks.saturating_sub(1);
+        }
+
+        let expired = self
+            .buffs
+            .iter()
+            .filter(|buff| buff.remaining_ticks == 0)
+            .map(|buff| buff.kind)
+            .collect();
+        self.buffs.retain(|buff| buff.remaining_ticks > 0);
+
+        expired
+    }
+
+    #[must_use]
+    pub fn get_buffs(&self) -> &[TimedBuff] {
+        &self.buffs
+    }
+
+    /// Returns the effects of every buff held, as stat sheet modifiers.
+    #[must_use]
+    pub fn get_modifiers(&self) -> Vec<StatModifier> {
+        self.buffs
+            .iter()
+            .map(|buff| {
+                StatModifier::mult(
+                    StatKey::AttackSpeedMult,
+                    buff.kind.get_attack_speed_mult(),
+                    StatSource::Buff(buff.kind),
+                )
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::TICK_RATE;
+    use crate::common::buffs::{BuffKind, Buffs};
+    use crate::prelude::Duration;
+
+    #[test]
+    fn buffs_refresh_and_run_out() {
+        let mut buffs = Buffs::default();
+        buffs.add(BuffKind::Frenzy, Duration::from_secs(1));
+        buffs.add(BuffKind::Frenzy, Duration::from_secs(2));
+
+        assert_eq!(buffs.get_buffs().len(), 1);
+        assert_eq!(buffs.get_modifiers().len(), 1);
+
+        let ticks = (2. * TICK_RATE).round() as usize;
+        for _ in 1..ticks {
+            assert!(buffs.tick().is_empty());
+        }
+
+        assert_eq!(buffs.tick(), vec![BuffKind::Frenzy]);
+        assert!(buffs.get_buffs().is_empty());
+        assert!(buffs.get_modifiers().is_empty());
+    }
+}
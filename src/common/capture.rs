@@ -0,0 +1,505 @@
+//! This module captures the game view: screenshots of the current frame as plain or ANSI coloured
+//! text, and clips of the last few seconds as animated GIFs. Clips have no font to draw glyphs
+//! with, so each cell is drawn as a tile in its colours, with a block in the middle where there's
+//! something on it.
+
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Write as _;
+
+use ratatui::{
+    Frame,
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier},
+};
+
+use crate::common::settings::setting::{ScreenshotFormat, Settings};
+use crate::common::toasts::{ToastSeverity, Toasts};
+use crate::prelude::{Duration, Instant, KeyCode, SystemTime};
+
+/// What the player asked to capture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureKind {
+    Screenshot,
+    Clip,
+}
+
+/// A finished capture, for the platform to save or download.
+pub enum CaptureOutput {
+    Screenshot { name: String, contents: String },
+    Clip { name: String, encoder: ClipEncoder },
+}
+
+/// A cell of a clip frame, reduced to its colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Tile {
+    fg: [u8; 3],
+    bg: [u8; 3],
+    /// Whether anything is drawn in the cell, rather than it being blank.
+    filled: bool,
+}
+
+/// One frame of a clip.
+#[derive(Debug, Clone)]
+pub struct ClipFrame {
+    width: u16,
+    height: u16,
+    tiles: Vec<Tile>,
+}
+
+impl ClipFrame {
+    fn new(buffer: &Buffer) -> Self {
+        let tiles = buffer
+            .content
+            .iter()
+            .map(|cell| {
+                let mut fg = to_rgb(cell.fg, [204, 204, 204]);
+                let mut bg = to_rgb(cell.bg, [0, 0, 0]);
+                if cell.modifier.contains(Modifier::REVERSED) {
+                    std::mem::swap(&mut fg, &mut bg);
+                }
+
+                Tile {
+                    fg,
+                    bg,
+                    filled: !cell.symbol().trim().is_empty(),
+                }
+            })
+            .collect();
+
+        Self {
+            width: buffer.area.width,
+            height: buffer.area.height,
+            tiles,
+        }
+    }
+}
+
+/// Captures screenshots and keeps the last few seconds of frames for clips.
+#[derive(Default)]
+pub struct Capture {
+    /// The most recent frames, oldest first, while clip recording is on.
+    clip: VecDeque<ClipFrame>,
+    last_recorded_at: Option<Instant>,
+    requested: Option<CaptureKind>,
+    /// Notices about captures, shown in the bottom-right corner.
+    notices: Toasts,
+}
+
+impl Capture {
+    /// The key that saves a screenshot of the current frame.
+    pub const SCREENSHOT_KEY: KeyCode = KeyCode::F(2);
+    /// The key that saves the last few seconds as a clip.
+    pub const CLIP_KEY: KeyCode = KeyCode::F(4);
+
+    /// How far back clips go.
+    const CLIP_LENGTH: Duration = Duration::from_secs(10);
+    /// How often a frame is kept for clips.
+    const CLIP_FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asks for a capture of the next frame drawn.
+    pub fn request(&mut self, kind: CaptureKind) {
+        self.requested = Some(kind);
+    }
+
+    /// Shows a notice about a capture, like where it was saved.
+    pub fn notify(&mut self, message: impl Into<String>, severity: ToastSeverity) {
+        self.notices.push(message, severity);
+    }
+
+    /// Keeps the frame just drawn for clips, if they're being recorded, and captures it if that
+    /// was asked for.
+    pub fn on_render(&mut self, buffer: &Buffer, settings: &Settings) -> Option<CaptureOutput> {
+        if settings.record_clips {
+            self.record(buffer);
+        } else {
+            self.clip.clear();
+        }
+
+        let name = get_capture_name();
+        match self.requested.take()? {
+            CaptureKind::Screenshot => Some(match settings.screenshot_format {
+                ScreenshotFormat::Ansi => CaptureOutput::Screenshot {
+                    name: format!("{name}.ans"),
+                    contents: to_ansi(buffer),
+                },
+                ScreenshotFormat::PlainText => CaptureOutput::Screenshot {
+                    name: format!("{name}.txt"),
+                    contents: to_plain_text(buffer),
+                },
+            }),
+            CaptureKind::Clip if self.clip.is_empty() => {
+                self.notify(
+                    "turn on clip recording in the settings first",
+                    ToastSeverity::Warning,
+                );
+                None
+            }
+            CaptureKind::Clip => {
+                self.notify("saving the clip...", ToastSeverity::Info);
+                Some(CaptureOutput::Clip {
+                    name: format!("{name}.gif"),
+                    encoder: ClipEncoder::new(self.clip.iter().cloned().collect()),
+                })
+            }
+        }
+    }
+
+    /// Renders the notices stacked up from the bottom-right corner of the screen.
+    pub fn render_notices(&mut self, frame: &mut Frame) {
+        self.notices.clear_expired();
+
+        let area = frame.area();
+        let height = (self.notices.len() as u16 * 3).min(area.height);
+        let notice_area = Rect::new(area.x, area.bottom() - height, area.width, height);
+
+        self.notices.render(frame, notice_area);
+    }
+
+    /// Keeps `buffer` for clips, at most once every `CLIP_FRAME_INTERVAL`.
+    fn record(&mut self, buffer: &Buffer) {
+        if self
+            .last_recorded_at
+            .is_some_and(|at| at.elapsed() < Self::CLIP_FRAME_INTERVAL)
+        {
+            return;
+        }
+        self.last_recorded_at = Some(Instant::now());
+
+        self.clip.push_back(ClipFrame::new(buffer));
+
+        let max_frames = (Self::CLIP_LENGTH.as_millis() / Self::CLIP_FRAME_INTERVAL.as_millis())
+            .try_into()
+            .unwrap_or(usize::MAX);
+        while self.clip.len() > max_frames {
+            self.clip.pop_front();
+        }
+    }
+}
+
+/// Encodes a clip into a GIF a frame at a time, so it can be spread across ticks or handed to
+/// another thread.
+pub struct ClipEncoder {
+    frames: VecDeque<ClipFrame>,
+    width: u16,
+    height: u16,
+    palette: Vec<[u8; 3]>,
+    encoder: Option<gif::Encoder<Vec<u8>>>,
+    error: Option<String>,
+}
+
+impl ClipEncoder {
+    /// How many pixels across and down each cell is drawn with.
+    const TILE_WIDTH: u16 = 4;
+    const TILE_HEIGHT: u16 = 8;
+    /// The delay between frames, in hundredths of a second.
+    const FRAME_DELAY: u16 = 10;
+
+    fn new(mut frames: Vec<ClipFrame>) -> Self {
+        // the screen may have been resized, so only the frames at the final size are kept
+        let (width, height) = frames
+            .last()
+            .map_or((0, 0), |frame| (frame.width, frame.height));
+        frames.retain(|frame| frame.width == width && frame.height == height);
+
+        // terminal games use few colours, so the first 256 seen almost always covers them all
+        let mut palette: Vec<[u8; 3]> = vec![];
+        for tile in frames.iter().flat_map(|frame| &frame.tiles) {
+            for colour in [tile.bg, tile.fg] {
+                if palette.len() < 256 && !palette.contains(&colour) {
+                    palette.push(colour);
+                }
+            }
+        }
+
+        let global_palette: Vec<u8> = palette.iter().flatten().copied().collect();
+        let (encoder, error) = match gif::Encoder::new(
+            Vec::new(),
+            width.saturating_mul(Self::TILE_WIDTH),
+            height.saturating_mul(Self::TILE_HEIGHT),
+            &global_palette,
+        )
+        .and_then(|mut encoder| {
+            encoder.set_repeat(gif::Repeat::Infinite)?;
+            Ok(encoder)
+        }) {
+            Ok(encoder) => (Some(encoder), None),
+            Err(err) => (None, Some(err.to_string())),
+        };
+
+        Self {
+            frames: frames.into(),
+            width,
+            height,
+            palette,
+            encoder,
+            error,
+        }
+    }
+
+    /// Encodes the next frame. Returns whether there are any left to encode.
+    pub fn step(&mut self) -> bool {
+        let Some(encoder) = &mut self.encoder else {
+            return false;
+        };
+        let Some(frame) = self.frames.pop_front() else {
+            return false;
+        };
+
+        let mut lookup: HashMap<[u8; 3], u8> = HashMap::new();
+        let mut index_of = |colour: [u8; 3]| {
+            *lookup
+                .entry(colour)
+                .or_insert_with(|| get_nearest(&self.palette, colour))
+        };
+
+        let pixel_width = usize::from(self.width * Self::TILE_WIDTH);
+        let mut pixels = vec![0u8; pixel_width * usize::from(self.height * Self::TILE_HEIGHT)];
+        for (i, tile) in frame.tiles.iter().enumerate() {
+            let (bg, fg) = (index_of(tile.bg), index_of(tile.fg));
+            let column = i % usize::from(self.width) * usize::from(Self::TILE_WIDTH);
+            let row = i / usize::from(self.width) * usize::from(Self::TILE_HEIGHT);
+
+            for y in 0..usize::from(Self::TILE_HEIGHT) {
+                for x in 0..usize::from(Self::TILE_WIDTH) {
+                    // a block in the middle of the tile stands in for the glyph
+                    let inside = (1..3).contains(&x) && (2..6).contains(&y);
+                    pixels[(row + y) * pixel_width + column + x] =
+                        if tile.filled && inside { fg } else { bg };
+                }
+            }
+        }
+
+        let gif_frame = gif::Frame {
+            delay: Self::FRAME_DELAY,
+            width: self.width * Self::TILE_WIDTH,
+            height: self.height * Self::TILE_HEIGHT,
+            buffer: pixels.into(),
+            ..gif::Frame::default()
+        };
+        if let Err(err) = encoder.write_frame(&gif_frame) {
+            self.error = Some(err.to_string());
+            self.encoder = None;
+            return false;
+        }
+
+        !self.frames.is_empty()
+    }
+
+    /// Finishes the GIF, encoding any frames that are left.
+    ///
+    /// # Errors
+    ///
+    /// Errors if a frame couldn't be encoded.
+    pub fn finish(mut self) -> Result<Vec<u8>, String> {
+        while self.step() {}
+
+        if let Some(err) = self.error {
+            return Err(err);
+        }
+        self.encoder
+            .ok_or_else(|| "the clip couldn't be encoded".to_string())?
+            .into_inner()
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Writes `buffer` out as plain text, a line per row.
+#[must_use]
+pub fn to_plain_text(buffer: &Buffer) -> String {
+    get_rows(buffer)
+        .map(|row| {
+            row.iter()
+                .map(ratatui::buffer::Cell::symbol)
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Writes `buffer` out as text coloured with ANSI escape codes, for `cat` in a terminal.
+#[must_use]
+pub fn to_ansi(buffer: &Buffer) -> String {
+    let mut out = String::new();
+
+    for row in get_rows(buffer) {
+        let mut style = None;
+        for cell in row {
+            let cell_style = (cell.fg, cell.bg, cell.modifier);
+            if style != Some(cell_style) {
+                out.push_str(&get_sgr(cell.fg, cell.bg, cell.modifier));
+                style = Some(cell_style);
+            }
+            out.push_str(cell.symbol());
+        }
+        out.push_str("\x1b[0m\n");
+    }
+
+    out
+}
+
+fn get_rows(buffer: &Buffer) -> impl Iterator<Item = &[ratatui::buffer::Cell]> {
+    buffer.content.chunks(usize::from(buffer.area.width.max(1)))
+}
+
+/// Builds the escape code that switches to the given colours and modifiers.
+fn get_sgr(fg: Color, bg: Color, modifier: Modifier) -> String {
+    let mut codes = vec!["0".to_string()];
+
+    for (flag, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+    ] {
+        if modifier.contains(flag) {
+            codes.push(code.to_string());
+        }
+    }
+
+    for (colour, base) in [(fg, 30), (bg, 40)] {
+        match colour {
+            Color::Reset => {}
+            Color::Indexed(index) => codes.push(format!("{};5;{index}", base + 8)),
+            Color::Rgb(r, g, b) => codes.push(format!("{};2;{r};{g};{b}", base + 8)),
+            named => {
+                if let Some(index) = get_named_index(named) {
+                    // the bright colours sit 60 above the normal ones
+                    let code = if index < 8 {
+                        base + index
+                    } else {
+                        base + 60 + index - 8
+                    };
+                    codes.push(code.to_string());
+                }
+            }
+        }
+    }
+
+    let mut sgr = String::from("\x1b[");
+    let _ = write!(sgr, "{}m", codes.join(";"));
+    sgr
+}
+
+/// Returns the 16 colour palette index of a named colour.
+fn get_named_index(colour: Color) -> Option<u16> {
+    Some(match colour {
+        Color::Black => 0,
+        Color::Red => 1,
+        Color::Green => 2,
+        Color::Yellow => 3,
+        Color::Blue => 4,
+        Color::Magenta => 5,
+        Color::Cyan => 6,
+        Color::Gray => 7,
+        Color::DarkGray => 8,
+        Color::LightRed => 9,
+        Color::LightGreen => 10,
+        Color::LightYellow => 11,
+        Color::LightBlue => 12,
+        Color::LightMagenta => 13,
+        Color::LightCyan => 14,
+        Color::White => 15,
+        _ => return None,
+    })
+}
+
+/// Works out the colour a cell is drawn in, using xterm's palette, with `reset` standing in for
+/// the terminal's own colour.
+fn to_rgb(colour: Color, reset: [u8; 3]) -> [u8; 3] {
+    const NAMED: [[u8; 3]; 16] = [
+        [0, 0, 0],
+        [205, 0, 0],
+        [0, 205, 0],
+        [205, 205, 0],
+        [0, 0, 238],
+        [205, 0, 205],
+        [0, 205, 205],
+        [229, 229, 229],
+        [127, 127, 127],
+        [255, 0, 0],
+        [0, 255, 0],
+        [255, 255, 0],
+        [92, 92, 255],
+        [255, 0, 255],
+        [0, 255, 255],
+        [255, 255, 255],
+    ];
+
+    match colour {
+        Color::Rgb(r, g, b) => [r, g, b],
+        Color::Indexed(index @ 0..16) => NAMED[usize::from(index)],
+        Color::Indexed(index @ 16..232) => {
+            let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+            let index = index - 16;
+            [level(index / 36), level(index / 6 % 6), level(index % 6)]
+        }
+        Color::Indexed(index) => {
+            let grey = 8 + (index - 232) * 10;
+            [grey, grey, grey]
+        }
+        named => get_named_index(named).map_or(reset, |index| NAMED[usize::from(index)]),
+    }
+}
+
+/// Returns the index of the colour in `palette` closest to `colour`.
+fn get_nearest(palette: &[[u8; 3]], colour: [u8; 3]) -> u8 {
+    let distance = |other: &[u8; 3]| -> u32 {
+        other
+            .iter()
+            .zip(colour)
+            .map(|(a, b)| u32::from(a.abs_diff(b)).pow(2))
+            .sum()
+    };
+
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, other)| distance(other))
+        .and_then(|(index, _)| u8::try_from(index).ok())
+        .unwrap_or_default()
+}
+
+/// Names a capture after when it was taken, so captures don't overwrite each other.
+fn get_capture_name() -> String {
+    let secs = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+
+    format!("dispair-{secs}")
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+
+    use crate::common::capture::{ClipEncoder, ClipFrame, to_ansi, to_plain_text};
+
+    #[test]
+    fn snapshots_keep_the_text() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 4, 2));
+        buffer.set_string(0, 0, "@ x", ratatui::style::Style::new().fg(Color::Red));
+
+        assert_eq!(to_plain_text(&buffer), "@ x\n");
+        assert!(to_ansi(&buffer).starts_with("\x1b[0;31m@ x"));
+    }
+
+    #[test]
+    fn clips_encode_as_gifs() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 3, 2));
+        buffer.set_string(1, 1, "@", ratatui::style::Style::new().fg(Color::Green));
+        let frames = vec![ClipFrame::new(&buffer); 3];
+
+        let gif = ClipEncoder::new(frames).finish().unwrap();
+        assert!(gif.starts_with(b"GIF89a"));
+    }
+}
@@ -0,0 +1,232 @@
+//! This module provides challenges: a run's seed, class, starting loadout, mutators and hype
+//! level packed into a short code. Playing a challenge's code sets up the same run, so friends can
+//! compete on it, and finished runs are kept in the player's history by code so results on the
+//! same challenge can be compared.
+
+use std::str::FromStr;
+
+use ratatui::{style::Stylize, text::Line};
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::common::charms::CharmWrapper;
+use crate::common::classes::class::CharacterClass;
+use crate::common::loadouts::loadout::Loadout;
+use crate::common::mutators::mutator::Mutator;
+use crate::common::upgrades::upgrade::PlayerState;
+use crate::common::weapons::WeaponWrapper;
+
+/// Everything needed to set up the same run again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Challenge {
+    pub seed: u64,
+    pub class: CharacterClass,
+    pub loadout: Loadout,
+    pub mutators: Vec<Mutator>,
+    /// The hype level, which sets how far ahead the run's difficulty starts.
+    pub hype: u32,
+}
+
+impl Challenge {
+    /// Starts every code, so codes from a future format can be told apart.
+    const VERSION: &str = "c1";
+
+    /// Returns the challenge for a run on `seed` set up by `player_state`.
+    #[must_use]
+    pub fn from_run(seed: u64, player_state: &PlayerState) -> Self {
+        Self {
+            seed,
+            class: player_state.class,
+            loadout: player_state.loadout.clone(),
+            mutators: player_state.mutators.clone(),
+            hype: player_state.get_hype(),
+        }
+    }
+
+    /// Packs the challenge into a code like `c1-4d2-0-1-3-2-x`: the seed, class, hype level,
+    /// mutators, METHOD and CHARM, with `x` for a loadout slot left empty.
+    #[must_use]
+    pub fn encode(&self) -> String {
+        let class = get_index(CharacterClass::iter(), &self.class);
+        let mutators = Mutator::iter()
+            .enumerate()
+            .filter(|(_, mutator)| self.mutators.contains(mutator))
+            .fold(0u32, |mask, (i, _)| mask | 1 << i);
+
+        let weapon = self.loadout.weapon.as_deref().and_then(|name| {
+            let weapon: &'static str = WeaponWrapper::from_str(name).ok()?.into();
+            WeaponWrapper::iter().position(|other| <&str>::from(other) == weapon)
+        });
+        let charm = self.loadout.charm.as_deref().and_then(|name| {
+            let charm: &'static str = CharmWrapper::from_str(name).ok()?.into();
+            CharmWrapper::iter().position(|other| <&str>::from(other) == charm)
+        });
+        let slot = |index: Option<usize>| index.map_or("x".to_string(), |i| format!("{i:x}"));
+
+        format!(
+            "{}-{:x}-{class:x}-{:x}-{mutators:x}-{}-{}",
+            Self::VERSION,
+            self.seed,
+            self.hype,
+            slot(weapon),
+            slot(charm),
+        )
+    }
+
+    /// Unpacks a code made by [`Challenge::encode`]. Malformed codes, or ones naming things this
+    /// build doesn't have, are rejected.
+    #[must_use]
+    pub fn decode(code: &str) -> Option<Self> {
+        let parts: Vec<&str> = code.trim().split('-').collect();
+        let [version, seed, class, hype, mutators, weapon, charm] = parts[..] else {
+            return None;
+        };
+        if version != Self::VERSION {
+            return None;
+        }
+
+        let number = |part: &str| usize::from_str_radix(part, 16).ok();
+        let slot = |part: &str| match part {
+            "x" => Some(None),
+            part => number(part).map(Some),
+        };
+
+        let mutator_mask = u32::from_str_radix(mutators, 16).ok()?;
+        let weapon = match slot(weapon)? {
+            Some(i) => Some(<&str>::from(WeaponWrapper::iter().nth(i)?).to_string()),
+            None => None,
+        };
+        let charm = match slot(charm)? {
+            Some(i) => Some(<&str>::from(CharmWrapper::iter().nth(i)?).to_string()),
+            None => None,
+        };
+
+        Some(Self {
+            seed: u64::from_str_radix(seed, 16).ok()?,
+            class: CharacterClass::iter().nth(number(class)?)?,
+            loadout: Loadout { weapon, charm },
+            mutators: Mutator::iter()
+                .enumerate()
+                .filter(|(i, _)| mutator_mask & (1 << i) != 0)
+                .map(|(_, mutator)| mutator)
+                .collect(),
+            hype: u32::from_str_radix(hype, 16).ok()?,
+        })
+    }
+}
+
+/// A finished run, as kept in the player's history.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RunRecord {
+    /// The code of the challenge the run was, to compare runs on the same one.
+    pub challenge: String,
+    pub survival_secs: u64,
+    pub kills: u64,
+}
+
+impl RunRecord {
+    /// How many runs the history keeps, dropping the oldest first.
+    pub const HISTORY_LEN: usize = 50;
+
+    /// Returns the best run in `history` on the challenge `code`: the one that survived longest,
+    /// then the one with the most kills.
+    #[must_use]
+    pub fn get_best<'a>(history: &'a [RunRecord], code: &str) -> Option<&'a RunRecord> {
+        history
+            .iter()
+            .filter(|record| record.challenge == code)
+            .max_by_key(|record| (record.survival_secs, record.kills))
+    }
+}
+
+/// How a finished run did on its challenge, for the carnage report.
+#[derive(Debug, Clone)]
+pub struct ChallengeResult {
+    pub run: RunRecord,
+    /// The best earlier run on the same challenge, if it's been played before.
+    pub previous_best: Option<RunRecord>,
+}
+
+impl ChallengeResult {
+    /// Builds the lines showing the challenge's code and how the run compares to the best one.
+    #[must_use]
+    pub fn get_lines(&self) -> Vec<Line<'static>> {
+        let comparison = match &self.previous_best {
+            None => "first run on this challenge".dark_gray(),
+            Some(best)
+                if (self.run.survival_secs, self.run.kills) > (best.survival_secs, best.kills) =>
+            {
+                "new best on this challenge!".light_yellow()
+            }
+            Some(best) => format!(
+                "best on this challenge: {}s, {} kills",
+                best.survival_secs, best.kills
+            )
+            .dark_gray(),
+        };
+
+        vec![
+            Line::from(vec![
+                "Challenge: ".into(),
+                self.run.challenge.clone().bold(),
+            ]),
+            Line::from(comparison),
+            Line::from("share the code to have others play the same run".dark_gray()),
+        ]
+    }
+}
+
+fn get_index<T: PartialEq>(mut all: impl Iterator<Item = T>, item: &T) -> usize {
+    all.position(|other| other == *item).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::challenge::{Challenge, RunRecord};
+    use crate::common::classes::class::CharacterClass;
+    use crate::common::loadouts::loadout::Loadout;
+    use crate::common::mutators::mutator::Mutator;
+
+    #[test]
+    fn codes_round_trip() {
+        let challenge = Challenge {
+            seed: 0xDEAD_BEEF,
+            class: CharacterClass::Shard,
+            loadout: Loadout {
+                weapon: Some("Flash".into()),
+                charm: None,
+            },
+            mutators: vec![Mutator::GlassCannon, Mutator::WrapAround],
+            hype: 2,
+        };
+
+        let code = challenge.encode();
+        let decoded = Challenge::decode(&code).expect("codes decode");
+        assert_eq!(decoded.encode(), code);
+        assert_eq!(decoded.seed, challenge.seed);
+        assert_eq!(decoded.class, challenge.class);
+        assert_eq!(decoded.mutators, challenge.mutators);
+        assert!(decoded.loadout.weapon.is_some() && decoded.loadout.charm.is_none());
+
+        assert_eq!(Challenge::decode("c1-1-0-0"), None);
+        assert_eq!(Challenge::decode("c1-1-9-0-0-x-x"), None);
+    }
+
+    #[test]
+    fn best_run_survives_longest() {
+        let record = |challenge: &str, survival_secs, kills| RunRecord {
+            challenge: challenge.into(),
+            survival_secs,
+            kills,
+        };
+        let history = [
+            record("a", 40, 10),
+            record("a", 60, 5),
+            record("b", 90, 50),
+            record("a", 60, 8),
+        ];
+
+        assert_eq!(RunRecord::get_best(&history, "a"), Some(&history[3]));
+        assert_eq!(RunRecord::get_best(&history, "c"), None);
+    }
+}
@@ -2,19 +2,25 @@
 //! It handles character movement, health, attacks, and other core gameplay mechanics.
 
 use ratatui::style::Style;
+use ratatui::text::Span;
 use std::cell::RefCell;
+use std::collections::VecDeque;
 use std::rc::Rc;
 
+use crate::common::buffs::{BuffKind, Buffs};
+use crate::common::deathrecap::{DamageLog, DamageSource};
 use crate::common::enemies::enemy::Enemy;
 use crate::common::entities::EntityCharacters;
 use crate::common::map::Layer;
 use crate::common::sound::SoundWrangler;
+use crate::common::turrets::DeployedTurret;
+use crate::common::utils::{per_sec_to_tick_count_to_u64, ticks_to_duration};
 use crate::common::{
     PlayerStateRef,
     charms::CharmWrapper,
     coords::{Direction, Position},
     effects::DamageEffect,
-    weapons::{DamageArea, WeaponWrapper, flash::Flash},
+    weapons::{DamageArea, WeaponWrapper},
 };
 use crate::prelude::{Duration, Instant};
 
@@ -31,6 +37,11 @@ pub trait Renderable {
 
     ///Get the `EntityCharacters` to render the entity
     fn get_entity_char(&self) -> &EntityCharacters;
+
+    /// Get the styled span to draw for the entity.
+    fn get_styled(&self) -> Span<'static> {
+        self.get_entity_char().to_styled()
+    }
 }
 
 /// A trait for entities that can move within the game world.
@@ -57,29 +68,14 @@ pub trait Movable: Renderable {
     /// Moves the entity back a certain number of steps from its current facing direction.
     fn move_back(&mut self, steps: i32, layer: &Layer) {
         let current_direction = self.get_facing();
-
-        match current_direction {
-            Direction::UP => self.move_to_safe(
-                Position(self.get_pos().0, self.get_pos().1 + steps),
-                Direction::DOWN,
-                layer,
-            ),
-            Direction::DOWN => self.move_to_safe(
-                Position(self.get_pos().0, self.get_pos().1 - steps),
-                Direction::UP,
-                layer,
-            ),
-            Direction::LEFT => self.move_to_safe(
-                Position(self.get_pos().0 + steps, self.get_pos().1),
-                Direction::RIGHT,
-                layer,
-            ),
-            Direction::RIGHT => self.move_to_safe(
-                Position(self.get_pos().0 - steps, self.get_pos().1),
-                Direction::LEFT,
-                layer,
-            ),
-        }
+        let (x, y) = self.get_pos().get();
+        let (step_x, step_y) = current_direction.get_offset();
+
+        self.move_to_safe(
+            Position(x - step_x * steps, y - step_y * steps),
+            current_direction.opposite(),
+            layer,
+        );
     }
 }
 
@@ -102,8 +98,16 @@ pub trait Damageable {
 pub struct Character {
     position: Position,
     prev_position: Position,
-    last_moved: Instant,
+    trail: VecDeque<(Position, Instant)>,
+    /// The tick the character last moved on.
+    last_moved: u64,
+    /// The run's current tick, as of the last call to `on_tick`. The character's timings are
+    /// measured in ticks so they play out the same on every machine in a networked run.
+    tickcount: u64,
+    terrain_cost: f64,
     pub facing: Direction,
+    /// The direction the character is aiming in manual aim mode, separate from movement.
+    pub aim: Option<Direction>,
 
     pub stats: PlayerStateRef,
 
@@ -113,6 +117,21 @@ pub struct Character {
     max_health: i32,
     is_alive: bool,
 
+    shield: i32,
+    /// The tick the character was last hurt on, or `None` if it hasn't been yet this run.
+    last_hit: Option<u64>,
+    /// Whether the character takes no damage, switched from the debug console.
+    pub god_mode: bool,
+    /// The hits taken recently, to explain a death.
+    damage_log: DamageLog,
+    /// The timed buffs currently active.
+    buffs: Buffs,
+
+    lifesteal_pool: f64,
+    lifesteal_window: (u64, i32),
+    /// Blocked damage that hasn't added up to a whole point yet.
+    armor_pool: f64,
+
     pub weapons: Vec<WeaponWrapper>,
     pub charms: Vec<CharmWrapper>,
 
@@ -121,11 +140,20 @@ pub struct Character {
 }
 
 impl Character {
+    const SHIELD_REGEN_P_S: f64 = 2.;
+    const LIFESTEAL_CAP_P_S: i32 = 3;
+    const TRAIL_LENGTH: usize = 5;
+    const TRAIL_FADE: Duration = Duration::from_millis(500);
+    const MAX_DAMAGE_REDUCTION: f64 = 0.75;
+    /// Steps taken per second at base movement speed on open ground.
+    const STEPS_P_S: f64 = 10.;
+
     /// Creates a new Character initialized from the given player state.
     ///
     /// The new character starts at `Position(0,0)`, facing up, with health and stats
-    /// taken from `player_state.stats.player_stats`. The character's weapon loadout
-    /// is initialized from `player_state.stats.weapon_stats`.
+    /// taken from `player_state.stats.player_stats`. The character's starting weapon
+    /// is chosen by `player_state.loadout`, falling back to `player_state.class`, and initialized
+    /// from `player_state.stats.weapon_stats`. A starting charm is added if the loadout has one.
     ///
     /// # Parameters
     ///
@@ -137,20 +165,33 @@ impl Character {
     /// and weapons derived from the provided `player_state`.
     #[must_use]
     pub fn new(player_state: &PlayerStateRef, sound_wrangler: Rc<RefCell<SoundWrangler>>) -> Self {
-        let stats = &player_state.borrow().stats;
-        let weapon_stats = stats.weapon_stats.clone();
-        let max_health = stats.player_stats.health;
-
-        let weapon =
-            WeaponWrapper::Flash(Some(Flash::new(weapon_stats.clone(), player_state.clone())));
-
-        let charms = vec![];
+        let state = player_state.borrow();
+        let weapon_stats = state.stats.weapon_stats.clone();
+        let max_health = state.stats.player_stats.health;
+        let shield = state.stats.player_stats.shield;
+
+        let mut weapon = state.loadout.get_starting_weapon_type(&state);
+        weapon.populate_inner(weapon_stats, player_state.clone());
+
+        let charms = state
+            .loadout
+            .get_starting_charm_type(&state)
+            .map(|mut charm| {
+                charm.populate_inner(player_state.clone());
+                charm
+            })
+            .into_iter()
+            .collect();
 
         Character {
             position: Position(0, 0),
             prev_position: Position(0, 0),
-            last_moved: Instant::now(),
+            trail: VecDeque::new(),
+            last_moved: 0,
+            tickcount: 0,
+            terrain_cost: 1.,
             facing: Direction::UP,
+            aim: None,
 
             sound_wrangler,
 
@@ -160,6 +201,16 @@ impl Character {
             health: max_health,
             max_health,
             is_alive: true,
+            god_mode: false,
+            damage_log: DamageLog::default(),
+            buffs: Buffs::default(),
+
+            shield,
+            last_hit: None,
+
+            lifesteal_pool: 0.,
+            lifesteal_window: (0, 0),
+            armor_pool: 0.,
 
             entitychar: EntityCharacters::Character(Style::default()),
 
@@ -168,16 +219,275 @@ impl Character {
         }
     }
 
+    /// Takes damage like `take_damage`, remembering what dealt it for the death recap. Hits that
+    /// are fully blocked, e.g. by i-frames, aren't remembered.
+    pub fn take_damage_from(&mut self, damage: i32, source: DamageSource) {
+        let before = self.health + self.shield;
+        self.take_damage(damage);
+        let dealt = before - (self.health + self.shield);
+
+        if dealt > 0 {
+            self.damage_log.record(source, dealt);
+        }
+    }
+
+    /// Gets the hits the character has taken recently.
+    #[must_use]
+    pub fn get_damage_log(&self) -> &DamageLog {
+        &self.damage_log
+    }
+
+    /// Lists each METHOD and CHARM held, with its level.
+    #[must_use]
+    pub fn get_build(&self) -> Vec<String> {
+        let weapons = self.weapons.iter().map(|weapon| {
+            let weapon = weapon.get_inner();
+            format!("{} {}", weapon.get_name(), weapon.get_level())
+        });
+        let charms = self.charms.iter().map(|charm| {
+            let charm = charm.get_inner();
+            format!("{} {}", charm.get_name(), charm.get_level())
+        });
+
+        weapons.chain(charms).collect()
+    }
+
+    /// Gives the character a timed buff, refreshing it if already active.
+    pub fn add_buff(&mut self, kind: BuffKind, duration: Duration) {
+        self.buffs.add(kind, duration);
+    }
+
+    /// Counts the character's buffs down by a tick, returning the kinds that ran out.
+    pub fn tick_buffs(&mut self) -> Vec<BuffKind> {
+        self.buffs.tick()
+    }
+
+    #[must_use]
+    pub fn get_buffs(&self) -> &Buffs {
+        &self.buffs
+    }
+
+    /// Gets the current shield of the character.
+    #[must_use]
+    pub fn get_shield(&self) -> i32 {
+        self.shield
+    }
+
+    #[must_use]
+    pub fn get_max_health(&self) -> i32 {
+        self.max_health
+    }
+
+    /// Gets the maximum shield, read live from the player's stats so charms apply immediately.
+    #[must_use]
+    pub fn get_max_shield(&self) -> i32 {
+        self.stats.borrow().stats.player_stats.shield
+    }
+
+    /// Moves the character's clock on to `tickcount`, then regenerates its shield if it's due.
+    pub fn on_tick(&mut self, tickcount: u64) {
+        self.tickcount = tickcount;
+        self.regen_shield();
+    }
+
+    /// Gets how long it's been since `tick`, by the character's clock.
+    fn get_time_since(&self, tick: u64) -> Duration {
+        ticks_to_duration(self.tickcount.saturating_sub(tick))
+    }
+
+    /// Regenerates one point of shield at a fixed rate once the character has gone
+    /// `shield_regen_delay` seconds without being hit.
+    fn regen_shield(&mut self) {
+        let max_shield = self.get_max_shield();
+
+        if self.shield >= max_shield {
+            self.shield = max_shield;
+            return;
+        }
+
+        let regen_delay =
+            Duration::from_secs_f64(self.stats.borrow().stats.player_stats.shield_regen_delay);
+
+        if self
+            .last_hit
+            .is_none_or(|hit| self.get_time_since(hit) >= regen_delay)
+            && self
+                .tickcount
+                .is_multiple_of(per_sec_to_tick_count_to_u64(Self::SHIELD_REGEN_P_S))
+        {
+            self.shield += 1;
+        }
+    }
+
+    /// Heals the character by its `lifesteal` fraction of `damage_dealt`, healing at most
+    /// `LIFESTEAL_CAP_P_S` health per second. Fractional healing carries over between calls.
+    pub fn lifesteal(&mut self, damage_dealt: i32, tickcount: u64) {
+        let lifesteal = self.stats.borrow().stats.player_stats.lifesteal;
+        if lifesteal <= 0. || damage_dealt <= 0 {
+            return;
+        }
+
+        let (window_start, healed) = &mut self.lifesteal_window;
+        if tickcount >= *window_start + per_sec_to_tick_count_to_u64(1.) {
+            *window_start = tickcount;
+            *healed = 0;
+        }
+
+        self.lifesteal_pool += f64::from(damage_dealt) * lifesteal;
+
+        let remaining_cap = Self::LIFESTEAL_CAP_P_S - *healed;
+        let heal_amount = (self.lifesteal_pool.floor() as i32).min(remaining_cap);
+        if heal_amount <= 0 {
+            return;
+        }
+
+        *healed += heal_amount;
+        self.lifesteal_pool = if heal_amount == remaining_cap {
+            0.
+        } else {
+            self.lifesteal_pool.fract()
+        };
+
+        self.heal(heal_amount);
+    }
+
+    /// Restores health to the character, up to its maximum health.
+    pub fn heal(&mut self, amount: i32) {
+        self.health = (self.health + amount).min(self.max_health);
+
+        self.entitychar
+            .replace(EntityCharacters::Character(self.get_health_style()));
+    }
+
+    /// Gets how long it's been since the character last moved.
+    #[must_use]
+    pub fn get_still_for(&self) -> Duration {
+        self.get_time_since(self.last_moved)
+    }
+
+    /// Gets the fraction of incoming damage the character's CHARMs currently block.
+    #[must_use]
+    pub fn get_damage_reduction(&self) -> f64 {
+        let still_for = self.get_still_for();
+
+        self.charms
+            .iter()
+            .map(|charm| charm.get_inner().get_damage_reduction(still_for))
+            .sum::<f64>()
+            .min(Self::MAX_DAMAGE_REDUCTION)
+    }
+
+    /// Checks if the character is still within its invulnerability window after the last hit.
+    #[must_use]
+    pub fn is_invulnerable(&self) -> bool {
+        let iframe_secs = self.stats.borrow().stats.player_stats.iframe_secs;
+
+        self.last_hit.is_some_and(|hit| {
+            self.get_time_since(hit) < Duration::from_secs_f64(iframe_secs.max(0.))
+        })
+    }
+
+    /// Returns the style reflecting the character's current health.
+    fn get_health_style(&self) -> Style {
+        if self.health < self.max_health / 2 {
+            Style::default().gray().italic()
+        } else {
+            Style::default()
+        }
+    }
+
+    /// Updates the character's render style, blinking while invulnerable.
+    pub fn on_frame(&mut self) {
+        let mut style = self.get_health_style();
+
+        // with reduced flashing, stay dimmed for the whole invulnerability window instead
+        let blink_on = self.stats.borrow().settings.reduced_flashing
+            || self
+                .last_hit
+                .is_some_and(|hit| (self.get_time_since(hit).as_millis() / 100).is_multiple_of(2));
+
+        if self.is_invulnerable() && blink_on {
+            style = style.dark_gray();
+        }
+
+        *self.entitychar.style_mut() = style;
+    }
+
+    /// Gets how many ticks each step takes, from the character's movement speed multiplier and
+    /// the terrain it is standing on.
+    #[must_use]
+    pub fn get_ticks_per_step(&self) -> u64 {
+        let movement_speed_mult = self.stats.borrow().stats.player_stats.movement_speed_mult;
+        let steps_p_s = Self::STEPS_P_S * movement_speed_mult.max(0.01) / self.terrain_cost;
+
+        per_sec_to_tick_count_to_u64(steps_p_s).max(1)
+    }
+
+    /// Sets how much slower the character moves on the terrain it is standing on.
+    pub fn set_terrain_cost(&mut self, terrain_cost: f64) {
+        self.terrain_cost = terrain_cost;
+    }
+
+    /// Returns the recently left positions that haven't faded yet, each with how far through
+    /// fading it is from `0.` to `1.`.
+    pub fn get_trail(&self) -> impl Iterator<Item = (&Position, f64)> {
+        self.trail.iter().filter_map(|(position, left_at)| {
+            let fade = left_at.elapsed().as_secs_f64() / Self::TRAIL_FADE.as_secs_f64();
+            (fade < 1. && position != &self.position).then_some((position, fade))
+        })
+    }
+
+    /// Returns the direction weapons fire in: the aim direction if one is set, otherwise the
+    /// direction the character last moved.
+    #[must_use]
+    pub fn get_aim_direction(&self) -> Direction {
+        self.aim.clone().unwrap_or_else(|| self.facing.clone())
+    }
+
+    /// Returns the cell in front of the character and an arrow pointing the way it aims.
+    #[must_use]
+    pub fn get_facing_indicator(&self) -> (Position, Span<'static>) {
+        let direction = self.get_aim_direction();
+        let arrow = match direction {
+            Direction::LEFT => "←",
+            Direction::RIGHT => "→",
+            Direction::UP => "↑",
+            Direction::DOWN => "↓",
+            Direction::UPLEFT => "↖",
+            Direction::UPRIGHT => "↗",
+            Direction::DOWNLEFT => "↙",
+            Direction::DOWNRIGHT => "↘",
+        };
+        let (x, y) = direction.step_from(&self.position).get();
+
+        (
+            Position::new(x, y),
+            Span::styled(arrow, Style::default().dark_gray()),
+        )
+    }
+
     #[must_use]
     pub fn get_pos_data(&self) -> CharacterPositionData {
         CharacterPositionData {
             position: self.get_pos().clone(),
             prev_position: self.get_prev_pos().clone(),
-            facing: self.get_facing(),
+            facing: self.get_aim_direction(),
         }
     }
 
-    /// Generates damage areas for each equipped weapon and corresponding damage effects, applies each effect to the provided layer, staggers their start times, and updates them.
+    /// Collects the turrets deployed by each weapon in `weapon_indices`.
+    pub fn deploy_turrets(&mut self, weapon_indices: &[usize]) -> Vec<DeployedTurret> {
+        let pos_data = self.get_pos_data();
+
+        self.weapons
+            .iter_mut()
+            .enumerate()
+            .filter(|(i, _)| weapon_indices.contains(i))
+            .filter_map(|(_, weapon)| weapon.get_inner_mut().deploy(&pos_data))
+            .collect()
+    }
+
+    /// Generates damage areas for each weapon in `weapon_indices` and corresponding damage effects, applies each effect to the provided layer, staggers their start times, and updates them.
     ///
     /// The provided `layer_effects` is modified by constraining each damage area's region to the layer before effects are produced.
     ///
@@ -186,6 +496,7 @@ impl Character {
     /// A tuple where the first element is a `Vec<DamageArea>` produced by the weapons, and the second element is a `Vec<DamageEffect>` derived from those areas with staggered delays applied (`0.15` seconds multiplied by each effect's index).
     pub fn attack(
         &mut self,
+        weapon_indices: &[usize],
         layer: &Layer,
         enemies: &[Enemy],
     ) -> (Vec<DamageArea>, Vec<DamageEffect>) {
@@ -194,12 +505,26 @@ impl Character {
         let damage_areas: Vec<DamageArea> = self
             .weapons
             .iter_mut()
-            .map(|weapon| {
+            .enumerate()
+            .filter(|(i, _)| weapon_indices.contains(i))
+            .map(|(_, weapon)| {
                 self.sound_wrangler.borrow().play(weapon.get_sound());
 
-                weapon
-                    .get_inner_mut()
-                    .attack(pos_data.clone(), enemies, layer)
+                let mut damage_area =
+                    weapon
+                        .get_inner_mut()
+                        .attack(pos_data.clone(), enemies, layer);
+                let name: &'static str = (&*weapon).into();
+                damage_area.source = Some(name);
+
+                if weapon.get_inner().get_element().is_none()
+                    && let Some(color) = self.stats.borrow().get_mastery(name).get_glyph_color()
+                {
+                    let style = damage_area.entity.style_mut();
+                    *style = style.fg(color);
+                }
+
+                damage_area
             })
             .map(|mut damage_area| {
                 damage_area.area.get_inner_mut().constrain(layer);
@@ -234,26 +559,22 @@ impl Renderable for Character {
 
 impl Movable for Character {
     fn set_pos(&mut self, new_pos: Position) {
+        if new_pos != self.position {
+            self.trail
+                .push_front((self.position.clone(), Instant::now()));
+            self.trail.truncate(Self::TRAIL_LENGTH);
+        }
+
         self.prev_position = self.position.clone();
         self.position = new_pos;
     }
 
-    /// Attempts to move the character to `new_pos` and update its facing; movement is throttled by the character's movement speed multiplier and `last_moved` is updated when the move occurs.
+    /// Moves the character to `new_pos` and updates its facing. How often this happens is up to
+    /// the run, see [`Character::get_ticks_per_step`].
     fn move_to(&mut self, new_pos: Position, facing: Direction) {
         self.facing = facing;
-
-        let attempt_time = Instant::now();
-        let difference = attempt_time.duration_since(self.last_moved).as_millis() as u64;
-
-        let movement_speed_multiplier = self.stats.borrow().stats.player_stats.movement_speed_mult;
-
-        // this is what movement speed controls vv
-        let timeout = (100.0 / movement_speed_multiplier.max(0.01)).round() as u64;
-
-        if difference > timeout {
-            self.set_pos(new_pos);
-            self.last_moved = attempt_time;
-        }
+        self.set_pos(new_pos);
+        self.last_moved = self.tickcount;
     }
 
     fn get_prev_pos(&self) -> &Position {
@@ -271,19 +592,34 @@ impl Damageable for Character {
     }
 
     fn take_damage(&mut self, damage: i32) {
-        let normal_style = Style::default();
-        let hurt_style = Style::default().gray().italic();
+        let mut damage = damage;
+
+        if damage > 0 {
+            if self.is_invulnerable() || self.god_mode {
+                return;
+            }
+
+            self.last_hit = Some(self.tickcount);
+
+            // blocked damage carries over between hits so small hits still get reduced
+            let reduction = self.get_damage_reduction();
+            if reduction > 0. {
+                self.armor_pool += f64::from(damage) * reduction;
+                let blocked = (self.armor_pool.floor() as i32).min(damage);
+                self.armor_pool -= f64::from(blocked);
+                damage -= blocked;
+            }
+
+            let absorbed = damage.min(self.shield);
+            self.shield -= absorbed;
+            damage -= absorbed;
+        }
 
         self.health -= damage;
 
-        if self.health >= self.max_health / 2 {
-            self.entitychar
-                .replace(EntityCharacters::Character(normal_style));
-        }
-        if self.health < self.max_health / 2 {
-            self.entitychar
-                .replace(EntityCharacters::Character(hurt_style));
-        }
+        self.entitychar
+            .replace(EntityCharacters::Character(self.get_health_style()));
+
         if self.health <= 0 {
             self.die();
         }
@@ -297,3 +633,31 @@ impl Damageable for Character {
         self.is_alive
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::common::character::{Character, Damageable};
+    use crate::common::sound::SoundWrangler;
+    use crate::common::upgrades::upgrade::PlayerState;
+    use crate::common::utils::duration_to_ticks;
+    use crate::prelude::Duration;
+
+    #[test]
+    fn new_characters_start_vulnerable() {
+        let player_state = Rc::new(RefCell::new(PlayerState::default()));
+        let wrangler = Rc::new(RefCell::new(SoundWrangler::default()));
+        let mut character = Character::new(&player_state, wrangler);
+        assert!(!character.is_invulnerable());
+
+        character.take_damage(1);
+        assert!(character.is_invulnerable());
+
+        // i-frames run out with the ticks played, not the time on the clock
+        let iframe_secs = player_state.borrow().stats.player_stats.iframe_secs;
+        character.on_tick(duration_to_ticks(Duration::from_secs_f64(iframe_secs)));
+        assert!(!character.is_invulnerable());
+    }
+}
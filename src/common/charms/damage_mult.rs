@@ -3,7 +3,7 @@ use crate::common::{
     PlayerStateRef,
     charms::Charm,
     powerup::{DynPowerup, PowerupTypes, Poweruppable},
-    stats::Stats,
+    statsheet::{StatKey, StatModifier, StatSource},
 };
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -33,8 +33,12 @@ impl Default for CharmDamageMult {
 }
 
 impl Charm for CharmDamageMult {
-    fn manipulate_stats(&self, stats: &mut Stats) {
-        stats.player_stats.damage_mult *= self.stat_boost;
+    fn get_modifiers(&self) -> Vec<StatModifier> {
+        vec![StatModifier::mult(
+            StatKey::DamageMult,
+            self.stat_boost,
+            StatSource::Charm(self.get_name()),
+        )]
     }
 }
 
@@ -51,6 +55,7 @@ impl Poweruppable for CharmDamageMult {
         PowerupTypes::Charm
     }
 
+    #[allow(clippy::match_same_arms)]
     fn upgrade_desc(&self, level: i32) -> String {
         match level {
             1 => "Multiply your Damage Multiplier by 1.25".into(),
@@ -62,6 +67,7 @@ impl Poweruppable for CharmDamageMult {
         }
     }
 
+    #[allow(clippy::match_same_arms)]
     fn upgrade_self(&mut self, powerup: &DynPowerup) {
         let from = powerup.get_current_level();
         let to = powerup.get_new_level();
@@ -3,7 +3,7 @@ use crate::common::{
     PlayerStateRef,
     charms::Charm,
     powerup::{DynPowerup, PowerupTypes, Poweruppable},
-    stats::Stats,
+    statsheet::{StatKey, StatModifier, StatSource},
 };
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -33,8 +33,12 @@ impl Default for CharmDoomOffset {
 }
 
 impl Charm for CharmDoomOffset {
-    fn manipulate_stats(&self, stats: &mut Stats) {
-        stats.game_stats.doom_offset += self.stat_boost;
+    fn get_modifiers(&self) -> Vec<StatModifier> {
+        vec![StatModifier::add(
+            StatKey::DoomOffset,
+            self.stat_boost,
+            StatSource::Charm(self.get_name()),
+        )]
     }
 }
 
@@ -0,0 +1,118 @@
+use crate::common::upgrades::upgrade::PlayerState;
+use crate::common::{
+    PlayerStateRef,
+    charms::Charm,
+    powerup::{DynPowerup, PowerupTypes, Poweruppable},
+    statsheet::StatModifier,
+};
+use crate::prelude::Duration;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The chance for a METHOD attack to repeat, and how hard the repeat hits.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Echo {
+    pub chance: f64,
+    /// The fraction of the original attack's damage the repeat deals.
+    pub damage_mult: f64,
+}
+
+impl Echo {
+    /// How long after the original attack the repeat lands.
+    pub const DELAY: Duration = Duration::from_millis(250);
+}
+
+/// Gives each METHOD attack a chance to fire again shortly after, at reduced damage.
+#[derive(Clone)]
+pub struct CharmEcho {
+    pub echo: Echo,
+    pub level: i32,
+    pub player_state: PlayerStateRef,
+}
+
+impl CharmEcho {
+    #[must_use]
+    pub fn new(player_state_ref: PlayerStateRef) -> Self {
+        Self {
+            echo: Echo {
+                chance: 0.15,
+                damage_mult: 0.5,
+            },
+            level: 1,
+            player_state: player_state_ref,
+        }
+    }
+}
+
+impl Default for CharmEcho {
+    fn default() -> Self {
+        Self::new(Rc::new(RefCell::new(PlayerState::default())))
+    }
+}
+
+impl Charm for CharmEcho {
+    fn get_modifiers(&self) -> Vec<StatModifier> {
+        vec![]
+    }
+
+    fn get_echo(&self) -> Option<Echo> {
+        Some(self.echo)
+    }
+}
+
+impl Poweruppable for CharmEcho {
+    fn get_max_level(&self) -> i32 {
+        self.player_state.borrow().stats.game_stats.max_charm_level
+    }
+
+    fn get_name(&self) -> String {
+        "Echo Charm".into()
+    }
+
+    fn get_powerup_type(&self) -> PowerupTypes {
+        PowerupTypes::Charm
+    }
+
+    #[allow(clippy::match_same_arms)]
+    fn upgrade_desc(&self, level: i32) -> String {
+        match level {
+            1 => "METHOD attacks have a 15% chance to repeat, dealing 50% damage".into(),
+            2 => "Increase repeat chance by 5%".into(),
+            3 => "Increase repeat damage to 60%".into(),
+            4 => "Increase repeat chance by 5%".into(),
+            5 => "Increase repeat chance by 10% and repeat damage to 75%".into(),
+            _ => String::new(),
+        }
+    }
+
+    fn upgrade_self(&mut self, powerup: &DynPowerup) {
+        let from = powerup.get_current_level();
+        let to = powerup.get_new_level();
+        if to <= from {
+            return;
+        }
+        self.level = to;
+
+        for i in (from + 1)..=to {
+            match i {
+                1 => {
+                    self.echo = Echo {
+                        chance: 0.15,
+                        damage_mult: 0.5,
+                    };
+                }
+                2 | 4 => self.echo.chance += 0.05,
+                3 => self.echo.damage_mult = 0.6,
+                5 => {
+                    self.echo.chance += 0.1;
+                    self.echo.damage_mult = 0.75;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get_level(&self) -> i32 {
+        self.level
+    }
+}
@@ -3,15 +3,28 @@ use strum::{EnumIter, EnumString, IntoStaticStr};
 use crate::common::{
     PlayerStateRef,
     charms::{
-        attack_speed::CharmAttackSpeed, damage_mult::CharmDamageMult, doom_offset::CharmDoomOffset,
+        attack_speed::CharmAttackSpeed,
+        damage_mult::CharmDamageMult,
+        doom_offset::CharmDoomOffset,
+        echo::{CharmEcho, Echo},
+        overclock::CharmOverclock,
+        shield::CharmShield,
+        stonefoot::CharmStonefoot,
     },
+    onkill::OnKillEffect,
     powerup::Poweruppable,
-    stats::Stats,
+    statsheet::StatModifier,
+    upgrades::upgrade::PlayerState,
 };
+use crate::prelude::Duration;
 
 pub mod attack_speed;
 pub mod damage_mult;
 pub mod doom_offset;
+pub mod echo;
+pub mod overclock;
+pub mod shield;
+pub mod stonefoot;
 
 #[derive(Clone, IntoStaticStr, EnumIter, EnumString)]
 pub enum CharmWrapper {
@@ -26,6 +39,18 @@ pub enum CharmWrapper {
 
     #[strum(serialize = "Attack Speed Charm", serialize = "ATTACK SPEED CHARM")]
     AttackSpeed(Option<CharmAttackSpeed>),
+
+    #[strum(serialize = "Shield Charm", serialize = "SHIELD CHARM")]
+    Shield(Option<CharmShield>),
+
+    #[strum(serialize = "Overclock Charm", serialize = "OVERCLOCK CHARM")]
+    Overclock(Option<CharmOverclock>),
+
+    #[strum(serialize = "Stonefoot Charm", serialize = "STONEFOOT CHARM")]
+    Stonefoot(Option<CharmStonefoot>),
+
+    #[strum(serialize = "Echo Charm", serialize = "ECHO CHARM")]
+    Echo(Option<CharmEcho>),
 }
 
 impl PartialEq for CharmWrapper {
@@ -37,6 +62,21 @@ impl PartialEq for CharmWrapper {
 }
 
 impl CharmWrapper {
+    /// Checks whether the player has unlocked this CHARM in the upgrade tree, so it can be
+    /// offered in runs and picked to start with.
+    #[must_use]
+    pub fn is_unlocked(&self, player_state: &PlayerState) -> bool {
+        match self {
+            CharmWrapper::DamageMult(_) => true,
+            CharmWrapper::AttackSpeed(_) => player_state.upgrade_owned("711"),
+            CharmWrapper::DoomOffset(_) => player_state.upgrade_owned("712"),
+            CharmWrapper::Shield(_) => player_state.upgrade_owned("713"),
+            CharmWrapper::Overclock(_) => player_state.upgrade_owned("714"),
+            CharmWrapper::Stonefoot(_) => player_state.upgrade_owned("715"),
+            CharmWrapper::Echo(_) => player_state.upgrade_owned("716"),
+        }
+    }
+
     /// Get a reference to the inner weapon.
     ///
     /// # Panics
@@ -50,6 +90,10 @@ impl CharmWrapper {
             CharmWrapper::AttackSpeed(attack_speed) => {
                 attack_speed.as_ref().expect("No inner charm.")
             }
+            CharmWrapper::Shield(shield) => shield.as_ref().expect("No inner charm."),
+            CharmWrapper::Overclock(overclock) => overclock.as_ref().expect("No inner charm."),
+            CharmWrapper::Stonefoot(stonefoot) => stonefoot.as_ref().expect("No inner charm."),
+            CharmWrapper::Echo(echo) => echo.as_ref().expect("No inner charm."),
         }
     }
     /// Get a mutable reference to the inner weapon.
@@ -64,6 +108,10 @@ impl CharmWrapper {
             CharmWrapper::AttackSpeed(attack_speed) => {
                 attack_speed.as_mut().expect("No inner charm.")
             }
+            CharmWrapper::Shield(shield) => shield.as_mut().expect("No inner charm."),
+            CharmWrapper::Overclock(overclock) => overclock.as_mut().expect("No inner charm."),
+            CharmWrapper::Stonefoot(stonefoot) => stonefoot.as_mut().expect("No inner charm."),
+            CharmWrapper::Echo(echo) => echo.as_mut().expect("No inner charm."),
         }
     }
 
@@ -78,11 +126,44 @@ impl CharmWrapper {
             CharmWrapper::AttackSpeed(attack_speed) => {
                 *attack_speed = Some(CharmAttackSpeed::new(player_state_ref));
             }
+            CharmWrapper::Shield(shield) => {
+                *shield = Some(CharmShield::new(player_state_ref));
+            }
+            CharmWrapper::Overclock(overclock) => {
+                *overclock = Some(CharmOverclock::new(player_state_ref));
+            }
+            CharmWrapper::Stonefoot(stonefoot) => {
+                *stonefoot = Some(CharmStonefoot::new(player_state_ref));
+            }
+            CharmWrapper::Echo(echo) => {
+                *echo = Some(CharmEcho::new(player_state_ref));
+            }
         }
     }
 }
 
 pub trait Charm: Poweruppable {
-    /// Manipulate Stats to be increased by this charm's effects. Stats should be reset before calling this method.
-    fn manipulate_stats(&self, stats: &mut Stats);
+    /// Returns this charm's effects as stat sheet modifiers.
+    fn get_modifiers(&self) -> Vec<StatModifier>;
+
+    /// Returns the effects this charm sets off when any METHOD kills an enemy.
+    fn get_on_kill_effects(&self) -> Vec<OnKillEffect> {
+        vec![]
+    }
+
+    /// Returns how often this charm drains a point of health, for charms that do.
+    fn get_health_drain(&self) -> Option<Duration> {
+        None
+    }
+
+    /// Returns the fraction of incoming damage this charm blocks, given how long the character
+    /// has stood still.
+    fn get_damage_reduction(&self, _still_for: Duration) -> f64 {
+        0.
+    }
+
+    /// Returns the chance for METHOD attacks to repeat, for charms that give one.
+    fn get_echo(&self) -> Option<Echo> {
+        None
+    }
 }
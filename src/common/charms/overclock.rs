@@ -0,0 +1,108 @@
+use crate::common::upgrades::upgrade::PlayerState;
+use crate::common::{
+    PlayerStateRef,
+    charms::Charm,
+    powerup::{DynPowerup, PowerupTypes, Poweruppable},
+    statsheet::{StatKey, StatModifier, StatSource},
+};
+use crate::prelude::Duration;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Multiplies attack speed, at the cost of draining health every few seconds.
+#[derive(Clone)]
+pub struct CharmOverclock {
+    pub stat_boost: f64,
+    /// Seconds between each point of health drained.
+    pub drain_secs: f64,
+    pub level: i32,
+    pub player_state: PlayerStateRef,
+}
+
+impl CharmOverclock {
+    #[must_use]
+    pub fn new(player_state_ref: PlayerStateRef) -> Self {
+        Self {
+            stat_boost: 1.5,
+            drain_secs: 4.,
+            level: 1,
+            player_state: player_state_ref,
+        }
+    }
+}
+
+impl Default for CharmOverclock {
+    fn default() -> Self {
+        Self::new(Rc::new(RefCell::new(PlayerState::default())))
+    }
+}
+
+impl Charm for CharmOverclock {
+    fn get_modifiers(&self) -> Vec<StatModifier> {
+        vec![StatModifier::mult(
+            StatKey::AttackSpeedMult,
+            self.stat_boost,
+            StatSource::Charm(self.get_name()),
+        )]
+    }
+
+    fn get_health_drain(&self) -> Option<Duration> {
+        Some(Duration::from_secs_f64(self.drain_secs))
+    }
+}
+
+impl Poweruppable for CharmOverclock {
+    fn get_max_level(&self) -> i32 {
+        self.player_state.borrow().stats.game_stats.max_charm_level
+    }
+
+    fn get_name(&self) -> String {
+        "Overclock Charm".into()
+    }
+
+    fn get_powerup_type(&self) -> PowerupTypes {
+        PowerupTypes::Charm
+    }
+
+    fn upgrade_desc(&self, level: i32) -> String {
+        match level {
+            1 => "Multiply your Attack Speed by 1.5, but lose 1 health every 4s".into(),
+            2 => "Increase Attack Speed Mult by 0.25, lose health every 3.5s".into(),
+            3 => "Increase Attack Speed Mult by 0.25, lose health every 3s".into(),
+            4 => "Increase Attack Speed Mult by 0.5, lose health every 2.5s".into(),
+            5 => "Increase Attack Speed Mult by 0.5, lose health every 2s".into(),
+            _ => String::new(),
+        }
+    }
+
+    fn upgrade_self(&mut self, powerup: &DynPowerup) {
+        let from = powerup.get_current_level();
+        let to = powerup.get_new_level();
+        if to <= from {
+            return;
+        }
+        self.level = to;
+
+        for i in (from + 1)..=to {
+            match i {
+                1 => {
+                    self.stat_boost = 1.5;
+                    self.drain_secs = 4.;
+                }
+                2 | 3 => {
+                    self.stat_boost += 0.25;
+                    self.drain_secs -= 0.5;
+                }
+                4 | 5 => {
+                    self.stat_boost += 0.5;
+                    self.drain_secs -= 0.5;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get_level(&self) -> i32 {
+        self.level
+    }
+}
@@ -0,0 +1,94 @@
+use crate::common::upgrades::upgrade::PlayerState;
+use crate::common::{
+    PlayerStateRef,
+    charms::Charm,
+    powerup::{DynPowerup, PowerupTypes, Poweruppable},
+    statsheet::{StatKey, StatModifier, StatSource},
+};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+#[derive(Clone)]
+pub struct CharmShield {
+    pub stat_boost: i32,
+    pub level: i32,
+    pub player_state: PlayerStateRef,
+}
+
+impl CharmShield {
+    #[must_use]
+    pub fn new(player_state_ref: PlayerStateRef) -> Self {
+        Self {
+            stat_boost: 3,
+            level: 1,
+            player_state: player_state_ref,
+        }
+    }
+}
+
+impl Default for CharmShield {
+    fn default() -> Self {
+        Self::new(Rc::new(RefCell::new(PlayerState::default())))
+    }
+}
+
+impl Charm for CharmShield {
+    fn get_modifiers(&self) -> Vec<StatModifier> {
+        vec![StatModifier::add(
+            StatKey::Shield,
+            f64::from(self.stat_boost),
+            StatSource::Charm(self.get_name()),
+        )]
+    }
+}
+
+impl Poweruppable for CharmShield {
+    fn get_max_level(&self) -> i32 {
+        self.player_state.borrow().stats.game_stats.max_charm_level
+    }
+
+    fn get_name(&self) -> String {
+        "Shield Charm".into()
+    }
+
+    fn get_powerup_type(&self) -> PowerupTypes {
+        PowerupTypes::Charm
+    }
+
+    #[allow(clippy::match_same_arms)]
+    fn upgrade_desc(&self, level: i32) -> String {
+        match level {
+            1 => "Gain 3 Shield, which absorbs damage before health.".into(),
+            2 => "Increase Shield by 2".into(),
+            3 => "Increase Shield by 2".into(),
+            4 => "Increase Shield by 3".into(),
+            5 => "Increase Shield by 5".into(),
+            _ => String::new(),
+        }
+    }
+
+    #[allow(clippy::match_same_arms)]
+    fn upgrade_self(&mut self, powerup: &DynPowerup) {
+        let from = powerup.get_current_level();
+        let to = powerup.get_new_level();
+        if to <= from {
+            return;
+        }
+        self.level = to;
+
+        for i in (from + 1)..=to {
+            match i {
+                1 => self.stat_boost = 3,
+                2 => self.stat_boost += 2,
+                3 => self.stat_boost += 2,
+                4 => self.stat_boost += 3,
+                5 => self.stat_boost += 5,
+                _ => {}
+            }
+        }
+    }
+
+    fn get_level(&self) -> i32 {
+        self.level
+    }
+}
@@ -0,0 +1,133 @@
+use crate::common::upgrades::upgrade::PlayerState;
+use crate::common::{
+    PlayerStateRef,
+    charms::Charm,
+    powerup::{DynPowerup, PowerupTypes, Poweruppable},
+    statsheet::StatModifier,
+};
+use crate::prelude::Duration;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Builds up damage reduction while the character stands still, lost as soon as it moves.
+#[derive(Clone)]
+pub struct CharmStonefoot {
+    /// The fraction of damage blocked by each stack.
+    pub per_stack: f64,
+    pub max_stacks: u32,
+    pub level: i32,
+    pub player_state: PlayerStateRef,
+}
+
+impl CharmStonefoot {
+    /// How long the character has to stand still before the first stack.
+    pub const SETTLE: Duration = Duration::from_secs(2);
+    /// How long each further stack takes.
+    pub const STACK_EVERY: Duration = Duration::from_secs(1);
+
+    #[must_use]
+    pub fn new(player_state_ref: PlayerStateRef) -> Self {
+        Self {
+            per_stack: 0.08,
+            max_stacks: 3,
+            level: 1,
+            player_state: player_state_ref,
+        }
+    }
+
+    /// Returns how many stacks have built up after standing still for `still_for`.
+    #[must_use]
+    pub fn get_stacks(&self, still_for: Duration) -> u32 {
+        let Some(settled) = still_for.checked_sub(Self::SETTLE) else {
+            return 0;
+        };
+
+        let stacks = 1 + (settled.as_secs_f64() / Self::STACK_EVERY.as_secs_f64()).floor() as u32;
+        stacks.min(self.max_stacks)
+    }
+}
+
+impl Default for CharmStonefoot {
+    fn default() -> Self {
+        Self::new(Rc::new(RefCell::new(PlayerState::default())))
+    }
+}
+
+impl Charm for CharmStonefoot {
+    fn get_modifiers(&self) -> Vec<StatModifier> {
+        vec![]
+    }
+
+    fn get_damage_reduction(&self, still_for: Duration) -> f64 {
+        f64::from(self.get_stacks(still_for)) * self.per_stack
+    }
+}
+
+impl Poweruppable for CharmStonefoot {
+    fn get_max_level(&self) -> i32 {
+        self.player_state.borrow().stats.game_stats.max_charm_level
+    }
+
+    fn get_name(&self) -> String {
+        "Stonefoot Charm".into()
+    }
+
+    fn get_powerup_type(&self) -> PowerupTypes {
+        PowerupTypes::Charm
+    }
+
+    #[allow(clippy::match_same_arms)]
+    fn upgrade_desc(&self, level: i32) -> String {
+        match level {
+            1 => "Stand still for 2s to block 8% of damage per second still, up to 3 stacks".into(),
+            2 => "Increase damage blocked per stack to 10%".into(),
+            3 => "Increase max stacks by 1".into(),
+            4 => "Increase damage blocked per stack to 12%".into(),
+            5 => "Increase max stacks by 1".into(),
+            _ => String::new(),
+        }
+    }
+
+    fn upgrade_self(&mut self, powerup: &DynPowerup) {
+        let from = powerup.get_current_level();
+        let to = powerup.get_new_level();
+        if to <= from {
+            return;
+        }
+        self.level = to;
+
+        for i in (from + 1)..=to {
+            match i {
+                1 => {
+                    self.per_stack = 0.08;
+                    self.max_stacks = 3;
+                }
+                2 => self.per_stack = 0.1,
+                3 | 5 => self.max_stacks += 1,
+                4 => self.per_stack = 0.12,
+                _ => {}
+            }
+        }
+    }
+
+    fn get_level(&self) -> i32 {
+        self.level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::charms::{Charm, stonefoot::CharmStonefoot};
+    use crate::prelude::Duration;
+
+    #[test]
+    fn stacks_build_while_still() {
+        let charm = CharmStonefoot::default();
+
+        assert_eq!(charm.get_stacks(Duration::from_millis(1900)), 0);
+        assert_eq!(charm.get_stacks(CharmStonefoot::SETTLE), 1);
+        assert_eq!(charm.get_stacks(Duration::from_millis(3500)), 2);
+        assert_eq!(charm.get_stacks(Duration::from_mins(1)), charm.max_stacks);
+        assert!((charm.get_damage_reduction(Duration::from_mins(1)) - 0.24).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,118 @@
+//! This module defines the playable character classes.
+//! Each class brings its own starting METHOD, base stat changes and a passive.
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoStaticStr};
+
+use crate::common::statsheet::{StatKey, StatModifier, StatSource};
+use crate::common::{PlayerStateRef, stats::WeaponStats, weapons::WeaponWrapper};
+
+/// The class the player has chosen for their next run.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, IntoStaticStr,
+)]
+pub enum CharacterClass {
+    #[default]
+    #[strum(serialize = "WANDERER")]
+    Wanderer,
+
+    #[strum(serialize = "BULWARK")]
+    Bulwark,
+
+    #[strum(serialize = "SHARD")]
+    Shard,
+}
+
+impl CharacterClass {
+    #[must_use]
+    pub fn get_name(&self) -> &'static str {
+        self.into()
+    }
+
+    #[must_use]
+    pub fn get_desc(&self) -> &'static str {
+        match self {
+            CharacterClass::Wanderer => "no strengths. no weaknesses.\nwhat you make of it.",
+            CharacterClass::Bulwark => "slow to move, slower to fall.",
+            CharacterClass::Shard => "sharp enough to cut. brittle enough to break.",
+        }
+    }
+
+    /// Describes the class's unique passive.
+    #[must_use]
+    pub fn get_passive_desc(&self) -> &'static str {
+        match self {
+            CharacterClass::Wanderer => "none",
+            CharacterClass::Bulwark => {
+                "+50% health, -20% movement speed. shoves adjacent enemies back 1."
+            }
+            CharacterClass::Shard => "+50% damage, -40% health.",
+        }
+    }
+
+    /// Returns an empty `WeaponWrapper` for the METHOD this class starts a run with.
+    #[must_use]
+    pub fn get_starting_weapon_type(&self) -> WeaponWrapper {
+        match self {
+            CharacterClass::Wanderer => WeaponWrapper::Flash(None),
+            CharacterClass::Bulwark => WeaponWrapper::Pillar(None),
+            CharacterClass::Shard => WeaponWrapper::Lightning(None),
+        }
+    }
+
+    /// Creates the populated METHOD this class starts a run with.
+    #[must_use]
+    pub fn get_starting_weapon(
+        &self,
+        weapon_stats: WeaponStats,
+        player_state: PlayerStateRef,
+    ) -> WeaponWrapper {
+        let mut weapon = self.get_starting_weapon_type();
+        weapon.populate_inner(weapon_stats, player_state);
+        weapon
+    }
+
+    /// Returns this class's base stats and passive as stat sheet modifiers. Health is derived
+    /// from `health_mult` once they're applied.
+    #[must_use]
+    pub fn get_modifiers(&self) -> Vec<StatModifier> {
+        let source = StatSource::Class(*self);
+
+        match self {
+            CharacterClass::Wanderer => vec![],
+            CharacterClass::Bulwark => vec![
+                StatModifier::add(StatKey::HealthMult, 0.5, source.clone()),
+                StatModifier::add(StatKey::MovementSpeedMult, -0.2, source.clone()),
+                StatModifier::add(StatKey::ShoveAmount, 1., source),
+            ],
+            CharacterClass::Shard => vec![
+                StatModifier::add(StatKey::DamageMult, 0.5, source.clone()),
+                StatModifier::add(StatKey::HealthMult, -0.4, source),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::classes::class::CharacterClass;
+    use crate::common::upgrades::upgrade::PlayerState;
+
+    #[test]
+    fn class_stats_applied_on_refresh() {
+        let mut player_state = PlayerState::default();
+        let base_health = player_state.stats.player_stats.health;
+
+        player_state.class = CharacterClass::Bulwark;
+        player_state.refresh();
+
+        assert!(player_state.stats.player_stats.health > base_health);
+        assert_eq!(player_state.stats.player_stats.shove_amount, 1);
+
+        player_state.class = CharacterClass::Shard;
+        player_state.refresh();
+
+        assert!(player_state.stats.player_stats.health < base_health);
+        assert_eq!(player_state.stats.player_stats.shove_amount, 0);
+    }
+}
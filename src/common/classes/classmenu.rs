@@ -0,0 +1,147 @@
+//! This module provides the pre-run screen for choosing a character class.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+};
+use strum::IntoEnumIterator;
+
+use crate::common::classes::class::CharacterClass;
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::{Goto, PlayerStateRef, View};
+
+/// A struct that manages the state and rendering of the class selection screen.
+pub struct ClassMenu {
+    pub player_state: PlayerStateRef,
+    classes: Vec<CharacterClass>,
+    pub class_selection: ListState,
+    pub goto: Goto,
+}
+
+impl ClassMenu {
+    /// Creates a new `ClassMenu` with the player's current class selected.
+    #[must_use]
+    pub fn new(player_state: PlayerStateRef) -> Self {
+        let classes: Vec<CharacterClass> = CharacterClass::iter().collect();
+        let current_class = player_state.borrow().class;
+
+        let mut class_selection = ListState::default();
+        class_selection.select(Some(
+            classes
+                .iter()
+                .position(|class| *class == current_class)
+                .unwrap_or(0),
+        ));
+
+        Self {
+            player_state,
+            classes,
+            class_selection,
+            goto: Goto::ClassSelect,
+        }
+    }
+
+    /// Handles actions for the class menu.
+    pub fn on_action(&mut self, action: Action) {
+        match action {
+            Action::Up => self.class_selection.select_previous(),
+            Action::Down => self.class_selection.select_next(),
+            Action::Confirm => self.confirm_class(),
+            Action::Mutators => {
+                self.player_state.borrow_mut().class = self.get_selected_class();
+                self.goto = Goto::Mutators;
+            }
+            Action::Back => self.goto = Goto::Upgrades,
+            _ => {}
+        }
+    }
+
+    /// Returns the currently highlighted class.
+    #[must_use]
+    pub fn get_selected_class(&self) -> CharacterClass {
+        self.class_selection
+            .selected()
+            .and_then(|index| self.classes.get(index))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Stores the highlighted class in the player's state and moves on to the loadout.
+    pub fn confirm_class(&mut self) {
+        self.player_state.borrow_mut().class = self.get_selected_class();
+        self.goto = Goto::Loadout;
+    }
+
+    /// Renders the class menu to the frame.
+    pub fn render_classes(&mut self, frame: &mut Frame) {
+        let title = Line::from(" dispair.class ".bold());
+        let instructions = Line::from(vec![
+            " <W|UP> Up | <S|DOWN> Down | <ENTER|SPACE> Loadout | <M> Mutators | <Esc> Back "
+                .into(),
+        ]);
+
+        let window = Block::bordered()
+            .border_set(border::THICK)
+            .title(title.left_aligned())
+            .title_bottom(instructions.left_aligned());
+        let inner = window.inner(frame.area());
+
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Fill(1)]).areas(inner);
+
+        let list_items: Vec<ListItem> = self
+            .classes
+            .iter()
+            .map(|class| ListItem::from(format!(" {}", class.get_name())))
+            .collect();
+
+        let list = List::new(list_items)
+            .highlight_style(Style::new().bold())
+            .highlight_symbol(">");
+
+        let class = self.get_selected_class();
+        let starting_weapon: &'static str = class.get_starting_weapon_type().into();
+
+        let mut class_lines = vec![Line::from(class.get_name().bold()), Line::from("")];
+        class_lines.extend(class.get_desc().lines().map(Line::from));
+        class_lines.push(Line::from(""));
+        class_lines.push(Line::from("starting METHOD".dark_gray()));
+        class_lines.push(Line::from(starting_weapon.to_uppercase()));
+        class_lines.push(Line::from(""));
+        class_lines.push(Line::from("passive".dark_gray()));
+        class_lines.push(Line::from(class.get_passive_desc()));
+
+        let class_paragraph = Paragraph::new(class_lines)
+            .block(Block::bordered().border_set(border::ROUNDED))
+            .centered()
+            .wrap(Wrap { trim: false });
+
+        frame.render_widget(window, frame.area());
+        frame.render_stateful_widget(list, left, &mut self.class_selection);
+        frame.render_widget(class_paragraph, right);
+    }
+}
+
+impl View for ClassMenu {
+    fn on_tick(&mut self) {}
+
+    fn transition(&self) -> &Goto {
+        &self.goto
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.render_classes(frame);
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        self.on_action(action);
+    }
+
+    fn get_key_context(&self) -> KeyContext {
+        KeyContext::ClassSelect
+    }
+}
@@ -0,0 +1,2 @@
+pub mod class;
+pub mod classmenu;
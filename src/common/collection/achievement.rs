@@ -0,0 +1,77 @@
+//! This module defines achievements: milestones reached during runs that are kept between runs,
+//! some of which unlock METHODs.
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator, IntoStaticStr};
+
+use crate::common::events::RunEvent;
+
+/// A milestone the player has reached in a run.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, IntoStaticStr,
+)]
+pub enum Achievement {
+    #[strum(serialize = "PERSISTENT")]
+    Level15,
+
+    #[strum(serialize = "UNRELENTING")]
+    Level25,
+}
+
+impl Achievement {
+    #[must_use]
+    pub fn get_name(&self) -> &'static str {
+        self.into()
+    }
+
+    #[must_use]
+    pub fn get_desc(&self) -> &'static str {
+        match self {
+            Achievement::Level15 => "reach level 15 in a run.",
+            Achievement::Level25 => "reach level 25 in a run.",
+        }
+    }
+
+    /// Checks whether `event` earns this achievement.
+    #[must_use]
+    pub fn is_earned_by(&self, event: &RunEvent) -> bool {
+        match (self, event) {
+            (Achievement::Level15, RunEvent::LevelUp(level)) => *level >= 15,
+            (Achievement::Level25, RunEvent::LevelUp(level)) => *level >= 25,
+        }
+    }
+
+    /// Returns every achievement `event` earns.
+    #[must_use]
+    pub fn earned_by(event: &RunEvent) -> Vec<Achievement> {
+        Achievement::iter()
+            .filter(|achievement| achievement.is_earned_by(event))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::collection::achievement::Achievement;
+    use crate::common::events::RunEvent;
+    use crate::common::upgrades::upgrade::PlayerState;
+    use crate::common::weapons::WeaponWrapper;
+
+    #[test]
+    fn achievements_unlock_methods() {
+        assert!(Achievement::earned_by(&RunEvent::LevelUp(14)).is_empty());
+        assert_eq!(
+            Achievement::earned_by(&RunEvent::LevelUp(15)),
+            vec![Achievement::Level15]
+        );
+
+        let mut player_state = PlayerState::default();
+        let turret = WeaponWrapper::Turret(None);
+        assert!(!turret.is_unlocked(&player_state));
+
+        assert!(player_state.earn(Achievement::Level15));
+        assert!(!player_state.earn(Achievement::Level15));
+        assert!(turret.is_unlocked(&player_state));
+        assert!(!WeaponWrapper::Boomerang(None).is_unlocked(&player_state));
+    }
+}
@@ -0,0 +1,187 @@
+//! This module provides the collection screen, which lists every METHOD with how to unlock the
+//! ones still locked, and the achievements earned so far.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+};
+use strum::IntoEnumIterator;
+
+use crate::common::collection::achievement::Achievement;
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::upgrades::upgrade::{UpgradeNode, get_upgrade_tree};
+use crate::common::weapons::WeaponWrapper;
+use crate::common::{Goto, PlayerStateRef, View};
+
+/// A struct that manages the state and rendering of the collection screen.
+pub struct CollectionMenu {
+    pub player_state: PlayerStateRef,
+    weapons: Vec<WeaponWrapper>,
+    /// The upgrade tree, for naming the upgrades that unlock METHODs.
+    upgrade_tree: Vec<UpgradeNode>,
+    pub weapon_selection: ListState,
+    pub goto: Goto,
+}
+
+impl CollectionMenu {
+    #[must_use]
+    pub fn new(player_state: PlayerStateRef) -> Self {
+        let mut weapon_selection = ListState::default();
+        weapon_selection.select_first();
+
+        Self {
+            player_state,
+            weapons: WeaponWrapper::iter().collect(),
+            // hints fall back to upgrade ids if the tree can't be read
+            upgrade_tree: get_upgrade_tree().unwrap_or_default(),
+            weapon_selection,
+            goto: Goto::Collection,
+        }
+    }
+
+    /// Handles actions for the collection menu.
+    pub fn on_action(&mut self, action: Action) {
+        match action {
+            Action::Up => self.weapon_selection.select_previous(),
+            Action::Down => self.weapon_selection.select_next(),
+            Action::Mastery => self.goto = Goto::Mastery,
+            Action::Back => self.goto = Goto::Upgrades,
+            _ => {}
+        }
+    }
+
+    /// Returns the currently highlighted METHOD.
+    #[must_use]
+    pub fn get_selected_weapon(&self) -> Option<&WeaponWrapper> {
+        self.weapon_selection
+            .selected()
+            .and_then(|index| self.weapons.get(index))
+    }
+
+    /// Describes the highlighted METHOD and what unlocks it.
+    fn get_weapon_lines(&self) -> Vec<Line<'static>> {
+        let Some(weapon) = self.get_selected_weapon() else {
+            return vec![];
+        };
+
+        let player_state = self.player_state.borrow();
+        let name: &'static str = weapon.into();
+
+        let mut lines = vec![Line::from(name.to_uppercase().bold()), Line::from("")];
+
+        if weapon.is_unlocked(&player_state) {
+            lines.push(Line::from("unlocked".light_green()));
+        } else {
+            lines.push(Line::from("locked".light_red()));
+            lines.push(Line::from(""));
+            lines.push(Line::from("unlock by any of".dark_gray()));
+            lines.extend(
+                weapon
+                    .get_unlock_conditions()
+                    .iter()
+                    .map(|condition| Line::from(condition.get_hint(&self.upgrade_tree))),
+            );
+        }
+
+        lines
+    }
+
+    /// Lists every achievement, marking the ones earned.
+    fn get_achievement_lines(&self) -> Vec<Line<'static>> {
+        let player_state = self.player_state.borrow();
+
+        Achievement::iter()
+            .map(|achievement| {
+                let line = format!(" {} - {}", achievement.get_name(), achievement.get_desc());
+
+                if player_state.achievements.contains(&achievement) {
+                    Line::from(format!("[x]{line}"))
+                } else {
+                    Line::from(format!("[ ]{line}").dark_gray())
+                }
+            })
+            .collect()
+    }
+
+    /// Renders the collection menu to the frame.
+    pub fn render_collection(&mut self, frame: &mut Frame) {
+        let title = Line::from(" dispair.collection ".bold());
+        let instructions = Line::from(vec![
+            " <W|UP> Up | <S|DOWN> Down | <M> Mastery | <Esc> Back ".into(),
+        ]);
+
+        let window = Block::bordered()
+            .border_set(border::THICK)
+            .title(title.left_aligned())
+            .title_bottom(instructions.left_aligned());
+        let inner = window.inner(frame.area());
+
+        let [top, achievements_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(Achievement::iter().count() as u16 + 2),
+        ])
+        .areas(inner);
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Fill(1)]).areas(top);
+
+        let list_items: Vec<ListItem> = {
+            let player_state = self.player_state.borrow();
+
+            self.weapons
+                .iter()
+                .map(|weapon| {
+                    let name: &'static str = weapon.into();
+                    if weapon.is_unlocked(&player_state) {
+                        ListItem::from(format!(" [x] {}", name.to_uppercase()))
+                    } else {
+                        ListItem::from(format!(" [ ] {}", name.to_uppercase()).dark_gray())
+                    }
+                })
+                .collect()
+        };
+
+        let list = List::new(list_items)
+            .highlight_style(Style::new().bold())
+            .highlight_symbol(">");
+
+        let weapon_paragraph = Paragraph::new(self.get_weapon_lines())
+            .block(Block::bordered().border_set(border::ROUNDED))
+            .centered()
+            .wrap(Wrap { trim: false });
+
+        let achievements = Paragraph::new(self.get_achievement_lines()).block(
+            Block::bordered()
+                .border_set(border::ROUNDED)
+                .title(" achievements "),
+        );
+
+        frame.render_widget(window, frame.area());
+        frame.render_stateful_widget(list, left, &mut self.weapon_selection);
+        frame.render_widget(weapon_paragraph, right);
+        frame.render_widget(achievements, achievements_area);
+    }
+}
+
+impl View for CollectionMenu {
+    fn on_tick(&mut self) {}
+
+    fn transition(&self) -> &Goto {
+        &self.goto
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.render_collection(frame);
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        self.on_action(action);
+    }
+
+    fn get_key_context(&self) -> KeyContext {
+        KeyContext::Collection
+    }
+}
@@ -0,0 +1,133 @@
+//! This module provides the combo meter, which fills as enemies are killed in quick succession
+//! and drains once the kills stop. While it is full, gold and XP drops are worth more.
+
+use ratatui::style::Style;
+use ratatui::text::Span;
+
+use crate::common::TICK_RATE;
+use crate::prelude::Duration;
+
+/// Tracks the current kill streak and how full the combo meter is.
+#[derive(Debug, Clone, Default)]
+pub struct ComboMeter {
+    /// How full the meter is, counted in kills up to `MAX_HEAT`.
+    heat: f64,
+    /// The kills since the meter was last empty.
+    streak: u64,
+    /// The ticks since the last kill.
+    idle_ticks: u64,
+}
+
+impl ComboMeter {
+    /// How many kills it takes to fill the meter.
+    pub const MAX_HEAT: f64 = 15.;
+    /// How long the meter holds after a kill before it starts to drain.
+    pub const GRACE: Duration = Duration::from_millis(1500);
+    /// How long a full meter takes to drain once it starts.
+    pub const DRAIN: Duration = Duration::from_secs(3);
+    /// The multiplier on gold and XP drops while the meter is full.
+    pub const DROP_MULT: f64 = 1.5;
+
+    /// Adds kills to the streak and meter, holding the meter off draining again.
+    pub fn add_kills(&mut self, kills: u64) {
+        if kills == 0 {
+            return;
+        }
+
+        self.streak += kills;
+        self.heat = (self.heat + kills as f64).min(Self::MAX_HEAT);
+        self.idle_ticks = 0;
+    }
+
+    /// Drains the meter by a tick's worth once the grace period since the last kill has passed,
+    /// ending the streak when it empties.
+    pub fn tick(&mut self) {
+        self.idle_ticks += 1;
+        if (self.idle_ticks as f64) < Self::GRACE.as_secs_f64() * TICK_RATE {
+            return;
+        }
+
+        self.heat -= Self::MAX_HEAT / (Self::DRAIN.as_secs_f64() * TICK_RATE);
+        if self.heat <= 0. {
+            self.heat = 0.;
+            self.streak = 0;
+        }
+    }
+
+    #[must_use]
+    pub fn is_maxed(&self) -> bool {
+        self.heat >= Self::MAX_HEAT
+    }
+
+    /// Returns the multiplier on gold and XP drops, which only applies while the meter is full.
+    #[must_use]
+    pub fn get_drop_mult(&self) -> f64 {
+        if self.is_maxed() { Self::DROP_MULT } else { 1. }
+    }
+
+    #[must_use]
+    pub fn get_streak(&self) -> u64 {
+        self.streak
+    }
+
+    /// Builds the combo counter shown in the HUD, with a bar for how full the meter is. Returns
+    /// `None` when there is no streak going.
+    #[must_use]
+    pub fn get_chip(&self) -> Option<Span<'static>> {
+        const BAR_WIDTH: usize = 5;
+
+        if self.streak < 2 {
+            return None;
+        }
+
+        let filled = ((self.heat / Self::MAX_HEAT) * BAR_WIDTH as f64).ceil() as usize;
+        let bar = format!(
+            "{}{}",
+            "▰".repeat(filled),
+            "▱".repeat(BAR_WIDTH.saturating_sub(filled))
+        );
+
+        Some(if self.is_maxed() {
+            Span::styled(
+                format!(" COMBO {} {bar} x{} ", self.streak, Self::DROP_MULT),
+                Style::new().black().on_light_yellow().bold(),
+            )
+        } else {
+            Span::styled(
+                format!(" COMBO {} {bar} ", self.streak),
+                Style::new().light_yellow(),
+            )
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::TICK_RATE;
+    use crate::common::combo::ComboMeter;
+
+    #[test]
+    fn meter_fills_holds_and_drains() {
+        let mut combo = ComboMeter::default();
+        combo.add_kills(ComboMeter::MAX_HEAT as u64 + 5);
+
+        assert!(combo.is_maxed());
+        assert_eq!(combo.get_streak(), 20);
+        assert!((combo.get_drop_mult() - ComboMeter::DROP_MULT).abs() < f64::EPSILON);
+
+        // still full through the grace period
+        let grace_ticks = (ComboMeter::GRACE.as_secs_f64() * TICK_RATE).ceil() as usize;
+        for _ in 1..grace_ticks {
+            combo.tick();
+        }
+        assert!(combo.is_maxed());
+
+        let drain_ticks = (ComboMeter::DRAIN.as_secs_f64() * TICK_RATE).ceil() as usize;
+        for _ in 0..=drain_ticks {
+            combo.tick();
+        }
+        assert!(!combo.is_maxed());
+        assert_eq!(combo.get_streak(), 0);
+        assert!(combo.get_chip().is_none());
+    }
+}
@@ -0,0 +1,72 @@
+//! This module provides the partner for local co-op: a second character on the same keyboard,
+//! moved with the arrow keys while the first player keeps WASD. The partner has their own health
+//! and METHODs, while gold, XP and the stat sheet are shared, and both share one camera.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use ratatui::text::Span;
+
+use crate::common::PlayerStateRef;
+use crate::common::character::{Character, Damageable, Renderable};
+use crate::common::input::MoveIntent;
+use crate::common::sound::SoundWrangler;
+
+/// The second player in local co-op.
+pub struct Partner {
+    pub character: Character,
+    /// The direction the partner is holding, stepped in on ticks.
+    pub move_intent: MoveIntent,
+    /// The attack interval of each of the partner's weapons in ticks, in the same order as the
+    /// weapons.
+    pub weapon_attack_ticks: Vec<u64>,
+    /// Whether the partner going down has been announced.
+    down_reported: bool,
+}
+
+impl Partner {
+    /// The glyph the partner is drawn with, to tell them apart from the first player.
+    pub const GLYPH: &str = "@";
+
+    #[must_use]
+    pub fn new(player_state: &PlayerStateRef, sound_wrangler: Rc<RefCell<SoundWrangler>>) -> Self {
+        Self {
+            character: Character::new(player_state, sound_wrangler),
+            move_intent: MoveIntent::default(),
+            weapon_attack_ticks: vec![],
+            down_reported: false,
+        }
+    }
+
+    /// Returns the partner as drawn on the map.
+    #[must_use]
+    pub fn get_styled(&self) -> Span<'static> {
+        Span::styled(Self::GLYPH, self.character.get_styled().style)
+    }
+
+    /// Checks whether the partner has just gone down, only answering yes the first time.
+    pub fn take_down(&mut self) -> bool {
+        let down = !self.character.is_alive() && !self.down_reported;
+        self.down_reported |= down;
+        down
+    }
+}
+
+/// Checks whether two players `dist_x` and `dist_y` apart both fit in a camera `width` by
+/// `height` cells.
+#[must_use]
+pub fn fits_in_view(dist_x: i32, dist_y: i32, width: i32, height: i32) -> bool {
+    dist_x.abs() < width.max(1) && dist_y.abs() < height.max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::coop::fits_in_view;
+
+    #[test]
+    fn partners_stay_in_the_shared_camera() {
+        assert!(fits_in_view(19, -5, 20, 6));
+        assert!(!fits_in_view(20, 0, 20, 6));
+        assert!(!fits_in_view(0, -6, 20, 6));
+    }
+}
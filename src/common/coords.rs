@@ -10,6 +10,7 @@ pub enum AreaWrapper {
 }
 
 impl AreaWrapper {
+    #[must_use]
     pub fn get_inner(&self) -> &dyn Area {
         match self {
             AreaWrapper::Square(square) => square,
@@ -71,13 +72,162 @@ impl Position {
     }
 }
 
-/// Represents the four cardinal directions.
-#[derive(Clone, PartialEq, Eq)]
+/// How the edges of the map behave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Topology {
+    /// The edges are walls.
+    #[default]
+    Bounded,
+    /// Going off one edge comes back in at the opposite one.
+    Wrapping,
+}
+
+impl Topology {
+    /// Brings `position` back onto `layer`, clamping it to the edges or wrapping it around.
+    pub fn constrain(self, position: &mut Position, layer: &Layer) {
+        match self {
+            Topology::Bounded => position.constrain(layer),
+            Topology::Wrapping => {
+                position.0 = position.0.rem_euclid(layer[0].len() as i32);
+                position.1 = position.1.rem_euclid(layer.len() as i32);
+            }
+        }
+    }
+
+    /// Calculates the (dx, dy) distance from `from` to `to` on `layer`, crossing the edges when
+    /// wrapping makes that shorter.
+    #[must_use]
+    pub fn get_distance(self, from: &Position, to: &Position, layer: &Layer) -> (i32, i32) {
+        let (dx, dy) = from.get_distance(to);
+
+        match self {
+            Topology::Bounded => (dx, dy),
+            Topology::Wrapping => (
+                shortest_wrapped(dx, layer[0].len() as i32),
+                shortest_wrapped(dy, layer.len() as i32),
+            ),
+        }
+    }
+
+    /// Brings `area` onto `layer`. Wrapping areas that cross an edge are split into the cells on
+    /// either side, so they come back as a `ChaosArea`.
+    #[must_use]
+    pub fn constrain_area(self, area: AreaWrapper, layer: &Layer) -> AreaWrapper {
+        match self {
+            Topology::Bounded => {
+                let mut area = area;
+                area.get_inner_mut().constrain(layer);
+                area
+            }
+            Topology::Wrapping => {
+                let mut positions: Vec<Position> = area
+                    .get_inner()
+                    .get_positions()
+                    .into_iter()
+                    .map(|mut position| {
+                        self.constrain(&mut position, layer);
+                        position
+                    })
+                    .collect();
+                positions.sort_unstable();
+                positions.dedup();
+
+                AreaWrapper::Chaos(ChaosArea::new(positions))
+            }
+        }
+    }
+}
+
+/// Returns the shorter of going `delta` or the other way around a loop of `size`.
+fn shortest_wrapped(delta: i32, size: i32) -> i32 {
+    let delta = delta.rem_euclid(size);
+    if delta > size / 2 {
+        delta - size
+    } else {
+        delta
+    }
+}
+
+/// Represents the four cardinal directions and the four diagonals between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Direction {
     LEFT,
     RIGHT,
     UP,
     DOWN,
+    UPLEFT,
+    UPRIGHT,
+    DOWNLEFT,
+    DOWNRIGHT,
+}
+
+impl Direction {
+    /// Returns the direction pointing the other way.
+    #[must_use]
+    pub fn opposite(&self) -> Direction {
+        let (x, y) = self.get_offset();
+        Self::from_offset(-x, -y).unwrap_or(Direction::UP)
+    }
+
+    /// Returns how far a single step in this direction moves along each axis.
+    #[must_use]
+    pub fn get_offset(&self) -> (i32, i32) {
+        match self {
+            Direction::LEFT => (-1, 0),
+            Direction::RIGHT => (1, 0),
+            Direction::UP => (0, -1),
+            Direction::DOWN => (0, 1),
+            Direction::UPLEFT => (-1, -1),
+            Direction::UPRIGHT => (1, -1),
+            Direction::DOWNLEFT => (-1, 1),
+            Direction::DOWNRIGHT => (1, 1),
+        }
+    }
+
+    /// Returns the direction heading the way of `x` and `y`, by sign only, or `None` if both are
+    /// zero.
+    #[must_use]
+    pub fn from_offset(x: i32, y: i32) -> Option<Direction> {
+        match (x.signum(), y.signum()) {
+            (-1, 0) => Some(Direction::LEFT),
+            (1, 0) => Some(Direction::RIGHT),
+            (0, -1) => Some(Direction::UP),
+            (0, 1) => Some(Direction::DOWN),
+            (-1, -1) => Some(Direction::UPLEFT),
+            (1, -1) => Some(Direction::UPRIGHT),
+            (-1, 1) => Some(Direction::DOWNLEFT),
+            (1, 1) => Some(Direction::DOWNRIGHT),
+            _ => None,
+        }
+    }
+
+    /// Returns the diagonal between this direction and `other`, if they're cardinal directions at
+    /// right angles.
+    #[must_use]
+    pub fn combine(&self, other: &Direction) -> Option<Direction> {
+        let (x, y) = self.get_offset();
+        let (other_x, other_y) = other.get_offset();
+
+        if self.is_diagonal() || other.is_diagonal() {
+            return None;
+        }
+        Self::from_offset(x + other_x, y + other_y).filter(Direction::is_diagonal)
+    }
+
+    #[must_use]
+    pub fn is_diagonal(&self) -> bool {
+        let (x, y) = self.get_offset();
+        x != 0 && y != 0
+    }
+
+    /// Returns the position a step in this direction away from `position`, which may be off the
+    /// map.
+    #[must_use]
+    pub fn step_from(&self, position: &Position) -> Position {
+        let (x, y) = position.get();
+        let (step_x, step_y) = self.get_offset();
+        Position(x + step_x, y + step_y)
+    }
 }
 
 /// Represents a rectangular area defined by two corner positions.
@@ -124,6 +274,15 @@ impl SquareArea {
         SquareArea { corner1, corner2 }
     }
 
+    /// Constructs the area covering the whole of `layer`.
+    #[must_use]
+    pub fn covering(layer: &Layer) -> SquareArea {
+        SquareArea {
+            corner1: Position(0, 0),
+            corner2: Position(layer[0].len() as i32 - 1, layer.len() as i32 - 1),
+        }
+    }
+
     /// Constructs an Area with both corners at the world origin (0, 0).\n
     #[must_use]
     pub fn origin() -> SquareArea {
@@ -146,6 +305,53 @@ impl SquareArea {
             ),
         }
     }
+
+    /// Returns how many cells the position is outside the area, counting diagonal steps as one.
+    /// Positions inside the area are 0 away.
+    #[must_use]
+    pub fn get_distance_outside(&self, position: &Position) -> i32 {
+        let (x1, y1, x2, y2) = self.get_bounds();
+        let (x, y) = position.get();
+
+        let dx = (x1 - x).max(x - x2).max(0);
+        let dy = (y1 - y).max(y - y2).max(0);
+
+        dx.max(dy)
+    }
+
+    /// Returns the part of the area that's also in `other`, or `None` if they don't overlap.
+    #[must_use]
+    pub fn intersect(&self, other: &SquareArea) -> Option<SquareArea> {
+        let (left, top, right, bottom) = self.get_bounds();
+        let (x1, y1, x2, y2) = other.get_bounds();
+
+        let corner1 = Position(left.max(x1), top.max(y1));
+        let corner2 = Position(right.min(x2), bottom.min(y2));
+
+        (corner1.0 <= corner2.0 && corner1.1 <= corner2.1)
+            .then(|| SquareArea::new(corner1, corner2))
+    }
+
+    /// Returns a copy of the area grown by `margin` cells on every side.
+    #[must_use]
+    pub fn grow(&self, margin: i32) -> SquareArea {
+        let (x1, y1, x2, y2) = self.get_bounds();
+
+        SquareArea {
+            corner1: Position(x1 - margin, y1 - margin),
+            corner2: Position(x2 + margin, y2 + margin),
+        }
+    }
+
+    /// Returns the positions along the edge of the area.
+    #[must_use]
+    pub fn get_border(&self) -> Vec<Position> {
+        let (x1, y1, x2, y2) = self.get_bounds();
+
+        self.pos_iter()
+            .filter(|Position(x, y)| *x == x1 || *x == x2 || *y == y1 || *y == y2)
+            .collect()
+    }
 }
 
 impl Area for SquareArea {
@@ -185,6 +391,7 @@ impl ChaosArea {
         ChaosArea { position_list }
     }
 
+    #[must_use]
     pub fn empty() -> Self {
         Self::new(Vec::new())
     }
@@ -259,4 +466,130 @@ mod tests {
         assert_eq!(area.clone().pos_iter().fold(0, |acc, _| acc + 1), 16);
         assert_eq!(area.clone().pos_iter().max(), Some(Position(6, 5)));
     }
+
+    #[test]
+    fn distance_outside_square_area() {
+        let area = SquareArea::new(Position(10, 10), Position(20, 20));
+
+        assert_eq!(area.get_distance_outside(&Position(15, 15)), 0);
+        assert_eq!(area.get_distance_outside(&Position(20, 10)), 0);
+        assert_eq!(area.get_distance_outside(&Position(25, 15)), 5);
+        assert_eq!(area.get_distance_outside(&Position(2, 30)), 10);
+    }
+
+    #[test]
+    fn square_areas_intersect() {
+        let area = SquareArea::new(Position(10, 0), Position(20, 200));
+        let view = SquareArea::new(Position(0, 50), Position(15, 60));
+
+        let overlap = area.intersect(&view).unwrap();
+        assert_eq!(overlap.get_bounds(), (10, 50, 15, 60));
+        assert_eq!(overlap.pos_iter().count(), 66);
+
+        assert!(area.intersect(&SquareArea::from(Position(21, 5))).is_none());
+    }
+}
+
+#[cfg(all(test, not(target_family = "wasm")))]
+mod proptests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::common::entities::EntityCharacters;
+    use crate::common::map::Layer;
+    use ratatui::style::Style;
+
+    fn layer(width: usize, height: usize) -> Layer {
+        vec![vec![EntityCharacters::Empty(Style::new()); width]; height]
+    }
+
+    fn is_inside(position: &Position, width: usize, height: usize) -> bool {
+        (0..width as i32).contains(&position.0) && (0..height as i32).contains(&position.1)
+    }
+
+    proptest! {
+        #[test]
+        fn square_bounds_are_ordered_and_hold_both_corners(
+            x1 in -1000i32..1000, y1 in -1000i32..1000, x2 in -1000i32..1000, y2 in -1000i32..1000,
+        ) {
+            let area = SquareArea::new(Position(x1, y1), Position(x2, y2));
+            let (min_x, min_y, max_x, max_y) = area.get_bounds();
+
+            prop_assert!(min_x <= max_x && min_y <= max_y);
+            for (x, y) in [(x1, y1), (x2, y2)] {
+                prop_assert!((min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y));
+            }
+
+            let swapped = SquareArea::new(Position(x2, y2), Position(x1, y1));
+            prop_assert_eq!(swapped.get_bounds(), area.get_bounds());
+        }
+
+        #[test]
+        fn position_constrain_stays_in_layer(
+            x in -100i32..200, y in -100i32..200, width in 1usize..80, height in 1usize..80,
+        ) {
+            let layer = layer(width, height);
+            let mut position = Position(x, y);
+            position.constrain(&layer);
+
+            prop_assert!(is_inside(&position, width, height));
+
+            // positions already inside are left alone
+            if is_inside(&Position(x, y), width, height) {
+                prop_assert_eq!(position.clone(), Position(x, y));
+            }
+
+            let mut again = position.clone();
+            again.constrain(&layer);
+            prop_assert_eq!(again, position);
+        }
+
+        #[test]
+        fn chaos_constrain_keeps_every_position_in_layer(
+            points in prop::collection::vec((-100i32..200, -100i32..200), 0..20),
+            width in 1usize..80,
+            height in 1usize..80,
+        ) {
+            let layer = layer(width, height);
+            let mut area = ChaosArea::new(points.iter().map(|&(x, y)| Position(x, y)).collect());
+            area.constrain(&layer);
+
+            prop_assert_eq!(area.position_list.len(), points.len());
+            for position in &area.position_list {
+                prop_assert!(is_inside(position, width, height));
+            }
+        }
+
+        #[test]
+        fn distance_outside_is_zero_only_inside(
+            x1 in -50i32..50, y1 in -50i32..50, x2 in -50i32..50, y2 in -50i32..50,
+            x in -100i32..100, y in -100i32..100,
+        ) {
+            let area = SquareArea::new(Position(x1, y1), Position(x2, y2));
+            let (min_x, min_y, max_x, max_y) = area.get_bounds();
+            let inside = (min_x..=max_x).contains(&x) && (min_y..=max_y).contains(&y);
+
+            prop_assert_eq!(area.get_distance_outside(&Position(x, y)) == 0, inside);
+        }
+
+        #[test]
+        fn wrapped_distance_is_the_short_way_round(
+            x in 0i32..80, y in 0i32..80, to_x in 0i32..80, to_y in 0i32..80,
+            width in 1usize..80, height in 1usize..80,
+        ) {
+            let layer = layer(width, height);
+            let mut from = Position(x, y);
+            let mut to = Position(to_x, to_y);
+            Topology::Wrapping.constrain(&mut from, &layer);
+            Topology::Wrapping.constrain(&mut to, &layer);
+            prop_assert!(is_inside(&from, width, height) && is_inside(&to, width, height));
+
+            let (dx, dy) = Topology::Wrapping.get_distance(&from, &to, &layer);
+            prop_assert!(dx.abs() <= width as i32 / 2 && dy.abs() <= height as i32 / 2);
+
+            let mut arrived = Position(from.0 + dx, from.1 + dy);
+            Topology::Wrapping.constrain(&mut arrived, &layer);
+            prop_assert_eq!(arrived, to);
+        }
+    }
 }
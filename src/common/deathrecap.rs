@@ -0,0 +1,187 @@
+//! This module explains how a run ended in death: what landed the killing blow, the hits taken in
+//! the last few seconds, and the build the character died with.
+
+use std::collections::VecDeque;
+
+use ratatui::{style::Stylize, text::Line};
+
+use crate::common::enemies::enemy::EnemyKind;
+use crate::common::hazards::HazardKind;
+use crate::prelude::{Duration, Instant};
+
+/// What hurt the character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DamageSource {
+    Enemy {
+        kind: EnemyKind,
+        elite: bool,
+    },
+    Hazard(HazardKind),
+    /// A CHARM that costs health, by name.
+    Charm(&'static str),
+}
+
+impl DamageSource {
+    #[must_use]
+    pub fn get_name(&self) -> String {
+        match self {
+            DamageSource::Enemy { kind, elite: true } => format!("elite {}", kind.get_name()),
+            DamageSource::Enemy { kind, elite: false } => kind.get_name().to_string(),
+            DamageSource::Hazard(kind) => kind.get_name().to_string(),
+            DamageSource::Charm(name) => name.to_lowercase(),
+        }
+    }
+}
+
+/// A single hit the character took.
+#[derive(Debug, Clone)]
+pub struct DamageTaken {
+    pub source: DamageSource,
+    /// How much health and shield the hit took away.
+    pub damage: i32,
+    pub taken_at: Instant,
+}
+
+/// The hits taken over the last `RECAP_WINDOW`, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct DamageLog {
+    hits: VecDeque<DamageTaken>,
+}
+
+impl DamageLog {
+    /// How far back hits are kept for the recap.
+    pub const RECAP_WINDOW: Duration = Duration::from_secs(5);
+
+    /// Records a hit, forgetting any that are too old to show in the recap.
+    pub fn record(&mut self, source: DamageSource, damage: i32) {
+        let now = Instant::now();
+
+        self.hits.push_back(DamageTaken {
+            source,
+            damage,
+            taken_at: now,
+        });
+
+        while self
+            .hits
+            .front()
+            .is_some_and(|hit| now.duration_since(hit.taken_at) > Self::RECAP_WINDOW)
+        {
+            self.hits.pop_front();
+        }
+    }
+
+    #[must_use]
+    pub fn get_hits(&self) -> &VecDeque<DamageTaken> {
+        &self.hits
+    }
+}
+
+/// A hit shown in the recap, timed from the moment of death.
+#[derive(Debug, Clone)]
+pub struct RecapHit {
+    pub source: DamageSource,
+    pub damage: i32,
+    pub secs_before_death: f64,
+}
+
+/// A breakdown of how the character died, for the carnage report.
+#[derive(Debug, Clone)]
+pub struct DeathRecap {
+    /// What dealt the final hit, if anything was recorded.
+    pub killing_blow: Option<DamageSource>,
+    /// The hits leading up to death, oldest first.
+    pub hits: Vec<RecapHit>,
+    /// Each METHOD and CHARM held at death, with its level.
+    pub build: Vec<String>,
+}
+
+impl DeathRecap {
+    /// Builds a recap from the character's damage log at the moment of death.
+    #[must_use]
+    pub fn new(damage_log: &DamageLog, build: Vec<String>) -> Self {
+        let died_at = damage_log
+            .get_hits()
+            .back()
+            .map_or_else(Instant::now, |hit| hit.taken_at);
+
+        let hits = damage_log
+            .get_hits()
+            .iter()
+            .map(|hit| RecapHit {
+                source: hit.source,
+                damage: hit.damage,
+                secs_before_death: died_at
+                    .saturating_duration_since(hit.taken_at)
+                    .as_secs_f64(),
+            })
+            .collect();
+
+        Self {
+            killing_blow: damage_log.get_hits().back().map(|hit| hit.source),
+            hits,
+            build,
+        }
+    }
+
+    /// Builds the recap's lines for the carnage report.
+    #[must_use]
+    pub fn get_lines(&self) -> Vec<Line<'static>> {
+        let killed_by = self.killing_blow.map_or_else(
+            || "something unseen".to_string(),
+            |source| source.get_name(),
+        );
+
+        let mut lines = vec![
+            Line::from(vec!["Killed by: ".into(), killed_by.light_red().bold()]),
+            Line::from(""),
+            Line::from(format!("last {}s", DamageLog::RECAP_WINDOW.as_secs()).dark_gray()),
+        ];
+
+        lines.extend(self.hits.iter().map(|hit| {
+            Line::from(format!(
+                "{:>5.1}s {:<20} -{}",
+                -hit.secs_before_death,
+                hit.source.get_name(),
+                hit.damage
+            ))
+        }));
+
+        if !self.build.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::from("build".dark_gray()));
+            lines.push(Line::from(self.build.join(", ")));
+        }
+
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::deathrecap::{DamageLog, DamageSource, DeathRecap};
+    use crate::common::enemies::enemy::EnemyKind;
+    use crate::common::hazards::HazardKind;
+
+    #[test]
+    fn last_hit_is_the_killing_blow() {
+        let mut damage_log = DamageLog::default();
+        damage_log.record(DamageSource::Hazard(HazardKind::Spikes), 1);
+        damage_log.record(
+            DamageSource::Enemy {
+                kind: EnemyKind::Armored,
+                elite: true,
+            },
+            4,
+        );
+
+        let recap = DeathRecap::new(&damage_log, vec!["FLASH 2".to_string()]);
+
+        assert_eq!(
+            recap.killing_blow.map(|source| source.get_name()),
+            Some("elite armored enemy".to_string())
+        );
+        assert_eq!(recap.hits.len(), 2);
+        assert!(recap.hits.iter().all(|hit| hit.secs_before_death >= 0.));
+    }
+}
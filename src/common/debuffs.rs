@@ -1,12 +1,14 @@
 use crate::{
-    common::{TICK_RATE, character::Damageable, coords::ChaosArea, stats::WeaponStats},
+    common::{
+        TICK_RATE,
+        character::Damageable,
+        coords::ChaosArea,
+        stats::{ProcId, WeaponStats},
+    },
     prelude::Duration,
 };
-use std::cell::RefCell;
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::rc::Rc;
 
 use ratatui::style::Style;
 
@@ -16,7 +18,6 @@ use crate::common::enemies::enemy::{Debuffable, get_closest_enemies};
 use crate::common::enemies::enemy::{Enemy, move_to_point_granular};
 use crate::common::entities::EntityCharacters;
 use crate::common::map::Layer;
-use crate::common::sound::{SoundEffect, SoundWrangler};
 use crate::common::{
     coords::{Area, SquareArea},
     stats::{DebuffStats, Proc},
@@ -79,6 +80,31 @@ pub struct Debuff {
 
 impl Debuff {}
 
+/// The number of enemies currently affected by each kind of debuff.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DebuffCounts {
+    pub burning: usize,
+    pub shocked: usize,
+    pub marked: usize,
+}
+
+impl DebuffCounts {
+    /// Counts an enemy with the given debuffs once towards each kind it is affected by.
+    pub fn count(&mut self, debuffs: &Debuffs) {
+        let has = |types: &[DebuffTypes]| debuffs.iter().any(|d| types.contains(&d.debuff_type));
+
+        if has(&[DebuffTypes::FlameBurn, DebuffTypes::FlameIgnite]) {
+            self.burning += 1;
+        }
+        if has(&[DebuffTypes::ShockCharge, DebuffTypes::ShockElectrocute]) {
+            self.shocked += 1;
+        }
+        if has(&[DebuffTypes::MarkedForExplosion]) {
+            self.marked += 1;
+        }
+    }
+}
+
 /// A trait for effects that trigger when an enemy dies.
 pub trait OnDeathEffect {
     /// Called when an enemy dies, potentially creating a `DamageArea`.
@@ -101,7 +127,10 @@ impl OnDeathEffect for Debuff {
                         entity: EntityCharacters::AttackMist(Style::new().dark_gray()),
                         duration: Duration::from_secs_f64(0.05),
                         blink: false,
+                        destroys_walls: true,
+                        stages: vec![],
                         weapon_stats: None,
+                        source: None,
                     })
                 } else {
                     None
@@ -113,23 +142,13 @@ impl OnDeathEffect for Debuff {
 }
 
 pub trait OnTickEffect {
-    fn on_tick(
-        &mut self,
-        enemy: &mut Enemy,
-        layer: &Layer,
-        tickcount: u64,
-        sound_wrangler: Rc<RefCell<SoundWrangler>>,
-    ) -> Option<DamageArea>;
+    /// Runs the debuff for a tick. Only an ignition returns a damage area, and the caller plays
+    /// its sound, so debuffs can tick away from the main thread.
+    fn on_tick(&mut self, enemy: &mut Enemy, layer: &Layer, tickcount: u64) -> Option<DamageArea>;
 }
 
 impl OnTickEffect for Debuff {
-    fn on_tick(
-        &mut self,
-        enemy: &mut Enemy,
-        layer: &Layer,
-        tickcount: u64,
-        sound_wrangler: Rc<RefCell<SoundWrangler>>,
-    ) -> Option<DamageArea> {
+    fn on_tick(&mut self, enemy: &mut Enemy, layer: &Layer, tickcount: u64) -> Option<DamageArea> {
         if self.complete {
             return None;
         }
@@ -171,10 +190,8 @@ impl OnTickEffect for Debuff {
                     };
                     enemy.remove_debuff(DebuffTypes::FlameBurn);
 
-                    let mut procs = HashMap::new();
-                    procs.insert("burn".into(), proc);
-
-                    sound_wrangler.borrow().play(SoundEffect::Ignite);
+                    let mut weapon_stats = WeaponStats::default();
+                    weapon_stats.add_proc(ProcId::Burn, proc);
 
                     Some(DamageArea {
                         damage_amount: self.stats.damage.expect("No damage?") * 10,
@@ -182,10 +199,10 @@ impl OnTickEffect for Debuff {
                         entity: EntityCharacters::AttackMist(Style::new().red()),
                         duration: Duration::from_secs_f64(0.05),
                         blink: false,
-                        weapon_stats: Some(WeaponStats {
-                            procs,
-                            ..Default::default()
-                        }),
+                        destroys_walls: false,
+                        stages: vec![],
+                        source: None,
+                        weapon_stats: Some(weapon_stats),
                     })
                 } else {
                     None
@@ -231,7 +248,12 @@ impl OnDamageEffect for Debuff {
 
                 let mut positions = Vec::new();
 
-                let mut enemies = Vec::from(enemies);
+                // the charge arcs away from the enemy carrying it, never back into it
+                let mut enemies: Vec<Enemy> = enemies
+                    .iter()
+                    .filter(|other| other.get_id() != enemy.get_id())
+                    .cloned()
+                    .collect();
 
                 let size = self.stats.size.unwrap_or(1);
 
@@ -253,10 +275,8 @@ impl OnDamageEffect for Debuff {
                             move_to_point_granular(&current_pos, &desired_pos, false);
                         positions.push(current_pos.clone());
 
-                        enemies = enemies
-                            .iter()
-                            .filter_map(|e| if e != closest { Some(e.clone()) } else { None })
-                            .collect();
+                        let struck = closest.get_id();
+                        enemies.retain(|enemy| enemy.get_id() != struck);
                     }
                 }
 
@@ -280,9 +300,8 @@ impl OnDamageEffect for Debuff {
                     },
                 };
 
-                let mut procs = HashMap::new();
-
-                procs.insert("electrocute".into(), proc);
+                let mut weapon_stats = WeaponStats::default();
+                weapon_stats.add_proc(ProcId::Electrocute, proc);
 
                 area.constrain(layer);
 
@@ -292,10 +311,10 @@ impl OnDamageEffect for Debuff {
                     entity: EntityCharacters::AttackMist(Style::new().light_yellow()),
                     duration: Duration::from_secs_f64(0.01),
                     blink: false,
-                    weapon_stats: Some(WeaponStats {
-                        procs,
-                        ..Default::default()
-                    }),
+                    destroys_walls: false,
+                    stages: vec![],
+                    source: None,
+                    weapon_stats: Some(weapon_stats),
                 });
 
                 enemy.got_hit = (false, 0);
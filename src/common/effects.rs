@@ -1,25 +1,45 @@
 //! This module handles visual and gameplay effects, such as damage indicators.
-//! It defines the `DamageEffect` struct, which represents a temporary effect in a given area.
+//! It defines the `DamageEffect` struct, which represents a temporary effect in a given area,
+//! optionally animated through a sequence of `Keyframe`s.
 
 use crate::prelude::{Duration, Instant};
 
 use crate::common::character::Renderable;
 use crate::common::coords::AreaWrapper::Chaos;
-use crate::common::coords::{AreaWrapper, ChaosArea};
+use crate::common::coords::{Area, AreaWrapper, ChaosArea, SquareArea};
 use crate::common::entities::EntityCharacters;
-use crate::common::{
-    coords::{Area, Position},
-    weapons::DamageArea,
-};
+use crate::common::utils::ticks_to_duration;
+use crate::common::{coords::Position, weapons::DamageArea};
 use ratatui::prelude::Style;
 
+/// A single frame of an animated effect.
+#[derive(Clone)]
+pub struct Keyframe {
+    pub area: AreaWrapper,
+    pub entity: EntityCharacters,
+    pub duration: Duration,
+}
+
 /// Represents a visual effect that occurs over a specified area for a certain duration.
 #[derive(Clone)]
 pub struct DamageEffect {
     damage_area: DamageArea,
 
     start_time: Instant,
+    /// How long the effect was postponed for by `delay`.
+    delayed_by: Duration,
+    /// How many ticks the effect has been in play for. Stages deal their damage by this rather
+    /// than the clock, so they land on the same ticks on every machine in a networked run.
+    ticks_played: u64,
     pub complete: bool,
+    /// The last stage of a staged damage area that has dealt its damage.
+    stages_hit: Option<usize>,
+    /// Frames played in order instead of the damage area's own area and entity. Empty for
+    /// static effects.
+    keyframes: Vec<Keyframe>,
+    /// Scales how long a stageless effect stays on screen, to cut render cost when frames run
+    /// slow. Staged effects keep their full duration so their hits stay on time.
+    duration_mult: f64,
 
     pub active_area: AreaWrapper,
     pub active_entity: EntityCharacters,
@@ -30,7 +50,12 @@ impl From<DamageArea> for DamageEffect {
         Self {
             damage_area: damage_area.clone(),
             complete: false,
+            stages_hit: None,
+            duration_mult: 1.,
+            keyframes: vec![],
             start_time: Instant::now(),
+            delayed_by: Duration::ZERO,
+            ticks_played: 0,
 
             active_area: damage_area.area,
             active_entity: damage_area.entity,
@@ -39,6 +64,10 @@ impl From<DamageArea> for DamageEffect {
 }
 
 impl DamageEffect {
+    /// How long a blinking effect stays shown, then hidden, in turn.
+    pub const BLINK_INTERVAL: Duration = Duration::from_millis(50);
+
+    #[must_use]
     pub fn new(
         area: AreaWrapper,
         entity: EntityCharacters,
@@ -51,24 +80,90 @@ impl DamageEffect {
             entity: entity.clone(),
             duration,
             blink,
+            destroys_walls: false,
+            stages: vec![],
             weapon_stats: None,
+            source: None,
         };
 
         Self {
             damage_area,
             complete: false,
+            stages_hit: None,
+            duration_mult: 1.,
+            keyframes: vec![],
             start_time: Instant::now(),
+            delayed_by: Duration::ZERO,
+            ticks_played: 0,
 
             active_area: area,
             active_entity: entity,
         }
     }
 
+    /// Creates an effect that plays through `keyframes` in order, then completes.
+    #[must_use]
+    pub fn keyframed(keyframes: Vec<Keyframe>) -> Self {
+        let duration = keyframes.iter().map(|keyframe| keyframe.duration).sum();
+        let first = keyframes.first().cloned().unwrap_or(Keyframe {
+            area: Chaos(ChaosArea::empty()),
+            entity: EntityCharacters::Empty(Style::new()),
+            duration,
+        });
+
+        let mut effect = Self::new(first.area, first.entity, duration, false);
+        effect.keyframes = keyframes;
+        effect
+    }
+
+    /// Creates an effect of a ring spreading out from `center` across a `width` by `height` map
+    /// over `frames` frames.
+    #[must_use]
+    pub fn expanding_ring(
+        center: &Position,
+        width: i32,
+        height: i32,
+        frames: u32,
+        entity: &EntityCharacters,
+        duration: Duration,
+    ) -> Self {
+        let Position(center_x, center_y) = *center;
+        let max_radius = center_x
+            .max(width - center_x)
+            .max(center_y)
+            .max(height - center_y);
+        let frames = frames.max(1);
+        let band = (max_radius / frames as i32).max(1);
+
+        let keyframes = (0..frames as i32)
+            .map(|frame| {
+                let (inner, outer) = (frame * band, (frame + 1) * band);
+
+                let positions = SquareArea::get_square_around_position(center, outer)
+                    .pos_iter()
+                    .filter(|Position(x, y)| {
+                        let distance = (x - center_x).abs().max((y - center_y).abs());
+                        distance >= inner && (0..width).contains(x) && (0..height).contains(y)
+                    })
+                    .collect();
+
+                Keyframe {
+                    area: Chaos(ChaosArea::new(positions)),
+                    entity: entity.clone(),
+                    duration: duration / frames,
+                }
+            })
+            .collect();
+
+        Self::keyframed(keyframes)
+    }
+
     /// Postpone the effect's start time by a given duration.
     ///
     /// Advances the internal `start_time` forward by `delay`, causing the effect to begin later.
     pub fn delay(&mut self, delay: Duration) {
         self.start_time += delay;
+        self.delayed_by += delay;
     }
 
     /// Advance the effect's timing and update which area and entity should be rendered.
@@ -80,7 +175,7 @@ impl DamageEffect {
     /// is greater than or equal to the damage area's duration, the effect is marked `complete`.
     /// If the
     /// damage area is configured to blink, `active_entity` toggles between the damage entity and
-    /// `Empty` while the effect is active.
+    /// `Empty` every `BLINK_INTERVAL` while the effect is active, whatever the frame rate.
     pub fn update(&mut self) {
         let now = Instant::now();
 
@@ -88,35 +183,140 @@ impl DamageEffect {
             //hasn't started yet
             self.active_area = Chaos(ChaosArea::empty());
             self.active_entity = EntityCharacters::Empty(Style::new());
+        } else if let Some(keyframe) = self.get_current_keyframe().cloned() {
+            self.active_area = keyframe.area;
+            self.active_entity = keyframe.entity;
         } else {
-            self.active_area = self.damage_area.area.clone();
+            self.active_area = match self.get_current_stage() {
+                Some(stage) => self.damage_area.stages[stage].clone(),
+                None => self.damage_area.area.clone(),
+            };
             self.active_entity = self.damage_area.entity.clone();
         }
 
-        if now.duration_since(self.start_time) >= self.damage_area.duration {
+        let elapsed = now.saturating_duration_since(self.start_time);
+
+        if elapsed >= self.get_visible_duration() {
             self.complete = true;
-        } else if self.damage_area.blink {
-            if self.active_entity == self.damage_area.entity {
-                self.active_entity = EntityCharacters::Empty(Style::new());
-            } else {
-                self.active_entity = self.damage_area.entity.clone();
+        } else if self.damage_area.blink
+            && !(elapsed.as_millis() / Self::BLINK_INTERVAL.as_millis()).is_multiple_of(2)
+        {
+            self.active_entity = EntityCharacters::Empty(Style::new());
+        }
+    }
+
+    /// Sets how much of its duration this effect is shown for.
+    pub fn set_duration_mult(&mut self, duration_mult: f64) {
+        self.duration_mult = duration_mult;
+    }
+
+    /// Returns how long this effect stays on screen.
+    fn get_visible_duration(&self) -> Duration {
+        if self.damage_area.stages.is_empty() {
+            self.damage_area.duration.mul_f64(self.duration_mult)
+        } else {
+            self.damage_area.duration
+        }
+    }
+
+    /// Stops this effect from blinking, so it shows its entity for its whole duration.
+    pub fn hold_steady(&mut self) {
+        self.damage_area.blink = false;
+    }
+
+    /// Returns the keyframe that should be showing now, if the effect is keyframed and started.
+    fn get_current_keyframe(&self) -> Option<&Keyframe> {
+        let mut elapsed = Instant::now().checked_duration_since(self.start_time)?;
+
+        for keyframe in &self.keyframes {
+            if elapsed < keyframe.duration {
+                return Some(keyframe);
             }
+            elapsed = elapsed.saturating_sub(keyframe.duration);
+        }
+
+        self.keyframes.last()
+    }
+
+    /// Returns the index of the stage a staged damage area is showing, or `None` if the area has
+    /// no stages or hasn't started yet.
+    fn get_current_stage(&self) -> Option<usize> {
+        self.get_stage_at(Instant::now().checked_duration_since(self.start_time)?)
+    }
+
+    /// Returns the index of the stage a staged damage area reaches `elapsed` after it starts, or
+    /// `None` if the area has no stages.
+    fn get_stage_at(&self, elapsed: Duration) -> Option<usize> {
+        let stage_count = self.damage_area.stages.len();
+        if stage_count == 0 {
+            return None;
         }
+
+        let progress =
+            elapsed.as_secs_f64() / self.damage_area.duration.as_secs_f64().max(f64::EPSILON);
+
+        Some(((progress * stage_count as f64) as usize).min(stage_count - 1))
+    }
+
+    /// Returns a damage area for every stage reached since the last call, so that a staged area
+    /// deals its damage as it sweeps rather than all at once. Called once a tick.
+    pub fn take_stage_hits(&mut self) -> Vec<DamageArea> {
+        let played = ticks_to_duration(self.ticks_played);
+        self.ticks_played += 1;
+
+        let Some(current) = played
+            .checked_sub(self.delayed_by)
+            .and_then(|elapsed| self.get_stage_at(elapsed))
+        else {
+            return vec![];
+        };
+
+        let first = self.stages_hit.map_or(0, |stage| stage + 1);
+        self.stages_hit = Some(current);
+
+        (first..=current)
+            .map(|stage| DamageArea {
+                area: self.damage_area.stages[stage].clone(),
+                stages: vec![],
+                ..self.damage_area.clone()
+            })
+            .collect()
+    }
+
+    /// Checks if a staged damage area still has stages that haven't dealt their damage.
+    #[must_use]
+    pub fn has_pending_stages(&self) -> bool {
+        let stage_count = self.damage_area.stages.len();
+        stage_count > 0 && self.stages_hit != Some(stage_count - 1)
     }
 
     /// Produce an iterator over the currently active area that pairs each position with the active entity.
     ///
-    /// The returned iterator yields `(Position, EntityCharacters)` for every position in `self.active_area`.
+    /// The returned iterator yields `(Position, EntityCharacters)` for every position in `self.active_area`
+    /// that's inside `view`. Square areas are cut down to `view` before any positions are made, so
+    /// an area spanning the whole map only costs what's on screen.
     /// Value captures the positions and the active entity at the time of the call, so the
     /// iterator
     /// can be used independently of later mutations to the `DamageEffect`.
-    pub fn get_instructions(&self) -> impl Iterator<Item = RenderPosition> {
+    pub fn get_instructions(&self, view: &SquareArea) -> impl Iterator<Item = RenderPosition> {
         let active_entity = self.active_entity.clone();
 
-        self.active_area
-            .get_inner()
-            .pos_iter()
-            .map(move |pos| RenderPosition(pos, active_entity.clone()))
+        let positions: Box<dyn Iterator<Item = Position>> = match &self.active_area {
+            AreaWrapper::Square(square) => match square.intersect(view) {
+                Some(visible) => visible.pos_iter(),
+                None => Box::new(std::iter::empty()),
+            },
+            AreaWrapper::Chaos(chaos) => {
+                let view = view.clone();
+                Box::new(
+                    chaos
+                        .pos_iter()
+                        .filter(move |pos| view.get_distance_outside(pos) == 0),
+                )
+            }
+        };
+
+        positions.map(move |pos| RenderPosition(pos, active_entity.clone()))
     }
 }
 
@@ -0,0 +1,230 @@
+//! This module provides the spawn director, which paces natural spawns. Rather than spawning on a
+//! fixed beat, it builds up a threat budget over time and spends it in waves of enemies, elites
+//! and packs, so runs ebb and flow between quiet stretches and rushes.
+
+use rand::Rng;
+
+use crate::common::TICK_RATE;
+use crate::common::stats::GameStats;
+
+/// Something the director can spend its budget on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnPurchase {
+    Normal,
+    Elite,
+    /// An enemy that splits into smaller ones when killed.
+    Splitter,
+    /// A slow enemy with armor against every hit.
+    Armored,
+    /// An enemy that hangs back and calls in more enemies.
+    Summoner,
+    /// An enemy that can't be burned.
+    Blazing,
+    /// This many enemies, spawned together in one spot.
+    Pack(usize),
+}
+
+/// Decides when natural spawns happen and what they are.
+pub struct SpawnDirector {
+    /// Threat saved up to spend on spawns, in normal enemies' worth.
+    budget: f64,
+    /// How much budget is gained each second.
+    income: f64,
+    /// Whether a wave is being released, spending the budget down.
+    releasing: bool,
+    budget_mult: f64,
+    wave_secs: f64,
+    elite_cost: f64,
+    pack_size: usize,
+    /// Scales the chance of buying elites and other special enemies.
+    special_mult: u32,
+}
+
+impl SpawnDirector {
+    const NORMAL_COST: f64 = 1.;
+    /// About what a splitter and the splitlings it leaves behind are worth.
+    const SPLITTER_COST: f64 = 3.;
+    const ARMORED_COST: f64 = 4.;
+    const SUMMONER_COST: f64 = 5.;
+    const BLAZING_COST: f64 = 2.;
+    /// How much of the normal price each enemy in a pack costs, so bigger waves lean on packs.
+    const PACK_DISCOUNT: f64 = 0.75;
+    /// Chance out of 100 to buy a pack, when there's enough budget for one.
+    const PACK_CHANCE: u32 = 30;
+    /// Chance out of 100 to buy an elite, when there's enough budget for one.
+    const ELITE_CHANCE: u32 = 5;
+    /// Chance out of 100 to buy a splitter, when there's enough budget for one.
+    const SPLITTER_CHANCE: u32 = 10;
+    /// Chance out of 100 to buy an armored enemy, when there's enough budget for one.
+    const ARMORED_CHANCE: u32 = 8;
+    /// Chance out of 100 to buy a summoner, when there's enough budget for one.
+    const SUMMONER_CHANCE: u32 = 5;
+    /// Chance out of 100 to buy a blazing enemy, when there's enough budget for one.
+    const BLAZING_CHANCE: u32 = 8;
+    /// About how many seconds a wave takes to be released.
+    const RELEASE_SECS: f64 = 1.;
+
+    /// Creates a director with no budget, tuned by the run's `GameStats`.
+    #[must_use]
+    pub fn new(game_stats: &GameStats) -> Self {
+        Self {
+            budget: 0.,
+            income: 0.,
+            releasing: false,
+            budget_mult: game_stats.spawn_budget_mult,
+            wave_secs: game_stats.spawn_wave_secs,
+            elite_cost: game_stats.elite_spawn_cost,
+            pack_size: game_stats.spawn_pack_size,
+            special_mult: 1,
+        }
+    }
+
+    /// Sets how many normal enemies' worth of threat is gained each second, before the budget
+    /// multiplier.
+    pub fn set_spawn_rate(&mut self, per_sec: f64) {
+        self.income = per_sec * self.budget_mult;
+    }
+
+    /// Makes elites and other special enemies `mult` times as likely to be bought.
+    pub fn set_special_mult(&mut self, mult: u32) {
+        self.special_mult = mult.max(1);
+    }
+
+    /// Returns how much budget is gained each second.
+    #[must_use]
+    pub fn get_income(&self) -> f64 {
+        self.income
+    }
+
+    /// The budget saved up before a wave is released.
+    fn get_wave_budget(&self) -> f64 {
+        (self.income * self.wave_secs).max(Self::NORMAL_COST)
+    }
+
+    fn get_cost(&self, purchase: SpawnPurchase) -> f64 {
+        match purchase {
+            SpawnPurchase::Normal => Self::NORMAL_COST,
+            SpawnPurchase::Elite => self.elite_cost,
+            SpawnPurchase::Splitter => Self::SPLITTER_COST,
+            SpawnPurchase::Armored => Self::ARMORED_COST,
+            SpawnPurchase::Summoner => Self::SUMMONER_COST,
+            SpawnPurchase::Blazing => Self::BLAZING_COST,
+            SpawnPurchase::Pack(size) => size as f64 * Self::NORMAL_COST * Self::PACK_DISCOUNT,
+        }
+    }
+
+    /// Returns a special enemy's chance out of 100 to be bought, raised by the special multiplier.
+    fn get_special_chance(&self, chance: u32) -> u32 {
+        chance.saturating_mul(self.special_mult).min(100)
+    }
+
+    /// Picks something to spend the budget on, if anything is affordable.
+    fn choose(&self, rng: &mut impl Rng) -> Option<SpawnPurchase> {
+        let pack = SpawnPurchase::Pack(self.pack_size);
+
+        if self.pack_size > 1
+            && self.budget >= self.get_cost(pack)
+            && rng.random_ratio(Self::PACK_CHANCE, 100)
+        {
+            Some(pack)
+        } else if self.budget >= self.elite_cost
+            && rng.random_ratio(self.get_special_chance(Self::ELITE_CHANCE), 100)
+        {
+            Some(SpawnPurchase::Elite)
+        } else if self.budget >= Self::SPLITTER_COST
+            && rng.random_ratio(self.get_special_chance(Self::SPLITTER_CHANCE), 100)
+        {
+            Some(SpawnPurchase::Splitter)
+        } else if self.budget >= Self::ARMORED_COST
+            && rng.random_ratio(self.get_special_chance(Self::ARMORED_CHANCE), 100)
+        {
+            Some(SpawnPurchase::Armored)
+        } else if self.budget >= Self::SUMMONER_COST
+            && rng.random_ratio(self.get_special_chance(Self::SUMMONER_CHANCE), 100)
+        {
+            Some(SpawnPurchase::Summoner)
+        } else if self.budget >= Self::BLAZING_COST
+            && rng.random_ratio(self.get_special_chance(Self::BLAZING_CHANCE), 100)
+        {
+            Some(SpawnPurchase::Blazing)
+        } else if self.budget >= Self::NORMAL_COST {
+            Some(SpawnPurchase::Normal)
+        } else {
+            None
+        }
+    }
+
+    /// Gains a tick's worth of budget and returns what to spawn this tick. Nothing spawns while
+    /// the budget builds up to a wave, then the wave is spent over about a second.
+    pub fn tick(&mut self, rng: &mut impl Rng) -> Vec<SpawnPurchase> {
+        self.budget += self.income / TICK_RATE;
+
+        if !self.releasing {
+            if self.budget < self.get_wave_budget() {
+                return vec![];
+            }
+            self.releasing = true;
+        }
+
+        let per_tick = (self.get_wave_budget() / (Self::RELEASE_SECS * TICK_RATE))
+            .ceil()
+            .max(1.) as usize;
+
+        let mut purchases = Vec::new();
+        while purchases.len() < per_tick {
+            let Some(purchase) = self.choose(rng) else {
+                self.releasing = false;
+                break;
+            };
+
+            self.budget -= self.get_cost(purchase);
+            purchases.push(purchase);
+        }
+
+        purchases
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    use crate::common::TICK_RATE;
+    use crate::common::enemies::director::{SpawnDirector, SpawnPurchase};
+    use crate::common::stats::GameStats;
+
+    #[test]
+    fn spawns_come_in_waves() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut director = SpawnDirector::new(&GameStats::default());
+        director.set_spawn_rate(2.);
+
+        let mut spawned = 0.;
+        let mut quiet_ticks = 0;
+
+        for _ in 0..(60. * TICK_RATE) as usize {
+            let purchases = director.tick(&mut rng);
+            if purchases.is_empty() {
+                quiet_ticks += 1;
+            }
+
+            spawned += purchases
+                .iter()
+                .map(|purchase| match purchase {
+                    SpawnPurchase::Pack(size) => *size as f64 * SpawnDirector::PACK_DISCOUNT,
+                    SpawnPurchase::Elite => GameStats::default().elite_spawn_cost,
+                    SpawnPurchase::Splitter => SpawnDirector::SPLITTER_COST,
+                    SpawnPurchase::Armored => SpawnDirector::ARMORED_COST,
+                    SpawnPurchase::Summoner => SpawnDirector::SUMMONER_COST,
+                    SpawnPurchase::Blazing => SpawnDirector::BLAZING_COST,
+                    SpawnPurchase::Normal => 1.,
+                })
+                .sum::<f64>();
+        }
+
+        // the budget is spent at about the spawn rate, but most ticks stay quiet between waves
+        assert!((100. ..=120.).contains(&spawned), "spent {spawned}");
+        assert!(quiet_ticks > (45. * TICK_RATE) as usize);
+    }
+}
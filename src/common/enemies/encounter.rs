@@ -0,0 +1,112 @@
+//! This module provides the per-run `Encounter` plan, a schedule of scripted horde events.
+//! Each horde is announced on screen shortly before a ring of enemies spawns around the map edge.
+
+use crate::common::stats::GameStats;
+use crate::prelude::Duration;
+
+/// A single scheduled horde.
+pub struct HordeEvent {
+    /// Time into the run at which the horde spawns.
+    pub at: Duration,
+    /// The number of enemies in the ring.
+    pub size: usize,
+    warned: bool,
+    triggered: bool,
+}
+
+/// The plan of scripted events for a single run.
+pub struct Encounter {
+    pub hordes: Vec<HordeEvent>,
+}
+
+impl Encounter {
+    const HORDE_SCHEDULE_SECS: [u64; 5] = [30, 60, 150, 240, 330];
+    const WARNING_SECS: u64 = 3;
+    const BASE_HORDE_SIZE: f64 = 12.;
+
+    /// Creates the horde schedule for a run, skipping hordes that would land after the timer ends.
+    /// Horde sizes grow with each horde and with the run's spawn rate and doom offset.
+    #[must_use]
+    pub fn new(game_stats: &GameStats) -> Self {
+        let difficulty = game_stats.enemy_spawn_mult * (1. + game_stats.doom_offset / 10.);
+
+        let hordes = Self::HORDE_SCHEDULE_SECS
+            .iter()
+            .filter(|secs| **secs < game_stats.timer)
+            .enumerate()
+            .map(|(i, secs)| HordeEvent {
+                at: Duration::from_secs(*secs),
+                size: (Self::BASE_HORDE_SIZE * difficulty * (1. + 0.5 * i as f64)).ceil() as usize,
+                warned: false,
+                triggered: false,
+            })
+            .collect();
+
+        Self { hordes }
+    }
+
+    /// Checks if a horde is about to spawn and the warning banner should be shown.
+    #[must_use]
+    pub fn is_warning(&self, elapsed: Duration) -> bool {
+        let warning = Duration::from_secs(Self::WARNING_SECS);
+
+        self.hordes
+            .iter()
+            .any(|horde| !horde.triggered && elapsed + warning >= horde.at && elapsed < horde.at)
+    }
+
+    /// Marks the next horde whose warning has started as warned and returns its size, so the
+    /// warning is only announced once.
+    pub fn take_warning(&mut self, elapsed: Duration) -> Option<usize> {
+        let warning = Duration::from_secs(Self::WARNING_SECS);
+
+        let horde = self
+            .hordes
+            .iter_mut()
+            .find(|horde| !horde.warned && elapsed + warning >= horde.at)?;
+
+        horde.warned = true;
+        Some(horde.size)
+    }
+
+    /// Marks the next due horde as triggered and returns its size, if one is due.
+    pub fn take_due_horde(&mut self, elapsed: Duration) -> Option<usize> {
+        let horde = self
+            .hordes
+            .iter_mut()
+            .find(|horde| !horde.triggered && elapsed >= horde.at)?;
+
+        horde.triggered = true;
+        Some(horde.size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::enemies::encounter::Encounter;
+    use crate::common::stats::GameStats;
+    use crate::prelude::Duration;
+
+    #[test]
+    fn hordes_warn_then_trigger_once() {
+        let game_stats = GameStats::default();
+        let mut encounter = Encounter::new(&game_stats);
+
+        assert!(
+            encounter
+                .hordes
+                .iter()
+                .all(|horde| horde.at.as_secs() < game_stats.timer)
+        );
+
+        let first = encounter.hordes[0].at;
+        let just_before = first.saturating_sub(Duration::from_secs(1));
+
+        assert!(!encounter.is_warning(Duration::ZERO));
+        assert!(encounter.is_warning(just_before));
+        assert!(encounter.take_due_horde(just_before).is_none());
+        assert!(encounter.take_due_horde(first).is_some());
+        assert!(encounter.take_due_horde(first).is_none());
+        assert!(!encounter.is_warning(just_before));
+    }
+}
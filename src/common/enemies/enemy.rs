@@ -1,6 +1,7 @@
 //! This module defines the `Enemy` struct and its related traits and behaviors.
 //! It includes logic for enemy movement, health, attacks, and debuffs.
 
+use crate::common::runrng;
 use crate::{
     common::{
         character::{Character, Damageable, Movable},
@@ -10,15 +11,19 @@ use crate::{
 };
 
 use rand::Rng;
+use rand::seq::SliceRandom;
 use ratatui::style::Style;
+use ratatui::text::Span;
 
 use crate::common::character::Renderable;
 use crate::common::coords::AreaWrapper::Square;
-use crate::common::entities::EntityCharacters;
+use crate::common::deathrecap::DamageSource;
+use crate::common::entities::{EntityCharacters, EntityId};
 use crate::common::map::Layer;
-use crate::common::utils::{can_stand, is_next_to_character};
+use crate::common::settings::setting::{EnemyHealthDisplay, TargetPriority};
+use crate::common::utils::can_stand;
 use crate::common::{
-    coords::{Direction, Position, SquareArea},
+    coords::{Direction, Position, SquareArea, Topology},
     effects::DamageEffect,
     stats::Proc,
 };
@@ -44,11 +49,97 @@ pub trait EnemyBehaviour {
 pub struct EnemyDrops {
     pub gold: u128,
     pub xp: u128,
+    /// Where the enemy was when it dropped these.
+    pub position: Position,
+    /// Whether a chest should be dropped.
+    pub chest: bool,
 }
 
-/// Represents an enemy in the game.
-#[derive(Clone, PartialEq, Eq)]
+/// What sort of enemy an enemy is, on top of whether it's an elite.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum EnemyKind {
+    #[default]
+    Normal,
+    /// Tougher, and splits into splitlings when killed.
+    Splitter,
+    /// Split off a splitter. Frail, but moves twice as often.
+    Splitling,
+    /// Tough and armored, but only moves every other time.
+    Armored,
+    /// Keeps its distance from the character and calls in more enemies around itself.
+    Summoner,
+    /// Wreathed in flame, so it can't be set alight.
+    Blazing,
+}
+
+impl EnemyKind {
+    #[must_use]
+    pub fn get_name(self) -> &'static str {
+        match self {
+            EnemyKind::Normal => "enemy",
+            EnemyKind::Splitter => "splitter",
+            EnemyKind::Splitling => "splitling",
+            EnemyKind::Armored => "armored enemy",
+            EnemyKind::Summoner => "summoner",
+            EnemyKind::Blazing => "blazing enemy",
+        }
+    }
+
+    /// Checks if enemies of this kind move twice for every normal enemy's move.
+    #[must_use]
+    pub fn is_fast(self) -> bool {
+        self == EnemyKind::Splitling
+    }
+
+    /// Checks if enemies of this kind only move on every other normal enemy's move.
+    #[must_use]
+    pub fn is_slow(self) -> bool {
+        self == EnemyKind::Armored
+    }
+
+    /// Returns the debuffs enemies of this kind can't be given at all.
+    #[must_use]
+    pub fn get_immunities(self) -> &'static [DebuffTypes] {
+        match self {
+            EnemyKind::Blazing => &[DebuffTypes::FlameBurn, DebuffTypes::FlameIgnite],
+            EnemyKind::Normal
+            | EnemyKind::Splitter
+            | EnemyKind::Splitling
+            | EnemyKind::Armored
+            | EnemyKind::Summoner => &[],
+        }
+    }
+}
+
+/// How well an enemy holds up against debuffs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DebuffResistance {
+    /// Debuffs that can't be given at all.
+    pub immunities: &'static [DebuffTypes],
+    /// Scales the chance of every proc landing.
+    pub proc_chance_mult: f64,
+}
+
+impl DebuffResistance {
+    /// Checks if a debuff can't be given at all.
+    #[must_use]
+    pub fn is_immune(&self, debuff_type: DebuffTypes) -> bool {
+        self.immunities.contains(&debuff_type)
+    }
+
+    /// Returns the chance out of 100 for a proc with the given chance to land.
+    #[must_use]
+    pub fn get_proc_chance(&self, chance: u32) -> u32 {
+        (f64::from(chance) * self.proc_chance_mult).round() as u32
+    }
+}
+
+/// Represents an enemy in the game. Enemies are equal when they share an id, i.e. when one is a
+/// clone of the other.
+#[derive(Clone)]
 pub struct Enemy {
+    id: EntityId,
+
     pub position: Position,
     prev_position: Position,
 
@@ -67,12 +158,27 @@ pub struct Enemy {
     pub debuffs: Vec<Debuff>,
 
     pub got_hit: (bool, i32),
+
+    pub is_elite: bool,
+
+    pub kind: EnemyKind,
+
+    /// Flat damage taken off every hit, though each hit still deals at least 1.
+    pub armor: i32,
+
+    /// Ticks since a summoner last called in enemies.
+    pub summon_ticks: u64,
+
+    pub health_display: EnemyHealthDisplay,
+
+    /// How many ticks in a row the enemy has spent far outside the camera.
+    pub far_ticks: u64,
 }
 
 /// A trait for entities that can have debuffs applied to them.
 pub trait Debuffable {
-    /// Attempts to apply a debuff with a certain chance of success.
-    fn try_proc(&mut self, proc: &Proc);
+    /// Attempts to apply a debuff with a certain chance of success. Returns whether it landed.
+    fn try_proc(&mut self, proc: &Proc) -> bool;
     /// Counts the number of a specific debuff on the entity.
     fn count_debuff(&self, debuff: &Debuff) -> u32;
 
@@ -80,36 +186,25 @@ pub trait Debuffable {
 }
 
 impl Debuffable for Enemy {
-    /// Attempts to apply the given `Proc`'s debuff to the enemy based on the proc's chance; if the proc succeeds and the enemy does not already have that debuff, the debuff is appended to the enemy's debuff list.
-    fn try_proc(&mut self, proc: &Proc) {
-        let mut rng = rand::rng();
+    /// Attempts to apply the given `Proc`'s debuff to the enemy based on the proc's chance, as
+    /// lowered by the enemy's resistance. Debuffs the enemy is immune to never land.
+    fn try_proc(&mut self, proc: &Proc) -> bool {
+        let resistance = self.get_debuff_resistance();
+
+        if resistance.is_immune(proc.debuff.debuff_type) {
+            return false;
+        }
+
+        let mut rng = runrng::rng();
 
         let roll = rng.random_range(1..=100);
 
-        if roll <= proc.chance {
-            match proc.debuff.debuff_type {
-                DebuffTypes::FlameBurn => {
-                    if self.count_debuff(&proc.debuff) < 2 {
-                        self.debuffs.push(proc.debuff.clone());
-                    } else {
-                        self.try_proc(&Proc {
-                            chance: 100,
-                            debuff: Debuff {
-                                debuff_type: DebuffTypes::FlameIgnite,
-                                stats: proc.debuff.stats.clone(),
-                                complete: false,
-                            },
-                        });
-                        self.remove_debuff(DebuffTypes::FlameBurn);
-                    }
-                }
-                _ => {
-                    if self.count_debuff(&proc.debuff) < 1 {
-                        self.debuffs.push(proc.debuff.clone());
-                    }
-                }
-            }
+        if roll <= resistance.get_proc_chance(proc.chance) {
+            self.apply_debuff(&proc.debuff);
+            return true;
         }
+
+        false
     }
 
     /// Counts how many active debuffs share the same debuff type as the provided `debuff`.
@@ -136,10 +231,218 @@ impl Debuffable for Enemy {
     }
 }
 
+impl PartialEq for Enemy {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Enemy {}
+
 impl Enemy {
+    const ELITE_HEALTH_MULT: i32 = 8;
+    const ELITE_DAMAGE_MULT: i32 = 2;
+    const ELITE_DROPS_MULT: u128 = 5;
+    const ELITE_PROC_CHANCE_MULT: f64 = 0.5;
+    const SPLITTER_HEALTH_MULT: i32 = 2;
+    const ARMORED_HEALTH_MULT: i32 = 3;
+    const SUMMONER_HEALTH_MULT: i32 = 2;
+    /// How many cells away from the character summoners try to stay.
+    const SUMMONER_DISTANCE: i32 = 6;
+    /// How many splitlings a splitter can split into, picked at random.
+    const SPLIT_COUNT: std::ops::RangeInclusive<usize> = 2..=3;
+
+    #[must_use]
+    pub fn get_id(&self) -> EntityId {
+        self.id
+    }
+
+    /// Turns this enemy into an elite: tougher, harder hitting, worth more, and dropping a chest
+    /// on death.
+    pub fn make_elite(&mut self) {
+        self.is_elite = true;
+
+        self.health *= Self::ELITE_HEALTH_MULT;
+        self.max_health = self.health;
+        self.damage *= Self::ELITE_DAMAGE_MULT;
+        self.drops.gold *= Self::ELITE_DROPS_MULT;
+        self.drops.xp *= Self::ELITE_DROPS_MULT;
+
+        self.entitychar.replace(EntityCharacters::Enemy(
+            Style::default().light_magenta().bold(),
+        ));
+    }
+
+    /// Turns this enemy into a splitter, which is tougher and splits into splitlings on death.
+    pub fn make_splitter(&mut self) {
+        self.kind = EnemyKind::Splitter;
+
+        self.health *= Self::SPLITTER_HEALTH_MULT;
+        self.max_health = self.health;
+
+        self.entitychar
+            .replace(EntityCharacters::Enemy(Style::default().light_green()));
+    }
+
+    /// Turns this enemy into an armored enemy, which is tougher, slower, and shrugs off `armor`
+    /// damage from every hit.
+    pub fn make_armored(&mut self, armor: i32) {
+        self.kind = EnemyKind::Armored;
+        self.armor = armor;
+
+        self.health *= Self::ARMORED_HEALTH_MULT;
+        self.max_health = self.health;
+
+        self.entitychar.replace(EntityCharacters::Enemy(
+            Style::default().light_cyan().bold(),
+        ));
+    }
+
+    /// Turns this enemy into a summoner, which hangs back and calls in more enemies.
+    pub fn make_summoner(&mut self) {
+        self.kind = EnemyKind::Summoner;
+
+        self.health *= Self::SUMMONER_HEALTH_MULT;
+        self.max_health = self.health;
+
+        self.entitychar
+            .replace(EntityCharacters::Enemy(Style::default().yellow()));
+    }
+
+    /// Turns this enemy into a blazing enemy, which can't be burned or ignited.
+    pub fn make_blazing(&mut self) {
+        self.kind = EnemyKind::Blazing;
+
+        self.entitychar
+            .replace(EntityCharacters::Enemy(Style::default().light_red()));
+    }
+
+    /// Returns how much of a hit for `damage` gets through the enemy's armor.
+    #[must_use]
+    pub fn reduce_damage(&self, damage: i32) -> i32 {
+        if damage <= 0 || self.armor <= 0 {
+            return damage;
+        }
+
+        (damage - self.armor).max(1)
+    }
+
+    /// Takes `damage` that has already been through the enemy's armor, marking the enemy as hit
+    /// and killing it if its health runs out.
+    pub fn take_reduced_damage(&mut self, damage: i32) {
+        let current_style = *self.entitychar.style_mut();
+
+        let normal_style = current_style;
+        let hurt_style = current_style.italic();
+
+        self.health = self.health.saturating_sub(damage);
+        self.got_hit = (true, damage);
+
+        if self.health >= self.max_health / 2 {
+            self.entitychar
+                .replace(EntityCharacters::Enemy(normal_style));
+        }
+        if self.health < self.max_health / 2 {
+            self.entitychar.replace(EntityCharacters::Enemy(hurt_style));
+        }
+        if self.health <= 0 {
+            self.die();
+        }
+    }
+
+    /// Splits a dead splitter into splitlings on free tiles next to it, skipping any tile in
+    /// `taken`. Splitlings have half the splitter's starting health, and drop the same as a
+    /// normal enemy.
+    #[must_use]
+    pub fn get_splits(&self, playable: &SquareArea, taken: &[Position]) -> Vec<Enemy> {
+        if self.kind != EnemyKind::Splitter {
+            return vec![];
+        }
+
+        let mut rng = runrng::rng();
+        let (x, y) = self.position.get();
+
+        let mut tiles: Vec<Position> = [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .into_iter()
+            .chain([(1, 1), (-1, 1), (1, -1), (-1, -1)])
+            .map(|(dx, dy)| Position(x + dx, y + dy))
+            .filter(|tile| can_stand(playable, None, tile) && !taken.contains(tile))
+            .collect();
+        tiles.shuffle(&mut rng);
+        tiles.truncate(rng.random_range(Self::SPLIT_COUNT));
+
+        let health = (self.max_health / Self::SPLITTER_HEALTH_MULT / 2).max(1);
+
+        tiles
+            .into_iter()
+            .map(|tile| {
+                let mut splitling = Enemy::new(tile, self.damage, health, self.drops.clone());
+                splitling.kind = EnemyKind::Splitling;
+                splitling.health_display = self.health_display;
+                splitling
+                    .entitychar
+                    .replace(EntityCharacters::Enemy(Style::default().green()));
+                splitling
+            })
+            .collect()
+    }
+
+    /// Adds a debuff that has landed, unless the enemy already has as many of it as can stack.
+    /// A third burn sets the enemy alight instead.
+    fn apply_debuff(&mut self, debuff: &Debuff) {
+        match debuff.debuff_type {
+            DebuffTypes::FlameBurn => {
+                if self.count_debuff(debuff) < 2 {
+                    self.debuffs.push(debuff.clone());
+                } else {
+                    if !self
+                        .get_debuff_resistance()
+                        .is_immune(DebuffTypes::FlameIgnite)
+                    {
+                        self.apply_debuff(&Debuff {
+                            debuff_type: DebuffTypes::FlameIgnite,
+                            stats: debuff.stats.clone(),
+                            complete: false,
+                        });
+                    }
+                    self.remove_debuff(DebuffTypes::FlameBurn);
+                }
+            }
+            _ => {
+                if self.count_debuff(debuff) < 1 {
+                    self.debuffs.push(debuff.clone());
+                }
+            }
+        }
+    }
+
+    /// Returns how well this enemy holds up against debuffs. Elites shrug off more procs.
+    #[must_use]
+    pub fn get_debuff_resistance(&self) -> DebuffResistance {
+        DebuffResistance {
+            immunities: self.kind.get_immunities(),
+            proc_chance_mult: if self.is_elite {
+                Self::ELITE_PROC_CHANCE_MULT
+            } else {
+                1.
+            },
+        }
+    }
+
+    /// Checks if the enemy is on fire, so it should leave burning ground when it dies.
+    #[must_use]
+    pub fn is_burning(&self) -> bool {
+        self.debuffs.iter().any(|debuff| {
+            matches!(
+                debuff.debuff_type,
+                DebuffTypes::FlameBurn | DebuffTypes::FlameIgnite
+            )
+        })
+    }
+
     /// Update the enemy's visual style to reflect any active debuffs.
     pub(crate) fn change_style_with_debuff(&mut self) {
-        let mut style = self.entitychar.style_mut().clone();
+        let mut style = *self.entitychar.style_mut();
 
         self.debuffs
             .iter()
@@ -159,6 +462,8 @@ impl Enemy {
 impl EnemyBehaviour for Enemy {
     fn new(position: Position, damage: i32, health: i32, drops: EnemyDrops) -> Self {
         Enemy {
+            id: EntityId::next(),
+
             position: position.clone(),
             prev_position: position,
 
@@ -177,11 +482,26 @@ impl EnemyBehaviour for Enemy {
             debuffs: Vec::new(),
 
             got_hit: (false, 0),
+
+            is_elite: false,
+
+            kind: EnemyKind::Normal,
+
+            armor: 0,
+
+            summon_ticks: 0,
+
+            health_display: EnemyHealthDisplay::default(),
+
+            far_ticks: 0,
         }
     }
 
     fn get_drops(&self) -> EnemyDrops {
-        self.drops.clone()
+        let mut drops = self.drops.clone();
+        drops.position = self.position.clone();
+        drops.chest = self.is_elite;
+        drops
     }
 
     fn update(
@@ -190,64 +510,131 @@ impl EnemyBehaviour for Enemy {
         layer: &Layer,
         damage_effects: &mut Vec<DamageEffect>,
     ) -> Option<(Position, Direction)> {
-        self.debuffs = self
-            .debuffs
-            .iter()
-            .filter(|debuff| !debuff.complete)
-            .cloned()
-            .collect();
+        let (diagonal, topology) = {
+            let player_state = character.stats.borrow();
+            (
+                player_state.settings.diagonal_movement,
+                player_state.get_topology(),
+            )
+        };
+        let intent = self.plan_move(character.get_pos(), layer, diagonal, topology);
+
+        if intent.attacks {
+            self.attack(character, damage_effects);
+        }
+
+        intent.desired_move
+    }
+}
+
+/// What an enemy wants to do this tick, worked out without touching the character so enemies
+/// can plan at the same time and have their plans applied one by one afterwards.
+#[derive(Clone)]
+pub struct EnemyIntent {
+    /// Whether the enemy is next to the character and hits it.
+    pub attacks: bool,
+    /// Where the enemy wants to step to, and which way it would face.
+    pub desired_move: Option<(Position, Direction)>,
+}
+
+impl Enemy {
+    /// Clears finished debuffs and works out this tick's attack and move towards the character,
+    /// stepping diagonally when `diagonal` is set and across the edges of a wrapping `topology`.
+    pub fn plan_move(
+        &mut self,
+        character_pos: &Position,
+        layer: &Layer,
+        diagonal: bool,
+        topology: Topology,
+    ) -> EnemyIntent {
+        self.debuffs.retain(|debuff| !debuff.complete);
 
         self.prev_position = self.position.clone();
 
-        if is_next_to_character(character.get_pos(), &self.position) {
-            character.take_damage(self.damage);
-            damage_effects.push(DamageEffect::new(
-                Square(SquareArea::from(character.get_pos().clone())),
-                EntityCharacters::AttackBlackout(Style::new().bold().dark_gray()),
-                Duration::from_secs_f64(0.2),
-                true,
-            ));
-        }
+        let (dist_x, dist_y) = topology.get_distance(&self.position, character_pos, layer);
+        let distance = dist_x.abs() + dist_y.abs();
+        let attacks = distance == 1;
 
         if self
             .debuffs
             .iter()
             .any(|debuff| debuff.debuff_type == DebuffTypes::ShockElectrocute)
         {
-            return None;
+            return EnemyIntent {
+                attacks,
+                desired_move: None,
+            };
+        }
+
+        let mut desired_facing = get_step_towards(dist_x, dist_y, diagonal);
+
+        // summoners back away when the character gets close, and hold still once far enough
+        if self.kind == EnemyKind::Summoner {
+            if distance < Self::SUMMONER_DISTANCE {
+                desired_facing = desired_facing.opposite();
+            } else if distance == Self::SUMMONER_DISTANCE {
+                return EnemyIntent {
+                    attacks,
+                    desired_move: None,
+                };
+            }
         }
 
-        let (desired_pos, desired_facing) =
-            move_to_point_granular(&self.position, character.get_pos(), true);
+        let mut desired_pos = desired_facing.step_from(&self.position);
+        topology.constrain(&mut desired_pos, layer);
 
-        if can_stand(
-            layer[0].len() as i32,
-            layer.len() as i32,
-            Some(character),
-            &desired_pos,
-        ) {
-            Some((desired_pos, desired_facing))
-        } else {
-            None
+        let desired_move = (can_stand(&SquareArea::covering(layer), None, &desired_pos)
+            && &desired_pos != character_pos)
+            .then_some((desired_pos, desired_facing));
+
+        EnemyIntent {
+            attacks,
+            desired_move,
         }
     }
+
+    /// Hits the character, flashing the tile it stands on.
+    pub fn attack(&self, character: &mut Character, damage_effects: &mut Vec<DamageEffect>) {
+        character.take_damage_from(
+            self.damage,
+            DamageSource::Enemy {
+                kind: self.kind,
+                elite: self.is_elite,
+            },
+        );
+        damage_effects.push(DamageEffect::new(
+            Square(SquareArea::from(character.get_pos().clone())),
+            EntityCharacters::AttackBlackout(Style::new().bold().dark_gray()),
+            Duration::from_secs_f64(0.2),
+            true,
+        ));
+    }
 }
 
+/// Works out which way an enemy steps to cover `dist_x` and `dist_y`. With `diagonal`, it cuts
+/// corners where it can, but never steps diagonally onto the target, since enemies only hit from
+/// the side.
 #[must_use]
-pub fn move_to_point_granular(
-    self_pos: &Position,
-    desired_location: &Position,
-    random: bool,
-) -> (Position, Direction) {
-    let (dist_x, dist_y) = self_pos.get_distance(desired_location);
-    let (x, y) = self_pos.get();
-    let desired_pos: Position;
-    let desired_facing: Direction;
+pub fn get_step_towards(dist_x: i32, dist_y: i32, diagonal: bool) -> Direction {
+    if diagonal
+        && dist_x != 0
+        && dist_y != 0
+        && (dist_x.abs() > 1 || dist_y.abs() > 1)
+        && let Some(direction) = Direction::from_offset(dist_x, dist_y)
+    {
+        return direction;
+    }
+
+    get_granular_step(dist_x, dist_y, true)
+}
 
+/// Picks which single axis to step along to cover `dist_x` and `dist_y`: at random weighted by
+/// distance with `random`, otherwise along the longer one.
+fn get_granular_step(dist_x: i32, dist_y: i32, random: bool) -> Direction {
     let total_dist = dist_x.abs() + dist_y.abs();
 
     let choice: bool = if random {
-        let mut rng = rand::rng();
+        let mut rng = runrng::rng();
         rng.random_ratio(dist_x.abs().max(1) as u32, total_dist.abs().max(1) as u32)
     } else {
         dist_x.abs() > dist_y.abs()
@@ -255,21 +642,28 @@ pub fn move_to_point_granular(
 
     if choice {
         if dist_x > 0 {
-            desired_pos = Position::new(x + 1, y);
-            desired_facing = Direction::RIGHT;
+            Direction::RIGHT
         } else {
-            desired_pos = Position::new(x - 1, y);
-            desired_facing = Direction::LEFT;
+            Direction::LEFT
         }
     } else if dist_y > 0 {
-        desired_pos = Position::new(x, y + 1);
-        desired_facing = Direction::DOWN;
+        Direction::DOWN
     } else {
-        desired_pos = Position::new(x, y - 1);
-        desired_facing = Direction::UP;
+        Direction::UP
     }
+}
+
+#[must_use]
+pub fn move_to_point_granular(
+    self_pos: &Position,
+    desired_location: &Position,
+    random: bool,
+) -> (Position, Direction) {
+    let (dist_x, dist_y) = self_pos.get_distance(desired_location);
+    let desired_facing = get_granular_step(dist_x, dist_y, random);
+    let (x, y) = desired_facing.step_from(self_pos).get();
 
-    (desired_pos, desired_facing)
+    (Position::new(x, y), desired_facing)
 }
 
 impl Renderable for Enemy {
@@ -280,6 +674,27 @@ impl Renderable for Enemy {
     fn get_entity_char(&self) -> &EntityCharacters {
         &self.entitychar
     }
+
+    /// Draws the enemy with a glyph graded by remaining health when enabled in settings.
+    fn get_styled(&self) -> Span<'static> {
+        let span = self.entitychar.to_styled();
+
+        match self.health_display {
+            EnemyHealthDisplay::Classic => span,
+            EnemyHealthDisplay::Glyphs => {
+                let health_fraction = f64::from(self.health) / f64::from(self.max_health.max(1));
+                let glyph = if health_fraction > 2. / 3. {
+                    "✖"
+                } else if health_fraction > 1. / 3. {
+                    "X"
+                } else {
+                    "x"
+                };
+
+                span.content(glyph)
+            }
+        }
+    }
 }
 
 impl Movable for Enemy {
@@ -308,24 +723,8 @@ impl Damageable for Enemy {
     }
 
     fn take_damage(&mut self, damage: i32) {
-        let current_style = *self.entitychar.style_mut();
-
-        let normal_style = current_style;
-        let hurt_style = current_style.italic();
-
-        self.health = self.health.saturating_sub(damage);
-        self.got_hit = (true, damage);
-
-        if self.health >= self.max_health / 2 {
-            self.entitychar
-                .replace(EntityCharacters::Enemy(normal_style));
-        }
-        if self.health < self.max_health / 2 {
-            self.entitychar.replace(EntityCharacters::Enemy(hurt_style));
-        }
-        if self.health <= 0 {
-            self.die();
-        }
+        let damage = self.reduce_damage(damage);
+        self.take_reduced_damage(damage);
     }
 
     fn die(&mut self) {
@@ -356,3 +755,199 @@ pub fn get_closest_enemies<'a>(
         }
     })
 }
+
+/// How far apart two enemies can be while still counting towards the same cluster.
+const CLUSTER_RADIUS: i32 = 2;
+
+/// Picks the enemy a seeking weapon should go for from `current_position`, following the given
+/// `TargetPriority`. Summoners are always gone for first, nearest first. Ties between equally
+/// good targets go to the nearest one.
+#[must_use]
+pub fn select_target<'a>(
+    enemies: &'a [Enemy],
+    current_position: &Position,
+    priority: TargetPriority,
+) -> Option<&'a Enemy> {
+    let distance = |enemy: &Enemy| {
+        let (dist_x, dist_y) = enemy.get_pos().get_distance(current_position);
+        dist_x.abs() + dist_y.abs()
+    };
+
+    let summoner = enemies
+        .iter()
+        .filter(|enemy| enemy.kind == EnemyKind::Summoner)
+        .min_by_key(|enemy| distance(enemy));
+    if summoner.is_some() {
+        return summoner;
+    }
+
+    match priority {
+        TargetPriority::Nearest => get_closest_enemies(enemies, current_position),
+        TargetPriority::LowestHealth => enemies
+            .iter()
+            .min_by_key(|enemy| (enemy.health, distance(enemy))),
+        TargetPriority::HighestHealth => enemies
+            .iter()
+            .min_by_key(|enemy| (-enemy.health, distance(enemy))),
+        TargetPriority::DensestCluster => enemies.iter().min_by_key(|enemy| {
+            let neighbours = enemies
+                .iter()
+                .filter(|other| {
+                    let (dist_x, dist_y) = other.get_pos().get_distance(enemy.get_pos());
+                    dist_x.abs().max(dist_y.abs()) <= CLUSTER_RADIUS
+                })
+                .count();
+
+            (std::cmp::Reverse(neighbours), distance(enemy))
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Style;
+
+    use crate::common::character::{Damageable, Renderable};
+    use crate::common::coords::{Direction, Position, SquareArea, Topology};
+    use crate::common::debuffs::{Debuff, DebuffTypes};
+    use crate::common::enemies::enemy::{
+        Debuffable, Enemy, EnemyBehaviour, EnemyDrops, EnemyKind, get_step_towards, select_target,
+    };
+    use crate::common::entities::EntityCharacters;
+    use crate::common::settings::setting::TargetPriority;
+    use crate::common::stats::{DebuffStats, Proc};
+
+    fn get_drops() -> EnemyDrops {
+        EnemyDrops {
+            gold: 1,
+            xp: 0,
+            position: Position::default(),
+            chest: false,
+        }
+    }
+
+    #[test]
+    fn splitters_split_onto_free_tiles() {
+        let layer = vec![vec![EntityCharacters::Empty(Style::new()); 3]; 3];
+
+        let mut enemy = Enemy::new(Position::new(1, 1), 1, 4, get_drops());
+        assert!(
+            enemy
+                .get_splits(&SquareArea::covering(&layer), &[])
+                .is_empty()
+        );
+
+        enemy.make_splitter();
+        let taken = [Position::new(0, 0), Position::new(2, 2)];
+
+        for _ in 0..20 {
+            let splits = enemy.get_splits(&SquareArea::covering(&layer), &taken);
+
+            assert!((2..=3).contains(&splits.len()));
+            assert!(splits.iter().all(|split| {
+                split.kind == EnemyKind::Splitling
+                    && split.max_health == 2
+                    && !taken.contains(split.get_pos())
+                    && split.get_pos() != enemy.get_pos()
+            }));
+        }
+    }
+
+    #[test]
+    fn enemies_cut_corners_but_attack_from_the_side() {
+        assert_eq!(get_step_towards(3, 2, true), Direction::DOWNRIGHT);
+
+        let next_to = get_step_towards(1, 1, true);
+        assert!(next_to == Direction::RIGHT || next_to == Direction::DOWN);
+
+        assert!(!get_step_towards(3, 2, false).is_diagonal());
+    }
+
+    #[test]
+    fn enemies_chase_across_wrapping_edges() {
+        let layer = vec![vec![EntityCharacters::Empty(Style::new()); 10]; 10];
+        let mut enemy = Enemy::new(Position::new(0, 5), 1, 4, get_drops());
+
+        let intent = enemy.plan_move(&Position::new(8, 5), &layer, false, Topology::Wrapping);
+        assert_eq!(
+            intent.desired_move.map(|(position, _)| position),
+            Some(Position::new(9, 5))
+        );
+
+        let beside = enemy.plan_move(&Position::new(9, 5), &layer, false, Topology::Wrapping);
+        assert!(beside.attacks);
+        assert!(
+            !enemy
+                .plan_move(&Position::new(9, 5), &layer, false, Topology::Bounded)
+                .attacks
+        );
+    }
+
+    #[test]
+    fn identical_enemies_stay_distinct() {
+        let enemy = Enemy::new(Position::new(0, 0), 1, 10, get_drops());
+        let twin = Enemy::new(Position::new(0, 0), 1, 10, get_drops());
+
+        assert!(enemy != twin);
+        assert_ne!(enemy.get_id(), twin.get_id());
+        assert!(enemy.clone() == enemy);
+    }
+
+    #[test]
+    fn armor_reduces_hits_to_at_least_one() {
+        let mut enemy = Enemy::new(Position::new(0, 0), 1, 10, get_drops());
+        enemy.make_armored(2);
+        assert_eq!(enemy.max_health, 30);
+
+        enemy.take_damage(5);
+        assert_eq!(*enemy.get_health(), 27);
+
+        enemy.take_damage(1);
+        assert_eq!(*enemy.get_health(), 26);
+    }
+
+    #[test]
+    fn summoners_are_targeted_first() {
+        let near = Enemy::new(Position::new(1, 0), 1, 1, get_drops());
+        let mut summoner = Enemy::new(Position::new(8, 0), 1, 10, get_drops());
+        summoner.make_summoner();
+
+        let enemies = [near, summoner];
+        let target = select_target(&enemies, &Position::new(0, 0), TargetPriority::LowestHealth);
+
+        assert_eq!(target.map(Renderable::get_pos), Some(&Position::new(8, 0)));
+    }
+
+    #[test]
+    fn resistances_stop_procs() {
+        let burn = Proc {
+            chance: 100,
+            debuff: Debuff {
+                debuff_type: DebuffTypes::FlameBurn,
+                stats: DebuffStats {
+                    size: None,
+                    damage: Some(1),
+                    misc_value: None,
+                    on_death_effect: false,
+                    on_tick_effect: true,
+                    on_damage_effect: false,
+                },
+                complete: false,
+            },
+        };
+
+        let mut enemy = Enemy::new(Position::new(0, 0), 1, 1, get_drops());
+        enemy.make_blazing();
+        assert!(!enemy.try_proc(&burn));
+        assert!(enemy.debuffs.is_empty());
+
+        let mut elite = Enemy::new(Position::new(0, 0), 1, 1, get_drops());
+        elite.make_elite();
+        assert_eq!(elite.get_debuff_resistance().get_proc_chance(100), 50);
+        assert!(
+            !elite
+                .get_debuff_resistance()
+                .is_immune(DebuffTypes::FlameBurn)
+        );
+    }
+}
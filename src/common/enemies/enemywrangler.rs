@@ -1,32 +1,63 @@
 use crate::common::character::{Character, Damageable, Movable, Renderable};
-use crate::common::coords::{ChaosArea, Position};
-use crate::common::debuffs::{GetDebuffTypes, OnDamageEffect, OnDeathEffect, OnTickEffect};
+use crate::common::coords::{ChaosArea, Position, SquareArea};
+use crate::common::debuffs::{
+    DebuffCounts, GetDebuffTypes, OnDamageEffect, OnDeathEffect, OnTickEffect,
+};
 use crate::common::effects::DamageEffect;
-use crate::common::enemies::enemy::{Enemy, EnemyBehaviour, EnemyDrops};
-use crate::common::map::Layer;
+use crate::common::enemies::director::{SpawnDirector, SpawnPurchase};
+use crate::common::enemies::enemy::{Enemy, EnemyBehaviour, EnemyDrops, EnemyKind};
+use crate::common::enemies::phase::Phase;
+use crate::common::hazards::{Hazard, Hazards};
+use crate::common::map::{Layer, Map};
+use crate::common::runrng;
 use crate::common::sound::{SoundEffect, SoundWrangler};
 use crate::common::timescaler::TimeScaler;
 use crate::common::utils::{
-    can_stand, convert_range, get_rand_position_on_edge, is_next_to_character,
-    per_sec_to_tick_count, per_sec_to_tick_count_to_u64,
+    can_stand, get_positions_on_edge, get_rand_position_around_area, is_next_to_character,
+    per_sec_to_tick_count_to_u64,
 };
-use crate::common::weapons::DamageArea;
+use crate::common::weapons::{DamageArea, resolve_damage};
 use crate::common::{PlayerStateRef, TICK_RATE};
+use rand::Rng;
 use std::cell::RefCell;
 use std::rc::Rc;
 
 pub struct EnemyWrangler {
     pub enemies: Rc<RefCell<Vec<Enemy>>>,
-    pub enemy_spawn_ticks: u64,
-    pub enemy_spawn_mult: f64,
+    /// Paces natural spawns, spending a threat budget on waves of enemies.
+    pub director: SpawnDirector,
     pub enemy_move_ticks: u64,
     pub enemy_health: i32,
     pub enemy_damage: i32,
+    /// How much armor armored enemies spawn with.
+    pub enemy_armor: i32,
     pub enemy_drops: EnemyDrops,
     pub player_state: PlayerStateRef,
     pub timescaler: Rc<RefCell<TimeScaler>>,
 
     pub sound_wrangler: Rc<RefCell<SoundWrangler>>,
+
+    /// How many living enemies are affected by each kind of debuff, as of the last tick.
+    pub debuff_counts: DebuffCounts,
+
+    /// The current phase of the run, which scales spawn and movement rates.
+    pub phase: Phase,
+
+    /// How many enemies have been killed this run.
+    pub kill_count: u64,
+
+    /// Whether enemies spawn on their own over time. Off for scripted runs like the tutorial.
+    pub natural_spawns: bool,
+
+    /// Enemies held back by the enemy cap or despawned far from the camera, waiting to be sent
+    /// back in near the character.
+    pub pressure: u64,
+
+    /// Where the character was on the last tick, to tell when it has moved.
+    last_character_pos: Position,
+
+    /// How many times every enemy has been moved, so slow enemies can sit out every other move.
+    move_count: u64,
 }
 
 impl EnemyWrangler {
@@ -34,6 +65,23 @@ impl EnemyWrangler {
     const DEFAULT_SPAWN_P_S: f64 = 0.4;
     const DEFAULT_MOVE_P_S: f64 = 1.3;
     const DEFAULT_HEALTH: i32 = 2;
+    const SPAWN_REROLLS: usize = 3;
+    /// Chance out of 100 for a destroyed wall to drop gold.
+    const RUBBLE_GOLD_CHANCE: u32 = 15;
+    /// How many cells outside the camera an enemy has to be to count as far away.
+    const DESPAWN_DISTANCE: i32 = 30;
+    /// How long an enemy has to stay far away before it is despawned.
+    const DESPAWN_SECS: f64 = 10.;
+    /// How many cells outside the camera held back enemies are sent back in.
+    const PRESSURE_MARGIN: i32 = 2;
+    /// The most held back enemies sent back in each tick the character moves.
+    const PRESSURE_RELEASE: u64 = 3;
+    /// How many enemies in a pack are spread over each extra cell around its spot.
+    const PACK_SPREAD: usize = 8;
+    /// How often each summoner calls in enemies.
+    const SUMMON_SECS: f64 = 5.;
+    /// How many enemies a summoner calls in at once.
+    const SUMMON_COUNT: usize = 2;
 
     pub fn new(
         player_state: PlayerStateRef,
@@ -44,21 +92,34 @@ impl EnemyWrangler {
         let player_state_ref = player_state.borrow().clone();
 
         let enemy_move_ticks = per_sec_to_tick_count_to_u64(Self::DEFAULT_MOVE_P_S);
-        let enemy_spawn_ticks = per_sec_to_tick_count_to_u64(
+        let mut director = SpawnDirector::new(&player_state_ref.stats.game_stats);
+        director.set_spawn_rate(
             Self::DEFAULT_SPAWN_P_S * player_state_ref.stats.game_stats.enemy_spawn_mult,
         );
 
         Self {
             enemy_move_ticks,
-            enemy_spawn_ticks,
-            enemy_spawn_mult: 1.0,
+            director,
             enemy_damage: 1,
+            enemy_armor: 1,
             enemy_health: Self::DEFAULT_HEALTH,
-            enemy_drops: EnemyDrops { gold: 1, xp: 0 },
+            enemy_drops: EnemyDrops {
+                gold: 1,
+                xp: 0,
+                position: Position::default(),
+                chest: false,
+            },
             enemies,
             player_state,
             timescaler,
             sound_wrangler,
+            debuff_counts: DebuffCounts::default(),
+            phase: Phase::default(),
+            kill_count: 0,
+            natural_spawns: true,
+            pressure: 0,
+            last_character_pos: Position::default(),
+            move_count: 0,
         }
     }
 
@@ -66,52 +127,128 @@ impl EnemyWrangler {
         &mut self,
         tickcount: u64,
         character: &mut Character,
-        layer: &Layer,
+        partner: Option<&mut Character>,
+        map: &mut Map,
+        camera_area: &SquareArea,
         active_damage_effects: &mut Vec<DamageEffect>,
     ) -> Vec<EnemyDrops> {
-        if tickcount.is_multiple_of(self.enemy_spawn_ticks) {
-            for _ in 0..self.enemy_spawn_mult.ceil() as i32 {
-                self.spawn_enemy(layer);
+        self.despawn_far_enemies(camera_area);
+
+        if character.get_pos() != &self.last_character_pos {
+            self.last_character_pos = character.get_pos().clone();
+            self.release_pressure(map, camera_area, character);
+        }
+
+        if self.natural_spawns {
+            for purchase in self.director.tick(&mut runrng::rng()) {
+                self.spawn_purchase(purchase, map, character);
             }
         }
 
+        self.summon_enemies(map, character);
+
         if tickcount.is_multiple_of(self.enemy_move_ticks) {
-            self.update_enemies(character, layer, active_damage_effects);
+            self.update_enemies(character, partner, map, active_damage_effects, false);
+        } else if self.enemy_move_ticks > 1
+            && tickcount % self.enemy_move_ticks == self.enemy_move_ticks / 2
+        {
+            // fast enemies get another move halfway between everyone's moves
+            self.update_enemies(character, partner, map, active_damage_effects, true);
         }
 
         if tickcount.is_multiple_of(TICK_RATE.floor() as u64) {
             self.scale_enemies();
         }
 
-        self.process_enemy_effects(layer, active_damage_effects, tickcount)
+        self.process_enemy_effects(map, active_damage_effects, tickcount, character.get_pos())
     }
 
+    /// Returns how quickly enemies are spawning, compared to the start of a default run.
     #[must_use]
     pub fn get_spawn_multiplier(&self) -> f64 {
-        if self.enemy_spawn_ticks > 1 {
-            convert_range(self.enemy_spawn_ticks as f64, 50., 1., 0., 1.)
-        } else {
-            convert_range(self.enemy_spawn_mult, 1., 10., 1., 100.)
-        }
+        self.director.get_income() / Self::DEFAULT_SPAWN_P_S
     }
 
+    /// Moves enemies towards the nearer player and lets those next to it attack. With
+    /// `fast_only`, only enemies that move twice as often get to act. Slow enemies still attack
+    /// when they sit out a move.
     fn update_enemies(
         &mut self,
         character: &mut Character,
-        layer: &Layer,
+        mut partner: Option<&mut Character>,
+        map: &Map,
         active_damage_effects: &mut Vec<DamageEffect>,
+        fast_only: bool,
     ) {
+        let layer = &map.map;
+        let playable = map.get_playable_area();
         let enemy_area = ChaosArea::new(self.get_enemy_positions());
+        let mut rng = runrng::rng();
 
-        self.enemies.borrow_mut().iter_mut().for_each(|enemy| {
-            if let Some((desired_pos, desired_facing)) =
-                enemy.update(character, layer, active_damage_effects)
-                && can_stand(
-                    self.player_state.borrow().stats.game_stats.width as i32,
-                    self.player_state.borrow().stats.game_stats.height as i32,
-                    Some(character),
-                    &desired_pos,
-                )
+        let mut enemies = self.enemies.borrow_mut();
+
+        if !fast_only {
+            self.move_count += 1;
+        }
+        let slow_rests = !self.move_count.is_multiple_of(2);
+
+        let character_pos = character.get_pos().clone();
+        let character_alive = character.is_alive();
+        let partner_pos = partner
+            .as_deref()
+            .filter(|partner| partner.is_alive())
+            .map(|partner| partner.get_pos().clone());
+        let (diagonal, topology) = {
+            let player_state = self.player_state.borrow();
+            (
+                player_state.settings.diagonal_movement,
+                player_state.get_topology(),
+            )
+        };
+        let intents = map_enemies(&mut enemies, |enemy| {
+            (!fast_only || enemy.kind.is_fast()).then(|| {
+                let get_steps = |target: &Position| {
+                    let (dist_x, dist_y) = topology.get_distance(enemy.get_pos(), target, layer);
+                    dist_x.abs() + dist_y.abs()
+                };
+                let partner_target = partner_pos.as_ref().filter(|partner_pos| {
+                    !character_alive || get_steps(partner_pos) < get_steps(&character_pos)
+                });
+
+                let mut intent = enemy.plan_move(
+                    partner_target.unwrap_or(&character_pos),
+                    layer,
+                    diagonal,
+                    topology,
+                );
+                if slow_rests && enemy.kind.is_slow() {
+                    intent.desired_move = None;
+                }
+                (intent, partner_target.is_some())
+            })
+        });
+
+        // plans are applied in enemy order, so the outcome doesn't depend on how planning was split
+        for (enemy, planned) in enemies.iter_mut().zip(intents) {
+            let Some((intent, targets_partner)) = planned else {
+                continue;
+            };
+
+            if intent.attacks {
+                let target = match partner.as_deref_mut() {
+                    Some(partner) if targets_partner => partner,
+                    _ => &mut *character,
+                };
+                enemy.attack(target, active_damage_effects);
+            }
+
+            // rough terrain makes enemies miss some of their steps
+            let terrain_cost = map.get_biome(enemy.get_pos()).movement_cost();
+
+            if let Some((desired_pos, desired_facing)) = intent.desired_move
+                && rng.random_bool(1. / terrain_cost.max(1.))
+                && can_stand(&playable, Some(character), &desired_pos)
+                && partner_pos.as_ref() != Some(&desired_pos)
                 && !desired_pos.is_in_area(&enemy_area)
             {
                 enemy.move_to(desired_pos, desired_facing);
@@ -119,9 +256,12 @@ impl EnemyWrangler {
 
             let character_stats = &self.player_state.borrow().stats.player_stats;
 
-            if character_stats.shove_amount > 0
-                && is_next_to_character(character.get_pos(), enemy.get_prev_pos())
-            {
+            let next_to_player = is_next_to_character(character.get_pos(), enemy.get_prev_pos())
+                || partner_pos.as_ref().is_some_and(|partner_pos| {
+                    is_next_to_character(partner_pos, enemy.get_prev_pos())
+                });
+
+            if character_stats.shove_amount > 0 && next_to_player {
                 if character_stats.shove_damage > 0 {
                     enemy.take_damage(
                         (f64::from(character_stats.shove_damage) * character_stats.damage_mult)
@@ -131,62 +271,334 @@ impl EnemyWrangler {
 
                 enemy.move_back(character_stats.shove_amount as i32, layer);
             }
-        });
+        }
     }
 
-    pub fn spawn_enemy(&mut self, layer: &Layer) {
-        if self.enemies.borrow().len() as u64 >= Self::ENEMY_CAP {
-            return;
+    /// The most enemies kept alive at once, as set by the player.
+    fn get_soft_cap(&self) -> u64 {
+        self.player_state
+            .borrow()
+            .settings
+            .enemy_cap
+            .get_cap()
+            .min(Self::ENEMY_CAP)
+    }
+
+    /// Whether there's room for another live enemy. When there isn't, the enemy is added to the
+    /// pressure to be sent in later instead.
+    fn has_room(&mut self) -> bool {
+        if self.enemies.borrow().len() as u64 >= self.get_soft_cap() {
+            self.pressure += 1;
+            return false;
+        }
+
+        true
+    }
+
+    /// Counts how long each enemy has been far outside the camera, and swaps those that have been
+    /// away too long for pressure. Elites are kept so their chests aren't lost.
+    fn despawn_far_enemies(&mut self, camera_area: &SquareArea) {
+        let despawn_ticks = (Self::DESPAWN_SECS * TICK_RATE).ceil() as u64;
+
+        let mut enemies = self.enemies.borrow_mut();
+
+        for enemy in enemies.iter_mut() {
+            if camera_area.get_distance_outside(enemy.get_pos()) > Self::DESPAWN_DISTANCE {
+                enemy.far_ticks += 1;
+            } else {
+                enemy.far_ticks = 0;
+            }
+        }
+
+        let init_size = enemies.len();
+        enemies.retain(|enemy| enemy.is_elite || enemy.far_ticks < despawn_ticks);
+
+        self.pressure += (init_size - enemies.len()) as u64;
+    }
+
+    /// Sends a few held back enemies in just outside the camera, as long as there's room under
+    /// the enemy cap.
+    fn release_pressure(&mut self, map: &Map, camera_area: &SquareArea, character: &Character) {
+        let room = self
+            .get_soft_cap()
+            .saturating_sub(self.enemies.borrow().len() as u64);
+        let count = self.pressure.min(room).min(Self::PRESSURE_RELEASE);
+
+        let playable = map.get_playable_area();
+
+        for _ in 0..count {
+            let position =
+                get_rand_position_around_area(camera_area, Self::PRESSURE_MARGIN, &map.map);
+
+            let enemy_area = ChaosArea::new(self.get_enemy_positions());
+
+            if position.is_in_area(&enemy_area) || !can_stand(&playable, Some(character), &position)
+            {
+                continue;
+            }
+
+            let mut enemy = Enemy::new(
+                position,
+                self.enemy_damage,
+                self.enemy_health,
+                self.enemy_drops.clone(),
+            );
+
+            enemy.health_display = self.player_state.borrow().settings.enemy_health_display;
+
+            self.enemies.borrow_mut().push(enemy);
+            self.pressure -= 1;
+        }
+    }
+
+    /// Picks a spot on the edge of the playable area to spawn at, rerolling spots in biomes that enemies are less
+    /// likely to spawn in.
+    fn get_spawn_position(map: &Map) -> Position {
+        let mut rng = runrng::rng();
+
+        let playable = map.get_playable_area();
+
+        let mut position = get_rand_position_around_area(&playable, 0, &map.map);
+        for _ in 0..Self::SPAWN_REROLLS {
+            if rng.random_bool(map.get_biome(&position).spawn_weight().clamp(0., 1.)) {
+                break;
+            }
+            position = get_rand_position_around_area(&playable, 0, &map.map);
         }
 
-        let position = get_rand_position_on_edge(layer);
+        position
+    }
+
+    /// Spawns a single enemy somewhere on the map edge.
+    pub fn spawn_enemy(&mut self, map: &Map) {
+        self.spawn_at(map, Self::get_spawn_position(map), None, |_| {});
+    }
+
+    /// Spawns what the spawn director bought. Packs are bunched up around one spot on the edge.
+    fn spawn_purchase(&mut self, purchase: SpawnPurchase, map: &Map, character: &Character) {
+        match purchase {
+            SpawnPurchase::Normal => {
+                self.spawn_at(map, Self::get_spawn_position(map), Some(character), |_| {});
+            }
+            SpawnPurchase::Elite => {
+                self.spawn_at(
+                    map,
+                    Self::get_spawn_position(map),
+                    Some(character),
+                    Enemy::make_elite,
+                );
+            }
+            SpawnPurchase::Splitter => {
+                self.spawn_at(
+                    map,
+                    Self::get_spawn_position(map),
+                    Some(character),
+                    Enemy::make_splitter,
+                );
+            }
+            SpawnPurchase::Armored => {
+                let armor = self.enemy_armor;
+                self.spawn_at(
+                    map,
+                    Self::get_spawn_position(map),
+                    Some(character),
+                    |enemy| {
+                        enemy.make_armored(armor);
+                    },
+                );
+            }
+            SpawnPurchase::Summoner => {
+                self.spawn_at(
+                    map,
+                    Self::get_spawn_position(map),
+                    Some(character),
+                    Enemy::make_summoner,
+                );
+            }
+            SpawnPurchase::Blazing => {
+                self.spawn_at(
+                    map,
+                    Self::get_spawn_position(map),
+                    Some(character),
+                    Enemy::make_blazing,
+                );
+            }
+            SpawnPurchase::Pack(size) => {
+                let anchor = Self::get_spawn_position(map);
+                let anchor_area = SquareArea::new(anchor.clone(), anchor);
+
+                for index in 0..size {
+                    let margin = 1 + (index / Self::PACK_SPREAD) as i32;
+                    let position = get_rand_position_around_area(&anchor_area, margin, &map.map);
+                    self.spawn_at(map, position, Some(character), |_| {});
+                }
+            }
+        }
+    }
+
+    /// Counts down each summoner's timer, and has those that are due call in enemies around
+    /// themselves. Summoners don't call in anything past the enemy cap.
+    fn summon_enemies(&mut self, map: &Map, character: &Character) {
+        let summon_ticks = (Self::SUMMON_SECS * TICK_RATE).ceil() as u64;
+
+        let summoners: Vec<Position> = self
+            .enemies
+            .borrow_mut()
+            .iter_mut()
+            .filter(|enemy| enemy.kind == EnemyKind::Summoner)
+            .filter_map(|enemy| {
+                enemy.summon_ticks += 1;
+                (enemy.summon_ticks >= summon_ticks).then(|| {
+                    enemy.summon_ticks = 0;
+                    enemy.get_pos().clone()
+                })
+            })
+            .collect();
+
+        for summoner in summoners {
+            let summoner_area = SquareArea::new(summoner.clone(), summoner);
+
+            for _ in 0..Self::SUMMON_COUNT {
+                if self.enemies.borrow().len() as u64 >= self.get_soft_cap() {
+                    return;
+                }
+
+                let position = get_rand_position_around_area(&summoner_area, 1, &map.map);
+                self.spawn_at(map, position, Some(character), |_| {});
+            }
+        }
+    }
+
+    /// Spawns an enemy at `position`, unless the spot is taken, letting `prepare` make it into a
+    /// special enemy first. Enemies past the enemy cap are added to the pressure instead.
+    fn spawn_at(
+        &mut self,
+        map: &Map,
+        position: Position,
+        character: Option<&Character>,
+        prepare: impl FnOnce(&mut Enemy),
+    ) {
+        if !self.has_room() {
+            return;
+        }
 
         let enemy_area = ChaosArea::new(self.get_enemy_positions());
 
-        if position.is_in_area(&enemy_area) {
+        if position.is_in_area(&enemy_area)
+            || !can_stand(&map.get_playable_area(), character, &position)
+        {
             return;
         }
 
-        self.enemies.borrow_mut().push(Enemy::new(
-            get_rand_position_on_edge(layer),
+        let mut enemy = Enemy::new(
+            position,
             self.enemy_damage,
             self.enemy_health,
             self.enemy_drops.clone(),
-        ));
+        );
+
+        enemy.health_display = self.player_state.borrow().settings.enemy_health_display;
+
+        prepare(&mut enemy);
+
+        self.enemies.borrow_mut().push(enemy);
+    }
+
+    /// Rolls gold drops for walls destroyed at the given positions. Each wall has a small chance
+    /// to drop the same gold as an enemy would.
+    #[must_use]
+    pub fn get_rubble_drops(&self, destroyed_walls: &[Position]) -> Vec<EnemyDrops> {
+        let mut rng = runrng::rng();
+
+        destroyed_walls
+            .iter()
+            .filter(|_| rng.random_ratio(Self::RUBBLE_GOLD_CHANCE, 100))
+            .map(|position| EnemyDrops {
+                gold: self.enemy_drops.gold,
+                xp: 0,
+                position: position.clone(),
+                chest: false,
+            })
+            .collect()
+    }
+
+    /// Spawns a ring of `size` enemies evenly spaced around the map edge, skipping any spots that
+    /// are already taken. The first enemy of the ring is an elite. Enemies past the enemy cap are
+    /// added to the pressure instead.
+    pub fn spawn_horde(&mut self, map: &Map, size: usize, character: &Character) {
+        let enemy_area = ChaosArea::new(self.get_enemy_positions());
+        let playable = map.get_playable_area();
+        let mut elite_spawned = false;
+
+        for position in get_positions_on_edge(&map.map, size) {
+            if !self.has_room() {
+                continue;
+            }
+
+            if position.is_in_area(&enemy_area) || !can_stand(&playable, Some(character), &position)
+            {
+                continue;
+            }
+
+            let mut enemy = Enemy::new(
+                position,
+                self.enemy_damage,
+                self.enemy_health,
+                self.enemy_drops.clone(),
+            );
+
+            enemy.health_display = self.player_state.borrow().settings.enemy_health_display;
+
+            if !elite_spawned {
+                enemy.make_elite();
+                elite_spawned = true;
+            }
+
+            self.enemies.borrow_mut().push(enemy);
+        }
     }
 
     fn process_enemy_effects(
         &mut self,
-        layer: &Layer,
+        map: &mut Map,
         active_damage_effects: &mut Vec<DamageEffect>,
         tickcount: u64,
+        character_pos: &Position,
     ) -> Vec<EnemyDrops> {
+        let playable = map.get_playable_area();
+        let Map {
+            map: layer,
+            hazards,
+            ..
+        } = map;
+
         let mut damage_areas: Vec<DamageArea> = Vec::new();
 
         let mut drops = Vec::new();
+        let mut splits: Vec<Enemy> = Vec::new();
 
-        let mut enemies = self.enemies.borrow_mut().clone();
+        let snapshot = self.enemies.borrow().clone();
+        let mut enemies = snapshot.clone();
 
         let init_size = enemies.len();
 
-        for enemy in &mut enemies {
-            let mut debuffs = enemy.debuffs.clone();
+        let mut debuff_counts = DebuffCounts::default();
 
-            for debuff in &mut debuffs {
-                if let Some(damage_area) =
-                    debuff.on_tick(enemy, layer, tickcount, self.sound_wrangler.clone())
-                {
-                    damage_areas.push(damage_area);
-                }
-                if let Some(damage_area) = debuff.on_damage(enemy, layer, &self.enemies.borrow()) {
-                    {
-                        damage_areas.push(damage_area);
-                    }
-                }
+        let debuff_areas = map_enemies(&mut enemies, |enemy| {
+            tick_debuffs(enemy, layer, tickcount, &snapshot)
+        });
+
+        for (ignitions, mut areas) in debuff_areas {
+            for _ in 0..ignitions {
+                self.sound_wrangler.borrow().play(SoundEffect::Ignite);
             }
+            damage_areas.append(&mut areas);
+        }
 
-            debuffs.retain(|d| !d.complete);
-            enemy.debuffs = debuffs;
+        for enemy in &mut enemies {
+            if enemy.is_alive() {
+                debuff_counts.count(&enemy.debuffs);
+            }
 
             if !enemy.is_alive() {
                 if !enemy.debuffs.get_on_death_effects().is_empty() {
@@ -197,20 +609,49 @@ impl EnemyWrangler {
                     }
                 }
 
+                let mut taken: Vec<Position> = snapshot
+                    .iter()
+                    .map(|enemy| enemy.get_pos().clone())
+                    .chain(splits.iter().map(|split| split.get_pos().clone()))
+                    .collect();
+                taken.push(character_pos.clone());
+                splits.append(&mut enemy.get_splits(&playable, &taken));
+
+                if enemy.is_burning() {
+                    hazards.insert(
+                        enemy.position.clone(),
+                        Hazard::burning_ground(enemy.position.clone(), tickcount),
+                    );
+                }
+
                 drops.push(enemy.get_drops());
+                self.kill_count += 1;
             }
         }
 
         self.enemies.replace(enemies);
+        self.debuff_counts = debuff_counts;
 
         self.enemies.borrow_mut().retain(Damageable::is_alive);
 
+        for split in splits {
+            if self.has_room() {
+                self.enemies.borrow_mut().push(split);
+            }
+        }
+
         if self.enemies.borrow().len() < init_size {
             self.sound_wrangler.borrow().play(SoundEffect::EnemyKill);
         }
 
+        let _ = resolve_damage(
+            &damage_areas,
+            &mut self.enemies.borrow_mut(),
+            &self.sound_wrangler,
+        );
         for damage_area in damage_areas {
-            damage_area.deal_damage(&mut self.enemies.borrow_mut(), self.sound_wrangler.clone());
+            let destroyed_walls = map.destroy_walls(&damage_area);
+            drops.append(&mut self.get_rubble_drops(&destroyed_walls));
 
             let damage_effect = DamageEffect::from(damage_area);
 
@@ -220,6 +661,21 @@ impl EnemyWrangler {
         drops
     }
 
+    /// Hurts every enemy standing on a hazard by a share of its max health, or kills it outright
+    /// on a lethal one.
+    pub fn apply_hazards(&mut self, hazards: &Hazards) {
+        for enemy in self.enemies.borrow_mut().iter_mut() {
+            if let Some(hazard) = hazards.get(&enemy.position) {
+                let damage = if hazard.is_lethal() {
+                    enemy.max_health
+                } else {
+                    (f64::from(enemy.max_health) * Hazard::ENEMY_MAX_HEALTH_DAMAGE).ceil() as i32
+                };
+                enemy.take_damage(damage);
+            }
+        }
+    }
+
     pub fn on_frame(&mut self) {
         self.enemies.borrow_mut().iter_mut().for_each(|e| {
             e.change_style_with_debuff();
@@ -229,10 +685,12 @@ impl EnemyWrangler {
     fn scale_enemies(&mut self) {
         let init_enemy_health = Self::DEFAULT_HEALTH;
         let init_enemy_damage = 1.;
-        let init_enemy_spawn_secs =
-            Self::DEFAULT_SPAWN_P_S * self.player_state.borrow().stats.game_stats.enemy_spawn_mult;
-        let init_enemy_move_secs =
-            Self::DEFAULT_MOVE_P_S * self.player_state.borrow().stats.game_stats.enemy_move_mult;
+        let init_enemy_spawn_secs = Self::DEFAULT_SPAWN_P_S
+            * self.player_state.borrow().stats.game_stats.enemy_spawn_mult
+            * self.phase.spawn_mult();
+        let init_enemy_move_secs = Self::DEFAULT_MOVE_P_S
+            * self.player_state.borrow().stats.game_stats.enemy_move_mult
+            * self.phase.move_mult();
         let init_enemy_gold: u128 = 1;
         let init_enemy_xp: u128 = 1;
 
@@ -242,24 +700,13 @@ impl EnemyWrangler {
             (f64::from(init_enemy_health) * (time_scaler * 0.75).max(1.)).ceil() as i32;
 
         self.enemy_damage = (init_enemy_damage * (time_scaler / 50.).max(1.)).ceil() as i32;
-        let enemy_spawn_calc =
-            per_sec_to_tick_count(init_enemy_spawn_secs * (0.8 * time_scaler).max(1.));
-        if enemy_spawn_calc > 1.5 {
-            self.enemy_spawn_ticks = enemy_spawn_calc.ceil() as u64;
-        } else {
-            self.enemy_spawn_ticks = 1;
-            self.enemy_spawn_mult = convert_range(enemy_spawn_calc, 1.0, 0.0, 1.0, 5.0);
-        }
+        self.enemy_armor = (time_scaler / 4.).max(1.).ceil() as i32;
+        self.director
+            .set_spawn_rate(init_enemy_spawn_secs * (0.8 * time_scaler).max(1.));
 
         self.enemy_move_ticks =
             per_sec_to_tick_count_to_u64(init_enemy_move_secs * (time_scaler / 7.).max(1.));
 
-        if self.enemy_spawn_ticks < 5 {
-            self.enemy_spawn_ticks =
-                per_sec_to_tick_count_to_u64(init_enemy_move_secs * (time_scaler / 18.).max(1.))
-                    .min(5);
-        }
-
         self.enemy_drops = EnemyDrops {
             gold: (init_enemy_gold as f64 * (time_scaler / 2.).max(1.)).ceil() as u128,
             xp: if self.player_state.borrow().upgrade_owned("A") {
@@ -267,6 +714,8 @@ impl EnemyWrangler {
             } else {
                 0
             },
+            position: Position::default(),
+            chest: false,
         }
     }
 
@@ -279,3 +728,68 @@ impl EnemyWrangler {
             .collect()
     }
 }
+
+/// Runs an enemy's debuffs for a tick against a snapshot of every enemy from the start of the
+/// tick. Returns how many ignitions went off, and every damage area the debuffs set off.
+fn tick_debuffs(
+    enemy: &mut Enemy,
+    layer: &Layer,
+    tickcount: u64,
+    snapshot: &[Enemy],
+) -> (usize, Vec<DamageArea>) {
+    let mut ignitions = 0;
+    let mut damage_areas = vec![];
+    let mut debuffs = enemy.debuffs.clone();
+
+    for debuff in &mut debuffs {
+        if let Some(damage_area) = debuff.on_tick(enemy, layer, tickcount) {
+            ignitions += 1;
+            damage_areas.push(damage_area);
+        }
+        if let Some(damage_area) = debuff.on_damage(enemy, layer, snapshot) {
+            damage_areas.push(damage_area);
+        }
+    }
+
+    debuffs.retain(|d| !d.complete);
+    enemy.debuffs = debuffs;
+
+    (ignitions, damage_areas)
+}
+
+/// Runs `f` on every enemy, spread across threads, keeping the results in enemy order. Each
+/// enemy rolls from a seed of its own, so the run plays out the same whichever thread it's on.
+#[cfg(all(feature = "parallel", not(target_family = "wasm")))]
+fn map_enemies<T: Send>(
+    enemies: &mut [Enemy],
+    f: impl Fn(&mut Enemy) -> T + Sync + Send,
+) -> Vec<T> {
+    use rayon::prelude::*;
+
+    let seeds = roll_seeds(enemies.len());
+
+    enemies
+        .par_iter_mut()
+        .zip(seeds)
+        .map(|(enemy, seed)| runrng::with_seed(seed, || f(enemy)))
+        .collect()
+}
+
+/// Runs `f` on every enemy in order, keeping the results in enemy order. Each enemy rolls from a
+/// seed of its own, the same way as when they're spread across threads.
+#[cfg(not(all(feature = "parallel", not(target_family = "wasm"))))]
+fn map_enemies<T>(enemies: &mut [Enemy], f: impl Fn(&mut Enemy) -> T) -> Vec<T> {
+    let seeds = roll_seeds(enemies.len());
+
+    enemies
+        .iter_mut()
+        .zip(seeds)
+        .map(|(enemy, seed)| runrng::with_seed(seed, || f(enemy)))
+        .collect()
+}
+
+/// Rolls a seed for each of `count` enemies from the run generator.
+fn roll_seeds(count: usize) -> Vec<u64> {
+    let mut rng = runrng::rng();
+    (0..count).map(|_| rng.random()).collect()
+}
@@ -1,2 +1,5 @@
+pub mod director;
+pub mod encounter;
 pub mod enemy;
 pub mod enemywrangler;
+pub mod phase;
@@ -0,0 +1,100 @@
+//! This module provides the run's phase cycle, which alternates calm and surge phases on the run
+//! clock. Surges spawn more enemies that move faster, and tint the background while they last.
+
+use ratatui::style::{Color, Style};
+
+use crate::prelude::Duration;
+
+/// A phase of the run's cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Phase {
+    #[default]
+    Calm,
+    Surge,
+}
+
+impl Phase {
+    const CALM_SECS: u64 = 40;
+    const SURGE_SECS: u64 = 20;
+
+    /// Returns the phase at the given point in the run, and how long until the next phase starts.
+    #[must_use]
+    pub fn at(elapsed: Duration) -> (Self, Duration) {
+        let cycle = Duration::from_secs(Self::CALM_SECS + Self::SURGE_SECS);
+        let calm = Duration::from_secs(Self::CALM_SECS);
+
+        let into_cycle = Duration::from_nanos((elapsed.as_nanos() % cycle.as_nanos()) as u64);
+
+        if into_cycle < calm {
+            (Phase::Calm, calm.saturating_sub(into_cycle))
+        } else {
+            (Phase::Surge, cycle.saturating_sub(into_cycle))
+        }
+    }
+
+    #[must_use]
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            Phase::Calm => "calm",
+            Phase::Surge => "SURGE",
+        }
+    }
+
+    /// Multiplier applied to the enemy spawn rate.
+    #[must_use]
+    pub fn spawn_mult(&self) -> f64 {
+        match self {
+            Phase::Calm => 0.7,
+            Phase::Surge => 1.8,
+        }
+    }
+
+    /// Multiplier applied to how often enemies move.
+    #[must_use]
+    pub fn move_mult(&self) -> f64 {
+        match self {
+            Phase::Calm => 0.9,
+            Phase::Surge => 1.3,
+        }
+    }
+
+    /// The style used for the phase in the HUD.
+    #[must_use]
+    pub fn get_style(&self) -> Style {
+        match self {
+            Phase::Calm => Style::new().gray(),
+            Phase::Surge => Style::new().red().bold(),
+        }
+    }
+
+    /// The background tint for the map during this phase, if any.
+    #[must_use]
+    pub fn get_tint(&self) -> Option<Color> {
+        match self {
+            Phase::Calm => None,
+            Phase::Surge => Some(Color::Rgb(40, 6, 6)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::enemies::phase::Phase;
+    use crate::prelude::Duration;
+
+    #[test]
+    fn phases_alternate() {
+        assert_eq!(
+            Phase::at(Duration::ZERO),
+            (Phase::Calm, Duration::from_secs(40))
+        );
+        assert_eq!(
+            Phase::at(Duration::from_secs(45)),
+            (Phase::Surge, Duration::from_secs(15))
+        );
+        assert_eq!(
+            Phase::at(Duration::from_secs(61)),
+            (Phase::Calm, Duration::from_secs(39))
+        );
+    }
+}
@@ -1,5 +1,21 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
 use ratatui::prelude::{Span, Style};
 
+/// Identifies one entity for as long as it exists, however it moves or changes. Clones of an
+/// entity share its id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EntityId(u64);
+
+impl EntityId {
+    /// Makes an id that no other entity has been given.
+    #[must_use]
+    pub fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        Self(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
 #[derive(PartialEq, Eq, Clone)]
 pub enum EntityCharacters {
     Background1(Style),
@@ -11,6 +27,13 @@ pub enum EntityCharacters {
     AttackMist(Style),
     AttackWeak(Style),
     Orb(Style),
+    Chest(Style),
+    Bomb(Style),
+    GoldGem(Style, &'static str),
+    Wall(Style),
+    Spikes(Style),
+    BurningGround(Style),
+    Turret(Style),
 }
 
 impl EntityCharacters {
@@ -32,6 +55,15 @@ impl EntityCharacters {
                 Span::from(ratatui::symbols::shade::LIGHT).style(*style)
             }
             EntityCharacters::Orb(style) => Span::from("o").style(*style),
+            EntityCharacters::Chest(style) => Span::from("#").style(*style),
+            EntityCharacters::Bomb(style) => Span::from("*").style(*style),
+            EntityCharacters::GoldGem(style, glyph) => Span::from(*glyph).style(*style),
+            EntityCharacters::Spikes(style) => Span::from("^").style(*style),
+            EntityCharacters::BurningGround(style) => Span::from("≈").style(*style),
+            EntityCharacters::Turret(style) => Span::from("T").style(*style),
+            EntityCharacters::Wall(style) => {
+                Span::from(ratatui::symbols::shade::DARK).style(*style)
+            }
         }
     }
 
@@ -45,6 +77,13 @@ impl EntityCharacters {
             EntityCharacters::Character(style)
             | EntityCharacters::Enemy(style)
             | EntityCharacters::Orb(style)
+            | EntityCharacters::Chest(style)
+            | EntityCharacters::Bomb(style)
+            | EntityCharacters::GoldGem(style, _)
+            | EntityCharacters::Wall(style)
+            | EntityCharacters::Spikes(style)
+            | EntityCharacters::BurningGround(style)
+            | EntityCharacters::Turret(style)
             | EntityCharacters::AttackBlackout(style)
             | EntityCharacters::AttackMist(style)
             | EntityCharacters::Background1(style)
@@ -0,0 +1,96 @@
+//! This module provides the event log: a running, plain-text narration of what happens in a run,
+//! for players who can't easily follow the map itself.
+
+use std::collections::VecDeque;
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Paragraph, Wrap},
+};
+
+/// The lines of narration for a run, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct EventLog {
+    lines: VecDeque<String>,
+}
+
+impl EventLog {
+    /// How many lines are kept before the oldest are dropped.
+    pub const CAPACITY: usize = 200;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a line to the log, dropping the oldest line if the log is full.
+    pub fn push(&mut self, line: impl Into<String>) {
+        if self.lines.len() >= Self::CAPACITY {
+            self.lines.pop_front();
+        }
+
+        self.lines.push_back(line.into());
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Returns up to `count` of the newest lines, oldest first.
+    pub fn get_recent(&self, count: usize) -> impl Iterator<Item = &String> {
+        self.lines
+            .iter()
+            .skip(self.lines.len().saturating_sub(count))
+    }
+
+    /// Renders the newest lines that fit in `area`, with the latest at the bottom.
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let block = Block::bordered()
+            .title(" log ".dark_gray())
+            .border_set(border::PLAIN);
+        let visible = block.inner(area).height as usize;
+
+        let lines: Vec<Line> = self
+            .get_recent(visible)
+            .map(|line| Line::from(line.clone()))
+            .collect();
+
+        let log = Paragraph::new(lines).block(block).wrap(Wrap { trim: true });
+
+        frame.render_widget(log, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::eventlog::EventLog;
+
+    #[test]
+    fn oldest_lines_drop_when_full() {
+        let mut log = EventLog::new();
+
+        for i in 0..=EventLog::CAPACITY {
+            log.push(format!("line {i}"));
+        }
+
+        assert_eq!(log.len(), EventLog::CAPACITY);
+        let recent: Vec<&String> = log.get_recent(2).collect();
+        assert_eq!(
+            recent,
+            [
+                &format!("line {}", EventLog::CAPACITY - 1),
+                &format!("line {}", EventLog::CAPACITY)
+            ]
+        );
+    }
+}
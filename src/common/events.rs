@@ -0,0 +1,9 @@
+//! This module defines events emitted during a run, so other systems can react to
+//! what happened without the game loop knowing about them.
+
+/// Something notable that happened during a run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunEvent {
+    /// The player reached a new in-run level.
+    LevelUp(i32),
+}
@@ -1,85 +1,184 @@
+use crate::common::apperror::AppError;
+use crate::common::capture::{Capture, CaptureKind, CaptureOutput};
+use crate::common::classes::classmenu::ClassMenu;
+use crate::common::collection::collectionmenu::CollectionMenu;
+use crate::common::keybinds::Action;
+#[cfg(feature = "leaderboard")]
+use crate::common::leaderboard::leaderboardmenu::LeaderboardMenu;
+use crate::common::loadouts::loadoutmenu::LoadoutMenu;
+use crate::common::mastery::masterymenu::MasteryMenu;
+use crate::common::mutators::mutatormenu::MutatorMenu;
+#[cfg(feature = "netplay")]
+use crate::common::netplay::netplaymenu::NetplayMenu;
+use crate::common::perf::PerfStats;
+use crate::common::popups::helpoverlay::HelpOverlay;
+use crate::common::render::apply_theme;
 use crate::common::rogue::Rogue;
+use crate::common::router::Router;
+use crate::common::settings::settingsmenu::SettingsMenu;
 use crate::common::sound::SoundWrangler;
+use crate::common::toasts::ToastSeverity;
 use crate::common::upgrades::upgrade::PlayerState;
 use crate::common::upgrades::upgrademenu::UpgradesMenu;
-use crate::common::{Goto, PlayerStateRef, Viewable};
-use crate::prelude::{KeyEvent, save_progress};
-use ratatui::Frame;
+use crate::common::{Goto, MIN_TERMINAL_HEIGHT, MIN_TERMINAL_WIDTH, PlayerStateRef, View};
+use crate::prelude::{Instant, KeyEvent, save_progress};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    text::Line,
+    widgets::{Block, Paragraph},
+};
 use std::cell::RefCell;
 use std::rc::Rc;
 
-pub enum View {
-    Rogue(Rogue),
-    Upgrades(UpgradesMenu),
-}
-
-impl View {
-    pub fn get_view_mut(&mut self) -> &mut dyn Viewable {
-        match self {
-            View::Rogue(rogue_game) => rogue_game,
-            View::Upgrades(upgrades_menu) => upgrades_menu,
-        }
-    }
-    #[must_use]
-    pub fn get_view_ref(&self) -> &dyn Viewable {
-        match self {
-            View::Rogue(rogue_game) => rogue_game,
-            View::Upgrades(upgrades_menu) => upgrades_menu,
-        }
-    }
-
-    #[must_use]
-    pub fn get_goto(&self) -> &Goto {
-        self.get_view_ref().get_goto()
-    }
-}
-
 pub struct Game {
-    view: View,
+    router: Router,
     pub player_state: PlayerStateRef,
     pub sound_wrangler: Rc<RefCell<SoundWrangler>>,
+    /// Timings for the performance overlay, shown while `show_perf` is on.
+    perf: PerfStats,
+    show_perf: bool,
+    /// A failure for the app to show on its error screen, if one has happened.
+    error: Option<AppError>,
+    /// Whether progress has been saved since the app last checked, for it to confirm.
+    saved: bool,
+    capture: Capture,
+    /// A screenshot or clip waiting for the app to save it.
+    captured: Option<CaptureOutput>,
 }
 
 impl Game {
-    #[must_use]
-    pub fn new(player_state: PlayerState, sound_wrangler: Rc<RefCell<SoundWrangler>>) -> Self {
+    /// Creates a new game, starting on the tutorial for new players and the upgrades menu for
+    /// everyone else.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the upgrades menu can't be opened.
+    pub fn new(
+        player_state: PlayerState,
+        sound_wrangler: Rc<RefCell<SoundWrangler>>,
+    ) -> Result<Self, AppError> {
         let player_state_rc = Rc::new(RefCell::new(player_state));
 
-        Self {
-            view: View::Upgrades(UpgradesMenu::new(player_state_rc.clone())),
+        let view: Box<dyn View> = if player_state_rc.borrow().is_fresh() {
+            Box::new(Rogue::tutorial(&player_state_rc, sound_wrangler.clone()))
+        } else {
+            Box::new(UpgradesMenu::new(player_state_rc.clone())?)
+        };
+
+        Ok(Self {
+            router: Router::new(view),
             player_state: player_state_rc.clone(),
             sound_wrangler,
-        }
+            perf: PerfStats::new(),
+            show_perf: false,
+            error: None,
+            saved: false,
+            capture: Capture::new(),
+            captured: None,
+        })
+    }
+
+    /// Takes the latest failure, if there's been one, for the app to show.
+    pub fn take_error(&mut self) -> Option<AppError> {
+        self.error.take()
+    }
+
+    /// Checks whether progress has been saved since this was last called.
+    pub fn take_saved(&mut self) -> bool {
+        std::mem::take(&mut self.saved)
+    }
+
+    /// Takes the latest screenshot or clip, if one has been captured, for the app to save.
+    pub fn take_capture(&mut self) -> Option<CaptureOutput> {
+        self.captured.take()
+    }
+
+    /// Tells the player how saving a capture went.
+    pub fn notify_capture(&mut self, message: impl Into<String>, severity: ToastSeverity) {
+        self.capture.notify(message, severity);
     }
 
+    /// Opens the view for `goto`. The help opens over the current view and going back closes
+    /// it again, while anything else replaces every open view.
+    #[tracing::instrument(skip(self))]
     pub fn go_to(&mut self, goto: &Goto) {
-        match goto {
+        let view: Box<dyn View> = match goto {
             Goto::Upgrades => {
-                self.view = View::Upgrades(UpgradesMenu::new(self.player_state.clone()));
+                // challenges last until the player heads back to change their build
+                self.player_state.borrow_mut().leave_challenge();
+
+                match UpgradesMenu::new(self.player_state.clone()) {
+                    Ok(upgrades_menu) => Box::new(upgrades_menu),
+                    Err(err) => {
+                        self.error = Some(err);
+                        return;
+                    }
+                }
             }
             Goto::Game => {
-                self.view = View::Rogue(Rogue::new(
+                // a co-op room that's just filled up starts its run over the network
+                #[cfg(feature = "netplay")]
+                if let Some(session) = self.router.get_view_mut().take_session() {
+                    self.router.open(Box::new(Rogue::networked(
+                        &self.player_state,
+                        self.sound_wrangler.clone(),
+                        session,
+                    )));
+                    return;
+                }
+
+                Box::new(Rogue::new(
                     &self.player_state.clone(),
                     self.sound_wrangler.clone(),
-                ));
+                ))
             }
-            Goto::Menu => {}
-        }
+            Goto::Retry(seed) => Box::new(Rogue::with_seed(
+                &self.player_state.clone(),
+                self.sound_wrangler.clone(),
+                *seed,
+            )),
+            Goto::ClassSelect => Box::new(ClassMenu::new(self.player_state.clone())),
+            Goto::Loadout => Box::new(LoadoutMenu::new(self.player_state.clone())),
+            Goto::Mutators => Box::new(MutatorMenu::new(self.player_state.clone())),
+            Goto::Collection => Box::new(CollectionMenu::new(self.player_state.clone())),
+            Goto::Mastery => Box::new(MasteryMenu::new(self.player_state.clone())),
+            Goto::Settings => Box::new(SettingsMenu::new(self.player_state.clone())),
+            #[cfg(feature = "leaderboard")]
+            Goto::Leaderboard => Box::new(LeaderboardMenu::new()),
+            #[cfg(feature = "netplay")]
+            Goto::Netplay => Box::new(NetplayMenu::new(self.player_state.clone())),
+            Goto::Help => {
+                let context = self.router.get_key_context();
+                self.router.push(Box::new(HelpOverlay::new(context)));
+                return;
+            }
+            Goto::Back => {
+                self.router.pop();
+                return;
+            }
+            Goto::Menu => {
+                self.player_state.borrow_mut().leave_challenge();
+                return;
+            }
+        };
+
+        self.router.open(view);
     }
 
     #[must_use]
-    pub fn is_correct_view(&self) -> bool {
-        let goto = self.view.get_goto().clone();
-
-        match self.view {
-            View::Upgrades(_) => goto == Goto::Upgrades,
-            View::Rogue(_) => goto == Goto::Game,
-        }
+    pub fn get_goto(&self) -> &Goto {
+        self.router.get_view_ref().transition()
     }
 
+    /// Checks whether the next tick will leave the current view to start a new run.
     #[must_use]
-    pub fn get_goto(&self) -> &Goto {
-        self.view.get_goto()
+    pub fn is_starting_run(&self) -> bool {
+        matches!(
+            self.router.get_transition(),
+            Some(Goto::Game | Goto::Retry(_))
+        )
     }
 
     #[must_use]
@@ -87,34 +186,119 @@ impl Game {
         self.player_state.borrow().clone()
     }
 
-    ///# Panics
-    ///
-    /// will panic if save fails
+    /// Moves the current view on, or switches to the view it asked for, saving progress on the
+    /// way. A failed save is kept for the app to show.
     pub fn on_tick(&mut self) {
-        let goto = self.view.get_goto().clone();
-
-        if self.is_correct_view() {
-            self.view.get_view_mut().tick();
-        } else {
-            self.player_state.borrow_mut().refresh();
-            save_progress(&self.player_state.borrow()).expect("Save failed");
-            self.go_to(&goto);
+        match self.router.get_transition().cloned() {
+            None => {
+                let started = Instant::now();
+                self.router.on_tick();
+                self.perf.record_tick(started.elapsed());
+            }
+            // the help doesn't change anything, so there's nothing new to save
+            Some(goto @ (Goto::Help | Goto::Back)) => self.go_to(&goto),
+            Some(goto) => {
+                self.player_state.borrow_mut().refresh();
+                match save_progress(&self.player_state.borrow()) {
+                    Ok(()) => self.saved = true,
+                    Err(err) => self.error = Some(err),
+                }
+                self.go_to(&goto);
+            }
         }
     }
 
     pub fn on_frame(&mut self) {
-        self.view.get_view_mut().frame();
+        let started = Instant::now();
+        self.router.on_frame();
+        self.perf.record_frame(started.elapsed());
+    }
+
+    /// Returns what the player is doing right now, for their Discord profile.
+    #[must_use]
+    pub fn get_presence(&self) -> String {
+        self.router.get_presence()
+    }
+
+    /// Returns the most frames per second the player wants drawn.
+    #[must_use]
+    pub fn get_frame_limit(&self) -> f64 {
+        self.player_state.borrow().settings.frame_limit.get_fps()
     }
 
     pub fn handle_key_event(&mut self, key_event: &KeyEvent) {
         #[cfg(not(target_family = "wasm"))]
-        if !key_event.is_press() {
+        if key_event.is_release() {
             return;
         }
-        self.view.get_view_mut().handle_key_event(key_event);
+
+        match self.router.get_key_context().get_action(&key_event.code) {
+            Some(Action::PerfOverlay) => self.show_perf = !self.show_perf,
+            Some(Action::Screenshot) => self.capture.request(CaptureKind::Screenshot),
+            Some(Action::Clip) => self.capture.request(CaptureKind::Clip),
+            // the help's own key closes it, so it's left for the help to handle
+            Some(Action::Help) if *self.router.get_route() != Goto::Help => {
+                self.go_to(&Goto::Help);
+            }
+            _ => self.router.handle_key_event(key_event),
+        }
+    }
+
+    /// Passes a released key on to the view on top.
+    pub fn handle_key_release(&mut self, key_event: &KeyEvent) {
+        self.router.handle_key_release(key_event);
+    }
+
+    /// Lets the open views lay themselves out again for a screen of the given size.
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.router.resize(Rect::new(0, 0, width, height));
+    }
+
+    /// Tells the current view whether it can be seen, so runs can pause while hidden.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.router.set_visible(visible);
     }
 
     pub fn render(&mut self, frame: &mut Frame) {
-        self.view.get_view_mut().render(frame);
+        let area = frame.area();
+        if area.width < MIN_TERMINAL_WIDTH || area.height < MIN_TERMINAL_HEIGHT {
+            render_too_small(frame);
+            return;
+        }
+
+        let started = Instant::now();
+        self.router.render(frame);
+        self.perf.record_render(started.elapsed());
+
+        if self.show_perf {
+            self.perf
+                .render(frame, area, self.router.get_entity_counts());
+        }
+
+        let settings = &self.player_state.borrow().settings;
+        apply_theme(frame.buffer_mut(), settings.theme);
+
+        // captured before the notices, so they don't show up in their own screenshots
+        if let Some(output) = self.capture.on_render(frame.buffer_mut(), settings) {
+            self.captured = Some(output);
+        }
+        self.capture.render_notices(frame);
     }
 }
+
+/// Renders a notice in place of the view while the screen is too small to play in.
+fn render_too_small(frame: &mut Frame) {
+    let area = frame.area();
+
+    let notice = Paragraph::new(vec![
+        Line::from("terminal too small".bold()),
+        Line::from(format!(
+            "{}x{} / {MIN_TERMINAL_WIDTH}x{MIN_TERMINAL_HEIGHT}",
+            area.width, area.height
+        )),
+    ])
+    .block(Block::bordered())
+    .centered();
+
+    frame.render_widget(notice, area);
+}
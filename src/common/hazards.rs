@@ -0,0 +1,117 @@
+//! This module defines hazard tiles, which hurt whatever stands on them.
+//! Spikes are placed by the map generator, burning ground is left behind by burning enemies, and
+//! closing walls take over the map's edges late in the run.
+
+use std::collections::BTreeMap;
+
+use ratatui::style::Style;
+
+use crate::common::character::Renderable;
+use crate::common::coords::Position;
+use crate::common::entities::EntityCharacters;
+use crate::common::utils::duration_to_ticks;
+use crate::prelude::Duration;
+
+pub type Hazards = BTreeMap<Position, Hazard>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HazardKind {
+    Spikes,
+    BurningGround,
+    /// Lethal, left behind as the walls close in.
+    ClosingWall,
+}
+
+impl HazardKind {
+    #[must_use]
+    pub fn get_name(self) -> &'static str {
+        match self {
+            HazardKind::Spikes => "spikes",
+            HazardKind::BurningGround => "burning ground",
+            HazardKind::ClosingWall => "the closing walls",
+        }
+    }
+}
+
+/// A single hazard tile.
+#[derive(Clone)]
+pub struct Hazard {
+    pub kind: HazardKind,
+    pub position: Position,
+    entity_char: EntityCharacters,
+    /// The tick the hazard runs out on, if it ever does.
+    expires_at: Option<u64>,
+}
+
+impl Hazard {
+    /// How long burning ground lasts.
+    pub const BURN_DURATION: Duration = Duration::from_secs(3);
+    /// How many times a second hazards hurt whatever stands on them.
+    pub const HURTS_P_S: f64 = 2.;
+    /// The share of an enemy's max health dealt each time a hazard hurts it.
+    pub const ENEMY_MAX_HEALTH_DAMAGE: f64 = 0.1;
+
+    /// Creates a permanent spike tile.
+    #[must_use]
+    pub fn spikes(position: Position) -> Self {
+        Self {
+            kind: HazardKind::Spikes,
+            position,
+            entity_char: EntityCharacters::Spikes(Style::new().gray()),
+            expires_at: None,
+        }
+    }
+
+    /// Creates a patch of burning ground, lit on `tickcount`, that goes out after `BURN_DURATION`.
+    #[must_use]
+    pub fn burning_ground(position: Position, tickcount: u64) -> Self {
+        Self {
+            kind: HazardKind::BurningGround,
+            position,
+            entity_char: EntityCharacters::BurningGround(Style::new().red()),
+            expires_at: Some(tickcount + duration_to_ticks(Self::BURN_DURATION)),
+        }
+    }
+
+    /// Creates a permanent tile of closing wall.
+    #[must_use]
+    pub fn closing_wall(position: Position) -> Self {
+        Self {
+            kind: HazardKind::ClosingWall,
+            position,
+            entity_char: EntityCharacters::Wall(Style::new().red()),
+            expires_at: None,
+        }
+    }
+
+    /// The damage this hazard deals to the character each time it hurts, unless it's lethal.
+    #[must_use]
+    pub fn get_damage(&self) -> i32 {
+        match self.kind {
+            HazardKind::Spikes | HazardKind::BurningGround | HazardKind::ClosingWall => 1,
+        }
+    }
+
+    /// Checks if the hazard kills whatever stands on it outright, ignoring hazard resistance.
+    #[must_use]
+    pub fn is_lethal(&self) -> bool {
+        self.kind == HazardKind::ClosingWall
+    }
+
+    /// Checks if the hazard has run out by `tickcount`.
+    #[must_use]
+    pub fn is_expired(&self, tickcount: u64) -> bool {
+        self.expires_at
+            .is_some_and(|expires_at| tickcount >= expires_at)
+    }
+}
+
+impl Renderable for Hazard {
+    fn get_pos(&self) -> &Position {
+        &self.position
+    }
+
+    fn get_entity_char(&self) -> &EntityCharacters {
+        &self.entity_char
+    }
+}
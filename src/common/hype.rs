@@ -0,0 +1,99 @@
+//! This module provides `Hype`, the player-facing side of the doom offset. The offset raises the
+//! difficulty from the start of a run; hype shows how far it's been pushed, tints the HUD to match
+//! and, past a threshold, doubles the gold enemies drop.
+
+use ratatui::style::{Color, Style};
+use ratatui::text::Span;
+
+/// How hyped the run is, from its doom offset.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hype {
+    offset: f64,
+}
+
+impl Hype {
+    /// The offset that fills the hype meter.
+    pub const MAX: f64 = 16.;
+    /// The offset from which enemies drop double gold.
+    pub const THRESHOLD: f64 = 8.;
+    /// The multiplier on gold drops once past the threshold.
+    pub const GOLD_MULT: f64 = 2.;
+
+    #[must_use]
+    pub fn new(doom_offset: f64) -> Self {
+        Self {
+            offset: doom_offset.max(0.),
+        }
+    }
+
+    /// Returns how full the hype meter is, between 0 and 1.
+    #[must_use]
+    pub fn get_ratio(&self) -> f64 {
+        (self.offset / Self::MAX).clamp(0., 1.)
+    }
+
+    /// Checks whether the run is past the threshold for double gold.
+    #[must_use]
+    pub fn is_hyped(&self) -> bool {
+        self.offset >= Self::THRESHOLD
+    }
+
+    #[must_use]
+    pub fn get_gold_mult(&self) -> f64 {
+        if self.is_hyped() { Self::GOLD_MULT } else { 1. }
+    }
+
+    /// The colour the HUD is tinted, shifting from yellow to hot pink as hype builds. `None` with
+    /// no hype at all.
+    #[must_use]
+    pub fn get_tint(&self) -> Option<Color> {
+        if self.offset <= 0. {
+            return None;
+        }
+
+        let ratio = self.get_ratio();
+        let lerp =
+            |from: u8, to: u8| (f64::from(from) + (f64::from(to) - f64::from(from)) * ratio) as u8;
+
+        Some(Color::Rgb(lerp(230, 255), lerp(200, 60), lerp(90, 150)))
+    }
+
+    /// Builds the hype meter shown in the HUD. Returns `None` with no hype at all.
+    #[must_use]
+    pub fn get_chip(&self) -> Option<Span<'static>> {
+        const BAR_WIDTH: usize = 5;
+
+        let tint = self.get_tint()?;
+        let filled = (self.get_ratio() * BAR_WIDTH as f64).ceil() as usize;
+        let bar = format!(
+            "{}{}",
+            "▰".repeat(filled),
+            "▱".repeat(BAR_WIDTH.saturating_sub(filled))
+        );
+
+        Some(if self.is_hyped() {
+            Span::styled(
+                format!(" HYPE {bar} x{} gold ", Self::GOLD_MULT),
+                Style::new().black().bg(tint).bold(),
+            )
+        } else {
+            Span::styled(format!(" HYPE {bar} "), Style::new().fg(tint))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::hype::Hype;
+
+    #[test]
+    fn hype_doubles_gold_past_threshold() {
+        assert!(Hype::new(0.).get_chip().is_none());
+        assert!((Hype::new(3.).get_gold_mult() - 1.).abs() < f64::EPSILON);
+        assert!(
+            (Hype::new(Hype::THRESHOLD).get_gold_mult() - Hype::GOLD_MULT).abs() < f64::EPSILON
+        );
+        assert!((Hype::new(100.).get_ratio() - 1.).abs() < f64::EPSILON);
+        assert_ne!(Hype::new(2.).get_tint(), Hype::new(14.).get_tint());
+    }
+}
@@ -0,0 +1,186 @@
+//! This module provides the movement intent, which turns direction key presses into steps taken
+//! on ticks, so movement speed depends on the run's stats rather than the terminal's key repeat
+//! rate.
+
+use crate::common::TICK_RATE;
+use crate::common::coords::Direction;
+use crate::prelude::Duration;
+
+/// The directions the player is trying to move in, sampled once per tick.
+///
+/// Where key releases are reported, a direction is held from its press until its release, and
+/// holding two keys at right angles moves diagonally, or steps between them for four way
+/// movement. Otherwise a direction counts as held for as long as key repeats keep arriving within
+/// [`MoveIntent::HOLD`] of each other.
+#[derive(Clone, Default)]
+pub struct MoveIntent {
+    /// The held directions, most recently pressed last.
+    held: Vec<Direction>,
+    /// The last tick the held direction is still held on without another press, when releases
+    /// aren't reported.
+    held_until: u64,
+    /// Whether the held direction has been pressed again since it was first pressed.
+    repeating: bool,
+    /// Whether releases have been seen, so held directions no longer need repeats.
+    reports_releases: bool,
+    /// A press still owed its step, so quick taps are never lost.
+    pending: Option<Direction>,
+    /// How many steps have been taken while holding keys, to alternate between them.
+    held_steps: usize,
+    /// The first tick the next step can be taken on.
+    next_step: u64,
+}
+
+impl MoveIntent {
+    /// How long a direction stays held after a press without another one, when releases aren't
+    /// reported.
+    pub const HOLD: Duration = Duration::from_millis(150);
+
+    /// Records a press of `direction` on `tickcount`.
+    pub fn press(&mut self, direction: Direction, tickcount: u64) {
+        if self.reports_releases {
+            // repeats of a key that's already held change nothing
+            if !self.held.contains(&direction) {
+                self.held.push(direction.clone());
+                self.pending = Some(direction);
+            }
+            return;
+        }
+
+        let hold_ticks = (Self::HOLD.as_secs_f64() * TICK_RATE).ceil() as u64;
+
+        if self.held.last() == Some(&direction) && tickcount <= self.held_until {
+            self.repeating = true;
+        } else {
+            self.held = vec![direction.clone()];
+            self.repeating = false;
+            self.pending = Some(direction);
+        }
+        self.held_until = tickcount + hold_ticks;
+    }
+
+    /// Records a release of `direction`. A tap released before its step still gets it.
+    pub fn release(&mut self, direction: &Direction) {
+        self.reports_releases = true;
+        self.held.retain(|held| held != direction);
+    }
+
+    /// Forgets any held direction, such as when the run is paused.
+    pub fn clear(&mut self) {
+        self.held.clear();
+        self.repeating = false;
+        self.pending = None;
+    }
+
+    /// Returns the direction to step in on `tickcount`, if any, taking steps at most every
+    /// `ticks_per_step` ticks. Held keys only combine into diagonals with `diagonal` set.
+    pub fn take_step(
+        &mut self,
+        tickcount: u64,
+        ticks_per_step: u64,
+        diagonal: bool,
+    ) -> Option<Direction> {
+        if tickcount < self.next_step {
+            return None;
+        }
+
+        let direction = if let Some(direction) = self.pending.take() {
+            direction
+        } else if self.reports_releases {
+            self.get_held_step(diagonal)?
+        } else if self.repeating && tickcount <= self.held_until {
+            self.held.last()?.clone()
+        } else {
+            self.clear();
+            return None;
+        };
+
+        self.next_step = tickcount + ticks_per_step.max(1);
+        Some(direction)
+    }
+
+    /// Returns the direction to step in while keys are held: the latest one, combined with the
+    /// one before it when the two are at right angles, or alternating with it without `diagonal`.
+    fn get_held_step(&mut self, diagonal: bool) -> Option<Direction> {
+        let (latest, rest) = self.held.split_last()?;
+
+        if diagonal
+            && let Some(combined) = rest.last().and_then(|previous| latest.combine(previous))
+        {
+            return Some(combined);
+        }
+
+        let step = match rest.last() {
+            Some(previous) if previous != &latest.opposite() => {
+                self.held_steps += 1;
+                if self.held_steps.is_multiple_of(2) {
+                    previous.clone()
+                } else {
+                    latest.clone()
+                }
+            }
+            _ => latest.clone(),
+        };
+
+        Some(step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::coords::Direction;
+    use crate::common::input::MoveIntent;
+
+    #[test]
+    fn taps_step_once_and_holds_step_at_the_tick_rate() {
+        let mut intent = MoveIntent::default();
+
+        intent.press(Direction::UP, 0);
+        assert_eq!(intent.take_step(1, 2, true), Some(Direction::UP));
+        assert_eq!(intent.take_step(3, 2, true), None);
+
+        // key repeats keep the direction held, but steps still wait on the step rate
+        let steps = (10..30)
+            .filter_map(|tick| {
+                intent.press(Direction::RIGHT, tick);
+                intent.take_step(tick, 4, true)
+            })
+            .count();
+        assert_eq!(steps, 5);
+
+        assert_eq!(intent.take_step(40, 4, true), None);
+    }
+
+    #[test]
+    fn released_keys_stay_held_until_let_go() {
+        let mut intent = MoveIntent::default();
+        intent.release(&Direction::UP);
+
+        intent.press(Direction::RIGHT, 0);
+        intent.press(Direction::DOWN, 0);
+        let steps: Vec<_> = (0..4)
+            .filter_map(|tick| intent.take_step(tick * 10, 1, false))
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                Direction::DOWN,
+                Direction::DOWN,
+                Direction::RIGHT,
+                Direction::DOWN
+            ]
+        );
+
+        assert_eq!(intent.take_step(50, 1, true), Some(Direction::DOWNRIGHT));
+
+        intent.release(&Direction::DOWN);
+        assert_eq!(intent.take_step(100, 1, true), Some(Direction::RIGHT));
+
+        // a tap let go of before the next tick still steps once
+        intent.release(&Direction::RIGHT);
+        intent.press(Direction::LEFT, 100);
+        intent.release(&Direction::LEFT);
+        assert_eq!(intent.take_step(101, 1, true), Some(Direction::LEFT));
+        assert_eq!(intent.take_step(102, 1, true), None);
+    }
+}
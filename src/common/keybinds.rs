@@ -0,0 +1,790 @@
+//! This module holds the keybinding map: which keys do what in each part of the game. The help
+//! overlay is built from it, and key presses are turned into actions with it, so it is the one
+//! place to update when a binding changes.
+
+use crate::common::autopilot::AutoPilot;
+use crate::common::capture::Capture;
+use crate::common::coords::Direction;
+use crate::common::perf::PerfStats;
+use crate::prelude::KeyCode;
+
+/// What the player wants to do, whichever key they pressed for it. Views handle these instead of
+/// keys, so a binding can change without touching them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    /// Moves the character, or the selection in a menu.
+    Up,
+    Down,
+    Left,
+    Right,
+    /// Aims the character, with manual aim on.
+    AimUp,
+    AimDown,
+    AimLeft,
+    AimRight,
+    /// Moves the second player in local co-op.
+    PartnerUp,
+    PartnerDown,
+    PartnerLeft,
+    PartnerRight,
+    Confirm,
+    Back,
+    Quit,
+    /// Flips between the two choices on offer.
+    Switch,
+    /// Moves the highlighted setting on to its next value.
+    Change,
+    /// Heads on towards starting a run.
+    Start,
+    /// Skips a powerup choice for gold.
+    Skip,
+    Reroll,
+    /// Plays the finished run again on the same map.
+    Retry,
+    Inspect,
+    AutoPilot,
+    BuyTen,
+    BuyMax,
+    Settings,
+    Collection,
+    Mutators,
+    Mastery,
+    Leaderboard,
+    Netplay,
+    /// Hosts a co-op room.
+    Host,
+    /// Deletes the last character typed.
+    Delete,
+    Refresh,
+    PerfOverlay,
+    Screenshot,
+    Clip,
+    Help,
+}
+
+impl Action {
+    /// Returns the direction of a move or aim, for either player.
+    #[must_use]
+    pub fn get_direction(self) -> Option<Direction> {
+        match self {
+            Action::Up | Action::AimUp | Action::PartnerUp => Some(Direction::UP),
+            Action::Down | Action::AimDown | Action::PartnerDown => Some(Direction::DOWN),
+            Action::Left | Action::AimLeft | Action::PartnerLeft => Some(Direction::LEFT),
+            Action::Right | Action::AimRight | Action::PartnerRight => Some(Direction::RIGHT),
+            _ => None,
+        }
+    }
+}
+
+/// The part of the game a set of keybindings applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    MainMenu,
+    Run,
+    /// A run with manual aim turned on in the settings.
+    ManualAimRun,
+    /// A run with a second player in local co-op.
+    CoopRun,
+    /// The tutorial run, which always uses the standard controls it teaches.
+    Tutorial,
+    PowerupPopup,
+    ChestPopup,
+    /// The offer to loop a run after surviving the timer.
+    LoopPopup,
+    /// The merchant met partway through a run.
+    MerchantPopup,
+    /// The live stats of the METHODs and CHARMs held in a run.
+    InspectPopup,
+    CarnageReport,
+    Upgrades,
+    ClassSelect,
+    Loadout,
+    Mutators,
+    Collection,
+    Mastery,
+    Settings,
+    /// The screen explaining something that went wrong, with ways to recover.
+    ErrorScreen,
+    /// The prompt to pick a save when the synced copy and this machine's are out of step.
+    SyncConflict,
+    /// The warning that holds back a new run while the last one isn't saved.
+    HeldStart,
+    #[cfg(feature = "leaderboard")]
+    Leaderboard,
+    #[cfg(feature = "netplay")]
+    Netplay,
+}
+
+/// One action, the keys bound to it and what it does here, for the help overlay.
+pub struct Keybind {
+    pub keys: Vec<KeyCode>,
+    pub action: Action,
+    pub desc: &'static str,
+}
+
+impl Keybind {
+    #[must_use]
+    pub fn new(keys: Vec<KeyCode>, action: Action, desc: &'static str) -> Self {
+        Self { keys, action, desc }
+    }
+
+    /// Returns whether `code` is one of the keys bound to this action.
+    #[must_use]
+    pub fn matches(&self, code: &KeyCode) -> bool {
+        self.keys.contains(code)
+    }
+
+    /// Returns the bound keys as they are shown to the player, e.g. `W|UP`.
+    #[must_use]
+    pub fn get_keys_label(&self) -> String {
+        self.keys
+            .iter()
+            .map(get_key_label)
+            .collect::<Vec<String>>()
+            .join("|")
+    }
+}
+
+impl KeyContext {
+    /// The key that opens and closes the help overlay, in every context.
+    pub const HELP_KEY: KeyCode = KeyCode::Char('?');
+
+    #[must_use]
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            KeyContext::MainMenu => "main menu",
+            KeyContext::Run | KeyContext::ManualAimRun | KeyContext::CoopRun => "run",
+            KeyContext::Tutorial => "tutorial",
+            KeyContext::PowerupPopup => "powerup",
+            KeyContext::ChestPopup => "chest",
+            KeyContext::LoopPopup => "loop",
+            KeyContext::MerchantPopup => "merchant",
+            KeyContext::InspectPopup => "inspect",
+            KeyContext::CarnageReport => "carnage report",
+            KeyContext::Upgrades => "upgrades",
+            KeyContext::ClassSelect => "class select",
+            KeyContext::Loadout => "loadout",
+            KeyContext::Mutators => "mutators",
+            KeyContext::Collection => "collection",
+            KeyContext::Mastery => "mastery",
+            KeyContext::Settings => "settings",
+            KeyContext::ErrorScreen => "error",
+            KeyContext::SyncConflict => "save conflict",
+            KeyContext::HeldStart => "not saved",
+            #[cfg(feature = "leaderboard")]
+            KeyContext::Leaderboard => "leaderboard",
+            #[cfg(feature = "netplay")]
+            KeyContext::Netplay => "co-op",
+        }
+    }
+
+    /// Returns the action `code` is bound to in this context, if it's bound to one. This is the
+    /// one place key presses become actions.
+    #[must_use]
+    pub fn get_action(&self, code: &KeyCode) -> Option<Action> {
+        self.get_keybinds()
+            .into_iter()
+            .find(|bind| bind.matches(code))
+            .map(|bind| bind.action)
+    }
+
+    /// Returns the first key bound to `action` in this context, if any is.
+    #[must_use]
+    pub fn get_key(&self, action: Action) -> Option<KeyCode> {
+        self.get_keybinds()
+            .into_iter()
+            .find(|bind| bind.action == action)
+            .and_then(|bind| bind.keys.into_iter().next())
+    }
+
+    /// Sums up this context's own keybindings on one line, e.g. `enter: select · esc: back`, for
+    /// screens that show their keys along their border.
+    #[must_use]
+    pub fn get_hint(&self) -> String {
+        self.get_own_keybinds()
+            .iter()
+            .filter_map(|bind| {
+                let key = bind.keys.first()?;
+                Some(format!(
+                    "{}: {}",
+                    get_key_label(key).to_lowercase(),
+                    bind.desc
+                ))
+            })
+            .collect::<Vec<String>>()
+            .join(" · ")
+    }
+
+    /// Returns every keybinding for this context.
+    #[must_use]
+    pub fn get_keybinds(&self) -> Vec<Keybind> {
+        let mut keybinds = self.get_own_keybinds();
+
+        // the screens shown over the main menu have nothing to capture
+        if !matches!(
+            self,
+            KeyContext::MainMenu
+                | KeyContext::ErrorScreen
+                | KeyContext::SyncConflict
+                | KeyContext::HeldStart
+        ) {
+            keybinds.push(Keybind::new(
+                vec![PerfStats::TOGGLE_KEY],
+                Action::PerfOverlay,
+                "toggle the performance overlay",
+            ));
+            keybinds.push(Keybind::new(
+                vec![Capture::SCREENSHOT_KEY],
+                Action::Screenshot,
+                "save a screenshot",
+            ));
+            keybinds.push(Keybind::new(
+                vec![Capture::CLIP_KEY],
+                Action::Clip,
+                "save a GIF clip (if recording)",
+            ));
+        }
+        keybinds.push(Keybind::new(
+            vec![Self::HELP_KEY],
+            Action::Help,
+            "toggle this help",
+        ));
+
+        keybinds
+    }
+
+    /// Returns the keybindings particular to this context, without the ones shared by all of
+    /// them.
+    #[allow(clippy::too_many_lines)]
+    fn get_own_keybinds(self) -> Vec<Keybind> {
+        match self {
+            KeyContext::MainMenu => {
+                #[cfg_attr(target_family = "wasm", allow(unused_mut))]
+                let mut keybinds = vec![
+                    Keybind::new(vec![KeyCode::Char('w'), KeyCode::Up], Action::Up, "move up"),
+                    Keybind::new(
+                        vec![KeyCode::Char('s'), KeyCode::Down],
+                        Action::Down,
+                        "move down",
+                    ),
+                    Keybind::new(vec![KeyCode::Enter], Action::Confirm, "select"),
+                ];
+                #[cfg(not(target_family = "wasm"))]
+                keybinds.push(Keybind::new(vec![KeyCode::Esc], Action::Quit, "quit"));
+                keybinds
+            }
+            KeyContext::Run => {
+                let mut keybinds = get_movement_keybinds();
+                keybinds.extend([
+                    Keybind::new(
+                        vec![KeyCode::Char('i'), KeyCode::Tab],
+                        Action::Inspect,
+                        "inspect METHODs and CHARMs",
+                    ),
+                    Keybind::new(
+                        vec![AutoPilot::TOGGLE_KEY],
+                        Action::AutoPilot,
+                        "autopilot, once unlocked",
+                    ),
+                    Keybind::new(vec![KeyCode::Esc], Action::Back, "end the run"),
+                ]);
+                keybinds
+            }
+            KeyContext::ManualAimRun => vec![
+                Keybind::new(vec![KeyCode::Char('w')], Action::Up, "move up"),
+                Keybind::new(vec![KeyCode::Char('a')], Action::Left, "move left"),
+                Keybind::new(vec![KeyCode::Char('s')], Action::Down, "move down"),
+                Keybind::new(vec![KeyCode::Char('d')], Action::Right, "move right"),
+                Keybind::new(
+                    vec![KeyCode::Char('i'), KeyCode::Up],
+                    Action::AimUp,
+                    "aim up",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Char('j'), KeyCode::Left],
+                    Action::AimLeft,
+                    "aim left",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Char('k'), KeyCode::Down],
+                    Action::AimDown,
+                    "aim down",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Char('l'), KeyCode::Right],
+                    Action::AimRight,
+                    "aim right",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Tab],
+                    Action::Inspect,
+                    "inspect METHODs and CHARMs",
+                ),
+                Keybind::new(
+                    vec![AutoPilot::TOGGLE_KEY],
+                    Action::AutoPilot,
+                    "autopilot, once unlocked",
+                ),
+                Keybind::new(vec![KeyCode::Esc], Action::Back, "end the run"),
+            ],
+            KeyContext::CoopRun => vec![
+                Keybind::new(vec![KeyCode::Char('w')], Action::Up, "player 1 up"),
+                Keybind::new(vec![KeyCode::Char('a')], Action::Left, "player 1 left"),
+                Keybind::new(vec![KeyCode::Char('s')], Action::Down, "player 1 down"),
+                Keybind::new(vec![KeyCode::Char('d')], Action::Right, "player 1 right"),
+                Keybind::new(vec![KeyCode::Up], Action::PartnerUp, "player 2 up"),
+                Keybind::new(vec![KeyCode::Left], Action::PartnerLeft, "player 2 left"),
+                Keybind::new(vec![KeyCode::Down], Action::PartnerDown, "player 2 down"),
+                Keybind::new(vec![KeyCode::Right], Action::PartnerRight, "player 2 right"),
+                Keybind::new(
+                    vec![KeyCode::Tab],
+                    Action::Inspect,
+                    "inspect METHODs and CHARMs",
+                ),
+                Keybind::new(
+                    vec![AutoPilot::TOGGLE_KEY],
+                    Action::AutoPilot,
+                    "autopilot, once unlocked",
+                ),
+                Keybind::new(vec![KeyCode::Esc], Action::Back, "end the run"),
+            ],
+            KeyContext::Tutorial => {
+                let mut keybinds = get_movement_keybinds();
+                keybinds.extend([
+                    Keybind::new(
+                        vec![KeyCode::Char('i'), KeyCode::Tab],
+                        Action::Inspect,
+                        "inspect METHODs and CHARMs",
+                    ),
+                    Keybind::new(
+                        vec![KeyCode::Enter],
+                        Action::Confirm,
+                        "head to the upgrades, once done",
+                    ),
+                    Keybind::new(vec![KeyCode::Esc], Action::Back, "skip the tutorial"),
+                ]);
+                keybinds
+            }
+            KeyContext::PowerupPopup => vec![
+                Keybind::new(
+                    vec![KeyCode::Char('a'), KeyCode::Left],
+                    Action::Left,
+                    "previous choice",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Char('d'), KeyCode::Right],
+                    Action::Right,
+                    "next choice",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Enter, KeyCode::Char(' ')],
+                    Action::Confirm,
+                    "choose",
+                ),
+                Keybind::new(vec![KeyCode::Char('x')], Action::Skip, "skip for gold"),
+                Keybind::new(
+                    vec![KeyCode::Char('r')],
+                    Action::Reroll,
+                    "reroll the choices",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Esc, KeyCode::Backspace],
+                    Action::Back,
+                    "cancel a replacement",
+                ),
+            ],
+            KeyContext::ChestPopup => vec![Keybind::new(
+                vec![KeyCode::Enter, KeyCode::Char(' ')],
+                Action::Confirm,
+                "take rewards",
+            )],
+            KeyContext::LoopPopup => vec![
+                Keybind::new(
+                    vec![
+                        KeyCode::Char('a'),
+                        KeyCode::Char('d'),
+                        KeyCode::Left,
+                        KeyCode::Right,
+                    ],
+                    Action::Switch,
+                    "switch between looping and ending",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Enter, KeyCode::Char(' ')],
+                    Action::Confirm,
+                    "confirm",
+                ),
+                Keybind::new(vec![KeyCode::Esc], Action::Back, "end the run"),
+            ],
+            KeyContext::MerchantPopup => vec![
+                Keybind::new(
+                    vec![KeyCode::Char('w'), KeyCode::Up],
+                    Action::Up,
+                    "previous ware",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Char('s'), KeyCode::Down],
+                    Action::Down,
+                    "next ware",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Enter, KeyCode::Char(' ')],
+                    Action::Confirm,
+                    "buy",
+                ),
+                Keybind::new(vec![KeyCode::Esc], Action::Back, "leave"),
+            ],
+            KeyContext::InspectPopup => vec![
+                Keybind::new(
+                    vec![KeyCode::Char('w'), KeyCode::Up],
+                    Action::Up,
+                    "scroll up",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Char('s'), KeyCode::Down],
+                    Action::Down,
+                    "scroll down",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Char('i'), KeyCode::Tab, KeyCode::Esc],
+                    Action::Back,
+                    "close",
+                ),
+            ],
+            KeyContext::CarnageReport => vec![
+                Keybind::new(
+                    vec![KeyCode::Char('w'), KeyCode::Up],
+                    Action::Up,
+                    "scroll up",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Char('s'), KeyCode::Down],
+                    Action::Down,
+                    "scroll down",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Char('a'), KeyCode::Left],
+                    Action::Left,
+                    "previous option",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Char('d'), KeyCode::Right],
+                    Action::Right,
+                    "next option",
+                ),
+                Keybind::new(vec![KeyCode::Enter], Action::Confirm, "confirm"),
+                Keybind::new(
+                    vec![KeyCode::Char('r')],
+                    Action::Retry,
+                    "retry the same map",
+                ),
+                Keybind::new(vec![KeyCode::Esc], Action::Back, "go to upgrades"),
+            ],
+            KeyContext::Upgrades => {
+                let mut keybinds = get_list_keybinds();
+                keybinds.extend([
+                    Keybind::new(
+                        vec![KeyCode::Enter],
+                        Action::Confirm,
+                        "buy, or open a group",
+                    ),
+                    Keybind::new(vec![KeyCode::Char('t')], Action::BuyTen, "buy 10"),
+                    Keybind::new(
+                        vec![KeyCode::Char('m')],
+                        Action::BuyMax,
+                        "buy as many as you can",
+                    ),
+                    Keybind::new(
+                        vec![KeyCode::Char(' ')],
+                        Action::Start,
+                        "pick a class and start",
+                    ),
+                    Keybind::new(vec![KeyCode::Char('o')], Action::Settings, "settings"),
+                    Keybind::new(vec![KeyCode::Char('c')], Action::Collection, "collection"),
+                ]);
+                #[cfg(feature = "leaderboard")]
+                keybinds.push(Keybind::new(
+                    vec![KeyCode::Char('l')],
+                    Action::Leaderboard,
+                    "leaderboard",
+                ));
+                #[cfg(feature = "netplay")]
+                keybinds.push(Keybind::new(
+                    vec![KeyCode::Char('n')],
+                    Action::Netplay,
+                    "online co-op",
+                ));
+                keybinds.push(Keybind::new(vec![KeyCode::Esc], Action::Back, "back"));
+                keybinds
+            }
+            KeyContext::ClassSelect => {
+                let mut keybinds = get_list_keybinds();
+                keybinds.extend([
+                    Keybind::new(
+                        vec![KeyCode::Enter, KeyCode::Char(' ')],
+                        Action::Confirm,
+                        "pick a loadout",
+                    ),
+                    Keybind::new(vec![KeyCode::Char('m')], Action::Mutators, "mutators"),
+                    Keybind::new(vec![KeyCode::Esc], Action::Back, "back"),
+                ]);
+                keybinds
+            }
+            KeyContext::Loadout => {
+                let mut keybinds = get_list_keybinds();
+                keybinds.extend([
+                    Keybind::new(
+                        vec![KeyCode::Char('a'), KeyCode::Left],
+                        Action::Left,
+                        "pick a METHOD",
+                    ),
+                    Keybind::new(
+                        vec![KeyCode::Char('d'), KeyCode::Right],
+                        Action::Right,
+                        "pick a CHARM",
+                    ),
+                    Keybind::new(
+                        vec![KeyCode::Enter, KeyCode::Char(' ')],
+                        Action::Confirm,
+                        "start the run",
+                    ),
+                    Keybind::new(vec![KeyCode::Char('m')], Action::Mutators, "mutators"),
+                    Keybind::new(vec![KeyCode::Esc], Action::Back, "back"),
+                ]);
+                keybinds
+            }
+            KeyContext::Mutators => {
+                let mut keybinds = get_list_keybinds();
+                keybinds.extend([
+                    Keybind::new(vec![KeyCode::Enter], Action::Confirm, "toggle"),
+                    Keybind::new(vec![KeyCode::Char(' ')], Action::Start, "start the run"),
+                    Keybind::new(vec![KeyCode::Esc], Action::Back, "back"),
+                ]);
+                keybinds
+            }
+            KeyContext::Collection => {
+                let mut keybinds = get_list_keybinds();
+                keybinds.extend([
+                    Keybind::new(vec![KeyCode::Char('m')], Action::Mastery, "METHOD mastery"),
+                    Keybind::new(vec![KeyCode::Esc], Action::Back, "back"),
+                ]);
+                keybinds
+            }
+            KeyContext::Mastery => {
+                let mut keybinds = get_list_keybinds();
+                keybinds.push(Keybind::new(
+                    vec![KeyCode::Esc],
+                    Action::Back,
+                    "back to the collection",
+                ));
+                keybinds
+            }
+            KeyContext::Settings => {
+                let mut keybinds = get_list_keybinds();
+                keybinds.extend([
+                    Keybind::new(
+                        vec![
+                            KeyCode::Enter,
+                            KeyCode::Char(' '),
+                            KeyCode::Char('a'),
+                            KeyCode::Char('d'),
+                            KeyCode::Left,
+                            KeyCode::Right,
+                        ],
+                        Action::Change,
+                        "change",
+                    ),
+                    Keybind::new(vec![KeyCode::Esc], Action::Back, "back"),
+                ]);
+                keybinds
+            }
+            KeyContext::ErrorScreen => {
+                let mut keybinds = get_list_keybinds();
+                keybinds.push(Keybind::new(
+                    vec![KeyCode::Enter],
+                    Action::Confirm,
+                    "choose",
+                ));
+                keybinds
+            }
+            KeyContext::SyncConflict => {
+                let mut keybinds = get_list_keybinds();
+                keybinds.push(Keybind::new(
+                    vec![KeyCode::Enter],
+                    Action::Confirm,
+                    "keep the highlighted save",
+                ));
+                keybinds
+            }
+            KeyContext::HeldStart => vec![
+                Keybind::new(vec![KeyCode::Enter], Action::Confirm, "start anyway"),
+                Keybind::new(vec![KeyCode::Esc], Action::Back, "back"),
+            ],
+            #[cfg(feature = "leaderboard")]
+            KeyContext::Leaderboard => vec![
+                Keybind::new(vec![KeyCode::Char('r')], Action::Refresh, "refresh"),
+                Keybind::new(vec![KeyCode::Esc], Action::Back, "back"),
+            ],
+            #[cfg(feature = "netplay")]
+            KeyContext::Netplay => vec![
+                Keybind::new(vec![KeyCode::Char('h')], Action::Host, "host a room"),
+                Keybind::new(
+                    vec![KeyCode::Enter],
+                    Action::Confirm,
+                    "join the room whose code was typed",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Backspace],
+                    Action::Delete,
+                    "delete a digit of the code",
+                ),
+                Keybind::new(
+                    vec![KeyCode::Esc],
+                    Action::Back,
+                    "leave the room, or go back",
+                ),
+            ],
+        }
+    }
+
+    /// Returns a short description of each part of the HUD shown in this context, if any.
+    #[must_use]
+    pub fn get_hud_notes(&self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            KeyContext::Run | KeyContext::ManualAimRun | KeyContext::Tutorial => &[
+                (
+                    "Phase",
+                    "how hard the run is right now, and when it changes",
+                ),
+                ("Health", "you lose when this runs out"),
+                ("Time", "seconds left until the run ends"),
+                ("Gold", "spent on upgrades between runs"),
+                ("lv", "experience towards the next powerup"),
+                ("shield", "soaks damage and regenerates"),
+                ("stats", "your METHODs, charms and the enemies' strength"),
+                ("log", "kills, health and choices as text, if turned on"),
+            ],
+            KeyContext::CoopRun => &[
+                (
+                    "Phase",
+                    "how hard the run is right now, and when it changes",
+                ),
+                ("Health", "player 1's health"),
+                ("P2", "player 2's health, the run ends when both are down"),
+                ("Time", "seconds left until the run ends"),
+                ("Gold", "spent on upgrades between runs"),
+                ("lv", "experience towards the next powerup"),
+                ("shield", "soaks damage and regenerates"),
+                ("stats", "your METHODs, charms and the enemies' strength"),
+                ("log", "kills, health and choices as text, if turned on"),
+            ],
+            KeyContext::Upgrades => &[("cost", "gold needed for the next level")],
+            _ => &[],
+        }
+    }
+}
+
+/// Returns the bindings for walking around with either WASD or the arrow keys.
+fn get_movement_keybinds() -> Vec<Keybind> {
+    vec![
+        Keybind::new(vec![KeyCode::Char('w'), KeyCode::Up], Action::Up, "move up"),
+        Keybind::new(
+            vec![KeyCode::Char('a'), KeyCode::Left],
+            Action::Left,
+            "move left",
+        ),
+        Keybind::new(
+            vec![KeyCode::Char('s'), KeyCode::Down],
+            Action::Down,
+            "move down",
+        ),
+        Keybind::new(
+            vec![KeyCode::Char('d'), KeyCode::Right],
+            Action::Right,
+            "move right",
+        ),
+    ]
+}
+
+/// Returns the bindings for moving up and down a list, shared by the menus.
+fn get_list_keybinds() -> Vec<Keybind> {
+    vec![
+        Keybind::new(vec![KeyCode::Char('w'), KeyCode::Up], Action::Up, "move up"),
+        Keybind::new(
+            vec![KeyCode::Char('s'), KeyCode::Down],
+            Action::Down,
+            "move down",
+        ),
+    ]
+}
+
+/// Returns how a key is shown to the player.
+#[must_use]
+pub fn get_key_label(code: &KeyCode) -> String {
+    match code {
+        KeyCode::Char(' ') => "SPACE".to_string(),
+        KeyCode::Char(c) => c.to_uppercase().to_string(),
+        KeyCode::Up => "UP".to_string(),
+        KeyCode::Down => "DOWN".to_string(),
+        KeyCode::Left => "LEFT".to_string(),
+        KeyCode::Right => "RIGHT".to_string(),
+        KeyCode::Enter => "ENTER".to_string(),
+        KeyCode::Esc => "ESC".to_string(),
+        KeyCode::Backspace => "BACKSPACE".to_string(),
+        KeyCode::F(number) => format!("F{number}"),
+        other => format!("{other:?}").to_uppercase(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::keybinds::{Action, KeyContext, get_key_label};
+    use crate::prelude::KeyCode;
+
+    #[test]
+    fn every_context_lists_help() {
+        let run = KeyContext::Run.get_keybinds();
+
+        assert!(run.iter().any(|bind| bind.matches(&KeyContext::HELP_KEY)));
+        assert_eq!(run[0].get_keys_label(), "W|UP");
+        assert_eq!(get_key_label(&KeyCode::Char(' ')), "SPACE");
+    }
+
+    #[test]
+    fn keys_map_to_the_context_s_action() {
+        assert_eq!(KeyContext::Run.get_action(&KeyCode::Up), Some(Action::Up));
+        assert_eq!(
+            KeyContext::ManualAimRun.get_action(&KeyCode::Up),
+            Some(Action::AimUp)
+        );
+        assert_eq!(
+            KeyContext::CoopRun.get_action(&KeyCode::Up),
+            Some(Action::PartnerUp)
+        );
+        assert_eq!(
+            KeyContext::Upgrades.get_action(&KeyCode::Char('m')),
+            Some(Action::BuyMax)
+        );
+        assert_eq!(
+            KeyContext::ErrorScreen.get_action(&KeyCode::Char('s')),
+            Some(Action::Down)
+        );
+        assert_eq!(
+            KeyContext::Collection.get_action(&KeyCode::Char('m')),
+            Some(Action::Mastery)
+        );
+        assert_eq!(
+            KeyContext::MainMenu.get_action(&KeyContext::HELP_KEY),
+            Some(Action::Help)
+        );
+        assert_eq!(KeyContext::ChestPopup.get_action(&KeyCode::Esc), None);
+        assert_eq!(
+            KeyContext::HeldStart.get_key(Action::Back),
+            Some(KeyCode::Esc)
+        );
+        assert_eq!(
+            KeyContext::HeldStart.get_hint(),
+            "enter: start anyway · esc: back"
+        );
+    }
+}
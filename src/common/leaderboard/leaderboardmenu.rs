@@ -0,0 +1,132 @@
+//! This module provides the leaderboard screen, reachable from the upgrades menu.
+
+use ratatui::{
+    Frame,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Paragraph},
+};
+
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::leaderboard::{LeaderboardClient, LeaderboardStatus};
+use crate::common::{Goto, View};
+
+/// A struct that manages the state and rendering of the leaderboard screen.
+pub struct LeaderboardMenu {
+    client: Option<LeaderboardClient>,
+    pub goto: Goto,
+}
+
+impl LeaderboardMenu {
+    /// Creates a new `LeaderboardMenu` and starts fetching the top entries.
+    #[must_use]
+    pub fn new() -> Self {
+        let client = LeaderboardClient::from_env();
+        if let Some(client) = &client {
+            client.fetch_top();
+        }
+
+        Self {
+            client,
+            goto: Goto::Leaderboard,
+        }
+    }
+
+    /// Handles actions for the leaderboard menu.
+    pub fn on_action(&mut self, action: Action) {
+        match action {
+            Action::Refresh => {
+                if let Some(client) = &self.client {
+                    client.fetch_top();
+                }
+            }
+            Action::Back => self.goto = Goto::Upgrades,
+            _ => {}
+        }
+    }
+
+    /// Builds the lines of the leaderboard for the current status.
+    #[must_use]
+    pub fn get_lines(&self) -> Vec<Line<'static>> {
+        let Some(client) = &self.client else {
+            return vec![Line::from("no leaderboard configured".dark_gray())];
+        };
+
+        match client.get_status() {
+            LeaderboardStatus::Idle | LeaderboardStatus::Loading => {
+                vec![Line::from("loading...".dark_gray())]
+            }
+            LeaderboardStatus::Failed(err) => vec![
+                Line::from("couldn't reach the leaderboard".red()),
+                Line::from(err.dark_gray()),
+            ],
+            LeaderboardStatus::Loaded(entries) if entries.is_empty() => {
+                vec![Line::from("no runs yet".dark_gray())]
+            }
+            LeaderboardStatus::Loaded(entries) => {
+                let mut lines = vec![
+                    Line::from(format!(
+                        "{:>3}  {:>6}  {:>6}  {:<10}  build",
+                        "#", "time", "kills", "class"
+                    ))
+                    .bold(),
+                ];
+
+                lines.extend(entries.iter().enumerate().map(|(i, entry)| {
+                    Line::from(format!(
+                        "{:>3}  {:>5}s  {:>6}  {:<10}  {}",
+                        i + 1,
+                        entry.survival_secs,
+                        entry.kills,
+                        entry.class,
+                        entry.build.join(", ")
+                    ))
+                }));
+
+                lines
+            }
+        }
+    }
+
+    /// Renders the leaderboard to the frame.
+    pub fn render_leaderboard(&self, frame: &mut Frame) {
+        let title = Line::from(" dispair.leaderboard ".bold());
+        let instructions = Line::from(vec![" <R> Refresh | <Esc> Back ".into()]);
+
+        let window = Block::bordered()
+            .border_set(border::THICK)
+            .title(title.left_aligned())
+            .title_bottom(instructions.left_aligned());
+
+        let leaderboard = Paragraph::new(self.get_lines()).block(window);
+
+        frame.render_widget(leaderboard, frame.area());
+    }
+}
+
+impl Default for LeaderboardMenu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl View for LeaderboardMenu {
+    fn on_tick(&mut self) {}
+
+    fn transition(&self) -> &Goto {
+        &self.goto
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.render_leaderboard(frame);
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        self.on_action(action);
+    }
+
+    fn get_key_context(&self) -> KeyContext {
+        KeyContext::Leaderboard
+    }
+}
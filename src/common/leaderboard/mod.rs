@@ -0,0 +1,218 @@
+//! This module provides the optional online leaderboard. Finished runs are sent as signed
+//! summaries to a configurable HTTP endpoint, and the top entries can be fetched back for the
+//! leaderboard screen.
+//!
+//! The endpoint and signing key are read from `DISPAIR_LEADERBOARD_URL` and
+//! `DISPAIR_LEADERBOARD_KEY`, at build time or, on the terminal, at run time.
+
+pub mod leaderboardmenu;
+
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+
+use crate::prelude::send_request;
+
+/// A finished run, as submitted to the leaderboard.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct RunSummary {
+    pub seed: u64,
+    pub survival_secs: u64,
+    pub kills: u64,
+    pub class: String,
+    /// Each METHOD and charm held at the end of the run, with its level.
+    pub build: Vec<String>,
+}
+
+/// A `RunSummary` with a signature proving it came from a build that knows the key.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignedRunSummary {
+    #[serde(flatten)]
+    pub summary: RunSummary,
+    pub signature: String,
+}
+
+impl RunSummary {
+    /// Signs the serialized summary with HMAC-SHA256, returning the signature as hex.
+    ///
+    /// # Panics
+    ///
+    /// Will not panic, as HMAC accepts keys of any length.
+    #[must_use]
+    pub fn sign(&self, key: &str) -> String {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key.as_bytes())
+            .expect("HMAC accepts keys of any length");
+        mac.update(serde_json::to_string(self).unwrap_or_default().as_bytes());
+
+        mac.finalize()
+            .into_bytes()
+            .iter()
+            .fold(String::new(), |mut hex, byte| {
+                let _ = write!(hex, "{byte:02x}");
+                hex
+            })
+    }
+
+    #[must_use]
+    pub fn signed(self, key: &str) -> SignedRunSummary {
+        let signature = self.sign(key);
+
+        SignedRunSummary {
+            summary: self,
+            signature,
+        }
+    }
+}
+
+/// Where the leaderboard lives and the key runs are signed with.
+#[derive(Debug, Clone)]
+pub struct LeaderboardConfig {
+    pub endpoint: String,
+    pub key: String,
+}
+
+impl LeaderboardConfig {
+    /// Reads the configuration from the environment, if an endpoint has been set.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        #[cfg(not(target_family = "wasm"))]
+        let runtime = (
+            std::env::var("DISPAIR_LEADERBOARD_URL").ok(),
+            std::env::var("DISPAIR_LEADERBOARD_KEY").ok(),
+        );
+        #[cfg(target_family = "wasm")]
+        let runtime: (Option<String>, Option<String>) = (None, None);
+
+        let endpoint = runtime
+            .0
+            .or_else(|| option_env!("DISPAIR_LEADERBOARD_URL").map(String::from))?;
+        let key = runtime
+            .1
+            .or_else(|| option_env!("DISPAIR_LEADERBOARD_KEY").map(String::from))
+            .unwrap_or_default();
+
+        Some(Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            key,
+        })
+    }
+}
+
+/// The state of the latest request for the top entries.
+#[derive(Debug, Clone, Default)]
+pub enum LeaderboardStatus {
+    #[default]
+    Idle,
+    Loading,
+    Loaded(Vec<RunSummary>),
+    Failed(String),
+}
+
+/// Submits runs to, and fetches entries from, the leaderboard. Requests run in the background;
+/// their results land in `status`.
+#[derive(Debug, Clone)]
+pub struct LeaderboardClient {
+    config: LeaderboardConfig,
+    status: Arc<Mutex<LeaderboardStatus>>,
+}
+
+impl LeaderboardClient {
+    /// Creates a client for the leaderboard configured in the environment, if there is one.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        LeaderboardConfig::from_env().map(Self::new)
+    }
+
+    #[must_use]
+    pub fn new(config: LeaderboardConfig) -> Self {
+        Self {
+            config,
+            status: Arc::new(Mutex::new(LeaderboardStatus::Idle)),
+        }
+    }
+
+    /// Signs and submits a finished run. Failures are only logged, as there is nothing to retry
+    /// with.
+    pub fn submit(&self, summary: RunSummary) {
+        let body = serde_json::to_string(&summary.signed(&self.config.key)).unwrap_or_default();
+
+        send_request(
+            "POST",
+            format!("{}/runs", self.config.endpoint),
+            Some(body),
+            Box::new(|response| {
+                if let Err(err) = response {
+                    tracing::warn!(%err, "failed to submit run to the leaderboard");
+                }
+            }),
+        );
+    }
+
+    /// Starts fetching the top entries.
+    pub fn fetch_top(&self) {
+        self.set_status(LeaderboardStatus::Loading);
+
+        let status = self.status.clone();
+        send_request(
+            "GET",
+            format!("{}/top", self.config.endpoint),
+            None,
+            Box::new(move |response| {
+                let new_status = response
+                    .and_then(|body| {
+                        serde_json::from_str::<Vec<RunSummary>>(&body).map_err(|e| e.to_string())
+                    })
+                    .inspect_err(|err| tracing::warn!(%err, "failed to fetch the leaderboard"))
+                    .map_or_else(LeaderboardStatus::Failed, LeaderboardStatus::Loaded);
+
+                if let Ok(mut status) = status.lock() {
+                    *status = new_status;
+                }
+            }),
+        );
+    }
+
+    #[must_use]
+    pub fn get_status(&self) -> LeaderboardStatus {
+        self.status
+            .lock()
+            .map(|status| status.clone())
+            .unwrap_or_default()
+    }
+
+    fn set_status(&self, new_status: LeaderboardStatus) {
+        if let Ok(mut status) = self.status.lock() {
+            *status = new_status;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::leaderboard::RunSummary;
+
+    #[test]
+    fn signature_covers_summary() {
+        let summary = RunSummary {
+            seed: 7,
+            survival_secs: 120,
+            kills: 300,
+            class: "WANDERER".into(),
+            build: vec!["FLASH 3".into()],
+        };
+
+        let signature = summary.sign("key");
+        assert_eq!(signature.len(), 64);
+        assert_eq!(signature, summary.sign("key"));
+        assert_ne!(signature, summary.sign("other key"));
+
+        let tampered = RunSummary {
+            kills: 301,
+            ..summary
+        };
+        assert_ne!(signature, tampered.sign("key"));
+    }
+}
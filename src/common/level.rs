@@ -1,3 +1,6 @@
+//! This module handles in-run experience and levelling.
+//! Each level-up grants the player a powerup, with any overflow XP carried into the next level.
+
 #[allow(clippy::struct_field_names)]
 pub struct Level {
     xp: u128,
@@ -10,38 +13,48 @@ impl Default for Level {
         Level {
             xp: 0,
             level: 0,
-            xp_to_level: 100,
+            xp_to_level: Self::xp_for_level(0),
         }
     }
 }
 
 impl Level {
+    const BASE_XP: f64 = 100.;
     const SCALE_MULT: f64 = 1.5;
 
     #[must_use]
     pub fn new() -> Self {
-        Level {
-            xp: 0,
-            level: 0,
-            xp_to_level: 100,
-        }
+        Self::default()
+    }
+
+    /// Returns the XP needed to advance from `level` to the next level.
+    #[must_use]
+    pub fn xp_for_level(level: i32) -> u128 {
+        (Self::BASE_XP * Self::SCALE_MULT.powi(level)).ceil() as u128
     }
 
     pub fn add_xp(&mut self, xp: u128) {
         self.xp += xp;
     }
 
+    /// Advances at most one level if enough XP has been gained, carrying over any excess XP.
+    /// Returns the new level if a level-up occurred.
     pub fn update(&mut self) -> Option<i32> {
         if self.xp >= self.xp_to_level {
+            self.xp -= self.xp_to_level;
             self.level += 1;
-            self.xp = 0;
-            self.xp_to_level = (self.xp_to_level as f64 * Self::SCALE_MULT).ceil() as u128;
+            self.xp_to_level = Self::xp_for_level(self.level);
             Some(self.level)
         } else {
             None
         }
     }
 
+    #[must_use]
+    pub fn get_level(&self) -> i32 {
+        self.level
+    }
+
     #[must_use]
     pub fn get_progress_percentage(&self) -> u16 {
         (self.xp as f64 / self.xp_to_level as f64 * 100.0)
@@ -49,3 +62,20 @@ impl Level {
             .min(100.) as u16
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::common::level::Level;
+
+    #[test]
+    fn overflow_xp_carries_over() {
+        let mut level = Level::new();
+        level.add_xp(Level::xp_for_level(0) + Level::xp_for_level(1) + 10);
+
+        assert_eq!(level.update(), Some(1));
+        assert_eq!(level.update(), Some(2));
+        assert_eq!(level.update(), None);
+        assert_eq!(level.get_level(), 2);
+        assert!(level.get_progress_percentage() > 0);
+    }
+}
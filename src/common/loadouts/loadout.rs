@@ -0,0 +1,82 @@
+//! This module defines the loadout: the METHOD and CHARM the player picks to start their runs
+//! with, from the ones unlocked in the upgrade tree.
+
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+use crate::common::{charms::CharmWrapper, upgrades::upgrade::PlayerState, weapons::WeaponWrapper};
+
+/// The starting METHOD and CHARM picked for the next runs, stored by name.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Loadout {
+    /// The METHOD to start with in place of the class's own.
+    pub weapon: Option<String>,
+    /// A CHARM to start with.
+    pub charm: Option<String>,
+}
+
+impl Loadout {
+    /// Returns the picked METHOD, if it's still unlocked or a challenge being played lends it.
+    #[must_use]
+    pub fn get_weapon_type(&self, player_state: &PlayerState) -> Option<WeaponWrapper> {
+        self.weapon
+            .as_deref()
+            .and_then(|name| WeaponWrapper::from_str(name).ok())
+            .filter(|weapon| player_state.challenge.is_some() || weapon.is_unlocked(player_state))
+    }
+
+    /// Returns an empty `WeaponWrapper` for the METHOD a run starts with: the picked one, or the
+    /// class's own if none is picked or it's no longer unlocked.
+    #[must_use]
+    pub fn get_starting_weapon_type(&self, player_state: &PlayerState) -> WeaponWrapper {
+        self.get_weapon_type(player_state)
+            .unwrap_or_else(|| player_state.class.get_starting_weapon_type())
+    }
+
+    /// Returns an empty `CharmWrapper` for the CHARM a run starts with, if one is picked, still
+    /// unlocked or lent by a challenge, and the run allows charms at all.
+    #[must_use]
+    pub fn get_starting_charm_type(&self, player_state: &PlayerState) -> Option<CharmWrapper> {
+        if player_state.stats.game_stats.max_charms == 0 {
+            return None;
+        }
+
+        self.charm
+            .as_deref()
+            .and_then(|name| CharmWrapper::from_str(name).ok())
+            .filter(|charm| player_state.challenge.is_some() || charm.is_unlocked(player_state))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::loadouts::loadout::Loadout;
+    use crate::common::mutators::mutator::Mutator;
+    use crate::common::upgrades::upgrade::PlayerState;
+
+    #[test]
+    fn locked_picks_fall_back() {
+        let mut player_state = PlayerState::default();
+        let class_weapon: &'static str = player_state.class.get_starting_weapon_type().into();
+
+        let loadout = Loadout {
+            weapon: Some("NOVA".into()),
+            charm: Some("SHIELD CHARM".into()),
+        };
+        let weapon: &'static str = loadout.get_starting_weapon_type(&player_state).into();
+        assert_eq!(weapon, class_weapon);
+        assert!(loadout.get_starting_charm_type(&player_state).is_none());
+
+        let loadout = Loadout {
+            weapon: Some("Flash".into()),
+            charm: Some("Damage Multiplier Charm".into()),
+        };
+        assert!(loadout.get_weapon_type(&player_state).is_some());
+        assert!(loadout.get_starting_charm_type(&player_state).is_some());
+
+        player_state.mutators.push(Mutator::NoCharms);
+        player_state.refresh();
+        assert!(loadout.get_starting_charm_type(&player_state).is_none());
+    }
+}
@@ -0,0 +1,253 @@
+//! This module provides the pre-run screen for picking the METHOD and CHARM to start with.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+};
+use strum::IntoEnumIterator;
+
+use crate::common::charms::CharmWrapper;
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::loadouts::loadout::Loadout;
+use crate::common::weapons::WeaponWrapper;
+use crate::common::{Goto, PlayerStateRef, View};
+
+/// Which of the two lists is being picked from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LoadoutColumn {
+    Weapon,
+    Charm,
+}
+
+/// A struct that manages the state and rendering of the loadout screen.
+pub struct LoadoutMenu {
+    pub player_state: PlayerStateRef,
+    /// The unlocked METHODs, after `None` for keeping the class's own.
+    weapons: Vec<Option<&'static str>>,
+    /// The unlocked CHARMs, after `None` for starting without one.
+    charms: Vec<Option<&'static str>>,
+    pub weapon_selection: ListState,
+    pub charm_selection: ListState,
+    column: LoadoutColumn,
+    pub goto: Goto,
+}
+
+impl LoadoutMenu {
+    /// Creates a new `LoadoutMenu` with the player's current loadout selected.
+    #[must_use]
+    pub fn new(player_state: PlayerStateRef) -> Self {
+        let state = player_state.borrow();
+
+        let weapons: Vec<Option<&'static str>> = std::iter::once(None)
+            .chain(
+                WeaponWrapper::iter()
+                    .filter(|weapon| weapon.is_unlocked(&state))
+                    .map(|weapon| Some(weapon.into())),
+            )
+            .collect();
+        let charms: Vec<Option<&'static str>> = std::iter::once(None)
+            .chain(
+                CharmWrapper::iter()
+                    .filter(|charm| charm.is_unlocked(&state))
+                    .map(|charm| Some(charm.into())),
+            )
+            .collect();
+
+        let current_weapon = state.loadout.weapon.as_deref();
+        let current_charm = state.loadout.charm.as_deref();
+
+        let weapon_selection = ListState::default().with_selected(Some(
+            weapons
+                .iter()
+                .position(|weapon| *weapon == current_weapon)
+                .unwrap_or(0),
+        ));
+        let charm_selection = ListState::default().with_selected(Some(
+            charms
+                .iter()
+                .position(|charm| *charm == current_charm)
+                .unwrap_or(0),
+        ));
+
+        drop(state);
+
+        Self {
+            player_state,
+            weapons,
+            charms,
+            weapon_selection,
+            charm_selection,
+            column: LoadoutColumn::Weapon,
+            goto: Goto::Loadout,
+        }
+    }
+
+    /// Handles actions for the loadout menu.
+    pub fn on_action(&mut self, action: Action) {
+        let selection = match self.column {
+            LoadoutColumn::Weapon => &mut self.weapon_selection,
+            LoadoutColumn::Charm => &mut self.charm_selection,
+        };
+
+        match action {
+            Action::Up => selection.select_previous(),
+            Action::Down => selection.select_next(),
+            Action::Left => self.column = LoadoutColumn::Weapon,
+            Action::Right => self.column = LoadoutColumn::Charm,
+            Action::Confirm => self.confirm_loadout(),
+            Action::Mutators => {
+                self.player_state.borrow_mut().loadout = self.get_selected_loadout();
+                self.goto = Goto::Mutators;
+            }
+            Action::Back => self.goto = Goto::ClassSelect,
+            _ => {}
+        }
+    }
+
+    /// Returns the loadout made of the highlighted METHOD and CHARM.
+    #[must_use]
+    pub fn get_selected_loadout(&self) -> Loadout {
+        let picked = |names: &[Option<&'static str>], selection: &ListState| {
+            selection
+                .selected()
+                .and_then(|index| names.get(index).copied().flatten())
+                .map(String::from)
+        };
+
+        Loadout {
+            weapon: picked(&self.weapons, &self.weapon_selection),
+            charm: picked(&self.charms, &self.charm_selection),
+        }
+    }
+
+    /// Stores the highlighted loadout in the player's state and starts the run.
+    pub fn confirm_loadout(&mut self) {
+        self.player_state.borrow_mut().loadout = self.get_selected_loadout();
+        self.goto = Goto::Game;
+    }
+
+    /// Builds the list for one column, with the focused column's highlight stronger.
+    fn get_list(
+        &self,
+        title: &'static str,
+        items: Vec<ListItem<'static>>,
+        column: LoadoutColumn,
+    ) -> List<'static> {
+        let focused = self.column == column;
+
+        let block = Block::bordered()
+            .border_set(border::ROUNDED)
+            .title(if focused {
+                Line::from(title.bold())
+            } else {
+                Line::from(title.dark_gray())
+            });
+
+        List::new(items)
+            .block(block)
+            .highlight_style(if focused {
+                Style::new().bold()
+            } else {
+                Style::new().dark_gray()
+            })
+            .highlight_symbol(">")
+    }
+
+    /// Renders the loadout menu to the frame.
+    pub fn render_loadout(&mut self, frame: &mut Frame) {
+        let title = Line::from(" dispair.loadout ".bold());
+        let instructions = Line::from(vec![
+            " <W|S> Up/Down | <A|D> METHOD/CHARM | <ENTER|SPACE> Start Game | <M> Mutators | <Esc> Back "
+                .into(),
+        ]);
+
+        let window = Block::bordered()
+            .border_set(border::THICK)
+            .title(title.left_aligned())
+            .title_bottom(instructions.left_aligned());
+        let inner = window.inner(frame.area());
+
+        let [weapons_area, charms_area, summary_area] = Layout::horizontal([
+            Constraint::Percentage(30),
+            Constraint::Percentage(30),
+            Constraint::Fill(1),
+        ])
+        .areas(inner);
+
+        let player_state = self.player_state.borrow().clone();
+        let class_weapon: &'static str = player_state.class.get_starting_weapon_type().into();
+
+        let weapon_items: Vec<ListItem> = self
+            .weapons
+            .iter()
+            .map(|weapon| match weapon {
+                Some(name) => ListItem::from(format!(" {}", name.to_uppercase())),
+                None => ListItem::from(format!(" class ({})", class_weapon.to_uppercase())),
+            })
+            .collect();
+        let charm_items: Vec<ListItem> = self
+            .charms
+            .iter()
+            .map(|charm| ListItem::from(format!(" {}", charm.unwrap_or("none").to_uppercase())))
+            .collect();
+
+        let weapon_list = self.get_list(" METHOD ", weapon_items, LoadoutColumn::Weapon);
+        let charm_list = self.get_list(" CHARM ", charm_items, LoadoutColumn::Charm);
+
+        let loadout = self.get_selected_loadout();
+        let starting_weapon: &'static str = loadout.get_starting_weapon_type(&player_state).into();
+        let starting_charm = loadout.charm.as_deref().unwrap_or("none").to_uppercase();
+
+        let mut summary_lines = vec![
+            Line::from("starting METHOD".dark_gray()),
+            Line::from(starting_weapon.to_uppercase()),
+            Line::from(""),
+            Line::from("starting CHARM".dark_gray()),
+            Line::from(starting_charm),
+        ];
+
+        // mutators like NO CHARMS leave no room for the starting charm
+        if loadout.charm.is_some() && loadout.get_starting_charm_type(&player_state).is_none() {
+            summary_lines.push(Line::from("CHARMs are off for these runs".light_red()));
+        }
+
+        summary_lines.push(Line::from(""));
+        summary_lines.push(Line::from(
+            "unlock more METHODs and CHARMs in the upgrade tree".dark_gray(),
+        ));
+
+        let summary = Paragraph::new(summary_lines)
+            .block(Block::bordered().border_set(border::ROUNDED))
+            .centered()
+            .wrap(Wrap { trim: false });
+
+        frame.render_widget(window, frame.area());
+        frame.render_stateful_widget(weapon_list, weapons_area, &mut self.weapon_selection);
+        frame.render_stateful_widget(charm_list, charms_area, &mut self.charm_selection);
+        frame.render_widget(summary, summary_area);
+    }
+}
+
+impl View for LoadoutMenu {
+    fn on_tick(&mut self) {}
+
+    fn transition(&self) -> &Goto {
+        &self.goto
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.render_loadout(frame);
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        self.on_action(action);
+    }
+
+    fn get_key_context(&self) -> KeyContext {
+        KeyContext::Loadout
+    }
+}
@@ -0,0 +1,2 @@
+pub mod loadout;
+pub mod loadoutmenu;
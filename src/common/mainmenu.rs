@@ -0,0 +1,200 @@
+//! This module provides the main menu shown when the game starts, under the typed-out title. It
+//! only picks what to do, and the app starts or loads the game from the choice.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::Style,
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, List, ListItem, ListState},
+};
+
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::popups::helpoverlay::render_help;
+use crate::common::splash::{Splash, SplashArt};
+use crate::common::utils::{center_horizontal, center_vertical};
+use crate::prelude::{KeyCode, KeyEvent};
+
+/// An option on the main menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuChoice {
+    NewGame,
+    Continue,
+    Quit,
+}
+
+impl MenuChoice {
+    fn get_label(self) -> &'static str {
+        match self {
+            MenuChoice::NewGame => "New Game",
+            MenuChoice::Continue => "Continue",
+            MenuChoice::Quit => "Quit",
+        }
+    }
+}
+
+/// The main menu, with the options the platform offers.
+pub struct MainMenu {
+    choices: Vec<MenuChoice>,
+    selection: ListState,
+    /// Whether there's a save to continue from. Continue is greyed out and skipped without one.
+    pub save_exists: bool,
+    /// Whether the help overlay is open over the menu.
+    show_help: bool,
+    /// The title art, typed out when the game starts.
+    title: Splash,
+}
+
+impl MainMenu {
+    #[must_use]
+    pub fn new(choices: Vec<MenuChoice>) -> Self {
+        Self {
+            choices,
+            selection: ListState::default().with_selected(Some(0)),
+            save_exists: false,
+            show_help: false,
+            title: Splash::new(SplashArt::Title),
+        }
+    }
+
+    /// Handles a key press, returning the option picked if there was one. A key pressed while
+    /// the title is typing out finishes it instead.
+    pub fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<MenuChoice> {
+        let action = KeyContext::MainMenu.get_action(&key_event.code);
+
+        if !self.title.is_typed() {
+            self.title.skip();
+        } else if self.show_help {
+            if action == Some(Action::Help) || key_event.code == KeyCode::Esc {
+                self.show_help = false;
+            }
+        } else {
+            match action {
+                Some(Action::Help) => self.show_help = true,
+                Some(Action::Down) => self.select_next(),
+                Some(Action::Up) => self.select_prev(),
+                Some(Action::Confirm) => {
+                    return self
+                        .selection
+                        .selected()
+                        .and_then(|index| self.choices.get(index))
+                        .copied();
+                }
+                Some(Action::Quit) if self.choices.contains(&MenuChoice::Quit) => {
+                    return Some(MenuChoice::Quit);
+                }
+                _ => {}
+            }
+        }
+
+        None
+    }
+
+    fn is_available(&self, choice: MenuChoice) -> bool {
+        choice != MenuChoice::Continue || self.save_exists
+    }
+
+    fn select_next(&mut self) {
+        let current = self.selection.selected().unwrap_or(0);
+        let next =
+            (current + 1..self.choices.len()).find(|&index| self.is_available(self.choices[index]));
+
+        if let Some(next) = next {
+            self.selection.select(Some(next));
+        }
+    }
+
+    fn select_prev(&mut self) {
+        let current = self.selection.selected().unwrap_or(0);
+        let prev = (0..current)
+            .rev()
+            .find(|&index| self.is_available(self.choices[index]));
+
+        if let Some(prev) = prev {
+            self.selection.select(Some(prev));
+        }
+    }
+
+    /// Renders the menu, with `footer` on a line under the options for anything the app wants
+    /// the player to know before they start.
+    pub fn render(&mut self, frame: &mut Frame, footer: Option<Line>) {
+        let block = Block::bordered().border_set(border::DOUBLE);
+
+        let [top, bottom] = Layout::vertical([Constraint::Percentage(35), Constraint::Fill(1)])
+            .areas(block.inner(frame.area()));
+
+        let options_area = center_vertical(
+            center_horizontal(bottom, 12),
+            u16::try_from(self.choices.len()).unwrap_or(u16::MAX),
+        );
+
+        let options_items: Vec<ListItem> = self
+            .choices
+            .iter()
+            .map(|&choice| {
+                ListItem::from(choice.get_label()).style(if self.is_available(choice) {
+                    Style::new()
+                } else {
+                    Style::new().dark_gray()
+                })
+            })
+            .collect();
+
+        let options = List::new(options_items)
+            .highlight_symbol("> ")
+            .highlight_style(Style::new().bold());
+
+        frame.render_widget(block, frame.area());
+
+        if self.title.fits(top) {
+            self.title.render(frame, top);
+        } else {
+            frame.render_widget(Text::from("Dispair").centered(), center_vertical(top, 1));
+        }
+        frame.render_stateful_widget(options, options_area, &mut self.selection);
+
+        if let Some(footer) = footer {
+            let footer_area = Rect {
+                y: options_area.bottom() + 1,
+                height: 1,
+                ..bottom
+            };
+
+            frame.render_widget(footer.centered(), footer_area.intersection(bottom));
+        }
+
+        if self.show_help {
+            render_help(frame, KeyContext::MainMenu);
+        }
+    }
+}
+
+#[cfg(all(test, not(target_family = "wasm")))]
+mod tests {
+    use crate::common::mainmenu::{MainMenu, MenuChoice};
+    use crate::prelude::{KeyCode, KeyEvent};
+
+    fn press(menu: &mut MainMenu, code: KeyCode) -> Option<MenuChoice> {
+        menu.handle_key_event(&KeyEvent::from(code))
+    }
+
+    #[test]
+    fn continue_is_skipped_without_a_save() {
+        let mut menu = MainMenu::new(vec![
+            MenuChoice::NewGame,
+            MenuChoice::Continue,
+            MenuChoice::Quit,
+        ]);
+
+        // the first key finishes typing out the title
+        assert_eq!(press(&mut menu, KeyCode::Enter), None);
+
+        press(&mut menu, KeyCode::Down);
+        assert_eq!(press(&mut menu, KeyCode::Enter), Some(MenuChoice::Quit));
+
+        menu.save_exists = true;
+        press(&mut menu, KeyCode::Up);
+        assert_eq!(press(&mut menu, KeyCode::Enter), Some(MenuChoice::Continue));
+    }
+}
@@ -1,6 +1,11 @@
+use ratatui::text::Span;
+
+use crate::common::coords::{Position, SquareArea};
 use crate::common::entities::EntityCharacters;
-use rand::Rng;
-use ratatui::style::Style;
+use crate::common::hazards::{Hazard, HazardKind, Hazards};
+use crate::common::mapgen::{Biome, BiomeMapGenerator, MapGenerator};
+use crate::common::weapons::DamageArea;
+use crate::prelude::Duration;
 
 pub type Layer = Vec<Vec<EntityCharacters>>;
 
@@ -8,55 +13,189 @@ pub struct Map {
     pub width: usize,
     pub height: usize,
     pub map: Layer,
+    pub biomes: Vec<Vec<Biome>>,
+    pub hazards: Hazards,
+    /// How many rings of tiles in from the edges the walls have closed over, or `None` before
+    /// they start closing in.
+    pub closed_in: Option<i32>,
+    /// The tiles of `map` styled ready to draw, so the background isn't styled again every
+    /// frame. Tiles changed through the map's own methods are restyled here too.
+    pub tile_spans: Vec<Vec<Span<'static>>>,
 }
 
 impl Map {
-    pub fn new(width: usize, height: usize) -> Self {
-        let mut new = Self {
-            height,
-            width,
-            map: Vec::new(),
-        };
+    /// The share of the timer that passes before the walls start closing in.
+    pub const CLOSING_START: f64 = 2. / 3.;
+    /// How long the walls wait before closing in by another ring.
+    pub const CLOSING_INTERVAL: Duration = Duration::from_secs(4);
+    /// The fewest cells across the walls leave open.
+    pub const MIN_OPEN: i32 = 3;
 
-        new.fill();
+    /// Creates a new map from `seed` using the default biome generator.
+    #[must_use]
+    pub fn new(width: usize, height: usize, seed: u64) -> Self {
+        Self::generate(width, height, seed, &BiomeMapGenerator::default())
+    }
 
-        new
+    /// Creates a new map from `seed` using the given generator.
+    #[must_use]
+    pub fn generate(width: usize, height: usize, seed: u64, generator: &dyn MapGenerator) -> Self {
+        generator.generate(width, height, seed)
     }
 
-    pub fn fill(&mut self) {
-        let mut rng = rand::rng();
+    #[must_use]
+    pub fn get_layer(&self) -> &Layer {
+        &self.map
+    }
 
-        self.map = Vec::new();
+    /// Styles every tile of `layer` ready to draw, for `tile_spans`.
+    #[must_use]
+    pub fn style_layer(layer: &Layer) -> Vec<Vec<Span<'static>>> {
+        layer
+            .iter()
+            .map(|row| row.iter().map(EntityCharacters::to_styled).collect())
+            .collect()
+    }
 
-        for _ in 0..self.height {
-            let mut row = Vec::new();
-            for _ in 0..self.width {
-                let choice = rng.random_range(0..=1);
-                match choice {
-                    0 => row.push(EntityCharacters::Background1(Style::new().dark_gray())),
-                    1 => row.push(EntityCharacters::Background2(Style::new().dark_gray())),
-                    _ => {}
-                }
-            }
-            self.map.push(row);
+    /// Breaks the walls `area` covers, if it breaks walls, restyling the tiles left behind.
+    ///
+    /// Returns the positions of the walls that were destroyed.
+    pub fn destroy_walls(&mut self, area: &DamageArea) -> Vec<Position> {
+        let destroyed = area.destroy_walls(&mut self.map);
+
+        for position in &destroyed {
+            let (x, y) = position.get_as_usize();
+            self.tile_spans[y][x] = self.map[y][x].to_styled();
         }
 
-        self.map.iter_mut().for_each(|row| {
-            for entity in row.iter_mut() {
-                let entity_style = entity.style_mut();
+        destroyed
+    }
+
+    /// Removes hazards that have run out by `tickcount`.
+    pub fn clear_expired_hazards(&mut self, tickcount: u64) {
+        self.hazards
+            .retain(|_, hazard| !hazard.is_expired(tickcount));
+    }
+
+    /// Returns the biome at the given position, or plains if it is off the map.
+    #[must_use]
+    pub fn get_biome(&self, position: &Position) -> Biome {
+        let (x, y) = position.get_as_usize();
+
+        self.biomes
+            .get(y)
+            .and_then(|row| row.get(x))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Works out how many rings the walls should have closed over `elapsed` into a run against
+    /// `timer`, or `None` before the final third of the timer.
+    #[must_use]
+    pub fn get_closing_depth(elapsed: Duration, timer: Duration) -> Option<i32> {
+        let closing = elapsed.checked_sub(timer.mul_f64(Self::CLOSING_START))?;
+
+        Some((closing.as_secs_f64() / Self::CLOSING_INTERVAL.as_secs_f64()).floor() as i32)
+    }
+
+    /// The most rings the walls close over, leaving `MIN_OPEN` cells across.
+    fn get_max_depth(&self) -> i32 {
+        ((self.width.min(self.height) as i32 - Self::MIN_OPEN) / 2).max(0)
+    }
+
+    /// Returns the area the walls haven't closed over.
+    #[must_use]
+    pub fn get_playable_area(&self) -> SquareArea {
+        SquareArea::covering(&self.map).grow(-self.closed_in.unwrap_or(0))
+    }
 
-                let choice = rng.random_range(0..10);
+    /// Closes the walls in to `depth` rings from the edges, as far as they go, turning the tiles
+    /// they close over into lethal hazard. Returns whether the walls moved.
+    pub fn close_in(&mut self, depth: i32) -> bool {
+        let from = self.closed_in.unwrap_or(0);
+        let depth = depth.min(self.get_max_depth()).max(from);
 
-                if choice < 2 {
-                    *entity_style = entity_style.black();
-                } else if choice < 8 {
-                    *entity_style = entity_style.dark_gray();
-                }
+        for ring in from..depth {
+            for position in SquareArea::covering(&self.map).grow(-ring).get_border() {
+                self.hazards
+                    .insert(position.clone(), Hazard::closing_wall(position));
             }
-        });
+        }
+
+        self.closed_in = Some(depth);
+        depth > from
     }
 
-    pub fn get_layer(&self) -> &Layer {
-        &self.map
+    /// Returns the ring of tiles the walls close over next, for the warning line. Empty before
+    /// the walls start closing in, and once they've gone as far as they go.
+    #[must_use]
+    pub fn get_closing_edge(&self) -> Vec<Position> {
+        match self.closed_in {
+            Some(depth) if depth < self.get_max_depth() => self.get_playable_area().get_border(),
+            _ => vec![],
+        }
+    }
+
+    /// Opens the walls back up to the map's edges.
+    pub fn open_up(&mut self) {
+        self.closed_in = None;
+        self.hazards
+            .retain(|_, hazard| hazard.kind != HazardKind::ClosingWall);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Style;
+    use ratatui::symbols::shade;
+
+    use crate::common::coords::{Area, AreaWrapper, ChaosArea, Position};
+    use crate::common::entities::EntityCharacters;
+    use crate::common::map::Map;
+    use crate::common::weapons::DamageArea;
+    use crate::prelude::Duration;
+
+    #[test]
+    fn walls_close_in_over_the_final_third() {
+        let timer = Duration::from_mins(1);
+        assert_eq!(Map::get_closing_depth(Duration::from_secs(39), timer), None);
+        assert_eq!(
+            Map::get_closing_depth(Duration::from_secs(40), timer),
+            Some(0)
+        );
+        assert_eq!(
+            Map::get_closing_depth(Duration::from_secs(49), timer),
+            Some(2)
+        );
+
+        let mut map = Map::new(20, 9, 1);
+        assert!(map.get_closing_edge().is_empty());
+
+        assert!(!map.close_in(0));
+        assert_eq!(map.get_closing_edge().len(), 54);
+
+        assert!(map.close_in(5));
+        assert_eq!(map.closed_in, Some(3));
+        assert_eq!(map.get_playable_area().get_bounds(), (3, 3, 16, 5));
+        assert!(map.get_closing_edge().is_empty());
+        assert!(map.hazards[&Position(2, 4)].is_lethal());
+
+        map.open_up();
+        assert!(map.hazards.values().all(|hazard| !hazard.is_lethal()));
+    }
+
+    #[test]
+    fn broken_walls_are_restyled() {
+        let mut map = Map::new(5, 5, 1);
+        map.map[2][3] = EntityCharacters::Wall(Style::new());
+        map.tile_spans = Map::style_layer(&map.map);
+
+        let mut area = DamageArea::new_empty();
+        area.area = AreaWrapper::Chaos(ChaosArea::new(vec![Position(3, 2)]));
+        area.destroys_walls = true;
+
+        assert_eq!(map.destroy_walls(&area), [Position(3, 2)]);
+        assert_eq!(map.tile_spans[2][3], map.map[2][3].to_styled());
+        assert_ne!(map.tile_spans[2][3].content, shade::DARK);
     }
 }
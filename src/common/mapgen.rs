@@ -0,0 +1,253 @@
+//! This module generates the background map for a run.
+//! Generators implement `MapGenerator`, so fixed layouts can be added alongside the procedural one.
+//! The default `BiomeMapGenerator` uses value noise to split the map into biome regions.
+
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use ratatui::style::Style;
+
+use crate::common::coords::Position;
+use crate::common::entities::EntityCharacters;
+use crate::common::hazards::{Hazard, Hazards};
+use crate::common::map::{Layer, Map};
+
+/// A region type on the map, affecting how it looks, how fast things move through it and how
+/// often enemies spawn in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Biome {
+    #[default]
+    Plains,
+    Rubble,
+    Walls,
+}
+
+impl Biome {
+    /// How much slower movement is while standing in this biome.
+    #[must_use]
+    pub fn movement_cost(&self) -> f64 {
+        match self {
+            Biome::Plains => 1.,
+            Biome::Rubble => 1.5,
+            Biome::Walls => 2.5,
+        }
+    }
+
+    /// How likely an enemy is to spawn in this biome, relative to plains.
+    #[must_use]
+    pub fn spawn_weight(&self) -> f64 {
+        match self {
+            Biome::Plains => 1.,
+            Biome::Rubble => 0.6,
+            Biome::Walls => 0.2,
+        }
+    }
+
+    /// Rolls a background tile for this biome.
+    fn roll_tile(self, rng: &mut impl Rng) -> EntityCharacters {
+        let roll = rng.random_range(0..10);
+
+        match self {
+            Biome::Plains => match roll {
+                0..2 => EntityCharacters::Background1(Style::new().dark_gray()),
+                2..8 => EntityCharacters::Empty(Style::new()),
+                _ => EntityCharacters::Background2(Style::new().black()),
+            },
+            Biome::Rubble => match roll {
+                0..3 => EntityCharacters::Background1(Style::new().dark_gray()),
+                3..8 => EntityCharacters::Background2(Style::new().dark_gray()),
+                _ => EntityCharacters::Background2(Style::new().gray()),
+            },
+            Biome::Walls => match roll {
+                0..6 => EntityCharacters::Wall(Style::new().dark_gray()),
+                6..8 => EntityCharacters::Background2(Style::new().dark_gray()),
+                _ => EntityCharacters::Background1(Style::new().black()),
+            },
+        }
+    }
+}
+
+/// Something that can build a map of the given size. The same seed always builds the same map.
+pub trait MapGenerator {
+    fn generate(&self, width: usize, height: usize, seed: u64) -> Map;
+}
+
+/// The original generator: uniformly random background with no biomes.
+pub struct RandomMapGenerator;
+
+impl MapGenerator for RandomMapGenerator {
+    fn generate(&self, width: usize, height: usize, seed: u64) -> Map {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let map: Layer = (0..height)
+            .map(|_| {
+                (0..width)
+                    .map(|_| {
+                        let mut entity = if rng.random_bool(0.5) {
+                            EntityCharacters::Background1(Style::new().dark_gray())
+                        } else {
+                            EntityCharacters::Background2(Style::new().dark_gray())
+                        };
+
+                        let entity_style = entity.style_mut();
+                        let choice = rng.random_range(0..10);
+                        if choice < 2 {
+                            *entity_style = entity_style.black();
+                        } else if choice < 8 {
+                            *entity_style = entity_style.dark_gray();
+                        }
+
+                        entity
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Map {
+            width,
+            height,
+            tile_spans: Map::style_layer(&map),
+            map,
+            biomes: vec![vec![Biome::Plains; width]; height],
+            hazards: Hazards::new(),
+            closed_in: None,
+        }
+    }
+}
+
+/// Generates biome regions from smoothly interpolated value noise.
+pub struct BiomeMapGenerator {
+    /// The distance in cells between noise lattice points. Larger values make larger regions.
+    pub scale: usize,
+}
+
+impl Default for BiomeMapGenerator {
+    fn default() -> Self {
+        Self { scale: 14 }
+    }
+}
+
+impl BiomeMapGenerator {
+    /// Noise values below this are plains.
+    const PLAINS_THRESHOLD: f64 = 0.55;
+    /// Noise values below this, but above the plains threshold, are rubble.
+    const RUBBLE_THRESHOLD: f64 = 0.78;
+    /// Chance for a rubble tile to hold spikes.
+    const SPIKE_CHANCE: f64 = 0.02;
+
+    /// Returns the biome for a noise value between `0.` and `1.`.
+    fn biome_for(noise: f64) -> Biome {
+        if noise < Self::PLAINS_THRESHOLD {
+            Biome::Plains
+        } else if noise < Self::RUBBLE_THRESHOLD {
+            Biome::Rubble
+        } else {
+            Biome::Walls
+        }
+    }
+}
+
+impl MapGenerator for BiomeMapGenerator {
+    fn generate(&self, width: usize, height: usize, seed: u64) -> Map {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let noise = ValueNoise::new(width, height, self.scale.max(1), &mut rng);
+
+        let biomes: Vec<Vec<Biome>> = (0..height)
+            .map(|y| {
+                (0..width)
+                    .map(|x| Self::biome_for(noise.sample(x, y)))
+                    .collect()
+            })
+            .collect();
+
+        let map: Layer = biomes
+            .iter()
+            .map(|row| row.iter().map(|biome| biome.roll_tile(&mut rng)).collect())
+            .collect();
+
+        let mut hazards = Hazards::new();
+        for (y, row) in biomes.iter().enumerate() {
+            for (x, biome) in row.iter().enumerate() {
+                if *biome == Biome::Rubble && rng.random_bool(Self::SPIKE_CHANCE) {
+                    let position = Position(x as i32, y as i32);
+                    hazards.insert(position.clone(), Hazard::spikes(position));
+                }
+            }
+        }
+
+        Map {
+            width,
+            height,
+            tile_spans: Map::style_layer(&map),
+            map,
+            biomes,
+            hazards,
+            closed_in: None,
+        }
+    }
+}
+
+/// A grid of random values that is sampled with smoothed bilinear interpolation.
+struct ValueNoise {
+    lattice: Vec<Vec<f64>>,
+    scale: usize,
+}
+
+impl ValueNoise {
+    fn new(width: usize, height: usize, scale: usize, rng: &mut impl Rng) -> Self {
+        let lattice_width = width / scale + 2;
+        let lattice_height = height / scale + 2;
+
+        let lattice = (0..lattice_height)
+            .map(|_| (0..lattice_width).map(|_| rng.random::<f64>()).collect())
+            .collect();
+
+        Self { lattice, scale }
+    }
+
+    fn sample(&self, x: usize, y: usize) -> f64 {
+        let (cell_x, cell_y) = (x / self.scale, y / self.scale);
+        let smooth = |t: f64| t * t * (3. - 2. * t);
+        let tx = smooth((x % self.scale) as f64 / self.scale as f64);
+        let ty = smooth((y % self.scale) as f64 / self.scale as f64);
+
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+
+        let top = lerp(
+            self.lattice[cell_y][cell_x],
+            self.lattice[cell_y][cell_x + 1],
+            tx,
+        );
+        let bottom = lerp(
+            self.lattice[cell_y + 1][cell_x],
+            self.lattice[cell_y + 1][cell_x + 1],
+            tx,
+        );
+
+        lerp(top, bottom, ty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::mapgen::{BiomeMapGenerator, MapGenerator};
+
+    #[test]
+    fn biome_map_matches_requested_size() {
+        let map = BiomeMapGenerator::default().generate(53, 21, 0);
+
+        assert_eq!(map.map.len(), 21);
+        assert_eq!(map.biomes.len(), 21);
+        assert!(map.map.iter().all(|row| row.len() == 53));
+        assert!(map.biomes.iter().all(|row| row.len() == 53));
+    }
+
+    #[test]
+    fn same_seed_builds_same_map() {
+        let generator = BiomeMapGenerator::default();
+
+        let first = generator.generate(53, 21, 42);
+        let second = generator.generate(53, 21, 42);
+
+        assert!(first.biomes == second.biomes);
+        assert!(first.hazards.keys().eq(second.hazards.keys()));
+    }
+}
@@ -0,0 +1,192 @@
+//! This module provides the mastery screen, which shows the lifetime kills with each METHOD and
+//! the mastery tiers they have earned.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, Gauge, List, ListItem, ListState, Paragraph, Wrap},
+};
+use strum::IntoEnumIterator;
+
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::mastery::tier::MasteryTier;
+use crate::common::numbers::format_number;
+use crate::common::weapons::WeaponWrapper;
+use crate::common::{Goto, PlayerStateRef, View};
+
+/// A struct that manages the state and rendering of the mastery screen.
+pub struct MasteryMenu {
+    pub player_state: PlayerStateRef,
+    weapons: Vec<WeaponWrapper>,
+    pub weapon_selection: ListState,
+    pub goto: Goto,
+}
+
+impl MasteryMenu {
+    #[must_use]
+    pub fn new(player_state: PlayerStateRef) -> Self {
+        let mut weapon_selection = ListState::default();
+        weapon_selection.select_first();
+
+        Self {
+            player_state,
+            weapons: WeaponWrapper::iter().collect(),
+            weapon_selection,
+            goto: Goto::Mastery,
+        }
+    }
+
+    /// Handles actions for the mastery menu.
+    pub fn on_action(&mut self, action: Action) {
+        match action {
+            Action::Up => self.weapon_selection.select_previous(),
+            Action::Down => self.weapon_selection.select_next(),
+            Action::Back => self.goto = Goto::Collection,
+            _ => {}
+        }
+    }
+
+    /// Returns the name of the currently highlighted METHOD.
+    #[must_use]
+    pub fn get_selected_name(&self) -> Option<&'static str> {
+        self.weapon_selection
+            .selected()
+            .and_then(|index| self.weapons.get(index))
+            .map(Into::into)
+    }
+
+    /// Lists every tier for the highlighted METHOD, marking the ones reached.
+    fn get_tier_lines(&self, name: &str) -> Vec<Line<'static>> {
+        let player_state = self.player_state.borrow();
+        let number_format = player_state.settings.number_format;
+        let kills = player_state.get_weapon_kills(name);
+
+        let mut lines = vec![
+            Line::from(name.to_uppercase().bold()),
+            Line::from(format!(
+                "{} kills",
+                format_number(u128::from(kills), number_format)
+            )),
+            Line::from(""),
+        ];
+
+        lines.extend(MasteryTier::iter().skip(1).map(|tier| {
+            let line = format!(
+                " {} ({}) - {}",
+                tier.get_name(),
+                format_number(u128::from(tier.get_kills_needed()), number_format),
+                tier.get_reward_desc()
+            );
+
+            if kills >= tier.get_kills_needed() {
+                Line::from(format!("[x]{line}"))
+            } else {
+                Line::from(format!("[ ]{line}").dark_gray())
+            }
+        }));
+
+        lines
+    }
+
+    /// Builds the bar showing progress towards the next tier of the METHOD named `name`.
+    fn get_progress_gauge(&self, name: &str) -> Gauge<'static> {
+        let player_state = self.player_state.borrow();
+        let kills = player_state.get_weapon_kills(name);
+        let tier = player_state.get_mastery(name);
+
+        let gauge = Gauge::default().gauge_style(
+            tier.get_glyph_color()
+                .map_or_else(|| Style::new().white(), |color| Style::new().fg(color)),
+        );
+
+        match tier.next() {
+            Some(next) => {
+                let from = tier.get_kills_needed();
+                let to = next.get_kills_needed();
+
+                gauge
+                    .label(format!("{} -> {}", tier.get_name(), next.get_name()))
+                    .ratio(((kills - from) as f64 / (to - from) as f64).clamp(0., 1.))
+            }
+            None => gauge.label(tier.get_name()).ratio(1.),
+        }
+    }
+
+    /// Renders the mastery menu to the frame.
+    pub fn render_mastery(&mut self, frame: &mut Frame) {
+        let title = Line::from(" dispair.mastery ".bold());
+        let instructions = Line::from(vec![" <W|UP> Up | <S|DOWN> Down | <Esc> Back ".into()]);
+
+        let window = Block::bordered()
+            .border_set(border::THICK)
+            .title(title.left_aligned())
+            .title_bottom(instructions.left_aligned());
+        let inner = window.inner(frame.area());
+
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Fill(1)]).areas(inner);
+
+        let list_items: Vec<ListItem> = {
+            let player_state = self.player_state.borrow();
+
+            self.weapons
+                .iter()
+                .map(|weapon| {
+                    let name: &'static str = weapon.into();
+                    let tier = player_state.get_mastery(name);
+                    let line = format!(" {:<10} {}", name.to_uppercase(), tier.get_name());
+
+                    match tier.get_glyph_color() {
+                        Some(color) => ListItem::from(line).style(Style::new().fg(color)),
+                        None => ListItem::from(line),
+                    }
+                })
+                .collect()
+        };
+
+        let list = List::new(list_items)
+            .highlight_style(Style::new().bold())
+            .highlight_symbol(">");
+
+        frame.render_widget(window, frame.area());
+        frame.render_stateful_widget(list, left, &mut self.weapon_selection);
+
+        let Some(name) = self.get_selected_name() else {
+            return;
+        };
+
+        let details_block = Block::bordered().border_set(border::ROUNDED);
+        let [gauge_area, tiers_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)])
+                .areas(details_block.inner(right));
+
+        let tiers = Paragraph::new(self.get_tier_lines(name)).wrap(Wrap { trim: false });
+
+        frame.render_widget(details_block, right);
+        frame.render_widget(self.get_progress_gauge(name), gauge_area);
+        frame.render_widget(tiers, tiers_area);
+    }
+}
+
+impl View for MasteryMenu {
+    fn on_tick(&mut self) {}
+
+    fn transition(&self) -> &Goto {
+        &self.goto
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.render_mastery(frame);
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        self.on_action(action);
+    }
+
+    fn get_key_context(&self) -> KeyContext {
+        KeyContext::Mastery
+    }
+}
@@ -0,0 +1,2 @@
+pub mod masterymenu;
+pub mod tier;
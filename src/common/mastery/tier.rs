@@ -0,0 +1,104 @@
+//! This module defines METHOD mastery: lifetime kills with a METHOD earn it tiers, each of which
+//! carries a small permanent bonus for that METHOD.
+
+use ratatui::style::Color;
+use strum::{EnumIter, IntoEnumIterator};
+
+/// How far a METHOD has been mastered, from its lifetime kills.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, EnumIter)]
+pub enum MasteryTier {
+    Novice,
+    /// Tints the METHOD's attacks.
+    Adept,
+    /// The METHOD starts a level higher.
+    Expert,
+    /// The METHOD starts two levels higher.
+    Master,
+}
+
+impl MasteryTier {
+    /// The lifetime kills a METHOD needs to reach this tier.
+    #[must_use]
+    pub fn get_kills_needed(self) -> u64 {
+        match self {
+            MasteryTier::Novice => 0,
+            MasteryTier::Adept => 250,
+            MasteryTier::Expert => 1000,
+            MasteryTier::Master => 5000,
+        }
+    }
+
+    /// Returns the highest tier `kills` reaches.
+    #[must_use]
+    pub fn from_kills(kills: u64) -> Self {
+        MasteryTier::iter()
+            .rev()
+            .find(|tier| kills >= tier.get_kills_needed())
+            .unwrap_or(MasteryTier::Novice)
+    }
+
+    /// Returns the tier after this one, if there is one.
+    #[must_use]
+    pub fn next(self) -> Option<Self> {
+        MasteryTier::iter().find(|tier| *tier > self)
+    }
+
+    #[must_use]
+    pub fn get_name(self) -> &'static str {
+        match self {
+            MasteryTier::Novice => "NOVICE",
+            MasteryTier::Adept => "ADEPT",
+            MasteryTier::Expert => "EXPERT",
+            MasteryTier::Master => "MASTER",
+        }
+    }
+
+    /// Describes what reaching this tier gives.
+    #[must_use]
+    pub fn get_reward_desc(self) -> &'static str {
+        match self {
+            MasteryTier::Novice => "nothing yet",
+            MasteryTier::Adept => "attacks take on a new colour",
+            MasteryTier::Expert => "starts at +1 level",
+            MasteryTier::Master => "starts at +2 levels, and a gold finish",
+        }
+    }
+
+    /// How many levels above 1 the METHOD starts at, before its level cap.
+    #[must_use]
+    pub fn get_start_levels(self) -> i32 {
+        match self {
+            MasteryTier::Novice | MasteryTier::Adept => 0,
+            MasteryTier::Expert => 1,
+            MasteryTier::Master => 2,
+        }
+    }
+
+    /// The colour the METHOD's attacks are drawn in, for tiers that change it. Elemental METHODs
+    /// keep their element's colour.
+    #[must_use]
+    pub fn get_glyph_color(self) -> Option<Color> {
+        match self {
+            MasteryTier::Novice => None,
+            MasteryTier::Adept | MasteryTier::Expert => Some(Color::LightCyan),
+            MasteryTier::Master => Some(Color::Rgb(255, 200, 60)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::mastery::tier::MasteryTier;
+
+    #[test]
+    fn tiers_follow_kills() {
+        assert_eq!(MasteryTier::from_kills(0), MasteryTier::Novice);
+        assert_eq!(
+            MasteryTier::from_kills(MasteryTier::Expert.get_kills_needed()),
+            MasteryTier::Expert
+        );
+        assert_eq!(MasteryTier::from_kills(u64::MAX), MasteryTier::Master);
+        assert_eq!(MasteryTier::Adept.next(), Some(MasteryTier::Expert));
+        assert_eq!(MasteryTier::Master.next(), None);
+    }
+}
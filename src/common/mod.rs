@@ -2,30 +2,76 @@
 //! between the terminal and WASM versions of the application. It includes
 //! modules for characters, coordinates, game state, and more.
 
+use crate::common::keybinds::{Action, KeyContext};
+#[cfg(feature = "netplay")]
+use crate::common::netplay::NetSession;
+use crate::common::perf::EntityCounts;
 use crate::common::upgrades::upgrade::PlayerState;
 use crate::prelude::KeyEvent;
-use ratatui::Frame;
+use ratatui::{Frame, layout::Rect};
 use std::cell::RefCell;
 use std::rc::Rc;
 
+pub mod app;
+pub mod apperror;
+pub mod autopilot;
+pub mod buffs;
+pub mod capture;
+pub mod challenge;
 pub mod character;
 pub mod charms;
+pub mod classes;
+pub mod collection;
+pub mod combo;
+pub mod coop;
 pub mod coords;
+pub mod deathrecap;
 pub mod debuffs;
 pub mod effects;
 pub mod enemies;
 pub mod entities;
+pub mod eventlog;
+pub mod events;
 pub mod game;
+pub mod hazards;
+pub mod hype;
+pub mod input;
+pub mod keybinds;
+#[cfg(feature = "leaderboard")]
+pub mod leaderboard;
 pub mod level;
+pub mod loadouts;
+pub mod mainmenu;
 pub mod map;
+pub mod mapgen;
+pub mod mastery;
+pub mod mutators;
+#[cfg(feature = "netplay")]
+pub mod netplay;
+pub mod numbers;
+pub mod onkill;
+pub mod perf;
 pub mod pickups;
 pub mod popups;
 pub mod powerup;
+#[cfg(feature = "cloudsync")]
+pub mod remotesync;
 pub mod render;
 pub mod rogue;
+pub mod router;
+pub mod runrng;
+pub mod runstats;
+pub mod savestore;
+pub mod schedule;
+pub mod settings;
 pub mod sound;
+pub mod splash;
 pub mod stats;
+pub mod statsheet;
 pub mod timescaler;
+pub mod toasts;
+pub mod turrets;
+pub mod tutorial;
 pub mod upgrades;
 pub(crate) mod utils;
 pub mod weapons;
@@ -35,24 +81,86 @@ pub mod widgets;
 pub const TICK_RATE: f64 = 20.0;
 /// The target number of frames per second.
 pub const FRAME_RATE: f64 = 180.0;
+/// The smallest terminal width the game will draw in.
+pub const MIN_TERMINAL_WIDTH: u16 = 60;
+/// The smallest terminal height the game will draw in.
+pub const MIN_TERMINAL_HEIGHT: u16 = 16;
 
 pub type PlayerStateRef = Rc<RefCell<PlayerState>>;
 
-/// An enum representing the possible destinations when closing the upgrade menu.
-#[derive(Clone, PartialEq)]
+/// An enum representing the possible destinations when leaving a view.
+#[derive(Clone, PartialEq, Debug)]
 pub enum Goto {
     Game,
+    Mutators,
+    /// Starts a new run straight away, on the map built from the given seed.
+    Retry(u64),
+    ClassSelect,
+    Loadout,
+    Collection,
+    Mastery,
+    Settings,
     Menu,
     Upgrades,
+    #[cfg(feature = "leaderboard")]
+    Leaderboard,
+    #[cfg(feature = "netplay")]
+    Netplay,
+    /// Opens the help over the current view.
+    Help,
+    /// Closes the view on top, going back to the one it was opened over.
+    Back,
 }
 
-pub trait Viewable {
-    fn tick(&mut self);
-    fn frame(&mut self) {}
+/// A screen the router can show. Views are shared by both platforms, so a new screen only
+/// needs a view and a `Goto` to open it by.
+pub trait View {
+    fn on_tick(&mut self);
+    fn on_frame(&mut self) {}
 
-    fn get_goto(&self) -> &Goto;
+    /// Where the view wants to go next. The view stays open while this is still the destination
+    /// it was opened for.
+    fn transition(&self) -> &Goto;
 
     fn render(&mut self, frame: &mut Frame);
 
-    fn handle_key_event(&mut self, key_event: &KeyEvent);
+    /// Handles a key press as the action it's bound to in the view's key context, if any. Views
+    /// that take typed text, or pass keys on as they are, handle them here.
+    fn handle_key_event(&mut self, key_event: &KeyEvent) {
+        if let Some(action) = self.get_key_context().get_action(&key_event.code) {
+            self.handle_action(action);
+        }
+    }
+
+    /// Handles an action the player asked for.
+    fn handle_action(&mut self, _action: Action) {}
+
+    /// Called when a key is let go of, on platforms that report it.
+    fn handle_key_release(&mut self, _key_event: &KeyEvent) {}
+
+    /// Which keybindings are live in this view right now, for the help overlay.
+    fn get_key_context(&self) -> KeyContext;
+
+    /// How many entities and effects this view is drawing, for the performance overlay.
+    fn get_entity_counts(&self) -> Option<EntityCounts> {
+        None
+    }
+
+    /// Called when the screen is resized to `area`, before the next render.
+    fn resize(&mut self, _area: Rect) {}
+
+    /// Called when the game is hidden or shown, e.g. when the browser tab loses focus.
+    fn set_visible(&mut self, _visible: bool) {}
+
+    /// What the player is doing in this view, for their Discord profile. Every menu hangs off
+    /// the upgrades screen, so they all count as browsing upgrades.
+    fn get_presence(&self) -> String {
+        "Browsing upgrades".into()
+    }
+
+    /// Hands over the co-op session this view set up, once it's ready for a run to be played over.
+    #[cfg(feature = "netplay")]
+    fn take_session(&mut self) -> Option<NetSession> {
+        None
+    }
 }
@@ -0,0 +1,115 @@
+//! This module defines run mutators: optional modifiers chosen before a run that make it harder
+//! or stranger in exchange for more gold.
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoStaticStr};
+
+use crate::common::statsheet::{StatKey, StatModifier, StatSource};
+
+/// A modifier the player can switch on for their next runs.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, IntoStaticStr,
+)]
+pub enum Mutator {
+    #[strum(serialize = "GLASS CANNON")]
+    GlassCannon,
+
+    #[strum(serialize = "FRENZY")]
+    Frenzy,
+
+    #[strum(serialize = "NO CHARMS")]
+    NoCharms,
+
+    #[strum(serialize = "MIRROR")]
+    MirrorControls,
+
+    #[strum(serialize = "WRAP")]
+    WrapAround,
+}
+
+impl Mutator {
+    #[must_use]
+    pub fn get_name(&self) -> &'static str {
+        self.into()
+    }
+
+    #[must_use]
+    pub fn get_desc(&self) -> &'static str {
+        match self {
+            Mutator::GlassCannon => "+100% damage, -60% health.",
+            Mutator::Frenzy => "enemies move 50% faster.",
+            Mutator::NoCharms => "charms are never offered.",
+            Mutator::MirrorControls => "every direction is reversed.",
+            Mutator::WrapAround => "the map's edges wrap around, so enemies come from every side.",
+        }
+    }
+
+    /// How much this mutator multiplies gold rewards by.
+    #[must_use]
+    pub fn get_gold_mult(&self) -> f64 {
+        match self {
+            Mutator::GlassCannon => 1.3,
+            Mutator::Frenzy => 1.25,
+            Mutator::NoCharms => 1.2,
+            Mutator::MirrorControls => 1.15,
+            Mutator::WrapAround => 1.1,
+        }
+    }
+
+    /// Returns this mutator's changes and gold reward as stat sheet modifiers. Like class stats,
+    /// health is derived from `health_mult` once they're applied.
+    #[must_use]
+    pub fn get_modifiers(&self) -> Vec<StatModifier> {
+        let source = StatSource::Mutator(*self);
+
+        let mut modifiers = match self {
+            Mutator::GlassCannon => vec![
+                StatModifier::add(StatKey::DamageMult, 1.0, source.clone()),
+                StatModifier::add(StatKey::HealthMult, -0.6, source.clone()),
+            ],
+            Mutator::Frenzy => vec![StatModifier::mult(
+                StatKey::EnemyMoveMult,
+                1.5,
+                source.clone(),
+            )],
+            Mutator::NoCharms => vec![StatModifier::set(StatKey::MaxCharms, 0., source.clone())],
+            Mutator::MirrorControls | Mutator::WrapAround => vec![],
+        };
+
+        modifiers.push(StatModifier::mult(
+            StatKey::GoldMult,
+            self.get_gold_mult(),
+            source,
+        ));
+        modifiers
+    }
+}
+
+/// Returns the combined gold multiplier of the given mutators.
+#[must_use]
+pub fn get_total_gold_mult(mutators: &[Mutator]) -> f64 {
+    mutators.iter().map(Mutator::get_gold_mult).product()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::mutators::mutator::Mutator;
+    use crate::common::upgrades::upgrade::PlayerState;
+
+    #[test]
+    fn mutators_applied_on_refresh() {
+        let mut player_state = PlayerState::default();
+        let base_stats = player_state.stats.clone();
+
+        player_state.mutators = vec![Mutator::GlassCannon, Mutator::NoCharms];
+        player_state.refresh();
+
+        let stats = &player_state.stats;
+        assert!(stats.player_stats.health < base_stats.player_stats.health);
+        assert_eq!(stats.game_stats.max_charms, 0);
+        assert!(
+            (stats.game_stats.gold_mult - base_stats.game_stats.gold_mult * 1.3 * 1.2).abs()
+                < f64::EPSILON
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! This module provides the pre-run screen for switching run mutators on and off.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+};
+use strum::IntoEnumIterator;
+
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::mutators::mutator::{Mutator, get_total_gold_mult};
+use crate::common::{Goto, PlayerStateRef, View};
+
+/// A struct that manages the state and rendering of the mutator selection screen.
+pub struct MutatorMenu {
+    pub player_state: PlayerStateRef,
+    mutators: Vec<Mutator>,
+    pub mutator_selection: ListState,
+    pub goto: Goto,
+}
+
+impl MutatorMenu {
+    #[must_use]
+    pub fn new(player_state: PlayerStateRef) -> Self {
+        let mut mutator_selection = ListState::default();
+        mutator_selection.select_first();
+
+        Self {
+            player_state,
+            mutators: Mutator::iter().collect(),
+            mutator_selection,
+            goto: Goto::Mutators,
+        }
+    }
+
+    /// Handles actions for the mutator menu.
+    pub fn on_action(&mut self, action: Action) {
+        match action {
+            Action::Up => self.mutator_selection.select_previous(),
+            Action::Down => self.mutator_selection.select_next(),
+            Action::Confirm => self.toggle_selected(),
+            Action::Start => self.goto = Goto::Game,
+            Action::Back => self.goto = Goto::Loadout,
+            _ => {}
+        }
+    }
+
+    /// Returns the currently highlighted mutator.
+    #[must_use]
+    pub fn get_selected_mutator(&self) -> Option<Mutator> {
+        self.mutator_selection
+            .selected()
+            .and_then(|index| self.mutators.get(index))
+            .copied()
+    }
+
+    /// Switches the highlighted mutator on or off.
+    pub fn toggle_selected(&mut self) {
+        let Some(mutator) = self.get_selected_mutator() else {
+            return;
+        };
+
+        let mut player_state = self.player_state.borrow_mut();
+        if let Some(index) = player_state.mutators.iter().position(|m| *m == mutator) {
+            player_state.mutators.remove(index);
+        } else {
+            player_state.mutators.push(mutator);
+        }
+    }
+
+    /// Renders the mutator menu to the frame.
+    pub fn render_mutators(&mut self, frame: &mut Frame) {
+        let title = Line::from(" dispair.mutators ".bold());
+        let instructions = Line::from(vec![
+            " <W|UP> Up | <S|DOWN> Down | <ENTER> Toggle | <SPACE> Start Game | <Esc> Back ".into(),
+        ]);
+
+        let window = Block::bordered()
+            .border_set(border::THICK)
+            .title(title.left_aligned())
+            .title_bottom(instructions.left_aligned());
+        let inner = window.inner(frame.area());
+
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Fill(1)]).areas(inner);
+
+        let active = self.player_state.borrow().mutators.clone();
+
+        let list_items: Vec<ListItem> = self
+            .mutators
+            .iter()
+            .map(|mutator| {
+                let marker = if active.contains(mutator) {
+                    "[x]"
+                } else {
+                    "[ ]"
+                };
+                ListItem::from(format!(" {marker} {}", mutator.get_name()))
+            })
+            .collect();
+
+        let list = List::new(list_items)
+            .highlight_style(Style::new().bold())
+            .highlight_symbol(">");
+
+        let mut mutator_lines = vec![];
+        if let Some(mutator) = self.get_selected_mutator() {
+            mutator_lines.push(Line::from(mutator.get_name().bold()));
+            mutator_lines.push(Line::from(""));
+            mutator_lines.push(Line::from(mutator.get_desc()));
+            mutator_lines.push(Line::from(""));
+            mutator_lines.push(Line::from(
+                format!("x{} gold", mutator.get_gold_mult()).light_yellow(),
+            ));
+        }
+        mutator_lines.push(Line::from(""));
+        mutator_lines.push(Line::from("total reward".dark_gray()));
+        mutator_lines.push(Line::from(format!(
+            "x{:.2} gold",
+            get_total_gold_mult(&active)
+        )));
+
+        let mutator_paragraph = Paragraph::new(mutator_lines)
+            .block(Block::bordered().border_set(border::ROUNDED))
+            .centered()
+            .wrap(Wrap { trim: false });
+
+        frame.render_widget(window, frame.area());
+        frame.render_stateful_widget(list, left, &mut self.mutator_selection);
+        frame.render_widget(mutator_paragraph, right);
+    }
+}
+
+impl View for MutatorMenu {
+    fn on_tick(&mut self) {}
+
+    fn transition(&self) -> &Goto {
+        &self.goto
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.render_mutators(frame);
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        self.on_action(action);
+    }
+
+    fn get_key_context(&self) -> KeyContext {
+        KeyContext::Mutators
+    }
+}
@@ -0,0 +1,607 @@
+//! This module provides networked co-op. Two players meet in a room on a WebSocket relay, which
+//! passes each player's messages on to the other. The host sends their seed and save, and both
+//! then play the same run in lockstep: each tick's keys are swapped and only replayed once both
+//! players' keys for it have arrived, so the two copies of the run see the same inputs on the
+//! same ticks.
+//!
+//! The relay is read from `DISPAIR_NETPLAY_URL`, at build time or, on the terminal, at run time.
+//! Rooms are joined at `<url>/<room code>`.
+//!
+//! Each player also sends a checksum of their run, so a run that drifts is reported as out of
+//! sync rather than quietly playing out differently. For the copies to stay in step, the run
+//! keeps time in ticks rather than by the clock, so timers, i-frames and the like run out on the
+//! same tick on both machines.
+
+pub mod netplaymenu;
+
+use std::collections::BTreeMap;
+use std::hash::Hasher;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::common::upgrades::upgrade::PlayerState;
+use crate::prelude::{KeyCode, KeyEvent, Socket};
+
+/// Something that happened on a socket.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocketEvent {
+    Open,
+    Message(String),
+    /// The connection closed or couldn't be made, with the reason why.
+    Closed(String),
+}
+
+/// Where the relay lives.
+#[derive(Debug, Clone)]
+pub struct NetplayConfig {
+    pub endpoint: String,
+}
+
+impl NetplayConfig {
+    /// Reads the configuration from the environment, if a relay has been set.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        #[cfg(not(target_family = "wasm"))]
+        let runtime = std::env::var("DISPAIR_NETPLAY_URL").ok();
+        #[cfg(target_family = "wasm")]
+        let runtime: Option<String> = None;
+
+        let endpoint = runtime.or_else(|| option_env!("DISPAIR_NETPLAY_URL").map(String::from))?;
+
+        Some(Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+        })
+    }
+
+    #[must_use]
+    pub fn get_room_url(&self, room: &str) -> String {
+        format!("{}/{room}", self.endpoint)
+    }
+}
+
+/// Which side of a networked run a player is on. The run is played with the host's seed and save.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetRole {
+    Host,
+    Guest,
+}
+
+/// A key a player pressed or let go of, as sent to the other player.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetKey {
+    pub code: NetKeyCode,
+    pub released: bool,
+}
+
+/// The keys that can be sent, shared by the terminal and the web.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetKeyCode {
+    Char(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Esc,
+    Tab,
+    Backspace,
+}
+
+impl NetKey {
+    /// Returns `key_event` as a key to send, if it's one that can be.
+    #[must_use]
+    pub fn from_event(key_event: &KeyEvent, released: bool) -> Option<Self> {
+        let code = match key_event.code {
+            KeyCode::Char(c) => NetKeyCode::Char(c),
+            KeyCode::Up => NetKeyCode::Up,
+            KeyCode::Down => NetKeyCode::Down,
+            KeyCode::Left => NetKeyCode::Left,
+            KeyCode::Right => NetKeyCode::Right,
+            KeyCode::Enter => NetKeyCode::Enter,
+            KeyCode::Esc => NetKeyCode::Esc,
+            KeyCode::Tab => NetKeyCode::Tab,
+            KeyCode::Backspace => NetKeyCode::Backspace,
+            _ => return None,
+        };
+
+        Some(Self { code, released })
+    }
+
+    /// Returns the key as the player in `role` would press it on the shared keyboard of local
+    /// co-op, so either player can move with WASD or the arrow keys: the host's movement keys
+    /// become WASD and the guest's become the arrow keys.
+    #[must_use]
+    pub fn get_coop_code(&self, role: NetRole) -> KeyCode {
+        match (role, self.code) {
+            (NetRole::Host, NetKeyCode::Up) => KeyCode::Char('w'),
+            (NetRole::Host, NetKeyCode::Left) => KeyCode::Char('a'),
+            (NetRole::Host, NetKeyCode::Down) => KeyCode::Char('s'),
+            (NetRole::Host, NetKeyCode::Right) => KeyCode::Char('d'),
+            (NetRole::Guest, NetKeyCode::Char('w') | NetKeyCode::Up) => KeyCode::Up,
+            (NetRole::Guest, NetKeyCode::Char('a') | NetKeyCode::Left) => KeyCode::Left,
+            (NetRole::Guest, NetKeyCode::Char('s') | NetKeyCode::Down) => KeyCode::Down,
+            (NetRole::Guest, NetKeyCode::Char('d') | NetKeyCode::Right) => KeyCode::Right,
+            (_, NetKeyCode::Char(c)) => KeyCode::Char(c),
+            (_, NetKeyCode::Enter) => KeyCode::Enter,
+            (_, NetKeyCode::Esc) => KeyCode::Esc,
+            (_, NetKeyCode::Tab) => KeyCode::Tab,
+            (_, NetKeyCode::Backspace) => KeyCode::Backspace,
+        }
+    }
+
+    /// Returns the key event for [`NetKey::get_coop_code`].
+    #[must_use]
+    pub fn get_coop_event(&self, role: NetRole) -> KeyEvent {
+        let code = self.get_coop_code(role);
+
+        #[cfg(not(target_family = "wasm"))]
+        let key_event = KeyEvent::from(code);
+        #[cfg(target_family = "wasm")]
+        let key_event = KeyEvent {
+            code,
+            ctrl: false,
+            alt: false,
+            shift: false,
+        };
+
+        key_event
+    }
+}
+
+/// A message between the two players, passed on by the relay.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum NetMessage {
+    /// Sent by the guest once connected, asking the host to start.
+    Join,
+    /// The host's answer to a join, with the seed and save the run is played with.
+    Start {
+        seed: u64,
+        player_state: Box<PlayerState>,
+    },
+    /// One player's keys for a turn, and a checksum of their run when the turn was scheduled.
+    Turn {
+        turn: u64,
+        keys: Vec<NetKey>,
+        checksum: u64,
+    },
+}
+
+/// Swaps keys between the two players a turn at a time. Keys are scheduled a few turns ahead,
+/// so turns only wait on the other player when the connection is slower than the delay.
+pub struct Lockstep {
+    /// How many turns ahead keys are scheduled.
+    delay: u64,
+    /// The next turn to run.
+    next_turn: u64,
+    local: BTreeMap<u64, Vec<NetKey>>,
+    remote: BTreeMap<u64, Vec<NetKey>>,
+    /// Keys pressed since the last turn was scheduled.
+    pending: Vec<NetKey>,
+    /// Checksums from one player, kept until the other player's checksum for the turn arrives.
+    checksums: BTreeMap<u64, u64>,
+    desynced: bool,
+}
+
+impl Lockstep {
+    /// The default delay, 150ms at 20 ticks a second.
+    pub const DELAY: u64 = 3;
+
+    #[must_use]
+    pub fn new(delay: u64) -> Self {
+        // nobody has pressed anything before the first scheduled turn
+        let empty: BTreeMap<u64, Vec<NetKey>> = (0..delay).map(|turn| (turn, vec![])).collect();
+
+        Self {
+            delay,
+            next_turn: 0,
+            local: empty.clone(),
+            remote: empty,
+            pending: vec![],
+            checksums: BTreeMap::new(),
+            desynced: false,
+        }
+    }
+
+    /// Holds `key` for the next scheduled turn.
+    pub fn press(&mut self, key: NetKey) {
+        self.pending.push(key);
+    }
+
+    /// Schedules the keys pressed since the last turn for `delay` turns from now, returning the
+    /// message to send, unless that turn has already been scheduled while waiting.
+    pub fn schedule(&mut self, checksum: u64) -> Option<NetMessage> {
+        let turn = self.next_turn + self.delay;
+        if self.local.contains_key(&turn) {
+            return None;
+        }
+
+        let keys = std::mem::take(&mut self.pending);
+        self.local.insert(turn, keys.clone());
+        self.check(turn, checksum);
+
+        Some(NetMessage::Turn {
+            turn,
+            keys,
+            checksum,
+        })
+    }
+
+    /// Takes in the other player's keys for `turn`.
+    pub fn receive(&mut self, turn: u64, keys: Vec<NetKey>, checksum: u64) {
+        self.remote.insert(turn, keys);
+        self.check(turn, checksum);
+    }
+
+    /// Takes this player's and the other player's keys for the next turn, once both are in.
+    pub fn advance(&mut self) -> Option<(Vec<NetKey>, Vec<NetKey>)> {
+        if !self.local.contains_key(&self.next_turn) || !self.remote.contains_key(&self.next_turn) {
+            return None;
+        }
+
+        let local = self.local.remove(&self.next_turn)?;
+        let remote = self.remote.remove(&self.next_turn)?;
+        self.next_turn += 1;
+
+        Some((local, remote))
+    }
+
+    /// Checks whether the two players' runs have ever been seen to differ.
+    #[must_use]
+    pub fn is_desynced(&self) -> bool {
+        self.desynced
+    }
+
+    fn check(&mut self, turn: u64, checksum: u64) {
+        match self.checksums.remove(&turn) {
+            Some(other) => self.desynced |= other != checksum,
+            None => {
+                self.checksums.insert(turn, checksum);
+            }
+        }
+    }
+}
+
+/// How far a session has got.
+#[derive(Debug, Clone, Default)]
+pub enum SessionStatus {
+    #[default]
+    Connecting,
+    /// Connected, and waiting for the other player.
+    Waiting,
+    /// Both players are in, and the run can start on the map built from `seed`.
+    Started { seed: u64 },
+    /// The connection closed, with the reason why.
+    Closed(String),
+}
+
+/// A connection to the other player through a room on the relay.
+pub struct NetSession {
+    socket: Socket,
+    pub role: NetRole,
+    pub room: String,
+    status: SessionStatus,
+    /// The host's save. The host keeps a copy to send, and the guest receives it.
+    host_state: Option<PlayerState>,
+    /// Messages for the run, kept until it takes them.
+    inbox: Vec<NetMessage>,
+}
+
+impl NetSession {
+    /// Opens a new room, with a random code, to play `player_state` in.
+    #[must_use]
+    pub fn host(config: &NetplayConfig, player_state: PlayerState) -> Self {
+        let room = format!("{:04}", rand::rng().random_range(0..10_000));
+        Self::connect(config, NetRole::Host, room, Some(player_state))
+    }
+
+    /// Joins the room with the code `room`.
+    #[must_use]
+    pub fn join(config: &NetplayConfig, room: String) -> Self {
+        Self::connect(config, NetRole::Guest, room, None)
+    }
+
+    fn connect(
+        config: &NetplayConfig,
+        role: NetRole,
+        room: String,
+        host_state: Option<PlayerState>,
+    ) -> Self {
+        Self {
+            socket: Socket::connect(&config.get_room_url(&room)),
+            role,
+            room,
+            status: SessionStatus::Connecting,
+            host_state,
+            inbox: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn get_status(&self) -> &SessionStatus {
+        &self.status
+    }
+
+    /// Returns the seed of the run, once both players are in.
+    #[must_use]
+    pub fn get_seed(&self) -> Option<u64> {
+        match self.status {
+            SessionStatus::Started { seed } => Some(seed),
+            _ => None,
+        }
+    }
+
+    /// Takes the host's save, once the guest has received it.
+    pub fn take_host_state(&mut self) -> Option<PlayerState> {
+        match self.role {
+            NetRole::Host => None,
+            NetRole::Guest => self.host_state.take(),
+        }
+    }
+
+    pub fn send(&self, message: &NetMessage) {
+        if let Ok(text) = serde_json::to_string(message) {
+            self.socket.send(text);
+        }
+    }
+
+    /// Handles whatever has arrived on the socket, starting the run once both players are in.
+    pub fn poll(&mut self) {
+        for event in self.socket.poll() {
+            match event {
+                SocketEvent::Open => {
+                    self.status = SessionStatus::Waiting;
+                    if self.role == NetRole::Guest {
+                        self.send(&NetMessage::Join);
+                    }
+                }
+                SocketEvent::Message(text) => match serde_json::from_str(&text) {
+                    Ok(message) => self.receive(message),
+                    Err(err) => tracing::warn!(%err, "unreadable netplay message"),
+                },
+                SocketEvent::Closed(reason) => self.status = SessionStatus::Closed(reason),
+            }
+        }
+    }
+
+    /// Takes the messages meant for the run.
+    pub fn take_messages(&mut self) -> Vec<NetMessage> {
+        std::mem::take(&mut self.inbox)
+    }
+
+    fn receive(&mut self, message: NetMessage) {
+        match message {
+            NetMessage::Join if self.role == NetRole::Host => {
+                if let (SessionStatus::Waiting, Some(player_state)) =
+                    (&self.status, &self.host_state)
+                {
+                    let seed = rand::random();
+                    self.send(&NetMessage::Start {
+                        seed,
+                        player_state: Box::new(player_state.clone()),
+                    });
+                    self.status = SessionStatus::Started { seed };
+                }
+            }
+            NetMessage::Start { seed, player_state } if self.role == NetRole::Guest => {
+                self.host_state = Some(*player_state);
+                self.status = SessionStatus::Started { seed };
+            }
+            NetMessage::Join | NetMessage::Start { .. } => {}
+            NetMessage::Turn { .. } => self.inbox.push(message),
+        }
+    }
+}
+
+/// What a run should do about the next turn.
+pub enum NetTurn {
+    /// The other player's keys haven't arrived yet.
+    Waiting,
+    /// Both players' keys for the turn, to replay in this order.
+    Ready {
+        host: Vec<NetKey>,
+        guest: Vec<NetKey>,
+    },
+    /// The other player has gone, with the reason why.
+    Closed(String),
+}
+
+/// A run's side of networked co-op.
+pub struct Netplay {
+    session: NetSession,
+    lockstep: Lockstep,
+    /// Whether going out of sync has been reported.
+    desync_reported: bool,
+}
+
+impl Netplay {
+    #[must_use]
+    pub fn new(session: NetSession) -> Self {
+        Self {
+            session,
+            lockstep: Lockstep::new(Lockstep::DELAY),
+            desync_reported: false,
+        }
+    }
+
+    #[must_use]
+    pub fn get_role(&self) -> NetRole {
+        self.session.role
+    }
+
+    /// Holds a key this player pressed, or let go of with `released`, for the next turn.
+    pub fn press(&mut self, key_event: &KeyEvent, released: bool) {
+        if let Some(key) = NetKey::from_event(key_event, released) {
+            self.lockstep.press(key);
+        }
+    }
+
+    /// Swaps keys with the other player, returning both players' keys for the next turn once
+    /// they're in. `checksum` sums up the run as it is now.
+    pub fn exchange(&mut self, checksum: u64) -> NetTurn {
+        self.session.poll();
+        if let SessionStatus::Closed(reason) = self.session.get_status() {
+            return NetTurn::Closed(reason.clone());
+        }
+
+        for message in self.session.take_messages() {
+            if let NetMessage::Turn {
+                turn,
+                keys,
+                checksum,
+            } = message
+            {
+                self.lockstep.receive(turn, keys, checksum);
+            }
+        }
+
+        if let Some(message) = self.lockstep.schedule(checksum) {
+            self.session.send(&message);
+        }
+
+        match (self.lockstep.advance(), self.session.role) {
+            (None, _) => NetTurn::Waiting,
+            (Some((host, guest)), NetRole::Host) | (Some((guest, host)), NetRole::Guest) => {
+                NetTurn::Ready { host, guest }
+            }
+        }
+    }
+
+    /// Checks whether the runs have just gone out of sync, only answering yes the first time.
+    pub fn take_desync(&mut self) -> bool {
+        let desynced = self.lockstep.is_desynced() && !self.desync_reported;
+        self.desync_reported |= desynced;
+        desynced
+    }
+}
+
+/// Hashes a run into a checksum with 64-bit FNV-1a. Unlike the standard library's hashers, it
+/// gives the same result for the same run on every build and platform: numbers are always hashed
+/// as little-endian, and `usize`s as 64 bits.
+#[derive(Debug, Clone, Copy)]
+pub struct ChecksumHasher(u64);
+
+impl ChecksumHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+}
+
+impl Default for ChecksumHasher {
+    fn default() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+}
+
+impl Hasher for ChecksumHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ u64::from(*byte)).wrapping_mul(Self::PRIME);
+        }
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::hash::{Hash, Hasher};
+
+    use crate::common::netplay::{
+        ChecksumHasher, Lockstep, NetKey, NetKeyCode, NetMessage, NetRole,
+    };
+    use crate::prelude::KeyCode;
+
+    fn send(message: Option<NetMessage>, to: &mut Lockstep) {
+        if let Some(NetMessage::Turn {
+            turn,
+            keys,
+            checksum,
+        }) = message
+        {
+            to.receive(turn, keys, checksum);
+        }
+    }
+
+    #[test]
+    fn turns_wait_for_both_players() {
+        let mut host = Lockstep::new(2);
+        let mut guest = Lockstep::new(2);
+        let key = NetKey {
+            code: NetKeyCode::Up,
+            released: false,
+        };
+
+        // the host's key lands two turns later, after turns nobody could press anything in
+        host.press(key);
+        send(host.schedule(0), &mut guest);
+        send(guest.schedule(0), &mut host);
+        assert_eq!(host.advance(), Some((vec![], vec![])));
+        assert_eq!(guest.advance(), Some((vec![], vec![])));
+
+        send(host.schedule(0), &mut guest);
+        let late = guest.schedule(1);
+        assert_eq!(host.advance(), Some((vec![], vec![])));
+        assert_eq!(guest.advance(), Some((vec![], vec![])));
+
+        assert_eq!(host.advance(), Some((vec![key], vec![])));
+        assert_eq!(guest.advance(), Some((vec![], vec![key])));
+
+        // the next turn waits on the guest, whose run turns out to have drifted
+        send(host.schedule(0), &mut guest);
+        assert_eq!(host.advance(), None);
+        assert!(!host.is_desynced());
+
+        send(late, &mut host);
+        assert_eq!(host.advance(), Some((vec![], vec![])));
+        assert!(host.is_desynced());
+    }
+
+    #[test]
+    fn each_player_moves_their_own_character() {
+        let up = NetKey {
+            code: NetKeyCode::Up,
+            released: false,
+        };
+        let w = NetKey {
+            code: NetKeyCode::Char('w'),
+            released: false,
+        };
+
+        assert_eq!(up.get_coop_code(NetRole::Host), KeyCode::Char('w'));
+        assert_eq!(w.get_coop_code(NetRole::Host), KeyCode::Char('w'));
+        assert_eq!(up.get_coop_code(NetRole::Guest), KeyCode::Up);
+        assert_eq!(w.get_coop_code(NetRole::Guest), KeyCode::Up);
+    }
+
+    #[test]
+    fn checksums_are_stable() {
+        let mut hasher = ChecksumHasher::default();
+        42_u64.hash(&mut hasher);
+        (-7_i32).hash(&mut hasher);
+
+        assert_eq!(hasher.finish(), 0xa248_9c02_5ba6_a555);
+    }
+}
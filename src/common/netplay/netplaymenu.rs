@@ -0,0 +1,183 @@
+//! This module provides the screen for hosting or joining a networked co-op room, reachable from
+//! the upgrades menu.
+
+use ratatui::{
+    Frame,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Paragraph},
+};
+
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::netplay::{NetRole, NetSession, NetplayConfig, SessionStatus};
+use crate::common::{Goto, PlayerStateRef, View};
+use crate::prelude::{KeyCode, KeyEvent};
+
+/// A struct that manages the state and rendering of the co-op screen.
+pub struct NetplayMenu {
+    config: Option<NetplayConfig>,
+    player_state: PlayerStateRef,
+    /// The room code typed in so far.
+    room_input: String,
+    session: Option<NetSession>,
+    pub goto: Goto,
+}
+
+impl NetplayMenu {
+    /// How many digits a room code has.
+    pub const ROOM_CODE_LEN: usize = 4;
+
+    #[must_use]
+    pub fn new(player_state: PlayerStateRef) -> Self {
+        Self {
+            config: NetplayConfig::from_env(),
+            player_state,
+            room_input: String::new(),
+            session: None,
+            goto: Goto::Netplay,
+        }
+    }
+
+    /// Takes the session, once both players are in, for the run to be played over.
+    pub fn take_session(&mut self) -> Option<NetSession> {
+        self.session
+            .take_if(|session| matches!(session.get_status(), SessionStatus::Started { .. }))
+    }
+
+    /// Handles key events for the co-op screen. Digits type the room code, and other keys are
+    /// handled as the actions they're bound to.
+    pub fn key_event(&mut self, key_event: &KeyEvent) {
+        if let KeyCode::Char(c) = key_event.code
+            && c.is_ascii_digit()
+        {
+            if self.session.is_none() && self.room_input.len() < Self::ROOM_CODE_LEN {
+                self.room_input.push(c);
+            }
+        } else if let Some(action) = KeyContext::Netplay.get_action(&key_event.code) {
+            self.on_action(action);
+        }
+    }
+
+    /// Handles actions for the co-op screen.
+    pub fn on_action(&mut self, action: Action) {
+        match action {
+            Action::Back if self.session.is_some() => self.session = None,
+            Action::Back => self.goto = Goto::Upgrades,
+            _ if self.session.is_some() => {}
+            Action::Host => {
+                if let Some(config) = &self.config {
+                    let player_state = self.player_state.borrow().clone();
+                    self.session = Some(NetSession::host(config, player_state));
+                }
+            }
+            Action::Delete => {
+                self.room_input.pop();
+            }
+            Action::Confirm => {
+                if let Some(config) = &self.config
+                    && self.room_input.len() == Self::ROOM_CODE_LEN
+                {
+                    let room = std::mem::take(&mut self.room_input);
+                    self.session = Some(NetSession::join(config, room));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Builds the lines of the co-op screen for the current status.
+    #[must_use]
+    pub fn get_lines(&self) -> Vec<Line<'static>> {
+        if self.config.is_none() {
+            return vec![Line::from("no co-op relay configured".dark_gray())];
+        }
+
+        let Some(session) = &self.session else {
+            return vec![
+                Line::from("<H> host a room"),
+                Line::from(""),
+                Line::from(format!(
+                    "or type a room code and press <Enter> to join: {:_<width$}",
+                    self.room_input,
+                    width = Self::ROOM_CODE_LEN
+                )),
+            ];
+        };
+
+        match (session.get_status(), session.role) {
+            (SessionStatus::Connecting, _) => {
+                vec![Line::from(
+                    format!("connecting to room {}...", session.room).dark_gray(),
+                )]
+            }
+            (SessionStatus::Waiting, NetRole::Host) => vec![
+                Line::from(format!("room {}", session.room).bold()),
+                Line::from("waiting for player 2, share the code with them".dark_gray()),
+            ],
+            (SessionStatus::Waiting, NetRole::Guest) => {
+                vec![Line::from(
+                    format!("waiting for the host of room {}...", session.room).dark_gray(),
+                )]
+            }
+            (SessionStatus::Started { .. }, _) => vec![Line::from("starting...".dark_gray())],
+            (SessionStatus::Closed(reason), _) => vec![
+                Line::from("couldn't connect to the room".red()),
+                Line::from(reason.clone().dark_gray()),
+            ],
+        }
+    }
+
+    /// Renders the co-op screen to the frame.
+    pub fn render_netplay(&self, frame: &mut Frame) {
+        let title = Line::from(" dispair.co-op ".bold());
+        let instructions = if self.session.is_some() {
+            Line::from(vec![" <Esc> Leave the room ".into()])
+        } else {
+            Line::from(vec![
+                " <H> Host | <0-9> Room code | <Enter> Join | <Esc> Back ".into(),
+            ])
+        };
+
+        let window = Block::bordered()
+            .border_set(border::THICK)
+            .title(title.left_aligned())
+            .title_bottom(instructions.left_aligned());
+
+        let netplay = Paragraph::new(self.get_lines()).block(window);
+
+        frame.render_widget(netplay, frame.area());
+    }
+}
+
+impl View for NetplayMenu {
+    fn on_tick(&mut self) {
+        if let Some(session) = &mut self.session {
+            session.poll();
+
+            if matches!(session.get_status(), SessionStatus::Started { .. }) {
+                self.goto = Goto::Game;
+            }
+        }
+    }
+
+    fn transition(&self) -> &Goto {
+        &self.goto
+    }
+
+    fn take_session(&mut self) -> Option<NetSession> {
+        NetplayMenu::take_session(self)
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.render_netplay(frame);
+    }
+
+    fn handle_key_event(&mut self, key_event: &KeyEvent) {
+        self.key_event(key_event);
+    }
+
+    fn get_key_context(&self) -> KeyContext {
+        KeyContext::Netplay
+    }
+}
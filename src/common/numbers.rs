@@ -0,0 +1,81 @@
+//! This module formats large numbers like gold and upgrade costs so they stay readable once they
+//! grow past a handful of digits.
+
+use crate::common::settings::setting::NumberFormat;
+
+/// Suffixes for each power of a thousand, starting at a thousand.
+const SUFFIXES: [&str; 12] = [
+    "K", "M", "B", "T", "Qa", "Qi", "Sx", "Sp", "Oc", "No", "Dc", "Ud",
+];
+
+/// Formats a number in the given style. Numbers under a thousand are always shown in full.
+///
+/// Shortened numbers are rounded down, so a shortened cost is never shown as affordable when it
+/// isn't.
+#[must_use]
+pub fn format_number(value: impl Into<u128>, format: NumberFormat) -> String {
+    let value = value.into();
+
+    if value < 1000 {
+        return value.to_string();
+    }
+
+    match format {
+        NumberFormat::Full => value.to_string(),
+        NumberFormat::Short => {
+            let tier = (value.ilog10() / 3) as usize;
+            let suffix = SUFFIXES[(tier - 1).min(SUFFIXES.len() - 1)];
+            let scaled = value as f64 / 1000f64.powi(tier.min(SUFFIXES.len()) as i32);
+
+            format!("{}{suffix}", truncate_to_sig_figs(scaled))
+        }
+        NumberFormat::Scientific => {
+            let exponent = value.ilog10();
+            let mantissa = value as f64 / 10f64.powi(exponent as i32);
+
+            format!("{}e{exponent}", truncate_to_sig_figs(mantissa))
+        }
+    }
+}
+
+/// Rounds a number of at least 1 down to three significant figures, dropping trailing zeros.
+fn truncate_to_sig_figs(value: f64) -> String {
+    let decimals = match value {
+        v if v < 10. => 2,
+        v if v < 100. => 1,
+        _ => 0,
+    };
+
+    let scale = 10f64.powi(decimals);
+    let truncated = (value * scale).floor() / scale;
+
+    let text = format!("{truncated:.*}", decimals as usize);
+    if text.contains('.') {
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::numbers::format_number;
+    use crate::common::settings::setting::NumberFormat;
+
+    #[test]
+    fn numbers_shorten() {
+        assert_eq!(format_number(999u32, NumberFormat::Short), "999");
+        assert_eq!(format_number(1000u32, NumberFormat::Short), "1K");
+        assert_eq!(format_number(1_240_000u32, NumberFormat::Short), "1.24M");
+        assert_eq!(format_number(3_100_000_000u64, NumberFormat::Short), "3.1B");
+        assert_eq!(format_number(999_999u32, NumberFormat::Short), "999K");
+        assert_eq!(format_number(45_678u32, NumberFormat::Short), "45.6K");
+        assert_eq!(format_number(u128::MAX, NumberFormat::Short), "340Ud");
+
+        assert_eq!(
+            format_number(1_240_000u32, NumberFormat::Scientific),
+            "1.24e6"
+        );
+        assert_eq!(format_number(1_240_000u32, NumberFormat::Full), "1240000");
+    }
+}
@@ -0,0 +1,148 @@
+//! This module defines on-kill effects: things a METHOD or CHARM sets off when its damage kills an
+//! enemy. Each effect becomes a staged damage area, so it deals its damage through the same sweep
+//! as any other staged attack, and never sets off further effects itself.
+
+use ratatui::style::Style;
+use serde::{Deserialize, Serialize};
+
+use crate::common::character::{Damageable, Renderable};
+use crate::common::coords::{Area, AreaWrapper, ChaosArea, Position, SquareArea};
+use crate::common::enemies::enemy::{Enemy, move_to_point_granular};
+use crate::common::entities::EntityCharacters;
+use crate::common::map::Layer;
+use crate::common::weapons::DamageArea;
+use crate::prelude::Duration;
+
+/// An effect set off where an enemy was killed.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum OnKillEffect {
+    /// Leaves a patch of fire that burns enemies in it a few times, each for `damage_mult` of the
+    /// killing hit's damage.
+    FirePatch { size: i32, damage_mult: f64 },
+    /// Sends a free bolt to the nearest enemy, dealing `damage_mult` of the killing hit's damage.
+    ChainBolt { damage_mult: f64 },
+}
+
+impl OnKillEffect {
+    /// How long a fire patch burns for.
+    pub const FIRE_PATCH_DURATION: Duration = Duration::from_millis(1500);
+    /// How many times a fire patch burns the enemies in it.
+    pub const FIRE_PATCH_BURNS: usize = 3;
+
+    /// Builds the damage area for this effect, set off by `killer` killing an enemy at
+    /// `position`. Returns `None` if the effect has nothing to hit, e.g. a bolt with no enemy left.
+    #[must_use]
+    pub fn get_damage_area(
+        &self,
+        killer: &DamageArea,
+        position: &Position,
+        enemies: &[Enemy],
+        layer: &Layer,
+    ) -> Option<DamageArea> {
+        match *self {
+            OnKillEffect::FirePatch { size, damage_mult } => {
+                let (x, y) = position.get();
+                let mut area = SquareArea {
+                    corner1: Position(x - size, y - size),
+                    corner2: Position(x + size, y + size),
+                };
+                area.constrain(layer);
+
+                Some(DamageArea {
+                    damage_amount: Self::scale_damage(killer, damage_mult),
+                    area: AreaWrapper::Square(area.clone()),
+                    entity: EntityCharacters::BurningGround(Style::new().red()),
+                    duration: Self::FIRE_PATCH_DURATION,
+                    blink: false,
+                    destroys_walls: false,
+                    stages: vec![AreaWrapper::Square(area); Self::FIRE_PATCH_BURNS],
+                    weapon_stats: None,
+                    source: killer.source,
+                })
+            }
+            OnKillEffect::ChainBolt { damage_mult } => {
+                let target = enemies
+                    .iter()
+                    .filter(|enemy| enemy.is_alive() && enemy.get_pos() != position)
+                    .min_by_key(|enemy| {
+                        let (dist_x, dist_y) = enemy.get_pos().get_distance(position);
+                        dist_x.abs() + dist_y.abs()
+                    })?;
+
+                let mut positions = vec![];
+                let mut current_pos = position.clone();
+                while &current_pos != target.get_pos() {
+                    (current_pos, _) =
+                        move_to_point_granular(&current_pos, target.get_pos(), false);
+                    positions.push(current_pos.clone());
+                }
+
+                let mut area = ChaosArea::new(positions);
+                area.constrain(layer);
+                let area = AreaWrapper::Chaos(area);
+
+                Some(DamageArea {
+                    damage_amount: Self::scale_damage(killer, damage_mult),
+                    area: area.clone(),
+                    entity: EntityCharacters::AttackMist(Style::new().light_yellow()),
+                    duration: Duration::from_secs_f64(0.1),
+                    blink: false,
+                    destroys_walls: false,
+                    stages: vec![area],
+                    weapon_stats: None,
+                    source: killer.source,
+                })
+            }
+        }
+    }
+
+    fn scale_damage(killer: &DamageArea, damage_mult: f64) -> i32 {
+        (f64::from(killer.damage_amount) * damage_mult)
+            .ceil()
+            .max(1.) as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::style::Style;
+
+    use crate::common::coords::Position;
+    use crate::common::enemies::enemy::{Enemy, EnemyBehaviour, EnemyDrops};
+    use crate::common::entities::EntityCharacters;
+    use crate::common::onkill::OnKillEffect;
+    use crate::common::weapons::DamageArea;
+
+    #[test]
+    fn chain_bolt_reaches_the_nearest_enemy() {
+        let layer = vec![vec![EntityCharacters::Empty(Style::new()); 10]; 10];
+        let drops = EnemyDrops {
+            gold: 1,
+            xp: 0,
+            position: Position::default(),
+            chest: false,
+        };
+        let enemies = vec![
+            Enemy::new(Position(8, 8), 1, 3, drops.clone()),
+            Enemy::new(Position(2, 4), 1, 3, drops),
+        ];
+        let killer = DamageArea {
+            damage_amount: 4,
+            ..DamageArea::new_empty()
+        };
+
+        let bolt = OnKillEffect::ChainBolt { damage_mult: 0.5 }
+            .get_damage_area(&killer, &Position(2, 2), &enemies, &layer)
+            .expect("an enemy to chain to");
+
+        assert_eq!(bolt.damage_amount, 2);
+        assert!(Position(2, 4).is_in_area(bolt.area.get_inner()));
+        assert!(!Position(8, 8).is_in_area(bolt.area.get_inner()));
+        assert_eq!(bolt.stages.len(), 1);
+        assert!(
+            OnKillEffect::ChainBolt { damage_mult: 0.5 }
+                .get_damage_area(&killer, &Position(2, 2), &[], &layer)
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! This module provides the performance overlay, toggled with F3, which shows how long ticks and
+//! frames take and how much is on screen, so slow terminals can be diagnosed.
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::prelude::{Duration, Instant, KeyCode};
+
+/// How many of each kind of thing a view is currently drawing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EntityCounts {
+    pub enemies: usize,
+    /// Enemies held back by the enemy cap, waiting to be sent in.
+    pub pressure: u64,
+    pub pickups: usize,
+    pub turrets: usize,
+    pub effects: usize,
+    /// Damage areas waiting to land on a later tick.
+    pub scheduled: usize,
+}
+
+/// Smoothed timings for ticks and frames.
+#[derive(Debug, Clone, Default)]
+pub struct PerfStats {
+    tick_ms: f64,
+    frame_ms: f64,
+    render_ms: f64,
+    frame_interval_ms: f64,
+    last_frame_at: Option<Instant>,
+}
+
+impl PerfStats {
+    /// The key that shows and hides the overlay.
+    pub const TOGGLE_KEY: KeyCode = KeyCode::F(3);
+
+    /// How much each new sample moves the smoothed timings.
+    const SMOOTHING: f64 = 0.1;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records how long a tick took to run.
+    pub fn record_tick(&mut self, duration: Duration) {
+        self.tick_ms = smooth(self.tick_ms, duration);
+    }
+
+    /// Records how long a frame's update took, and the time since the previous frame.
+    pub fn record_frame(&mut self, duration: Duration) {
+        self.frame_ms = smooth(self.frame_ms, duration);
+
+        let now = Instant::now();
+        if let Some(last_frame_at) = self.last_frame_at {
+            self.frame_interval_ms = smooth(self.frame_interval_ms, now - last_frame_at);
+        }
+        self.last_frame_at = Some(now);
+    }
+
+    /// Records how long drawing a frame took.
+    pub fn record_render(&mut self, duration: Duration) {
+        self.render_ms = smooth(self.render_ms, duration);
+    }
+
+    /// Returns the frames per second, going by the smoothed time between frames.
+    #[must_use]
+    pub fn get_fps(&self) -> f64 {
+        if self.frame_interval_ms > 0. {
+            1000. / self.frame_interval_ms
+        } else {
+            0.
+        }
+    }
+
+    #[must_use]
+    pub fn get_lines(&self, counts: Option<EntityCounts>) -> Vec<Line<'static>> {
+        let mut lines = vec![
+            Line::from(format!("fps    {:>7.1}", self.get_fps())),
+            Line::from(format!("tick   {:>6.2}ms", self.tick_ms)),
+            Line::from(format!("frame  {:>6.2}ms", self.frame_ms)),
+            Line::from(format!("render {:>6.2}ms", self.render_ms)),
+        ];
+
+        if let Some(counts) = counts {
+            lines.push(Line::from(format!("enemies {:>6}", counts.enemies)));
+            lines.push(Line::from(format!("waiting {:>6}", counts.pressure)));
+            lines.push(Line::from(format!("pickups {:>6}", counts.pickups)));
+            lines.push(Line::from(format!("turrets {:>6}", counts.turrets)));
+            lines.push(Line::from(format!("effects {:>6}", counts.effects)));
+            lines.push(Line::from(format!("queued  {:>6}", counts.scheduled)));
+        }
+
+        lines
+    }
+
+    /// Renders the overlay in the bottom-left corner of `area`.
+    pub fn render(&self, frame: &mut Frame, area: Rect, counts: Option<EntityCounts>) {
+        let lines = self.get_lines(counts);
+
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let width = 20.min(area.width);
+        let overlay_area = Rect::new(area.x, area.bottom() - height, width, height);
+
+        let overlay = Paragraph::new(lines).block(
+            Block::bordered()
+                .title(" perf ".dark_gray())
+                .border_set(border::PLAIN),
+        );
+
+        frame.render_widget(Clear, overlay_area);
+        frame.render_widget(overlay, overlay_area);
+    }
+}
+
+/// Moves a smoothed millisecond timing towards a new sample.
+fn smooth(average_ms: f64, sample: Duration) -> f64 {
+    let sample_ms = sample.as_secs_f64() * 1000.;
+
+    if average_ms == 0. {
+        sample_ms
+    } else {
+        average_ms + (sample_ms - average_ms) * PerfStats::SMOOTHING
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::perf::PerfStats;
+    use crate::prelude::Duration;
+
+    #[test]
+    fn timings_are_smoothed() {
+        let mut perf = PerfStats::new();
+
+        perf.record_tick(Duration::from_millis(10));
+        perf.record_tick(Duration::from_millis(20));
+
+        assert!((perf.tick_ms - 11.).abs() < 1e-9);
+    }
+}
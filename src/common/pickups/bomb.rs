@@ -0,0 +1,70 @@
+//! This module defines the `Bomb` pickup, an occasional enemy drop.
+//! Collecting a bomb blasts every enemy nearby for a large share of their max health.
+
+use ratatui::style::{Color, Style};
+
+use crate::common::character::Renderable;
+use crate::common::coords::Position;
+use crate::common::entities::EntityCharacters;
+use crate::common::pickups::{PickupEffect, Pickupable};
+
+/// Represents a bomb that detonates when the player collects it.
+pub struct Bomb {
+    /// The visual character of the bomb.
+    pub entity_char: EntityCharacters,
+    /// The position of the bomb in the game world.
+    pub position: Position,
+
+    pub picked_up: bool,
+}
+
+impl Bomb {
+    /// Chance out of 1000 for a killed enemy to drop a bomb.
+    pub const DROP_CHANCE: u32 = 3;
+    /// How far the blast reaches from the bomb in each direction.
+    pub const BLAST_RADIUS: i32 = 12;
+    /// The share of each enemy's max health dealt as damage.
+    pub const MAX_HEALTH_DAMAGE: f64 = 0.75;
+
+    /// Creates a new `Bomb` at the specified position.
+    #[must_use]
+    pub fn new(position: Position) -> Self {
+        Bomb {
+            entity_char: EntityCharacters::Bomb(Style::new().fg(Color::LightRed)),
+            position,
+            picked_up: false,
+        }
+    }
+}
+
+impl Renderable for Bomb {
+    fn get_pos(&self) -> &Position {
+        &self.position
+    }
+
+    fn get_entity_char(&self) -> &EntityCharacters {
+        &self.entity_char
+    }
+}
+
+impl Pickupable for Bomb {
+    /// Animates the bomb by flickering its fuse between red and yellow every 4 ticks.
+    fn animate(&mut self, tick: u64) {
+        if !tick.is_multiple_of(4) {
+        } else if let EntityCharacters::Bomb(style) = &mut self.entity_char {
+            *style = match style.fg {
+                Some(Color::LightRed) => style.fg(Color::LightYellow),
+                _ => style.fg(Color::LightRed),
+            };
+        }
+    }
+
+    fn on_pickup(&mut self) -> PickupEffect {
+        self.picked_up = true;
+        PickupEffect::Bomb(self.position.clone())
+    }
+
+    fn is_picked_up(&self) -> bool {
+        self.picked_up
+    }
+}
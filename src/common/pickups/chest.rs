@@ -0,0 +1,68 @@
+//! This module defines the `Chest` pickup, a rare drop from elite enemies.
+//! Opening a chest grants several powerups at once.
+
+use rand::Rng;
+use ratatui::style::{Color, Style};
+
+use crate::common::character::Renderable;
+use crate::common::coords::Position;
+use crate::common::entities::EntityCharacters;
+use crate::common::pickups::{PickupEffect, Pickupable};
+use crate::common::runrng;
+
+/// Represents a treasure chest that the player can collect.
+pub struct Chest {
+    /// The visual character of the chest.
+    pub entity_char: EntityCharacters,
+    /// The position of the chest in the game world.
+    pub position: Position,
+
+    pub picked_up: bool,
+}
+
+impl Chest {
+    const MAX_REWARDS: usize = 3;
+
+    /// Creates a new `Chest` at the specified position.
+    #[must_use]
+    pub fn new(position: Position) -> Self {
+        Chest {
+            entity_char: EntityCharacters::Chest(Style::new().yellow().bold()),
+            position,
+            picked_up: false,
+        }
+    }
+}
+
+impl Renderable for Chest {
+    fn get_pos(&self) -> &Position {
+        &self.position
+    }
+
+    fn get_entity_char(&self) -> &EntityCharacters {
+        &self.entity_char
+    }
+}
+
+impl Pickupable for Chest {
+    /// Animates the chest by glinting between yellow and white every 10 ticks.
+    fn animate(&mut self, tick: u64) {
+        if !tick.is_multiple_of(10) {
+        } else if let EntityCharacters::Chest(style) = &mut self.entity_char {
+            *style = match style.fg {
+                Some(Color::Yellow) => style.fg(Color::White),
+                _ => style.fg(Color::Yellow),
+            };
+        }
+    }
+
+    /// Picks up the chest, rolling how many powerups it holds.
+    fn on_pickup(&mut self) -> PickupEffect {
+        self.picked_up = true;
+        PickupEffect::Chest(runrng::rng().random_range(1..=Self::MAX_REWARDS))
+    }
+
+    fn is_picked_up(&self) -> bool {
+        self.picked_up
+    }
+}
@@ -0,0 +1,78 @@
+//! This module defines the `GoldGem` pickup, dropped by enemies on death.
+//! Gems hold gold which is only banked once the player collects them.
+
+use ratatui::style::Style;
+
+use crate::common::character::Renderable;
+use crate::common::coords::Position;
+use crate::common::entities::EntityCharacters;
+use crate::common::pickups::{PickupEffect, Pickupable};
+
+/// Represents a gem worth some amount of gold.
+pub struct GoldGem {
+    /// The visual character of the gem, which depends on its value.
+    pub entity_char: EntityCharacters,
+    /// The position of the gem in the game world.
+    pub position: Position,
+
+    pub value: u128,
+
+    pub picked_up: bool,
+}
+
+impl GoldGem {
+    /// Creates a new `GoldGem` worth `value` gold at the specified position.
+    #[must_use]
+    pub fn new(position: Position, value: u128) -> Self {
+        GoldGem {
+            entity_char: Self::get_tier_char(value),
+            position,
+            value,
+            picked_up: false,
+        }
+    }
+
+    /// Adds `value` gold to the gem, upgrading its glyph if it reaches a new tier.
+    pub fn merge(&mut self, value: u128) {
+        self.value = self.value.saturating_add(value);
+        self.entity_char = Self::get_tier_char(self.value);
+    }
+
+    /// Returns the glyph for a gem of the given value.
+    fn get_tier_char(value: u128) -> EntityCharacters {
+        match value {
+            0..10 => EntityCharacters::GoldGem(Style::new().yellow(), "∙"),
+            10..100 => EntityCharacters::GoldGem(Style::new().light_yellow(), "◆"),
+            _ => EntityCharacters::GoldGem(Style::new().light_yellow().bold(), "◈"),
+        }
+    }
+
+    /// Moves the gem one step towards `target`.
+    pub fn step_towards(&mut self, target: &Position) {
+        let (dx, dy) = self.position.get_distance(target);
+        self.position = Position::new(self.position.0 + dx.signum(), self.position.1 + dy.signum());
+    }
+}
+
+impl Renderable for GoldGem {
+    fn get_pos(&self) -> &Position {
+        &self.position
+    }
+
+    fn get_entity_char(&self) -> &EntityCharacters {
+        &self.entity_char
+    }
+}
+
+impl Pickupable for GoldGem {
+    fn animate(&mut self, _tick: u64) {}
+
+    fn on_pickup(&mut self) -> PickupEffect {
+        self.picked_up = true;
+        PickupEffect::Gold(self.value)
+    }
+
+    fn is_picked_up(&self) -> bool {
+        self.picked_up
+    }
+}
@@ -1,8 +1,14 @@
 use crate::common::character::Renderable;
 use crate::common::coords::Position;
 use crate::common::entities::EntityCharacters;
+use crate::common::pickups::bomb::Bomb;
+use crate::common::pickups::chest::Chest;
+use crate::common::pickups::goldgem::GoldGem;
 use crate::common::pickups::poweruporb::PowerupOrb;
 
+pub mod bomb;
+pub mod chest;
+pub mod goldgem;
 pub mod pickupwrangler;
 pub mod poweruporb;
 
@@ -18,20 +24,29 @@ pub trait Pickupable: Renderable {
 
 pub enum PickupTypes {
     PowerupOrb(PowerupOrb),
+    Chest(Chest),
+    Bomb(Bomb),
+    GoldGem(GoldGem),
 }
 
 impl PickupTypes {
     #[must_use]
-    pub fn get_inner(&self) -> &impl Pickupable {
+    pub fn get_inner(&self) -> &dyn Pickupable {
         match self {
             PickupTypes::PowerupOrb(orb) => orb,
+            PickupTypes::Chest(chest) => chest,
+            PickupTypes::Bomb(bomb) => bomb,
+            PickupTypes::GoldGem(gem) => gem,
         }
     }
 
     #[must_use]
-    pub fn get_inner_mut(&mut self) -> &mut impl Pickupable {
+    pub fn get_inner_mut(&mut self) -> &mut dyn Pickupable {
         match self {
             PickupTypes::PowerupOrb(orb) => orb,
+            PickupTypes::Chest(chest) => chest,
+            PickupTypes::Bomb(bomb) => bomb,
+            PickupTypes::GoldGem(gem) => gem,
         }
     }
 }
@@ -49,4 +64,10 @@ impl Renderable for PickupTypes {
 #[derive(Debug, Clone)]
 pub enum PickupEffect {
     PowerupOrb,
+    /// Grants the given number of powerups at once.
+    Chest(usize),
+    /// Blows up enemies around the given position.
+    Bomb(Position),
+    /// Banks the given amount of gold.
+    Gold(u128),
 }
@@ -1,28 +1,39 @@
 use crate::common::PlayerStateRef;
-use crate::common::character::Renderable;
-use crate::common::coords::AreaWrapper::Square;
-use crate::common::coords::{Position, SquareArea};
+use crate::common::coords::Position;
 use crate::common::effects::DamageEffect;
 use crate::common::entities::EntityCharacters;
 use crate::common::map::Layer;
-use crate::common::pickups::Pickupable;
+use crate::common::pickups::bomb::Bomb;
+use crate::common::pickups::chest::Chest;
+use crate::common::pickups::goldgem::GoldGem;
 use crate::common::pickups::poweruporb::PowerupOrb;
 use crate::common::pickups::{PickupEffect, PickupTypes};
+use crate::common::runrng;
 use crate::common::utils::get_rand_position_on_layer;
 use crate::prelude::Duration;
+use rand::Rng;
 use ratatui::prelude::Style;
 
 pub struct PickupWrangler {
     pub player_state: PlayerStateRef,
     pub pickups: Vec<PickupTypes>,
     pub start_popup: bool,
+    /// The number of powerups waiting to be granted from an opened chest.
+    pub chest_rewards: Option<usize>,
+    /// Positions of bombs collected this tick, waiting to be detonated.
+    pub bomb_detonations: Vec<Position>,
 }
 
 impl PickupWrangler {
+    /// How many frames the ring from a collected powerup orb spreads out over.
+    const ORB_RING_FRAMES: u32 = 12;
+
     pub fn new(player_state: PlayerStateRef) -> Self {
         PickupWrangler {
             player_state,
             start_popup: false,
+            chest_rewards: None,
+            bomb_detonations: Vec::new(),
             pickups: Vec::new(),
         }
     }
@@ -36,6 +47,61 @@ impl PickupWrangler {
         }
     }
 
+    /// Drops a chest at the given position.
+    pub fn spawn_chest(&mut self, position: Position) {
+        self.pickups.push(PickupTypes::Chest(Chest::new(position)));
+    }
+
+    /// Has a small chance to drop a bomb at the given position.
+    pub fn roll_bomb_drop(&mut self, position: &Position) {
+        if runrng::rng().random_ratio(Bomb::DROP_CHANCE, 1000) {
+            self.pickups
+                .push(PickupTypes::Bomb(Bomb::new(position.clone())));
+        }
+    }
+
+    /// Drops a gold gem worth `value` at the given position. If another gem is already lying on or
+    /// next to that spot, the gold is merged into it instead to keep dense piles cheap.
+    pub fn drop_gold(&mut self, position: &Position, value: u128) {
+        if value == 0 {
+            return;
+        }
+
+        let nearby_gem = self.pickups.iter_mut().find_map(|pickup| match pickup {
+            PickupTypes::GoldGem(gem) => {
+                let (dx, dy) = gem.position.get_distance(position);
+                (dx.abs() <= 1 && dy.abs() <= 1).then_some(gem)
+            }
+            _ => None,
+        });
+
+        if let Some(gem) = nearby_gem {
+            gem.merge(value);
+        } else {
+            self.pickups
+                .push(PickupTypes::GoldGem(GoldGem::new(position.clone(), value)));
+        }
+    }
+
+    /// Pulls gold gems within the pickup radius one step towards the nearest character.
+    fn magnetize_gems(&mut self, char_positions: &[Position]) {
+        let pickup_radius = self.player_state.borrow().stats.player_stats.pickup_radius;
+
+        self.pickups.iter_mut().for_each(|pickup| {
+            if let PickupTypes::GoldGem(gem) = pickup {
+                let nearest = char_positions
+                    .iter()
+                    .map(|char_pos| (char_pos, gem.position.get_distance(char_pos)))
+                    .filter(|(_, (dx, dy))| dx.abs() <= pickup_radius && dy.abs() <= pickup_radius)
+                    .min_by_key(|(_, (dx, dy))| dx.abs() + dy.abs());
+
+                if let Some((char_pos, _)) = nearest {
+                    gem.step_towards(char_pos);
+                }
+            }
+        });
+    }
+
     pub fn handle_pickups(
         &mut self,
         char_pos: &Position,
@@ -47,23 +113,28 @@ impl PickupWrangler {
 
                 match effect {
                     PickupEffect::PowerupOrb => {
-                        let area = SquareArea::new(
-                            Position(0, 0),
-                            Position(
-                                self.player_state.borrow().stats.game_stats.width as i32,
-                                self.player_state.borrow().stats.game_stats.height as i32,
-                            ),
-                        );
-
-                        active_damage_effects.push(DamageEffect::new(
-                            Square(area),
-                            EntityCharacters::AttackWeak(Style::new().red()),
+                        let game_stats = &self.player_state.borrow().stats.game_stats;
+
+                        active_damage_effects.push(DamageEffect::expanding_ring(
+                            char_pos,
+                            game_stats.width as i32,
+                            game_stats.height as i32,
+                            Self::ORB_RING_FRAMES,
+                            &EntityCharacters::AttackWeak(Style::new().red()),
                             Duration::from_secs_f64(0.5),
-                            false,
                         ));
 
                         self.start_popup = true;
                     }
+                    PickupEffect::Chest(rewards) => {
+                        *self.chest_rewards.get_or_insert(0) += rewards;
+                    }
+                    PickupEffect::Bomb(position) => {
+                        self.bomb_detonations.push(position);
+                    }
+                    PickupEffect::Gold(value) => {
+                        self.player_state.borrow_mut().inventory.add_gold(value);
+                    }
                 }
             }
         });
@@ -75,10 +146,13 @@ impl PickupWrangler {
     pub fn on_tick(
         &mut self,
         tickcount: u64,
-        char_pos: &Position,
+        char_positions: &[Position],
         active_damage_effects: &mut Vec<DamageEffect>,
     ) {
-        self.handle_pickups(char_pos, active_damage_effects);
+        self.magnetize_gems(char_positions);
+        for char_pos in char_positions {
+            self.handle_pickups(char_pos, active_damage_effects);
+        }
 
         self.pickups
             .iter_mut()
@@ -1,30 +1,70 @@
 use ratatui::{
     Frame,
+    layout::{Constraint, Layout},
+    style::Stylize,
     symbols::border,
-    text::Line,
-    widgets::{Block, Clear},
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
 };
+use strum::{EnumIter, IntoEnumIterator, IntoStaticStr};
 
-use crate::common::utils::{center_horizontal, center_vertical};
 use crate::common::{
+    challenge::ChallengeResult,
+    deathrecap::DeathRecap,
+    keybinds::Action,
+    mutators::mutator::{Mutator, get_total_gold_mult},
+    numbers::format_number,
     popups::popup_area,
+    runstats::RunStats,
     upgrades::upgrade::{PlayerState, PlayerStateDiff},
 };
 
+/// Where to go once the player is done with the carnage report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, IntoStaticStr)]
+pub enum GameOverChoice {
+    /// Start another run straight away, with the same build on the same map.
+    #[default]
+    Retry,
+    Upgrades,
+    #[strum(serialize = "Main Menu")]
+    MainMenu,
+}
+
 /// Displays the results of a game level to the player.
 #[derive(Clone)]
 pub struct CarnageReport {
     prev_player_state: PlayerState,
     new_player_state: PlayerState,
+    run_stats: RunStats,
+    /// How the character died, if the run ended in death rather than on the timer.
+    death_recap: Option<DeathRecap>,
+    /// The run's challenge code and how it compares to earlier runs on it.
+    challenge: Option<ChallengeResult>,
+    scroll: u16,
+    pub selection: GameOverChoice,
 }
 
 impl CarnageReport {
+    /// How many rows of braille the timeline sparklines are drawn with.
+    const SPARKLINE_ROWS: usize = 3;
+
     /// Creates a new `CarnageReport`.
     #[must_use]
-    pub fn new(prev_player_state: PlayerState, new_player_state: PlayerState) -> Self {
+    pub fn new(
+        prev_player_state: PlayerState,
+        new_player_state: PlayerState,
+        run_stats: RunStats,
+        death_recap: Option<DeathRecap>,
+        challenge: Option<ChallengeResult>,
+    ) -> Self {
         Self {
             prev_player_state,
             new_player_state,
+            run_stats,
+            death_recap,
+            challenge,
+            scroll: 0,
+            selection: GameOverChoice::default(),
         }
     }
 
@@ -34,27 +74,206 @@ impl CarnageReport {
         self.new_player_state.clone() - self.prev_player_state.clone()
     }
 
+    /// Handles actions for scrolling the report and choosing where to go next. Returns the
+    /// choice once it has been confirmed.
+    pub fn handle_action(&mut self, action: Action) -> Option<GameOverChoice> {
+        match action {
+            Action::Up => self.scroll = self.scroll.saturating_sub(1),
+            Action::Down => self.scroll = self.scroll.saturating_add(1),
+            Action::Left => self.cycle_selection(-1),
+            Action::Right => self.cycle_selection(1),
+            Action::Retry => return Some(GameOverChoice::Retry),
+            Action::Confirm => return Some(self.selection),
+            Action::Back => return Some(GameOverChoice::Upgrades),
+            _ => {}
+        }
+        None
+    }
+
+    /// Moves the selected choice by `offset`, wrapping around at either end.
+    fn cycle_selection(&mut self, offset: isize) {
+        let choices: Vec<GameOverChoice> = GameOverChoice::iter().collect();
+        let current = choices
+            .iter()
+            .position(|choice| *choice == self.selection)
+            .unwrap_or(0);
+
+        self.selection =
+            choices[(current as isize + offset).rem_euclid(choices.len() as isize) as usize];
+    }
+
+    /// Builds the line of choices, with the selected one highlighted.
+    fn get_choices_line(&self) -> Line<'static> {
+        let spans: Vec<Span> = GameOverChoice::iter()
+            .map(|choice| {
+                let name: &'static str = choice.into();
+                if choice == self.selection {
+                    format!(" >{name}< ").bold().light_red()
+                } else {
+                    format!("  {name}  ").into()
+                }
+            })
+            .collect();
+
+        Line::from(spans).centered()
+    }
+
+    /// Builds every line of the report, from the totals and death recap down to the damage table.
+    #[must_use]
+    pub fn get_lines(&self) -> Vec<Line<'static>> {
+        let state_diff = self.get_diff();
+        let run_stats = &self.run_stats;
+        let number_format = self.new_player_state.settings.number_format;
+
+        let mut lines = vec![
+            Line::from(vec![
+                "Gold: ".into(),
+                format_number(state_diff.inventory.gold, number_format).into(),
+            ]),
+            Line::from(vec![
+                "Kills: ".into(),
+                format_number(run_stats.get_total_kills(), number_format).into(),
+            ]),
+            Line::from(vec![
+                "Highest hit: ".into(),
+                format_number(
+                    u128::try_from(run_stats.highest_hit).unwrap_or_default(),
+                    number_format,
+                )
+                .into(),
+            ]),
+        ];
+
+        if run_stats.loops > 0 {
+            lines.push(Line::from(vec![
+                "Loops: ".into(),
+                run_stats.loops.to_string().light_yellow(),
+            ]));
+        }
+
+        let mutators = &self.new_player_state.mutators;
+        if !mutators.is_empty() {
+            let names: Vec<&str> = mutators.iter().map(Mutator::get_name).collect();
+            lines.push(Line::from(vec![
+                "Mutators: ".into(),
+                names.join(", ").into(),
+                format!(" (x{:.2} gold)", get_total_gold_mult(mutators)).light_yellow(),
+            ]));
+        }
+
+        if let Some(challenge) = &self.challenge {
+            lines.push(Line::from(""));
+            lines.extend(challenge.get_lines());
+        }
+
+        if let Some(death_recap) = &self.death_recap {
+            lines.push(Line::from(""));
+            lines.extend(death_recap.get_lines());
+        }
+
+        for (title, timeline) in [
+            ("kills", &run_stats.kills_timeline),
+            ("gold", &run_stats.gold_timeline),
+        ] {
+            lines.push(Line::from(""));
+            lines.push(Line::from(
+                format!("{title} per {}s", RunStats::BUCKET_SECS).dark_gray(),
+            ));
+            lines.extend(
+                braille_sparkline(timeline, Self::SPARKLINE_ROWS)
+                    .into_iter()
+                    .map(|row| Line::from(row.light_red())),
+            );
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from("damage by METHOD".dark_gray()));
+
+        let mut damage: Vec<(&&str, &u64)> = run_stats.damage_by_weapon.iter().collect();
+        damage.sort_by(|a, b| b.1.cmp(a.1));
+        let total_damage: u64 = damage.iter().map(|(_, amount)| **amount).sum();
+
+        for (weapon, amount) in damage {
+            let share = *amount as f64 / total_damage.max(1) as f64 * 100.;
+            lines.push(Line::from(format!(
+                "{:<10} {amount:>8} {share:>5.1}%",
+                weapon.to_uppercase()
+            )));
+        }
+
+        lines
+    }
+
     /// Renders the carnage report to the screen.
     pub fn render(&self, frame: &mut Frame) {
-        let area = popup_area(frame.area(), 50, 30);
+        let area = popup_area(frame.area(), 50, 60);
 
         let popup = Block::bordered()
             .border_set(border::PLAIN)
             .title(" Carnage Report ")
-            .title_bottom(Line::from(vec![" <ESC> Upgrades ".into()]))
+            .title_bottom(Line::from(vec![
+                " <W|S> Scroll | <A|D> Choose | <ENTER> Confirm | <R> Retry | <ESC> Upgrades "
+                    .into(),
+            ]))
             .title_alignment(ratatui::layout::Alignment::Center);
 
-        let inner_area = center_vertical(center_horizontal(popup.inner(area), 10), 1);
+        let [report_area, choices_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(popup.inner(area));
 
-        let state_diff = self.get_diff();
-
-        let inner = Line::from(vec![
-            "Gold: ".into(),
-            state_diff.inventory.gold.to_string().into(),
-        ]);
+        let report = Paragraph::new(self.get_lines())
+            .centered()
+            .scroll((self.scroll, 0));
 
         frame.render_widget(Clear, area);
         frame.render_widget(popup, area);
-        frame.render_widget(inner, inner_area);
+        frame.render_widget(report, report_area);
+        frame.render_widget(self.get_choices_line(), choices_area);
+    }
+}
+
+/// Draws `values` as a braille bar graph `rows` characters tall, two values per character.
+/// Returns the rows from top to bottom.
+#[must_use]
+pub fn braille_sparkline(values: &[u64], rows: usize) -> Vec<String> {
+    // Dot bits for the left and right columns of a braille cell, from the bottom dot up.
+    const LEFT_DOTS: [u32; 4] = [0x40, 0x04, 0x02, 0x01];
+    const RIGHT_DOTS: [u32; 4] = [0x80, 0x20, 0x10, 0x08];
+
+    let max = values.iter().copied().max().unwrap_or(0).max(1);
+    let levels = rows * 4;
+    let heights: Vec<usize> = values
+        .iter()
+        .map(|value| (*value as f64 / max as f64 * levels as f64).ceil() as usize)
+        .collect();
+
+    (0..rows)
+        .rev()
+        .map(|row| {
+            heights
+                .chunks(2)
+                .map(|pair| {
+                    let mut cell = 0x2800;
+                    for (column, dots) in [LEFT_DOTS, RIGHT_DOTS].iter().enumerate() {
+                        let filled = pair
+                            .get(column)
+                            .map_or(0, |height| height.saturating_sub(row * 4).min(4));
+                        cell |= dots[..filled].iter().sum::<u32>();
+                    }
+                    char::from_u32(cell).unwrap_or(' ')
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::popups::carnagereport::braille_sparkline;
+
+    #[test]
+    fn braille_sparkline_fills_from_bottom() {
+        let rows = braille_sparkline(&[0, 8, 4], 2);
+
+        assert_eq!(rows, vec!["⢸⠀", "⢸⡇"]);
     }
 }
@@ -0,0 +1,174 @@
+//! This module provides the popup shown when opening a treasure chest.
+//! A chest grants several powerups at once, revealed one by one like a slot machine.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Cell, Clear, Row, Table},
+};
+
+use crate::common::PlayerStateRef;
+use crate::{
+    common::{
+        charms::CharmWrapper,
+        keybinds::Action,
+        popups::{
+            popup_area,
+            poweruppopup::{apply_powerup, roll_powerup_choices},
+        },
+        powerup::DynPowerup,
+        stats::WeaponStats,
+        weapons::WeaponWrapper,
+    },
+    prelude::{Duration, Instant},
+};
+
+pub struct ChestPopup {
+    rewards: Vec<DynPowerup>,
+    reel_names: Vec<String>,
+    opened_at: Instant,
+    skip_reveal: bool,
+    pub weapons: Vec<WeaponWrapper>,
+    pub charms: Vec<CharmWrapper>,
+    pub finished: bool,
+}
+
+impl ChestPopup {
+    const REVEAL_START: Duration = Duration::from_millis(600);
+    const REVEAL_INTERVAL: Duration = Duration::from_millis(500);
+    const REEL_SPIN_MILLIS: u128 = 80;
+
+    /// Creates a new `ChestPopup`, rolling and applying up to `reward_count` powerups.
+    /// Each reward is rolled after the previous one is applied, so a chest can level the same
+    /// weapon or charm more than once.
+    #[must_use]
+    pub fn new(
+        current_weapons: &[WeaponWrapper],
+        current_charms: &[CharmWrapper],
+        weapon_stats: &WeaponStats,
+        player_state: &PlayerStateRef,
+        reward_count: usize,
+    ) -> Self {
+        let mut weapons = Vec::from(current_weapons);
+        let mut charms = Vec::from(current_charms);
+
+        let reel_names = roll_powerup_choices(&weapons, &charms, player_state)
+            .iter()
+            .map(|powerup| powerup.get_name().to_string())
+            .collect();
+
+        let mut rewards = Vec::new();
+        for _ in 0..reward_count {
+            let Some(powerup) = roll_powerup_choices(&weapons, &charms, player_state)
+                .into_iter()
+                .next()
+            else {
+                break;
+            };
+
+            apply_powerup(
+                &powerup,
+                &mut weapons,
+                &mut charms,
+                weapon_stats,
+                player_state,
+            );
+            rewards.push(powerup);
+        }
+
+        Self {
+            finished: rewards.is_empty(),
+            rewards,
+            reel_names,
+            opened_at: Instant::now(),
+            skip_reveal: false,
+            weapons,
+            charms,
+        }
+    }
+
+    /// Checks if the reward in the given slot has finished spinning.
+    #[must_use]
+    pub fn is_revealed(&self, slot: usize) -> bool {
+        self.skip_reveal
+            || self.opened_at.elapsed() >= Self::REVEAL_START + Self::REVEAL_INTERVAL * slot as u32
+    }
+
+    pub fn handle_action(&mut self, action: Action) {
+        if action == Action::Confirm {
+            if self.is_revealed(self.rewards.len().saturating_sub(1)) {
+                self.finished = true;
+            } else {
+                self.skip_reveal = true;
+            }
+        }
+    }
+
+    pub fn render(&self, frame: &mut Frame) {
+        let area = popup_area(frame.area(), 80, 60);
+
+        let instructions = if self.is_revealed(self.rewards.len().saturating_sub(1)) {
+            " <ENTER|SPACE> Collect "
+        } else {
+            " <ENTER|SPACE> Skip "
+        };
+
+        let popup = Block::bordered()
+            .border_set(border::PLAIN)
+            .title(" Treasure Chest ")
+            .title_bottom(Line::from(instructions))
+            .title_alignment(ratatui::layout::Alignment::Center);
+
+        let inner_area = popup.inner(area);
+
+        let widths: Vec<Constraint> = self
+            .rewards
+            .iter()
+            .map(|_| Constraint::Ratio(1, self.rewards.len() as u32))
+            .collect();
+
+        let cell_width = Layout::horizontal(widths.clone())
+            .split(inner_area)
+            .first()
+            .map_or(inner_area.width, |rect| rect.width);
+
+        let elapsed_millis = self.opened_at.elapsed().as_millis();
+
+        let cells: Vec<Cell> = self
+            .rewards
+            .iter()
+            .enumerate()
+            .map(|(slot, reward)| {
+                if !self.is_revealed(slot) && !self.reel_names.is_empty() {
+                    let reel_index = (elapsed_millis / Self::REEL_SPIN_MILLIS) as usize + slot;
+                    let name = &self.reel_names[reel_index % self.reel_names.len()];
+
+                    return Cell::from(Text::from(name.clone().dark_gray()).centered());
+                }
+
+                let level = format!(
+                    "Level {} -> {}",
+                    reward.get_current_level(),
+                    reward.get_new_level()
+                );
+                let reward_text = [reward.get_name(), reward.get_desc(), level.as_str()].join("\n");
+
+                let lines: Vec<Line> = textwrap::wrap(reward_text.as_str(), cell_width as usize)
+                    .iter()
+                    .map(|line| Line::from(line.to_string()))
+                    .collect();
+
+                Cell::from(Text::from(lines).centered()).style(Style::new().light_yellow())
+            })
+            .collect();
+
+        let table = Table::new([Row::new(cells).height(inner_area.height)], widths);
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+        frame.render_widget(table, inner_area);
+    }
+}
@@ -0,0 +1,235 @@
+//! This module provides the debug console, a popup for typing commands that change the current
+//! run, for tuning and reproducing bugs. It's only opened in debug builds.
+
+use std::str::FromStr;
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::common::statsheet::StatKey;
+use crate::common::weapons::WeaponWrapper;
+use crate::prelude::{KeyCode, KeyEvent};
+
+/// A command typed into the debug console.
+pub enum DebugCommand {
+    /// Spawns this many enemies around the map.
+    Spawn(usize),
+    /// Adds this much gold to the inventory.
+    Gold(u128),
+    /// Sets how far the difficulty has scaled.
+    Doom(f64),
+    /// Gives the character a METHOD at the given level, or sets its level if already held.
+    Weapon(WeaponWrapper, i32),
+    /// Shows how a stat was worked out, from its base and each modifier on it.
+    Stat(StatKey),
+    /// Switches on or off taking no damage.
+    God,
+    /// Opens the powerup popup, as the old debug key did.
+    Powerup,
+    /// Ends the run straight away.
+    GameOver,
+    Help,
+}
+
+impl DebugCommand {
+    /// Every command, as shown by `help`.
+    pub const USAGE: [&str; 9] = [
+        "spawn <count>",
+        "gold <amount>",
+        "doom <scale>",
+        "weapon <name> [level]",
+        "stat <name>",
+        "god",
+        "powerup",
+        "gameover",
+        "help",
+    ];
+}
+
+impl FromStr for DebugCommand {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut words = input.split_whitespace();
+        let name = words.next().unwrap_or_default().to_lowercase();
+        let args: Vec<&str> = words.collect();
+
+        let arg = |index: usize| {
+            args.get(index)
+                .copied()
+                .ok_or_else(|| format!("`{name}` needs more arguments, try `help`"))
+        };
+
+        match name.as_str() {
+            "spawn" => parse_arg(arg(0)?).map(DebugCommand::Spawn),
+            "gold" => parse_arg(arg(0)?).map(DebugCommand::Gold),
+            "doom" => parse_arg(arg(0)?)
+                .and_then(|doom: f64| {
+                    if doom.is_finite() && doom > 0. {
+                        Ok(doom)
+                    } else {
+                        Err(format!("`{doom}` isn't a positive number"))
+                    }
+                })
+                .map(DebugCommand::Doom),
+            "weapon" => {
+                let weapon_name = arg(0)?;
+                let weapon = WeaponWrapper::from_str(&weapon_name.to_uppercase())
+                    .map_err(|_| format!("there's no METHOD called `{weapon_name}`"))?;
+                let level = args.get(1).map_or(Ok(1), |level| parse_arg(level))?;
+
+                Ok(DebugCommand::Weapon(weapon, level))
+            }
+            "stat" => {
+                let stat_name = arg(0)?;
+                StatKey::from_str(&stat_name.to_lowercase())
+                    .map(DebugCommand::Stat)
+                    .map_err(|_| format!("there's no stat called `{stat_name}`"))
+            }
+            "god" => Ok(DebugCommand::God),
+            "powerup" => Ok(DebugCommand::Powerup),
+            "gameover" => Ok(DebugCommand::GameOver),
+            "help" | "" => Ok(DebugCommand::Help),
+            _ => Err(format!("unknown command `{name}`, try `help`")),
+        }
+    }
+}
+
+fn parse_arg<T: FromStr>(arg: &str) -> Result<T, String> {
+    arg.parse()
+        .map_err(|_| format!("`{arg}` isn't a valid number"))
+}
+
+/// What the debug console wants done after a key press.
+pub enum ConsoleInput {
+    /// Run this command in the current run.
+    Run(DebugCommand),
+    Close,
+}
+
+/// The debug console's typed input and the replies to earlier commands.
+#[derive(Default)]
+pub struct DebugConsole {
+    input: String,
+    /// Earlier commands and their replies, oldest first.
+    output: Vec<String>,
+    /// The last command run, so it can be brought back to run again.
+    last_command: Option<String>,
+}
+
+impl DebugConsole {
+    /// Opens the console in debug builds.
+    pub const OPEN_KEY: KeyCode = KeyCode::Char('u');
+
+    /// How many lines of output are kept.
+    const OUTPUT_LENGTH: usize = 6;
+
+    #[must_use]
+    pub fn new() -> Self {
+        let mut console = Self::default();
+        console.print("type `help` for commands");
+        console
+    }
+
+    /// Handles a key press, returning a command once one has been typed and entered.
+    pub fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<ConsoleInput> {
+        match key_event.code {
+            KeyCode::Esc => return Some(ConsoleInput::Close),
+            KeyCode::Enter => {
+                let input = std::mem::take(&mut self.input);
+                self.print(format!("> {input}"));
+
+                match input.parse() {
+                    Ok(command) => {
+                        tracing::debug!(input, "debug command");
+                        self.last_command = Some(input);
+                        return Some(ConsoleInput::Run(command));
+                    }
+                    Err(err) => self.print(err),
+                }
+            }
+            KeyCode::Backspace => {
+                self.input.pop();
+            }
+            KeyCode::Up => {
+                if let Some(last_command) = &self.last_command {
+                    self.input.clone_from(last_command);
+                }
+            }
+            KeyCode::Char(c) => self.input.push(c),
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Adds a line to the console's output.
+    pub fn print(&mut self, line: impl Into<String>) {
+        self.output.push(line.into());
+
+        let excess = self.output.len().saturating_sub(Self::OUTPUT_LENGTH);
+        self.output.drain(..excess);
+    }
+
+    /// Draws the console along the bottom of `area`.
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let height = Self::OUTPUT_LENGTH as u16 + 3;
+        let [_, console_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(height)]).areas(area);
+
+        let mut lines: Vec<Line> = self
+            .output
+            .iter()
+            .map(|line| Line::from(line.clone().dark_gray()))
+            .collect();
+        lines.push(Line::from(format!("> {}_", self.input).bold()));
+
+        let console = Paragraph::new(lines).block(
+            Block::bordered()
+                .border_set(border::PLAIN)
+                .title(" debug console ")
+                .title_bottom(" enter: run · ↑: last command · esc: close "),
+        );
+
+        frame.render_widget(Clear, console_area);
+        frame.render_widget(console, console_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::popups::debugconsole::DebugCommand;
+    use crate::common::statsheet::StatKey;
+
+    #[test]
+    fn commands_parse() {
+        assert!(matches!("spawn 20".parse(), Ok(DebugCommand::Spawn(20))));
+        assert!(matches!("GOLD 500".parse(), Ok(DebugCommand::Gold(500))));
+        assert!(matches!(
+            "weapon nova 3".parse(),
+            Ok(DebugCommand::Weapon(_, 3))
+        ));
+        assert!(matches!(
+            "weapon flash".parse(),
+            Ok(DebugCommand::Weapon(_, 1))
+        ));
+
+        assert!("spawn".parse::<DebugCommand>().is_err());
+        assert!("spawn lots".parse::<DebugCommand>().is_err());
+        assert!("doom -1".parse::<DebugCommand>().is_err());
+        assert!(matches!(
+            "stat attack_speed_mult".parse(),
+            Ok(DebugCommand::Stat(StatKey::AttackSpeedMult))
+        ));
+
+        assert!("weapon spoon".parse::<DebugCommand>().is_err());
+        assert!("stat luck".parse::<DebugCommand>().is_err());
+        assert!("dance".parse::<DebugCommand>().is_err());
+    }
+}
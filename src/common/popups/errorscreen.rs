@@ -0,0 +1,124 @@
+//! This module renders the error screen, which explains an `AppError` and lets the player pick
+//! how to recover from it.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use crate::common::apperror::{AppError, ErrorChoice};
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::popups::helpoverlay::render_help;
+use crate::common::popups::popup_area;
+use crate::prelude::{KeyCode, KeyEvent};
+
+/// Shows an error and the ways to recover from it.
+pub struct ErrorScreen {
+    pub error: AppError,
+    selection: ListState,
+    /// Whether the help overlay is open over the screen.
+    show_help: bool,
+}
+
+impl ErrorScreen {
+    #[must_use]
+    pub fn new(error: AppError) -> Self {
+        tracing::error!(%error, "showing error screen");
+
+        Self {
+            error,
+            selection: ListState::default().with_selected(Some(0)),
+            show_help: false,
+        }
+    }
+
+    /// Handles a key press, returning the player's choice once they've made one.
+    pub fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<ErrorChoice> {
+        let action = KeyContext::ErrorScreen.get_action(&key_event.code);
+
+        if self.show_help {
+            if action == Some(Action::Help) || key_event.code == KeyCode::Esc {
+                self.show_help = false;
+            }
+            None
+        } else if action == Some(Action::Help) {
+            self.show_help = true;
+            None
+        } else {
+            action.and_then(|action| self.handle_action(action))
+        }
+    }
+
+    /// Handles an action, returning the player's choice once they've made one.
+    pub fn handle_action(&mut self, action: Action) -> Option<ErrorChoice> {
+        let choices = self.error.get_choices();
+
+        match action {
+            Action::Up => self.selection.select_previous(),
+            Action::Down => {
+                let next = self.selection.selected().map_or(0, |index| index + 1);
+                self.selection.select(Some(next.min(choices.len() - 1)));
+            }
+            Action::Confirm => {
+                return self
+                    .selection
+                    .selected()
+                    .and_then(|index| choices.get(index))
+                    .copied();
+            }
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Draws the error in a popup over the current view.
+    pub fn render(&mut self, frame: &mut Frame) {
+        let area = popup_area(frame.area(), 60, 50);
+
+        let block = Block::bordered()
+            .border_set(border::DOUBLE)
+            .title(Line::from(format!(" {} ", self.error.get_title())).centered())
+            .red();
+        let inner = block.inner(area);
+
+        let choices = self.error.get_choices();
+        let [message_area, choices_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(choices.len() as u16),
+        ])
+        .margin(1)
+        .areas(inner);
+
+        let reason = match &self.error {
+            AppError::Load(reason) | AppError::Save(reason) | AppError::UpgradeTree(reason) => {
+                reason.as_str()
+            }
+        };
+        let message = Paragraph::new(Text::from(reason).reset())
+            .wrap(Wrap { trim: true })
+            .centered();
+
+        let items: Vec<ListItem> = choices
+            .iter()
+            .map(|choice| ListItem::from(self.error.get_choice_label(*choice)))
+            .collect();
+        let list = List::new(items)
+            .highlight_symbol("> ")
+            .highlight_style(Style::new().bold())
+            .reset();
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(message, message_area);
+        frame.render_stateful_widget(list, choices_area, &mut self.selection);
+
+        if self.show_help {
+            render_help(frame, KeyContext::ErrorScreen);
+        }
+    }
+}
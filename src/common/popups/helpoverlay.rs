@@ -0,0 +1,131 @@
+//! This module renders the help overlay, which lists the keybindings for whatever is on screen.
+//! The overlay is opened over the current view like any other screen.
+
+use ratatui::{
+    Frame,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Wrap},
+};
+
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::{Goto, View, popups::popup_area};
+use crate::prelude::{KeyCode, KeyEvent};
+
+/// The help, open over a view and listing that view's keybindings.
+pub struct HelpOverlay {
+    context: KeyContext,
+    goto: Goto,
+}
+
+impl HelpOverlay {
+    #[must_use]
+    pub fn new(context: KeyContext) -> Self {
+        Self {
+            context,
+            goto: Goto::Help,
+        }
+    }
+}
+
+impl View for HelpOverlay {
+    fn on_tick(&mut self) {}
+
+    fn transition(&self) -> &Goto {
+        &self.goto
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        render_help(frame, self.context);
+    }
+
+    /// Closes the help on Esc, or the key that opened it.
+    fn handle_key_event(&mut self, key_event: &KeyEvent) {
+        if key_event.code == KeyCode::Esc {
+            self.goto = Goto::Back;
+        } else if let Some(action) = self.context.get_action(&key_event.code) {
+            self.handle_action(action);
+        }
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        if action == Action::Help {
+            self.goto = Goto::Back;
+        }
+    }
+
+    /// Keys are read as the covered view's, so the help closes on the key that opened it.
+    fn get_key_context(&self) -> KeyContext {
+        self.context
+    }
+
+    fn get_presence(&self) -> String {
+        "Reading the help".into()
+    }
+}
+
+/// Renders the keybindings and HUD notes for `context` in a popup over the current view.
+pub fn render_help(frame: &mut Frame, context: KeyContext) {
+    let area = popup_area(frame.area(), 60, 80);
+
+    let keybinds = context.get_keybinds();
+    let key_width = keybinds
+        .iter()
+        .map(|bind| bind.get_keys_label().len())
+        .max()
+        .unwrap_or(0);
+
+    let mut lines: Vec<Line> = keybinds
+        .iter()
+        .map(|bind| {
+            Line::from(vec![
+                format!("{:>key_width$}  ", bind.get_keys_label()).bold(),
+                bind.desc.into(),
+            ])
+        })
+        .collect();
+
+    let hud_notes = context.get_hud_notes();
+    if !hud_notes.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from("hud".dark_gray()));
+        for (name, desc) in hud_notes {
+            lines.push(Line::from(vec![
+                format!("{name:>key_width$}  ").bold(),
+                (*desc).into(),
+            ]));
+        }
+    }
+
+    let help = Paragraph::new(lines)
+        .block(
+            Block::bordered()
+                .title(Line::from(format!(" help: {} ", context.get_name()).bold()))
+                .title_bottom(Line::from(" <?|Esc> Close ").centered())
+                .border_set(border::THICK),
+        )
+        .wrap(Wrap { trim: false });
+
+    frame.render_widget(Clear, area);
+    frame.render_widget(help, area);
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use crate::common::keybinds::KeyContext;
+    use crate::common::popups::helpoverlay::render_help;
+
+    #[test]
+    fn run_help_snapshot() {
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+        terminal
+            .draw(|frame| render_help(frame, KeyContext::Run))
+            .unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+}
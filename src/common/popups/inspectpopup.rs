@@ -0,0 +1,201 @@
+//! This module provides the inspect popup, which lists the live stats of every METHOD and CHARM
+//! held in a run, so upgrades can be checked to have actually applied.
+
+use ratatui::{
+    Frame,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::common::TICK_RATE;
+use crate::common::character::Character;
+use crate::common::charms::CharmWrapper;
+use crate::common::debuffs::Elements;
+use crate::common::keybinds::Action;
+use crate::common::popups::popup_area;
+use crate::common::statsheet::StatModifier;
+use crate::common::weapons::WeaponWrapper;
+use crate::prelude::Duration;
+
+#[derive(Default)]
+pub struct InspectPopup {
+    scroll: u16,
+    pub finished: bool,
+}
+
+impl InspectPopup {
+    pub fn handle_action(&mut self, action: Action) {
+        match action {
+            Action::Up => self.scroll = self.scroll.saturating_sub(1),
+            Action::Down => self.scroll = self.scroll.saturating_add(1),
+            Action::Back => self.finished = true,
+            _ => {}
+        }
+    }
+
+    /// Describes each METHOD in `weapons`, using `attack_ticks` for how often each attacks.
+    #[must_use]
+    pub fn get_weapon_lines(weapons: &[WeaponWrapper], attack_ticks: &[u64]) -> Vec<Line<'static>> {
+        let mut lines = vec![Line::from("METHODS".bold())];
+
+        for (i, weapon) in weapons.iter().enumerate() {
+            let inner = weapon.get_inner();
+            let stats = inner.get_stats();
+
+            lines.push(Line::from(
+                format!(" {} lv{}", inner.get_name(), inner.get_level()).bold(),
+            ));
+
+            let interval = attack_ticks.get(i).map_or_else(
+                || "-".into(),
+                |ticks| format!("{:.2}s", *ticks as f64 / TICK_RATE),
+            );
+            lines.push(Line::from(format!(
+                "   damage {}  every {interval}  size {}",
+                weapon.get_damage(),
+                stats.size
+            )));
+
+            if let Some(element) = inner.get_element() {
+                let name = match element {
+                    Elements::Flame(_) => "flame",
+                    Elements::Shock(_) => "shock",
+                };
+                lines.push(Line::from(format!(
+                    "   element {name} (honage {})",
+                    element.get_honage()
+                )));
+            }
+
+            if !stats.procs.is_empty() {
+                let procs: Vec<String> = stats
+                    .procs
+                    .iter()
+                    .map(|(id, proc)| format!("{} {}%", id.get_name(), proc.chance))
+                    .collect();
+                lines.push(Line::from(format!("   procs {}", procs.join(", "))));
+            }
+
+            if !stats.on_kill.is_empty() {
+                let mut effects: Vec<&str> = stats.on_kill.keys().map(String::as_str).collect();
+                effects.sort_unstable();
+                lines.push(Line::from(format!("   on kill {}", effects.join(", "))));
+            }
+        }
+
+        lines
+    }
+
+    /// Describes what each CHARM in `charms` is currently doing, with `still_for` as how long the
+    /// character has stood still.
+    #[must_use]
+    pub fn get_charm_lines(charms: &[CharmWrapper], still_for: Duration) -> Vec<Line<'static>> {
+        let mut lines = vec![Line::from("CHARMS".bold())];
+        if charms.is_empty() {
+            lines.push(Line::from(" none".dark_gray()));
+        }
+
+        for charm in charms {
+            let inner = charm.get_inner();
+            lines.push(Line::from(
+                format!(" {} lv{}", inner.get_name(), inner.get_level()).bold(),
+            ));
+
+            lines.extend(inner.get_modifiers().iter().map(|modifier: &StatModifier| {
+                let key: &'static str = modifier.key.into();
+                Line::from(format!("   {key} {}", modifier.describe_value()))
+            }));
+
+            if let Some(interval) = inner.get_health_drain() {
+                lines.push(Line::from(format!(
+                    "   -1 health every {:.1}s",
+                    interval.as_secs_f64()
+                )));
+            }
+
+            let reduction = inner.get_damage_reduction(still_for);
+            if reduction > 0. {
+                lines.push(Line::from(format!(
+                    "   blocking {:.0}% of damage",
+                    reduction * 100.
+                )));
+            }
+
+            if let Some(echo) = inner.get_echo() {
+                lines.push(Line::from(format!(
+                    "   {:.0}% echo chance at {:.0}% damage",
+                    echo.chance * 100.,
+                    echo.damage_mult * 100.
+                )));
+            }
+
+            lines.extend(
+                inner
+                    .get_on_kill_effects()
+                    .iter()
+                    .map(|effect| Line::from(format!("   on kill {effect:?}"))),
+            );
+        }
+
+        lines
+    }
+
+    /// Renders the popup with the live stats of `character`, whose METHODs attack every
+    /// `attack_ticks` ticks.
+    pub fn render(&self, frame: &mut Frame, character: &Character, attack_ticks: &[u64]) {
+        let area = popup_area(frame.area(), 70, 80);
+
+        let mut lines = Self::get_weapon_lines(&character.weapons, attack_ticks);
+        lines.push(Line::from(""));
+        lines.extend(Self::get_charm_lines(
+            &character.charms,
+            character.get_still_for(),
+        ));
+
+        let popup = Paragraph::new(lines).scroll((self.scroll, 0)).block(
+            Block::bordered()
+                .border_set(border::PLAIN)
+                .title(" Inspect ")
+                .title_bottom(Line::from(" <W|S> Scroll | <I|Tab|Esc> Close ").centered()),
+        );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::common::charms::CharmWrapper;
+    use crate::common::popups::inspectpopup::InspectPopup;
+    use crate::common::stats::WeaponStats;
+    use crate::common::upgrades::upgrade::PlayerState;
+    use crate::common::weapons::WeaponWrapper;
+    use crate::prelude::Duration;
+
+    #[test]
+    fn lists_live_weapon_and_charm_stats() {
+        let player_state = Rc::new(RefCell::new(PlayerState::default()));
+
+        let mut weapon = WeaponWrapper::Flash(None);
+        weapon.populate_inner(WeaponStats::default(), player_state.clone());
+        let mut charm = CharmWrapper::AttackSpeed(None);
+        charm.populate_inner(player_state);
+
+        let text: Vec<String> = InspectPopup::get_weapon_lines(&[weapon.clone()], &[20])
+            .into_iter()
+            .chain(InspectPopup::get_charm_lines(&[charm], Duration::ZERO))
+            .map(|line| line.to_string())
+            .collect();
+
+        let damage = format!("damage {}", weapon.get_damage());
+        assert!(text.iter().any(|line| line.contains(&damage)));
+        assert!(text.iter().any(|line| line.contains("every 1.00s")));
+        assert!(text.iter().any(|line| line.contains("attack_speed_mult x")));
+    }
+}
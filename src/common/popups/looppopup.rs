@@ -0,0 +1,111 @@
+//! This module provides the popup shown when the character survives the full timer, offering to
+//! loop into a harder continuation of the run instead of ending it.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::Stylize,
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, Wrap},
+};
+use strum::{EnumIter, IntoEnumIterator, IntoStaticStr};
+
+use crate::common::keybinds::Action;
+use crate::common::popups::popup_area;
+
+/// What to do once the timer has been survived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, IntoStaticStr)]
+pub enum LoopChoice {
+    /// Carry on with the same build against tougher enemies, for more gold.
+    #[default]
+    Loop,
+    #[strum(serialize = "End Run")]
+    End,
+}
+
+/// Offers to loop the run once the timer runs out.
+pub struct LoopPopup {
+    /// How many loops have been completed, counting the one just survived.
+    completed_loops: u32,
+    /// The gold multiplier the next loop would bring.
+    next_gold_mult: f64,
+    pub selection: LoopChoice,
+    /// The confirmed choice, once the player has made it.
+    pub choice: Option<LoopChoice>,
+}
+
+impl LoopPopup {
+    #[must_use]
+    pub fn new(completed_loops: u32, next_gold_mult: f64) -> Self {
+        Self {
+            completed_loops,
+            next_gold_mult,
+            selection: LoopChoice::default(),
+            choice: None,
+        }
+    }
+
+    /// Handles actions for choosing whether to loop.
+    pub fn handle_action(&mut self, action: Action) {
+        match action {
+            Action::Switch => {
+                self.selection = match self.selection {
+                    LoopChoice::Loop => LoopChoice::End,
+                    LoopChoice::End => LoopChoice::Loop,
+                };
+            }
+            Action::Confirm => self.choice = Some(self.selection),
+            Action::Back => self.choice = Some(LoopChoice::End),
+            _ => {}
+        }
+    }
+
+    /// Builds the line of choices, with the selected one highlighted.
+    fn get_choices_line(&self) -> Line<'static> {
+        let spans: Vec<Span> = LoopChoice::iter()
+            .map(|choice| {
+                let name: &'static str = choice.into();
+                if choice == self.selection {
+                    format!(" >{name}< ").bold().light_red()
+                } else {
+                    format!("  {name}  ").into()
+                }
+            })
+            .collect();
+
+        Line::from(spans).centered()
+    }
+
+    /// Renders the loop popup to the screen.
+    pub fn render(&self, frame: &mut Frame) {
+        let area = popup_area(frame.area(), 50, 30);
+
+        let popup = Block::bordered()
+            .border_set(border::DOUBLE)
+            .title(" Survived ")
+            .title_bottom(Line::from(
+                " <A|D> Choose | <ENTER> Confirm | <ESC> End Run ",
+            ))
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .light_yellow();
+
+        let [text_area, choices_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(popup.inner(area));
+
+        let text = Paragraph::new(vec![
+            Line::from(format!("loop {} survived", self.completed_loops).bold()),
+            Line::from(""),
+            Line::from("loop again with the same build?"),
+            Line::from("enemies get tougher and special enemies show up more often").dark_gray(),
+            Line::from(format!("gold x{:.2}", self.next_gold_mult).light_yellow()),
+        ])
+        .centered()
+        .wrap(Wrap { trim: true });
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+        frame.render_widget(text, text_area);
+        frame.render_widget(self.get_choices_line(), choices_area);
+    }
+}
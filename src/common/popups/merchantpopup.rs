@@ -0,0 +1,224 @@
+//! This module provides the merchant met partway through a run, who sells short-term help for the
+//! gold picked up during the run. Unlike the upgrade tree, nothing bought here outlasts the run.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph},
+};
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::common::PlayerStateRef;
+use crate::common::buffs::BuffKind;
+use crate::common::keybinds::Action;
+use crate::common::popups::popup_area;
+use crate::prelude::Duration;
+
+/// Something the merchant sells. Each can be bought once per visit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum MerchantWare {
+    Heal,
+    /// A charge for rerolling the choices in a powerup popup.
+    RerollCharge,
+    /// Attack speed raised for a while.
+    Frenzy,
+    /// A random powerup, taken through a one-reward chest.
+    Powerup,
+}
+
+impl MerchantWare {
+    /// The price before scaling with doom.
+    fn get_base_price(self) -> f64 {
+        match self {
+            MerchantWare::Heal => 100.,
+            MerchantWare::RerollCharge => 75.,
+            MerchantWare::Frenzy => 150.,
+            MerchantWare::Powerup => 250.,
+        }
+    }
+
+    #[must_use]
+    pub fn get_name(self) -> &'static str {
+        match self {
+            MerchantWare::Heal => "HEAL",
+            MerchantWare::RerollCharge => "REROLL",
+            MerchantWare::Frenzy => "FRENZY",
+            MerchantWare::Powerup => "POWERUP",
+        }
+    }
+
+    #[must_use]
+    pub fn get_desc(self) -> String {
+        match self {
+            MerchantWare::Heal => {
+                format!("restore {}% of max health", MerchantPopup::HEAL_PERCENT)
+            }
+            MerchantWare::RerollCharge => "reroll one powerup choice with <R>".to_string(),
+            MerchantWare::Frenzy => format!(
+                "x{} attack speed for {}s",
+                BuffKind::Frenzy.get_attack_speed_mult(),
+                MerchantPopup::FRENZY.as_secs()
+            ),
+            MerchantWare::Powerup => "a random powerup".to_string(),
+        }
+    }
+}
+
+/// Sells `MerchantWare`s for gold picked up this run.
+pub struct MerchantPopup {
+    player_state: PlayerStateRef,
+    /// The gold picked up this run and not yet spent, which is all the merchant will take.
+    run_gold: u128,
+    /// Scales every price, so the merchant keeps up with the gold dropped later in a run.
+    price_mult: f64,
+    selection: ListState,
+    /// The wares bought this visit, for the run to apply once the merchant is left.
+    pub bought: Vec<MerchantWare>,
+    pub finished: bool,
+}
+
+impl MerchantPopup {
+    /// The percentage of max health restored by a heal.
+    pub const HEAL_PERCENT: i32 = 50;
+    /// How long a frenzy lasts.
+    pub const FRENZY: Duration = Duration::from_secs(30);
+
+    #[must_use]
+    pub fn new(player_state: PlayerStateRef, run_gold: u128, price_mult: f64) -> Self {
+        let mut selection = ListState::default();
+        selection.select_first();
+
+        Self {
+            player_state,
+            run_gold,
+            price_mult: price_mult.max(1.),
+            selection,
+            bought: vec![],
+            finished: false,
+        }
+    }
+
+    /// Handles actions for browsing and buying wares.
+    pub fn handle_action(&mut self, action: Action) {
+        match action {
+            Action::Up => self.selection.select_previous(),
+            Action::Down => self.selection.select_next(),
+            Action::Confirm => {
+                if let Some(ware) = self
+                    .selection
+                    .selected()
+                    .and_then(|index| MerchantWare::iter().nth(index))
+                {
+                    self.buy(ware);
+                }
+            }
+            Action::Back => self.finished = true,
+            _ => {}
+        }
+    }
+
+    #[must_use]
+    pub fn get_price(&self, ware: MerchantWare) -> u128 {
+        (ware.get_base_price() * self.price_mult).round() as u128
+    }
+
+    /// Returns the gold picked up this run that is still left to spend.
+    #[must_use]
+    pub fn get_run_gold(&self) -> u128 {
+        self.run_gold
+    }
+
+    /// Buys a ware, taking its price from the player's gold. Returns whether it was bought, which
+    /// it isn't if it was already bought this visit or the run's gold can't cover it.
+    pub fn buy(&mut self, ware: MerchantWare) -> bool {
+        let price = self.get_price(ware);
+        if self.bought.contains(&ware) || price > self.run_gold {
+            return false;
+        }
+
+        self.run_gold -= price;
+        let mut player_state = self.player_state.borrow_mut();
+        player_state.inventory.gold = player_state.inventory.gold.saturating_sub(price);
+        self.bought.push(ware);
+
+        true
+    }
+
+    /// Renders the merchant popup to the screen.
+    pub fn render(&mut self, frame: &mut Frame) {
+        let area = popup_area(frame.area(), 50, 40);
+
+        let popup = Block::bordered()
+            .border_set(border::DOUBLE)
+            .title(" Merchant ")
+            .title_bottom(Line::from(" <W|S> Choose | <ENTER> Buy | <ESC> Leave "))
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .light_yellow();
+
+        let [gold_area, wares_area] =
+            Layout::vertical([Constraint::Length(2), Constraint::Fill(1)]).areas(popup.inner(area));
+
+        let gold = Paragraph::new(vec![
+            Line::from(format!("{} run gold to spend", self.run_gold)),
+            Line::from("gold from earlier runs stays in your pocket".dark_gray()),
+        ])
+        .centered();
+
+        let items: Vec<ListItem> = MerchantWare::iter()
+            .map(|ware| {
+                let price = self.get_price(ware);
+                let line = format!(" {:<8} {:<32}", ware.get_name(), ware.get_desc());
+
+                if self.bought.contains(&ware) {
+                    ListItem::from(format!("{line} sold").dark_gray())
+                } else if price > self.run_gold {
+                    ListItem::from(format!("{line} {price}g").light_red())
+                } else {
+                    ListItem::from(format!("{line} {price}g").white())
+                }
+            })
+            .collect();
+
+        let wares = List::new(items)
+            .highlight_style(Style::new().bold())
+            .highlight_symbol(">");
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(popup, area);
+        frame.render_widget(gold, gold_area);
+        frame.render_stateful_widget(wares, wares_area, &mut self.selection);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::common::popups::merchantpopup::{MerchantPopup, MerchantWare};
+    use crate::common::upgrades::upgrade::PlayerState;
+
+    #[test]
+    fn only_run_gold_is_spent() {
+        let player_state = Rc::new(RefCell::new(PlayerState::default()));
+        player_state.borrow_mut().inventory.gold = 1000;
+
+        let mut merchant = MerchantPopup::new(player_state.clone(), 200, 1.);
+
+        assert!(merchant.buy(MerchantWare::Heal));
+        assert_eq!(player_state.borrow().inventory.gold, 900);
+        assert_eq!(merchant.get_run_gold(), 100);
+
+        // bought once per visit, and the banked gold can't cover the rest
+        assert!(!merchant.buy(MerchantWare::Heal));
+        assert!(!merchant.buy(MerchantWare::Powerup));
+        assert!(merchant.buy(MerchantWare::RerollCharge));
+        assert_eq!(
+            merchant.bought,
+            vec![MerchantWare::Heal, MerchantWare::RerollCharge]
+        );
+    }
+}
@@ -1,7 +1,16 @@
 use ratatui::layout::{Constraint, Flex, Layout, Rect};
 
 pub mod carnagereport;
+pub mod chestpopup;
+pub mod debugconsole;
+pub mod errorscreen;
+pub mod helpoverlay;
+pub mod inspectpopup;
+pub mod looppopup;
+pub mod merchantpopup;
 pub mod poweruppopup;
+#[cfg(feature = "cloudsync")]
+pub mod syncconflict;
 
 /// Helper function to create a centered popup area.
 #[must_use]
@@ -4,32 +4,232 @@ use rand::seq::SliceRandom;
 use ratatui::{
     Frame,
     layout::{Constraint, Layout, Rect},
-    style::{Color, Style},
+    style::{Color, Style, Stylize},
     symbols::border,
     text::{Line, Text},
-    widgets::{Block, Cell, Clear, Row, Table, TableState},
+    widgets::{Block, Cell, Clear, Paragraph, Row, Table, TableState},
 };
 use strum::IntoEnumIterator;
 
 use crate::common::PlayerStateRef;
-use crate::{
-    common::{
-        charms::CharmWrapper,
-        popups::popup_area,
-        powerup::{DynPowerup, PowerupTypes, PowerupUpgrade},
-        stats::WeaponStats,
-        weapons::WeaponWrapper,
-    },
-    prelude::{KeyCode, KeyEvent},
+use crate::common::runrng;
+use crate::common::{
+    charms::CharmWrapper,
+    keybinds::Action,
+    popups::popup_area,
+    powerup::{DynPowerup, PowerupTypes, PowerupUpgrade},
+    stats::WeaponStats,
+    weapons::{Weapon, WeaponWrapper},
 };
 
+/// Returns every powerup currently available to the player, in a random order.
+///
+/// Held weapons and charms offer their next level, while unlocked ones that aren't held yet are
+/// offered while there is a free slot.
+#[must_use]
+pub fn roll_powerup_choices(
+    current_weapons: &[WeaponWrapper],
+    current_charms: &[CharmWrapper],
+    player_state: &PlayerStateRef,
+) -> Vec<DynPowerup> {
+    let mut choices = Vec::new();
+
+    WeaponWrapper::iter().for_each(|weapon_wrapper| {
+        // weapons already held (e.g. a class's starting METHOD) can always be levelled
+        let unlocked = weapon_wrapper.is_unlocked(&player_state.borrow());
+        if let Some(weapon) = current_weapons.iter().find(|w| *w == &weapon_wrapper) {
+            let next_upgrade = weapon.get_inner().get_next_upgrade(1);
+            if let Some(next_upgrade) = next_upgrade {
+                choices.push(next_upgrade);
+            }
+        } else if unlocked && current_weapons.len() < 3 {
+            let weapon = weapon_wrapper;
+            let powerup = PowerupUpgrade::init_weapon(weapon);
+            choices.push(Box::new(powerup));
+        }
+    });
+
+    CharmWrapper::iter().for_each(|charm_wrapper| {
+        if !charm_wrapper.is_unlocked(&player_state.borrow()) {
+            return;
+        }
+
+        if let Some(charm) = current_charms.iter().find(|c| *c == &charm_wrapper) {
+            let next_upgrade = charm.get_inner().get_next_upgrade(1);
+            if let Some(next_upgrade) = next_upgrade {
+                choices.push(next_upgrade);
+            }
+        } else if current_charms.len() < player_state.borrow().stats.game_stats.max_charms {
+            let charm = charm_wrapper;
+            let powerup = PowerupUpgrade::init_charm(charm);
+            choices.push(Box::new(powerup));
+        }
+    });
+
+    choices.shuffle(&mut runrng::rng());
+
+    choices
+}
+
+/// Applies a chosen powerup, levelling up the matching weapon or charm or adding it if not held.
+pub fn apply_powerup(
+    powerup: &DynPowerup,
+    weapons: &mut Vec<WeaponWrapper>,
+    charms: &mut Vec<CharmWrapper>,
+    base_weapon_stats: &WeaponStats,
+    player_state: &PlayerStateRef,
+) {
+    match powerup.get_powerup_type() {
+        PowerupTypes::Weapon => {
+            for weapon in weapons.iter_mut() {
+                let weapon_upper = weapon.get_inner().get_name().to_uppercase();
+                let powerup_upper = powerup.get_name().to_uppercase();
+                if weapon_upper == powerup_upper {
+                    weapon.get_inner_mut().upgrade_self(powerup);
+                }
+            }
+
+            if !weapons.iter().any(|weapon| {
+                weapon.get_inner().get_name().to_uppercase() == powerup.get_name().to_uppercase()
+            }) && let Ok(mut new_weapon) =
+                WeaponWrapper::from_str(powerup.get_name().to_uppercase().as_str())
+            {
+                new_weapon.populate_inner(base_weapon_stats.clone(), player_state.clone());
+                weapons.push(new_weapon);
+            }
+        }
+
+        PowerupTypes::Charm => {
+            for charm in charms.iter_mut() {
+                let charm_upper = charm.get_inner().get_name().to_uppercase();
+                let powerup_upper = powerup.get_name().to_uppercase();
+                if charm_upper == powerup_upper {
+                    charm.get_inner_mut().upgrade_self(powerup);
+                }
+            }
+
+            if !charms.iter().any(|charm| {
+                charm.get_inner().get_name().to_uppercase() == powerup.get_name().to_uppercase()
+            }) && let Ok(mut new_charm) =
+                CharmWrapper::from_str(powerup.get_name().to_uppercase().as_str())
+            {
+                new_charm.populate_inner(player_state.clone());
+                charms.push(new_charm);
+            }
+        }
+    }
+}
+
+/// Describes how a weapon powerup would change the weapon's damage, size and procs, by applying
+/// it to a copy of the current weapons. Charm powerups have no stat summary.
+#[must_use]
+pub fn describe_weapon_changes(
+    powerup: &DynPowerup,
+    weapons: &[WeaponWrapper],
+    base_weapon_stats: &WeaponStats,
+    player_state: &PlayerStateRef,
+) -> Vec<String> {
+    if powerup.get_powerup_type() != PowerupTypes::Weapon {
+        return vec![];
+    }
+
+    let find = |weapons: &[WeaponWrapper]| {
+        weapons
+            .iter()
+            .find(|weapon| {
+                weapon.get_inner().get_name().to_uppercase() == powerup.get_name().to_uppercase()
+            })
+            .cloned()
+    };
+
+    let before = find(weapons);
+
+    let mut upgraded = Vec::from(weapons);
+    apply_powerup(
+        powerup,
+        &mut upgraded,
+        &mut vec![],
+        base_weapon_stats,
+        player_state,
+    );
+    let Some(after) = find(&upgraded) else {
+        return vec![];
+    };
+
+    let change = |label: &str, before: Option<i32>, after: i32| match before {
+        Some(before) if before != after => format!("{label} {before} -> {after}"),
+        Some(_) | None => format!("{label} {after}"),
+    };
+
+    let before_weapon = before.as_ref().map(WeaponWrapper::get_inner);
+    let after_weapon = after.get_inner();
+
+    let mut lines = vec![
+        change(
+            "damage",
+            before_weapon.map(Weapon::get_damage),
+            after_weapon.get_damage(),
+        ),
+        change(
+            "size",
+            before_weapon.map(|weapon| weapon.get_stats().size),
+            after_weapon.get_stats().size,
+        ),
+    ];
+
+    for (id, proc) in &after_weapon.get_stats().procs {
+        let name = id.get_name();
+        let after_chance = proc.chance;
+        let before_chance = before_weapon
+            .and_then(|weapon| weapon.get_stats().procs.get(id))
+            .map(|proc| proc.chance);
+
+        lines.push(match before_chance {
+            Some(before) if before != after_chance => {
+                format!("{name} {before}% -> {after_chance}%")
+            }
+            Some(_) => format!("{name} {after_chance}%"),
+            None => format!("{name} {after_chance}% (new)"),
+        });
+    }
+
+    lines
+}
+
+/// A bonus offered in place of powerups once every METHOD and CHARM is maxed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MaxedBonus {
+    Gold,
+    Heal,
+}
+
+impl MaxedBonus {
+    /// Every bonus, in the order they're offered.
+    const ALL: [MaxedBonus; 2] = [MaxedBonus::Gold, MaxedBonus::Heal];
+}
+
+/// A choice that has been selected once and is waiting for confirmation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PendingChoice {
+    Powerup(usize),
+    Bonus(MaxedBonus),
+    Skip,
+}
+
 pub struct PowerupPopup {
     powerup_choices: Vec<DynPowerup>,
+    pending: Option<PendingChoice>,
+    /// The stat changes each choice would make, in the same order as the choices.
+    choice_changes: Vec<Vec<String>>,
     selection_state: TableState,
     pub weapons: Vec<WeaponWrapper>,
     pub charms: Vec<CharmWrapper>,
     pub base_weapon_stats: WeaponStats,
     pub finished: bool,
+    /// The bonus taken instead of a powerup, for the run to apply a heal from.
+    pub taken_bonus: Option<MaxedBonus>,
+    /// Charges left for rerolling the choices, bought from the merchant.
+    pub rerolls: u32,
     pub player_state: PlayerStateRef,
 }
 
@@ -41,217 +241,318 @@ impl PowerupPopup {
         weapon_stats: WeaponStats,
         player_state: PlayerStateRef,
     ) -> Self {
-        let mut choices = Vec::new();
-
-        WeaponWrapper::iter().for_each(|weapon_wrapper| {
-            match weapon_wrapper {
-                WeaponWrapper::Flash(_) => {}
-                WeaponWrapper::Row(_) => {
-                    if !player_state.borrow().upgrade_owned("611") {
-                        return;
-                    }
-                }
-                WeaponWrapper::Pillar(_) => {
-                    if !player_state.borrow().upgrade_owned("612") {
-                        return;
-                    }
-                }
-                WeaponWrapper::Lightning(_) => {
-                    if !player_state.borrow().upgrade_owned("613") {
-                        return;
-                    }
-                }
-            }
-            if let Some(weapon) = current_weapons.iter().find(|w| *w == &weapon_wrapper) {
-                let next_upgrade = weapon.get_inner().get_next_upgrade(1);
-                if let Some(next_upgrade) = next_upgrade {
-                    choices.push(next_upgrade);
-                }
-            } else if current_weapons.len() < 3 {
-                let weapon = weapon_wrapper;
-                let powerup = PowerupUpgrade::init_weapon(weapon);
-                choices.push(Box::new(powerup));
-            }
-        });
-
-        CharmWrapper::iter().for_each(|charm_wrapper| {
-            match charm_wrapper {
-                CharmWrapper::DamageMult(_) => {}
-                CharmWrapper::AttackSpeed(_) => {
-                    if !player_state.borrow().upgrade_owned("711") {
-                        return;
-                    }
-                }
-                CharmWrapper::DoomOffset(_) => {
-                    if !player_state.borrow().upgrade_owned("712") {
-                        return;
-                    }
-                }
-            }
-
-            if let Some(charm) = current_charms.iter().find(|c| *c == &charm_wrapper) {
-                let next_upgrade = charm.get_inner().get_next_upgrade(1);
-                if let Some(next_upgrade) = next_upgrade {
-                    choices.push(next_upgrade);
-                }
-            } else if current_charms.len() < 3 {
-                let charm = charm_wrapper;
-                let powerup = PowerupUpgrade::init_charm(charm);
-                choices.push(Box::new(powerup));
-            }
-        });
+        // with nothing left to level, the bonuses take the powerups' place
+        let mut selection_state = TableState::new();
+        selection_state.select_first();
+        selection_state.select_first_column();
+
+        let mut popup = Self {
+            finished: false,
+            taken_bonus: None,
+            rerolls: 0,
+            pending: None,
+            choice_changes: vec![],
+            weapons: Vec::from(current_weapons),
+            charms: Vec::from(current_charms),
+            selection_state,
+            powerup_choices: vec![],
+            base_weapon_stats: weapon_stats,
+            player_state,
+        };
+        popup.roll_choices();
 
-        choices.shuffle(&mut rand::rng());
+        popup
+    }
 
+    /// Rolls up to three choices from what's held, replacing any already on offer.
+    fn roll_choices(&mut self) {
+        let mut choices = roll_powerup_choices(&self.weapons, &self.charms, &self.player_state);
         let _ = choices.split_off(3.min(choices.len()));
 
-        let mut selection_state = TableState::new();
+        self.choice_changes = choices
+            .iter()
+            .map(|choice| {
+                describe_weapon_changes(
+                    choice,
+                    &self.weapons,
+                    &self.base_weapon_stats,
+                    &self.player_state,
+                )
+            })
+            .collect();
+        self.powerup_choices = choices;
+        self.pending = None;
+    }
 
-        if !choices.is_empty() {
-            selection_state.select_first();
+    /// Spends a reroll charge on a fresh set of choices.
+    pub fn reroll(&mut self) {
+        if self.rerolls == 0 || self.is_maxed() {
+            return;
         }
 
-        let finished = choices.is_empty();
+        self.rerolls -= 1;
+        self.roll_choices();
+    }
 
-        Self {
-            finished,
-            weapons: Vec::from(current_weapons),
-            charms: Vec::from(current_charms),
-            selection_state,
-            powerup_choices: choices,
-            base_weapon_stats: weapon_stats,
-            player_state,
+    /// Returns the names of the powerups on offer, in order.
+    #[must_use]
+    pub fn get_choice_names(&self) -> Vec<String> {
+        self.powerup_choices
+            .iter()
+            .map(|choice| choice.get_name().to_string())
+            .collect()
+    }
+
+    /// Checks whether everything held is maxed and nothing new can be picked up, so bonuses are
+    /// offered instead of powerups.
+    #[must_use]
+    pub fn is_maxed(&self) -> bool {
+        self.powerup_choices.is_empty()
+    }
+
+    /// How many choices are laid out side by side.
+    fn get_column_count(&self) -> usize {
+        if self.is_maxed() {
+            MaxedBonus::ALL.len()
+        } else {
+            self.powerup_choices.len()
         }
     }
 
-    pub fn handle_key_event(&mut self, key_event: &KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('d') | KeyCode::Right => self.selection_state.select_next_column(),
-            KeyCode::Char('a') | KeyCode::Left => self.selection_state.select_previous_column(),
-            KeyCode::Enter | KeyCode::Char(' ') => self.select_current(),
+    /// Gold granted per level of held METHODs and CHARMs when skipping the choice.
+    const SKIP_GOLD_PER_LEVEL: f64 = 50.;
+
+    /// How many times the skip gold the gold bonus is worth, once everything is maxed.
+    const MAXED_GOLD_MULT: u128 = 2;
+
+    /// The percentage of max health restored by the heal bonus.
+    pub const MAXED_HEAL_PERCENT: i32 = 50;
+
+    pub fn handle_action(&mut self, action: Action) {
+        match action {
+            Action::Right => {
+                self.pending = None;
+                self.selection_state.select_next_column();
+            }
+            Action::Left => {
+                self.pending = None;
+                self.selection_state.select_previous_column();
+            }
+            Action::Confirm => self.select_current(),
+            Action::Skip if !self.is_maxed() => self.select_skip(),
+            Action::Reroll => self.reroll(),
+            Action::Back => self.pending = None,
             _ => {}
         }
     }
 
+    /// Selects the highlighted choice, or applies it if it was already selected.
     pub fn select_current(&mut self) {
-        if self.powerup_choices.is_empty() {
-            self.finished = true;
-            return;
-        }
-
         let maybe_selected_index = self.selection_state.selected_cell();
         if let Some((_, col)) = maybe_selected_index {
-            if col >= self.powerup_choices.len() {
+            if col >= self.get_column_count() {
                 return;
             }
-            let selected_powerup = &self.powerup_choices[col];
-
-            match selected_powerup.get_powerup_type() {
-                PowerupTypes::Weapon => {
-                    let mut new_weapons = self.weapons.clone();
-                    for weapon in &mut new_weapons {
-                        let weapon_upper = weapon.get_inner().get_name().to_uppercase();
-                        let powerup_upper = selected_powerup.get_name().to_uppercase();
-                        if weapon_upper == powerup_upper {
-                            weapon.get_inner_mut().upgrade_self(selected_powerup);
-                        }
-                    }
-
-                    if !new_weapons.iter().any(|weapon| {
-                        weapon.get_inner().get_name().to_uppercase()
-                            == selected_powerup.get_name().to_uppercase()
-                    }) && let Ok(mut new_weapon) =
-                        WeaponWrapper::from_str(selected_powerup.get_name().to_uppercase().as_str())
-                    {
-                        new_weapon.populate_inner(
-                            self.base_weapon_stats.clone(),
-                            self.player_state.clone(),
-                        );
-                        new_weapons.push(new_weapon);
-                    }
-
-                    self.weapons = new_weapons;
-                }
 
-                PowerupTypes::Charm => {
-                    let mut new_charms = self.charms.clone();
-                    for charm in &mut new_charms {
-                        let charm_upper = charm.get_inner().get_name().to_uppercase();
-                        let powerup_upper = selected_powerup.get_name().to_uppercase();
-                        if charm_upper == powerup_upper {
-                            charm.get_inner_mut().upgrade_self(selected_powerup);
-                        }
-                    }
-
-                    if !new_charms.iter().any(|charm| {
-                        charm.get_inner().get_name().to_uppercase()
-                            == selected_powerup.get_name().to_uppercase()
-                    }) && let Ok(mut new_charm) =
-                        CharmWrapper::from_str(selected_powerup.get_name().to_uppercase().as_str())
-                    {
-                        new_charm.populate_inner(self.player_state.clone());
-                        new_charms.push(new_charm);
-                    }
-                    self.charms = new_charms;
-                }
+            if self.is_maxed() {
+                self.select_bonus(MaxedBonus::ALL[col]);
+                return;
+            }
+
+            if self.pending != Some(PendingChoice::Powerup(col)) {
+                self.pending = Some(PendingChoice::Powerup(col));
+                return;
             }
 
+            apply_powerup(
+                &self.powerup_choices[col],
+                &mut self.weapons,
+                &mut self.charms,
+                &self.base_weapon_stats,
+                &self.player_state,
+            );
+
             self.finished = true;
         }
     }
 
-    pub fn render_choices(&mut self, frame: &mut Frame, rect: Rect) {
-        let mut texts: Vec<Cell> = Vec::new();
+    /// Selects a bonus, or takes it if it was already selected.
+    fn select_bonus(&mut self, bonus: MaxedBonus) {
+        if self.pending != Some(PendingChoice::Bonus(bonus)) {
+            self.pending = Some(PendingChoice::Bonus(bonus));
+            return;
+        }
+
+        if bonus == MaxedBonus::Gold {
+            let gold = self.get_maxed_gold();
+            self.player_state.borrow_mut().inventory.add_gold(gold);
+        }
 
-        for choice in &self.powerup_choices {
-            let widths = [
-                Constraint::Ratio(1, 3),
-                Constraint::Ratio(1, 3),
-                Constraint::Ratio(1, 3),
-            ];
+        self.taken_bonus = Some(bonus);
+        self.finished = true;
+    }
 
-            let area_width = Layout::horizontal(widths).areas::<3>(rect)[0];
+    /// Selects skipping the choice, or takes the gold if skipping was already selected.
+    pub fn select_skip(&mut self) {
+        if self.pending != Some(PendingChoice::Skip) {
+            self.pending = Some(PendingChoice::Skip);
+            return;
+        }
 
-            let raw_title = choice.get_name();
-            let title = String::from(raw_title);
+        let gold = self.get_skip_gold();
+        self.player_state.borrow_mut().inventory.add_gold(gold);
+        self.finished = true;
+    }
 
-            let raw_desc = choice.get_desc();
-            let desc = String::from(raw_desc);
+    /// The gold granted for skipping, which grows with the levels of everything held.
+    #[must_use]
+    pub fn get_skip_gold(&self) -> u128 {
+        let levels: i32 = self
+            .weapons
+            .iter()
+            .map(|weapon| weapon.get_inner().get_level())
+            .chain(
+                self.charms
+                    .iter()
+                    .map(|charm| charm.get_inner().get_level()),
+            )
+            .sum();
+        let gold_mult = self.player_state.borrow().stats.game_stats.gold_mult;
+
+        (Self::SKIP_GOLD_PER_LEVEL * f64::from(levels.max(1)) * gold_mult) as u128
+    }
 
-            let curr_level = choice.get_current_level();
-            let new_level = choice.get_new_level();
-            let amount = format!("Level {curr_level} -> {new_level}");
+    /// The gold granted by the gold bonus once everything is maxed.
+    #[must_use]
+    pub fn get_maxed_gold(&self) -> u128 {
+        self.get_skip_gold() * Self::MAXED_GOLD_MULT
+    }
 
-            let powerup_text =
-                String::from_iter(vec![title, "\n".into(), desc, "\n".into(), amount]);
+    /// Describes the loadout the player would have after taking the highlighted choice.
+    fn get_preview_lines(&self) -> Vec<Line<'static>> {
+        let mut weapons = self.weapons.clone();
+        let mut charms = self.charms.clone();
+
+        let highlighted = self
+            .selection_state
+            .selected_cell()
+            .and_then(|(_, col)| self.powerup_choices.get(col));
+
+        if self.pending != Some(PendingChoice::Skip)
+            && let Some(choice) = highlighted
+        {
+            apply_powerup(
+                choice,
+                &mut weapons,
+                &mut charms,
+                &self.base_weapon_stats,
+                &self.player_state,
+            );
+        }
 
-            let wrapped = textwrap::wrap(powerup_text.as_str(), area_width.width as usize);
+        let describe = |name: String, level: i32| format!("{name} {level}");
+
+        let weapon_names: Vec<String> = weapons
+            .iter()
+            .map(|weapon| {
+                describe(
+                    weapon.get_inner().get_name(),
+                    weapon.get_inner().get_level(),
+                )
+            })
+            .collect();
+        let charm_names: Vec<String> = charms
+            .iter()
+            .map(|charm| describe(charm.get_inner().get_name(), charm.get_inner().get_level()))
+            .collect();
+
+        let heading = if self.is_maxed() {
+            "everything is maxed, take a bonus instead"
+        } else {
+            "loadout after pick"
+        };
+
+        vec![
+            Line::from(heading.dark_gray()),
+            Line::from(format!("METHODs: {}", weapon_names.join(", "))),
+            Line::from(format!("CHARMs: {}", charm_names.join(", "))),
+        ]
+    }
 
-            let yass: Vec<Line> = wrapped
+    /// Returns the text for each choice, in order: the powerups, or the bonuses once everything
+    /// is maxed.
+    fn get_choice_texts(&self) -> Vec<String> {
+        if self.is_maxed() {
+            return MaxedBonus::ALL
                 .iter()
-                .map(|line| Line::from(line.to_string()))
+                .map(|bonus| match bonus {
+                    MaxedBonus::Gold => format!(
+                        "GOLD\nTake {} gold instead of a powerup",
+                        self.get_maxed_gold()
+                    ),
+                    MaxedBonus::Heal => format!(
+                        "HEAL\nRestore {}% of your max health",
+                        Self::MAXED_HEAL_PERCENT
+                    ),
+                })
                 .collect();
+        }
 
-            let text = Text::from(yass).centered();
+        self.powerup_choices
+            .iter()
+            .zip(&self.choice_changes)
+            .map(|(choice, changes)| {
+                let curr_level = choice.get_current_level();
+                let new_level = choice.get_new_level();
+
+                let mut powerup_text = format!(
+                    "{}\n{}\nLevel {curr_level} -> {new_level}",
+                    choice.get_name(),
+                    choice.get_desc()
+                );
+                for change in changes {
+                    powerup_text.push('\n');
+                    powerup_text.push_str(change);
+                }
 
-            let text = Cell::from(text);
+                powerup_text
+            })
+            .collect()
+    }
 
-            texts.push(text);
-        }
+    pub fn render_choices(&mut self, frame: &mut Frame, rect: Rect) {
+        // one or two choices share the width between them rather than leaving gaps
+        let columns = self.get_column_count().max(1) as u32;
+        let widths = vec![Constraint::Ratio(1, columns); columns as usize];
+
+        let area_width = Layout::horizontal(widths.clone()).split(rect)[0];
+
+        let texts: Vec<Cell> = self
+            .get_choice_texts()
+            .iter()
+            .map(|choice_text| {
+                let wrapped = textwrap::wrap(choice_text, area_width.width as usize);
+
+                let lines: Vec<Line> = wrapped
+                    .iter()
+                    .map(|line| Line::from(line.to_string()))
+                    .collect();
+
+                Cell::from(Text::from(lines).centered())
+            })
+            .collect();
 
         let row = [Row::new(texts).height(rect.height)];
 
-        let table = Table::new(
-            row,
-            [
-                Constraint::Ratio(1, 3),
-                Constraint::Ratio(1, 3),
-                Constraint::Ratio(1, 3),
-            ],
-        );
+        let table = Table::new(row, widths);
+
+        let highlight_colour = match self.pending {
+            Some(PendingChoice::Powerup(_) | PendingChoice::Bonus(_)) => Color::LightGreen,
+            _ => Color::LightBlue,
+        };
 
         let table =
-            table.cell_highlight_style(Style::default().bg(Color::LightBlue).fg(Color::Black));
+            table.cell_highlight_style(Style::default().bg(highlight_colour).fg(Color::Black));
 
         frame.render_stateful_widget(table, rect, &mut self.selection_state);
     }
@@ -259,15 +560,78 @@ impl PowerupPopup {
     pub fn render(&mut self, frame: &mut Frame) {
         let area = popup_area(frame.area(), 80, 60);
 
+        let confirm_hint = match self.pending {
+            Some(PendingChoice::Powerup(_) | PendingChoice::Bonus(_)) => {
+                " <ENTER> Confirm | <ESC> Cancel ".to_string()
+            }
+            Some(PendingChoice::Skip) => format!(
+                " <X> Confirm skip for {} gold | <ESC> Cancel ",
+                self.get_skip_gold()
+            ),
+            None if self.is_maxed() => " <A|D> Choose | <ENTER> Select ".to_string(),
+            None if self.rerolls > 0 => format!(
+                " <A|D> Choose | <ENTER> Select | <X> Skip for gold | <R> Reroll ({}) ",
+                self.rerolls
+            ),
+            None => " <A|D> Choose | <ENTER> Select | <X> Skip for gold ".to_string(),
+        };
+
         let popup = Block::bordered()
             .border_set(border::PLAIN)
             .title(" Powerup Choice ")
-            .title_alignment(ratatui::layout::Alignment::Center);
+            .title_alignment(ratatui::layout::Alignment::Center)
+            .title_bottom(Line::from(confirm_hint).centered());
 
         let inner_area = popup.inner(area);
 
+        let [choices_area, preview_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(3)]).areas(inner_area);
+
         frame.render_widget(Clear, area);
         frame.render_widget(popup, area);
-        self.render_choices(frame, inner_area);
+        self.render_choices(frame, choices_area);
+        frame.render_widget(
+            Paragraph::new(self.get_preview_lines()).centered(),
+            preview_area,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::common::popups::poweruppopup::{MaxedBonus, PowerupPopup};
+    use crate::common::upgrades::upgrade::PlayerState;
+
+    #[test]
+    fn maxed_popup_offers_bonuses() {
+        let player_state = Rc::new(RefCell::new(PlayerState::default()));
+        let weapon_stats = player_state.borrow().stats.weapon_stats.clone();
+
+        let mut popup = PowerupPopup::new(&[], &[], weapon_stats.clone(), player_state.clone());
+        assert!(!popup.is_maxed());
+
+        // nothing left to roll, as when everything held is at its max level
+        popup.powerup_choices.clear();
+        assert!(popup.is_maxed());
+        assert_eq!(popup.get_choice_texts().len(), 2);
+
+        let gold = popup.get_maxed_gold();
+        popup.select_current();
+        assert!(!popup.finished);
+        popup.select_current();
+        assert!(popup.finished);
+        assert_eq!(popup.taken_bonus, Some(MaxedBonus::Gold));
+        assert_eq!(player_state.borrow().inventory.gold, gold);
+
+        let mut popup = PowerupPopup::new(&[], &[], weapon_stats, player_state.clone());
+        popup.powerup_choices.clear();
+        popup.selection_state.select_next_column();
+        popup.select_current();
+        popup.select_current();
+        assert_eq!(popup.taken_bonus, Some(MaxedBonus::Heal));
+        assert_eq!(player_state.borrow().inventory.gold, gold);
     }
 }
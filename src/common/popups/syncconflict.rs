@@ -0,0 +1,151 @@
+//! This module renders the prompt shown when the save on this machine and the synced copy have
+//! both changed, letting the player pick which one to keep.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, List, ListItem, ListState, Paragraph, Wrap},
+};
+
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::popups::helpoverlay::render_help;
+use crate::common::popups::popup_area;
+use crate::common::upgrades::upgrade::PlayerState;
+use crate::prelude::{KeyCode, KeyEvent};
+
+/// One of the two saves the player is choosing between.
+pub struct SaveSummary {
+    /// When the save was made, in milliseconds since the Unix epoch.
+    pub saved_at: u64,
+    pub gold: u128,
+    pub upgrades: u32,
+}
+
+impl SaveSummary {
+    #[must_use]
+    pub fn new(saved_at: u64, player_state: &PlayerState) -> Self {
+        Self {
+            saved_at,
+            gold: player_state.inventory.gold,
+            upgrades: player_state
+                .upgrades
+                .iter()
+                .map(|upgrade| upgrade.amount)
+                .sum(),
+        }
+    }
+
+    /// Describes the save for its choice, with how long ago it was made as of `now`.
+    fn get_label(&self, name: &str, now: u64) -> String {
+        let minutes = now.saturating_sub(self.saved_at) / 60_000;
+        let age = match minutes {
+            0 => "just now".to_string(),
+            1..60 => format!("{minutes}m ago"),
+            60..1440 => format!("{}h ago", minutes / 60),
+            _ => format!("{}d ago", minutes / 1440),
+        };
+
+        format!(
+            "keep {name}: saved {age}, {} gold, {} upgrades",
+            self.gold, self.upgrades
+        )
+    }
+}
+
+/// Asks the player whether to keep this machine's save or the synced one, starting on whichever
+/// is newer.
+pub struct SyncConflict {
+    local: SaveSummary,
+    remote: SaveSummary,
+    selection: ListState,
+    /// Whether the help overlay is open over the prompt.
+    show_help: bool,
+}
+
+impl SyncConflict {
+    #[must_use]
+    pub fn new(local: SaveSummary, remote: SaveSummary) -> Self {
+        let newest = usize::from(remote.saved_at > local.saved_at);
+
+        Self {
+            local,
+            remote,
+            selection: ListState::default().with_selected(Some(newest)),
+            show_help: false,
+        }
+    }
+
+    /// Handles a key press, returning whether to keep this machine's save once the player has
+    /// picked.
+    pub fn handle_key_event(&mut self, key_event: &KeyEvent) -> Option<bool> {
+        let action = KeyContext::SyncConflict.get_action(&key_event.code);
+
+        if self.show_help {
+            if action == Some(Action::Help) || key_event.code == KeyCode::Esc {
+                self.show_help = false;
+            }
+            None
+        } else if action == Some(Action::Help) {
+            self.show_help = true;
+            None
+        } else {
+            action.and_then(|action| self.handle_action(action))
+        }
+    }
+
+    /// Handles an action, returning whether to keep this machine's save once the player has
+    /// picked.
+    pub fn handle_action(&mut self, action: Action) -> Option<bool> {
+        match action {
+            Action::Up => self.selection.select(Some(0)),
+            Action::Down => self.selection.select(Some(1)),
+            Action::Confirm => return Some(self.selection.selected() == Some(0)),
+            _ => {}
+        }
+
+        None
+    }
+
+    /// Draws the prompt in a popup over the current view, with the saves' ages as of `now`.
+    pub fn render(&mut self, frame: &mut Frame, now: u64) {
+        let area = popup_area(frame.area(), 60, 40);
+
+        let block = Block::bordered()
+            .border_set(border::DOUBLE)
+            .title(Line::from(" your saves are out of step ").centered())
+            .light_yellow();
+        let [message_area, choices_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(2)])
+                .margin(1)
+                .areas(block.inner(area));
+
+        let message = Paragraph::new(
+            "progress was made both here and on another machine since they last synced. pick the \
+             save to keep, the other is replaced",
+        )
+        .wrap(Wrap { trim: true })
+        .centered()
+        .reset();
+
+        let items = vec![
+            ListItem::from(self.local.get_label("this machine's", now)),
+            ListItem::from(self.remote.get_label("the synced one", now)),
+        ];
+        let list = List::new(items)
+            .highlight_symbol("> ")
+            .highlight_style(Style::new().bold())
+            .reset();
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+        frame.render_widget(message, message_area);
+        frame.render_stateful_widget(list, choices_area, &mut self.selection);
+
+        if self.show_help {
+            render_help(frame, KeyContext::SyncConflict);
+        }
+    }
+}
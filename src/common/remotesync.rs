@@ -0,0 +1,132 @@
+//! This module defines `RemoteSync`, the interface for keeping the save in step with a copy
+//! stored elsewhere, so progress follows the player across machines. It also decides which copy
+//! should win when they've drifted apart.
+
+use serde::{Deserialize, Serialize};
+
+/// Something that stores a copy of the save away from this machine. Requests run in the
+/// background, and their results are picked up with [`RemoteSync::poll`].
+pub trait RemoteSync {
+    /// Starts downloading the remote copy.
+    fn fetch(&self);
+
+    /// Starts uploading `body` over the remote copy.
+    fn upload(&self, body: String);
+
+    /// Takes the results of the requests that have finished since the last poll.
+    fn poll(&self) -> Vec<SyncEvent>;
+}
+
+/// The result of a request to the remote copy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncEvent {
+    /// The remote copy was downloaded, or found not to exist yet.
+    Fetched(Option<String>),
+    Uploaded,
+    Failed(String),
+}
+
+/// The save as it's stored remotely, stamped with when it was saved.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SyncedSave {
+    /// When the save was made, in milliseconds since the Unix epoch.
+    pub saved_at: u64,
+    /// The save's JSON.
+    pub save: String,
+}
+
+/// The timestamps the two copies had when they were last in step, to tell which has changed
+/// since.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncBase {
+    pub local: u64,
+    pub remote: u64,
+}
+
+/// What to do to bring the two copies back in step.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncAction {
+    UpToDate,
+    /// This machine's copy is the one to keep, so it replaces the remote one.
+    Upload,
+    /// The remote copy is the one to keep, so it replaces this machine's.
+    Download,
+    /// There's no telling which copy to keep, so the player has to pick one.
+    Conflict,
+}
+
+/// Works out what to do from when each copy was saved, if it exists, and when they were last in
+/// step. If only one copy has changed it wins, and if both have the one saved last wins. Copies
+/// saved at the same moment, or that have never been in step on this machine, conflict, as
+/// there's no telling which one has the progress the player wants.
+#[must_use]
+pub fn get_sync_action(
+    local: Option<u64>,
+    remote: Option<u64>,
+    base: Option<SyncBase>,
+) -> SyncAction {
+    let (local, remote) = match (local, remote) {
+        (None, None) => return SyncAction::UpToDate,
+        (Some(_), None) => return SyncAction::Upload,
+        (None, Some(_)) => return SyncAction::Download,
+        (Some(local), Some(remote)) => (local, remote),
+    };
+
+    let Some(base) = base else {
+        return SyncAction::Conflict;
+    };
+
+    match (local != base.local, remote != base.remote) {
+        (false, false) => SyncAction::UpToDate,
+        (true, false) => SyncAction::Upload,
+        (false, true) => SyncAction::Download,
+        (true, true) if local > remote => SyncAction::Upload,
+        (true, true) if remote > local => SyncAction::Download,
+        (true, true) => SyncAction::Conflict,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::remotesync::{SyncAction, SyncBase, get_sync_action};
+
+    #[test]
+    fn the_latest_change_wins() {
+        let base = Some(SyncBase {
+            local: 10,
+            remote: 20,
+        });
+
+        assert_eq!(
+            get_sync_action(Some(10), Some(20), base),
+            SyncAction::UpToDate
+        );
+        assert_eq!(
+            get_sync_action(Some(15), Some(20), base),
+            SyncAction::Upload
+        );
+        assert_eq!(
+            get_sync_action(Some(10), Some(30), base),
+            SyncAction::Download
+        );
+        assert_eq!(
+            get_sync_action(Some(15), Some(30), base),
+            SyncAction::Download
+        );
+        assert_eq!(
+            get_sync_action(Some(35), Some(30), base),
+            SyncAction::Upload
+        );
+        assert_eq!(
+            get_sync_action(Some(30), Some(30), base),
+            SyncAction::Conflict
+        );
+
+        assert_eq!(get_sync_action(Some(10), None, None), SyncAction::Upload);
+        assert_eq!(get_sync_action(None, Some(20), None), SyncAction::Download);
+        assert_eq!(
+            get_sync_action(Some(10), Some(20), None),
+            SyncAction::Conflict
+        );
+    }
+}
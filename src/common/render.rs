@@ -1,12 +1,19 @@
-use crate::common::character::{Character, Renderable};
+use crate::common::character::{Character, Damageable, Renderable};
 use crate::common::coords::{Area, Position, SquareArea};
 use crate::common::enemies::enemy::Enemy;
+use crate::common::hazards::Hazard;
 use crate::common::map::Layer;
 use crate::common::pickups::PickupTypes;
 use crate::common::rogue::Rogue;
-use crate::common::utils::get_mut_item_in_2d_enum_vec;
+use crate::common::settings::setting::Theme;
+use crate::common::turrets::DeployedTurret;
+use crate::common::utils::OffsetGrid;
+use crate::prelude::{Duration, Instant};
+use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::prelude::{Line, Span, Text};
+use ratatui::prelude::{Line, Modifier, Span, Style, Text};
+use ratatui::style::Color;
+use ratatui::symbols::{block, shade};
 
 #[must_use]
 pub fn spans_to_text(spans: Vec<Vec<Span<'_>>>) -> Text<'_> {
@@ -20,6 +27,177 @@ pub fn spans_to_text(spans: Vec<Vec<Span<'_>>>) -> Text<'_> {
     out
 }
 
+/// How much detail the map view is drawn with. Lowered automatically while frames run slow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum RenderQuality {
+    #[default]
+    Full,
+    /// No movement trail, and effects are shown for less time.
+    Reduced,
+    /// As `Reduced`, with the map zoomed out so each cell stands for a block of tiles.
+    Minimal,
+}
+
+impl RenderQuality {
+    #[must_use]
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            RenderQuality::Full => "full",
+            RenderQuality::Reduced => "reduced",
+            RenderQuality::Minimal => "minimal",
+        }
+    }
+
+    #[must_use]
+    fn lower(self) -> Self {
+        match self {
+            RenderQuality::Full => RenderQuality::Reduced,
+            RenderQuality::Reduced | RenderQuality::Minimal => RenderQuality::Minimal,
+        }
+    }
+
+    #[must_use]
+    fn raise(self) -> Self {
+        match self {
+            RenderQuality::Full | RenderQuality::Reduced => RenderQuality::Full,
+            RenderQuality::Minimal => RenderQuality::Reduced,
+        }
+    }
+
+    /// How many tiles across and down each cell of the view stands for.
+    #[must_use]
+    pub fn get_zoom(&self) -> u16 {
+        match self {
+            RenderQuality::Full | RenderQuality::Reduced => 1,
+            RenderQuality::Minimal => 2,
+        }
+    }
+
+    /// How much of their duration effects are shown for.
+    #[must_use]
+    pub fn get_effect_duration_mult(&self) -> f64 {
+        match self {
+            RenderQuality::Full => 1.,
+            RenderQuality::Reduced => 0.6,
+            RenderQuality::Minimal => 0.4,
+        }
+    }
+
+    #[must_use]
+    pub fn shows_trail(&self) -> bool {
+        *self == RenderQuality::Full
+    }
+}
+
+/// Watches how long frames take and steps the render quality down when they run slow, then
+/// back up once they have been fast for a while.
+#[derive(Debug, Clone, Default)]
+pub struct AdaptiveQuality {
+    pub quality: RenderQuality,
+    frame_ms: f64,
+    fast_since: Option<Instant>,
+}
+
+impl AdaptiveQuality {
+    /// Smoothed frame cost above which quality is lowered.
+    const DEGRADE_MS: f64 = 12.;
+    /// Smoothed frame cost below which quality can be raised again.
+    const RECOVER_MS: f64 = 4.;
+    /// How long frames must stay cheap before quality is raised.
+    const RECOVER_AFTER: Duration = Duration::from_secs(3);
+    const SMOOTHING: f64 = 0.1;
+
+    /// Records what a frame cost at `now`. Returns the new quality if it changed.
+    pub fn record(&mut self, frame_cost: Duration, now: Instant) -> Option<RenderQuality> {
+        let cost_ms = frame_cost.as_secs_f64() * 1000.;
+        self.frame_ms += (cost_ms - self.frame_ms) * Self::SMOOTHING;
+
+        let new_quality = if self.frame_ms > Self::DEGRADE_MS {
+            self.quality.lower()
+        } else if self.frame_ms < Self::RECOVER_MS {
+            let fast_since = *self.fast_since.get_or_insert(now);
+            if now.saturating_duration_since(fast_since) >= Self::RECOVER_AFTER {
+                self.quality.raise()
+            } else {
+                self.quality
+            }
+        } else {
+            self.fast_since = None;
+            self.quality
+        };
+
+        if new_quality == self.quality {
+            return None;
+        }
+
+        // start measuring afresh so one change has a chance to settle before the next
+        self.quality = new_quality;
+        self.frame_ms = f64::midpoint(Self::DEGRADE_MS, Self::RECOVER_MS);
+        self.fast_since = None;
+
+        Some(new_quality)
+    }
+}
+
+/// Shrinks a grid of spans by `zoom` in both directions, keeping the most important span in
+/// each block: the character, then enemies, then anything that isn't background.
+#[must_use]
+pub fn aggregate_spans(spans: &[Vec<Span<'static>>], zoom: usize) -> Vec<Vec<Span<'static>>> {
+    fn importance(span: &Span) -> u8 {
+        match span.content.as_ref() {
+            "0" => 3,
+            "x" | "X" | "✖" => 2,
+            "." | "," | " " => 0,
+            _ => 1,
+        }
+    }
+
+    spans
+        .chunks(zoom.max(1))
+        .map(|rows| {
+            let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+
+            (0..width)
+                .step_by(zoom.max(1))
+                .filter_map(|x| {
+                    rows.iter()
+                        .flat_map(|row| row.iter().skip(x).take(zoom.max(1)))
+                        .max_by_key(|span| importance(span))
+                        .cloned()
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Makes a span safe for players sensitive to flashing: blinking is removed, and full-block
+/// attack flashes become a steady, dimmed shade.
+#[must_use]
+pub fn reduce_flashing(span: Span<'static>) -> Span<'static> {
+    let mut span = span.patch_style(
+        Style::new()
+            .remove_modifier(Modifier::SLOW_BLINK | Modifier::RAPID_BLINK | Modifier::REVERSED),
+    );
+
+    if span.content == block::FULL {
+        span = Span::styled(shade::MEDIUM, span.style.add_modifier(Modifier::DIM));
+    }
+
+    span
+}
+
+/// Redraws an already rendered buffer in the given theme.
+pub fn apply_theme(buffer: &mut Buffer, theme: Theme) {
+    match theme {
+        Theme::Classic => {}
+        Theme::Mono => {
+            for cell in &mut buffer.content {
+                cell.set_fg(Color::Reset).set_bg(Color::Reset);
+            }
+        }
+    }
+}
+
 #[must_use]
 pub fn get_camera_area(content_area: Rect, player_pos: &Position, layer: &Layer) -> SquareArea {
     let view_height = i32::from(content_area.height);
@@ -66,18 +244,56 @@ pub fn get_camera_area(content_area: Rect, player_pos: &Position, layer: &Layer)
     }
 }
 
+/// Centres the camera on the player without clamping it to the map's edges, for maps that wrap
+/// around.
+#[must_use]
+pub fn get_wrapped_camera_area(content_area: Rect, player_pos: &Position) -> SquareArea {
+    let (player_x, player_y) = player_pos.get();
+
+    let x1 = player_x - i32::from(content_area.width) / 2;
+    let y1 = player_y - i32::from(content_area.height) / 2;
+
+    SquareArea {
+        corner1: Position(x1, y1),
+        corner2: Position(
+            x1 + i32::from(content_area.width),
+            y1 + i32::from(content_area.height),
+        ),
+    }
+}
+
+/// Cuts `window` out of the whole map's `spans`, carrying on from the opposite edge wherever the
+/// window runs off the map.
+#[must_use]
+pub fn wrap_spans(spans: &[Vec<Span<'static>>], window: &SquareArea) -> Vec<Vec<Span<'static>>> {
+    let (x1, y1, x2, y2) = window.get_bounds();
+    let height = spans.len() as i32;
+
+    (y1..=y2)
+        .map(|y| {
+            let row = &spans[y.rem_euclid(height) as usize];
+            let width = row.len() as i32;
+
+            (x1..=x2)
+                .map(|x| row[x.rem_euclid(width) as usize].clone())
+                .collect()
+        })
+        .collect()
+}
+
 #[must_use]
+#[allow(clippy::too_many_lines)]
 pub fn flatten_to_span(rogue: &Rogue, area: Option<SquareArea>) -> Vec<Vec<Span<'static>>> {
     fn callback_creator<F: std::borrow::Borrow<T>, T: Renderable>(
-        enum_2d: &mut Vec<(usize, Vec<(usize, Span)>)>,
+        grid: &mut OffsetGrid<Span<'static>>,
         layer: &Layer,
     ) -> impl FnMut(F) {
         |entity: F| {
             let mut pos = entity.borrow().get_pos().clone();
             pos.constrain(layer);
 
-            if let Some(entity_pos) = get_mut_item_in_2d_enum_vec(enum_2d, &pos) {
-                *entity_pos = entity.borrow().get_entity_char().to_styled();
+            if let Some(cell) = grid.get_mut(&pos) {
+                *cell = entity.borrow().get_styled();
             }
         }
     }
@@ -94,38 +310,78 @@ pub fn flatten_to_span(rogue: &Rogue, area: Option<SquareArea>) -> Vec<Vec<Span<
         );
     }
 
-    let mut enum_2d: Vec<(usize, Vec<(usize, Span<'static>)>)> = rogue
-        .map
+    // the area can run off the map, so only the part of it on the map is drawn
+    let (x1, y1) = (x1.max(0), y1.max(0));
+    let width = usize::try_from(x2 - x1 + 1).unwrap_or(0);
+    let height = usize::try_from(y2 - y1 + 1).unwrap_or(0);
+
+    // the background's spans are styled once, and only the cells with something on them change
+    let rows = rogue
         .map
+        .tile_spans
         .iter()
-        .enumerate()
-        .filter_map(|(i, line)| {
-            if i >= y1 as usize && i <= y2 as usize {
-                Some((
-                    i,
-                    line.iter()
-                        .enumerate()
-                        .filter_map(|(i, entity)| {
-                            if i >= x1 as usize && i <= x2 as usize {
-                                Some((i, entity.to_styled()))
-                            } else {
-                                None
-                            }
-                        })
-                        .collect(),
-                ))
-            } else {
-                None
-            }
-        })
+        .skip(y1 as usize)
+        .take(height)
+        .map(|line| line.iter().skip(x1 as usize).take(width).cloned().collect())
         .collect();
+    let mut grid = OffsetGrid::new(Position(x1, y1), rows);
+
+    rogue
+        .map
+        .hazards
+        .values()
+        .for_each(callback_creator::<_, Hazard>(&mut grid, &rogue.map.map));
+
+    // the warning line marks the ring of tiles the walls close over next
+    for position in rogue.map.get_closing_edge() {
+        if let Some(cell) = grid.get_mut(&position) {
+            cell.style = cell.style.bg(Color::Red);
+        }
+    }
+
+    {
+        let topology = rogue.player_state.borrow().get_topology();
+        let mut draw = |position: &Position, span: Span<'static>| {
+            let mut position = position.clone();
+            topology.constrain(&mut position, &rogue.map.map);
+
+            if let Some(cell) = grid.get_mut(&position) {
+                *cell = span;
+            }
+        };
+
+        if rogue.player_state.borrow().settings.show_trail
+            && rogue.adaptive_quality.quality.shows_trail()
+        {
+            rogue.character.get_trail().for_each(|(position, fade)| {
+                let span = if fade < 0.5 {
+                    Span::styled("∙", Style::new().gray())
+                } else {
+                    Span::styled("·", Style::new().dark_gray())
+                };
+                draw(position, span);
+            });
+        }
+
+        let (facing_position, facing_span) = rogue.character.get_facing_indicator();
+        draw(&facing_position, facing_span);
+    }
 
     rogue
         .pickup_wrangler
         .pickups
         .iter()
         .for_each(callback_creator::<_, PickupTypes>(
-            &mut enum_2d,
+            &mut grid,
+            &rogue.map.map,
+        ));
+
+    rogue
+        .turret_wrangler
+        .turrets
+        .iter()
+        .for_each(callback_creator::<_, DeployedTurret>(
+            &mut grid,
             &rogue.map.map,
         ));
 
@@ -133,25 +389,141 @@ pub fn flatten_to_span(rogue: &Rogue, area: Option<SquareArea>) -> Vec<Vec<Span<
         .enemies
         .borrow()
         .iter()
-        .for_each(callback_creator::<_, Enemy>(&mut enum_2d, &rogue.map.map));
+        .for_each(callback_creator::<_, Enemy>(&mut grid, &rogue.map.map));
 
+    let view = SquareArea::new(Position(x1, y1), Position(x2, y2));
     rogue.active_damage_effects.iter().for_each(|effect| {
         effect
-            .get_instructions()
-            .for_each(callback_creator(&mut enum_2d, &rogue.map.map));
+            .get_instructions(&view)
+            .for_each(callback_creator(&mut grid, &rogue.map.map));
     });
 
     {
-        let mut character_callback = callback_creator::<_, Character>(&mut enum_2d, &rogue.map.map);
-        character_callback(&rogue.character);
+        let mut character_callback = callback_creator::<_, Character>(&mut grid, &rogue.map.map);
+        // in co-op a downed player leaves the map while the other carries on
+        if rogue.character.is_alive() || rogue.partner.is_none() {
+            character_callback(&rogue.character);
+        }
     }
 
-    enum_2d
-        .into_iter()
-        .map(|(_, vec): (usize, Vec<(usize, Span)>)| {
-            vec.into_iter()
-                .map(|(_, item): (usize, Span)| item)
-                .collect()
-        })
+    if let Some(partner) = rogue.partner.as_ref().filter(|p| p.character.is_alive()) {
+        let mut position = partner.character.get_pos().clone();
+        position.constrain(&rogue.map.map);
+
+        if let Some(cell) = grid.get_mut(&position) {
+            *cell = partner.get_styled();
+        }
+    }
+
+    let reduced_flashing = rogue.player_state.borrow().settings.reduced_flashing;
+
+    let rows = grid.into_rows();
+    if !reduced_flashing {
+        return rows;
+    }
+
+    rows.into_iter()
+        .map(|row| row.into_iter().map(reduce_flashing).collect())
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use ratatui::text::Span;
+
+    use crate::common::coords::{Position, SquareArea};
+    use crate::common::render::{AdaptiveQuality, RenderQuality, aggregate_spans, wrap_spans};
+    use crate::prelude::{Duration, Instant};
+
+    #[test]
+    fn quality_drops_when_slow_and_recovers() {
+        let mut adaptive = AdaptiveQuality::default();
+        let now = Instant::now();
+
+        let mut changes = vec![];
+        for _ in 0..100 {
+            changes.extend(adaptive.record(Duration::from_millis(40), now));
+        }
+        assert_eq!(changes, [RenderQuality::Reduced, RenderQuality::Minimal]);
+
+        for _ in 0..100 {
+            adaptive.record(Duration::ZERO, now);
+        }
+        assert_eq!(adaptive.quality, RenderQuality::Minimal);
+
+        let later = now + Duration::from_secs(4);
+        let raised = (0..100).find_map(|_| adaptive.record(Duration::ZERO, later));
+        assert_eq!(raised, Some(RenderQuality::Reduced));
+    }
+
+    #[test]
+    fn aggregation_keeps_the_character() {
+        let row = |cells: &[&'static str]| cells.iter().map(|c| Span::raw(*c)).collect();
+        let spans = vec![row(&[".", "x", ".", "."]), row(&[".", "0", ",", "."])];
+
+        let zoomed = aggregate_spans(&spans, 2);
+
+        assert_eq!(zoomed.len(), 1);
+        assert_eq!(zoomed[0][0].content, "0");
+        assert_eq!(zoomed[0][1].content, ".");
+    }
+
+    #[test]
+    fn wrapped_view_carries_on_from_the_far_edge() {
+        let row = |cells: &[&'static str]| cells.iter().map(|c| Span::raw(*c)).collect();
+        let spans = vec![row(&["a", "b", "c"]), row(&["d", "e", "f"])];
+
+        let window = SquareArea::new(Position(-1, 1), Position(1, 2));
+        let cells: Vec<Vec<_>> = wrap_spans(&spans, &window)
+            .iter()
+            .map(|row| row.iter().map(|span| span.content.clone()).collect())
+            .collect();
+
+        assert_eq!(cells, [["f", "d", "e"], ["c", "a", "b"]]);
+    }
+}
+
+#[cfg(all(test, not(target_family = "wasm")))]
+mod proptests {
+    use proptest::prelude::*;
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+
+    use crate::common::coords::{Area, Position};
+    use crate::common::entities::EntityCharacters;
+    use crate::common::render::get_camera_area;
+
+    proptest! {
+        #[test]
+        fn camera_stays_in_layer_and_covers_the_player(
+            layer_width in 1usize..120,
+            layer_height in 1usize..60,
+            view_width in 1u16..150,
+            view_height in 1u16..80,
+            player in (0f64..1., 0f64..1.),
+        ) {
+            let layer = vec![vec![EntityCharacters::Empty(Style::new()); layer_width]; layer_height];
+            let player = Position(
+                (player.0 * layer_width as f64) as i32,
+                (player.1 * layer_height as f64) as i32,
+            );
+
+            let camera = get_camera_area(
+                Rect::new(0, 0, view_width, view_height),
+                &player,
+                &layer,
+            );
+            // the camera's far corner is exclusive
+            let (x1, y1, x2, y2) = camera.get_bounds();
+
+            prop_assert!(x1 >= 0 && y1 >= 0);
+            prop_assert!(x2 <= layer_width as i32 && y2 <= layer_height as i32);
+
+            prop_assert!((x1..x2).contains(&player.0));
+            prop_assert!((y1..y2).contains(&player.1));
+
+            prop_assert!(x2 - x1 <= i32::from(view_width));
+            prop_assert!(y2 - y1 <= i32::from(view_height));
+        }
+    }
+}
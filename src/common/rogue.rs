@@ -1,25 +1,72 @@
 //! This module implements the core game logic for the roguelike.
 //! It manages game state, character movement, enemy behavior, and rendering.
 
+use crate::common::autopilot::AutoPilot;
+use crate::common::buffs::{BuffKind, TimedBuff};
+use crate::common::challenge::{Challenge, ChallengeResult, RunRecord};
 use crate::common::character::Renderable;
+use crate::common::charms::echo::Echo;
+use crate::common::combo::ComboMeter;
+use crate::common::coop::{Partner, fits_in_view};
+use crate::common::coords::AreaWrapper::Square;
+use crate::common::deathrecap::{DamageSource, DeathRecap};
+use crate::common::enemies::encounter::Encounter;
 use crate::common::enemies::enemy::{Enemy, EnemyDrops};
 use crate::common::enemies::enemywrangler::EnemyWrangler;
+use crate::common::enemies::phase::Phase;
+use crate::common::entities::EntityCharacters;
+use crate::common::eventlog::EventLog;
+use crate::common::hazards::Hazard;
+use crate::common::hype::Hype;
+use crate::common::input::MoveIntent;
+use crate::common::keybinds::{Action, KeyContext};
 use crate::common::map::Map;
+use crate::common::mutators::mutator::Mutator;
+#[cfg(feature = "netplay")]
+use crate::common::netplay::{ChecksumHasher, NetKey, NetRole, NetSession, NetTurn, Netplay};
+use crate::common::numbers::format_number;
+use crate::common::onkill::OnKillEffect;
+use crate::common::perf::EntityCounts;
+use crate::common::pickups::bomb::Bomb;
 use crate::common::pickups::pickupwrangler::PickupWrangler;
-use crate::common::render::{flatten_to_span, get_camera_area, spans_to_text};
+use crate::common::render::{
+    AdaptiveQuality, aggregate_spans, flatten_to_span, get_camera_area, get_wrapped_camera_area,
+    spans_to_text, wrap_spans,
+};
+use crate::common::runrng;
+use crate::common::runstats::RunStats;
+use crate::common::schedule::DamageSchedule;
 use crate::common::sound::{SoundEffect, SoundWrangler};
+use crate::common::splash::{Splash, SplashArt};
+use crate::common::toasts::{ToastSeverity, Toasts};
+use crate::common::turrets::turretwrangler::TurretWrangler;
+use crate::common::tutorial::{Tutorial, TutorialAction, TutorialProgress};
 use crate::common::upgrades::upgrade::CurrentUpgradesTrait;
-use crate::common::utils::{center, move_entity, per_sec_to_tick_count_to_u64};
+use crate::common::utils::{
+    center, center_horizontal, move_entity, per_sec_to_tick_count_to_u64, ticks_to_duration,
+};
+use crate::common::weapons::{DamageArea, HitReport, resolve_damage};
 use crate::common::widgets::statswidget::StatsWidget;
-use crate::common::{Goto, PlayerStateRef, Viewable};
+use crate::common::{Goto, PlayerStateRef, View, charms::CharmWrapper, weapons::WeaponWrapper};
 use crate::{
     common::{
         TICK_RATE,
         character::{Character, Damageable, Movable},
-        coords::{Direction, Position, SquareArea},
+        collection::achievement::Achievement,
+        coords::{Area, Direction, Position, SquareArea, Topology},
         effects::DamageEffect,
+        events::RunEvent,
         level::Level,
-        popups::{carnagereport::CarnageReport, poweruppopup::PowerupPopup},
+        popups::{
+            carnagereport::{CarnageReport, GameOverChoice},
+            chestpopup::ChestPopup,
+            debugconsole::{ConsoleInput, DebugCommand, DebugConsole},
+            inspectpopup::InspectPopup,
+            looppopup::{LoopChoice, LoopPopup},
+            merchantpopup::{MerchantPopup, MerchantWare},
+            poweruppopup::{MaxedBonus, PowerupPopup},
+        },
+        powerup::{DynPowerup, PowerupTypes, PowerupUpgrade},
         timescaler::TimeScaler,
         upgrades::upgrade::PlayerState,
     },
@@ -29,13 +76,16 @@ use rand::Rng;
 use ratatui::{
     Frame,
     layout::{Constraint, Layout, Rect},
-    style::{Style, Stylize},
+    style::{Modifier, Style, Stylize},
     symbols::border,
-    text::{Line, Text},
-    widgets::{Block, Gauge, Paragraph},
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Gauge, Paragraph},
 };
 use std::cell::RefCell;
+#[cfg(feature = "netplay")]
+use std::hash::{Hash, Hasher};
 use std::rc::Rc;
+use strum::IntoEnumIterator;
 
 pub enum GameState {
     Paused,
@@ -45,6 +95,7 @@ pub enum GameState {
 }
 
 /// Represents the main game state and logic.
+#[allow(clippy::struct_excessive_bools)]
 pub struct Rogue {
     /// The player's current state, including stats and inventory.
     pub player_state: PlayerStateRef,
@@ -55,6 +106,24 @@ pub struct Rogue {
 
     pub powerup_popup: Option<PowerupPopup>,
 
+    pub chest_popup: Option<ChestPopup>,
+
+    /// The offer to loop the run, shown once the timer has been survived.
+    pub loop_popup: Option<LoopPopup>,
+
+    /// How many times the run has looped after surviving the timer.
+    pub loops: u32,
+
+    /// The merchant, while the character is shopping.
+    pub merchant_popup: Option<MerchantPopup>,
+    /// Whether the merchant should show up once nothing else is open.
+    merchant_due: bool,
+    /// Charges for rerolling powerup choices, bought from the merchant.
+    pub reroll_charges: u32,
+
+    /// The kill streak, which makes drops worth more while it's maxed.
+    pub combo: ComboMeter,
+
     pub sound_wrangler: Rc<RefCell<SoundWrangler>>,
 
     /// The rendered map text.
@@ -66,12 +135,19 @@ pub struct Rogue {
     // width: usize,
     pub map: Map,
 
+    /// The seed this run's map was built from, so it can be retried.
+    pub seed: u64,
+
     tickcount: u64,
     pub enemies: Rc<RefCell<Vec<Enemy>>>,
 
     enemy_wrangler: EnemyWrangler,
 
-    attack_ticks: u64,
+    /// The scripted horde events planned for this run.
+    pub encounter: Encounter,
+
+    /// The attack interval of each equipped weapon in ticks, in the same order as the weapons.
+    weapon_attack_ticks: Vec<u64>,
 
     pub game_state: GameState,
 
@@ -79,12 +155,64 @@ pub struct Rogue {
 
     pub active_damage_effects: Vec<DamageEffect>,
 
+    /// Damage areas waiting to land on a later tick.
+    damage_schedule: DamageSchedule,
+
+    /// The direction the player is holding, stepped in on ticks.
+    move_intent: MoveIntent,
+
+    /// The second player, in local co-op.
+    pub partner: Option<Partner>,
+    /// The connection to the other player, in networked co-op.
+    #[cfg(feature = "netplay")]
+    netplay: Option<Netplay>,
+    /// Whether the partner is owed a powerup popup.
+    partner_popup_due: bool,
+    /// Whether the open powerup popup is the partner's.
+    popup_for_partner: bool,
+
     pub pickup_wrangler: PickupWrangler,
 
+    pub turret_wrangler: TurretWrangler,
+
     pub level: Level,
 
+    /// Kills, gold and damage tracked over this run, for the carnage report.
+    pub run_stats: RunStats,
+
+    /// Transient notifications shown in the corner of the view.
+    pub toasts: Toasts,
+
+    /// The guided first-run tutorial, if this run is one.
+    pub tutorial: Option<Tutorial>,
+
+    /// Steers the character while switched on.
+    autopilot: Option<AutoPilot>,
+
+    /// The debug console, while it's open. Only debug builds can open it.
+    debug_console: Option<DebugConsole>,
+
+    /// The inspect popup, while it's open.
+    inspect_popup: Option<InspectPopup>,
+
+    /// Art typed out over the run for a level-up or death, until it's skipped or finishes.
+    splash: Option<Splash>,
+
+    /// A text narration of the run, for the event log pane.
+    pub event_log: EventLog,
+    /// The health last written to the event log, so only changes are narrated.
+    last_logged_health: i32,
+
+    /// How much detail the map is drawn with, lowered while frames run slow.
+    pub adaptive_quality: AdaptiveQuality,
+    /// How long the last call to `render_game` took, counted towards the frame's cost.
+    last_render_cost: Duration,
+
     timer: Duration,
-    start_time: Instant,
+    /// The tick the current loop started on, which the timer counts from.
+    loop_started_at: u64,
+    /// Whether the run is paused for being hidden.
+    suspended: bool,
 
     start_popup: bool,
 
@@ -95,23 +223,41 @@ pub struct Rogue {
 }
 
 impl Rogue {
-    const DEFAULT_ATTACK_P_S: f64 = 1.5;
-
+    /// How many rows the event log pane takes below the map, borders included.
+    const EVENT_LOG_HEIGHT: u16 = 7;
+    /// How much the difficulty jumps at the start of each loop.
+    const LOOP_DOOM_MULT: f64 = 2.;
+    /// How much gold is multiplied by for each loop, stacking with every loop.
+    const LOOP_GOLD_MULT: f64 = 1.5;
+    /// The merchant shows up every this many levels.
+    const MERCHANT_LEVEL_INTERVAL: i32 = 5;
+
+    /// Creates a new run on a randomly seeded map.
     #[must_use]
     pub fn new(player_state: &PlayerStateRef, sound_wrangler: Rc<RefCell<SoundWrangler>>) -> Self {
+        Self::with_seed(player_state, sound_wrangler, rand::random())
+    }
+
+    /// Creates a new run whose map is built from `seed`.
+    #[must_use]
+    pub fn with_seed(
+        player_state: &PlayerStateRef,
+        sound_wrangler: Rc<RefCell<SoundWrangler>>,
+        seed: u64,
+    ) -> Self {
         let init_player_state = player_state.borrow().clone();
 
         let width = init_player_state.stats.game_stats.width;
         let height = init_player_state.stats.game_stats.height;
 
-        let map = Map::new(width, height);
+        tracing::info!(seed, width, height, "run started");
+        runrng::reseed(seed);
 
-        let attack_ticks = per_sec_to_tick_count_to_u64(Self::DEFAULT_ATTACK_P_S);
+        let map = Map::new(width, height, seed);
 
-        let start_time = Instant::now();
         let timer = Duration::from_secs(init_player_state.stats.game_stats.timer);
 
-        let timescaler = Rc::new(RefCell::new(TimeScaler::now()));
+        let timescaler = Rc::new(RefCell::new(TimeScaler::new()));
         timescaler
             .borrow_mut()
             .offset_doom(init_player_state.stats.game_stats.doom_offset);
@@ -120,17 +266,28 @@ impl Rogue {
 
         let level = Level::new();
 
+        let encounter = Encounter::new(&init_player_state.stats.game_stats);
+
         let pickup_wrangler = PickupWrangler::new(player_state.clone());
 
         let mut game = Rogue {
             goto: Goto::Game,
 
             player_state: player_state.clone(),
-            init_state: init_player_state,
+            init_state: init_player_state.clone(),
             character: Character::new(&player_state.clone(), sound_wrangler.clone()),
+            partner: init_player_state
+                .settings
+                .local_coop
+                .then(|| Partner::new(player_state, sound_wrangler.clone())),
+            partner_popup_due: false,
+            popup_for_partner: false,
+            #[cfg(feature = "netplay")]
+            netplay: None,
 
             map,
-            attack_ticks,
+            seed,
+            weapon_attack_ticks: vec![],
 
             enemy_wrangler: EnemyWrangler::new(
                 player_state.clone(),
@@ -138,6 +295,7 @@ impl Rogue {
                 enemies.clone(),
                 sound_wrangler.clone(),
             ),
+            encounter,
             sound_wrangler,
 
             map_text: Text::from(""),
@@ -147,14 +305,36 @@ impl Rogue {
 
             carnage_report: None,
             powerup_popup: None,
+            chest_popup: None,
+            loop_popup: None,
+            loops: 0,
+            merchant_popup: None,
+            merchant_due: false,
+            reroll_charges: 0,
+            combo: ComboMeter::default(),
 
             level,
+            run_stats: RunStats::new(init_player_state.inventory.gold),
+            toasts: Toasts::new(),
+            tutorial: None,
+            autopilot: None,
+            debug_console: None,
+            inspect_popup: None,
+            splash: None,
+            event_log: EventLog::new(),
+            last_logged_health: 0,
+            adaptive_quality: AdaptiveQuality::default(),
+            last_render_cost: Duration::ZERO,
 
             tickcount: 0,
             enemies,
             pickup_wrangler,
+            turret_wrangler: TurretWrangler::new(),
             active_damage_effects: vec![],
-            start_time,
+            damage_schedule: DamageSchedule::default(),
+            move_intent: MoveIntent::default(),
+            loop_started_at: 0,
+            suspended: false,
             timer,
             timescaler,
 
@@ -167,7 +347,7 @@ impl Rogue {
 
         game.init_character();
 
-        game.update_stats_with_charms();
+        game.update_run_modifiers();
         game.update_stats();
 
         game.sound_wrangler.borrow().play(SoundEffect::RoundStart);
@@ -179,335 +359,2300 @@ impl Rogue {
         game
     }
 
+    /// Creates the guided tutorial run, on a fixed map with no natural spawns or timer.
+    #[must_use]
+    pub fn tutorial(
+        player_state: &PlayerStateRef,
+        sound_wrangler: Rc<RefCell<SoundWrangler>>,
+    ) -> Self {
+        let mut game = Self::with_seed(player_state, sound_wrangler, Tutorial::SEED);
+
+        game.tutorial = Some(Tutorial::new());
+        game.timer = Duration::from_secs(Tutorial::TIMER_SECS);
+        game.enemy_wrangler.natural_spawns = false;
+        game.partner = None;
+
+        game
+    }
+
+    /// Creates a run played in lockstep with another player over `session`, on the host's seed
+    /// and save. A guest plays on a copy of the host's save, leaving their own untouched.
+    #[cfg(feature = "netplay")]
+    #[must_use]
+    pub fn networked(
+        player_state: &PlayerStateRef,
+        sound_wrangler: Rc<RefCell<SoundWrangler>>,
+        mut session: NetSession,
+    ) -> Self {
+        let player_state = session.take_host_state().map_or_else(
+            || player_state.clone(),
+            |host_state| Rc::new(RefCell::new(host_state)),
+        );
+        let seed = session.get_seed().unwrap_or_default();
+
+        let mut game = Self::with_seed(&player_state, sound_wrangler.clone(), seed);
+        if game.partner.is_none() {
+            game.partner = Some(Partner::new(&player_state, sound_wrangler));
+            game.init_character();
+            game.update_run_modifiers();
+            game.update_stats();
+        }
+        game.netplay = Some(Netplay::new(session));
+
+        game
+    }
+
+    #[allow(clippy::too_many_lines)]
     pub fn on_tick(&mut self) {
+        if self.splash.as_ref().is_some_and(Splash::is_finished) {
+            self.splash = None;
+        }
+
+        #[cfg(feature = "netplay")]
+        if !self.play_netplay_turn() {
+            return;
+        }
+
         self.handle_popup();
+        self.toasts.clear_expired();
 
         match self.game_state {
             GameState::Paused | GameState::Exit => {}
             GameState::GameOver => {
-                self.carnage_report = Some(CarnageReport::new(
-                    self.init_state.clone(),
-                    self.player_state.borrow().clone(),
-                ));
+                if self.carnage_report.is_none() {
+                    self.end_run();
+                }
             }
             GameState::Play => {
                 self.tickcount += 1;
+                self.timescaler.borrow_mut().tick();
 
                 if self.pickup_wrangler.start_popup {
-                    self.start_popup = true;
+                    self.queue_powerups();
                     self.pickup_wrangler.start_popup = false;
                 }
 
-                if self.start_time.elapsed() >= self.timer {
-                    self.game_state = GameState::GameOver;
+                if self.get_loop_elapsed() >= self.timer {
+                    if self.tutorial.is_none() && self.is_anyone_alive() {
+                        self.offer_loop();
+                    } else {
+                        self.game_state = GameState::GameOver;
+                    }
                     return;
                 }
 
-                if !self.character.is_alive() {
+                if !self.is_anyone_alive() {
                     self.game_state = GameState::GameOver;
                     return;
                 }
 
-                if self.level.update().is_some() {
-                    self.start_popup = true;
+                if let Some(level) = self.level.update() {
+                    self.earn_achievements(&RunEvent::LevelUp(level));
+                    self.notify(format!("reached level {level}"), ToastSeverity::Info);
+                    self.queue_powerups();
+
+                    if self.tutorial.is_none() && level % Self::MERCHANT_LEVEL_INTERVAL == 0 {
+                        self.merchant_due = true;
+                    }
                 }
 
-                if self.start_popup {
-                    self.generate_popup();
+                self.open_due_popup();
+                self.tick_buffs();
+                self.combo.tick();
+
+                self.character.on_tick(self.tickcount);
+                if let Some(partner) = &mut self.partner {
+                    partner.character.on_tick(self.tickcount);
                 }
 
+                self.steer_with_autopilot();
+                self.step_character();
+                self.step_partner();
+
                 let char_pos = self.get_character_pos().clone();
 
+                let player_positions = self.get_player_positions();
                 self.pickup_wrangler.on_tick(
                     self.tickcount,
-                    &char_pos,
+                    &player_positions,
                     &mut self.active_damage_effects,
                 );
 
-                let drops = self.enemy_wrangler.on_tick(
-                    self.tickcount,
-                    &mut self.character,
-                    &self.map.map,
-                    &mut self.active_damage_effects,
+                if let Some(size) = self.encounter.take_warning(self.get_loop_elapsed()) {
+                    self.notify(format!("horde of {size} incoming"), ToastSeverity::Warning);
+                }
+
+                if self.tutorial.is_some() {
+                    self.tick_tutorial();
+                } else if let Some(size) = self.encounter.take_due_horde(self.get_loop_elapsed()) {
+                    self.enemy_wrangler
+                        .spawn_horde(&self.map, size, &self.character);
+                }
+
+                for position in std::mem::take(&mut self.pickup_wrangler.bomb_detonations) {
+                    self.detonate_bomb(&position);
+                }
+
+                let phase = Phase::at(self.get_loop_elapsed()).0;
+                if phase != self.enemy_wrangler.phase && phase == Phase::Surge {
+                    self.notify("SURGE: enemies are swarming", ToastSeverity::Warning);
+                }
+                self.enemy_wrangler.phase = phase;
+
+                self.close_walls();
+
+                self.character
+                    .set_terrain_cost(self.map.get_biome(&char_pos).movement_cost());
+                if let Some(partner) = &mut self.partner {
+                    let biome = self.map.get_biome(partner.character.get_pos());
+                    partner.character.set_terrain_cost(biome.movement_cost());
+                }
+
+                self.tick_enemies();
+
+                self.log_health();
+
+                self.run_stats.sample(
+                    self.timer * self.loops + self.get_loop_elapsed(),
+                    self.enemy_wrangler.kill_count,
+                    self.player_state.borrow().inventory.gold,
                 );
 
-                for drop in drops {
-                    self.consume_drops(&drop);
+                if self
+                    .tickcount
+                    .is_multiple_of((TICK_RATE / Hazard::HURTS_P_S).floor() as u64)
+                {
+                    self.apply_hazards();
                 }
+                self.apply_charm_drains();
 
                 if self.tickcount.is_multiple_of(TICK_RATE.floor() as u64) {
                     self.scale();
                 }
 
-                if self.tickcount.is_multiple_of(self.attack_ticks) {
-                    let (damage_areas, mut damage_effects) =
-                        self.character.attack(&self.map.map, &self.enemies.borrow());
-                    for area in damage_areas {
-                        area.deal_damage(
-                            &mut self.enemies.borrow_mut(),
-                            self.sound_wrangler.clone(),
-                        );
-                    }
-                    self.active_damage_effects.append(&mut damage_effects);
-                }
+                self.resolve_tick_damage();
             }
         }
     }
 
-    fn handle_popup(&mut self) {
-        if let Some(powerup_popup) = self.powerup_popup.take() {
-            if powerup_popup.finished {
-                self.game_state = GameState::Play;
-                self.character.weapons = powerup_popup.weapons;
-                self.character.charms = powerup_popup.charms;
-                self.reset_stats();
-                self.update_stats_with_charms();
-                self.update_stats();
+    /// Moves the enemies on, then counts the kills towards the combo and takes their drops.
+    fn tick_enemies(&mut self) {
+        let kills_before = self.enemy_wrangler.kill_count;
+        let drops = self.enemy_wrangler.on_tick(
+            self.tickcount,
+            &mut self.character,
+            self.partner.as_mut().map(|partner| &mut partner.character),
+            &mut self.map,
+            &self.camera_area,
+            &mut self.active_damage_effects,
+        );
 
-                self.player_state.borrow_mut().upgrades.set("A", 1);
-            } else {
-                self.powerup_popup = Some(powerup_popup);
-            }
+        self.combo
+            .add_kills(self.enemy_wrangler.kill_count - kills_before);
+        for drop in drops {
+            self.consume_drops(&drop);
+        }
+
+        if self.partner.as_mut().is_some_and(Partner::take_down) {
+            self.notify("player 2 is down", ToastSeverity::Warning);
         }
     }
 
-    pub fn consume_drops(&mut self, drops: &EnemyDrops) {
-        let mut player_state = self.player_state.borrow_mut();
+    /// Takes a point of health for each CHARM whose drain is due. The drain is a normal hit, so
+    /// shield and i-frames soak it.
+    fn apply_charm_drains(&mut self) {
+        let due: Vec<&'static str> = self
+            .character
+            .charms
+            .iter()
+            .filter(|charm| {
+                charm
+                    .get_inner()
+                    .get_health_drain()
+                    .is_some_and(|interval| {
+                        let ticks = (interval.as_secs_f64() * TICK_RATE).round().max(1.) as u64;
+                        self.tickcount.is_multiple_of(ticks)
+                    })
+            })
+            .map(Into::into)
+            .collect();
+
+        for name in due {
+            self.character
+                .take_damage_from(1, DamageSource::Charm(name));
+        }
+    }
 
-        player_state.inventory.gold +=
-            (drops.gold as f64 * player_state.stats.game_stats.gold_mult) as u128;
-        self.level.add_xp(drops.xp);
+    /// Opens the next popup that's waiting: a powerup, then a chest, then the merchant.
+    fn open_due_popup(&mut self) {
+        if self.start_popup {
+            self.generate_popup(false);
+        } else if self.partner_popup_due {
+            self.generate_popup(true);
+        } else if let Some(rewards) = self.pickup_wrangler.chest_rewards.take() {
+            self.generate_chest_popup(rewards);
+        } else if self.merchant_due {
+            self.generate_merchant_popup();
+        }
     }
 
-    pub fn on_frame(&mut self) {
-        if let GameState::Play = self.game_state {
-            update_effects(&mut self.active_damage_effects);
+    /// Wraps up a finished run and opens the carnage report.
+    fn end_run(&mut self) {
+        self.event_log.push(format!(
+            "Run over: {} kills",
+            self.enemy_wrangler.kill_count
+        ));
 
-            self.active_damage_effects = self
-                .active_damage_effects
-                .clone()
-                .into_iter()
-                .filter(|effect| !effect.complete)
-                .collect();
+        tracing::info!(
+            seed = self.seed,
+            kills = self.enemy_wrangler.kill_count,
+            loops = self.loops,
+            survival_secs = self.get_survival_time().as_secs(),
+            "run over"
+        );
 
-            self.enemy_wrangler.on_frame();
+        #[cfg(feature = "leaderboard")]
+        if self.tutorial.is_none() {
+            self.submit_to_leaderboard();
+        }
 
-            self.camera_area =
-                get_camera_area(self.view_area, self.get_character_pos(), &self.map.map);
+        let death_recap = (!self.character.is_alive())
+            .then(|| DeathRecap::new(self.character.get_damage_log(), self.character.get_build()));
 
-            let spans = flatten_to_span(&self, Some(self.camera_area.clone()));
+        let challenge = self.tutorial.is_none().then(|| self.record_run());
 
-            self.map_text = spans_to_text(spans);
+        if death_recap.is_some() {
+            self.show_splash(SplashArt::Death);
         }
+
+        self.carnage_report = Some(CarnageReport::new(
+            self.init_state.clone(),
+            self.player_state.borrow().clone(),
+            self.run_stats.clone(),
+            death_recap,
+            challenge,
+        ));
     }
 
-    pub fn update_stats(&mut self) {
-        self.attack_ticks = per_sec_to_tick_count_to_u64(Self::DEFAULT_ATTACK_P_S);
-        self.attack_ticks = (self.attack_ticks as f64
-            / self
+    /// Keeps the finished run in the player's history under its challenge code, returning how it
+    /// compares to earlier runs on the same challenge.
+    fn record_run(&mut self) -> ChallengeResult {
+        let run = RunRecord {
+            challenge: Challenge::from_run(self.seed, &self.init_state).encode(),
+            survival_secs: self.get_survival_time().as_secs(),
+            kills: self.enemy_wrangler.kill_count,
+        };
+        self.event_log
+            .push(format!("Challenge code: {}", run.challenge));
+
+        let mut player_state = self.player_state.borrow_mut();
+        let previous_best = RunRecord::get_best(&player_state.run_history, &run.challenge).cloned();
+        player_state.record_run(run.clone());
+
+        ChallengeResult { run, previous_best }
+    }
+
+    /// Moves the tutorial on when its current step is complete, and sets up the next one.
+    fn tick_tutorial(&mut self) {
+        let progress = TutorialProgress {
+            kills: self.enemy_wrangler.kill_count,
+            gold: self
                 .player_state
                 .borrow()
-                .stats
-                .game_stats
-                .attack_speed_mult)
-            .ceil() as u64;
+                .inventory
+                .gold
+                .saturating_sub(self.init_state.inventory.gold),
+            popup_open: self.powerup_popup.is_some(),
+        };
 
-        let offset = self.player_state.borrow().stats.game_stats.doom_offset;
+        let Some(tutorial) = &mut self.tutorial else {
+            return;
+        };
 
-        self.timescaler.borrow_mut().offset_doom(offset);
+        match tutorial.advance(&progress) {
+            Some(TutorialAction::SpawnEnemies(count)) => {
+                for _ in 0..count {
+                    self.enemy_wrangler.spawn_enemy(&self.map);
+                }
+            }
+            Some(TutorialAction::OpenPowerup) => self.start_popup = true,
+            None => {}
+        }
     }
 
-    pub fn generate_popup(&mut self) {
-        self.game_state = GameState::Paused;
-        self.powerup_popup = Some(PowerupPopup::new(
-            &self.character.weapons,
-            &self.character.charms,
-            self.player_state.borrow().stats.weapon_stats.clone(),
-            self.player_state.clone(),
-        ));
-        self.start_popup = false;
+    /// Ends the tutorial, finished or skipped, and heads to the upgrades menu.
+    fn finish_tutorial(&mut self) {
+        self.tutorial = None;
+        self.player_state.borrow_mut().tutorial_done = true;
+        self.game_state = GameState::Exit;
+        self.goto = Goto::Upgrades;
     }
 
-    fn scale(&mut self) -> f64 {
-        self.timescaler.borrow_mut().scale()
-    }
+    fn handle_popup(&mut self) {
+        if let Some(powerup_popup) = self.powerup_popup.take() {
+            if powerup_popup.finished {
+                let for_partner = std::mem::take(&mut self.popup_for_partner);
 
-    pub fn key_event(&mut self, key_event: &KeyEvent) {
-        if self.carnage_report.is_some() {
-            if key_event.code == KeyCode::Esc {
-                self.game_state = GameState::Exit;
-                self.goto = Goto::Upgrades;
-            }
-        } else if let Some(powerup_popup) = &mut self.powerup_popup {
-            powerup_popup.handle_key_event(key_event);
-        } else {
-            match key_event.code {
-                KeyCode::Char('s') | KeyCode::Down => {
-                    move_entity(&mut self.map.map, &mut self.character, Direction::DOWN);
+                if powerup_popup.taken_bonus == Some(MaxedBonus::Heal) {
+                    let player = self.get_player_mut(for_partner);
+                    let max_health = player.get_max_health();
+                    player.heal(max_health * PowerupPopup::MAXED_HEAL_PERCENT / 100);
                 }
-                KeyCode::Char('w') | KeyCode::Up => {
-                    move_entity(&mut self.map.map, &mut self.character, Direction::UP);
-                }
-                KeyCode::Char('d') | KeyCode::Right => {
-                    move_entity(&mut self.map.map, &mut self.character, Direction::RIGHT);
+
+                self.reroll_charges = powerup_popup.rerolls;
+                self.apply_loadout(powerup_popup.weapons, powerup_popup.charms, for_partner);
+
+                self.player_state.borrow_mut().upgrades.set("A", 1);
+            } else {
+                self.powerup_popup = Some(powerup_popup);
+            }
+        }
+
+        if let Some(chest_popup) = self.chest_popup.take() {
+            if chest_popup.finished {
+                self.apply_loadout(chest_popup.weapons, chest_popup.charms, false);
+            } else {
+                self.chest_popup = Some(chest_popup);
+            }
+        }
+
+        if let Some(merchant_popup) = self.merchant_popup.take() {
+            if merchant_popup.finished {
+                self.apply_merchant_wares(&merchant_popup.bought);
+            } else {
+                self.merchant_popup = Some(merchant_popup);
+            }
+        }
+
+        if let Some(loop_popup) = self.loop_popup.take() {
+            match loop_popup.choice {
+                Some(LoopChoice::Loop) => self.start_loop(),
+                Some(LoopChoice::End) => self.game_state = GameState::GameOver,
+                None => self.loop_popup = Some(loop_popup),
+            }
+        }
+    }
+
+    /// Gives the character what was bought from the merchant and carries on with the run.
+    fn apply_merchant_wares(&mut self, wares: &[MerchantWare]) {
+        for ware in wares {
+            match ware {
+                MerchantWare::Heal => {
+                    let max_health = self.character.get_max_health();
+                    self.character
+                        .heal(max_health * MerchantPopup::HEAL_PERCENT / 100);
                 }
-                KeyCode::Char('a') | KeyCode::Left => {
-                    move_entity(&mut self.map.map, &mut self.character, Direction::LEFT);
+                MerchantWare::RerollCharge => self.reroll_charges += 1,
+                MerchantWare::Frenzy => {
+                    self.character
+                        .add_buff(BuffKind::Frenzy, MerchantPopup::FRENZY);
+                    self.update_run_modifiers();
+                    self.update_stats();
                 }
-                KeyCode::Char(']') => {
-                    self.player_state.borrow_mut().inventory.gold += 10000;
+                MerchantWare::Powerup => {
+                    *self.pickup_wrangler.chest_rewards.get_or_insert(0) += 1;
                 }
-                KeyCode::Esc => self.game_state = GameState::GameOver,
-                #[cfg(debug_assertions)]
-                KeyCode::Char('u') => self.generate_popup(),
-                _ => {}
             }
+
+            self.event_log
+                .push(format!("Bought {} from the merchant", ware.get_name()));
         }
-    }
 
-    pub fn init_character(&mut self) {
-        let mut rng = rand::rng();
+        self.game_state = GameState::Play;
+    }
 
-        let (x, y) = (
-            rng.random_range(0..self.map.width) as i32,
-            rng.random_range(0..self.map.height) as i32,
-        );
+    /// Counts the character's buffs down, dropping their effects once they run out.
+    fn tick_buffs(&mut self) {
+        let expired = self.character.tick_buffs();
+        if expired.is_empty() {
+            return;
+        }
 
-        self.character.set_pos(Position(x, y));
+        self.update_run_modifiers();
+        self.update_stats();
+        for kind in expired {
+            self.notify(
+                format!("{} wore off", kind.get_name().to_lowercase()),
+                ToastSeverity::Info,
+            );
+        }
     }
 
-    pub fn reset_stats(&mut self) {
-        self.player_state.borrow_mut().refresh();
+    /// Returns the gold multiplier from the loops completed so far.
+    fn get_loop_gold_mult(loops: u32) -> f64 {
+        Self::LOOP_GOLD_MULT.powi(loops as i32)
     }
 
-    pub fn update_stats_with_charms(&mut self) {
-        self.character.charms.iter().for_each(|charm_wrapper| {
-            charm_wrapper
-                .get_inner()
-                .manipulate_stats(&mut self.player_state.borrow_mut().stats);
-        });
+    /// Pauses the run and asks whether to loop it, now the timer has been survived.
+    fn offer_loop(&mut self) {
+        self.game_state = GameState::Paused;
+        self.event_log
+            .push(format!("Loop {} survived", self.loops + 1));
+        self.loop_popup = Some(LoopPopup::new(
+            self.loops + 1,
+            Self::get_loop_gold_mult(self.loops + 1),
+        ));
     }
 
-    /// Returns the character's current position.
-    #[must_use]
-    pub fn get_character_pos(&self) -> &Position {
-        self.character.get_pos()
-    }
+    /// Starts the run over on the same map with the same build, with the timer reset, the
+    /// difficulty raised, special enemies more common, and gold worth more.
+    fn start_loop(&mut self) {
+        self.loops += 1;
+        self.run_stats.loops = self.loops;
 
-    pub fn render_game(&mut self, frame: &mut Frame) {
-        let timer = self.timer.saturating_sub(self.start_time.elapsed());
+        self.loop_started_at = self.tickcount;
+        self.encounter = Encounter::new(&self.player_state.borrow().stats.game_stats);
+        self.map.open_up();
 
-        let title = Line::from(" dispair.run ".bold());
+        let doom = self.timescaler.borrow().doom;
+        self.timescaler
+            .borrow_mut()
+            .set_doom(doom * Self::LOOP_DOOM_MULT);
+        self.enemy_wrangler
+            .director
+            .set_special_mult(self.loops + 1);
+
+        tracing::info!(loops = self.loops, "run looped");
+        self.notify(
+            format!(
+                "loop {}: gold x{:.2}",
+                self.loops + 1,
+                Self::get_loop_gold_mult(self.loops)
+            ),
+            ToastSeverity::Warning,
+        );
 
-        let instructions = Line::from(vec![
-            " Health: ".dark_gray(),
-            self.character.get_health().to_string().bold(),
-            " ".into(),
-            " Time: ".dark_gray(),
-            timer.as_secs().to_string().bold(),
-            " ".into(),
-            " Gold: ".dark_gray(),
-            self.player_state.borrow().inventory.gold.to_string().into(),
-            " ".into(),
-        ]);
-        let block = Block::bordered()
-            .title(title)
-            .title_bottom(instructions.right_aligned())
-            .border_set(border::THICK);
+        self.game_state = GameState::Play;
+    }
 
-        let mut game_area = block.inner(frame.area());
-        frame.render_widget(&block, frame.area());
+    /// How long the current loop has been played for, counted in ticks so every machine in a
+    /// networked run agrees on it.
+    fn get_loop_elapsed(&self) -> Duration {
+        ticks_to_duration(self.tickcount - self.loop_started_at)
+    }
 
-        if self.player_state.borrow().upgrade_owned("A") {
-            let progress_bar_area;
+    /// How long the character has survived across every loop of the run.
+    fn get_survival_time(&self) -> Duration {
+        self.timer * self.loops + self.get_loop_elapsed().min(self.timer)
+    }
 
-            [progress_bar_area, game_area] =
-                Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(game_area);
+    /// Gathers every damage area landing this tick, from METHOD attacks, the damage schedule,
+    /// turrets and staged effects, and deals them in a single pass so overlapping areas resolve
+    /// the same whatever order they came in. Turret shots don't count towards lifesteal, and the
+    /// partner's METHODs only heal the partner.
+    fn resolve_tick_damage(&mut self) {
+        let mut areas = self.attack_with_ready_weapons();
+        areas.append(&mut self.take_scheduled_damage());
+        let mut turret_areas = self.fire_turrets();
+        areas.append(&mut self.take_stage_hits());
+        let mut partner_areas = self.attack_with_partner_weapons();
+
+        let lifesteal_areas = areas.len();
+        areas.append(&mut turret_areas);
+        let partner_start = areas.len();
+        areas.append(&mut partner_areas);
+
+        let dealt = self.deal_areas_damage(&areas);
+        self.character
+            .lifesteal(dealt[..lifesteal_areas].iter().sum(), self.tickcount);
+        if let Some(partner) = &mut self.partner {
+            partner
+                .character
+                .lifesteal(dealt[partner_start..].iter().sum(), self.tickcount);
+        }
 
-            let progress_bar = Gauge::default()
-                .gauge_style(Style::new().light_blue())
-                .percent(self.level.get_progress_percentage());
+        let mut rubble_drops = vec![];
+        for area in &areas {
+            let destroyed_walls = self.map.destroy_walls(area);
+            rubble_drops.append(&mut self.enemy_wrangler.get_rubble_drops(&destroyed_walls));
+        }
+        for drop in rubble_drops {
+            self.consume_drops(&drop);
+        }
+    }
 
-            frame.render_widget(progress_bar, progress_bar_area);
+    /// Attacks with every weapon whose attack interval lands on this tick, and deploys any
+    /// turrets they place. Returns the attacks' damage areas.
+    fn attack_with_ready_weapons(&mut self) -> Vec<DamageArea> {
+        let ready_weapons = get_ready_weapons(&self.weapon_attack_ticks, self.tickcount);
+
+        if ready_weapons.is_empty() || !self.character.is_alive() {
+            return vec![];
         }
 
-        let [stats_area, view_area] =
-            Layout::horizontal([Constraint::Length(27), Constraint::Fill(1)]).areas(game_area);
+        let (damage_areas, mut damage_effects) =
+            self.character
+                .attack(&ready_weapons, &self.map.map, &self.enemies.borrow());
+        self.queue_echoes(&damage_areas);
+        self.active_damage_effects.append(&mut damage_effects);
 
-        self.view_area = view_area;
+        for turret in self.character.deploy_turrets(&ready_weapons) {
+            self.turret_wrangler.deploy(turret);
+        }
 
-        let content_area = self.view_area;
+        damage_areas
+    }
 
-        let height = self.map_text.lines.len() as u16;
-        let width = self.map_text.lines[0].iter().len() as u16;
+    /// Attacks with each of the partner's weapons whose attack interval lands on this tick, while
+    /// they're still standing. Returns the attacks' damage areas.
+    fn attack_with_partner_weapons(&mut self) -> Vec<DamageArea> {
+        let Some(partner) = self
+            .partner
+            .as_mut()
+            .filter(|partner| partner.character.is_alive())
+        else {
+            return vec![];
+        };
 
-        let centered_area = center(content_area, width, height);
+        let ready_weapons = get_ready_weapons(&partner.weapon_attack_ticks, self.tickcount);
+        if ready_weapons.is_empty() {
+            return vec![];
+        }
 
-        let content = Paragraph::new(self.map_text.clone()).centered();
+        let (damage_areas, mut damage_effects) =
+            partner
+                .character
+                .attack(&ready_weapons, &self.map.map, &self.enemies.borrow());
+        self.active_damage_effects.append(&mut damage_effects);
 
-        frame.render_widget(content, centered_area);
+        for turret in partner.character.deploy_turrets(&ready_weapons) {
+            self.turret_wrangler.deploy(turret);
+        }
 
-        let stats_widget = StatsWidget::new(
-            self.player_state.clone(),
-            &self.enemy_wrangler,
-            &self.character.weapons,
-            &self.character.charms,
-        );
+        damage_areas
+    }
 
-        frame.render_widget(stats_widget, stats_area);
+    /// Rolls each METHOD attack in `areas` against the CHARMs' echo chance, queueing a weaker,
+    /// dimmed copy of it to land a moment later for each that succeeds.
+    fn queue_echoes(&mut self, areas: &[DamageArea]) {
+        let Some(echo) = self
+            .character
+            .charms
+            .iter()
+            .find_map(|charm| charm.get_inner().get_echo())
+        else {
+            return;
+        };
 
-        if let Some(ref mut carnage) = self.carnage_report {
-            carnage.render(frame);
-        }
+        let mut rng = runrng::rng();
+        for area in areas {
+            if !rng.random_bool(echo.chance.clamp(0., 1.)) {
+                continue;
+            }
 
-        if let Some(ref mut powerup_popup) = self.powerup_popup {
-            powerup_popup.render(frame);
+            let mut echo_area = area.clone();
+            echo_area.damage_amount =
+                (f64::from(area.damage_amount) * echo.damage_mult).ceil() as i32;
+            echo_area.destroys_walls = false;
+            let style = echo_area.entity.style_mut();
+            *style = style.add_modifier(Modifier::DIM);
+
+            self.damage_schedule
+                .schedule(self.tickcount, Echo::DELAY, echo_area);
         }
     }
-}
 
-/// Calculates the camera's visible area based on the player's position and the layer dimensions.
+    /// Takes every scheduled area that's due this tick and shows it. Returns the areas.
+    fn take_scheduled_damage(&mut self) -> Vec<DamageArea> {
+        let due = self.damage_schedule.take_due(self.tickcount);
+        self.active_damage_effects
+            .extend(due.iter().cloned().map(DamageEffect::from));
 
-impl Viewable for Rogue {
-    fn tick(&mut self) {
-        self.on_tick();
+        due
     }
 
-    fn frame(&mut self) {
-        self.on_frame();
-    }
+    /// Lets every turret on the map take its shot at nearby enemies. Returns the shots' areas.
+    fn fire_turrets(&mut self) -> Vec<DamageArea> {
+        let turret_areas =
+            self.turret_wrangler
+                .on_tick(self.tickcount, &self.enemies.borrow(), &self.map.map);
+        self.active_damage_effects
+            .extend(turret_areas.iter().cloned().map(DamageEffect::from));
 
-    fn get_goto(&self) -> &Goto {
-        &self.goto
+        turret_areas
     }
 
-    fn render(&mut self, frame: &mut Frame) {
-        self.render_game(frame);
+    /// Takes the area of every staged damage effect that has swept into a new stage.
+    fn take_stage_hits(&mut self) -> Vec<DamageArea> {
+        self.active_damage_effects
+            .iter_mut()
+            .flat_map(DamageEffect::take_stage_hits)
+            .collect()
     }
 
-    fn handle_key_event(&mut self, key_event: &KeyEvent) {
-        self.key_event(key_event);
-    }
-}
+    /// Deals the damage of `areas` in a single pass, then records each area's hits. Returns the
+    /// damage each area dealt, in the same order.
+    fn deal_areas_damage(&mut self, areas: &[DamageArea]) -> Vec<i32> {
+        let reports = resolve_damage(areas, &mut self.enemies.borrow_mut(), &self.sound_wrangler);
 
-pub fn update_effects(damage_effects: &mut [DamageEffect]) {
-    for effect in damage_effects.iter_mut() {
-        effect.update();
+        areas
+            .iter()
+            .zip(&reports)
+            .map(|(area, report)| self.record_hits(area, report))
+            .collect()
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use std::cell::RefCell;
-    use std::rc::Rc;
-    use std::time::Instant;
+    /// Records an area's hits in the run stats, narrates any kills in the event log and sets off
+    /// on-kill effects. Returns the damage dealt.
+    fn record_hits(&mut self, area: &DamageArea, report: &HitReport) -> i32 {
+        let dealt = report.get_total_damage();
+        let killed = report.get_kill_count();
 
-    use crate::common::render::{flatten_to_span, spans_to_text};
-    use crate::common::sound::SoundWrangler;
-    use crate::common::{rogue::Rogue, upgrades::upgrade::PlayerState};
+        if killed > 0 {
+            self.trigger_on_kill_effects(area, report);
+            self.record_weapon_kills(area, killed as u64);
+        }
 
-    #[test]
-    fn renderspeed() {
+        self.run_stats.record_damage(area, dealt);
+
+        if killed > 0 {
+            let source = area.source.unwrap_or("unknown").to_uppercase();
+            self.event_log.push(if killed == 1 {
+                format!("Enemy killed by {source}")
+            } else {
+                format!("{killed} enemies killed by {source}")
+            });
+        }
+
+        dealt
+    }
+
+    /// Sets off the on-kill effects of the METHOD behind `area` and of every CHARM, once for each
+    /// enemy it killed. Areas not from a METHOD, such as the effects themselves, set off nothing.
+    fn trigger_on_kill_effects(&mut self, area: &DamageArea, report: &HitReport) {
+        let Some(weapon_stats) = &area.weapon_stats else {
+            return;
+        };
+
+        let effects: Vec<OnKillEffect> = weapon_stats
+            .on_kill
+            .values()
+            .copied()
+            .chain(
+                self.character
+                    .charms
+                    .iter()
+                    .flat_map(|charm| charm.get_inner().get_on_kill_effects()),
+            )
+            .collect();
+        if effects.is_empty() {
+            return;
+        }
+
+        let enemies = self.enemies.borrow();
+        for hit in report.hits.iter().filter(|hit| hit.killed) {
+            let Some(enemy) = enemies.iter().find(|enemy| enemy.get_id() == hit.id) else {
+                continue;
+            };
+            let position = enemy.get_pos();
+            for effect in &effects {
+                if let Some(effect_area) =
+                    effect.get_damage_area(area, position, &enemies, &self.map.map)
+                {
+                    self.active_damage_effects
+                        .push(DamageEffect::from(effect_area));
+                }
+            }
+        }
+    }
+
+    /// Counts kills towards the mastery of the METHOD behind `area`, announcing any tier reached.
+    fn record_weapon_kills(&mut self, area: &DamageArea, kills: u64) {
+        let Some(source) = area.source else {
+            return;
+        };
+
+        let reached = self
+            .player_state
+            .borrow_mut()
+            .add_weapon_kills(source, kills);
+        if let Some(tier) = reached {
+            self.notify(
+                format!("{} mastery: {}", source.to_uppercase(), tier.get_name()),
+                ToastSeverity::Success,
+            );
+        }
+    }
+
+    /// Shows a toast and writes the same message to the event log.
+    fn notify(&mut self, message: impl Into<String>, severity: ToastSeverity) {
+        let message = message.into();
+        self.event_log.push(message.clone());
+        self.toasts.push(message, severity);
+    }
+
+    /// Keeps any achievements `event` earns, announcing them and any METHODs they unlock.
+    fn earn_achievements(&mut self, event: &RunEvent) {
+        for achievement in Achievement::earned_by(event) {
+            let was_locked: Vec<WeaponWrapper> = WeaponWrapper::iter()
+                .filter(|weapon| !weapon.is_unlocked(&self.player_state.borrow()))
+                .collect();
+
+            if !self.player_state.borrow_mut().earn(achievement) {
+                continue;
+            }
+
+            tracing::info!(?achievement, "achievement earned");
+            self.notify(
+                format!("achievement: {}", achievement.get_name()),
+                ToastSeverity::Success,
+            );
+
+            for weapon in was_locked {
+                if weapon.is_unlocked(&self.player_state.borrow()) {
+                    let name: &'static str = (&weapon).into();
+                    self.notify(
+                        format!("{} unlocked", name.to_uppercase()),
+                        ToastSeverity::Success,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Narrates the character's health in the event log whenever it changes.
+    fn log_health(&mut self) {
+        let health = *self.character.get_health();
+
+        if health != self.last_logged_health {
+            self.last_logged_health = health;
+            self.event_log.push(format!(
+                "Health {health}/{}",
+                self.character.get_max_health()
+            ));
+        }
+    }
+
+    /// Closes the walls in over the final third of the timer. The tutorial has no closing walls.
+    fn close_walls(&mut self) {
+        if self.tutorial.is_some() {
+            return;
+        }
+
+        let Some(depth) = Map::get_closing_depth(self.get_loop_elapsed(), self.timer) else {
+            return;
+        };
+
+        if self.map.closed_in.is_none() {
+            self.notify("WALLS: the arena is closing in", ToastSeverity::Warning);
+        }
+        if self.map.close_in(depth) {
+            self.event_log.push("The walls closed in");
+        }
+    }
+
+    /// Hurts the players and any enemies standing on hazards, then clears out expired ones.
+    fn apply_hazards(&mut self) {
+        self.map.clear_expired_hazards(self.tickcount);
+
+        let resist = self.player_state.borrow().stats.player_stats.hazard_resist;
+        let players = std::iter::once(&mut self.character)
+            .chain(self.partner.as_mut().map(|partner| &mut partner.character));
+
+        for character in players.filter(|character| character.is_alive()) {
+            let Some(hazard) = self.map.hazards.get(character.get_pos()) else {
+                continue;
+            };
+
+            let damage = if hazard.is_lethal() {
+                character.get_max_health() + character.get_max_shield()
+            } else {
+                (f64::from(hazard.get_damage()) * (1. - resist)).round() as i32
+            };
+
+            if damage > 0 {
+                character.take_damage_from(damage, DamageSource::Hazard(hazard.kind));
+            }
+        }
+
+        self.enemy_wrangler.apply_hazards(&self.map.hazards);
+    }
+
+    /// Resumes play with the weapons and charms chosen in a popup, for the partner with
+    /// `for_partner` set.
+    fn apply_loadout(
+        &mut self,
+        weapons: Vec<WeaponWrapper>,
+        charms: Vec<CharmWrapper>,
+        for_partner: bool,
+    ) {
+        let held = &self.get_player(for_partner).weapons;
+        let newly_maxed: Vec<String> = weapons
+            .iter()
+            .map(WeaponWrapper::get_inner)
+            .filter(|weapon| {
+                let was_maxed = held.iter().any(|held| {
+                    held.get_inner().get_name() == weapon.get_name()
+                        && held.get_inner().get_level() >= held.get_inner().get_max_level()
+                });
+
+                !was_maxed && weapon.get_level() >= weapon.get_max_level()
+            })
+            .map(|weapon| weapon.get_name().clone())
+            .collect();
+
+        for name in newly_maxed {
+            self.notify(format!("{name} maxed"), ToastSeverity::Success);
+        }
+
+        self.game_state = GameState::Play;
+        let player = self.get_player_mut(for_partner);
+        player.weapons = weapons;
+        player.charms = charms;
+        self.update_run_modifiers();
+        self.update_stats();
+    }
+
+    pub fn consume_drops(&mut self, drops: &EnemyDrops) {
+        let combo_mult = self.combo.get_drop_mult();
+        let gold_mult = self.player_state.borrow().stats.game_stats.gold_mult
+            * Self::get_loop_gold_mult(self.loops)
+            * self.get_hype().get_gold_mult()
+            * combo_mult;
+
+        self.pickup_wrangler
+            .drop_gold(&drops.position, (drops.gold as f64 * gold_mult) as u128);
+        self.level
+            .add_xp((drops.xp as f64 * combo_mult).round() as u128);
+
+        if drops.chest {
+            self.pickup_wrangler.spawn_chest(drops.position.clone());
+        } else {
+            self.pickup_wrangler.roll_bomb_drop(&drops.position);
+        }
+    }
+
+    /// Deals a share of max health as damage to every enemy within the bomb's radius. Enemies
+    /// killed by the blast give their drops as usual.
+    fn detonate_bomb(&mut self, position: &Position) {
+        let area = DamageArea {
+            damage_amount: 0,
+            area: Square(SquareArea::get_square_around_position(
+                position,
+                Bomb::BLAST_RADIUS,
+            )),
+            entity: EntityCharacters::AttackBlackout(Style::new().light_red()),
+            duration: Duration::from_secs_f64(0.4),
+            blink: true,
+            destroys_walls: true,
+            stages: vec![],
+            weapon_stats: None,
+            source: None,
+        };
+
+        self.event_log.push("Bomb exploded");
+
+        let _ = area.deal_max_health_damage(
+            &mut self.enemies.borrow_mut(),
+            Bomb::MAX_HEALTH_DAMAGE,
+            &self.sound_wrangler,
+        );
+
+        for drop in self
+            .enemy_wrangler
+            .get_rubble_drops(&self.map.destroy_walls(&area))
+        {
+            self.consume_drops(&drop);
+        }
+
+        self.active_damage_effects.push(DamageEffect::from(area));
+    }
+
+    pub fn on_frame(&mut self) {
+        if let GameState::Play = self.game_state {
+            let started = Instant::now();
+
+            let reduced_flashing = self.player_state.borrow().settings.reduced_flashing;
+            let duration_mult = self.adaptive_quality.quality.get_effect_duration_mult();
+            for effect in &mut self.active_damage_effects {
+                effect.set_duration_mult(duration_mult);
+            }
+            update_effects(&mut self.active_damage_effects, reduced_flashing);
+
+            self.active_damage_effects = self
+                .active_damage_effects
+                .clone()
+                .into_iter()
+                .filter(|effect| !effect.complete || effect.has_pending_stages())
+                .collect();
+
+            self.enemy_wrangler.on_frame();
+            self.character.on_frame();
+            if let Some(partner) = &mut self.partner {
+                partner.character.on_frame();
+            }
+
+            self.refresh_view();
+
+            let frame_cost = started.elapsed() + self.last_render_cost;
+            if let Some(quality) = self.adaptive_quality.record(frame_cost, Instant::now()) {
+                self.event_log
+                    .push(format!("Render quality: {}", quality.get_name()));
+            }
+        }
+    }
+
+    /// Moves the camera to follow the players and redraws the map text for it.
+    fn refresh_view(&mut self) {
+        // zoomed out, the camera covers more of the map than the view has cells for
+        let zoom = self.adaptive_quality.quality.get_zoom();
+        let camera_view = Rect {
+            width: self.view_area.width.saturating_mul(zoom),
+            height: self.view_area.height.saturating_mul(zoom),
+            ..self.view_area
+        };
+        let focus = self.get_camera_focus();
+        self.camera_area = get_camera_area(camera_view, &focus, &self.map.map);
+
+        // a wrapping map keeps the character centred, drawing the far side past each edge
+        let mut spans = if self.player_state.borrow().get_topology() == Topology::Wrapping {
+            let window = get_wrapped_camera_area(camera_view, &focus);
+            wrap_spans(&flatten_to_span(self, None), &window)
+        } else {
+            flatten_to_span(self, Some(self.camera_area.clone()))
+        };
+        if zoom > 1 {
+            spans = aggregate_spans(&spans, zoom.into());
+        }
+
+        self.map_text = spans_to_text(spans);
+    }
+
+    /// Splits the area inside the game border into the level bar, shield bar, stats pane, map
+    /// view and event log, leaving out bars and panes the player doesn't have. In log-only mode
+    /// the event log takes the map view's place.
+    fn get_layout(
+        &self,
+        game_area: Rect,
+    ) -> (Option<Rect>, Option<Rect>, Rect, Rect, Option<Rect>) {
+        let mut game_area = game_area;
+
+        let mut progress_bar_area = None;
+        if self.player_state.borrow().upgrade_owned("A") {
+            let [bar_area, rest] =
+                Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(game_area);
+            progress_bar_area = Some(bar_area);
+            game_area = rest;
+        }
+
+        let mut shield_bar_area = None;
+        if self.character.get_max_shield() > 0 {
+            let [bar_area, rest] =
+                Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(game_area);
+            shield_bar_area = Some(bar_area);
+            game_area = rest;
+        }
+
+        let [stats_area, view_area] =
+            Layout::horizontal([Constraint::Length(27), Constraint::Fill(1)]).areas(game_area);
+
+        let settings = &self.player_state.borrow().settings;
+        let (view_area, log_area) = if settings.log_only {
+            (view_area, Some(view_area))
+        } else if settings.show_event_log {
+            let [view_area, log_area] = Layout::vertical([
+                Constraint::Fill(1),
+                Constraint::Length(Self::EVENT_LOG_HEIGHT),
+            ])
+            .areas(view_area);
+            (view_area, Some(log_area))
+        } else {
+            (view_area, None)
+        };
+
+        (
+            progress_bar_area,
+            shield_bar_area,
+            stats_area,
+            view_area,
+            log_area,
+        )
+    }
+
+    /// Submits a summary of this run to the online leaderboard, if one is configured.
+    #[cfg(feature = "leaderboard")]
+    fn submit_to_leaderboard(&self) {
+        use crate::common::leaderboard::{LeaderboardClient, RunSummary};
+
+        let Some(client) = LeaderboardClient::from_env() else {
+            return;
+        };
+
+        client.submit(RunSummary {
+            seed: self.seed,
+            survival_secs: self.get_survival_time().as_secs(),
+            kills: self.enemy_wrangler.kill_count,
+            class: self.player_state.borrow().class.get_name().to_string(),
+            build: self.character.get_build(),
+        });
+    }
+
+    /// Pauses the run while the game can't be seen, e.g. when the browser tab is hidden.
+    pub fn suspend(&mut self) {
+        if let GameState::Play = self.game_state {
+            self.game_state = GameState::Paused;
+            self.suspended = true;
+            self.move_intent.clear();
+            if let Some(partner) = &mut self.partner {
+                partner.move_intent.clear();
+            }
+        }
+    }
+
+    /// Resumes a run paused by `suspend`. The run's clock only moves on with the ticks played, so
+    /// the time away isn't counted against the timer.
+    pub fn resume(&mut self) {
+        if std::mem::take(&mut self.suspended) && matches!(self.game_state, GameState::Paused) {
+            self.game_state = GameState::Play;
+        }
+    }
+
+    /// Lays the view out again for a screen of the given size and moves the camera to match, so
+    /// the map doesn't render against a stale area until the next frame.
+    pub fn resize(&mut self, area: Rect) {
+        let (_, _, _, view_area, _) = self.get_layout(Block::bordered().inner(area));
+        self.view_area = view_area;
+
+        self.refresh_view();
+    }
+
+    pub fn update_stats(&mut self) {
+        let attack_speed_mult = self
+            .player_state
+            .borrow()
+            .stats
+            .game_stats
+            .attack_speed_mult;
+
+        let get_attack_ticks = |weapons: &[WeaponWrapper]| {
+            weapons
+                .iter()
+                .map(|weapon| {
+                    per_sec_to_tick_count_to_u64(weapon.get_base_attack_rate() * attack_speed_mult)
+                })
+                .collect()
+        };
+
+        self.weapon_attack_ticks = get_attack_ticks(&self.character.weapons);
+        if let Some(partner) = &mut self.partner {
+            partner.weapon_attack_ticks = get_attack_ticks(&partner.character.weapons);
+        }
+
+        let offset = self.player_state.borrow().stats.game_stats.doom_offset;
+
+        self.timescaler.borrow_mut().offset_doom(offset);
+    }
+
+    /// Pauses the run for a powerup popup, offering choices for the partner's METHODs and
+    /// CHARMs with `for_partner` set.
+    pub fn generate_popup(&mut self, for_partner: bool) {
+        self.game_state = GameState::Paused;
+        let player = self.get_player(for_partner);
+        let mut powerup_popup = PowerupPopup::new(
+            &player.weapons,
+            &player.charms,
+            self.player_state.borrow().stats.weapon_stats.clone(),
+            self.player_state.clone(),
+        );
+
+        let label = if for_partner {
+            "Player 2 powerup"
+        } else {
+            "Powerup"
+        };
+        let choices = powerup_popup.get_choice_names();
+        if powerup_popup.is_maxed() {
+            self.event_log
+                .push(format!("{label}: everything is maxed, take gold or a heal"));
+        } else {
+            self.event_log.push(format!(
+                "{label}: choose 1 of {}: {}",
+                choices.len(),
+                choices.join(", ")
+            ));
+        }
+
+        powerup_popup.rerolls = self.reroll_charges;
+
+        self.powerup_popup = Some(powerup_popup);
+        self.show_splash(SplashArt::LevelUp);
+        self.popup_for_partner = for_partner;
+        if for_partner {
+            self.partner_popup_due = false;
+        } else {
+            self.start_popup = false;
+        }
+    }
+
+    /// Types out `art` over the run, unless the run is only showing the event log.
+    fn show_splash(&mut self, art: SplashArt) {
+        if !self.player_state.borrow().settings.log_only {
+            self.splash = Some(Splash::new(art));
+        }
+    }
+
+    /// Owes a powerup popup to each player still standing.
+    fn queue_powerups(&mut self) {
+        self.start_popup |= self.character.is_alive();
+        self.partner_popup_due |= self
+            .partner
+            .as_ref()
+            .is_some_and(|partner| partner.character.is_alive());
+    }
+
+    /// Pauses the run for the merchant, who takes only gold picked up during this run.
+    pub fn generate_merchant_popup(&mut self) {
+        self.game_state = GameState::Paused;
+        self.merchant_due = false;
+        self.event_log.push("A merchant appears");
+
+        let run_gold = self
+            .player_state
+            .borrow()
+            .inventory
+            .gold
+            .saturating_sub(self.init_state.inventory.gold);
+        let price_mult = self.timescaler.borrow().doom;
+
+        self.merchant_popup = Some(MerchantPopup::new(
+            self.player_state.clone(),
+            run_gold,
+            price_mult,
+        ));
+    }
+
+    pub fn generate_chest_popup(&mut self, rewards: usize) {
+        self.game_state = GameState::Paused;
+        self.event_log
+            .push(format!("Chest opened: {rewards} rewards"));
+        self.chest_popup = Some(ChestPopup::new(
+            &self.character.weapons,
+            &self.character.charms,
+            &self.player_state.borrow().stats.weapon_stats,
+            &self.player_state,
+            rewards,
+        ));
+    }
+
+    fn scale(&mut self) -> f64 {
+        self.timescaler.borrow_mut().scale()
+    }
+
+    /// Handles a key press, which in networked co-op waits to be replayed on the turn it's
+    /// scheduled for.
+    pub fn key_event(&mut self, key_event: &KeyEvent) {
+        // splashes are only drawn on this screen, so skipping one isn't sent to the other player
+        if self.splash.take().is_some() {
+            return;
+        }
+
+        #[cfg(feature = "netplay")]
+        if let Some(netplay) = &mut self.netplay {
+            netplay.press(key_event, false);
+            return;
+        }
+
+        self.apply_key_event(key_event);
+    }
+
+    fn apply_key_event(&mut self, key_event: &KeyEvent) {
+        if let Some(debug_console) = &mut self.debug_console {
+            match debug_console.handle_key_event(key_event) {
+                Some(ConsoleInput::Run(command)) => self.run_debug_command(command),
+                Some(ConsoleInput::Close) => self.close_debug_console(),
+                None => {}
+            }
+            return;
+        }
+
+        let action = self.get_key_context().get_action(&key_event.code);
+
+        if let Some(carnage_report) = &mut self.carnage_report {
+            if let Some(choice) = action.and_then(|action| carnage_report.handle_action(action)) {
+                self.game_state = GameState::Exit;
+                self.goto = match choice {
+                    GameOverChoice::Retry => Goto::Retry(self.seed),
+                    GameOverChoice::Upgrades => Goto::Upgrades,
+                    GameOverChoice::MainMenu => Goto::Menu,
+                };
+            }
+        } else if let Some(action) = action {
+            self.handle_run_action(action);
+        } else if self.get_key_context() == self.get_run_key_context() {
+            // debug keys aren't bound to actions, so they stay out of the help
+            match key_event.code {
+                KeyCode::Char(']') => {
+                    self.player_state.borrow_mut().inventory.gold += 10000;
+                }
+                #[cfg(debug_assertions)]
+                DebugConsole::OPEN_KEY => self.open_debug_console(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Passes `action` to whichever popup is open, or acts on it in the run if none are.
+    fn handle_run_action(&mut self, action: Action) {
+        if let Some(loop_popup) = &mut self.loop_popup {
+            loop_popup.handle_action(action);
+        } else if let Some(powerup_popup) = &mut self.powerup_popup {
+            powerup_popup.handle_action(action);
+        } else if let Some(chest_popup) = &mut self.chest_popup {
+            chest_popup.handle_action(action);
+        } else if let Some(merchant_popup) = &mut self.merchant_popup {
+            merchant_popup.handle_action(action);
+        } else if let Some(inspect_popup) = &mut self.inspect_popup {
+            inspect_popup.handle_action(action);
+            if inspect_popup.finished {
+                self.close_inspect_popup();
+            }
+        } else {
+            match action {
+                Action::Up | Action::Down | Action::Left | Action::Right => {
+                    if let Some(direction) = action.get_direction() {
+                        self.move_character(direction);
+                    }
+                }
+                Action::AimUp | Action::AimDown | Action::AimLeft | Action::AimRight => {
+                    if let Some(direction) = action.get_direction() {
+                        self.character.aim = Some(self.get_input_direction(direction));
+                    }
+                }
+                Action::PartnerUp
+                | Action::PartnerDown
+                | Action::PartnerLeft
+                | Action::PartnerRight => {
+                    if let Some(direction) = action.get_direction() {
+                        self.move_partner(direction);
+                    }
+                }
+                Action::Confirm if self.tutorial.as_ref().is_some_and(Tutorial::is_done) => {
+                    self.finish_tutorial();
+                }
+                Action::Back if self.tutorial.is_some() => self.finish_tutorial(),
+                Action::Back => self.game_state = GameState::GameOver,
+                Action::Inspect => self.open_inspect_popup(),
+                Action::AutoPilot => self.toggle_autopilot(),
+                _ => {}
+            }
+        }
+    }
+
+    /// Opens the inspect popup, pausing the run while it's open.
+    fn open_inspect_popup(&mut self) {
+        self.suspend();
+        self.inspect_popup = Some(InspectPopup::default());
+    }
+
+    fn close_inspect_popup(&mut self) {
+        self.inspect_popup = None;
+        self.resume();
+    }
+
+    /// Opens the debug console, pausing the run while it's open.
+    #[cfg(debug_assertions)]
+    fn open_debug_console(&mut self) {
+        self.suspend();
+        self.debug_console = Some(DebugConsole::new());
+    }
+
+    fn close_debug_console(&mut self) {
+        self.debug_console = None;
+        self.resume();
+    }
+
+    /// Runs a command typed into the debug console, printing the reply to it.
+    fn run_debug_command(&mut self, command: DebugCommand) {
+        let reply = match command {
+            DebugCommand::Spawn(count) => {
+                let before = self.enemies.borrow().len();
+                for _ in 0..count {
+                    self.enemy_wrangler.spawn_enemy(&self.map);
+                }
+                let spawned = self.enemies.borrow().len() - before;
+
+                format!("spawned {spawned} enemies")
+            }
+            DebugCommand::Gold(amount) => {
+                self.player_state.borrow_mut().inventory.add_gold(amount);
+                format!("added {amount} gold")
+            }
+            DebugCommand::Doom(doom) => {
+                self.timescaler.borrow_mut().set_doom(doom);
+                format!("doom is now {doom}")
+            }
+            DebugCommand::Weapon(weapon, level) => self.give_weapon(weapon, level),
+            DebugCommand::Stat(key) => self
+                .player_state
+                .borrow()
+                .stat_sheet
+                .get_breakdown(key)
+                .describe(),
+            DebugCommand::God => {
+                self.character.god_mode = !self.character.god_mode;
+                format!(
+                    "god mode {}",
+                    if self.character.god_mode { "on" } else { "off" }
+                )
+            }
+            DebugCommand::Powerup => {
+                self.close_debug_console();
+                self.generate_popup(false);
+                return;
+            }
+            DebugCommand::GameOver => {
+                self.close_debug_console();
+                self.game_state = GameState::GameOver;
+                return;
+            }
+            DebugCommand::Help => DebugCommand::USAGE.join(", "),
+        };
+
+        if let Some(debug_console) = &mut self.debug_console {
+            debug_console.print(reply);
+        }
+    }
+
+    /// Gives the character `weapon` at `level`, or raises it to that level if it's already held.
+    /// The player's METHOD level cap is ignored, so levels not unlocked yet can be tried out.
+    fn give_weapon(&mut self, mut weapon: WeaponWrapper, level: i32) -> String {
+        let mut weapons = self.character.weapons.clone();
+
+        let index = if let Some(index) = weapons.iter().position(|held| *held == weapon) {
+            index
+        } else {
+            weapon.populate_inner(
+                self.player_state.borrow().stats.weapon_stats.clone(),
+                self.player_state.clone(),
+            );
+            weapons.push(weapon);
+            weapons.len() - 1
+        };
+
+        let held = weapons[index].get_inner_mut();
+        let name = held.get_name();
+        let level = level.max(1);
+
+        if level < held.get_level() {
+            return format!("{name} is already level {}", held.get_level());
+        }
+
+        let powerup: DynPowerup = Box::new(PowerupUpgrade::new(
+            &name,
+            String::new(),
+            held.get_level(),
+            level,
+            PowerupTypes::Weapon,
+        ));
+        held.upgrade_self(&powerup);
+
+        self.apply_loadout(weapons, self.character.charms.clone(), false);
+        // applying the loadout carries on the run, but it stays paused under the console
+        self.suspend();
+
+        format!("{name} is now level {level}")
+    }
+
+    /// Switches the autopilot on or off, if it has been unlocked. Debug builds can always use it.
+    fn toggle_autopilot(&mut self) {
+        let unlocked =
+            cfg!(debug_assertions) || self.player_state.borrow().stats.game_stats.autopilot;
+
+        if !unlocked {
+            return;
+        }
+
+        if self.autopilot.take().is_some() {
+            self.notify("autopilot off", ToastSeverity::Info);
+        } else {
+            self.autopilot = Some(AutoPilot::new());
+            self.notify("autopilot on", ToastSeverity::Info);
+        }
+    }
+
+    /// Moves the character the way the autopilot chooses, while it's switched on.
+    fn steer_with_autopilot(&mut self) {
+        let Some(autopilot) = &self.autopilot else {
+            return;
+        };
+
+        let enemies: Vec<Position> = self
+            .enemies
+            .borrow()
+            .iter()
+            .map(|enemy| enemy.get_pos().clone())
+            .collect();
+        let pickups: Vec<Position> = self
+            .pickup_wrangler
+            .pickups
+            .iter()
+            .map(|pickup| pickup.get_pos().clone())
+            .collect();
+
+        if let Some(direction) = autopilot.choose_direction(
+            self.get_character_pos(),
+            &enemies,
+            &pickups,
+            self.map.width as i32,
+            self.map.height as i32,
+        ) {
+            self.move_intent.press(direction, self.tickcount);
+        }
+    }
+
+    /// Returns the direction the player meant, reversed when the mirror mutator is on.
+    fn get_input_direction(&self, direction: Direction) -> Direction {
+        if self
+            .player_state
+            .borrow()
+            .mutators
+            .contains(&Mutator::MirrorControls)
+        {
+            direction.opposite()
+        } else {
+            direction
+        }
+    }
+
+    fn move_character(&mut self, direction: Direction) {
+        if let Some(tutorial) = &mut self.tutorial {
+            tutorial.record_move();
+        }
+
+        let direction = self.get_input_direction(direction);
+        self.move_intent.press(direction, self.tickcount);
+    }
+
+    /// Lets go of the direction held by a released movement key, on its turn in networked co-op.
+    pub fn key_release(&mut self, key_event: &KeyEvent) {
+        #[cfg(feature = "netplay")]
+        if let Some(netplay) = &mut self.netplay {
+            netplay.press(key_event, true);
+            return;
+        }
+
+        self.apply_key_release(key_event);
+    }
+
+    /// Returns the bindings for the run itself, whichever popups are open over it.
+    fn get_run_key_context(&self) -> KeyContext {
+        if self.tutorial.is_some() {
+            KeyContext::Tutorial
+        } else if self.partner.is_some() {
+            KeyContext::CoopRun
+        } else if self.player_state.borrow().settings.manual_aim {
+            KeyContext::ManualAimRun
+        } else {
+            KeyContext::Run
+        }
+    }
+
+    /// Lets go of a movement key. Keys are looked up in the run's own bindings, so a key let go
+    /// of while a popup is open still stops the movement it started.
+    fn apply_key_release(&mut self, key_event: &KeyEvent) {
+        let Some(action) = self.get_run_key_context().get_action(&key_event.code) else {
+            return;
+        };
+        let Some(direction) = action.get_direction() else {
+            return;
+        };
+        let direction = self.get_input_direction(direction);
+
+        match action {
+            Action::Up | Action::Down | Action::Left | Action::Right => {
+                self.move_intent.release(&direction);
+            }
+            Action::PartnerUp
+            | Action::PartnerDown
+            | Action::PartnerLeft
+            | Action::PartnerRight => {
+                if let Some(partner) = &mut self.partner {
+                    partner.move_intent.release(&direction);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Swaps keys with the other player in networked co-op, replaying both players' keys for the
+    /// turn that's due. Returns whether the tick can go ahead, which it can't until the other
+    /// player's keys have arrived.
+    #[cfg(feature = "netplay")]
+    fn play_netplay_turn(&mut self) -> bool {
+        let checksum = self.get_checksum();
+        let Some(netplay) = &mut self.netplay else {
+            return true;
+        };
+
+        let turn = netplay.exchange(checksum);
+        if netplay.take_desync() {
+            self.notify("out of sync with the other player", ToastSeverity::Warning);
+        }
+
+        match turn {
+            NetTurn::Waiting => false,
+            NetTurn::Ready { host, guest } => {
+                for key in host {
+                    self.replay_key(NetRole::Host, key);
+                }
+                for key in guest {
+                    self.replay_key(NetRole::Guest, key);
+                }
+                true
+            }
+            NetTurn::Closed(reason) => {
+                self.netplay = None;
+                self.notify(
+                    format!("the other player left: {reason}"),
+                    ToastSeverity::Warning,
+                );
+                true
+            }
+        }
+    }
+
+    /// Replays a key pressed by the player in `role`. The guest steers the partner and picks the
+    /// partner's powerups, and the host has the rest of the keyboard.
+    #[cfg(feature = "netplay")]
+    fn replay_key(&mut self, role: NetRole, key: NetKey) {
+        let key_event = key.get_coop_event(role);
+        if key.released {
+            self.apply_key_release(&key_event);
+            return;
+        }
+
+        let partners_popup = self.popup_for_partner && self.powerup_popup.is_some();
+        let allowed = match role {
+            NetRole::Host => !partners_popup,
+            NetRole::Guest => {
+                partners_popup
+                    || (matches!(self.game_state, GameState::Play)
+                        && matches!(
+                            self.get_run_key_context().get_action(&key_event.code),
+                            Some(
+                                Action::PartnerUp
+                                    | Action::PartnerDown
+                                    | Action::PartnerLeft
+                                    | Action::PartnerRight
+                            )
+                        ))
+            }
+        };
+
+        if allowed {
+            self.apply_key_event(&key_event);
+        }
+    }
+
+    /// Sums up the state of the run, for spotting a networked run that has drifted out of sync.
+    #[cfg(feature = "netplay")]
+    fn get_checksum(&self) -> u64 {
+        let mut hasher = ChecksumHasher::default();
+        self.tickcount.hash(&mut hasher);
+        self.player_state.borrow().inventory.gold.hash(&mut hasher);
+
+        let players = std::iter::once(&self.character)
+            .chain(self.partner.as_ref().map(|partner| &partner.character));
+        for player in players {
+            player.get_pos().get().hash(&mut hasher);
+            player.get_health().hash(&mut hasher);
+        }
+
+        for enemy in self.enemies.borrow().iter() {
+            enemy.get_pos().get().hash(&mut hasher);
+            enemy.get_health().hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+
+    /// Returns whether diagonal movement is on, and how the map's edges behave.
+    fn get_movement_settings(&self) -> (bool, Topology) {
+        let player_state = self.player_state.borrow();
+        (
+            player_state.settings.diagonal_movement,
+            player_state.get_topology(),
+        )
+    }
+
+    /// Checks whether a step in `direction` from `from` keeps a player in the shared camera with
+    /// the `other` player, if there is one. Steps that don't take them further apart are always
+    /// fine.
+    fn stays_in_view(
+        &self,
+        from: &Position,
+        direction: &Direction,
+        other: Option<&Position>,
+    ) -> bool {
+        let Some(other) = other else {
+            return true;
+        };
+
+        let topology = self.player_state.borrow().get_topology();
+        let mut to = direction.step_from(from);
+        topology.constrain(&mut to, &self.map.map);
+
+        let (from_x, from_y) = topology.get_distance(from, other, &self.map.map);
+        let (to_x, to_y) = topology.get_distance(&to, other, &self.map.map);
+        let (x1, y1, x2, y2) = self.camera_area.get_bounds();
+
+        fits_in_view(to_x, to_y, x2 - x1, y2 - y1)
+            || (to_x.abs() <= from_x.abs() && to_y.abs() <= from_y.abs())
+    }
+
+    /// Steps the character in the direction being held, if it's due a step this tick.
+    fn step_character(&mut self) {
+        if !self.character.is_alive() {
+            return;
+        }
+
+        let ticks_per_step = self.character.get_ticks_per_step();
+        let (diagonal, topology) = self.get_movement_settings();
+
+        let Some(direction) = self
+            .move_intent
+            .take_step(self.tickcount, ticks_per_step, diagonal)
+        else {
+            return;
+        };
+
+        let partner_pos = self
+            .partner
+            .as_ref()
+            .filter(|partner| partner.character.is_alive())
+            .map(|partner| partner.character.get_pos().clone());
+
+        if self.stays_in_view(self.character.get_pos(), &direction, partner_pos.as_ref()) {
+            move_entity(&self.map, &mut self.character, direction, topology);
+        } else {
+            let position = self.character.get_pos().clone();
+            self.character.move_to(position, direction);
+        }
+    }
+
+    /// Steps the partner in the direction they're holding, if they're due a step this tick.
+    fn step_partner(&mut self) {
+        let (diagonal, topology) = self.get_movement_settings();
+        let tickcount = self.tickcount;
+
+        let Some(partner) = self
+            .partner
+            .as_mut()
+            .filter(|partner| partner.character.is_alive())
+        else {
+            return;
+        };
+
+        let ticks_per_step = partner.character.get_ticks_per_step();
+        let Some(direction) = partner
+            .move_intent
+            .take_step(tickcount, ticks_per_step, diagonal)
+        else {
+            return;
+        };
+        let from = partner.character.get_pos().clone();
+
+        let character_pos = self
+            .character
+            .is_alive()
+            .then(|| self.character.get_pos().clone());
+        let in_view = self.stays_in_view(&from, &direction, character_pos.as_ref());
+
+        let Some(partner) = &mut self.partner else {
+            return;
+        };
+        if in_view {
+            move_entity(&self.map, &mut partner.character, direction, topology);
+        } else {
+            partner.character.move_to(from, direction);
+        }
+    }
+
+    /// Holds `direction` for the partner.
+    fn move_partner(&mut self, direction: Direction) {
+        let direction = self.get_input_direction(direction);
+        let tickcount = self.tickcount;
+
+        if let Some(partner) = &mut self.partner {
+            partner.move_intent.press(direction, tickcount);
+        }
+    }
+
+    pub fn init_character(&mut self) {
+        let mut rng = runrng::rng();
+
+        let (x, y) = (
+            rng.random_range(0..self.map.width) as i32,
+            rng.random_range(0..self.map.height) as i32,
+        );
+
+        self.character.set_pos(Position(x, y));
+
+        // the partner starts beside the first player, on whichever side is on the map
+        if let Some(partner) = &mut self.partner {
+            let side = if x + 1 < self.map.width as i32 { 1 } else { -1 };
+            partner.character.set_pos(Position(x + side, y));
+        }
+    }
+
+    /// Registers the held CHARMs and active buffs on the stat sheet, replacing what they gave
+    /// before. In co-op, the partner's CHARMs share the stat sheet too.
+    pub fn update_run_modifiers(&mut self) {
+        let was_hyped = self.get_hype().is_hyped();
+        let partner_charms = self
+            .partner
+            .iter()
+            .flat_map(|partner| &partner.character.charms);
+        let modifiers = self
+            .character
+            .charms
+            .iter()
+            .chain(partner_charms)
+            .flat_map(|charm| charm.get_inner().get_modifiers())
+            .chain(self.character.get_buffs().get_modifiers())
+            .collect();
+
+        self.player_state.borrow_mut().set_run_modifiers(modifiers);
+
+        if !was_hyped && self.get_hype().is_hyped() {
+            self.notify(
+                format!("HYPE: enemies drop x{} gold", Hype::GOLD_MULT),
+                ToastSeverity::Warning,
+            );
+        }
+    }
+
+    /// Returns how hyped the run is, from the doom offset on the player's stats.
+    #[must_use]
+    pub fn get_hype(&self) -> Hype {
+        Hype::new(self.player_state.borrow().stats.game_stats.doom_offset)
+    }
+
+    /// Returns the character's current position.
+    #[must_use]
+    pub fn get_character_pos(&self) -> &Position {
+        self.character.get_pos()
+    }
+
+    /// Returns the first player's character, or the partner's with `for_partner` set and a
+    /// partner in the run.
+    fn get_player(&self, for_partner: bool) -> &Character {
+        match &self.partner {
+            Some(partner) if for_partner => &partner.character,
+            _ => &self.character,
+        }
+    }
+
+    fn get_player_mut(&mut self, for_partner: bool) -> &mut Character {
+        match &mut self.partner {
+            Some(partner) if for_partner => &mut partner.character,
+            _ => &mut self.character,
+        }
+    }
+
+    /// Returns the positions of the players still standing.
+    fn get_player_positions(&self) -> Vec<Position> {
+        std::iter::once(&self.character)
+            .chain(self.partner.as_ref().map(|partner| &partner.character))
+            .filter(|character| character.is_alive())
+            .map(|character| character.get_pos().clone())
+            .collect()
+    }
+
+    /// Checks whether any player is still standing.
+    fn is_anyone_alive(&self) -> bool {
+        !self.get_player_positions().is_empty()
+    }
+
+    /// Returns where the camera centres: on the character, or halfway between the players in
+    /// co-op.
+    fn get_camera_focus(&self) -> Position {
+        let topology = self.player_state.borrow().get_topology();
+
+        match self.get_player_positions().as_slice() {
+            [first, second] => {
+                let (dist_x, dist_y) = topology.get_distance(first, second, &self.map.map);
+                let mut focus = Position(first.0 + dist_x / 2, first.1 + dist_y / 2);
+                topology.constrain(&mut focus, &self.map.map);
+                focus
+            }
+            [only] => only.clone(),
+            _ => self.get_character_pos().clone(),
+        }
+    }
+
+    /// Renders a flashing banner across the top of the view to announce an incoming horde.
+    fn render_horde_warning(&self, frame: &mut Frame) {
+        let flash_on = (self.get_loop_elapsed().as_millis() / 250).is_multiple_of(2)
+            && !self.player_state.borrow().settings.reduced_flashing;
+        let style = if flash_on {
+            Style::new().red().bold().reversed()
+        } else {
+            Style::new().red().bold()
+        };
+
+        let text = " !! HORDE INCOMING !! ";
+        let [banner_area, _] =
+            Layout::vertical([Constraint::Length(3), Constraint::Fill(1)]).areas(self.view_area);
+        let banner_area = center_horizontal(banner_area, text.len() as u16 + 2);
+
+        let banner = Paragraph::new(Line::from(text).style(style))
+            .block(Block::bordered().border_set(border::THICK).red())
+            .centered();
+
+        frame.render_widget(Clear, banner_area);
+        frame.render_widget(banner, banner_area);
+    }
+
+    /// Returns the chips shown in the top right of the HUD: the hype meter, the combo counter,
+    /// then a countdown for each buff.
+    /// Builds the chip showing damage blocked by standing still, if any is.
+    fn get_armor_chip(&self) -> Option<Span<'static>> {
+        let reduction = self.character.get_damage_reduction();
+        (reduction > 0.).then(|| {
+            Span::styled(
+                format!(" STONEFOOT -{:.0}% ", reduction * 100.),
+                Style::new().black().on_gray().bold(),
+            )
+        })
+    }
+
+    fn get_hud_chips(&self) -> Vec<Span<'static>> {
+        self.get_hype()
+            .get_chip()
+            .into_iter()
+            .chain(self.combo.get_chip())
+            .chain(self.get_armor_chip())
+            .chain(
+                self.character
+                    .get_buffs()
+                    .get_buffs()
+                    .iter()
+                    .map(TimedBuff::get_chip),
+            )
+            .flat_map(|chip| [chip, " ".into()])
+            .collect()
+    }
+
+    /// Renders the map text centred in the view, tinted for the current phase.
+    fn render_map(&self, frame: &mut Frame, phase: Phase) {
+        let height = self.map_text.lines.len() as u16;
+        let width = self
+            .map_text
+            .lines
+            .first()
+            .map_or(0, |line| line.iter().len() as u16);
+
+        let centered_area = center(self.view_area, width, height);
+
+        let mut content = Paragraph::new(self.map_text.clone()).centered();
+        if let Some(tint) = phase.get_tint() {
+            content = content.style(Style::new().bg(tint));
+        }
+
+        frame.render_widget(content, centered_area);
+    }
+
+    #[allow(clippy::too_many_lines)]
+    pub fn render_game(&mut self, frame: &mut Frame) {
+        let timer = self.timer.saturating_sub(self.get_loop_elapsed());
+
+        let title = Line::from(" dispair.run ".bold());
+
+        let (phase, next_phase) = Phase::at(self.get_loop_elapsed());
+
+        let mut instructions = Line::from(vec![
+            " Phase: ".dark_gray(),
+            Span::styled(phase.get_name(), phase.get_style()),
+            format!(" {}s", next_phase.as_secs()).dark_gray(),
+            " ".into(),
+            " Health: ".dark_gray(),
+            self.character.get_health().to_string().bold(),
+            " ".into(),
+        ]);
+        if let Some(partner) = &self.partner {
+            instructions.push_span(" P2: ".dark_gray());
+            instructions.push_span(partner.character.get_health().to_string().bold());
+            instructions.push_span(" ");
+        }
+        instructions.extend(vec![
+            " Time: ".dark_gray(),
+            timer.as_secs().to_string().bold(),
+            " ".into(),
+            " Gold: ".dark_gray(),
+            format_number(
+                self.player_state.borrow().inventory.gold,
+                self.player_state.borrow().settings.number_format,
+            )
+            .into(),
+            " ".into(),
+        ]);
+        let debuff_counts = self.enemy_wrangler.debuff_counts;
+        let mut debuff_spans = vec![];
+        for (label, count, style) in [
+            (" Burning: ", debuff_counts.burning, Style::new().red()),
+            (
+                " Shocked: ",
+                debuff_counts.shocked,
+                Style::new().light_yellow(),
+            ),
+            (" Marked: ", debuff_counts.marked, Style::new().bold()),
+        ] {
+            if count > 0 {
+                debuff_spans.push(label.dark_gray());
+                debuff_spans.push(Span::styled(count.to_string(), style));
+                debuff_spans.push(" ".into());
+            }
+        }
+
+        let block = Block::bordered()
+            .title(title)
+            .title(Line::from(self.get_hud_chips()).right_aligned())
+            .title_bottom(Line::from(debuff_spans).left_aligned())
+            .title_bottom(instructions.right_aligned())
+            .border_set(border::THICK)
+            .border_style(
+                self.get_hype()
+                    .get_tint()
+                    .map_or_else(Style::new, |tint| Style::new().fg(tint)),
+            );
+
+        let (progress_bar_area, shield_bar_area, stats_area, view_area, log_area) =
+            self.get_layout(block.inner(frame.area()));
+        frame.render_widget(&block, frame.area());
+
+        if let Some(progress_bar_area) = progress_bar_area {
+            let progress_bar = Gauge::default()
+                .gauge_style(Style::new().light_blue())
+                .label(format!(
+                    "lv {} {}%",
+                    self.level.get_level(),
+                    self.level.get_progress_percentage()
+                ))
+                .percent(self.level.get_progress_percentage());
+
+            frame.render_widget(progress_bar, progress_bar_area);
+        }
+
+        if let Some(shield_bar_area) = shield_bar_area {
+            let max_shield = self.character.get_max_shield();
+            let shield = self.character.get_shield();
+            let shield_bar = Gauge::default()
+                .gauge_style(Style::new().light_cyan())
+                .label(format!("shield {shield}/{max_shield}"))
+                .ratio((f64::from(shield) / f64::from(max_shield)).clamp(0., 1.));
+
+            frame.render_widget(shield_bar, shield_bar_area);
+        }
+
+        self.view_area = view_area;
+
+        if let Some(log_area) = log_area {
+            self.event_log.render(frame, log_area);
+        }
+
+        if !self.player_state.borrow().settings.log_only {
+            self.render_map(frame, phase);
+        }
+
+        if self.encounter.is_warning(self.get_loop_elapsed()) {
+            self.render_horde_warning(frame);
+        }
+
+        self.toasts.render(frame, self.view_area);
+
+        if let Some(tutorial) = &self.tutorial {
+            tutorial.render(frame, self.view_area);
+        }
+
+        let stats_widget = StatsWidget::new(
+            self.player_state.clone(),
+            &self.enemy_wrangler,
+            &self.character.weapons,
+            &self.character.charms,
+        );
+
+        frame.render_widget(stats_widget, stats_area);
+
+        self.render_popups(frame);
+    }
+
+    /// Draws whichever popups are open over the run.
+    fn render_popups(&mut self, frame: &mut Frame) {
+        if let Some(ref mut carnage) = self.carnage_report {
+            carnage.render(frame);
+        }
+
+        if let Some(ref mut powerup_popup) = self.powerup_popup {
+            powerup_popup.render(frame);
+        }
+
+        if let Some(ref chest_popup) = self.chest_popup {
+            chest_popup.render(frame);
+        }
+
+        if let Some(merchant_popup) = &mut self.merchant_popup {
+            merchant_popup.render(frame);
+        }
+
+        if let Some(loop_popup) = &self.loop_popup {
+            loop_popup.render(frame);
+        }
+
+        if let Some(inspect_popup) = &self.inspect_popup {
+            inspect_popup.render(frame, &self.character, &self.weapon_attack_ticks);
+        }
+
+        if let Some(splash) = &self.splash {
+            splash.render_popup(frame, self.view_area);
+        }
+
+        if let Some(debug_console) = &self.debug_console {
+            debug_console.render(frame, self.view_area);
+        }
+    }
+}
+
+impl View for Rogue {
+    fn on_tick(&mut self) {
+        self.on_tick();
+    }
+
+    fn on_frame(&mut self) {
+        self.on_frame();
+    }
+
+    fn transition(&self) -> &Goto {
+        &self.goto
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        let started = Instant::now();
+        self.render_game(frame);
+        self.last_render_cost = started.elapsed();
+    }
+
+    fn handle_key_event(&mut self, key_event: &KeyEvent) {
+        self.key_event(key_event);
+    }
+
+    fn handle_key_release(&mut self, key_event: &KeyEvent) {
+        self.key_release(key_event);
+    }
+
+    fn resize(&mut self, area: Rect) {
+        Rogue::resize(self, area);
+    }
+
+    fn set_visible(&mut self, visible: bool) {
+        // a networked run only pauses on keys both players have seen
+        #[cfg(feature = "netplay")]
+        if self.netplay.is_some() {
+            return;
+        }
+
+        if visible {
+            self.resume();
+        } else {
+            self.suspend();
+        }
+    }
+
+    fn get_entity_counts(&self) -> Option<EntityCounts> {
+        Some(EntityCounts {
+            enemies: self.enemies.borrow().len(),
+            pressure: self.enemy_wrangler.pressure,
+            pickups: self.pickup_wrangler.pickups.len(),
+            turrets: self.turret_wrangler.turrets.len(),
+            effects: self.active_damage_effects.len(),
+            scheduled: self.damage_schedule.len(),
+        })
+    }
+
+    fn get_key_context(&self) -> KeyContext {
+        if self.carnage_report.is_some() {
+            KeyContext::CarnageReport
+        } else if self.loop_popup.is_some() {
+            KeyContext::LoopPopup
+        } else if self.powerup_popup.is_some() {
+            KeyContext::PowerupPopup
+        } else if self.chest_popup.is_some() {
+            KeyContext::ChestPopup
+        } else if self.merchant_popup.is_some() {
+            KeyContext::MerchantPopup
+        } else if self.inspect_popup.is_some() {
+            KeyContext::InspectPopup
+        } else {
+            self.get_run_key_context()
+        }
+    }
+
+    fn get_presence(&self) -> String {
+        let kills = self.enemy_wrangler.kill_count;
+
+        if self.carnage_report.is_some() {
+            return format!("Run over — {kills} kills");
+        }
+        if self.tutorial.is_some() {
+            return "Learning the ropes".into();
+        }
+        if matches!(self.game_state, GameState::Paused) {
+            return "Paused".into();
+        }
+
+        let left = self.timer.saturating_sub(self.get_loop_elapsed()).as_secs();
+        format!(
+            "Surviving — {}:{:02} left, {kills} kills",
+            left / 60,
+            left % 60
+        )
+    }
+}
+
+/// Moves every damage effect on a frame. With `reduced_flashing`, blinking effects are held
+/// steady instead.
+pub fn update_effects(damage_effects: &mut [DamageEffect], reduced_flashing: bool) {
+    for effect in damage_effects.iter_mut() {
+        if reduced_flashing {
+            effect.hold_steady();
+        }
+        effect.update();
+    }
+}
+
+/// Returns the index of each weapon whose attack interval lands on `tickcount`.
+fn get_ready_weapons(weapon_attack_ticks: &[u64], tickcount: u64) -> Vec<usize> {
+    weapon_attack_ticks
+        .iter()
+        .enumerate()
+        .filter(|(_, ticks)| tickcount.is_multiple_of(**ticks))
+        .map(|(i, _)| i)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Instant;
+
+    use crate::common::character::Movable;
+    use crate::common::coords::{Position, SquareArea};
+    use crate::common::enemies::enemy::{Enemy, EnemyBehaviour, EnemyDrops};
+    use crate::common::popups::debugconsole::DebugCommand;
+    use crate::common::popups::looppopup::LoopChoice;
+    use crate::common::render::{flatten_to_span, spans_to_text};
+    use crate::common::rogue::GameState;
+    use crate::common::sound::SoundWrangler;
+    use crate::common::{View, rogue::Rogue, upgrades::upgrade::PlayerState};
+    use crate::prelude::Duration;
+
+    #[test]
+    fn presence_shows_time_left_and_kills() {
+        let player_state = Rc::new(RefCell::new(PlayerState::default()));
+        let wrangler = Rc::new(RefCell::new(SoundWrangler::default()));
+        let mut rogue_game = Rogue::with_seed(&player_state, wrangler, 7);
+
+        rogue_game.timer = Duration::from_millis(133_500);
+        rogue_game.enemy_wrangler.kill_count = 312;
+        assert_eq!(
+            rogue_game.get_presence(),
+            "Surviving — 2:13 left, 312 kills"
+        );
+
+        rogue_game.game_state = GameState::Paused;
+        assert_eq!(rogue_game.get_presence(), "Paused");
+    }
+
+    #[test]
+    fn debug_commands_change_the_run() {
+        let player_state = Rc::new(RefCell::new(PlayerState::default()));
+        let wrangler = Rc::new(RefCell::new(SoundWrangler::default()));
+        let mut rogue_game = Rogue::with_seed(&player_state, wrangler, 7);
+
+        rogue_game.run_debug_command("gold 500".parse().unwrap());
+        assert_eq!(player_state.borrow().inventory.gold, 500);
+
+        rogue_game.run_debug_command("weapon nova 3".parse().unwrap());
+        let nova = rogue_game
+            .character
+            .weapons
+            .iter()
+            .find(|weapon| weapon.get_inner().get_name() == "NOVA")
+            .expect("nova was given");
+        assert_eq!(nova.get_inner().get_level(), 3);
+
+        rogue_game.run_debug_command(DebugCommand::God);
+        assert!(rogue_game.character.god_mode);
+    }
+
+    #[test]
+    fn surviving_the_timer_offers_a_loop() {
+        let player_state = Rc::new(RefCell::new(PlayerState::default()));
+        let wrangler = Rc::new(RefCell::new(SoundWrangler::default()));
+        let mut rogue_game = Rogue::with_seed(&player_state, wrangler, 7);
+
+        rogue_game.timer = Duration::ZERO;
+        rogue_game.on_tick();
+        let loop_popup = rogue_game.loop_popup.as_mut().expect("loop was offered");
+
+        loop_popup.choice = Some(LoopChoice::Loop);
+        rogue_game.timer = Duration::from_mins(1);
+        rogue_game.on_tick();
+
+        assert_eq!(rogue_game.loops, 1);
+        assert_eq!(rogue_game.run_stats.loops, 1);
+        assert!(rogue_game.loop_popup.is_none());
+        assert!(matches!(rogue_game.game_state, GameState::Play));
+        assert!(Rogue::get_loop_gold_mult(2) > Rogue::get_loop_gold_mult(1));
+    }
+
+    #[test]
+    fn map_snapshot() {
+        let mut player_state = PlayerState::default();
+        player_state.stats.game_stats.width = 30;
+        player_state.stats.game_stats.height = 10;
+        // the trail would show where the character randomly started
+        player_state.settings.show_trail = false;
+
+        let wrangler = Rc::new(RefCell::new(SoundWrangler::default()));
+        let mut rogue_game = Rogue::with_seed(&Rc::new(RefCell::new(player_state)), wrangler, 7);
+
+        rogue_game.character.set_pos(Position(15, 5));
+        // pickups start in random spots
+        rogue_game.pickup_wrangler.pickups.clear();
+
+        let drops = EnemyDrops {
+            gold: 1,
+            xp: 0,
+            position: Position::default(),
+            chest: false,
+        };
+        let mut elite = Enemy::new(Position(20, 2), 1, 2, drops.clone());
+        elite.make_elite();
+        rogue_game.enemies.borrow_mut().extend([
+            Enemy::new(Position(3, 1), 1, 2, drops.clone()),
+            Enemy::new(Position(10, 8), 1, 2, drops),
+            elite,
+        ]);
+
+        let text = spans_to_text(flatten_to_span(&rogue_game, None));
+        let plain: Vec<String> = text
+            .lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect();
+
+        insta::assert_snapshot!(plain.join("\n"));
+    }
+
+    #[test]
+    fn renderspeed() {
         let mut player_state = PlayerState::default();
 
         let wrangler = Rc::new(RefCell::new(SoundWrangler::default()));
@@ -576,4 +2721,43 @@ mod tests {
         #[cfg(not(debug_assertions))]
         assert!(elapsed < 500);
     }
+
+    #[test]
+    fn crowded_renderspeed() {
+        let mut player_state = PlayerState::default();
+
+        let wrangler = Rc::new(RefCell::new(SoundWrangler::default()));
+        player_state.stats.game_stats.width = 1000;
+        player_state.stats.game_stats.height = 1000;
+
+        let rogue_game = Rogue::new(&Rc::new(RefCell::new(player_state)), wrangler);
+
+        // a wide view, with every other cell taken by an enemy
+        let camera = SquareArea::new(Position(300, 400), Position(600, 500));
+        let drops = EnemyDrops {
+            gold: 1,
+            xp: 0,
+            position: Position::default(),
+            chest: false,
+        };
+        rogue_game.enemies.borrow_mut().extend(
+            (400..500)
+                .flat_map(|y| (300..600).step_by(2).map(move |x| Position(x, y)))
+                .map(|position| Enemy::new(position, 1, 10, drops.clone())),
+        );
+
+        let start_time = Instant::now();
+
+        for _ in 0..10 {
+            let spans = flatten_to_span(&rogue_game, Some(camera.clone()));
+            let _ = spans_to_text(spans);
+        }
+
+        let elapsed = start_time.elapsed().as_millis();
+
+        println!("CrowdedRenderspeed Time taken for 10 frames: {elapsed}");
+
+        #[cfg(not(debug_assertions))]
+        assert!(elapsed < 500);
+    }
 }
@@ -0,0 +1,187 @@
+//! This module provides the router, which keeps a stack of the screens that are open and passes
+//! ticks, keys and draws on to them. Both platforms show their views through it.
+
+use ratatui::{Frame, layout::Rect};
+
+use crate::common::keybinds::KeyContext;
+use crate::common::perf::EntityCounts;
+use crate::common::{Goto, View};
+use crate::prelude::KeyEvent;
+
+/// An open view, and the destination it was opened for.
+struct Screen {
+    route: Goto,
+    view: Box<dyn View>,
+}
+
+/// A stack of open screens. The screen on top is the one being played, and the ones underneath
+/// are drawn beneath it, hidden until it closes.
+pub struct Router {
+    stack: Vec<Screen>,
+}
+
+impl Router {
+    #[must_use]
+    pub fn new(view: Box<dyn View>) -> Self {
+        Self {
+            stack: vec![Screen::new(view)],
+        }
+    }
+
+    fn get_top(&self) -> &Screen {
+        self.stack
+            .last()
+            .expect("the router always has a screen open")
+    }
+
+    fn get_top_mut(&mut self) -> &mut Screen {
+        self.stack
+            .last_mut()
+            .expect("the router always has a screen open")
+    }
+
+    #[must_use]
+    pub fn get_view_ref(&self) -> &dyn View {
+        self.get_top().view.as_ref()
+    }
+
+    pub fn get_view_mut(&mut self) -> &mut dyn View {
+        self.get_top_mut().view.as_mut()
+    }
+
+    /// Returns the destination the screen on top was opened for.
+    #[must_use]
+    pub fn get_route(&self) -> &Goto {
+        &self.get_top().route
+    }
+
+    /// Returns where the screen on top wants to go, if it's ready to leave.
+    #[must_use]
+    pub fn get_transition(&self) -> Option<&Goto> {
+        let top = self.get_top();
+        let goto = top.view.transition();
+        (*goto != top.route).then_some(goto)
+    }
+
+    /// Switches to `view`, closing every screen that's open.
+    pub fn open(&mut self, view: Box<dyn View>) {
+        self.stack.clear();
+        self.stack.push(Screen::new(view));
+    }
+
+    /// Opens `view` over the screen on top, which is hidden until it's back on top.
+    pub fn push(&mut self, view: Box<dyn View>) {
+        self.get_view_mut().set_visible(false);
+        self.stack.push(Screen::new(view));
+    }
+
+    /// Closes the screen on top, showing the one underneath again. The last screen stays open.
+    pub fn pop(&mut self) {
+        if self.stack.len() > 1 {
+            self.stack.pop();
+            self.get_view_mut().set_visible(true);
+        }
+    }
+
+    /// Moves the screen on top on.
+    pub fn on_tick(&mut self) {
+        self.get_view_mut().on_tick();
+    }
+
+    pub fn on_frame(&mut self) {
+        self.get_view_mut().on_frame();
+    }
+
+    pub fn handle_key_event(&mut self, key_event: &KeyEvent) {
+        self.get_view_mut().handle_key_event(key_event);
+    }
+
+    pub fn handle_key_release(&mut self, key_event: &KeyEvent) {
+        self.get_view_mut().handle_key_release(key_event);
+    }
+
+    /// Draws every open screen from the bottom up, so each one is drawn over the one it was
+    /// opened over.
+    pub fn render(&mut self, frame: &mut Frame) {
+        for screen in &mut self.stack {
+            screen.view.render(frame);
+        }
+    }
+
+    /// Lets every open screen lay itself out again for `area`.
+    pub fn resize(&mut self, area: Rect) {
+        for screen in &mut self.stack {
+            screen.view.resize(area);
+        }
+    }
+
+    /// Tells the screen on top whether it can be seen. The ones underneath stay hidden.
+    pub fn set_visible(&mut self, visible: bool) {
+        self.get_view_mut().set_visible(visible);
+    }
+
+    #[must_use]
+    pub fn get_key_context(&self) -> KeyContext {
+        self.get_view_ref().get_key_context()
+    }
+
+    /// Returns the entity counts of the topmost screen that has any, so they stay up while
+    /// something is open over a run.
+    #[must_use]
+    pub fn get_entity_counts(&self) -> Option<EntityCounts> {
+        self.stack
+            .iter()
+            .rev()
+            .find_map(|screen| screen.view.get_entity_counts())
+    }
+
+    #[must_use]
+    pub fn get_presence(&self) -> String {
+        self.get_view_ref().get_presence()
+    }
+}
+
+impl Screen {
+    fn new(view: Box<dyn View>) -> Self {
+        Self {
+            route: view.transition().clone(),
+            view,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::common::Goto;
+    use crate::common::keybinds::{Action, KeyContext};
+    use crate::common::popups::helpoverlay::HelpOverlay;
+    use crate::common::router::Router;
+    use crate::common::settings::settingsmenu::SettingsMenu;
+    use crate::common::upgrades::upgrade::PlayerState;
+
+    #[test]
+    fn screens_stack_and_close() {
+        let player_state = Rc::new(RefCell::new(PlayerState::default()));
+        let mut router = Router::new(Box::new(SettingsMenu::new(player_state.clone())));
+
+        router.push(Box::new(HelpOverlay::new(KeyContext::Settings)));
+        assert_eq!(router.get_route(), &Goto::Help);
+        assert_eq!(router.get_key_context(), KeyContext::Settings);
+        assert!(router.get_transition().is_none());
+
+        router.get_view_mut().handle_action(Action::Help);
+        assert_eq!(router.get_transition(), Some(&Goto::Back));
+
+        router.pop();
+        router.pop();
+        assert_eq!(router.get_route(), &Goto::Settings);
+
+        router.push(Box::new(HelpOverlay::new(KeyContext::Settings)));
+        router.open(Box::new(SettingsMenu::new(player_state)));
+        router.pop();
+        assert_eq!(router.get_route(), &Goto::Settings);
+    }
+}
@@ -0,0 +1,87 @@
+//! This module provides the random numbers a run is played out with. They're drawn from a
+//! generator seeded with the run's seed, so two copies of a run given the same inputs roll the
+//! same numbers, as networked co-op needs.
+//!
+//! The generator belongs to the thread. Work that may be spread across threads, like updating
+//! enemies with the `parallel` feature, is given seeds rolled beforehand and run with
+//! [`with_seed`], so what it rolls doesn't depend on which thread ran it or when.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+thread_local! {
+    static RUN_RNG: Rc<RefCell<StdRng>> = Rc::new(RefCell::new(StdRng::from_rng(&mut rand::rng())));
+}
+
+/// A handle to the thread's run generator, used like `rand::rng()`.
+#[derive(Clone)]
+pub struct RunRng(Rc<RefCell<StdRng>>);
+
+/// Returns a handle to the thread's run generator.
+#[must_use]
+pub fn rng() -> RunRng {
+    RunRng(RUN_RNG.with(Rc::clone))
+}
+
+/// Restarts the thread's run generator from `seed`.
+pub fn reseed(seed: u64) {
+    RUN_RNG.with(|rng| *rng.borrow_mut() = StdRng::seed_from_u64(seed));
+}
+
+/// Runs `f` with the thread's run generator restarted from `seed`, then puts the generator back
+/// the way it was.
+pub fn with_seed<T>(seed: u64, f: impl FnOnce() -> T) -> T {
+    let previous = RUN_RNG.with(|rng| rng.replace(StdRng::seed_from_u64(seed)));
+    let result = f();
+    RUN_RNG.with(|rng| rng.replace(previous));
+
+    result
+}
+
+impl RngCore for RunRng {
+    fn next_u32(&mut self) -> u32 {
+        self.0.borrow_mut().next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0.borrow_mut().next_u64()
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        self.0.borrow_mut().fill_bytes(dst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::Rng;
+
+    use crate::common::runrng::{reseed, rng, with_seed};
+
+    #[test]
+    fn reseeding_repeats_the_rolls() {
+        reseed(7);
+        let first: Vec<u32> = (0..8).map(|_| rng().random_range(0..100)).collect();
+
+        reseed(7);
+        let second: Vec<u32> = (0..8).map(|_| rng().random_range(0..100)).collect();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn seeded_work_leaves_the_run_rolls_alone() {
+        reseed(7);
+        let expected: Vec<u32> = (0..8).map(|_| rng().random_range(0..100)).collect();
+
+        reseed(7);
+        let inner = with_seed(3, || rng().random_range(0..100));
+        let rolls: Vec<u32> = (0..8).map(|_| rng().random_range(0..100)).collect();
+
+        assert_eq!(rolls, expected);
+        assert_eq!(inner, with_seed(3, || rng().random_range(0..100)));
+    }
+}
@@ -0,0 +1,105 @@
+//! This module tracks what happened over the course of a run, so the carnage report can break it
+//! down once the run is over.
+
+use std::collections::BTreeMap;
+
+use crate::common::weapons::DamageArea;
+use crate::prelude::Duration;
+
+/// Per-run totals and timelines, sampled as the run plays out.
+#[derive(Clone, Debug, Default)]
+pub struct RunStats {
+    /// Kills in each `BUCKET_SECS` slice of the run.
+    pub kills_timeline: Vec<u64>,
+    /// Gold gained in each `BUCKET_SECS` slice of the run.
+    pub gold_timeline: Vec<u64>,
+    /// Total damage dealt by each METHOD, keyed by its name.
+    pub damage_by_weapon: BTreeMap<&'static str, u64>,
+    /// The most damage dealt by a single hit.
+    pub highest_hit: i32,
+    /// How many times the run looped after surviving the timer.
+    pub loops: u32,
+
+    last_kill_count: u64,
+    last_gold: u128,
+}
+
+impl RunStats {
+    /// How many seconds of the run each timeline entry covers.
+    pub const BUCKET_SECS: u64 = 10;
+
+    /// Creates a new `RunStats`, counting gold gained from `starting_gold`.
+    #[must_use]
+    pub fn new(starting_gold: u128) -> Self {
+        Self {
+            last_gold: starting_gold,
+            ..Self::default()
+        }
+    }
+
+    /// Records the kills and gold gained since the last sample into the bucket `elapsed` falls in.
+    pub fn sample(&mut self, elapsed: Duration, kill_count: u64, gold: u128) {
+        let bucket = (elapsed.as_secs() / Self::BUCKET_SECS) as usize;
+        if self.kills_timeline.len() <= bucket {
+            self.kills_timeline.resize(bucket + 1, 0);
+            self.gold_timeline.resize(bucket + 1, 0);
+        }
+
+        self.kills_timeline[bucket] += kill_count.saturating_sub(self.last_kill_count);
+        self.gold_timeline[bucket] +=
+            u64::try_from(gold.saturating_sub(self.last_gold)).unwrap_or(u64::MAX);
+
+        self.last_kill_count = kill_count;
+        self.last_gold = gold;
+    }
+
+    /// Records the damage a `DamageArea` dealt against the METHOD that created it.
+    pub fn record_damage(&mut self, area: &DamageArea, damage_dealt: i32) {
+        if damage_dealt <= 0 {
+            return;
+        }
+
+        self.highest_hit = self.highest_hit.max(area.damage_amount);
+
+        if let Some(source) = area.source {
+            *self.damage_by_weapon.entry(source).or_default() +=
+                u64::from(damage_dealt.unsigned_abs());
+        }
+    }
+
+    #[must_use]
+    pub fn get_total_kills(&self) -> u64 {
+        self.kills_timeline.iter().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::runstats::RunStats;
+    use crate::common::weapons::DamageArea;
+    use crate::prelude::Duration;
+
+    #[test]
+    fn samples_into_buckets() {
+        let mut run_stats = RunStats::new(100);
+
+        run_stats.sample(Duration::from_secs(2), 3, 150);
+        run_stats.sample(Duration::from_secs(9), 5, 160);
+        run_stats.sample(Duration::from_secs(25), 6, 200);
+
+        assert_eq!(run_stats.kills_timeline, vec![5, 0, 1]);
+        assert_eq!(run_stats.gold_timeline, vec![60, 0, 40]);
+        assert_eq!(run_stats.get_total_kills(), 6);
+
+        let area = DamageArea {
+            damage_amount: 12,
+            source: Some("FLASH"),
+            ..DamageArea::new_empty()
+        };
+        run_stats.record_damage(&area, 20);
+        run_stats.record_damage(&area, 0);
+
+        assert_eq!(run_stats.damage_by_weapon.get("FLASH"), Some(&20));
+        assert_eq!(run_stats.highest_hit, 12);
+    }
+}
@@ -0,0 +1,111 @@
+//! This module defines `SaveStore`, the interface each platform's save backend implements, so the
+//! game can save and load progress without knowing where it ends up. Saves are kept in named
+//! slots, and every backend reports failures as a `SaveError`.
+
+use std::fmt::{self, Display};
+
+use crate::common::upgrades::upgrade::{PlayerState, get_upgrade_tree};
+
+/// The slot the game saves the player's progress to. It's named after where the one save was kept
+/// before there were slots, so older saves are still found.
+pub const MAIN_SLOT: &str = "player_state";
+
+/// Why a save couldn't be saved, loaded or deleted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SaveError {
+    /// There's nothing saved in the slot.
+    Missing,
+    /// Wherever saves are kept couldn't be read from or written to.
+    Storage(String),
+    /// The save was read, but isn't a valid save.
+    Damaged(String),
+}
+
+impl Display for SaveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SaveError::Missing => write!(f, "there's no save"),
+            SaveError::Storage(reason) => write!(f, "{reason}"),
+            SaveError::Damaged(reason) => write!(f, "the save is damaged: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for SaveError {}
+
+/// Somewhere the player's progress can be saved to and loaded from, in named slots.
+pub trait SaveStore {
+    /// Saves the player's progress to `slot`, replacing any previous save there.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the progress could not be serialized or written.
+    fn save(&self, slot: &str, player_state: &PlayerState) -> Result<(), SaveError>;
+
+    /// Loads the progress saved in `slot`.
+    ///
+    /// # Errors
+    ///
+    /// Errors with `SaveError::Missing` if there is no save there, or another `SaveError` if it
+    /// could not be read or deserialized.
+    fn load(&self, slot: &str) -> Result<PlayerState, SaveError>;
+
+    /// Lists the slots that have something saved in them, in no particular order.
+    ///
+    /// # Errors
+    ///
+    /// Errors if wherever saves are kept couldn't be read.
+    fn list_slots(&self) -> Result<Vec<String>, SaveError>;
+
+    /// Deletes the save in `slot`. Deleting an empty slot does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the save couldn't be removed.
+    fn delete(&self, slot: &str) -> Result<(), SaveError>;
+
+    /// Checks if there is progress in `slot` to continue from. A missing save just means there's
+    /// nothing to continue, so only a save that's there but can't be read is an error.
+    ///
+    /// # Errors
+    ///
+    /// Errors if there is a save, but it could not be read or deserialized.
+    fn has_save(&self, slot: &str) -> Result<bool, SaveError> {
+        match self.load(slot) {
+            Ok(_) => Ok(true),
+            Err(SaveError::Missing) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+}
+
+/// Reads a save from JSON, bringing anything out of range back within what the game allows.
+///
+/// # Errors
+///
+/// Errors if the JSON isn't a valid save.
+pub fn parse_save(json: &str) -> Result<PlayerState, serde_json::Error> {
+    let mut player_state: PlayerState = serde_json::from_str(json)?;
+
+    player_state.sanitize(&get_upgrade_tree()?);
+
+    Ok(player_state)
+}
+
+/// Writes a save out as JSON, for backends that store text.
+///
+/// # Errors
+///
+/// Errors with `SaveError::Storage` if the progress couldn't be serialized.
+pub fn serialize_save(player_state: &PlayerState) -> Result<String, SaveError> {
+    serde_json::to_string(player_state).map_err(|err| SaveError::Storage(err.to_string()))
+}
+
+/// Reads a save a backend has loaded, reporting one that isn't valid as damaged.
+///
+/// # Errors
+///
+/// Errors with `SaveError::Damaged` if the JSON isn't a valid save.
+pub fn read_save(json: &str) -> Result<PlayerState, SaveError> {
+    parse_save(json).map_err(|err| SaveError::Damaged(err.to_string()))
+}
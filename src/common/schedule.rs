@@ -0,0 +1,69 @@
+//! This module provides the damage schedule, which holds damage areas that should land on a later
+//! tick than the one they were made on, such as echoed attacks or bombs with fuses.
+
+use crate::common::TICK_RATE;
+use crate::common::weapons::DamageArea;
+use crate::prelude::Duration;
+
+/// Damage areas waiting to land, each with the tick it's due on.
+#[derive(Clone, Default)]
+pub struct DamageSchedule {
+    queue: Vec<(u64, DamageArea)>,
+}
+
+impl DamageSchedule {
+    /// Schedules `area` to land `delay` after `tickcount`, at least a tick later.
+    pub fn schedule(&mut self, tickcount: u64, delay: Duration, area: DamageArea) {
+        let ticks = (delay.as_secs_f64() * TICK_RATE).round().max(1.) as u64;
+        self.queue.push((tickcount + ticks, area));
+    }
+
+    /// Removes and returns every area due on or before `tickcount`, in the order they were
+    /// scheduled.
+    pub fn take_due(&mut self, tickcount: u64) -> Vec<DamageArea> {
+        let (due, waiting): (Vec<_>, Vec<_>) = std::mem::take(&mut self.queue)
+            .into_iter()
+            .partition(|(tick, _)| *tick <= tickcount);
+        self.queue = waiting;
+
+        due.into_iter().map(|(_, area)| area).collect()
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.queue.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::schedule::DamageSchedule;
+    use crate::common::weapons::DamageArea;
+    use crate::prelude::Duration;
+
+    #[test]
+    fn areas_land_once_due() {
+        let mut schedule = DamageSchedule::default();
+        let area = |damage_amount| DamageArea {
+            damage_amount,
+            ..DamageArea::new_empty()
+        };
+
+        schedule.schedule(10, Duration::from_secs(1), area(1));
+        schedule.schedule(10, Duration::ZERO, area(2));
+
+        let due = schedule.take_due(11);
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].damage_amount, 2);
+        assert_eq!(schedule.len(), 1);
+
+        assert!(schedule.take_due(12).is_empty());
+        assert_eq!(schedule.take_due(1000).len(), 1);
+        assert!(schedule.is_empty());
+    }
+}
@@ -0,0 +1,2 @@
+pub mod setting;
+pub mod settingsmenu;
@@ -0,0 +1,356 @@
+//! This module defines the player's persistent settings and the options shown in the settings menu.
+
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator, IntoStaticStr};
+
+/// How enemies show how much health they have left.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, IntoStaticStr,
+)]
+pub enum EnemyHealthDisplay {
+    /// Enemies turn italic below half health.
+    #[default]
+    #[strum(serialize = "classic")]
+    Classic,
+
+    /// Enemies shrink through ✖ → X → x as they lose health.
+    #[strum(serialize = "glyphs")]
+    Glyphs,
+}
+
+/// Which enemy weapons that seek targets go for first.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, IntoStaticStr,
+)]
+pub enum TargetPriority {
+    #[default]
+    #[strum(serialize = "nearest")]
+    Nearest,
+
+    #[strum(serialize = "lowest health")]
+    LowestHealth,
+
+    #[strum(serialize = "highest health")]
+    HighestHealth,
+
+    /// The enemy with the most other enemies packed around it.
+    #[strum(serialize = "densest cluster")]
+    DensestCluster,
+}
+
+/// The most frames drawn per second.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, IntoStaticStr,
+)]
+pub enum FrameLimit {
+    #[default]
+    #[strum(serialize = "60 fps")]
+    Sixty,
+
+    #[strum(serialize = "30 fps")]
+    Thirty,
+
+    #[strum(serialize = "15 fps")]
+    Fifteen,
+}
+
+impl FrameLimit {
+    #[must_use]
+    pub fn get_fps(&self) -> f64 {
+        match self {
+            FrameLimit::Sixty => 60.,
+            FrameLimit::Thirty => 30.,
+            FrameLimit::Fifteen => 15.,
+        }
+    }
+}
+
+/// The most enemies kept alive at once. Enemies past this are held back and sent in once there
+/// is room again.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, IntoStaticStr,
+)]
+pub enum EnemyCap {
+    #[strum(serialize = "250")]
+    Low,
+
+    #[default]
+    #[strum(serialize = "500")]
+    Normal,
+
+    #[strum(serialize = "1000")]
+    High,
+
+    #[strum(serialize = "2000")]
+    Max,
+}
+
+impl EnemyCap {
+    #[must_use]
+    pub fn get_cap(&self) -> u64 {
+        match self {
+            EnemyCap::Low => 250,
+            EnemyCap::Normal => 500,
+            EnemyCap::High => 1000,
+            EnemyCap::Max => 2000,
+        }
+    }
+}
+
+/// How large numbers like gold are written.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, IntoStaticStr,
+)]
+pub enum NumberFormat {
+    /// 1.24M, 3.1B and so on.
+    #[default]
+    #[strum(serialize = "short")]
+    Short,
+
+    /// 1.24e6.
+    #[strum(serialize = "scientific")]
+    Scientific,
+
+    /// Every digit.
+    #[strum(serialize = "full")]
+    Full,
+}
+
+/// The colours the game is drawn in.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, IntoStaticStr,
+)]
+pub enum Theme {
+    #[default]
+    #[strum(serialize = "classic")]
+    Classic,
+
+    /// No colours, only the terminal's own foreground and background.
+    #[strum(serialize = "mono")]
+    Mono,
+}
+
+/// How screenshots are written out.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, IntoStaticStr,
+)]
+pub enum ScreenshotFormat {
+    /// Text with ANSI colour codes, shown in colour by `cat` in a terminal.
+    #[default]
+    #[strum(serialize = "ansi colour")]
+    Ansi,
+
+    #[strum(serialize = "plain text")]
+    PlainText,
+}
+
+/// The player's settings, saved alongside their progress.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Settings {
+    pub enemy_health_display: EnemyHealthDisplay,
+    /// Whether the character leaves a fading trail of recent positions.
+    pub show_trail: bool,
+    /// Whether WASD only moves while IJKL or the arrow keys aim.
+    pub manual_aim: bool,
+    /// Whether holding two movement keys moves diagonally, and enemies can step diagonally too.
+    pub diagonal_movement: bool,
+    /// Whether runs start with a second player on the arrow keys.
+    pub local_coop: bool,
+    pub target_priority: TargetPriority,
+    /// Whether blinking and flashing effects are swapped for steady, dimmed highlights.
+    pub reduced_flashing: bool,
+    /// Whether a pane narrating run events as text is shown below the map.
+    pub show_event_log: bool,
+    /// Caps the frame rate, for slow terminals.
+    pub frame_limit: FrameLimit,
+    /// The most enemies alive at once before new ones are held back.
+    pub enemy_cap: EnemyCap,
+    pub number_format: NumberFormat,
+    pub theme: Theme,
+    pub screenshot_format: ScreenshotFormat,
+    /// Whether the last few seconds of frames are kept, so they can be saved as a clip.
+    pub record_clips: bool,
+    /// Whether runs show only the event log in place of the map. Set for the session by the
+    /// `--log-only` flag rather than saved.
+    #[serde(skip)]
+    pub log_only: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            enemy_health_display: EnemyHealthDisplay::default(),
+            show_trail: true,
+            manual_aim: false,
+            diagonal_movement: true,
+            local_coop: false,
+            target_priority: TargetPriority::default(),
+            reduced_flashing: false,
+            show_event_log: false,
+            frame_limit: FrameLimit::default(),
+            enemy_cap: EnemyCap::default(),
+            number_format: NumberFormat::default(),
+            theme: Theme::default(),
+            screenshot_format: ScreenshotFormat::default(),
+            record_clips: false,
+            log_only: false,
+        }
+    }
+}
+
+/// A single adjustable row in the settings menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum SettingOption {
+    EnemyHealthDisplay,
+    Trail,
+    ManualAim,
+    DiagonalMovement,
+    LocalCoop,
+    TargetPriority,
+    ReducedFlashing,
+    EventLog,
+    FrameLimit,
+    EnemyCap,
+    NumberFormat,
+    Theme,
+    ScreenshotFormat,
+    RecordClips,
+}
+
+impl SettingOption {
+    #[must_use]
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            SettingOption::EnemyHealthDisplay => "enemy health",
+            SettingOption::Trail => "movement trail",
+            SettingOption::ManualAim => "manual aim",
+            SettingOption::DiagonalMovement => "diagonal movement",
+            SettingOption::LocalCoop => "local co-op",
+            SettingOption::TargetPriority => "targeting",
+            SettingOption::ReducedFlashing => "reduced flashing",
+            SettingOption::EventLog => "event log",
+            SettingOption::FrameLimit => "frame limit",
+            SettingOption::EnemyCap => "enemy cap",
+            SettingOption::NumberFormat => "numbers",
+            SettingOption::Theme => "theme",
+            SettingOption::ScreenshotFormat => "screenshots",
+            SettingOption::RecordClips => "clip recording",
+        }
+    }
+
+    #[must_use]
+    pub fn get_desc(&self) -> &'static str {
+        match self {
+            SettingOption::EnemyHealthDisplay => {
+                "classic: enemies turn italic below half health.\nglyphs: enemies shrink from ✖ to X to x as they lose health."
+            }
+            SettingOption::Trail => "leave a fading trail behind the character as it moves.",
+            SettingOption::ManualAim => {
+                "WASD moves and IJKL or the arrow keys aim,\nso METHODs can fire behind you while you run."
+            }
+            SettingOption::DiagonalMovement => {
+                "hold two movement keys to move diagonally. enemies step diagonally\ntoo while this is on; turn it off for four way movement."
+            }
+            SettingOption::LocalCoop => {
+                "a second player joins on the arrow keys while the first keeps WASD.\nhealth and METHODs are your own, gold and XP are shared."
+            }
+            SettingOption::TargetPriority => {
+                "which enemy seeking METHODs such as LIGHTNING strike first."
+            }
+            SettingOption::ReducedFlashing => {
+                "stops effects and warnings from blinking or flashing,\nand dims full-block attacks to a steady shade."
+            }
+            SettingOption::EventLog => {
+                "show a log below the map that describes kills, health and choices as text.\nstart with --log-only to show just the log."
+            }
+            SettingOption::FrameLimit => {
+                "the most frames drawn each second.\nlower this if the game stutters; F3 shows timings."
+            }
+            SettingOption::EnemyCap => {
+                "the most enemies alive at once. extra enemies wait off screen\nand arrive as room frees up, so runs stay just as hard."
+            }
+            SettingOption::NumberFormat => {
+                "how gold, costs and other large numbers are written.\nshort: 1.24M, scientific: 1.24e6, full: every digit."
+            }
+            SettingOption::Theme => {
+                "classic: the usual colours.\nmono: no colours, for monochrome displays or a quieter screen."
+            }
+            SettingOption::ScreenshotFormat => {
+                "how F2 screenshots are saved. ansi colour keeps the colours for `cat`,\nplain text is just the characters."
+            }
+            SettingOption::RecordClips => {
+                "keep the last 10 seconds of frames, so F4 can save them as a GIF.\nuses some memory and time each frame while on."
+            }
+        }
+    }
+
+    /// Returns the current value of this option as shown in the menu.
+    #[must_use]
+    pub fn get_value(&self, settings: &Settings) -> &'static str {
+        match self {
+            SettingOption::EnemyHealthDisplay => settings.enemy_health_display.into(),
+            SettingOption::Trail => on_off(settings.show_trail),
+            SettingOption::ManualAim => on_off(settings.manual_aim),
+            SettingOption::DiagonalMovement => on_off(settings.diagonal_movement),
+            SettingOption::LocalCoop => on_off(settings.local_coop),
+            SettingOption::TargetPriority => settings.target_priority.into(),
+            SettingOption::ReducedFlashing => on_off(settings.reduced_flashing),
+            SettingOption::EventLog => on_off(settings.show_event_log),
+            SettingOption::FrameLimit => settings.frame_limit.into(),
+            SettingOption::EnemyCap => settings.enemy_cap.into(),
+            SettingOption::NumberFormat => settings.number_format.into(),
+            SettingOption::Theme => settings.theme.into(),
+            SettingOption::ScreenshotFormat => settings.screenshot_format.into(),
+            SettingOption::RecordClips => on_off(settings.record_clips),
+        }
+    }
+
+    /// Advances this option to its next value, wrapping around to the first.
+    pub fn cycle(&self, settings: &mut Settings) {
+        match self {
+            SettingOption::EnemyHealthDisplay => {
+                settings.enemy_health_display = next_variant(settings.enemy_health_display);
+            }
+            SettingOption::Trail => settings.show_trail = !settings.show_trail,
+            SettingOption::ManualAim => settings.manual_aim = !settings.manual_aim,
+            SettingOption::DiagonalMovement => {
+                settings.diagonal_movement = !settings.diagonal_movement;
+            }
+            SettingOption::LocalCoop => settings.local_coop = !settings.local_coop,
+            SettingOption::TargetPriority => {
+                settings.target_priority = next_variant(settings.target_priority);
+            }
+            SettingOption::ReducedFlashing => {
+                settings.reduced_flashing = !settings.reduced_flashing;
+            }
+            SettingOption::EventLog => settings.show_event_log = !settings.show_event_log,
+            SettingOption::FrameLimit => {
+                settings.frame_limit = next_variant(settings.frame_limit);
+            }
+            SettingOption::EnemyCap => {
+                settings.enemy_cap = next_variant(settings.enemy_cap);
+            }
+            SettingOption::NumberFormat => {
+                settings.number_format = next_variant(settings.number_format);
+            }
+            SettingOption::Theme => settings.theme = next_variant(settings.theme),
+            SettingOption::ScreenshotFormat => {
+                settings.screenshot_format = next_variant(settings.screenshot_format);
+            }
+            SettingOption::RecordClips => settings.record_clips = !settings.record_clips,
+        }
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value { "on" } else { "off" }
+}
+
+/// Returns the variant after `current`, wrapping around to the first.
+fn next_variant<T: IntoEnumIterator + PartialEq + Copy>(current: T) -> T {
+    let variants: Vec<T> = T::iter().collect();
+    let index = variants.iter().position(|v| *v == current).unwrap_or(0);
+    variants[(index + 1) % variants.len()]
+}
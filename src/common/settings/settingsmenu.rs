@@ -0,0 +1,157 @@
+//! This module provides the settings screen, reachable from the upgrades menu.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout},
+    style::{Style, Stylize},
+    symbols::border,
+    text::Line,
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+};
+use strum::IntoEnumIterator;
+
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::settings::setting::SettingOption;
+use crate::common::{Goto, PlayerStateRef, View};
+
+/// A struct that manages the state and rendering of the settings screen.
+pub struct SettingsMenu {
+    pub player_state: PlayerStateRef,
+    options: Vec<SettingOption>,
+    pub option_selection: ListState,
+    pub goto: Goto,
+}
+
+impl SettingsMenu {
+    #[must_use]
+    pub fn new(player_state: PlayerStateRef) -> Self {
+        let mut option_selection = ListState::default();
+        option_selection.select_first();
+
+        Self {
+            player_state,
+            options: SettingOption::iter().collect(),
+            option_selection,
+            goto: Goto::Settings,
+        }
+    }
+
+    /// Handles actions for the settings menu.
+    pub fn on_action(&mut self, action: Action) {
+        match action {
+            Action::Up => self.option_selection.select_previous(),
+            Action::Down => self.option_selection.select_next(),
+            Action::Change => {
+                self.cycle_selected();
+            }
+            Action::Back => self.goto = Goto::Upgrades,
+            _ => {}
+        }
+    }
+
+    /// Returns the currently highlighted option.
+    #[must_use]
+    pub fn get_selected_option(&self) -> Option<SettingOption> {
+        self.option_selection
+            .selected()
+            .and_then(|index| self.options.get(index))
+            .copied()
+    }
+
+    /// Advances the highlighted option to its next value.
+    pub fn cycle_selected(&mut self) {
+        if let Some(option) = self.get_selected_option() {
+            option.cycle(&mut self.player_state.borrow_mut().settings);
+        }
+    }
+
+    /// Renders the settings menu to the frame.
+    pub fn render_settings(&mut self, frame: &mut Frame) {
+        let title = Line::from(" dispair.settings ".bold());
+        let instructions = Line::from(vec![
+            " <W|UP> Up | <S|DOWN> Down | <ENTER|SPACE|A|D> Change | <Esc> Back ".into(),
+        ]);
+
+        let window = Block::bordered()
+            .border_set(border::THICK)
+            .title(title.left_aligned())
+            .title_bottom(instructions.left_aligned());
+        let inner = window.inner(frame.area());
+
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Fill(1)]).areas(inner);
+
+        let settings = self.player_state.borrow().settings.clone();
+
+        let list_items: Vec<ListItem> = self
+            .options
+            .iter()
+            .map(|option| {
+                ListItem::from(Line::from(vec![
+                    format!(" {}: ", option.get_name()).into(),
+                    option.get_value(&settings).bold(),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(list_items)
+            .highlight_style(Style::new().bold())
+            .highlight_symbol(">");
+
+        let desc_lines: Vec<Line> = self
+            .get_selected_option()
+            .map(|option| option.get_desc().lines().map(Line::from).collect())
+            .unwrap_or_default();
+
+        let desc = Paragraph::new(desc_lines)
+            .block(Block::bordered().border_set(border::ROUNDED))
+            .centered()
+            .wrap(Wrap { trim: false });
+
+        frame.render_widget(window, frame.area());
+        frame.render_stateful_widget(list, left, &mut self.option_selection);
+        frame.render_widget(desc, right);
+    }
+}
+
+impl View for SettingsMenu {
+    fn on_tick(&mut self) {}
+
+    fn transition(&self) -> &Goto {
+        &self.goto
+    }
+
+    fn render(&mut self, frame: &mut Frame) {
+        self.render_settings(frame);
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        self.on_action(action);
+    }
+
+    fn get_key_context(&self) -> KeyContext {
+        KeyContext::Settings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use crate::common::View;
+    use crate::common::settings::settingsmenu::SettingsMenu;
+    use crate::common::upgrades::upgrade::PlayerState;
+
+    #[test]
+    fn settings_menu_snapshot() {
+        let mut menu = SettingsMenu::new(Rc::new(RefCell::new(PlayerState::default())));
+        let mut terminal = Terminal::new(TestBackend::new(80, 20)).unwrap();
+
+        terminal.draw(|frame| menu.render(frame)).unwrap();
+
+        insta::assert_snapshot!(terminal.backend());
+    }
+}
@@ -5,6 +5,8 @@ use rodio::{Decoder, MixerDeviceSink, Source};
 use std::io::Cursor;
 
 static SOUNDS_DIR: Dir<'static> = include_dir!("assets/sounds");
+
+#[derive(Clone, Copy)]
 pub enum SoundEffect {
     Hit,
     Flash,
@@ -14,6 +16,12 @@ pub enum SoundEffect {
 }
 
 impl SoundEffect {
+    /// Returns a cursor over the embedded wav file for this effect.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the sound file is missing from the embedded assets.
+    #[must_use]
     pub fn get_file(&self) -> Cursor<&'static [u8]> {
         match self {
             SoundEffect::Hit => {
@@ -70,6 +78,12 @@ impl SoundEffect {
         }
     }
 
+    /// Decodes the effect's wav file and lowers its volume.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the embedded file cannot be decoded.
+    #[must_use]
     pub fn decoded(&self) -> Amplify<Decoder<Cursor<&'static [u8]>>> {
         Decoder::try_from(self.get_file())
             .expect("Couldn't decode file.")
@@ -88,6 +102,12 @@ impl Default for SoundWrangler {
 }
 
 impl SoundWrangler {
+    /// Opens the default audio output device.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if no audio device can be opened.
+    #[must_use]
     pub fn new() -> Self {
         let sink = rodio::DeviceSinkBuilder::open_default_sink().expect("Error opening sound");
 
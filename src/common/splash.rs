@@ -0,0 +1,238 @@
+//! This module provides splashes: ANSI art embedded from `assets/art`, typed out a character at a
+//! time for the title screen, level-ups and the death screen. Any key skips the typing.
+
+use ansi_to_tui::IntoText;
+use include_dir::{Dir, include_dir};
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Paragraph},
+};
+use strum::EnumIter;
+
+use crate::common::utils::center;
+use crate::prelude::{Duration, Instant};
+
+static ART_DIR: Dir<'static> = include_dir!("assets/art");
+
+/// The art a splash can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter)]
+pub enum SplashArt {
+    Title,
+    LevelUp,
+    Death,
+}
+
+impl SplashArt {
+    fn get_file_name(self) -> &'static str {
+        match self {
+            SplashArt::Title => "title.ans",
+            SplashArt::LevelUp => "levelup.ans",
+            SplashArt::Death => "death.ans",
+        }
+    }
+
+    /// How many characters are typed a second.
+    fn get_type_rate(self) -> f64 {
+        match self {
+            SplashArt::Title => 300.,
+            SplashArt::LevelUp => 200.,
+            SplashArt::Death => 120.,
+        }
+    }
+
+    /// How long the art stays up once it's typed, or `None` if it stays until it's closed.
+    fn get_hold(self) -> Option<Duration> {
+        match self {
+            SplashArt::Title => None,
+            SplashArt::LevelUp => Some(Duration::from_millis(600)),
+            SplashArt::Death => Some(Duration::from_millis(1500)),
+        }
+    }
+
+    /// Reads the art from its embedded file, keeping its colours. Art that doesn't parse as ANSI
+    /// is shown as plain text.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the art file is missing from the embedded assets.
+    #[must_use]
+    pub fn load(self) -> Text<'static> {
+        let file = ART_DIR.get_file(self.get_file_name()).unwrap();
+
+        file.contents()
+            .into_text()
+            .unwrap_or_else(|_| Text::raw(file.contents_utf8().unwrap_or_default()))
+    }
+}
+
+/// A piece of art being typed out.
+#[derive(Debug, Clone)]
+pub struct Splash {
+    pub art: SplashArt,
+    text: Text<'static>,
+    /// How many characters there are to type. Spaces are typed for free, so gaps in the art
+    /// don't hold it up.
+    length: usize,
+    started: Instant,
+    skipped: bool,
+}
+
+impl Splash {
+    #[must_use]
+    pub fn new(art: SplashArt) -> Self {
+        let text = art.load();
+        let length = text
+            .iter()
+            .flat_map(|line| line.iter())
+            .flat_map(|span| span.content.chars())
+            .filter(|char| !char.is_whitespace())
+            .count();
+
+        Self {
+            art,
+            text,
+            length,
+            started: Instant::now(),
+            skipped: false,
+        }
+    }
+
+    /// Finishes typing the art straight away.
+    pub fn skip(&mut self) {
+        self.skipped = true;
+    }
+
+    fn get_typed(&self) -> usize {
+        if self.skipped {
+            return self.length;
+        }
+
+        let typed = self.started.elapsed().as_secs_f64() * self.art.get_type_rate();
+        (typed as usize).min(self.length)
+    }
+
+    #[must_use]
+    pub fn is_typed(&self) -> bool {
+        self.get_typed() >= self.length
+    }
+
+    /// Whether the art has been typed and held for long enough to close on its own.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.art.get_hold().is_some_and(|hold| {
+            let typing = Duration::from_secs_f64(self.length as f64 / self.art.get_type_rate());
+            self.skipped || self.started.elapsed() >= typing + hold
+        })
+    }
+
+    /// Returns the art with only its first `typed` characters showing.
+    #[must_use]
+    pub fn get_revealed(&self, typed: usize) -> Text<'static> {
+        let mut left = typed;
+
+        self.text
+            .iter()
+            .map(|line| {
+                let spans: Vec<Span<'static>> = line
+                    .iter()
+                    .map(|span| {
+                        let content: String = span
+                            .content
+                            .chars()
+                            .take_while(|char| {
+                                if char.is_whitespace() {
+                                    return true;
+                                }
+                                let shown = left > 0;
+                                left = left.saturating_sub(1);
+                                shown
+                            })
+                            .collect();
+                        Span::styled(content, span.style)
+                    })
+                    .collect();
+                Line::from(spans).style(line.style)
+            })
+            .collect()
+    }
+
+    fn get_size(&self) -> (u16, u16) {
+        let width = u16::try_from(self.text.width()).unwrap_or(u16::MAX);
+        let height = u16::try_from(self.text.height()).unwrap_or(u16::MAX);
+        (width, height)
+    }
+
+    /// Whether the whole art fits in `area`.
+    #[must_use]
+    pub fn fits(&self, area: Rect) -> bool {
+        let (width, height) = self.get_size();
+        width <= area.width && height <= area.height
+    }
+
+    /// Draws the art typed so far in the middle of `area`. It's laid out as if it were all typed,
+    /// so it doesn't shift about as it goes.
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let (width, height) = self.get_size();
+        let art_area = center(area, width, height).intersection(area);
+
+        frame.render_widget(
+            Paragraph::new(self.get_revealed(self.get_typed())),
+            art_area,
+        );
+    }
+
+    /// Draws the art typed so far in a box over the middle of `area`.
+    pub fn render_popup(&self, frame: &mut Frame, area: Rect) {
+        let (width, height) = self.get_size();
+        let block = Block::bordered()
+            .title_bottom(Line::from(" any key to skip ".dark_gray()).centered())
+            .border_set(border::THICK);
+        let popup_area = center(area, width.max(17) + 4, height + 2).intersection(area);
+
+        frame.render_widget(Clear, popup_area);
+        self.render(frame, block.inner(popup_area));
+        frame.render_widget(block, popup_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::text::Text;
+    use strum::IntoEnumIterator;
+
+    use crate::common::splash::{Splash, SplashArt};
+
+    fn to_plain(text: &Text) -> String {
+        text.iter()
+            .map(|line| line.iter().map(|span| span.content.as_ref()).collect())
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn art_loads_without_escapes() {
+        for art in SplashArt::iter() {
+            let text = art.load();
+            let plain = to_plain(&text);
+
+            assert!(text.height() > 0, "{art:?} has no art");
+            assert!(!plain.contains('\x1b'), "{art:?} kept its escape codes");
+        }
+    }
+
+    #[test]
+    fn typing_skips_over_spaces() {
+        let mut splash = Splash::new(SplashArt::LevelUp);
+        let full = to_plain(&splash.get_revealed(usize::MAX));
+
+        assert_eq!(to_plain(&splash.get_revealed(1)).trim(), ".");
+        assert_eq!(to_plain(&splash.get_revealed(splash.length)), full);
+
+        splash.skip();
+        assert!(splash.is_typed() && splash.is_finished());
+    }
+}
@@ -1,11 +1,14 @@
-use std::{collections::HashMap, ops::Sub};
-
-use crate::prelude::Duration;
+use std::{
+    collections::{BTreeMap, HashMap, btree_map::Entry},
+    ops::Sub,
+};
 
 use derive_more::Sub;
 use serde::{Deserialize, Serialize};
+use strum::IntoStaticStr;
 
 use crate::common::debuffs::Debuff;
+use crate::common::onkill::OnKillEffect;
 
 /// Represents the player's inventory.
 #[derive(Serialize, Deserialize, Default, Debug, Clone)]
@@ -31,6 +34,85 @@ impl Inventory {
     }
 }
 
+/// A single derived stat that differs between two `Stats`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatChange {
+    pub name: String,
+    pub before: String,
+    pub after: String,
+}
+
+impl Stats {
+    /// Lists every stat that differs between `self` and `other`, named after its field. Nested
+    /// stats such as procs are named by their path, e.g. `procs.burn.chance`.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if `Stats` can't be serialized, which shouldn't happen.
+    #[must_use]
+    pub fn diff(&self, other: &Stats) -> Vec<StatChange> {
+        let before = serde_json::to_value(self).expect("Stats should serialize");
+        let after = serde_json::to_value(other).expect("Stats should serialize");
+
+        let mut changes = Vec::new();
+        for group in ["game_stats", "player_stats", "weapon_stats"] {
+            diff_values("", &before[group], &after[group], &mut changes);
+        }
+        changes
+    }
+}
+
+fn diff_values(
+    path: &str,
+    before: &serde_json::Value,
+    after: &serde_json::Value,
+    changes: &mut Vec<StatChange>,
+) {
+    use serde_json::Value;
+
+    match (before, after) {
+        (Value::Object(before_map), Value::Object(after_map)) => {
+            let mut keys: Vec<&String> = before_map.keys().chain(after_map.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                let child_path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                diff_values(
+                    &child_path,
+                    before_map.get(key).unwrap_or(&Value::Null),
+                    after_map.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ if before != after => changes.push(StatChange {
+            name: path.to_string(),
+            before: format_stat(before),
+            after: format_stat(after),
+        }),
+        _ => {}
+    }
+}
+
+fn format_stat(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "-".to_string(),
+        serde_json::Value::Number(number) if number.is_f64() => {
+            let formatted = format!("{:.2}", number.as_f64().unwrap_or_default());
+            formatted
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_string()
+        }
+        _ => value.to_string(),
+    }
+}
+
 /// Represents the player's stats.
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct Stats {
@@ -40,6 +122,7 @@ pub struct Stats {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(default)]
 pub struct GameStats {
     pub enemy_spawn_mult: f64,
     pub enemy_move_mult: f64,
@@ -53,6 +136,19 @@ pub struct GameStats {
 
     pub max_method_level: i32,
     pub max_charm_level: i32,
+    /// How many different charms can be held at once.
+    pub max_charms: usize,
+    /// Whether the autopilot can be switched on during runs.
+    pub autopilot: bool,
+
+    /// Scales how quickly the spawn director builds up its threat budget.
+    pub spawn_budget_mult: f64,
+    /// Seconds of threat the spawn director saves up before releasing a wave.
+    pub spawn_wave_secs: f64,
+    /// How many normal enemies' worth of budget an elite costs.
+    pub elite_spawn_cost: f64,
+    /// How many enemies the spawn director sends in together as a pack.
+    pub spawn_pack_size: usize,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Sub)]
@@ -68,13 +164,54 @@ pub struct PlayerStats {
     pub shove_damage: u32,
 
     pub movement_speed_mult: f64,
+
+    /// The maximum shield, which absorbs damage before health.
+    #[serde(default)]
+    pub shield: i32,
+    /// Seconds without being hit before the shield starts regenerating.
+    #[serde(default = "default_shield_regen_delay")]
+    pub shield_regen_delay: f64,
+
+    /// Seconds of immunity after taking damage.
+    #[serde(default = "default_iframe_secs")]
+    pub iframe_secs: f64,
+
+    /// Fraction of weapon damage dealt that heals the character.
+    #[serde(default)]
+    pub lifesteal: f64,
+
+    /// How close gold gems need to be before they are pulled towards the character.
+    #[serde(default = "default_pickup_radius")]
+    pub pickup_radius: i32,
+
+    /// The share of hazard damage ignored, where 1 is full immunity.
+    #[serde(default)]
+    pub hazard_resist: f64,
+}
+
+fn default_shield_regen_delay() -> f64 {
+    PlayerStats::default().shield_regen_delay
+}
+
+fn default_iframe_secs() -> f64 {
+    PlayerStats::default().iframe_secs
+}
+
+fn default_pickup_radius() -> i32 {
+    PlayerStats::default().pickup_radius
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct WeaponStats {
     pub damage_flat_boost: i32,
 
-    pub procs: HashMap<String, Proc>,
+    /// The debuffs this weapon's hits can apply. Add to it with `add_proc`, so a proc given by
+    /// more than one source merges rather than being overwritten.
+    pub procs: BTreeMap<ProcId, Proc>,
+
+    /// Effects set off when this weapon's damage kills an enemy, by name.
+    #[serde(default)]
+    pub on_kill: HashMap<String, OnKillEffect>,
 
     pub size: i32,
 
@@ -93,6 +230,43 @@ pub struct DebuffStats {
     pub on_damage_effect: bool,
 }
 
+impl WeaponStats {
+    /// Gives this weapon the proc `id`, merging it into the proc already there if another source
+    /// gave it first. See `Proc::merge` for how.
+    pub fn add_proc(&mut self, id: ProcId, proc: Proc) {
+        match self.procs.entry(id) {
+            Entry::Occupied(mut existing) => existing.get_mut().merge(proc),
+            Entry::Vacant(slot) => {
+                slot.insert(proc);
+            }
+        }
+    }
+}
+
+/// Identifies a proc, whichever source it comes from.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, IntoStaticStr,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum ProcId {
+    /// Marks enemies to explode on death, from the MARK upgrades.
+    Mark,
+    /// Sets enemies burning, from FLASH and ignitions.
+    Burn,
+    /// Charges enemies to shock their neighbours when hit, from LIGHTNING.
+    Charge,
+    /// Electrocutes enemies near a charged one.
+    Electrocute,
+}
+
+impl ProcId {
+    #[must_use]
+    pub fn get_name(self) -> &'static str {
+        self.into()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Proc {
     /// Chance is an int between 0-100.
@@ -100,6 +274,27 @@ pub struct Proc {
     pub debuff: Debuff,
 }
 
+impl Proc {
+    /// Merges `other`, the same proc from another source, into this one. The higher chance wins,
+    /// sizes add up, the higher damage and misc value are kept, and the debuff goes off at any
+    /// point either source would set it off.
+    pub fn merge(&mut self, other: Proc) {
+        let stats = &mut self.debuff.stats;
+        let other_stats = other.debuff.stats;
+
+        self.chance = self.chance.max(other.chance);
+        stats.size = match (stats.size, other_stats.size) {
+            (Some(size), Some(other_size)) => Some(size + other_size),
+            (size, other_size) => size.or(other_size),
+        };
+        stats.damage = stats.damage.max(other_stats.damage);
+        stats.misc_value = stats.misc_value.max(other_stats.misc_value);
+        stats.on_death_effect |= other_stats.on_death_effect;
+        stats.on_tick_effect |= other_stats.on_tick_effect;
+        stats.on_damage_effect |= other_stats.on_damage_effect;
+    }
+}
+
 impl Default for GameStats {
     /// Baseline game-level modifiers used when no upgrades are applied.
     fn default() -> Self {
@@ -114,12 +309,18 @@ impl Default for GameStats {
             timer: 60,
             max_charm_level: 1,
             max_method_level: 1,
+            max_charms: 3,
+            autopilot: false,
+            spawn_budget_mult: 1.,
+            spawn_wave_secs: 4.,
+            elite_spawn_cost: 12.,
+            spawn_pack_size: 6,
         }
     }
 }
 
 impl Default for PlayerStats {
-    /// Constructs a `PlayerStats` with baseline health, damage, movement, and shove defaults.
+    /// Constructs a `PlayerStats` with baseline health, damage, movement, shove, shield, i-frame, lifesteal, pickup radius, and hazard resistance defaults.
     fn default() -> Self {
         Self {
             base_health: 10,
@@ -129,19 +330,112 @@ impl Default for PlayerStats {
             movement_speed_mult: 1.,
             shove_amount: 0,
             shove_damage: 0,
+            shield: 0,
+            shield_regen_delay: 3.,
+            iframe_secs: 0.5,
+            lifesteal: 0.,
+            pickup_radius: 2,
+            hazard_resist: 0.,
         }
     }
 }
 
 impl Default for WeaponStats {
-    /// Creates a default `WeaponStats` with no damage boost, no size, and no procs.
+    /// Creates a default `WeaponStats` with no damage boost, no size, no procs and no on-kill
+    /// effects.
     fn default() -> Self {
         Self {
             damage_flat_boost: 0,
             size: 0,
-            procs: HashMap::new(),
+            procs: BTreeMap::new(),
+            on_kill: HashMap::new(),
             level: 1,
             elemental_honage: 1.,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::common::debuffs::{Debuff, DebuffTypes};
+    use crate::common::powerup::{DynPowerup, PowerupTypes, PowerupUpgrade};
+    use crate::common::stats::{DebuffStats, Proc, ProcId, WeaponStats};
+    use crate::common::upgrades::upgrade::{CurrentUpgradesTrait, PlayerState};
+    use crate::common::weapons::WeaponWrapper;
+
+    fn proc(debuff_type: DebuffTypes, chance: u32, size: i32, damage: i32) -> Proc {
+        Proc {
+            chance,
+            debuff: Debuff {
+                debuff_type,
+                complete: false,
+                stats: DebuffStats {
+                    size: Some(size),
+                    damage: Some(damage),
+                    misc_value: None,
+                    on_death_effect: false,
+                    on_tick_effect: true,
+                    on_damage_effect: false,
+                },
+            },
+        }
+    }
+
+    #[test]
+    fn procs_from_upgrades_and_weapons_merge() {
+        let mut player_state = PlayerState::default();
+        player_state.upgrades.set("311", 10);
+        player_state.upgrades.set("312", 1);
+        player_state.refresh();
+
+        let mut weapon_stats = player_state.stats.weapon_stats.clone();
+        let mark = &weapon_stats.procs[&ProcId::Mark];
+        assert_eq!(mark.chance, 10);
+        assert_eq!(mark.debuff.stats.size, Some(2));
+
+        weapon_stats.add_proc(
+            ProcId::Mark,
+            proc(DebuffTypes::MarkedForExplosion, 30, 1, 2),
+        );
+        let mark = &weapon_stats.procs[&ProcId::Mark];
+        assert_eq!(mark.chance, 30);
+        assert_eq!(mark.debuff.stats.size, Some(3));
+        assert_eq!(mark.debuff.stats.damage, Some(6));
+        assert!(mark.debuff.stats.on_death_effect && mark.debuff.stats.on_tick_effect);
+
+        // an ignition's burn already on the stats merges with FLASH's own
+        weapon_stats.add_proc(ProcId::Burn, proc(DebuffTypes::FlameBurn, 90, 1, 9));
+        let player_state = Rc::new(RefCell::new(player_state));
+        player_state.borrow_mut().stats.game_stats.max_method_level = 5;
+
+        let mut flash = WeaponWrapper::Flash(None);
+        flash.populate_inner(weapon_stats, player_state);
+        let powerup: DynPowerup = Box::new(PowerupUpgrade::new(
+            "Flash",
+            String::new(),
+            1,
+            5,
+            PowerupTypes::Weapon,
+        ));
+        flash.get_inner_mut().upgrade_self(&powerup);
+
+        let stats = flash.get_inner().get_stats();
+        let burn = &stats.procs[&ProcId::Burn];
+        assert_eq!(burn.chance, 90);
+        assert_eq!(burn.debuff.stats.damage, Some(9));
+        assert!(burn.debuff.stats.size > Some(1));
+        assert!(stats.procs.contains_key(&ProcId::Mark));
+    }
+
+    #[test]
+    fn procs_keep_their_names_when_saved() {
+        let mut weapon_stats = WeaponStats::default();
+        weapon_stats.add_proc(ProcId::Burn, proc(DebuffTypes::FlameBurn, 50, 1, 1));
+
+        let json = serde_json::to_value(&weapon_stats).unwrap();
+        assert_eq!(json["procs"]["burn"]["chance"], 50);
+    }
+}
@@ -0,0 +1,299 @@
+//! This module provides the `StatSheet`, which works out the player's stats from layered
+//! modifiers. The upgrade tree gives the base stats, then classes, mutators, CHARMs and buffs
+//! register modifiers with their source rather than changing the stats directly, so each stat can
+//! be rebuilt at any time and broken down by where it came from.
+
+use strum::{EnumIter, EnumString, IntoEnumIterator, IntoStaticStr};
+
+use crate::common::buffs::BuffKind;
+use crate::common::classes::class::CharacterClass;
+use crate::common::mutators::mutator::Mutator;
+use crate::common::stats::Stats;
+
+/// A stat that modifiers can change, named after its field in `Stats`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, EnumString, IntoStaticStr)]
+#[strum(serialize_all = "snake_case")]
+pub enum StatKey {
+    DamageMult,
+    HealthMult,
+    MovementSpeedMult,
+    ShoveAmount,
+    Shield,
+    AttackSpeedMult,
+    GoldMult,
+    EnemyMoveMult,
+    DoomOffset,
+    MaxCharms,
+}
+
+impl StatKey {
+    /// Reads this stat from `stats`.
+    #[must_use]
+    pub fn read(self, stats: &Stats) -> f64 {
+        match self {
+            StatKey::DamageMult => stats.player_stats.damage_mult,
+            StatKey::HealthMult => stats.player_stats.health_mult,
+            StatKey::MovementSpeedMult => stats.player_stats.movement_speed_mult,
+            StatKey::ShoveAmount => f64::from(stats.player_stats.shove_amount),
+            StatKey::Shield => f64::from(stats.player_stats.shield),
+            StatKey::AttackSpeedMult => stats.game_stats.attack_speed_mult,
+            StatKey::GoldMult => stats.game_stats.gold_mult,
+            StatKey::EnemyMoveMult => stats.game_stats.enemy_move_mult,
+            StatKey::DoomOffset => stats.game_stats.doom_offset,
+            StatKey::MaxCharms => stats.game_stats.max_charms as f64,
+        }
+    }
+
+    /// Writes `value` to this stat in `stats`, rounding it for whole-number stats.
+    pub fn write(self, stats: &mut Stats, value: f64) {
+        match self {
+            StatKey::DamageMult => stats.player_stats.damage_mult = value,
+            StatKey::HealthMult => stats.player_stats.health_mult = value,
+            StatKey::MovementSpeedMult => stats.player_stats.movement_speed_mult = value,
+            StatKey::ShoveAmount => stats.player_stats.shove_amount = value.round().max(0.) as u32,
+            StatKey::Shield => stats.player_stats.shield = value.round() as i32,
+            StatKey::AttackSpeedMult => stats.game_stats.attack_speed_mult = value,
+            StatKey::GoldMult => stats.game_stats.gold_mult = value,
+            StatKey::EnemyMoveMult => stats.game_stats.enemy_move_mult = value,
+            StatKey::DoomOffset => stats.game_stats.doom_offset = value,
+            StatKey::MaxCharms => stats.game_stats.max_charms = value.round().max(0.) as usize,
+        }
+    }
+}
+
+/// How a modifier is combined with the stat. Additive modifiers are summed onto the base, the
+/// total is then multiplied by every multiplicative modifier, and an override replaces the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModifierLayer {
+    Additive,
+    Multiplicative,
+    Override,
+}
+
+/// Where a modifier came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatSource {
+    Class(CharacterClass),
+    Mutator(Mutator),
+    /// A CHARM, by name.
+    Charm(String),
+    Buff(BuffKind),
+}
+
+impl StatSource {
+    #[must_use]
+    pub fn get_name(&self) -> String {
+        match self {
+            StatSource::Class(class) => class.get_name().to_lowercase(),
+            StatSource::Mutator(mutator) => mutator.get_name().to_lowercase(),
+            StatSource::Charm(name) => name.to_lowercase(),
+            StatSource::Buff(kind) => kind.get_name().to_lowercase(),
+        }
+    }
+
+    /// Checks whether the source only lasts for a run, rather than coming from the player's
+    /// setup before it.
+    #[must_use]
+    pub fn is_run_source(&self) -> bool {
+        matches!(self, StatSource::Charm(_) | StatSource::Buff(_))
+    }
+}
+
+/// A single change to a stat, from a source.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatModifier {
+    pub key: StatKey,
+    pub layer: ModifierLayer,
+    pub value: f64,
+    pub source: StatSource,
+}
+
+impl StatModifier {
+    #[must_use]
+    pub fn add(key: StatKey, value: f64, source: StatSource) -> Self {
+        Self {
+            key,
+            layer: ModifierLayer::Additive,
+            value,
+            source,
+        }
+    }
+
+    #[must_use]
+    pub fn mult(key: StatKey, value: f64, source: StatSource) -> Self {
+        Self {
+            key,
+            layer: ModifierLayer::Multiplicative,
+            value,
+            source,
+        }
+    }
+
+    #[must_use]
+    pub fn set(key: StatKey, value: f64, source: StatSource) -> Self {
+        Self {
+            key,
+            layer: ModifierLayer::Override,
+            value,
+            source,
+        }
+    }
+
+    /// Describes the modifier, e.g. `+0.5 bulwark`.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        format!("{} {}", self.describe_value(), self.source.get_name())
+    }
+
+    /// Describes the change the modifier makes without its source, e.g. `x1.25`.
+    #[must_use]
+    pub fn describe_value(&self) -> String {
+        let sign = match self.layer {
+            ModifierLayer::Additive if self.value >= 0. => "+",
+            ModifierLayer::Additive => "",
+            ModifierLayer::Multiplicative => "x",
+            ModifierLayer::Override => "=",
+        };
+
+        format!("{sign}{}", self.value)
+    }
+}
+
+/// How a stat's value was reached.
+#[derive(Debug, Clone)]
+pub struct StatBreakdown {
+    pub key: StatKey,
+    pub base: f64,
+    pub modifiers: Vec<StatModifier>,
+    pub total: f64,
+}
+
+impl StatBreakdown {
+    /// Describes the breakdown on one line, e.g.
+    /// `attack_speed_mult 1 x1.25 attack speed charm -> 1.25`.
+    #[must_use]
+    pub fn describe(&self) -> String {
+        let name: &'static str = self.key.into();
+        let mut parts = vec![name.to_string(), self.base.to_string()];
+        parts.extend(self.modifiers.iter().map(StatModifier::describe));
+        parts.push(format!("-> {}", self.total));
+
+        parts.join(" ")
+    }
+}
+
+/// The base stats and every modifier registered on them.
+#[derive(Debug, Clone, Default)]
+pub struct StatSheet {
+    base: Stats,
+    modifiers: Vec<StatModifier>,
+}
+
+impl StatSheet {
+    #[must_use]
+    pub fn new(base: Stats) -> Self {
+        Self {
+            base,
+            modifiers: vec![],
+        }
+    }
+
+    pub fn add_modifiers(&mut self, modifiers: impl IntoIterator<Item = StatModifier>) {
+        self.modifiers.extend(modifiers);
+    }
+
+    /// Removes every modifier from a source matching `predicate`.
+    pub fn remove_modifiers(&mut self, predicate: impl Fn(&StatSource) -> bool) {
+        self.modifiers
+            .retain(|modifier| !predicate(&modifier.source));
+    }
+
+    /// Works out the value of a stat from its base and modifiers.
+    #[must_use]
+    pub fn get(&self, key: StatKey) -> f64 {
+        self.get_breakdown(key).total
+    }
+
+    /// Breaks a stat down into its base and each modifier on it, in the order they apply.
+    #[must_use]
+    pub fn get_breakdown(&self, key: StatKey) -> StatBreakdown {
+        let base = key.read(&self.base);
+        let on_layer = |layer: ModifierLayer| {
+            self.modifiers
+                .iter()
+                .filter(move |modifier| modifier.key == key && modifier.layer == layer)
+        };
+
+        let added: f64 = on_layer(ModifierLayer::Additive)
+            .map(|modifier| modifier.value)
+            .sum();
+        let multiplied: f64 = on_layer(ModifierLayer::Multiplicative)
+            .map(|modifier| modifier.value)
+            .product();
+        let total = on_layer(ModifierLayer::Override)
+            .next_back()
+            .map_or((base + added) * multiplied, |modifier| modifier.value);
+
+        let modifiers = on_layer(ModifierLayer::Additive)
+            .chain(on_layer(ModifierLayer::Multiplicative))
+            .chain(on_layer(ModifierLayer::Override))
+            .cloned()
+            .collect();
+
+        StatBreakdown {
+            key,
+            base,
+            modifiers,
+            total,
+        }
+    }
+
+    /// Builds the stats with every modifier applied.
+    #[must_use]
+    pub fn get_stats(&self) -> Stats {
+        let mut stats = self.base.clone();
+        for key in StatKey::iter() {
+            key.write(&mut stats, self.get(key));
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::buffs::BuffKind;
+    use crate::common::stats::Stats;
+    use crate::common::statsheet::{StatKey, StatModifier, StatSheet, StatSource};
+
+    #[test]
+    fn layers_apply_in_order() {
+        let mut sheet = StatSheet::new(Stats::default());
+        let charm = StatSource::Charm("Attack Speed Charm".into());
+
+        sheet.add_modifiers([
+            StatModifier::mult(StatKey::AttackSpeedMult, 2., charm.clone()),
+            StatModifier::add(StatKey::AttackSpeedMult, 0.5, charm),
+            StatModifier::mult(
+                StatKey::AttackSpeedMult,
+                1.5,
+                StatSource::Buff(BuffKind::Frenzy),
+            ),
+        ]);
+
+        let breakdown = sheet.get_breakdown(StatKey::AttackSpeedMult);
+        assert!((breakdown.total - 4.5).abs() < f64::EPSILON);
+        assert_eq!(breakdown.modifiers.len(), 3);
+        assert!((sheet.get_stats().game_stats.attack_speed_mult - 4.5).abs() < f64::EPSILON);
+
+        sheet.remove_modifiers(StatSource::is_run_source);
+        assert!((sheet.get(StatKey::AttackSpeedMult) - 1.).abs() < f64::EPSILON);
+
+        sheet.add_modifiers([StatModifier::set(
+            StatKey::MaxCharms,
+            0.,
+            StatSource::Buff(BuffKind::Frenzy),
+        )]);
+        assert_eq!(sheet.get_stats().game_stats.max_charms, 0);
+    }
+}
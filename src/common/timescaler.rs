@@ -1,12 +1,12 @@
 //! This module provides a `TimeScaler` that dynamically adjusts a scaling factor
 //! over time. This is used to increase the game's difficulty as time progresses.
 
-use crate::prelude::SystemTime;
+use crate::common::TICK_RATE;
 
 /// Handles the scaling of game difficulty over time.
 pub struct TimeScaler {
-    /// The time at which the scaling began.
-    pub start_time: SystemTime,
+    /// How many ticks have been played since the scaling began.
+    pub ticks: u64,
     /// The current scaling factor.
     pub doom: f64,
 
@@ -16,11 +16,11 @@ pub struct TimeScaler {
 impl TimeScaler {
     const SCALE_BASE: f64 = 1.007_f64;
 
-    /// Creates a new `TimeScaler` with the start time set to now.
+    /// Creates a new `TimeScaler` that starts scaling from the first tick.
     #[must_use]
-    pub fn now() -> Self {
+    pub fn new() -> Self {
         Self {
-            start_time: SystemTime::now(),
+            ticks: 0,
             doom: 1.0,
             doom_offset: 0.,
         }
@@ -30,14 +30,22 @@ impl TimeScaler {
         self.doom_offset = offset;
     }
 
-    /// Returns the elapsed time in seconds since the `start_time`.
+    /// Counts a tick of play towards the scaling.
+    pub fn tick(&mut self) {
+        self.ticks += 1;
+    }
+
+    /// Returns how many whole seconds have been played since the scaling began.
     #[must_use]
     pub fn time_in_secs(&self) -> u64 {
-        if let Ok(elapsed) = self.start_time.elapsed() {
-            elapsed.as_secs()
-        } else {
-            0
-        }
+        (self.ticks as f64 / TICK_RATE) as u64
+    }
+
+    /// Sets the scaling factor, moving the offset so it keeps scaling up from there.
+    pub fn set_doom(&mut self, doom: f64) {
+        let time_scale = Self::SCALE_BASE.powf(self.time_in_secs() as f64);
+        self.doom_offset = doom / time_scale - 1.;
+        self.scale();
     }
 
     /// Calculates the new scaling factor based on the elapsed time.
@@ -50,3 +58,9 @@ impl TimeScaler {
         self.doom
     }
 }
+
+impl Default for TimeScaler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
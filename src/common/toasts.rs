@@ -0,0 +1,153 @@
+//! This module provides toasts: short, non-blocking notifications stacked in a corner of the game
+//! view, which expire on their own after a few seconds.
+
+use std::collections::VecDeque;
+
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::prelude::{Duration, Instant};
+
+/// How important a toast is, which decides how it is styled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ToastSeverity {
+    #[default]
+    Info,
+    Success,
+    Warning,
+}
+
+impl ToastSeverity {
+    #[must_use]
+    pub fn get_style(&self) -> Style {
+        match self {
+            ToastSeverity::Info => Style::new().gray(),
+            ToastSeverity::Success => Style::new().light_green(),
+            ToastSeverity::Warning => Style::new().light_red().bold(),
+        }
+    }
+}
+
+/// A single notification.
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub message: String,
+    pub severity: ToastSeverity,
+    expires_at: Instant,
+}
+
+impl Toast {
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.expires_at
+    }
+}
+
+/// A queue of toasts, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct Toasts {
+    toasts: VecDeque<Toast>,
+}
+
+impl Toasts {
+    /// How long a toast stays on screen.
+    pub const DURATION: Duration = Duration::from_secs(3);
+    /// The most toasts shown at once. Older toasts are dropped to make room for new ones.
+    pub const MAX_VISIBLE: usize = 4;
+    const MAX_WIDTH: u16 = 40;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a toast that stays up for `DURATION`.
+    pub fn push(&mut self, message: impl Into<String>, severity: ToastSeverity) {
+        self.push_for(message, severity, Self::DURATION);
+    }
+
+    /// Queues a toast that stays up for `duration`.
+    pub fn push_for(
+        &mut self,
+        message: impl Into<String>,
+        severity: ToastSeverity,
+        duration: Duration,
+    ) {
+        self.toasts.push_back(Toast {
+            message: message.into(),
+            severity,
+            expires_at: Instant::now() + duration,
+        });
+
+        while self.toasts.len() > Self::MAX_VISIBLE {
+            self.toasts.pop_front();
+        }
+    }
+
+    /// Removes toasts that have run out.
+    pub fn clear_expired(&mut self) {
+        self.toasts.retain(|toast| !toast.is_expired());
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.toasts.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    /// Renders the toasts stacked down from the top-right corner of `area`, oldest at the top.
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let mut y = area.y;
+
+        for toast in &self.toasts {
+            let width = (toast.message.chars().count() as u16 + 4)
+                .min(Self::MAX_WIDTH)
+                .min(area.width);
+            if y + 3 > area.bottom() {
+                break;
+            }
+
+            let toast_area = Rect::new(area.right() - width, y, width, 3);
+            let style = toast.severity.get_style();
+
+            let paragraph = Paragraph::new(Line::from(toast.message.clone()).style(style))
+                .block(Block::bordered().border_set(border::ROUNDED).style(style))
+                .centered();
+
+            frame.render_widget(Clear, toast_area);
+            frame.render_widget(paragraph, toast_area);
+
+            y += 3;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::toasts::{ToastSeverity, Toasts};
+    use crate::prelude::Duration;
+
+    #[test]
+    fn toasts_stack_and_expire() {
+        let mut toasts = Toasts::new();
+
+        for i in 0..=Toasts::MAX_VISIBLE {
+            toasts.push(format!("toast {i}"), ToastSeverity::Info);
+        }
+        assert_eq!(toasts.len(), Toasts::MAX_VISIBLE);
+
+        toasts.push_for("gone", ToastSeverity::Warning, Duration::ZERO);
+        toasts.clear_expired();
+        assert_eq!(toasts.len(), Toasts::MAX_VISIBLE - 1);
+    }
+}
@@ -0,0 +1,111 @@
+//! This module defines turrets: stationary attackers deployed by the TURRET METHOD.
+//! A turret sits where it was placed and fires small blasts at nearby enemies until it expires.
+
+use ratatui::style::Style;
+
+use crate::common::character::Renderable;
+use crate::common::coords::AreaWrapper::Square;
+use crate::common::coords::{Area, Position, SquareArea};
+use crate::common::enemies::enemy::{Enemy, select_target};
+use crate::common::entities::EntityCharacters;
+use crate::common::map::Layer;
+use crate::common::settings::setting::TargetPriority;
+use crate::common::stats::WeaponStats;
+use crate::common::utils::{duration_to_ticks, per_sec_to_tick_count_to_u64};
+use crate::common::weapons::{DamageArea, WeaponWrapper};
+use crate::prelude::Duration;
+
+pub mod turretwrangler;
+
+/// A turret placed on the map.
+pub struct DeployedTurret {
+    pub position: Position,
+    entity_char: EntityCharacters,
+
+    damage: i32,
+    /// How far away, in each direction, the turret can reach enemies.
+    range: i32,
+    fire_ticks: u64,
+    /// How many more ticks the turret lasts.
+    ticks_left: u64,
+
+    weapon_stats: WeaponStats,
+}
+
+impl DeployedTurret {
+    /// Creates a new turret at `position` that fires `fires_p_s` times a second for `lifetime`.
+    #[must_use]
+    pub fn new(
+        position: Position,
+        damage: i32,
+        range: i32,
+        fires_p_s: f64,
+        lifetime: Duration,
+        weapon_stats: WeaponStats,
+    ) -> Self {
+        Self {
+            position,
+            entity_char: EntityCharacters::Turret(Style::new().light_green().bold()),
+            damage,
+            range,
+            fire_ticks: per_sec_to_tick_count_to_u64(fires_p_s),
+            ticks_left: duration_to_ticks(lifetime),
+            weapon_stats,
+        }
+    }
+
+    /// Counts a tick off the turret's lifetime.
+    pub fn age(&mut self) {
+        self.ticks_left = self.ticks_left.saturating_sub(1);
+    }
+
+    /// Checks if the turret's lifetime has run out.
+    #[must_use]
+    pub fn is_expired(&self) -> bool {
+        self.ticks_left == 0
+    }
+
+    /// Fires at the nearest enemy in range, or the nearest summoner in range, if the turret is due to fire on this tick.
+    #[must_use]
+    pub fn fire(&self, tickcount: u64, enemies: &[Enemy], layer: &Layer) -> Option<DamageArea> {
+        if !tickcount.is_multiple_of(self.fire_ticks) {
+            return None;
+        }
+
+        let in_range: Vec<Enemy> = enemies
+            .iter()
+            .filter(|enemy| {
+                let (dx, dy) = enemy.get_pos().get_distance(&self.position);
+                dx.abs().max(dy.abs()) <= self.range
+            })
+            .cloned()
+            .collect();
+
+        let target = select_target(&in_range, &self.position, TargetPriority::Nearest)?;
+
+        let mut area = SquareArea::get_square_around_position(target.get_pos(), 1);
+        area.constrain(layer);
+
+        Some(DamageArea {
+            damage_amount: self.damage,
+            area: Square(area),
+            entity: EntityCharacters::AttackMist(Style::new().light_green()),
+            duration: Duration::from_secs_f64(0.05),
+            blink: false,
+            destroys_walls: false,
+            stages: vec![],
+            weapon_stats: Some(self.weapon_stats.clone()),
+            source: Some((&WeaponWrapper::Turret(None)).into()),
+        })
+    }
+}
+
+impl Renderable for DeployedTurret {
+    fn get_pos(&self) -> &Position {
+        &self.position
+    }
+
+    fn get_entity_char(&self) -> &EntityCharacters {
+        &self.entity_char
+    }
+}
@@ -0,0 +1,35 @@
+use crate::common::enemies::enemy::Enemy;
+use crate::common::map::Layer;
+use crate::common::turrets::DeployedTurret;
+use crate::common::weapons::DamageArea;
+
+/// Keeps track of every turret currently on the map.
+#[derive(Default)]
+pub struct TurretWrangler {
+    pub turrets: Vec<DeployedTurret>,
+}
+
+impl TurretWrangler {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn deploy(&mut self, turret: DeployedTurret) {
+        self.turrets.push(turret);
+    }
+
+    /// Ages every turret, removes expired ones and returns the damage areas of those that fire on
+    /// this tick.
+    pub fn on_tick(&mut self, tickcount: u64, enemies: &[Enemy], layer: &Layer) -> Vec<DamageArea> {
+        for turret in &mut self.turrets {
+            turret.age();
+        }
+        self.turrets.retain(|turret| !turret.is_expired());
+
+        self.turrets
+            .iter()
+            .filter_map(|turret| turret.fire(tickcount, enemies, layer))
+            .collect()
+    }
+}
@@ -0,0 +1,185 @@
+//! This module provides the guided first run: a short scripted tutorial which walks new players
+//! through moving, attacking, picking up gold and choosing a powerup, one prompt at a time.
+
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Wrap},
+};
+
+/// The steps of the tutorial, in the order they are played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TutorialStep {
+    #[default]
+    Move,
+    Attack,
+    Collect,
+    Powerup,
+    Done,
+}
+
+/// Something the run has to do for the tutorial to carry on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TutorialAction {
+    /// Spawn this many enemies for the player to fight.
+    SpawnEnemies(usize),
+    /// Open the powerup popup.
+    OpenPowerup,
+}
+
+/// What the player has done so far in the tutorial run.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TutorialProgress {
+    pub kills: u64,
+    /// Gold picked up since the tutorial started.
+    pub gold: u128,
+    pub popup_open: bool,
+}
+
+/// Tracks which step of the tutorial the player is on.
+#[derive(Debug, Clone, Default)]
+pub struct Tutorial {
+    pub step: TutorialStep,
+    moves: u32,
+}
+
+impl Tutorial {
+    /// The seed the tutorial map is always built from.
+    pub const SEED: u64 = 0xD15;
+    /// How long the tutorial run lasts, which is long enough to never run out.
+    pub const TIMER_SECS: u64 = 3600;
+
+    const MOVES_NEEDED: u32 = 4;
+    const ENEMY_COUNT: usize = 3;
+
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Counts a step taken by the player.
+    pub fn record_move(&mut self) {
+        self.moves += 1;
+    }
+
+    #[must_use]
+    pub fn is_done(&self) -> bool {
+        self.step == TutorialStep::Done
+    }
+
+    /// Moves on to the next step once the current one is complete, returning what the run needs
+    /// to do to set up the new step.
+    pub fn advance(&mut self, progress: &TutorialProgress) -> Option<TutorialAction> {
+        match self.step {
+            TutorialStep::Move if self.moves >= Self::MOVES_NEEDED => {
+                self.step = TutorialStep::Attack;
+                Some(TutorialAction::SpawnEnemies(Self::ENEMY_COUNT))
+            }
+            TutorialStep::Attack if progress.kills >= Self::ENEMY_COUNT as u64 => {
+                self.step = TutorialStep::Collect;
+                None
+            }
+            TutorialStep::Collect if progress.gold > 0 => {
+                self.step = TutorialStep::Powerup;
+                Some(TutorialAction::OpenPowerup)
+            }
+            TutorialStep::Powerup if !progress.popup_open => {
+                self.step = TutorialStep::Done;
+                None
+            }
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn get_prompt(&self) -> Vec<Line<'static>> {
+        let (title, body) = match self.step {
+            TutorialStep::Move => (
+                "MOVE",
+                "use <W A S D> or the arrow keys to walk around. you are the @.",
+            ),
+            TutorialStep::Attack => (
+                "ATTACK",
+                "enemies are coming. your METHODs attack on their own, just stay close and keep moving.",
+            ),
+            TutorialStep::Collect => (
+                "COLLECT",
+                "enemies drop gold when they die. walk over it to pick it up.",
+            ),
+            TutorialStep::Powerup => (
+                "POWERUP",
+                "levelling up lets you pick a new METHOD or charm. choose one to continue.",
+            ),
+            TutorialStep::Done => (
+                "READY",
+                "gold is spent on upgrades between runs. press <ENTER> to head to the upgrades.",
+            ),
+        };
+
+        vec![
+            Line::from(title.bold()),
+            Line::from(body),
+            Line::from(" <Esc> skip tutorial ".dark_gray()),
+        ]
+    }
+
+    /// Renders the prompt for the current step along the bottom of `area`.
+    pub fn render(&self, frame: &mut Frame, area: Rect) {
+        let [_, prompt_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(6)]).areas(area);
+
+        let prompt = Paragraph::new(self.get_prompt())
+            .block(
+                Block::bordered()
+                    .title(" tutorial ")
+                    .border_set(border::ROUNDED)
+                    .light_cyan(),
+            )
+            .centered()
+            .wrap(Wrap { trim: true });
+
+        frame.render_widget(Clear, prompt_area);
+        frame.render_widget(prompt, prompt_area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::tutorial::{Tutorial, TutorialAction, TutorialProgress, TutorialStep};
+
+    #[test]
+    fn steps_advance_in_order() {
+        let mut tutorial = Tutorial::new();
+        let mut progress = TutorialProgress::default();
+
+        assert_eq!(tutorial.advance(&progress), None);
+        for _ in 0..4 {
+            tutorial.record_move();
+        }
+        assert_eq!(
+            tutorial.advance(&progress),
+            Some(TutorialAction::SpawnEnemies(3))
+        );
+
+        progress.kills = 3;
+        assert_eq!(tutorial.advance(&progress), None);
+        assert_eq!(tutorial.step, TutorialStep::Collect);
+
+        progress.gold = 5;
+        assert_eq!(
+            tutorial.advance(&progress),
+            Some(TutorialAction::OpenPowerup)
+        );
+
+        progress.popup_open = true;
+        tutorial.advance(&progress);
+        assert_eq!(tutorial.step, TutorialStep::Powerup);
+
+        progress.popup_open = false;
+        tutorial.advance(&progress);
+        assert!(tutorial.is_done());
+    }
+}
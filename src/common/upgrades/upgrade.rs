@@ -1,15 +1,24 @@
 //! This module defines the data structures for player state, upgrades, and stats.
 //! It includes logic for applying upgrades and calculating player stats.
 
+use std::collections::{BTreeMap, HashSet};
 use std::ops::Sub;
 
-use crate::prelude::Duration;
-
+use serde::de::Error as _;
 use serde::{Deserialize, Serialize};
 
 use crate::common::{
+    challenge::{Challenge, RunRecord},
+    classes::class::CharacterClass,
+    collection::achievement::Achievement,
+    coords::Topology,
     debuffs::{Debuff, DebuffTypes},
-    stats::{DebuffStats, GameStats, Inventory, PlayerStats, Proc, Stats, WeaponStats},
+    loadouts::loadout::Loadout,
+    mastery::tier::MasteryTier,
+    mutators::mutator::Mutator,
+    settings::setting::Settings,
+    stats::{DebuffStats, GameStats, Inventory, PlayerStats, Proc, ProcId, Stats, WeaponStats},
+    statsheet::{StatModifier, StatSheet, StatSource},
 };
 
 /// Represents the complete state of the player, including upgrades, inventory, and stats.
@@ -18,6 +27,35 @@ pub struct PlayerState {
     pub upgrades: CurrentUpgrades,
     pub inventory: Inventory,
     pub stats: Stats,
+    #[serde(default)]
+    pub class: CharacterClass,
+    /// The METHOD and CHARM picked to start runs with.
+    #[serde(default)]
+    pub loadout: Loadout,
+    #[serde(default)]
+    pub settings: Settings,
+    /// The run mutators switched on for upcoming runs.
+    #[serde(default)]
+    pub mutators: Vec<Mutator>,
+    /// Whether the first-run tutorial has been finished or skipped.
+    #[serde(default)]
+    pub tutorial_done: bool,
+    /// The achievements earned so far, in the order they were earned.
+    #[serde(default)]
+    pub achievements: Vec<Achievement>,
+    /// Lifetime kills with each METHOD, keyed by its name in upper case.
+    #[serde(default)]
+    pub weapon_kills: BTreeMap<String, u64>,
+    /// The most recent finished runs, oldest first.
+    #[serde(default)]
+    pub run_history: Vec<RunRecord>,
+    /// The challenge being played, which overrides the class, loadout, mutators and hype level
+    /// until it's left.
+    #[serde(skip)]
+    pub challenge: Option<Challenge>,
+    /// The upgrade tree's stats with every modifier on them, which `stats` is worked out from.
+    #[serde(skip)]
+    pub stat_sheet: StatSheet,
 }
 
 /// Represents the difference between two `PlayerState` instances.
@@ -41,10 +79,6 @@ impl Sub for PlayerState {
 
 impl PlayerState {
     /// Refreshes the player's stats based on their current upgrades.
-    ///
-    /// # Panics
-    ///
-    /// Will panic if save state is impossible.
     #[allow(clippy::too_many_lines)]
     pub fn refresh(&mut self) {
         let mut game_stats = GameStats::default();
@@ -121,13 +155,29 @@ impl PlayerState {
             weapon_stats.elemental_honage += 0.25 * f64::from(self.amount_owned("27"));
         }
 
+        //upgrade 28 shield
+        //upgrade 281 shield/flat_up
+        if self.upgrade_owned("281") {
+            player_stats.shield += 2 * self.amount_owned("281") as i32;
+        }
+
+        //upgrade 282 shield/regen_delay
+        if self.upgrade_owned("282") {
+            player_stats.shield_regen_delay -= 0.5 * f64::from(self.amount_owned("282"));
+        }
+
+        //upgrade 29 iframes
+        if self.upgrade_owned("29") {
+            player_stats.iframe_secs += 0.15 * f64::from(self.amount_owned("29"));
+        }
+
         //upgrade 31 MARK
         //upgrade 311 mark chance
         if self.upgrade_owned("311") {
-            weapon_stats.procs.insert(
-                "mark".into(),
+            weapon_stats.add_proc(
+                ProcId::Mark,
                 Proc {
-                    chance: 1 * self.amount_owned("311"),
+                    chance: self.amount_owned("311"),
 
                     debuff: Debuff {
                         stats: DebuffStats {
@@ -146,14 +196,10 @@ impl PlayerState {
         }
 
         //upgrade 312 mark size
-        if self.upgrade_owned("312") {
-            weapon_stats
-                .procs
-                .get_mut("mark")
-                .unwrap()
-                .debuff
-                .stats
-                .size = Some(1 + self.amount_owned("312") as i32);
+        if self.upgrade_owned("312")
+            && let Some(mark) = weapon_stats.procs.get_mut(&ProcId::Mark)
+        {
+            mark.debuff.stats.size = Some(1 + self.amount_owned("312") as i32);
         }
 
         //upgrade 32 shove
@@ -167,11 +213,19 @@ impl PlayerState {
             player_stats.shove_damage += self.amount_owned("322");
         }
 
+        //upgrade 33 lifesteal
+        if self.upgrade_owned("33") {
+            player_stats.lifesteal += 0.02 * f64::from(self.amount_owned("33"));
+        }
+
+        // upgrade 34 hazard immunity
+        if self.upgrade_owned("34") {
+            player_stats.hazard_resist = 1.;
+        }
+
         // upgrade 4 GREED
         // upgrade 41 hype
-        if self.upgrade_owned("41") {
-            game_stats.doom_offset += 1. * f64::from(self.amount_owned("41"));
-        }
+        game_stats.doom_offset += 1. * f64::from(self.get_hype());
 
         // upgrade 42 growth
         if self.upgrade_owned("42") {
@@ -188,6 +242,10 @@ impl PlayerState {
 
             game_stats.enemy_spawn_mult += 0.3 * f64::from(amount_owned);
         }
+        // upgrade 44 autopilot
+        if self.upgrade_owned("44") {
+            game_stats.autopilot = true;
+        }
 
         if self.upgrade_owned("51") {
             let amount_owned = self.amount_owned("51");
@@ -232,15 +290,48 @@ impl PlayerState {
             self.inventory.add_gold(100_000);
         }
 
-        //cleanups
-        player_stats.health =
-            (f64::from(player_stats.base_health) * player_stats.health_mult).ceil() as i32;
-
-        self.stats = Stats {
+        let mut stat_sheet = StatSheet::new(Stats {
             game_stats,
             player_stats,
             weapon_stats,
+        });
+
+        stat_sheet.add_modifiers(self.class.get_modifiers());
+        for mutator in &self.mutators {
+            stat_sheet.add_modifiers(mutator.get_modifiers());
         }
+
+        self.stat_sheet = stat_sheet;
+        self.apply_stat_sheet();
+    }
+
+    /// Replaces the modifiers that only last for a run, from CHARMs and buffs, and works the
+    /// stats out again.
+    pub fn set_run_modifiers(&mut self, modifiers: Vec<StatModifier>) {
+        self.stat_sheet.remove_modifiers(StatSource::is_run_source);
+        self.stat_sheet.add_modifiers(modifiers);
+        self.apply_stat_sheet();
+    }
+
+    /// Works out `stats` from the stat sheet.
+    fn apply_stat_sheet(&mut self) {
+        let mut stats = self.stat_sheet.get_stats();
+
+        //cleanups
+        stats.player_stats.health = (f64::from(stats.player_stats.base_health)
+            * stats.player_stats.health_mult)
+            .ceil() as i32;
+
+        self.stats = stats;
+    }
+
+    /// Returns the stats this player would have after buying one more of the given upgrade.
+    #[must_use]
+    pub fn preview_upgrade(&self, id: &str) -> Stats {
+        let mut preview = self.clone();
+        preview.upgrades.set(id, preview.amount_owned(id) + 1);
+        preview.refresh();
+        preview.stats
     }
 
     /// Returns the number of times an upgrade has been purchased.
@@ -249,11 +340,109 @@ impl PlayerState {
         self.upgrades.get(id)
     }
 
+    /// Returns the hype level runs start at, which a challenge being played sets.
+    #[must_use]
+    pub fn get_hype(&self) -> u32 {
+        self.challenge
+            .as_ref()
+            .map_or_else(|| self.amount_owned("41"), |challenge| challenge.hype)
+    }
+
+    /// Sets up upcoming runs as `challenge`, until [`PlayerState::leave_challenge`].
+    pub fn accept_challenge(&mut self, challenge: Challenge) {
+        self.class = challenge.class;
+        self.loadout.clone_from(&challenge.loadout);
+        self.mutators.clone_from(&challenge.mutators);
+        self.challenge = Some(challenge);
+        self.refresh();
+    }
+
+    /// Stops playing the current challenge, if any. Its class, loadout and mutators are kept.
+    pub fn leave_challenge(&mut self) {
+        if self.challenge.take().is_some() {
+            self.refresh();
+        }
+    }
+
+    /// Keeps a finished run in the history, dropping the oldest past [`RunRecord::HISTORY_LEN`].
+    pub fn record_run(&mut self, record: RunRecord) {
+        self.run_history.push(record);
+        let overflow = self
+            .run_history
+            .len()
+            .saturating_sub(RunRecord::HISTORY_LEN);
+        self.run_history.drain(..overflow);
+    }
+
     /// Checks if the player owns at least one of a specific upgrade.
     #[must_use]
     pub fn upgrade_owned(&self, id: &str) -> bool {
         self.upgrades.get(id) > 0
     }
+
+    /// Keeps an achievement, returning whether it's newly earned.
+    pub fn earn(&mut self, achievement: Achievement) -> bool {
+        if self.achievements.contains(&achievement) {
+            return false;
+        }
+
+        self.achievements.push(achievement);
+        true
+    }
+
+    /// Returns how the map's edges behave in runs, from the chosen mutators.
+    #[must_use]
+    pub fn get_topology(&self) -> Topology {
+        if self.mutators.contains(&Mutator::WrapAround) {
+            Topology::Wrapping
+        } else {
+            Topology::Bounded
+        }
+    }
+
+    /// Returns the lifetime kills with the METHOD named `weapon`.
+    #[must_use]
+    pub fn get_weapon_kills(&self, weapon: &str) -> u64 {
+        self.weapon_kills
+            .get(&weapon.to_uppercase())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Returns how far the METHOD named `weapon` has been mastered.
+    #[must_use]
+    pub fn get_mastery(&self, weapon: &str) -> MasteryTier {
+        MasteryTier::from_kills(self.get_weapon_kills(weapon))
+    }
+
+    /// Adds kills to the METHOD named `weapon`, returning the tier it reached if that earned a new
+    /// one.
+    pub fn add_weapon_kills(&mut self, weapon: &str, kills: u64) -> Option<MasteryTier> {
+        let before = self.get_mastery(weapon);
+        *self.weapon_kills.entry(weapon.to_uppercase()).or_default() += kills;
+
+        let after = self.get_mastery(weapon);
+        (after > before).then_some(after)
+    }
+
+    /// Brings a loaded save back within what the game allows, so a damaged or edited save can't
+    /// break a run. Upgrades owned past their limit are lowered to it, and the stats are worked
+    /// out again rather than trusted.
+    pub fn sanitize(&mut self, upgrade_tree: &UpgradeTree) {
+        for upgrade in &mut self.upgrades {
+            if let Some(node) = find_node(upgrade_tree, &upgrade.id) {
+                upgrade.amount = upgrade.amount.min(node.limit);
+            }
+        }
+
+        self.refresh();
+    }
+
+    /// Whether this is a brand new player who should be shown the tutorial.
+    #[must_use]
+    pub fn is_fresh(&self) -> bool {
+        !self.tutorial_done && self.upgrades.is_empty() && self.inventory.gold == 0
+    }
 }
 
 impl Default for PlayerState {
@@ -262,6 +451,16 @@ impl Default for PlayerState {
             inventory: Inventory::default(),
             stats: Stats::default(),
             upgrades: Vec::new(),
+            class: CharacterClass::default(),
+            loadout: Loadout::default(),
+            settings: Settings::default(),
+            mutators: Vec::new(),
+            tutorial_done: false,
+            achievements: Vec::new(),
+            weapon_kills: BTreeMap::new(),
+            run_history: Vec::new(),
+            challenge: None,
+            stat_sheet: StatSheet::default(),
         };
 
         out.refresh();
@@ -334,11 +533,17 @@ impl UpgradeNode {
         }
     }
 
+    /// Returns the lowercase title without its path prefix.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the title changes between checking for and splitting on the separator.
+    #[must_use]
     pub fn get_raw_title(&self) -> String {
         let mut title = self.title.clone().to_lowercase();
-        if self.title.contains("\\") {
+        if self.title.contains('\\') {
             title = title
-                .rsplit_once("\\")
+                .rsplit_once('\\')
                 .expect("Something changed somehow")
                 .1
                 .to_string();
@@ -365,6 +570,36 @@ impl UpgradeNode {
     }
 }
 
+impl UpgradeNode {
+    /// Calculates the total cost of buying `count` more of this upgrade, with the cost rising
+    /// after each purchase.
+    #[must_use]
+    pub fn bulk_cost(&self, amount_owned: u32, count: u32) -> u128 {
+        (amount_owned..amount_owned + count)
+            .map(|owned| u128::from(self.next_cost(owned)))
+            .sum()
+    }
+
+    /// Returns how many more of this upgrade `gold` can buy, up to its limit, and what they cost
+    /// in total.
+    #[must_use]
+    pub fn max_affordable(&self, amount_owned: u32, gold: u128) -> (u32, u128) {
+        let mut count = 0;
+        let mut total = 0;
+
+        for owned in amount_owned..self.limit {
+            let cost = u128::from(self.next_cost(owned));
+            if total + cost > gold {
+                break;
+            }
+            total += cost;
+            count += 1;
+        }
+
+        (count, total)
+    }
+}
+
 /// A type alias for a vector of `UpgradeNode`s, representing the entire upgrade tree.
 pub type UpgradeTree = Vec<UpgradeNode>;
 
@@ -374,7 +609,7 @@ pub type UpgradeTree = Vec<UpgradeNode>;
 ///
 /// Will error if upgrades.json is invalid.
 pub fn get_upgrade_tree() -> Result<Vec<UpgradeNode>, serde_json::Error> {
-    let upgrade_tree: UpgradeTree = serde_json::from_str(include_str!("upgrades.json"))?;
+    let upgrade_tree = parse_upgrade_tree(include_str!("upgrades.json"))?;
 
     #[cfg(not(debug_assertions))]
     let upgrade_tree = upgrade_tree
@@ -385,13 +620,130 @@ pub fn get_upgrade_tree() -> Result<Vec<UpgradeNode>, serde_json::Error> {
     Ok(upgrade_tree)
 }
 
+/// Parses an upgrade tree from JSON and checks it makes sense.
+///
+/// # Errors
+///
+/// Errors if the JSON doesn't describe an upgrade tree, an id is empty or used twice, or a cost
+/// scale isn't a positive number.
+pub fn parse_upgrade_tree(json: &str) -> Result<UpgradeTree, serde_json::Error> {
+    let upgrade_tree: UpgradeTree = serde_json::from_str(json)?;
+
+    let mut ids = HashSet::new();
+    check_nodes(&upgrade_tree, &mut ids)?;
+
+    Ok(upgrade_tree)
+}
+
+fn check_nodes<'a>(
+    nodes: &'a [UpgradeNode],
+    ids: &mut HashSet<&'a str>,
+) -> Result<(), serde_json::Error> {
+    for node in nodes {
+        if node.id.is_empty() {
+            return Err(serde_json::Error::custom(format!(
+                "upgrade `{}` has no id",
+                node.title
+            )));
+        }
+        if !ids.insert(&node.id) {
+            return Err(serde_json::Error::custom(format!(
+                "upgrade id `{}` is used more than once",
+                node.id
+            )));
+        }
+        if node
+            .costscale_override
+            .is_some_and(|scale| !scale.is_finite() || scale <= 0.)
+        {
+            return Err(serde_json::Error::custom(format!(
+                "upgrade `{}` has an invalid cost scale",
+                node.id
+            )));
+        }
+
+        if let Some(children) = &node.children {
+            check_nodes(children, ids)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Finds the upgrade with the given id anywhere in the tree.
+#[must_use]
+pub fn find_node<'a>(nodes: &'a [UpgradeNode], id: &str) -> Option<&'a UpgradeNode> {
+    nodes.iter().find_map(|node| {
+        if node.id == id {
+            Some(node)
+        } else {
+            find_node(node.children.as_deref().unwrap_or_default(), id)
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn preview_upgrade_diffs_stats() {
+        let player_state = PlayerState::default();
+
+        let changes = player_state.stats.diff(&player_state.preview_upgrade("29"));
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].name, "iframe_secs");
+        assert_eq!(changes[0].before, "0.5");
+        assert_eq!(changes[0].after, "0.65");
+    }
+
+    #[test]
+    fn bulk_cost_escalates() {
+        let node = UpgradeNode {
+            cost: Some(100),
+            limit: 50,
+            ..UpgradeNode::default()
+        };
+
+        assert_eq!(node.bulk_cost(0, 3), 100 + 120 + 144);
+        assert_eq!(node.max_affordable(0, 300), (2, 220));
+        assert_eq!(
+            node.max_affordable(49, 1_000_000),
+            (1, u128::from(node.next_cost(49)))
+        );
+    }
+
     #[test]
     fn parse_correctly() {
         let upgrade_tree = get_upgrade_tree().unwrap();
         assert!(upgrade_tree[0].title.len() > 1);
     }
+
+    #[test]
+    fn invalid_trees_are_rejected() {
+        let duplicate = r#"[
+            {"title": "a", "description": "", "id": "1", "limit": 1, "requires": []},
+            {"title": "b", "description": "", "id": "1", "limit": 1, "requires": []}
+        ]"#;
+        assert!(parse_upgrade_tree(duplicate).is_err());
+
+        let bad_scale = r#"[
+            {"title": "a", "description": "", "id": "1", "limit": 1, "requires": [],
+             "costscale_override": -1.0}
+        ]"#;
+        assert!(parse_upgrade_tree(bad_scale).is_err());
+    }
+
+    #[test]
+    fn sanitize_clamps_upgrades() {
+        let mut player_state = PlayerState::default();
+        player_state.upgrades.set("42", u32::MAX);
+        player_state.upgrades.set("312", 2);
+
+        player_state.sanitize(&get_upgrade_tree().unwrap());
+
+        assert_eq!(player_state.amount_owned("42"), 3);
+        assert!(player_state.stats.game_stats.width < 1000);
+    }
 }
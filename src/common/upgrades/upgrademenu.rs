@@ -1,11 +1,13 @@
 //! This module provides the UI and logic for the upgrade menu.
 //! It allows the player to navigate and purchase upgrades for their character.
 
+use crate::common::apperror::AppError;
+use crate::common::keybinds::{Action, KeyContext};
+use crate::common::numbers::format_number;
 use crate::common::upgrades::upgrade::{
     CurrentUpgradesTrait, PlayerState, UpgradeNode, UpgradeTree, get_upgrade_tree,
 };
-use crate::common::{Goto, PlayerStateRef, Viewable};
-use crate::prelude::{KeyCode, KeyEvent};
+use crate::common::{Goto, PlayerStateRef, View};
 use ratatui::text::{Span, Text};
 use ratatui::widgets::BorderType;
 use ratatui::{
@@ -25,6 +27,13 @@ pub struct MenuCrumb {
 
 pub type MenuHistory = Vec<MenuCrumb>;
 
+/// A bulk purchase of a repeatable upgrade, waiting for confirmation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkBuy {
+    Ten,
+    Max,
+}
+
 /// A struct that manages the state and rendering of the upgrades menu.
 pub struct UpgradesMenu {
     pub player_state: PlayerStateRef,
@@ -33,17 +42,18 @@ pub struct UpgradesMenu {
     pub goto: Goto,
     pub current_layer: UpgradeTree,
     history: MenuHistory,
+    pending_bulk: Option<BulkBuy>,
 }
 
 impl UpgradesMenu {
     /// Creates a new `UpgradesMenu` instance.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// Will panic if the upgrade tree cannot be retrieved.
-    #[must_use]
-    pub fn new(player_state: PlayerStateRef) -> Self {
-        let upgrade_tree = get_upgrade_tree().unwrap();
+    /// Errors if the upgrade tree cannot be read.
+    pub fn new(player_state: PlayerStateRef) -> Result<Self, AppError> {
+        let upgrade_tree =
+            get_upgrade_tree().map_err(|err| AppError::UpgradeTree(err.to_string()))?;
         let mut menu = Self {
             player_state,
             root_upgrade_tree: upgrade_tree.clone(),
@@ -51,19 +61,29 @@ impl UpgradesMenu {
             upgrade_selection: ListState::default(),
             goto: Goto::Upgrades,
             history: Vec::new(),
+            pending_bulk: None,
         };
 
         menu.upgrade_selection.select_first();
 
-        menu
+        Ok(menu)
     }
 
-    /// Handles key events for the upgrade menu.
-    pub fn key_event(&mut self, key_event: &KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('w') | KeyCode::Up => self.prev_selection(),
-            KeyCode::Char('s') | KeyCode::Down => self.next_selection(),
-            KeyCode::Enter => {
+    /// Handles actions for the upgrade menu.
+    pub fn on_action(&mut self, action: Action) {
+        if let Some(bulk) = self.pending_bulk.take()
+            && action == Action::Confirm
+        {
+            self.buy_bulk(bulk).unwrap_or(());
+            return;
+        }
+
+        match action {
+            Action::Up => self.prev_selection(),
+            Action::Down => self.next_selection(),
+            Action::BuyTen => self.start_bulk(BulkBuy::Ten),
+            Action::BuyMax => self.start_bulk(BulkBuy::Max),
+            Action::Confirm => {
                 if let Some(current_node) = self.get_selected_node() {
                     if current_node.has_children() {
                         self.navigate_into_upgrade();
@@ -73,9 +93,15 @@ impl UpgradesMenu {
                     }
                 }
             }
-            KeyCode::Char(' ') => self.goto = Goto::Game,
-
-            KeyCode::Esc => {
+            Action::Start => self.goto = Goto::ClassSelect,
+            Action::Settings => self.goto = Goto::Settings,
+            Action::Collection => self.goto = Goto::Collection,
+            #[cfg(feature = "leaderboard")]
+            Action::Leaderboard => self.goto = Goto::Leaderboard,
+            #[cfg(feature = "netplay")]
+            Action::Netplay => self.goto = Goto::Netplay,
+
+            Action::Back => {
                 if !self.history.is_empty() {
                     self.go_back();
                     self.upgrade_selection.select_first();
@@ -112,6 +138,13 @@ impl UpgradesMenu {
                 player_state_mut
                     .upgrades
                     .set(&current_node.id, upgrade_count);
+
+                tracing::debug!(
+                    id = current_node.id,
+                    amount = upgrade_count,
+                    cost = next_cost,
+                    "bought upgrade"
+                );
                 Ok(())
             } else {
                 Err("Upgrade is not purchasable".to_string())
@@ -121,6 +154,87 @@ impl UpgradesMenu {
         }
     }
 
+    /// Asks for confirmation of a bulk purchase of the selected upgrade, if it is repeatable.
+    pub fn start_bulk(&mut self, bulk: BulkBuy) {
+        if let Some(node) = self.get_selected_node()
+            && node.cost.is_some()
+            && node.limit > 1
+        {
+            self.pending_bulk = Some(bulk);
+        }
+    }
+
+    /// Returns how many of the selected upgrade a bulk purchase would buy, and the total cost.
+    #[must_use]
+    pub fn get_bulk_purchase(&self, node: &UpgradeNode, bulk: BulkBuy) -> (u32, u128) {
+        let player_state = self.player_state.borrow();
+        let owned = player_state.amount_owned(&node.id);
+
+        match bulk {
+            BulkBuy::Ten => {
+                let count = node.limit.saturating_sub(owned).min(10);
+                (count, node.bulk_cost(owned, count))
+            }
+            BulkBuy::Max => node.max_affordable(owned, player_state.inventory.gold),
+        }
+    }
+
+    /// Builds the line asking the user to confirm a pending bulk purchase.
+    fn get_bulk_line(&self, node: &UpgradeNode, bulk: BulkBuy, gold: u128) -> Line<'static> {
+        let (count, cost) = self.get_bulk_purchase(node, bulk);
+        let number_format = self.player_state.borrow().settings.number_format;
+
+        Line::from(vec![
+            format!("buy {count} for ${}", format_number(cost, number_format)).bold(),
+            if cost <= gold {
+                " <ENTER> confirm".dark_gray()
+            } else {
+                " not enough gold".red()
+            },
+        ])
+    }
+
+    /// Builds the line showing what the upgrade costs next, or why it can't be bought.
+    fn get_cost_line(node: &UpgradeNode, player_state: &PlayerState) -> Line<'static> {
+        let owned = player_state.amount_owned(&node.id);
+
+        if node.limit > 0 && owned >= node.limit {
+            Line::from("owned")
+        } else if node.cost.is_some() {
+            Line::from(format!(
+                "${}",
+                format_number(node.next_cost(owned), player_state.settings.number_format)
+            ))
+        } else if node.has_children() {
+            Line::from("> enter folder")
+        } else {
+            Line::from("")
+        }
+    }
+
+    /// Buys the selected upgrade as many times as the bulk purchase covers.
+    ///
+    /// # Errors
+    ///
+    /// Will return a `String` error if there is nothing to buy or the user can't afford all of it.
+    pub fn buy_bulk(&mut self, bulk: BulkBuy) -> Result<(), String> {
+        let node = self
+            .get_selected_node()
+            .ok_or_else(|| "No upgrade selected".to_string())?;
+        let (count, cost) = self.get_bulk_purchase(&node, bulk);
+
+        if count == 0 {
+            return Err("Nothing to buy".to_string());
+        } else if cost > self.player_state.borrow().inventory.gold {
+            return Err("Not enough money".to_string());
+        }
+
+        for _ in 0..count {
+            self.buy_upgrade()?;
+        }
+        Ok(())
+    }
+
     /// Selects the previous item in the upgrade list.
     pub fn prev_selection(&mut self) {
         self.upgrade_selection.select_previous();
@@ -248,9 +362,25 @@ impl UpgradesMenu {
         let [left, right] = horizontal.areas(inner);
 
         let title = Line::from(" dispair.upgrade ".bold());
-        let gold_amount = Line::from(vec![" Gold: ".into(), gold.to_string().into()]);
+        let number_format = player_state.settings.number_format;
+        let gold_amount = Line::from(vec![
+            " Gold: ".into(),
+            format_number(gold, number_format).into(),
+        ]);
+        #[cfg(feature = "leaderboard")]
+        let leaderboard_hint = "<L> Leaderboard | ";
+        #[cfg(not(feature = "leaderboard"))]
+        let leaderboard_hint = "";
+        #[cfg(feature = "netplay")]
+        let netplay_hint = "<N> Online Co-op | ";
+        #[cfg(not(feature = "netplay"))]
+        let netplay_hint = "";
+
         let instructions = Line::from(vec![
-            " <W|UP> Up | <S|DOWN> Down | <SPACE> Start Game | <Esc> Back ".into(),
+            format!(
+                " <W|UP> Up | <S|DOWN> Down | <T> Buy 10 | <M> Buy Max | <SPACE> Start Game | <O> Settings | <C> Collection | {leaderboard_hint}{netplay_hint}<Esc> Back "
+            )
+            .into(),
         ]);
         window = window
             .title(title.left_aligned())
@@ -274,19 +404,7 @@ impl UpgradesMenu {
         let upgrade_block = Block::bordered().border_set(border::ROUNDED);
         let upgrade_title = Line::from(current_upgrade.clone().get_raw_title());
         let upgrade_desc = Text::from(current_upgrade.clone().description);
-        let mut upgrade_cost = Line::from("");
-        if current_upgrade.limit > 0
-            && player_state.amount_owned(&current_upgrade.id) >= current_upgrade.limit
-        {
-            upgrade_cost = Line::from("owned");
-        } else if current_upgrade.cost.is_some() {
-            upgrade_cost = Line::from(format!(
-                "${}",
-                current_upgrade.next_cost(player_state.amount_owned(&current_upgrade.id))
-            ));
-        } else if current_upgrade.has_children() {
-            upgrade_cost = Line::from("> enter folder");
-        }
+        let mut upgrade_cost = Self::get_cost_line(&current_upgrade, &player_state);
 
         let mut upgrade_amount = Line::from("");
         if current_upgrade.limit > 1 {
@@ -297,6 +415,31 @@ impl UpgradesMenu {
             ));
         }
 
+        if let Some(bulk) = self.pending_bulk {
+            upgrade_cost = self.get_bulk_line(&current_upgrade, bulk, gold);
+        }
+
+        let purchasable = current_upgrade.cost.is_some()
+            && player_state.amount_owned(&current_upgrade.id) < current_upgrade.limit;
+
+        let mut stat_lines: Vec<Line> = Vec::new();
+        if purchasable {
+            let preview = player_state.preview_upgrade(&current_upgrade.id);
+            stat_lines = player_state
+                .stats
+                .diff(&preview)
+                .into_iter()
+                .map(|change| {
+                    Line::from(vec![
+                        format!("{} ", change.name).dark_gray(),
+                        change.before.into(),
+                        " → ".dark_gray(),
+                        change.after.bold(),
+                    ])
+                })
+                .collect();
+        }
+
         let mut upgrade_lines = Vec::new();
         upgrade_lines.push(upgrade_title);
         upgrade_lines.push("".into());
@@ -305,6 +448,10 @@ impl UpgradesMenu {
         upgrade_lines.push(upgrade_cost);
         upgrade_lines.push("".into());
         upgrade_lines.push(upgrade_amount);
+        if !stat_lines.is_empty() {
+            upgrade_lines.push("".into());
+            upgrade_lines.append(&mut stat_lines);
+        }
 
         let upgrade_paragraph = Paragraph::new(upgrade_lines)
             .block(upgrade_block.title_bottom(gold_amount.centered()))
@@ -321,18 +468,48 @@ impl UpgradesMenu {
     }
 }
 
-impl Viewable for UpgradesMenu {
-    fn tick(&mut self) {}
+impl View for UpgradesMenu {
+    fn on_tick(&mut self) {}
 
-    fn get_goto(&self) -> &Goto {
+    fn transition(&self) -> &Goto {
         &self.goto
     }
 
     fn render(&mut self, frame: &mut Frame) {
-        self.render_upgrades(frame)
+        self.render_upgrades(frame);
+    }
+
+    fn handle_action(&mut self, action: Action) {
+        self.on_action(action);
+    }
+
+    fn get_key_context(&self) -> KeyContext {
+        KeyContext::Upgrades
     }
+}
+
+// the leaderboard and netplay features add key hints to the menu
+#[cfg(all(test, not(feature = "leaderboard"), not(feature = "netplay")))]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use ratatui::{Terminal, backend::TestBackend};
+
+    use crate::common::View;
+    use crate::common::upgrades::upgrade::PlayerState;
+    use crate::common::upgrades::upgrademenu::UpgradesMenu;
+
+    #[test]
+    fn upgrades_menu_snapshot() {
+        let mut player_state = PlayerState::default();
+        player_state.inventory.gold = 1_240_000;
+
+        let mut menu = UpgradesMenu::new(Rc::new(RefCell::new(player_state))).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+
+        terminal.draw(|frame| menu.render(frame)).unwrap();
 
-    fn handle_key_event(&mut self, key_event: &KeyEvent) {
-        self.key_event(key_event);
+        insta::assert_snapshot!(terminal.backend());
     }
 }
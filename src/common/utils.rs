@@ -1,7 +1,9 @@
 use crate::common::TICK_RATE;
 use crate::common::character::{Character, Movable, Renderable};
-use crate::common::coords::{Direction, Position};
-use crate::common::map::Layer;
+use crate::common::coords::{Area, Direction, Position, SquareArea, Topology};
+use crate::common::map::{Layer, Map};
+use crate::common::runrng;
+use crate::prelude::Duration;
 use rand::Rng;
 use ratatui::layout::{Constraint, Layout, Rect};
 
@@ -30,30 +32,51 @@ pub fn center(area: Rect, width: u16, height: u16) -> Rect {
     center_horizontal(centered_area, width)
 }
 
+/// Picks a random position on the border of `area` grown by `margin` cells, kept inside the
+/// layer.
 #[must_use]
-pub fn get_rand_position_on_edge(layer: &Layer) -> Position {
-    let mut rng = rand::rng();
-
-    let which_edge = rng.random_range(0..4);
-
-    match which_edge {
-        0 => Position::new(0, rng.random_range(0..layer.len() as i32)),
-        1 => Position::new(
-            layer[0].len() as i32 - 1,
-            rng.random_range(0..layer.len() as i32),
-        ),
-        2 => Position::new(rng.random_range(0..layer[0].len() as i32), 0),
-        3 => Position::new(
-            rng.random_range(0..layer[0].len() as i32),
-            layer.len() as i32 - 1,
-        ),
-        _ => Position::new(0, 0),
+pub fn get_rand_position_around_area(area: &SquareArea, margin: i32, layer: &Layer) -> Position {
+    let mut rng = runrng::rng();
+
+    let (x1, y1, x2, y2) = area.grow(margin).get_bounds();
+
+    let mut position = match rng.random_range(0..4) {
+        0 => Position(x1, rng.random_range(y1..=y2)),
+        1 => Position(x2, rng.random_range(y1..=y2)),
+        2 => Position(rng.random_range(x1..=x2), y1),
+        _ => Position(rng.random_range(x1..=x2), y2),
+    };
+
+    position.constrain(layer);
+    position
+}
+
+/// Returns up to `count` positions spaced evenly around the edge of the layer, clockwise from
+/// the top left corner.
+#[must_use]
+pub fn get_positions_on_edge(layer: &Layer, count: usize) -> Vec<Position> {
+    let width = layer[0].len() as i32;
+    let height = layer.len() as i32;
+
+    let mut edge: Vec<Position> = (0..width).map(|x| Position::new(x, 0)).collect();
+    edge.extend((1..height).map(|y| Position::new(width - 1, y)));
+    edge.extend((0..width - 1).rev().map(|x| Position::new(x, height - 1)));
+    edge.extend((1..height - 1).rev().map(|y| Position::new(0, y)));
+
+    let count = count.min(edge.len());
+    if count == 0 {
+        return vec![];
     }
+
+    let step = edge.len() as f64 / count as f64;
+    (0..count)
+        .map(|i| edge[(i as f64 * step).floor() as usize].clone())
+        .collect()
 }
 
 #[must_use]
 pub fn get_rand_position_on_layer(layer: &Layer) -> Position {
-    let mut rng = rand::rng();
+    let mut rng = runrng::rng();
 
     let x = rng.random_range(0..layer[0].len() as i32);
     let y = rng.random_range(0..layer.len() as i32);
@@ -69,35 +92,30 @@ pub fn is_next_to_character(char_position: &Position, position: &Position) -> bo
         || (y == char_y.saturating_add(1) || y == char_y.saturating_sub(1)) && x == char_x
 }
 
+/// Checks whether `position` is inside the `playable` area and not taken by the character.
 #[must_use]
 pub fn can_stand(
-    width: i32,
-    height: i32,
+    playable: &SquareArea,
     character: Option<&Character>,
     position: &Position,
 ) -> bool {
     let (x, y) = position.get();
+    let (x1, y1, x2, y2) = playable.get_bounds();
 
-    let char_collision = character.map(|c| c.get_pos() == position).unwrap_or(false);
+    let char_collision = character.is_some_and(|c| c.get_pos() == position);
 
-    if x < 0 || x >= width || y < 0 || y >= height || char_collision {
+    if x < x1 || x > x2 || y < y1 || y > y2 || char_collision {
         return false;
     }
     true
 }
 
-pub fn move_entity(layer: &mut Layer, entity: &mut impl Movable, direction: Direction) {
-    let (x, y) = entity.get_pos().get();
-    let mut new_pos = match direction {
-        Direction::LEFT => Position::new(x - 1, y),
-        Direction::RIGHT => Position::new(x + 1, y),
-        Direction::UP => Position::new(x, y - 1),
-        Direction::DOWN => Position::new(x, y + 1),
-    };
+pub fn move_entity(map: &Map, entity: &mut impl Movable, direction: Direction, topology: Topology) {
+    let mut new_pos = direction.step_from(entity.get_pos());
 
-    new_pos.constrain(layer);
+    topology.constrain(&mut new_pos, &map.map);
 
-    if can_stand(layer[0].len() as i32, layer.len() as i32, None, &new_pos) {
+    if can_stand(&map.get_playable_area(), None, &new_pos) {
         entity.move_to(new_pos, direction);
         // update_entity_positions(layer, entity);
     } else {
@@ -105,34 +123,56 @@ pub fn move_entity(layer: &mut Layer, entity: &mut impl Movable, direction: Dire
     }
 }
 
-pub fn get_mut_item_in_2d_enum_vec<'a, T>(
-    vec: &'a mut [(usize, Vec<(usize, T)>)],
-    position: &'a Position,
-) -> Option<&'a mut T> {
-    let (x, y) = position.get_as_usize();
-    let maybe_row = vec.iter_mut().find(|(in_y, _)| in_y == &y);
-    if let Some(row) = maybe_row {
-        let maybe_item = row.1.iter_mut().find(|(in_x, _)| in_x == &x);
-        if let Some(item) = maybe_item {
-            Some(&mut item.1)
-        } else {
-            None
-        }
-    } else {
-        None
+/// A grid of cells covering part of the map, looked up by map position. The cell for a position
+/// is found by offsetting it from the grid's corner, rather than searched for.
+pub struct OffsetGrid<T> {
+    corner: Position,
+    rows: Vec<Vec<T>>,
+}
+
+impl<T> OffsetGrid<T> {
+    /// Creates a grid whose first row and cell are at `corner` on the map.
+    #[must_use]
+    pub fn new(corner: Position, rows: Vec<Vec<T>>) -> Self {
+        Self { corner, rows }
+    }
+
+    /// Returns the cell at `position`, if the grid covers it.
+    pub fn get_mut(&mut self, position: &Position) -> Option<&mut T> {
+        let x = usize::try_from(position.0 - self.corner.0).ok()?;
+        let y = usize::try_from(position.1 - self.corner.1).ok()?;
+
+        self.rows.get_mut(y)?.get_mut(x)
+    }
+
+    #[must_use]
+    pub fn into_rows(self) -> Vec<Vec<T>> {
+        self.rows
     }
 }
 
 #[must_use]
 pub fn per_sec_to_tick_count_to_u64(per_sec: f64) -> u64 {
-    let per_tick = TICK_RATE / per_sec;
-    per_tick.ceil() as u64
+    per_sec_to_tick_count(per_sec).ceil() as u64
 }
 #[must_use]
 pub fn per_sec_to_tick_count(per_sec: f64) -> f64 {
     TICK_RATE / per_sec
 }
 
+/// Converts a number of game ticks to how long they take to play out. Game logic measures time
+/// in ticks rather than by the clock, so networked runs see the same timings on every machine.
+#[must_use]
+pub fn ticks_to_duration(ticks: u64) -> Duration {
+    Duration::from_secs_f64(ticks as f64 / TICK_RATE)
+}
+
+/// Converts a duration to the number of game ticks it lasts, rounding up.
+#[must_use]
+pub fn duration_to_ticks(duration: Duration) -> u64 {
+    (duration.as_secs_f64() * TICK_RATE).ceil() as u64
+}
+
 pub fn trim_string(s: String, max_len: usize) -> String {
     if s.len() > max_len {
         s[0..max_len].to_string()
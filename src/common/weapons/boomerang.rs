@@ -0,0 +1,169 @@
+use crate::common::PlayerStateRef;
+
+use crate::{
+    common::{
+        coords::{Direction, Position},
+        debuffs::Elements,
+        powerup::PowerupTypes,
+    },
+    new_weapon,
+    prelude::Duration,
+};
+
+use crate::common::character::CharacterPositionData;
+use crate::common::coords::AreaWrapper::{self, Chaos};
+use crate::common::coords::ChaosArea;
+use crate::common::enemies::enemy::Enemy;
+use crate::common::entities::EntityCharacters;
+use crate::common::map::Layer;
+use crate::common::{
+    powerup::{DynPowerup, Poweruppable},
+    stats::WeaponStats,
+    weapons::{DamageArea, Weapon},
+};
+use ratatui::style::Style;
+
+new_weapon!(Boomerang, 2, 5, 0.4);
+
+impl Boomerang {
+    /// How long the boomerang spends on each cell of its flight.
+    const STAGE_DURATION: Duration = Duration::from_millis(40);
+    /// The level from which the boomerang is three cells wide.
+    const WIDE_LEVEL: i32 = 5;
+
+    /// The cells covered when the boomerang is `distance` cells away from `origin`.
+    fn get_stage(&self, origin: &Position, facing: &Direction, distance: i32) -> Vec<Position> {
+        let Position(x, y) = *origin;
+        let half_width = i32::from(self.stats.level >= Self::WIDE_LEVEL);
+
+        let (step_x, step_y) = facing.get_offset();
+        let (centre_x, centre_y) = (x + step_x * distance, y + step_y * distance);
+
+        // the width runs across the flight, which for diagonals is along the other diagonal
+        let (across_x, across_y) = if facing.is_diagonal() {
+            (step_x, -step_y)
+        } else {
+            (step_y.abs(), step_x.abs())
+        };
+
+        (-half_width..=half_width)
+            .map(|offset| Position(centre_x + across_x * offset, centre_y + across_y * offset))
+            .collect()
+    }
+}
+
+impl Weapon for Boomerang {
+    /// Throws the boomerang out along the wielder's facing and back again, hitting each cell on the
+    /// way out and again on the way back.
+    fn attack(
+        &mut self,
+        wielder: CharacterPositionData,
+        _enemies: &[Enemy],
+        layer: &Layer,
+    ) -> DamageArea {
+        let range = self.stats.size;
+        let topology = self.player_state.borrow().get_topology();
+
+        let stages: Vec<AreaWrapper> = (1..=range)
+            .chain((1..range).rev())
+            .map(|distance| {
+                let area =
+                    ChaosArea::new(self.get_stage(&wielder.position, &wielder.facing, distance));
+                topology.constrain_area(Chaos(area), layer)
+            })
+            .collect();
+
+        let mut entity = EntityCharacters::AttackBlackout(Style::new().yellow());
+
+        if let Some(style) = self.get_elemental_style() {
+            *entity.style_mut() = style;
+        }
+
+        DamageArea {
+            damage_amount: self.get_damage(),
+            area: Chaos(ChaosArea::empty()),
+            entity,
+            duration: Self::STAGE_DURATION * stages.len() as u32,
+            blink: false,
+            destroys_walls: false,
+            stages,
+            weapon_stats: Some(self.stats.clone()),
+            source: None,
+        }
+    }
+
+    fn get_damage(&self) -> i32 {
+        ((f64::from(self.base_damage) * self.damage_scalar)
+            * self.player_state.borrow().stats.player_stats.damage_mult)
+            .ceil() as i32
+    }
+
+    fn get_stats(&self) -> &WeaponStats {
+        &self.stats
+    }
+
+    fn get_element(&self) -> Option<Elements> {
+        self.element
+    }
+}
+
+impl Poweruppable for Boomerang {
+    fn get_max_level(&self) -> i32 {
+        self.player_state.borrow().stats.game_stats.max_method_level
+    }
+
+    fn get_name(&self) -> String {
+        "BOOMERANG".into()
+    }
+
+    fn get_powerup_type(&self) -> PowerupTypes {
+        PowerupTypes::Weapon
+    }
+
+    fn upgrade_desc(&self, level: i32) -> String {
+        match level {
+            1 => "BOOMERANG will fly out in front of you and return, hitting enemies on the way out and back."
+                .into(),
+            2 => "Increase range by 1, increase base damage by 1.".into(),
+            3 => "Increase range by 2.".into(),
+            4 => "Increase base damage by 2, increase damage scalar by 25%".into(),
+            5 => "BOOMERANG is three cells wide. Increase damage scalar by 50%".into(),
+            _ => String::new(),
+        }
+    }
+
+    fn upgrade_self(&mut self, powerup: &DynPowerup) {
+        let from = powerup.get_current_level();
+        let to = powerup.get_new_level();
+        if to <= from {
+            return;
+        }
+        self.stats.level = to;
+
+        for i in (from + 1)..=to {
+            match i {
+                2 => {
+                    self.stats.size += 1;
+                    self.stats.damage_flat_boost += 1;
+                    self.base_damage += 1;
+                }
+                3 => {
+                    self.stats.size += 2;
+                }
+                4 => {
+                    self.stats.damage_flat_boost += 2;
+                    self.base_damage += 2;
+                    self.damage_scalar += 0.25;
+                }
+                5 => {
+                    self.damage_scalar += 0.5;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get_level(&self) -> i32 {
+        self.stats.level
+    }
+}
@@ -2,12 +2,12 @@ use crate::common::PlayerStateRef;
 
 use crate::{
     common::{
-        coords::Area,
         coords::{Direction, Position, SquareArea},
         debuffs::{Debuff, DebuffTypes, Elements},
+        onkill::OnKillEffect,
         powerup::{DynPowerup, PowerupTypes, Poweruppable},
         stats::WeaponStats,
-        stats::{DebuffStats, Proc},
+        stats::{DebuffStats, Proc, ProcId},
         weapons::{DamageArea, Weapon},
     },
     new_weapon,
@@ -21,7 +21,7 @@ use crate::common::entities::EntityCharacters;
 use crate::common::map::Layer;
 use ratatui::style::Style;
 
-new_weapon!(Flash, 1, 1, 1);
+new_weapon!(Flash, 1, 1, 1.5);
 
 impl Poweruppable for Flash {
     fn get_max_level(&self) -> i32 {
@@ -41,7 +41,7 @@ impl Poweruppable for Flash {
             1 => "FLASH will create a brief damaging field directly in front of you.".into(),
             2 => "Increase size by 1, increase base damage by 1.".into(),
             3 => "Increase base damage by 1".into(),
-            4 => "Increase damage scalar by 25%".into(),
+            4 => "Increase damage scalar by 25%. Kills leave a small patch of fire.".into(),
             5 => "Increase damage scalar by 25%. Imbue FLASH with Flame element, burning enemies when hit.".into(),
             _ => String::new(),
         }
@@ -68,13 +68,20 @@ impl Poweruppable for Flash {
                 }
                 4 => {
                     self.damage_scalar += 0.25;
+                    self.stats.on_kill.insert(
+                        "fire_patch".into(),
+                        OnKillEffect::FirePatch {
+                            size: 1,
+                            damage_mult: 0.5,
+                        },
+                    );
                 }
                 5 => {
                     self.damage_scalar += 0.25;
                     self.element = Some(Elements::Flame(self.stats.elemental_honage));
                     let honage = self.element.expect("Something crazy happened").get_honage();
-                    self.stats.procs.insert(
-                        "burn".into(),
+                    self.stats.add_proc(
+                        ProcId::Burn,
                         Proc {
                             chance: 80,
                             debuff: Debuff {
@@ -112,22 +119,12 @@ impl Weapon for Flash {
         _enemies: &[Enemy],
         layer: &Layer,
     ) -> DamageArea {
-        if self.cooldown_ticks > 0 && self.cooldown_ticks < Self::BASE_COOLDOWN {
-            self.cooldown_ticks += 1;
-            if self.cooldown_ticks == Self::BASE_COOLDOWN {
-            } else {
-                return DamageArea::new_empty();
-            }
-        } else if self.cooldown_ticks >= Self::BASE_COOLDOWN {
-            self.cooldown_ticks = 0;
-        }
-
         let (x, y) = wielder.position.get();
         let direction = wielder.facing;
 
         let size = self.stats.size;
 
-        let mut new_area: SquareArea = match direction {
+        let new_area: SquareArea = match direction {
             Direction::DOWN => SquareArea {
                 corner1: Position(x + size, y + 1),
                 corner2: Position(x - size, y + size),
@@ -144,11 +141,24 @@ impl Weapon for Flash {
                 corner1: Position(x + 1, y + size),
                 corner2: Position(x + size, y - size),
             },
+            // diagonals cover the square off the character's corner, about as many cells as a
+            // straight flash
+            Direction::UPLEFT | Direction::UPRIGHT | Direction::DOWNLEFT | Direction::DOWNRIGHT => {
+                let (step_x, step_y) = direction.get_offset();
+                let reach = (f64::from(size) * std::f64::consts::SQRT_2).ceil() as i32;
+
+                SquareArea {
+                    corner1: Position(x + step_x, y + step_y),
+                    corner2: Position(x + step_x * reach, y + step_y * reach),
+                }
+            }
         };
 
-        new_area.constrain(layer);
-
-        self.cooldown_ticks += 1;
+        let area = self
+            .player_state
+            .borrow()
+            .get_topology()
+            .constrain_area(Square(new_area), layer);
 
         let mut entity = EntityCharacters::AttackBlackout(Style::new().bold().white());
 
@@ -157,12 +167,15 @@ impl Weapon for Flash {
         }
 
         DamageArea {
-            area: Square(new_area),
+            area,
             damage_amount: self.get_damage(),
             entity,
             duration: Duration::from_secs_f32(0.05),
             blink: false,
+            destroys_walls: false,
+            stages: vec![],
             weapon_stats: Some(self.stats.clone()),
+            source: None,
         }
     }
 
@@ -173,6 +186,10 @@ impl Weapon for Flash {
             .ceil() as i32
     }
 
+    fn get_stats(&self) -> &WeaponStats {
+        &self.stats
+    }
+
     fn get_element(&self) -> Option<Elements> {
         self.element
     }
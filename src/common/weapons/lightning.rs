@@ -4,7 +4,8 @@ use crate::{
     common::{
         coords::Area,
         debuffs::{Debuff, DebuffTypes, Elements},
-        stats::{DebuffStats, Proc},
+        onkill::OnKillEffect,
+        stats::{DebuffStats, Proc, ProcId},
     },
     new_weapon,
     prelude::Duration,
@@ -14,7 +15,9 @@ use ratatui::style::Style;
 
 use crate::common::character::{CharacterPositionData, Renderable};
 use crate::common::coords::AreaWrapper::Chaos;
-use crate::common::enemies::enemy::{Enemy, get_closest_enemies, move_to_point_granular};
+use crate::common::enemies::enemy::{
+    Enemy, get_closest_enemies, move_to_point_granular, select_target,
+};
 use crate::common::entities::EntityCharacters;
 use crate::common::map::Layer;
 use crate::common::{
@@ -25,7 +28,7 @@ use crate::common::{
     weapons::{DamageArea, Weapon},
 };
 
-new_weapon!(Lightning, 1, 1, 3);
+new_weapon!(Lightning, 1, 1, 0.375);
 
 impl Weapon for Lightning {
     fn attack(
@@ -34,22 +37,22 @@ impl Weapon for Lightning {
         enemies: &[Enemy],
         layer: &Layer,
     ) -> DamageArea {
-        if self.cooldown_ticks > 0 && self.cooldown_ticks < Self::BASE_COOLDOWN {
-            self.cooldown_ticks += 1;
-            return DamageArea::new_empty();
-        } else if self.cooldown_ticks >= Self::BASE_COOLDOWN {
-            self.cooldown_ticks = 0;
-            return DamageArea::new_empty();
-        }
-
         let mut begin_pos = wielder.position;
 
         let mut positions = Vec::new();
 
         let mut enemies = Vec::from(enemies);
 
-        for _ in 0..self.stats.size {
-            let closest = get_closest_enemies(&enemies, &begin_pos);
+        let target_priority = self.player_state.borrow().settings.target_priority;
+
+        for bounce in 0..self.stats.size {
+            // the first strike follows the player's targeting setting, later bounces chain to
+            // whatever is nearest
+            let closest = if bounce == 0 {
+                select_target(&enemies, &begin_pos, target_priority)
+            } else {
+                get_closest_enemies(&enemies, &begin_pos)
+            };
 
             let mut current_pos = begin_pos.clone();
 
@@ -66,10 +69,8 @@ impl Weapon for Lightning {
 
                 begin_pos = desired_pos;
 
-                enemies = enemies
-                    .iter()
-                    .filter_map(|e| if e != closest { Some(e.clone()) } else { None })
-                    .collect();
+                let struck = closest.get_id();
+                enemies.retain(|enemy| enemy.get_id() != struck);
             }
         }
 
@@ -82,15 +83,16 @@ impl Weapon for Lightning {
             *entity.style_mut() = style;
         }
 
-        self.cooldown_ticks += 1;
-
         DamageArea {
             damage_amount: self.get_damage(),
             area: Chaos(area),
             entity,
             duration: Duration::from_secs_f64(0.1),
             blink: false,
+            destroys_walls: false,
+            stages: vec![],
             weapon_stats: Some(self.stats.clone()),
+            source: None,
         }
     }
 
@@ -100,6 +102,10 @@ impl Weapon for Lightning {
             .ceil() as i32
     }
 
+    fn get_stats(&self) -> &WeaponStats {
+        &self.stats
+    }
+
     fn get_element(&self) -> Option<Elements> {
         None
     }
@@ -123,7 +129,8 @@ impl Poweruppable for Lightning {
             1 => "LIGHTNING will seek the nearest enemy and damage them.".into(),
             2 => "Increase bounces by 1, increase base damage by 1. ".into(),
             3 => "Increase bounces by 1, increase base damage by 2".into(),
-            4 => "Increase bounces by 1, increase damage scalar by 25%".into(),
+            4 => "Increase bounces by 1, increase damage scalar by 25%. Kills chain a free bolt to the \
+            nearest enemy.".into(),
             5 => "Double bounces, increase damage scalar by 75%. Imbue LIGHTNING with Shock element, giving a chance \
             to charge enemies on hit.".into(),
             _ => String::new(),
@@ -153,6 +160,10 @@ impl Poweruppable for Lightning {
                 4 => {
                     self.stats.size += 1;
                     self.damage_scalar += 0.25;
+                    self.stats.on_kill.insert(
+                        "chain_bolt".into(),
+                        OnKillEffect::ChainBolt { damage_mult: 1. },
+                    );
                 }
                 5 => {
                     self.stats.size *= 2;
@@ -160,8 +171,8 @@ impl Poweruppable for Lightning {
 
                     self.element = Some(Elements::Shock(self.stats.elemental_honage));
                     let honage = self.element.expect("Something crazy happened").get_honage();
-                    self.stats.procs.insert(
-                        "charge".into(),
+                    self.stats.add_proc(
+                        ProcId::Charge,
                         Proc {
                             chance: (20. * honage).ceil().min(100.) as u32,
                             debuff: Debuff {
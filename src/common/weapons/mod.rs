@@ -2,7 +2,10 @@
 //! It includes a `Weapon` trait, a `Sword` implementation, and a `DamageArea` struct
 //! for handling attacks and their effects on enemies.
 
-use crate::{common::debuffs::Elements, prelude::Duration};
+use crate::{
+    common::debuffs::{DebuffTypes, Elements},
+    prelude::Duration,
+};
 use std::cell::RefCell;
 
 use ratatui::style::Style;
@@ -11,38 +14,47 @@ use std::rc::Rc;
 use strum::{EnumIter, EnumString, IntoStaticStr};
 
 use crate::common::character::{CharacterPositionData, Renderable};
-use crate::common::coords::{AreaWrapper, ChaosArea};
+use crate::common::collection::achievement::Achievement;
+use crate::common::coords::{AreaWrapper, ChaosArea, Position};
 use crate::common::enemies::enemy::{Debuffable, Enemy};
-use crate::common::entities::EntityCharacters;
+use crate::common::entities::{EntityCharacters, EntityId};
 use crate::common::map::Layer;
+use crate::common::turrets::DeployedTurret;
+use crate::common::upgrades::upgrade::{PlayerState, UpgradeNode, find_node};
 
 use crate::common::sound::{SoundEffect, SoundWrangler};
 use crate::common::{
-    PlayerStateRef, character::Damageable, powerup::PoweruppableWeapon, stats::WeaponStats,
+    PlayerStateRef,
+    character::Damageable,
+    powerup::{DynPowerup, PowerupTypes, PowerupUpgrade, PoweruppableWeapon},
+    stats::WeaponStats,
 };
 
+pub mod boomerang;
 pub mod flash;
 pub mod lightning;
+pub mod nova;
 pub mod pillar;
 pub mod row;
+pub mod turret;
 
 #[macro_export]
 macro_rules! new_weapon {
-    ($weapon_name: ident, $base_damage:expr, $base_size:expr, $base_cooldown:expr ) => {
+    ($weapon_name: ident, $base_damage:expr, $base_size:expr, $base_attack_rate:expr ) => {
         #[derive(Clone)]
         pub struct $weapon_name {
             base_damage: i32,
             damage_scalar: f64,
             stats: WeaponStats,
             element: Option<Elements>,
-            cooldown_ticks: u64,
             player_state: PlayerStateRef,
         }
 
         impl $weapon_name {
             const BASE_DAMAGE: i32 = $base_damage;
             const BASE_SIZE: i32 = $base_size;
-            const BASE_COOLDOWN: u64 = $base_cooldown;
+            /// How many times a second this weapon attacks, before attack speed.
+            pub const BASE_ATTACK_RATE: f64 = $base_attack_rate;
 
             #[doc = concat!("Creates a new `", stringify!($weapon_name), "` with stats based on \
             the \
@@ -53,7 +65,6 @@ macro_rules! new_weapon {
                 Self {
                     base_damage: Self::BASE_DAMAGE + base_weapon_stats.damage_flat_boost,
                     damage_scalar: 1.,
-                    cooldown_ticks: 0,
                     stats: WeaponStats {
                         size: Self::BASE_SIZE + base_weapon_stats.size,
                         ..base_weapon_stats
@@ -79,6 +90,15 @@ pub enum WeaponWrapper {
 
     #[strum(serialize = "Row", serialize = "ROW")]
     Row(Option<row::Row>),
+
+    #[strum(serialize = "Nova", serialize = "NOVA")]
+    Nova(Option<nova::Nova>),
+
+    #[strum(serialize = "Turret", serialize = "TURRET")]
+    Turret(Option<turret::Turret>),
+
+    #[strum(serialize = "Boomerang", serialize = "BOOMERANG")]
+    Boomerang(Option<boomerang::Boomerang>),
 }
 
 impl PartialEq for WeaponWrapper {
@@ -89,7 +109,78 @@ impl PartialEq for WeaponWrapper {
     }
 }
 
+/// Something the player has to have done for a METHOD to be unlocked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnlockCondition {
+    /// Owning the upgrade with this id.
+    Upgrade(&'static str),
+    Achievement(Achievement),
+}
+
+impl UnlockCondition {
+    #[must_use]
+    pub fn is_met(&self, player_state: &PlayerState) -> bool {
+        match self {
+            UnlockCondition::Upgrade(id) => player_state.upgrade_owned(id),
+            UnlockCondition::Achievement(achievement) => {
+                player_state.achievements.contains(achievement)
+            }
+        }
+    }
+
+    /// Describes how to meet this condition, naming upgrades by their title in `upgrade_tree`.
+    #[must_use]
+    pub fn get_hint(&self, upgrade_tree: &[UpgradeNode]) -> String {
+        match self {
+            UnlockCondition::Upgrade(id) => find_node(upgrade_tree, id).map_or_else(
+                || format!("buy upgrade {id}"),
+                |node| format!("buy {}", node.title),
+            ),
+            UnlockCondition::Achievement(achievement) => {
+                format!(
+                    "earn {}: {}",
+                    achievement.get_name(),
+                    achievement.get_desc()
+                )
+            }
+        }
+    }
+}
+
 impl WeaponWrapper {
+    /// Returns the ways this METHOD can be unlocked, any one of which is enough. METHODs with no
+    /// conditions are always unlocked.
+    #[must_use]
+    pub fn get_unlock_conditions(&self) -> &'static [UnlockCondition] {
+        match self {
+            WeaponWrapper::Flash(_) => &[],
+            WeaponWrapper::Row(_) => &[UnlockCondition::Upgrade("611")],
+            WeaponWrapper::Pillar(_) => &[UnlockCondition::Upgrade("612")],
+            WeaponWrapper::Lightning(_) => &[UnlockCondition::Upgrade("613")],
+            WeaponWrapper::Nova(_) => &[UnlockCondition::Upgrade("614")],
+            WeaponWrapper::Turret(_) => &[
+                UnlockCondition::Upgrade("615"),
+                UnlockCondition::Achievement(Achievement::Level15),
+            ],
+            WeaponWrapper::Boomerang(_) => &[
+                UnlockCondition::Upgrade("616"),
+                UnlockCondition::Achievement(Achievement::Level25),
+            ],
+        }
+    }
+
+    /// Checks whether the player has met any of this METHOD's unlock conditions, so it can be
+    /// offered in runs and picked to start with.
+    #[must_use]
+    pub fn is_unlocked(&self, player_state: &PlayerState) -> bool {
+        let conditions = self.get_unlock_conditions();
+
+        conditions.is_empty()
+            || conditions
+                .iter()
+                .any(|condition| condition.is_met(player_state))
+    }
+
     /// Get a reference to the inner weapon.
     ///
     /// # Panics
@@ -102,9 +193,27 @@ impl WeaponWrapper {
             WeaponWrapper::Pillar(pillar) => pillar.as_ref().expect("No inner weapon."),
             WeaponWrapper::Lightning(lightning) => lightning.as_ref().expect("No inner weapon."),
             WeaponWrapper::Row(row) => row.as_ref().expect("No inner weapon."),
+            WeaponWrapper::Nova(nova) => nova.as_ref().expect("No inner weapon."),
+            WeaponWrapper::Turret(turret) => turret.as_ref().expect("No inner weapon."),
+            WeaponWrapper::Boomerang(boomerang) => boomerang.as_ref().expect("No inner weapon."),
         }
     }
 
+    /// How many times a second this weapon attacks, before attack speed.
+    #[must_use]
+    pub fn get_base_attack_rate(&self) -> f64 {
+        match self {
+            WeaponWrapper::Flash(_) => flash::Flash::BASE_ATTACK_RATE,
+            WeaponWrapper::Pillar(_) => pillar::Pillar::BASE_ATTACK_RATE,
+            WeaponWrapper::Lightning(_) => lightning::Lightning::BASE_ATTACK_RATE,
+            WeaponWrapper::Row(_) => row::Row::BASE_ATTACK_RATE,
+            WeaponWrapper::Nova(_) => nova::Nova::BASE_ATTACK_RATE,
+            WeaponWrapper::Turret(_) => turret::Turret::BASE_ATTACK_RATE,
+            WeaponWrapper::Boomerang(_) => boomerang::Boomerang::BASE_ATTACK_RATE,
+        }
+    }
+
+    #[must_use]
     pub fn get_sound(&self) -> SoundEffect {
         match self {
             WeaponWrapper::Flash(_) => SoundEffect::Flash,
@@ -123,13 +232,20 @@ impl WeaponWrapper {
             WeaponWrapper::Pillar(pillar) => pillar.as_mut().expect("No inner weapon."),
             WeaponWrapper::Lightning(lightning) => lightning.as_mut().expect("No inner weapon."),
             WeaponWrapper::Row(row) => row.as_mut().expect("No inner weapon."),
+            WeaponWrapper::Nova(nova) => nova.as_mut().expect("No inner weapon."),
+            WeaponWrapper::Turret(turret) => turret.as_mut().expect("No inner weapon."),
+            WeaponWrapper::Boomerang(boomerang) => boomerang.as_mut().expect("No inner weapon."),
         }
     }
 
+    /// Creates the inner weapon from `weapon_stats`, starting it at the level its mastery gives.
     pub fn populate_inner(&mut self, weapon_stats: WeaponStats, player_state: PlayerStateRef) {
+        let name: &'static str = (&*self).into();
+        let start_levels = player_state.borrow().get_mastery(name).get_start_levels();
+
         match self {
             WeaponWrapper::Flash(flash) => {
-                *flash = Some(flash::Flash::new(weapon_stats, player_state))
+                *flash = Some(flash::Flash::new(weapon_stats, player_state));
             }
             WeaponWrapper::Pillar(pillar) => {
                 *pillar = Some(pillar::Pillar::new(weapon_stats, player_state));
@@ -138,6 +254,27 @@ impl WeaponWrapper {
                 *lightning = Some(lightning::Lightning::new(weapon_stats, player_state));
             }
             WeaponWrapper::Row(row) => *row = Some(row::Row::new(weapon_stats, player_state)),
+            WeaponWrapper::Nova(nova) => *nova = Some(nova::Nova::new(weapon_stats, player_state)),
+            WeaponWrapper::Turret(turret) => {
+                *turret = Some(turret::Turret::new(weapon_stats, player_state));
+            }
+            WeaponWrapper::Boomerang(boomerang) => {
+                *boomerang = Some(boomerang::Boomerang::new(weapon_stats, player_state));
+            }
+        }
+
+        if start_levels > 0 {
+            let inner = self.get_inner_mut();
+            let level = inner.get_level();
+            let new_level = (level + start_levels).min(inner.get_max_level().max(level));
+            let powerup: DynPowerup = Box::new(PowerupUpgrade::new(
+                name,
+                String::new(),
+                level,
+                new_level,
+                PowerupTypes::Weapon,
+            ));
+            inner.upgrade_self(&powerup);
         }
     }
 
@@ -147,6 +284,43 @@ impl WeaponWrapper {
     }
 }
 
+/// A single enemy hit by a damage area.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EnemyHit {
+    /// The id of the enemy hit.
+    pub id: EntityId,
+    /// The damage applied, not counting damage past the enemy's remaining health.
+    pub damage: i32,
+    pub killed: bool,
+    /// The debuffs that landed on the enemy from the area's procs.
+    pub procs: Vec<DebuffTypes>,
+}
+
+/// What applying a damage area did, one entry per enemy hit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HitReport {
+    pub hits: Vec<EnemyHit>,
+}
+
+impl HitReport {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.hits.is_empty()
+    }
+
+    /// Returns the total damage applied across every hit.
+    #[must_use]
+    pub fn get_total_damage(&self) -> i32 {
+        self.hits.iter().map(|hit| hit.damage).sum()
+    }
+
+    /// Returns how many of the enemies hit were killed by it.
+    #[must_use]
+    pub fn get_kill_count(&self) -> usize {
+        self.hits.iter().filter(|hit| hit.killed).count()
+    }
+}
+
 /// Represents an area where damage is applied, created by a weapon attack.
 #[derive(Clone)]
 pub struct DamageArea {
@@ -155,10 +329,18 @@ pub struct DamageArea {
     pub entity: EntityCharacters,
     pub duration: Duration,
     pub blink: bool,
+    /// Whether this area breaks wall tiles it covers.
+    pub destroys_walls: bool,
+    /// Areas swept through one after another over the duration, each dealing `damage_amount` as
+    /// it is reached. Empty for areas that hit all at once.
+    pub stages: Vec<AreaWrapper>,
     pub weapon_stats: Option<WeaponStats>,
+    /// The name of the METHOD that created this area, if any.
+    pub source: Option<&'static str>,
 }
 
 impl DamageArea {
+    #[must_use]
     pub fn new_empty() -> Self {
         DamageArea {
             damage_amount: 0,
@@ -166,40 +348,168 @@ impl DamageArea {
             duration: Duration::from_secs_f32(0.),
             entity: EntityCharacters::Empty(Style::new()),
             blink: false,
+            destroys_walls: false,
+            stages: vec![],
             weapon_stats: None,
+            source: None,
         }
     }
 
-    /// Applies this damage area to every enemy whose position lies inside the area.
+    /// Applies this damage area to every living enemy whose position lies inside the area, the
+    /// same as `resolve_damage` with this area alone.
+    ///
+    /// Returns a report of every enemy hit, with the damage applied to it, whether it died and the
+    /// debuffs that landed.
+    pub fn deal_damage(
+        &self,
+        enemies: &mut [Enemy],
+        sound_wrangler: &Rc<RefCell<SoundWrangler>>,
+    ) -> HitReport {
+        resolve_damage(std::slice::from_ref(self), enemies, sound_wrangler)
+            .pop()
+            .unwrap_or_default()
+    }
+
+    /// Rolls each of this area's procs against `enemy`, returning the debuffs that landed.
+    fn roll_procs(&self, enemy: &mut Enemy) -> Vec<DebuffTypes> {
+        let Some(stats) = &self.weapon_stats else {
+            return vec![];
+        };
+
+        stats
+            .procs
+            .values()
+            .filter(|proc| proc.chance > 0 && enemy.try_proc(proc))
+            .map(|proc| proc.debuff.debuff_type)
+            .collect()
+    }
+
+    /// Turns every wall tile inside this area into background, if this area destroys walls.
     ///
-    /// For each affected enemy, reduces its health by `damage_amount`. If `weapon_stats` is present,
-    /// iterates its `procs` and invokes each proc with `chance > 0` on the enemy.
-    pub fn deal_damage(&self, enemies: &mut [Enemy], sound_wrangler: Rc<RefCell<SoundWrangler>>) {
-        let mut any_hit = false;
+    /// Returns the positions of the walls that were destroyed.
+    pub fn destroy_walls(&self, layer: &mut Layer) -> Vec<Position> {
+        if !self.destroys_walls {
+            return vec![];
+        }
+
+        let mut destroyed = vec![];
+        for position in self.area.get_inner().pos_iter() {
+            let (x, y) = position.get_as_usize();
+            if let Some(tile) = layer.get_mut(y).and_then(|row| row.get_mut(x))
+                && matches!(tile, EntityCharacters::Wall(_))
+            {
+                tile.replace(EntityCharacters::Background2(Style::new().dark_gray()));
+                destroyed.push(position);
+            }
+        }
+
+        destroyed
+    }
+
+    /// Applies this damage area to every enemy inside it, dealing `percent` of each enemy's max
+    /// health as damage on top of `damage_amount`. Procs are not applied.
+    ///
+    /// Returns a report of every enemy hit, like `deal_damage`.
+    pub fn deal_max_health_damage(
+        &self,
+        enemies: &mut [Enemy],
+        percent: f64,
+        sound_wrangler: &Rc<RefCell<SoundWrangler>>,
+    ) -> HitReport {
+        let mut report = HitReport::default();
         for enemy in enemies.iter_mut() {
             if enemy.get_pos().is_in_area(self.area.get_inner()) {
-                any_hit = true;
-                // sound_wrangler.borrow_mut().play(SoundEffect::Hit);
-                enemy.take_damage(self.damage_amount);
-
-                // if was hit by a weapon, do the following
-                if let Some(stats) = &self.weapon_stats
-                    && !stats.procs.is_empty()
-                {
-                    stats.procs.iter().for_each(|(_key, proc)| {
-                        if proc.chance > 0 {
-                            enemy.try_proc(proc);
-                        }
-                    });
-                }
+                let was_alive = enemy.is_alive();
+                let damage =
+                    self.damage_amount + (f64::from(enemy.max_health) * percent).ceil() as i32;
+
+                let applied = damage.min(*enemy.get_health()).max(0);
+                enemy.take_damage(damage);
+
+                report.hits.push(EnemyHit {
+                    id: enemy.get_id(),
+                    damage: applied,
+                    killed: was_alive && !enemy.is_alive(),
+                    procs: vec![],
+                });
             }
         }
-        if any_hit {
+        if report.get_total_damage() > 0 {
             sound_wrangler.borrow_mut().play(SoundEffect::Hit);
         }
+
+        report
     }
 }
 
+/// Deals the damage of every area in `areas` to `enemies` in a single pass, returning a report
+/// for each area in the same order.
+///
+/// The outcome doesn't depend on the order of `areas`. Every area is checked against the living
+/// enemies as they were before any damage landed, each enemy rolls the procs of every area hitting
+/// it before taking all of its hits at once, and its remaining health is shared out between the
+/// hits biggest first, with ties going by METHOD name. The kill goes to the hit that takes the
+/// enemy to zero.
+pub fn resolve_damage(
+    areas: &[DamageArea],
+    enemies: &mut [Enemy],
+    sound_wrangler: &Rc<RefCell<SoundWrangler>>,
+) -> Vec<HitReport> {
+    let mut reports = vec![HitReport::default(); areas.len()];
+
+    for enemy in enemies.iter_mut() {
+        if !enemy.is_alive() {
+            continue;
+        }
+
+        let position = enemy.get_pos().clone();
+        let mut hits: Vec<(usize, i32, Vec<DebuffTypes>)> = areas
+            .iter()
+            .enumerate()
+            .filter(|(_, area)| position.is_in_area(area.area.get_inner()))
+            .map(|(area_index, area)| {
+                (
+                    area_index,
+                    enemy.reduce_damage(area.damage_amount),
+                    area.roll_procs(enemy),
+                )
+            })
+            .collect();
+        if hits.is_empty() {
+            continue;
+        }
+
+        hits.sort_by(|(a, a_damage, _), (b, b_damage, _)| {
+            b_damage
+                .cmp(a_damage)
+                .then_with(|| areas[*a].source.cmp(&areas[*b].source))
+        });
+
+        let mut remaining = (*enemy.get_health()).max(0);
+        let mut total = 0;
+        for (area_index, damage, procs) in hits {
+            let applied = damage.min(remaining).max(0);
+            let killed = remaining > 0 && applied == remaining;
+            remaining -= applied;
+            total += damage;
+
+            reports[area_index].hits.push(EnemyHit {
+                id: enemy.get_id(),
+                damage: applied,
+                killed,
+                procs,
+            });
+        }
+        enemy.take_reduced_damage(total);
+    }
+
+    if reports.iter().any(|report| !report.is_empty()) {
+        sound_wrangler.borrow_mut().play(SoundEffect::Hit);
+    }
+
+    reports
+}
+
 #[must_use]
 pub fn get_strongest_weapon(weapons: &[WeaponWrapper]) -> Option<&WeaponWrapper> {
     weapons.iter().max_by(|a, b| {
@@ -225,8 +535,17 @@ pub trait Weapon {
     ///Damage should be rounded up to the nearest int.
     fn get_damage(&self) -> i32;
 
+    /// The weapon's current stats, including its size, level and procs.
+    fn get_stats(&self) -> &WeaponStats;
+
     fn get_element(&self) -> Option<Elements>;
 
+    /// Creates a turret to place on the map alongside this attack. Most weapons don't deploy
+    /// anything.
+    fn deploy(&mut self, _wielder: &CharacterPositionData) -> Option<DeployedTurret> {
+        None
+    }
+
     fn get_elemental_style(&self) -> Option<Style> {
         self.get_element().map(|element| match element {
             Elements::Flame(_) => Some(Style::new().red()),
@@ -234,3 +553,139 @@ pub trait Weapon {
         })?
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use crate::common::character::Damageable;
+    use crate::common::coords::{AreaWrapper, ChaosArea, Position};
+    use crate::common::enemies::enemy::{Enemy, EnemyBehaviour, EnemyDrops};
+    use crate::common::mastery::tier::MasteryTier;
+    use crate::common::sound::SoundWrangler;
+    use crate::common::stats::WeaponStats;
+    use crate::common::upgrades::upgrade::PlayerState;
+    use crate::common::weapons::{DamageArea, WeaponWrapper, resolve_damage};
+
+    #[test]
+    fn hit_report_lists_each_enemy_hit() {
+        let drops = EnemyDrops {
+            gold: 1,
+            xp: 0,
+            position: Position::default(),
+            chest: false,
+        };
+        let mut enemies = vec![
+            Enemy::new(Position(0, 0), 1, 3, drops.clone()),
+            Enemy::new(Position(5, 5), 1, 3, drops.clone()),
+            Enemy::new(Position(1, 0), 1, 10, drops),
+        ];
+
+        let area = DamageArea {
+            damage_amount: 5,
+            area: AreaWrapper::Chaos(ChaosArea::new(vec![Position(0, 0), Position(1, 0)])),
+            ..DamageArea::new_empty()
+        };
+        let report = area.deal_damage(
+            &mut enemies,
+            &Rc::new(RefCell::new(SoundWrangler::default())),
+        );
+
+        assert_eq!(report.hits.len(), 2);
+        assert_eq!(
+            (report.hits[0].id, report.hits[0].damage),
+            (enemies[0].get_id(), 3)
+        );
+        assert!(report.hits[0].killed);
+        assert_eq!(
+            (report.hits[1].id, report.hits[1].damage),
+            (enemies[2].get_id(), 5)
+        );
+        assert!(!report.hits[1].killed);
+        assert_eq!(report.get_total_damage(), 8);
+        assert_eq!(report.get_kill_count(), 1);
+    }
+
+    #[test]
+    fn overlapping_areas_resolve_in_any_order() {
+        let drops = EnemyDrops {
+            gold: 1,
+            xp: 0,
+            position: Position::default(),
+            chest: false,
+        };
+        let enemies = vec![
+            Enemy::new(Position(0, 0), 1, 6, drops.clone()),
+            Enemy::new(Position(1, 0), 1, 20, drops),
+        ];
+        let area = |damage_amount, source, positions| DamageArea {
+            damage_amount,
+            area: AreaWrapper::Chaos(ChaosArea::new(positions)),
+            source: Some(source),
+            ..DamageArea::new_empty()
+        };
+        let small = area(3, "Row", vec![Position(0, 0), Position(1, 0)]);
+        let big = area(5, "Flash", vec![Position(0, 0)]);
+        let sound_wrangler = Rc::new(RefCell::new(SoundWrangler::default()));
+
+        let mut forwards = enemies.clone();
+        let reports = resolve_damage(
+            &[small.clone(), big.clone()],
+            &mut forwards,
+            &sound_wrangler,
+        );
+        let mut backwards = enemies;
+        let mut reversed = resolve_damage(&[big, small], &mut backwards, &sound_wrangler);
+        reversed.reverse();
+
+        assert_eq!(reports, reversed);
+        assert_eq!(
+            forwards
+                .iter()
+                .map(|enemy| *enemy.get_health())
+                .collect::<Vec<_>>(),
+            backwards
+                .iter()
+                .map(|enemy| *enemy.get_health())
+                .collect::<Vec<_>>()
+        );
+
+        // the big hit lands first, so the small one takes the last point of health and the kill
+        let [small_report, big_report] = &reports[..] else {
+            panic!("a report for each area");
+        };
+        assert_eq!(
+            (big_report.hits[0].damage, big_report.hits[0].killed),
+            (5, false)
+        );
+        assert_eq!(
+            (small_report.hits[0].damage, small_report.hits[0].killed),
+            (1, true)
+        );
+        assert_eq!(
+            (small_report.hits[1].id, small_report.hits[1].damage),
+            (forwards[1].get_id(), 3)
+        );
+        assert!(!forwards[0].is_alive());
+    }
+
+    #[test]
+    fn mastered_weapons_start_levelled() {
+        let player_state = Rc::new(RefCell::new(PlayerState::default()));
+        player_state.borrow_mut().stats.game_stats.max_method_level = 5;
+
+        let reached = player_state
+            .borrow_mut()
+            .add_weapon_kills("Flash", MasteryTier::Expert.get_kills_needed());
+        assert_eq!(reached, Some(MasteryTier::Expert));
+
+        let mut flash = WeaponWrapper::Flash(None);
+        flash.populate_inner(WeaponStats::default(), player_state.clone());
+        assert_eq!(flash.get_inner().get_level(), 2);
+
+        let mut row = WeaponWrapper::Row(None);
+        row.populate_inner(WeaponStats::default(), player_state);
+        assert_eq!(row.get_inner().get_level(), 1);
+    }
+}
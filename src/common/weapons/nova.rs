@@ -0,0 +1,168 @@
+use crate::common::PlayerStateRef;
+
+use crate::{
+    common::{coords::Position, debuffs::Elements, powerup::PowerupTypes},
+    new_weapon,
+    prelude::Duration,
+};
+
+use crate::common::character::CharacterPositionData;
+use crate::common::coords::AreaWrapper::Chaos;
+use crate::common::coords::ChaosArea;
+use crate::common::enemies::enemy::Enemy;
+use crate::common::entities::EntityCharacters;
+use crate::common::map::Layer;
+use crate::common::{
+    powerup::{DynPowerup, Poweruppable},
+    stats::WeaponStats,
+    weapons::{DamageArea, Weapon},
+};
+use ratatui::style::Style;
+
+new_weapon!(Nova, 2, 2, 0.5);
+
+impl Nova {
+    /// The level from which NOVA releases a second pulse in a ring around the cross.
+    const SECOND_PULSE_LEVEL: i32 = 5;
+    /// How much further out the second pulse lands than the end of the cross.
+    const SECOND_PULSE_GAP: i32 = 2;
+
+    /// Positions in a cross centred on `center`, with arms `radius` long.
+    fn get_cross(center: &Position, radius: i32) -> Vec<Position> {
+        let Position(x, y) = *center;
+
+        (-radius..=radius)
+            .flat_map(|offset| [Position(x + offset, y), Position(x, y + offset)])
+            .collect()
+    }
+
+    /// Positions on a diamond-shaped ring centred on `center`, `radius` steps out.
+    fn get_ring(center: &Position, radius: i32) -> Vec<Position> {
+        let Position(x, y) = *center;
+
+        (0..radius)
+            .flat_map(|step| {
+                let other = radius - step;
+                [
+                    Position(x + step, y - other),
+                    Position(x + other, y + step),
+                    Position(x - step, y + other),
+                    Position(x - other, y - step),
+                ]
+            })
+            .collect()
+    }
+}
+
+impl Weapon for Nova {
+    fn attack(
+        &mut self,
+        wielder: CharacterPositionData,
+        _enemies: &[Enemy],
+        layer: &Layer,
+    ) -> DamageArea {
+        let radius = self.stats.size;
+
+        let mut positions = Self::get_cross(&wielder.position, radius);
+
+        if self.stats.level >= Self::SECOND_PULSE_LEVEL {
+            positions.append(&mut Self::get_ring(
+                &wielder.position,
+                radius + Self::SECOND_PULSE_GAP,
+            ));
+        }
+
+        let area = self
+            .player_state
+            .borrow()
+            .get_topology()
+            .constrain_area(Chaos(ChaosArea::new(positions)), layer);
+
+        DamageArea {
+            damage_amount: self.get_damage(),
+            area,
+            entity: EntityCharacters::AttackMist(Style::new().light_cyan()),
+            duration: Duration::from_secs_f64(0.1),
+            blink: false,
+            destroys_walls: false,
+            stages: vec![],
+            weapon_stats: Some(self.stats.clone()),
+            source: None,
+        }
+    }
+
+    fn get_damage(&self) -> i32 {
+        ((f64::from(self.base_damage) * self.damage_scalar)
+            * self.player_state.borrow().stats.player_stats.damage_mult)
+            .ceil() as i32
+    }
+
+    fn get_stats(&self) -> &WeaponStats {
+        &self.stats
+    }
+
+    fn get_element(&self) -> Option<Elements> {
+        self.element
+    }
+}
+
+impl Poweruppable for Nova {
+    fn get_max_level(&self) -> i32 {
+        self.player_state.borrow().stats.game_stats.max_method_level
+    }
+
+    fn get_name(&self) -> String {
+        "NOVA".into()
+    }
+
+    fn get_powerup_type(&self) -> PowerupTypes {
+        PowerupTypes::Weapon
+    }
+
+    fn upgrade_desc(&self, level: i32) -> String {
+        match level {
+            1 => "NOVA will burst outwards in a cross centred on you.".into(),
+            2 => "Increase radius by 1, increase base damage by 1.".into(),
+            3 => "Increase base damage by 2.".into(),
+            4 => "Increase radius by 1, increase damage scalar by 25%".into(),
+            5 => "NOVA releases a second pulse in a ring around the cross. Increase damage scalar by 50%"
+                .into(),
+            _ => String::new(),
+        }
+    }
+
+    fn upgrade_self(&mut self, powerup: &DynPowerup) {
+        let from = powerup.get_current_level();
+        let to = powerup.get_new_level();
+        if to <= from {
+            return;
+        }
+        self.stats.level = to;
+
+        for i in (from + 1)..=to {
+            match i {
+                2 => {
+                    self.stats.size += 1;
+                    self.stats.damage_flat_boost += 1;
+                    self.base_damage += 1;
+                }
+                3 => {
+                    self.stats.damage_flat_boost += 2;
+                    self.base_damage += 2;
+                }
+                4 => {
+                    self.stats.size += 1;
+                    self.damage_scalar += 0.25;
+                }
+                5 => {
+                    self.damage_scalar += 0.5;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get_level(&self) -> i32 {
+        self.stats.level
+    }
+}
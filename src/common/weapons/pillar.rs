@@ -20,9 +20,14 @@ use crate::common::{
     stats::WeaponStats,
     weapons::{DamageArea, Weapon},
 };
-use ratatui::style::{Style, Stylize};
+use ratatui::style::Style;
 
-new_weapon!(Pillar, 5, 0, 6);
+new_weapon!(Pillar, 5, 0, 0.2);
+
+impl Pillar {
+    /// The level from which PILLAR breaks walls in its path.
+    const WALL_BREAK_LEVEL: i32 = 4;
+}
 
 impl Weapon for Pillar {
     fn attack(
@@ -31,14 +36,6 @@ impl Weapon for Pillar {
         _enemies: &[Enemy],
         layer: &Layer,
     ) -> DamageArea {
-        if self.cooldown_ticks > 0 && self.cooldown_ticks < Self::BASE_COOLDOWN {
-            self.cooldown_ticks += 1;
-            return DamageArea::new_empty();
-        } else if self.cooldown_ticks >= Self::BASE_COOLDOWN {
-            self.cooldown_ticks = 0;
-            return DamageArea::new_empty();
-        }
-
         let (x, _) = wielder.position.get();
 
         //size should be half the size for balancing
@@ -51,8 +48,6 @@ impl Weapon for Pillar {
 
         area.constrain(layer);
 
-        self.cooldown_ticks += 1;
-
         DamageArea {
             damage_amount: self.get_damage(),
 
@@ -60,7 +55,10 @@ impl Weapon for Pillar {
             entity: EntityCharacters::AttackWeak(Style::new().gray()),
             duration: Duration::from_secs_f64(0.05),
             blink: false,
+            destroys_walls: self.stats.level >= Self::WALL_BREAK_LEVEL,
+            stages: vec![],
             weapon_stats: Some(self.stats.clone()),
+            source: None,
         }
     }
 
@@ -70,6 +68,10 @@ impl Weapon for Pillar {
             .ceil() as i32
     }
 
+    fn get_stats(&self) -> &WeaponStats {
+        &self.stats
+    }
+
     fn get_element(&self) -> Option<Elements> {
         self.element
     }
@@ -94,7 +96,7 @@ impl Poweruppable for Pillar {
                 .into(),
             2 => "Increase size by 1, increase base damage by 1".into(),
             3 => "Increase base damage by 2".into(),
-            4 => "Increase damage scalar by 25%".into(),
+            4 => "Increase damage scalar by 25%. PILLAR now breaks walls".into(),
             5 => "Increase damage scalar by 75%".into(),
             //TODO: add element
             _ => String::new(),
@@ -12,7 +12,7 @@ use crate::new_weapon;
 use crate::prelude::Duration;
 use ratatui::prelude::Style;
 
-new_weapon!(Row, 6, 0, 5);
+new_weapon!(Row, 6, 0, 0.25);
 
 impl Weapon for Row {
     fn attack(
@@ -21,14 +21,6 @@ impl Weapon for Row {
         _enemies: &[Enemy],
         layer: &Layer,
     ) -> DamageArea {
-        if self.cooldown_ticks > 0 && self.cooldown_ticks < Self::BASE_COOLDOWN {
-            self.cooldown_ticks += 1;
-            return DamageArea::new_empty();
-        } else if self.cooldown_ticks >= Self::BASE_COOLDOWN {
-            self.cooldown_ticks = 0;
-            return DamageArea::new_empty();
-        }
-
         let (_, y) = wielder.position.get();
 
         //size should be half the size for balancing
@@ -41,15 +33,16 @@ impl Weapon for Row {
 
         area.constrain(layer);
 
-        self.cooldown_ticks += 1;
-
         DamageArea {
             damage_amount: self.get_damage(),
             area: Square(area),
             entity: EntityCharacters::AttackWeak(Style::new().gray()),
             duration: Duration::from_secs_f64(0.05),
             blink: false,
+            destroys_walls: false,
+            stages: vec![],
             weapon_stats: Some(self.stats.clone()),
+            source: None,
         }
     }
 
@@ -59,6 +52,10 @@ impl Weapon for Row {
             .ceil() as i32
     }
 
+    fn get_stats(&self) -> &WeaponStats {
+        &self.stats
+    }
+
     fn get_element(&self) -> Option<Elements> {
         self.element
     }
@@ -0,0 +1,138 @@
+use crate::common::PlayerStateRef;
+
+use crate::{
+    common::{debuffs::Elements, powerup::PowerupTypes},
+    new_weapon,
+    prelude::Duration,
+};
+
+use crate::common::character::CharacterPositionData;
+use crate::common::enemies::enemy::Enemy;
+use crate::common::map::Layer;
+use crate::common::turrets::DeployedTurret;
+use crate::common::{
+    powerup::{DynPowerup, Poweruppable},
+    stats::WeaponStats,
+    weapons::{DamageArea, Weapon},
+};
+
+new_weapon!(Turret, 1, 4, 0.2);
+
+impl Turret {
+    const BASE_LIFETIME: Duration = Duration::from_secs(5);
+    /// The level from which turrets last longer.
+    const LONG_LIFETIME_LEVEL: i32 = 3;
+    const LONG_LIFETIME: Duration = Duration::from_secs(8);
+    const BASE_FIRES_P_S: f64 = 2.;
+    /// The level from which turrets fire twice as fast.
+    const RAPID_FIRE_LEVEL: i32 = 5;
+}
+
+impl Weapon for Turret {
+    /// TURRET doesn't hit anything itself; its damage comes from the turrets it deploys.
+    fn attack(
+        &mut self,
+        _wielder: CharacterPositionData,
+        _enemies: &[Enemy],
+        _layer: &Layer,
+    ) -> DamageArea {
+        DamageArea::new_empty()
+    }
+
+    fn deploy(&mut self, wielder: &CharacterPositionData) -> Option<DeployedTurret> {
+        let lifetime = if self.stats.level >= Self::LONG_LIFETIME_LEVEL {
+            Self::LONG_LIFETIME
+        } else {
+            Self::BASE_LIFETIME
+        };
+
+        let fires_p_s = if self.stats.level >= Self::RAPID_FIRE_LEVEL {
+            Self::BASE_FIRES_P_S * 2.
+        } else {
+            Self::BASE_FIRES_P_S
+        };
+
+        Some(DeployedTurret::new(
+            wielder.position.clone(),
+            self.get_damage(),
+            self.stats.size,
+            fires_p_s,
+            lifetime,
+            self.stats.clone(),
+        ))
+    }
+
+    fn get_damage(&self) -> i32 {
+        ((f64::from(self.base_damage) * self.damage_scalar)
+            * self.player_state.borrow().stats.player_stats.damage_mult)
+            .ceil() as i32
+    }
+
+    fn get_stats(&self) -> &WeaponStats {
+        &self.stats
+    }
+
+    fn get_element(&self) -> Option<Elements> {
+        self.element
+    }
+}
+
+impl Poweruppable for Turret {
+    fn get_max_level(&self) -> i32 {
+        self.player_state.borrow().stats.game_stats.max_method_level
+    }
+
+    fn get_name(&self) -> String {
+        "TURRET".into()
+    }
+
+    fn get_powerup_type(&self) -> PowerupTypes {
+        PowerupTypes::Weapon
+    }
+
+    fn upgrade_desc(&self, level: i32) -> String {
+        match level {
+            1 => {
+                "TURRET will leave behind a turret which fires at nearby enemies for a few seconds."
+                    .into()
+            }
+            2 => "Increase range by 1, increase base damage by 1.".into(),
+            3 => "Turrets last 3 seconds longer.".into(),
+            4 => "Increase base damage by 2, increase damage scalar by 25%".into(),
+            5 => "Turrets fire twice as fast. Increase damage scalar by 50%".into(),
+            _ => String::new(),
+        }
+    }
+
+    fn upgrade_self(&mut self, powerup: &DynPowerup) {
+        let from = powerup.get_current_level();
+        let to = powerup.get_new_level();
+        if to <= from {
+            return;
+        }
+        self.stats.level = to;
+
+        for i in (from + 1)..=to {
+            match i {
+                2 => {
+                    self.stats.size += 1;
+                    self.stats.damage_flat_boost += 1;
+                    self.base_damage += 1;
+                }
+                4 => {
+                    self.stats.damage_flat_boost += 2;
+                    self.base_damage += 2;
+                    self.damage_scalar += 0.25;
+                }
+                5 => {
+                    self.damage_scalar += 0.5;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn get_level(&self) -> i32 {
+        self.stats.level
+    }
+}
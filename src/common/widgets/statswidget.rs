@@ -2,12 +2,12 @@ use crate::common::PlayerStateRef;
 use crate::common::charms::CharmWrapper;
 use crate::common::enemies::enemywrangler::EnemyWrangler;
 use crate::common::utils::{convert_range, trim_string};
-use crate::common::weapons::{Weapon, WeaponWrapper, get_strongest_weapon};
+use crate::common::weapons::{WeaponWrapper, get_strongest_weapon};
 use crate::common::widgets::inviconwidget::InvIconWidget;
 use ratatui::buffer::Buffer;
 use ratatui::layout::{Constraint, Layout, Rect};
 use ratatui::prelude::Widget;
-use ratatui::style::{Style, Stylize};
+use ratatui::style::Style;
 use ratatui::text::{Line, Text};
 use ratatui::widgets::{Block, BorderType};
 
@@ -34,6 +34,7 @@ impl<'a> StatsWidget<'a> {
     }
 
     #[must_use]
+    #[allow(clippy::too_many_lines)]
     pub fn get_stat_vecs(&self) -> (Vec<Line<'_>>, Vec<Line<'_>>) {
         // Extract stats and their corresponding labels into separate vectors
         let mut stat_labels = Vec::new();
@@ -44,7 +45,7 @@ impl<'a> StatsWidget<'a> {
         let weapon_stats = &self.player_state.borrow().stats.weapon_stats;
 
         let best_weapon = get_strongest_weapon(self.weapons)
-            .map(|w| w.get_damage())
+            .map(WeaponWrapper::get_damage)
             .unwrap_or_default();
 
         stat_labels.push(Line::raw("damage_boost").left_aligned());
@@ -76,6 +77,20 @@ impl<'a> StatsWidget<'a> {
             Line::raw(trim_string(format!("x{}", player_stats.health_mult), 5)).right_aligned(),
         );
 
+        if player_stats.shield > 0 {
+            stat_labels.push(Line::raw("shield").left_aligned());
+            stat_values.push(
+                Line::raw(trim_string(format!("{}", player_stats.shield), 5)).right_aligned(),
+            );
+        }
+
+        if player_stats.lifesteal > 0. {
+            stat_labels.push(Line::raw("lifesteal").left_aligned());
+            stat_values.push(
+                Line::raw(trim_string(format!("{}", player_stats.lifesteal), 5)).right_aligned(),
+            );
+        }
+
         stat_labels.push(Line::from(""));
         stat_values.push(Line::from(""));
 
@@ -220,11 +235,11 @@ impl StatsWidget<'_> {
 
         for (i, charm) in charms.iter().enumerate() {
             let icon = InvIconWidget::new(charm.get_inner());
-            icon.render(icons_top[i], buf)
+            icon.render(icons_top[i], buf);
         }
         for (i, weapon) in weapons.iter().enumerate() {
             let icon = InvIconWidget::new(weapon.get_inner());
-            icon.render(icons_bottom[i], buf)
+            icon.render(icons_bottom[i], buf);
         }
     }
 }
@@ -0,0 +1,48 @@
+//! The game itself, shared by the terminal and WASM builds. `main.rs` starts the right one for the
+//! platform.
+#![allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_wrap,
+    clippy::cast_precision_loss,
+    clippy::if_not_else
+)]
+extern crate core;
+
+pub mod common;
+
+#[cfg(not(target_family = "wasm"))]
+pub mod terminal;
+
+#[cfg(target_family = "wasm")]
+pub mod wasm;
+
+#[cfg(target_family = "wasm")]
+pub mod prelude {
+    pub type KeyCode = ratzilla::event::KeyCode;
+    pub type KeyEvent = ratzilla::event::KeyEvent;
+    pub type Duration = web_time::Duration;
+    pub type SystemTime = web_time::SystemTime;
+    pub type Instant = web_time::Instant;
+
+    pub use crate::wasm::app::save_progress;
+    #[cfg(feature = "leaderboard")]
+    pub use crate::wasm::request::send_request;
+    #[cfg(feature = "netplay")]
+    pub use crate::wasm::socket::Socket;
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub mod prelude {
+    pub type KeyCode = crossterm::event::KeyCode;
+    pub type KeyEvent = crossterm::event::KeyEvent;
+    pub type Duration = std::time::Duration;
+    pub type SystemTime = std::time::SystemTime;
+    pub type Instant = std::time::Instant;
+
+    pub use crate::terminal::app::save_progress;
+    #[cfg(feature = "leaderboard")]
+    pub use crate::terminal::request::send_request;
+    #[cfg(feature = "netplay")]
+    pub use crate::terminal::socket::Socket;
+}
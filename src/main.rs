@@ -1,51 +1,14 @@
 //! The main entry point for the application, responsible for initializing and running
 //! the appropriate version (terminal or WASM) of the game.
-#![allow(
-    clippy::cast_possible_truncation,
-    clippy::cast_sign_loss,
-    clippy::cast_possible_wrap,
-    clippy::cast_precision_loss,
-    clippy::if_not_else
-)]
-extern crate core;
-
-pub mod common;
-
-#[cfg(not(target_family = "wasm"))]
-pub mod terminal;
-
-#[cfg(target_family = "wasm")]
-pub mod wasm;
-
-#[cfg(target_family = "wasm")]
-pub mod prelude {
-    pub type KeyCode = ratzilla::event::KeyCode;
-    pub type KeyEvent = ratzilla::event::KeyEvent;
-    pub type Duration = web_time::Duration;
-    pub type SystemTime = web_time::SystemTime;
-    pub type Instant = web_time::Instant;
-
-    pub use crate::wasm::app::save_progress;
-}
-
-#[cfg(not(target_family = "wasm"))]
-pub mod prelude {
-    pub type KeyCode = crossterm::event::KeyCode;
-    pub type KeyEvent = crossterm::event::KeyEvent;
-    pub type Duration = std::time::Duration;
-    pub type SystemTime = std::time::SystemTime;
-    pub type Instant = std::time::Instant;
-
-    pub use crate::terminal::app::save_progress;
-}
 
 /// The main entry point for the terminal application.
 #[cfg(not(target_family = "wasm"))]
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    use crate::terminal::app::App;
+    use dispair::terminal::{self, app::App, logging::init_logging};
 
     color_eyre::install()?;
+    let _log_guard = init_logging();
 
     let mut app = App::new();
 
@@ -63,9 +26,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 fn main() -> std::io::Result<()> {
     use std::{cell::RefCell, rc::Rc};
 
-    use crate::wasm::app::App;
+    use dispair::wasm::app::App;
+    use dispair::wasm::logging::init_logging;
+    use dispair::wasm::params::LaunchParams;
+
+    init_logging();
 
-    let app = App::new();
+    let app = App::new(LaunchParams::from_url());
 
     App::run(&Rc::new(RefCell::new(app)))
 }
@@ -1,29 +1,37 @@
 //! This module defines the main application structure for the terminal UI.
 //! It handles the main loop, event handling, and switching between different views (menu, game, upgrades).
 
-use crate::common::{FRAME_RATE, Goto, TICK_RATE};
-use crate::prelude::{KeyCode, KeyEvent};
-use ratatui::{
-    Frame,
-    layout::{Constraint, Layout},
-    style::{Style, Stylize},
-    symbols::border,
-    text::Text,
-    widgets::{Block, List, ListItem, ListState},
-};
-use serde::de::Error as serdeError;
+use crate::common::{FRAME_RATE, TICK_RATE};
+use crate::prelude::KeyEvent;
+use ratatui::Frame;
+#[cfg(feature = "cloudsync")]
+use ratatui::{style::Stylize, text::Line};
 use std::cell::RefCell;
-use std::fs::{File, OpenOptions};
 use std::rc::Rc;
-
+#[cfg(feature = "cloudsync")]
+use std::time::SystemTime;
+
+use super::capture::CaptureSaver;
+#[cfg(feature = "cloudsync")]
+use super::cloudsync::{CloudSync, SyncStatus};
+#[cfg(feature = "discord")]
+use super::presence::DiscordPresence;
+use super::save::FileStore;
 use super::tui::{Event, Tui};
 
+use crate::common::app::{AppCore, Platform};
+use crate::common::apperror::AppError;
+use crate::common::challenge::Challenge;
 use crate::common::game::Game;
+use crate::common::mainmenu::MenuChoice;
+#[cfg(feature = "cloudsync")]
+use crate::common::popups::syncconflict::SyncConflict;
+use crate::common::savestore::{MAIN_SLOT, SaveStore};
 use crate::common::sound::SoundWrangler;
+use crate::common::toasts::ToastSeverity;
 use crate::common::upgrades::upgrade::PlayerState;
-use crate::common::utils::{center_horizontal, center_vertical};
 
-/// Saves the player's progress to a JSON file.
+/// Saves the player's progress to the main slot's JSON file.
 ///
 /// # Panics
 ///
@@ -31,30 +39,15 @@ use crate::common::utils::{center_horizontal, center_vertical};
 ///
 /// # Errors
 ///
-/// Can throw `serde_json::Error` if it cannot create directory, cannot access save file, or cannot write to save file
-pub fn save_progress(player_state: &PlayerState) -> Result<(), serde_json::Error> {
-    let path = dirs::config_dir()
-        .expect("Failed to get config directory")
-        .join("dispair")
-        .join("player_state.json");
-
-    std::fs::create_dir_all(path.parent().unwrap())
-        .map_err(|e| serde_json::Error::custom(e.to_string()))?;
-
-    let save_file = OpenOptions::new()
-        .read(true)
-        .create(true)
-        .write(true)
-        .truncate(true)
-        .open(path)
-        .map_err(|e| serde_json::Error::custom(e.to_string()))?;
-
-    serde_json::to_writer(save_file, player_state)?;
-
-    Ok(())
+/// Errors with `AppError::Save` if it cannot create directory, cannot access save file, or cannot write to save file
+#[tracing::instrument(skip_all, err)]
+pub fn save_progress(player_state: &PlayerState) -> Result<(), AppError> {
+    FileStore::default()
+        .save(MAIN_SLOT, player_state)
+        .map_err(|err| AppError::Save(err.to_string()))
 }
 
-/// Loads the player's progress from a JSON file.
+/// Loads the player's progress from the main slot's JSON file.
 ///
 /// # Panics
 ///
@@ -62,50 +55,137 @@ pub fn save_progress(player_state: &PlayerState) -> Result<(), serde_json::Error
 ///
 /// # Errors
 ///
-/// Can throw `serde_json::Error` if it cannot create directory, cannot access save file, or cannot write to save file
-pub fn load_progress() -> Result<PlayerState, serde_json::Error> {
-    let path = dirs::config_dir()
-        .expect("Failed to get config directory")
-        .join("dispair")
-        .join("player_state.json");
+/// Errors with `AppError::Load` if there is no save, or it cannot be read
+#[tracing::instrument(skip_all)]
+pub fn load_progress() -> Result<PlayerState, AppError> {
+    FileStore::default()
+        .load(MAIN_SLOT)
+        .map_err(|err| AppError::Load(err.to_string()))
+}
+
+/// Starts saving any screenshot or clip the game has captured, and tells the player where the
+/// finished ones went.
+fn save_captures(game: &mut Game, captures: &CaptureSaver) {
+    if let Some(output) = game.take_capture() {
+        captures.save(output);
+    }
 
-    let save_file = File::open(path).map_err(|e| serde_json::Error::custom(e.to_string()))?;
+    for result in captures.poll() {
+        match result {
+            Ok(path) => {
+                game.notify_capture(format!("saved {}", path.display()), ToastSeverity::Success);
+            }
+            Err(err) => {
+                game.notify_capture(format!("couldn't save: {err}"), ToastSeverity::Warning);
+            }
+        }
+    }
+}
 
-    let i: PlayerState = serde_json::from_reader(save_file)?;
+/// Reads the challenge code following the `--challenge` argument, if it's given and valid.
+fn get_challenge_arg() -> Option<Challenge> {
+    let mut args = std::env::args()
+        .skip_while(|arg| arg != "--challenge")
+        .skip(1);
+    let code = args.next()?;
+    let challenge = Challenge::decode(&code);
+    if challenge.is_none() {
+        tracing::warn!(code, "ignoring malformed challenge code");
+    }
+    challenge
+}
 
-    Ok(i)
+/// Where the terminal saves progress, and the options it was launched with.
+struct TerminalPlatform {
+    /// Whether runs show only the event log, from the `--log-only` flag.
+    log_only: bool,
+    /// The challenge to start straight into, from the `--challenge <CODE>` argument.
+    challenge: Option<Challenge>,
+    /// Keeps the save in step with a copy elsewhere, if the player has set up where.
+    #[cfg(feature = "cloudsync")]
+    cloud_sync: Option<CloudSync>,
 }
 
-/// The main application struct, which manages the state of the different views.
+impl Platform for TerminalPlatform {
+    fn save(&mut self, player_state: &PlayerState) -> Result<(), AppError> {
+        save_progress(player_state)
+    }
+
+    fn load(&mut self) -> Result<PlayerState, AppError> {
+        load_progress()
+    }
+
+    fn check_save(&mut self) -> Result<bool, AppError> {
+        FileStore::default()
+            .has_save(MAIN_SLOT)
+            .map_err(|err| AppError::Load(err.to_string()))
+    }
+
+    /// Applies the session-only settings. The challenge is only played the first time.
+    fn prepare_game(&mut self, player_state: &mut PlayerState) -> Option<u64> {
+        player_state.settings.log_only = self.log_only;
+
+        let challenge = self.challenge.take()?;
+        let seed = challenge.seed;
+        player_state.accept_challenge(challenge);
+        Some(seed)
+    }
+
+    fn on_menu(&mut self) {
+        #[cfg(feature = "cloudsync")]
+        if let Some(cloud_sync) = &mut self.cloud_sync {
+            cloud_sync.sync();
+        }
+    }
+}
+
+/// The main application struct, which runs the terminal and passes its events on to the app.
 pub struct App {
-    game: Option<Game>,
+    core: AppCore<TerminalPlatform>,
     exit: bool,
-    player_state: Option<PlayerState>,
-    sound_wrangler: Rc<RefCell<SoundWrangler>>,
     pub frame_rate: f64,
     pub tick_rate: f64,
-    current_selection: ListState,
-    save_exists: bool,
+    /// The player's Discord presence, if an application id has been set.
+    #[cfg(feature = "discord")]
+    presence: Option<DiscordPresence>,
+    /// The prompt to pick a save, while the synced copy and this machine's both have new progress.
+    #[cfg(feature = "cloudsync")]
+    sync_conflict: Option<SyncConflict>,
+    captures: CaptureSaver,
 }
 
 impl App {
     /// Creates a new `App` instance.
     #[must_use]
     pub fn new() -> Self {
-        let mut out = Self {
-            game: None,
+        let platform = TerminalPlatform {
+            log_only: std::env::args().any(|arg| arg == "--log-only"),
+            challenge: get_challenge_arg(),
+            #[cfg(feature = "cloudsync")]
+            cloud_sync: CloudSync::from_env(),
+        };
+        let mut core = AppCore::new(
+            platform,
+            vec![MenuChoice::NewGame, MenuChoice::Continue, MenuChoice::Quit],
+        );
+        core.sound_wrangler = Some(Rc::new(RefCell::new(SoundWrangler::new())));
+
+        #[cfg(feature = "cloudsync")]
+        if let Some(cloud_sync) = &mut core.platform.cloud_sync {
+            cloud_sync.sync();
+        }
+
+        Self {
+            core,
             exit: false,
-            player_state: None,
-            sound_wrangler: Rc::new(RefCell::new(SoundWrangler::new())),
             frame_rate: FRAME_RATE,
             tick_rate: TICK_RATE,
-            current_selection: ListState::default(),
-            save_exists: load_progress().is_ok(),
-        };
-
-        out.current_selection.select_first();
-
-        out
+            #[cfg(feature = "discord")]
+            presence: DiscordPresence::from_env(),
+            #[cfg(feature = "cloudsync")]
+            sync_conflict: None,
+            captures: CaptureSaver::new(),
+        }
     }
 
     /// Runs the main application loop.
@@ -127,7 +207,25 @@ impl App {
                 self.handle_event(&event);
             }
 
-            if self.exit {
+            if let Some(game) = self.core.get_game() {
+                let frame_limit = game.get_frame_limit();
+                if (frame_limit - tui.frame_rate).abs() > f64::EPSILON {
+                    tui.set_frame_rate(frame_limit);
+                }
+            }
+
+            // quitting waits for the sync in progress, so the latest progress isn't left behind
+            #[cfg(feature = "cloudsync")]
+            let syncing = self
+                .core
+                .platform
+                .cloud_sync
+                .as_ref()
+                .is_some_and(CloudSync::is_busy);
+            #[cfg(not(feature = "cloudsync"))]
+            let syncing = false;
+
+            if self.exit && !syncing {
                 break;
             }
         }
@@ -142,123 +240,118 @@ impl App {
                 self.on_tick();
             }
             Event::Render => {
-                self.on_frame();
+                self.core.on_frame();
             }
             Event::Key(key_event) => self.handle_key_event(key_event),
+            Event::KeyRelease(key_event) => {
+                if let Some(game) = self.core.get_game_mut() {
+                    game.handle_key_release(key_event);
+                }
+            }
+            Event::Resize(width, height) => {
+                if let Some(game) = self.core.get_game_mut() {
+                    game.resize(*width, *height);
+                }
+            }
             _ => {}
         }
     }
 
     /// Handles key press events.
     pub fn handle_key_event(&mut self, key_event: &KeyEvent) {
-        if !key_event.is_press() {
+        if key_event.is_release() {
             return;
         }
-        if let Some(game) = &mut self.game {
-            game.handle_key_event(key_event);
-        } else {
-            match key_event.code {
-                KeyCode::Char('s') | KeyCode::Down => self.select_next(),
-                KeyCode::Char('w') | KeyCode::Up => self.select_prev(),
-                KeyCode::Enter => self.confirm_curr(),
-                KeyCode::Esc => self.exit = true,
-                _ => {}
+
+        #[cfg(feature = "cloudsync")]
+        if self.core.is_on_menu()
+            && let Some(sync_conflict) = &mut self.sync_conflict
+        {
+            if let Some(keep_local) = sync_conflict.handle_key_event(key_event) {
+                self.sync_conflict = None;
+                if let Some(cloud_sync) = &mut self.core.platform.cloud_sync {
+                    cloud_sync.resolve(keep_local);
+                }
             }
+            return;
         }
-    }
 
-    fn select_next(&mut self) {
-        self.current_selection.select_next();
-        if !self.save_exists && self.current_selection.selected().unwrap_or(0) == 1 {
-            self.select_next();
+        if self.core.handle_key_event(key_event) {
+            self.exit = true;
         }
     }
 
-    fn select_prev(&mut self) {
-        self.current_selection.select_previous();
-        if !self.save_exists && self.current_selection.selected().unwrap_or(0) == 1 {
-            self.select_prev();
+    fn ui(&mut self, frame: &mut Frame) {
+        #[cfg(feature = "cloudsync")]
+        let footer = self.get_sync_status();
+        #[cfg(not(feature = "cloudsync"))]
+        let footer = None;
+
+        self.core.render(frame, footer);
+
+        #[cfg(feature = "cloudsync")]
+        if self.core.is_on_menu()
+            && let Some(sync_conflict) = &mut self.sync_conflict
+        {
+            let now = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_millis() as u64);
+            sync_conflict.render(frame, now);
         }
     }
 
-    fn confirm_curr(&mut self) {
-        match self.current_selection.selected() {
-            Some(0) => {
-                self.player_state = Some(PlayerState::default());
-                self.game = Some(Game::new(
-                    self.player_state.clone().unwrap(),
-                    self.sound_wrangler.clone(),
-                ));
-            }
-            Some(1) => {
-                self.player_state = Some(load_progress().unwrap_or_default());
-                self.game = Some(Game::new(
-                    self.player_state.clone().unwrap(),
-                    self.sound_wrangler.clone(),
-                ));
-            }
-            Some(2) => self.exit = true,
-            _ => {}
+    fn on_tick(&mut self) {
+        self.core.on_tick();
+
+        if let Some(game) = self.core.get_game_mut() {
+            save_captures(game, &self.captures);
         }
-    }
 
-    fn ui(&mut self, frame: &mut Frame) {
-        if let Some(ref mut game) = self.game {
-            game.render(frame);
-        } else {
-            self.render_menu(frame);
+        #[cfg(feature = "cloudsync")]
+        self.tick_cloud_sync();
+
+        #[cfg(feature = "discord")]
+        if let Some(presence) = &mut self.presence {
+            let status = self
+                .core
+                .get_game()
+                .map_or_else(|| "In the main menu".into(), Game::get_presence);
+            presence.update(status);
         }
     }
 
-    fn on_tick(&mut self) {
-        if let Some(game) = &mut self.game {
-            game.on_tick();
-            if game.get_goto().clone() == Goto::Menu {
-                self.player_state = Some(game.get_player_state());
-                save_progress(self.player_state.as_ref().expect("it's here")).unwrap_or(());
-                self.save_exists = load_progress().is_ok();
-                self.game = None;
-            }
+    /// Handles finished sync requests while on the main menu. Results that turn up during a run
+    /// wait until it's over, so the run's progress is weighed against them.
+    #[cfg(feature = "cloudsync")]
+    fn tick_cloud_sync(&mut self) {
+        if self.core.get_game().is_some() {
+            return;
         }
-    }
+        let Some(cloud_sync) = &mut self.core.platform.cloud_sync else {
+            return;
+        };
 
-    fn on_frame(&mut self) {
-        if let Some(game) = &mut self.game {
-            game.on_frame();
+        if let Some(sync_conflict) = cloud_sync.tick() {
+            self.sync_conflict = Some(sync_conflict);
+        }
+        if cloud_sync.take_downloaded() {
+            self.core.check_save();
         }
     }
 
-    /// Renders the main menu.
-    pub fn render_menu(&mut self, frame: &mut Frame) {
-        let block = Block::bordered().border_set(border::DOUBLE);
-
-        let [top, bottom] = Layout::vertical([Constraint::Percentage(25), Constraint::Fill(1)])
-            .areas(block.inner(frame.area()));
-
-        let title_area = center_vertical(top, 1);
-
-        let title = Text::from("Dispair").centered();
-
-        let options_area = center_vertical(center_horizontal(bottom, 12), 3);
-
-        let options_items = vec![
-            ListItem::from("New Game"),
-            ListItem::from("Continue").style(if !self.save_exists {
-                Style::new().dark_gray()
-            } else {
-                Style::new()
-            }),
-            ListItem::from("Quit"),
-        ];
-
-        let options = List::new(options_items)
-            .highlight_symbol("> ")
-            .highlight_style(Style::new().bold());
-
-        frame.render_widget(block, frame.area());
+    /// Describes how syncing the save is going, for the main menu.
+    #[cfg(feature = "cloudsync")]
+    fn get_sync_status(&self) -> Option<Line<'static>> {
+        let status = match self.core.platform.cloud_sync.as_ref()?.get_status() {
+            SyncStatus::Idle => "cloud save: waiting".to_string(),
+            SyncStatus::Syncing if self.exit => "cloud save: finishing the sync...".to_string(),
+            SyncStatus::Syncing => "cloud save: syncing...".to_string(),
+            SyncStatus::Synced => "cloud save: synced".to_string(),
+            SyncStatus::Conflict => "cloud save: pick a save to keep".to_string(),
+            SyncStatus::Failed(reason) => format!("cloud save: couldn't sync, {reason}"),
+        };
 
-        frame.render_widget(title, title_area);
-        frame.render_stateful_widget(options, options_area, &mut self.current_selection);
+        Some(Line::from(status.dark_gray()))
     }
 }
 
@@ -0,0 +1,64 @@
+//! This module saves screenshots and clips for the terminal build, into `dispair/captures` in the
+//! user's config directory. Clips are encoded on their own thread so the game doesn't stall.
+
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+
+use crate::common::capture::CaptureOutput;
+
+/// Saves captures in the background, reporting where each one ended up.
+pub struct CaptureSaver {
+    finished: mpsc::Sender<Result<PathBuf, String>>,
+    results: mpsc::Receiver<Result<PathBuf, String>>,
+}
+
+impl CaptureSaver {
+    #[must_use]
+    pub fn new() -> Self {
+        let (finished, results) = mpsc::channel();
+
+        Self { finished, results }
+    }
+
+    /// Starts saving `output`. How it went turns up in [`CaptureSaver::poll`].
+    pub fn save(&self, output: CaptureOutput) {
+        let finished = self.finished.clone();
+
+        thread::spawn(move || {
+            let result = match output {
+                CaptureOutput::Screenshot { name, contents } => write(&name, contents.as_bytes()),
+                CaptureOutput::Clip { name, encoder } => {
+                    encoder.finish().and_then(|gif| write(&name, &gif))
+                }
+            };
+            let _ = finished.send(result);
+        });
+    }
+
+    /// Takes how each save that has finished since the last poll went.
+    #[must_use]
+    pub fn poll(&self) -> Vec<Result<PathBuf, String>> {
+        self.results.try_iter().collect()
+    }
+}
+
+impl Default for CaptureSaver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Writes a capture called `name` into the captures directory, returning its path.
+fn write(name: &str, contents: &[u8]) -> Result<PathBuf, String> {
+    let dir = dirs::config_dir()
+        .ok_or("couldn't find the config directory")?
+        .join("dispair")
+        .join("captures");
+    std::fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+
+    let path = dir.join(name);
+    std::fs::write(&path, contents).map_err(|err| err.to_string())?;
+
+    Ok(path)
+}
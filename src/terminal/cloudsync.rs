@@ -0,0 +1,424 @@
+//! This module syncs the terminal's save with a copy kept on a `WebDAV` server, or any storage that
+//! serves a file with GET and replaces it with PUT, like an S3-compatible bucket behind a gateway.
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::{Duration, SystemTime};
+
+use reqwest::{Client, RequestBuilder, StatusCode};
+
+use crate::common::popups::syncconflict::{SaveSummary, SyncConflict};
+use crate::common::remotesync::{
+    RemoteSync, SyncAction, SyncBase, SyncEvent, SyncedSave, get_sync_action,
+};
+use crate::common::savestore::{MAIN_SLOT, SaveStore, parse_save};
+use crate::terminal::save::{FileStore, SYNC_FILE_NAME};
+
+/// Where the synced copy is kept, and how to sign in to it.
+#[derive(Debug, Clone)]
+pub struct SyncConfig {
+    /// The URL of the synced save file itself.
+    pub url: String,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    /// A bearer token, for storage that takes one in place of a user and password.
+    pub token: Option<String>,
+}
+
+impl SyncConfig {
+    /// Reads the configuration from the environment, if a sync URL has been set.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        Some(Self {
+            url: std::env::var("DISPAIR_SYNC_URL").ok()?,
+            user: std::env::var("DISPAIR_SYNC_USER").ok(),
+            password: std::env::var("DISPAIR_SYNC_PASSWORD").ok(),
+            token: std::env::var("DISPAIR_SYNC_TOKEN").ok(),
+        })
+    }
+
+    /// Signs `request` in with whichever credentials are set.
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match (&self.token, &self.user) {
+            (Some(token), _) => request.bearer_auth(token),
+            (None, Some(user)) => request.basic_auth(user, self.password.as_ref()),
+            (None, None) => request,
+        }
+    }
+}
+
+/// Keeps the synced copy over HTTP, sending requests in the background on the tokio runtime.
+pub struct WebDavSync {
+    config: SyncConfig,
+    client: Client,
+    finished: mpsc::Sender<SyncEvent>,
+    events: mpsc::Receiver<SyncEvent>,
+}
+
+impl WebDavSync {
+    /// How long a request can take before it's given up on.
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    #[must_use]
+    pub fn new(config: SyncConfig) -> Self {
+        let (finished, events) = mpsc::channel();
+
+        Self {
+            config,
+            client: Client::builder()
+                .timeout(Self::TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            finished,
+            events,
+        }
+    }
+}
+
+impl RemoteSync for WebDavSync {
+    fn fetch(&self) {
+        let request = self.config.authorize(self.client.get(&self.config.url));
+        let finished = self.finished.clone();
+
+        tokio::spawn(async move {
+            let event = match request.send().await {
+                Ok(response) if response.status() == StatusCode::NOT_FOUND => {
+                    SyncEvent::Fetched(None)
+                }
+                Ok(response) => match response.error_for_status() {
+                    Ok(response) => match response.text().await {
+                        Ok(body) => SyncEvent::Fetched(Some(body)),
+                        Err(err) => SyncEvent::Failed(err.to_string()),
+                    },
+                    Err(err) => SyncEvent::Failed(err.to_string()),
+                },
+                Err(err) => SyncEvent::Failed(err.to_string()),
+            };
+            let _ = finished.send(event);
+        });
+    }
+
+    fn upload(&self, body: String) {
+        let request = self
+            .config
+            .authorize(self.client.put(&self.config.url))
+            .header("Content-Type", "application/json")
+            .body(body);
+        let finished = self.finished.clone();
+
+        tokio::spawn(async move {
+            let event = match request
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+            {
+                Ok(_) => SyncEvent::Uploaded,
+                Err(err) => SyncEvent::Failed(err.to_string()),
+            };
+            let _ = finished.send(event);
+        });
+    }
+
+    fn poll(&self) -> Vec<SyncEvent> {
+        self.events.try_iter().collect()
+    }
+}
+
+/// How syncing is going, for the main menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncStatus {
+    Idle,
+    Syncing,
+    Synced,
+    /// There's no telling which copy to keep, and the player is picking one.
+    Conflict,
+    Failed(String),
+}
+
+/// Keeps the save file in step with the synced copy, whichever changed last.
+pub struct CloudSync<R: RemoteSync = WebDavSync> {
+    remote: R,
+    store: FileStore,
+    status: SyncStatus,
+    /// Where the copies are in step once the upload in progress finishes.
+    uploading: Option<SyncBase>,
+    /// The synced copy, while the player picks between it and this machine's.
+    conflict: Option<SyncedSave>,
+    /// Whether the save file has been replaced since the app last checked.
+    downloaded: bool,
+}
+
+impl CloudSync {
+    /// Starts syncing with the storage set in the environment, if any.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        SyncConfig::from_env()
+            .map(|config| Self::new(WebDavSync::new(config), FileStore::default()))
+    }
+}
+
+impl<R: RemoteSync> CloudSync<R> {
+    #[must_use]
+    pub fn new(remote: R, store: FileStore) -> Self {
+        Self {
+            remote,
+            store,
+            status: SyncStatus::Idle,
+            uploading: None,
+            conflict: None,
+            downloaded: false,
+        }
+    }
+
+    #[must_use]
+    pub fn get_status(&self) -> &SyncStatus {
+        &self.status
+    }
+
+    /// Checks whether the save file has been replaced with the synced copy since this was last
+    /// called.
+    pub fn take_downloaded(&mut self) -> bool {
+        std::mem::take(&mut self.downloaded)
+    }
+
+    /// Whether a request is still on its way, which quitting waits for.
+    #[must_use]
+    pub fn is_busy(&self) -> bool {
+        self.status == SyncStatus::Syncing
+    }
+
+    /// Starts bringing the copies back in step, unless that's already happening.
+    pub fn sync(&mut self) {
+        if matches!(self.status, SyncStatus::Syncing | SyncStatus::Conflict) {
+            return;
+        }
+
+        self.status = SyncStatus::Syncing;
+        self.remote.fetch();
+    }
+
+    /// Handles finished requests. Returns a prompt for the player if there's no telling which
+    /// copy to keep.
+    pub fn tick(&mut self) -> Option<SyncConflict> {
+        let mut prompt = None;
+
+        for event in self.remote.poll() {
+            match event {
+                SyncEvent::Fetched(None) => prompt = self.act(None),
+                SyncEvent::Fetched(Some(body)) => match serde_json::from_str(&body) {
+                    Ok(remote) => prompt = self.act(Some(remote)),
+                    Err(err) => self.fail(&format!("the synced save is damaged: {err}")),
+                },
+                SyncEvent::Uploaded => {
+                    if let Some(base) = self.uploading.take() {
+                        self.write_base(base);
+                    }
+                    self.status = SyncStatus::Synced;
+                }
+                SyncEvent::Failed(err) => self.fail(&err),
+            }
+        }
+
+        prompt
+    }
+
+    /// Settles a conflict, keeping this machine's save or replacing it with the synced one.
+    pub fn resolve(&mut self, keep_local: bool) {
+        let Some(remote) = self.conflict.take() else {
+            return;
+        };
+
+        if keep_local {
+            self.upload();
+        } else {
+            self.download(&remote);
+        }
+    }
+
+    /// Does whatever brings the copies back in step, now the synced copy is known.
+    fn act(&mut self, remote: Option<SyncedSave>) -> Option<SyncConflict> {
+        // a damaged local save is left for the error screen, and the synced one is taken
+        let local = self.store.load(MAIN_SLOT).ok();
+        let local_at = local
+            .as_ref()
+            .and_then(|_| get_modified_at(&self.get_save_path()));
+        let action = get_sync_action(
+            local_at,
+            remote.as_ref().map(|remote| remote.saved_at),
+            self.read_base(),
+        );
+
+        match (action, remote) {
+            (SyncAction::Upload, _) => self.upload(),
+            (SyncAction::Download, Some(remote)) => self.download(&remote),
+            (SyncAction::Conflict, Some(remote)) => {
+                let summary = parse_save(&remote.save)
+                    .map(|player_state| SaveSummary::new(remote.saved_at, &player_state));
+                let (Some(local), Some(local_at), Ok(summary)) = (local, local_at, summary) else {
+                    self.fail("the synced save is damaged");
+                    return None;
+                };
+
+                self.status = SyncStatus::Conflict;
+                self.conflict = Some(remote);
+                return Some(SyncConflict::new(
+                    SaveSummary::new(local_at, &local),
+                    summary,
+                ));
+            }
+            _ => self.status = SyncStatus::Synced,
+        }
+
+        None
+    }
+
+    /// Replaces the synced copy with this machine's save.
+    fn upload(&mut self) {
+        let (Ok(save), Some(saved_at)) = (
+            std::fs::read_to_string(self.get_save_path()),
+            get_modified_at(&self.get_save_path()),
+        ) else {
+            self.fail("the save couldn't be read");
+            return;
+        };
+
+        match serde_json::to_string(&SyncedSave { saved_at, save }) {
+            Ok(body) => {
+                self.uploading = Some(SyncBase {
+                    local: saved_at,
+                    remote: saved_at,
+                });
+                self.status = SyncStatus::Syncing;
+                self.remote.upload(body);
+            }
+            Err(err) => self.fail(&err.to_string()),
+        }
+    }
+
+    /// Replaces this machine's save with the synced copy.
+    fn download(&mut self, remote: &SyncedSave) {
+        if let Err(err) = parse_save(&remote.save) {
+            self.fail(&format!("the synced save is damaged: {err}"));
+            return;
+        }
+
+        let _ = std::fs::create_dir_all(&self.store.dir);
+        if let Err(err) = std::fs::write(self.get_save_path(), &remote.save) {
+            self.fail(&err.to_string());
+            return;
+        }
+
+        if let Some(local) = get_modified_at(&self.get_save_path()) {
+            self.write_base(SyncBase {
+                local,
+                remote: remote.saved_at,
+            });
+        }
+        self.status = SyncStatus::Synced;
+        self.downloaded = true;
+    }
+
+    fn fail(&mut self, reason: &str) {
+        tracing::warn!(reason, "couldn't sync the save");
+        self.uploading = None;
+        self.status = SyncStatus::Failed(reason.to_string());
+    }
+
+    /// Reads where the copies were last in step, kept next to the save.
+    fn read_base(&self) -> Option<SyncBase> {
+        let json = std::fs::read_to_string(self.get_base_path()).ok()?;
+        serde_json::from_str(&json).ok()
+    }
+
+    fn write_base(&self, base: SyncBase) {
+        if let Ok(json) = serde_json::to_string(&base)
+            && let Err(err) = std::fs::write(self.get_base_path(), json)
+        {
+            tracing::warn!(%err, "couldn't record the sync");
+        }
+    }
+
+    fn get_base_path(&self) -> PathBuf {
+        self.store.dir.join(SYNC_FILE_NAME)
+    }
+
+    /// Returns where the save that's synced is kept. Only the main slot is synced.
+    fn get_save_path(&self) -> PathBuf {
+        self.store.get_path(MAIN_SLOT)
+    }
+}
+
+/// Returns when the file at `path` was last written, in milliseconds since the Unix epoch.
+fn get_modified_at(path: &Path) -> Option<u64> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let elapsed = modified.duration_since(SystemTime::UNIX_EPOCH).ok()?;
+
+    u64::try_from(elapsed.as_millis()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use crate::common::remotesync::{RemoteSync, SyncEvent, SyncedSave};
+    use crate::common::savestore::{MAIN_SLOT, SaveStore};
+    use crate::common::upgrades::upgrade::PlayerState;
+    use crate::terminal::cloudsync::{CloudSync, SyncStatus};
+    use crate::terminal::save::FileStore;
+
+    /// Answers every request straight away, keeping the uploaded copy.
+    #[derive(Default)]
+    struct MemorySync {
+        stored: RefCell<Option<String>>,
+        events: RefCell<Vec<SyncEvent>>,
+    }
+
+    impl RemoteSync for MemorySync {
+        fn fetch(&self) {
+            let stored = self.stored.borrow().clone();
+            self.events.borrow_mut().push(SyncEvent::Fetched(stored));
+        }
+
+        fn upload(&self, body: String) {
+            *self.stored.borrow_mut() = Some(body);
+            self.events.borrow_mut().push(SyncEvent::Uploaded);
+        }
+
+        fn poll(&self) -> Vec<SyncEvent> {
+            self.events.borrow_mut().drain(..).collect()
+        }
+    }
+
+    #[test]
+    fn newer_copies_replace_unchanged_saves() {
+        let dir = std::env::temp_dir().join(format!("dispair-sync-{}", std::process::id()));
+        let store = FileStore { dir: dir.clone() };
+        store.save(MAIN_SLOT, &PlayerState::default()).unwrap();
+
+        let mut cloud_sync = CloudSync::new(MemorySync::default(), store);
+        cloud_sync.sync();
+        assert!(cloud_sync.tick().is_none());
+        cloud_sync.tick();
+        assert_eq!(cloud_sync.get_status(), &SyncStatus::Synced);
+        assert!(cloud_sync.remote.stored.borrow().is_some());
+
+        // progress made on another machine
+        let mut player_state = PlayerState::default();
+        player_state.inventory.gold = 250;
+        let newer = SyncedSave {
+            saved_at: u64::MAX,
+            save: serde_json::to_string(&player_state).unwrap(),
+        };
+        *cloud_sync.remote.stored.borrow_mut() = Some(serde_json::to_string(&newer).unwrap());
+
+        cloud_sync.sync();
+        assert!(cloud_sync.tick().is_none());
+        assert!(cloud_sync.take_downloaded());
+        assert_eq!(
+            cloud_sync.store.load(MAIN_SLOT).unwrap().inventory.gold,
+            250
+        );
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,113 @@
+//! This module sets up logging for the terminal build. Logging is off unless the game is started
+//! with `--log-level <level>`, in which case logs go to a daily rotating file next to the save,
+//! since anything printed to the terminal would be drawn over by the game.
+
+use std::path::PathBuf;
+
+use tracing::Level;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::fmt::format::FmtSpan;
+
+/// How many days of log files are kept before the oldest is deleted.
+const MAX_LOG_FILES: usize = 7;
+
+/// Starts logging if `--log-level` was passed. The returned guard flushes the log when dropped,
+/// so it has to be held until the game exits.
+///
+/// # Panics
+///
+/// Panics if it cannot find config directory via `dirs::config_dir()`
+#[must_use]
+pub fn init_logging() -> Option<WorkerGuard> {
+    let level = match get_log_level(std::env::args()) {
+        Ok(level) => level?,
+        Err(value) => {
+            eprintln!(
+                "unknown log level `{value}`, expected one of error, warn, info, debug, trace"
+            );
+            return None;
+        }
+    };
+
+    let appender = match RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("dispair")
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(get_log_dir())
+    {
+        Ok(appender) => appender,
+        Err(err) => {
+            eprintln!("failed to open log file: {err}");
+            return None;
+        }
+    };
+
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+
+    tracing_subscriber::fmt()
+        .with_writer(writer)
+        .with_ansi(false)
+        .with_max_level(level)
+        .with_span_events(FmtSpan::CLOSE)
+        .init();
+
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "logging started");
+
+    Some(guard)
+}
+
+/// Where log files are written: `dispair/logs` in the user's config directory.
+fn get_log_dir() -> PathBuf {
+    dirs::config_dir()
+        .expect("Failed to get config directory")
+        .join("dispair")
+        .join("logs")
+}
+
+/// Reads the level from `--log-level <level>` or `--log-level=<level>`. Gives back the value if
+/// it isn't a level.
+fn get_log_level(args: impl IntoIterator<Item = String>) -> Result<Option<Level>, String> {
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let value = if arg == "--log-level" {
+            args.next().unwrap_or_default()
+        } else if let Some(value) = arg.strip_prefix("--log-level=") {
+            value.to_string()
+        } else {
+            continue;
+        };
+
+        return value.parse().map(Some).map_err(|_| value);
+    }
+
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing::Level;
+
+    use crate::terminal::logging::get_log_level;
+
+    #[test]
+    fn log_level_parses() {
+        let args = |list: &[&str]| list.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+        assert_eq!(get_log_level(args(&["dispair"])), Ok(None));
+        assert_eq!(
+            get_log_level(args(&["dispair", "--log-level", "debug"])),
+            Ok(Some(Level::DEBUG))
+        );
+        assert_eq!(
+            get_log_level(args(&["dispair", "--log-only", "--log-level=warn"])),
+            Ok(Some(Level::WARN))
+        );
+        assert_eq!(
+            get_log_level(args(&["dispair", "--log-level", "loud"])),
+            Err("loud".to_string())
+        );
+    }
+}
@@ -2,4 +2,15 @@
 //! It includes the main application loop, TUI rendering, and event handling.
 
 pub mod app;
+pub mod capture;
+#[cfg(feature = "cloudsync")]
+pub mod cloudsync;
+pub mod logging;
+#[cfg(feature = "discord")]
+pub mod presence;
+#[cfg(feature = "leaderboard")]
+pub mod request;
+pub mod save;
+#[cfg(feature = "netplay")]
+pub mod socket;
 pub mod tui;
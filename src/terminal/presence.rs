@@ -0,0 +1,98 @@
+//! This module shows what the player is up to on their Discord profile, through the Discord
+//! client's local IPC socket. Nothing is shown, and nothing goes wrong, if Discord isn't running.
+
+use std::sync::mpsc::{self, Sender, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use discord_rich_presence::{DiscordIpc, DiscordIpcClient, activity::Activity};
+
+/// Keeps the player's Discord presence up to date from a background thread, so a slow or missing
+/// Discord client never holds up the game.
+pub struct DiscordPresence {
+    updates: Sender<String>,
+    /// The status last handed to the background thread.
+    status: String,
+}
+
+impl DiscordPresence {
+    /// How often the presence is sent. Discord drops updates sent faster than about one every
+    /// four seconds.
+    const UPDATE_INTERVAL: Duration = Duration::from_secs(4);
+
+    /// Starts keeping the presence up to date, if a Discord application id has been set.
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        // a runtime value lets players point at their own application without rebuilding
+        let client_id = std::env::var("DISPAIR_DISCORD_CLIENT_ID")
+            .ok()
+            .or_else(|| option_env!("DISPAIR_DISCORD_CLIENT_ID").map(String::from))?;
+
+        let (updates, received) = mpsc::channel();
+        thread::spawn(move || {
+            let mut client = DiscordIpcClient::new(client_id);
+            let mut connected = false;
+            let mut latest: Option<String> = None;
+            let mut sent: Option<String> = None;
+
+            loop {
+                thread::sleep(Self::UPDATE_INTERVAL);
+
+                loop {
+                    match received.try_recv() {
+                        Ok(status) => latest = Some(status),
+                        Err(TryRecvError::Empty) => break,
+                        // the game has closed
+                        Err(TryRecvError::Disconnected) => {
+                            if connected {
+                                let _ = client.close();
+                            }
+                            return;
+                        }
+                    }
+                }
+
+                let Some(status) = &latest else {
+                    continue;
+                };
+                if sent.as_ref() == Some(status) {
+                    continue;
+                }
+
+                // keeps trying, so Discord can be opened after the game
+                if !connected {
+                    connected = client.connect().is_ok();
+                    if !connected {
+                        continue;
+                    }
+                }
+
+                match client
+                    .set_activity(Activity::new().details(status.as_str()))
+                    .and_then(|()| client.recv())
+                {
+                    Ok(_) => sent.clone_from(&latest),
+                    Err(err) => {
+                        tracing::debug!(%err, "lost the connection to discord");
+                        let _ = client.close();
+                        connected = false;
+                        sent = None;
+                    }
+                }
+            }
+        });
+
+        Some(Self {
+            updates,
+            status: String::new(),
+        })
+    }
+
+    /// Shows `status` on the player's profile, once the next update goes out.
+    pub fn update(&mut self, status: String) {
+        if status != self.status {
+            let _ = self.updates.send(status.clone());
+            self.status = status;
+        }
+    }
+}
@@ -0,0 +1,33 @@
+//! This module sends HTTP requests for the terminal build, in the background on the tokio runtime.
+
+/// Called with the response body, or an error message, once a request finishes.
+pub type OnResponse = Box<dyn FnOnce(Result<String, String>) + Send>;
+
+/// Sends an HTTP request with an optional JSON body without blocking the game loop.
+pub fn send_request(method: &'static str, url: String, body: Option<String>, on_done: OnResponse) {
+    tokio::spawn(async move {
+        let client = reqwest::Client::new();
+        let mut request = match method {
+            "POST" => client.post(url),
+            _ => client.get(url),
+        };
+
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/json")
+                .body(body);
+        }
+
+        let response = match request.send().await {
+            Ok(response) => response.error_for_status().map_err(|e| e.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+
+        let body = match response {
+            Ok(response) => response.text().await.map_err(|e| e.to_string()),
+            Err(err) => Err(err),
+        };
+
+        on_done(body);
+    });
+}
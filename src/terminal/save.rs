@@ -0,0 +1,127 @@
+//! This module provides the terminal's save backend, which keeps each slot's progress in a JSON
+//! file in the user's config directory.
+
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use crate::common::savestore::{SaveError, SaveStore, read_save, serialize_save};
+use crate::common::upgrades::upgrade::PlayerState;
+
+/// The file cloud sync keeps next to the saves, which isn't a save itself.
+pub const SYNC_FILE_NAME: &str = "sync.json";
+
+/// Saves progress to JSON files, one per slot, named after the slot.
+pub struct FileStore {
+    pub dir: PathBuf,
+}
+
+impl FileStore {
+    /// Returns the path of the file `slot` is saved to.
+    #[must_use]
+    pub fn get_path(&self, slot: &str) -> PathBuf {
+        self.dir.join(format!("{slot}.json"))
+    }
+}
+
+impl Default for FileStore {
+    /// Points at `dispair` in the user's config directory.
+    ///
+    /// # Panics
+    ///
+    /// Panics if it cannot find config directory via `dirs::config_dir()`
+    fn default() -> Self {
+        Self {
+            dir: dirs::config_dir()
+                .expect("Failed to get config directory")
+                .join("dispair"),
+        }
+    }
+}
+
+impl SaveStore for FileStore {
+    fn save(&self, slot: &str, player_state: &PlayerState) -> Result<(), SaveError> {
+        let json = serialize_save(player_state)?;
+
+        std::fs::create_dir_all(&self.dir)
+            .and_then(|()| std::fs::write(self.get_path(slot), json))
+            .map_err(|err| SaveError::Storage(err.to_string()))
+    }
+
+    fn load(&self, slot: &str) -> Result<PlayerState, SaveError> {
+        let json = std::fs::read_to_string(self.get_path(slot)).map_err(|err| {
+            if err.kind() == ErrorKind::NotFound {
+                SaveError::Missing
+            } else {
+                SaveError::Storage(err.to_string())
+            }
+        })?;
+
+        read_save(&json)
+    }
+
+    fn list_slots(&self) -> Result<Vec<String>, SaveError> {
+        let entries = match std::fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(SaveError::Storage(err.to_string())),
+        };
+
+        Ok(entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "json")
+                    && path.file_name().is_some_and(|name| name != SYNC_FILE_NAME)
+            })
+            .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+            .collect())
+    }
+
+    fn delete(&self, slot: &str) -> Result<(), SaveError> {
+        match std::fs::remove_file(self.get_path(slot)) {
+            Err(err) if err.kind() != ErrorKind::NotFound => {
+                Err(SaveError::Storage(err.to_string()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::savestore::{MAIN_SLOT, SaveError, SaveStore};
+    use crate::common::upgrades::upgrade::PlayerState;
+    use crate::terminal::save::{FileStore, SYNC_FILE_NAME};
+
+    #[test]
+    fn slots_are_listed_and_deleted() {
+        let dir = std::env::temp_dir().join(format!("dispair-slots-{}", std::process::id()));
+        let store = FileStore { dir: dir.clone() };
+        assert_eq!(store.list_slots(), Ok(Vec::new()));
+        assert_eq!(store.has_save(MAIN_SLOT), Ok(false));
+
+        let mut player_state = PlayerState::default();
+        player_state.inventory.gold = 40;
+        store.save(MAIN_SLOT, &PlayerState::default()).unwrap();
+        store.save("backup", &player_state).unwrap();
+        std::fs::write(dir.join(SYNC_FILE_NAME), "{}").unwrap();
+
+        let mut slots = store.list_slots().unwrap();
+        slots.sort();
+        assert_eq!(slots, vec!["backup".to_string(), MAIN_SLOT.to_string()]);
+        assert_eq!(store.load("backup").unwrap().inventory.gold, 40);
+
+        store.delete("backup").unwrap();
+        store.delete("backup").unwrap();
+        assert_eq!(store.load("backup").unwrap_err(), SaveError::Missing);
+
+        std::fs::write(store.get_path(MAIN_SLOT), "not a save").unwrap();
+        assert!(matches!(
+            store.has_save(MAIN_SLOT),
+            Err(SaveError::Damaged(_))
+        ));
+
+        let _ = std::fs::remove_dir_all(dir);
+    }
+}
@@ -0,0 +1,78 @@
+//! This module connects to the co-op relay for the terminal build, running the WebSocket in the
+//! background on the tokio runtime.
+
+use std::sync::mpsc;
+
+use futures::{SinkExt, StreamExt};
+use tokio::sync::mpsc::{UnboundedSender, unbounded_channel};
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+use crate::common::netplay::SocketEvent;
+
+/// A WebSocket connection, sending and receiving text without blocking the game loop. The
+/// connection closes when this is dropped.
+pub struct Socket {
+    outgoing: UnboundedSender<String>,
+    incoming: mpsc::Receiver<SocketEvent>,
+}
+
+impl Socket {
+    /// Starts connecting to `url`. [`SocketEvent::Open`] arrives once connected.
+    #[must_use]
+    pub fn connect(url: &str) -> Self {
+        let (outgoing, mut to_send) = unbounded_channel::<String>();
+        let (received, incoming) = mpsc::channel();
+        let url = url.to_string();
+
+        tokio::spawn(async move {
+            let stream = match connect_async(url).await {
+                Ok((stream, _)) => stream,
+                Err(err) => {
+                    let _ = received.send(SocketEvent::Closed(err.to_string()));
+                    return;
+                }
+            };
+            let _ = received.send(SocketEvent::Open);
+
+            let (mut write, mut read) = stream.split();
+            let reason = loop {
+                tokio::select! {
+                    text = to_send.recv() => {
+                        // the socket was dropped, so nobody is listening any more
+                        let Some(text) = text else {
+                            let _ = write.close().await;
+                            return;
+                        };
+
+                        if let Err(err) = write.send(Message::text(text)).await {
+                            break err.to_string();
+                        }
+                    }
+                    message = read.next() => match message {
+                        Some(Ok(Message::Text(text))) => {
+                            let _ = received.send(SocketEvent::Message(text.to_string()));
+                        }
+                        Some(Ok(Message::Close(_))) | None => break "the connection closed".to_string(),
+                        Some(Err(err)) => break err.to_string(),
+                        Some(Ok(_)) => {}
+                    },
+                }
+            };
+
+            let _ = received.send(SocketEvent::Closed(reason));
+        });
+
+        Self { outgoing, incoming }
+    }
+
+    /// Sends `text` once connected. Text sent after the connection closes is dropped.
+    pub fn send(&self, text: String) {
+        let _ = self.outgoing.send(text);
+    }
+
+    /// Takes everything that has happened on the connection since the last poll.
+    #[must_use]
+    pub fn poll(&self) -> Vec<SocketEvent> {
+        self.incoming.try_iter().collect()
+    }
+}
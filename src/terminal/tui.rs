@@ -3,6 +3,7 @@
 use std::{
     io,
     ops::{Deref, DerefMut},
+    sync::atomic::{AtomicBool, Ordering},
     time::Duration,
 };
 
@@ -12,7 +13,8 @@ use crossterm::{
     cursor,
     event::{
         DisableBracketedPaste, DisableMouseCapture, EnableBracketedPaste, EnableMouseCapture,
-        Event as CrosstermEvent, KeyEvent, KeyEventKind, MouseEvent,
+        Event as CrosstermEvent, KeyEvent, KeyEventKind, KeyboardEnhancementFlags, MouseEvent,
+        PopKeyboardEnhancementFlags, PushKeyboardEnhancementFlags,
     },
     terminal::{EnterAlternateScreen, LeaveAlternateScreen},
 };
@@ -45,8 +47,10 @@ pub enum Event {
     FocusLost,
     /// A string has been pasted into the terminal.
     Paste(String),
-    /// A key has been pressed.
+    /// A key has been pressed, or is repeating while held.
     Key(KeyEvent),
+    /// A key has been released, on terminals that report it.
+    KeyRelease(KeyEvent),
     /// A mouse event has occurred.
     Mouse(MouseEvent),
     /// The terminal has been resized.
@@ -112,6 +116,12 @@ impl Tui {
         self
     }
 
+    /// Changes the frame rate of a running TUI, restarting its event loop to apply it.
+    pub fn set_frame_rate(&mut self, frame_rate: f64) {
+        self.frame_rate = frame_rate;
+        self.start();
+    }
+
     /// Starts the TUI event loop.
     ///
     /// # Panics
@@ -142,7 +152,9 @@ impl Tui {
                       Some(Ok(evt)) => {
                         match evt {
                           CrosstermEvent::Key(key) => {
-                            if key.kind == KeyEventKind::Press {
+                            if key.kind == KeyEventKind::Release {
+                              event_tx.send(Event::KeyRelease(key)).unwrap();
+                            } else {
                               event_tx.send(Event::Key(key)).unwrap();
                             }
                           },
@@ -210,6 +222,14 @@ impl Tui {
         if self.paste {
             crossterm::execute!(io::stderr(), EnableBracketedPaste)?;
         }
+        // lets held keys be told apart from repeated presses, where the terminal supports it
+        if crossterm::terminal::supports_keyboard_enhancement().unwrap_or(false) {
+            crossterm::execute!(
+                io::stderr(),
+                PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::REPORT_EVENT_TYPES)
+            )?;
+            KEYBOARD_ENHANCED.store(true, Ordering::Relaxed);
+        }
         self.start();
         Ok(())
     }
@@ -229,6 +249,7 @@ impl Tui {
             if self.mouse {
                 crossterm::execute!(io::stderr(), DisableMouseCapture)?;
             }
+            pop_keyboard_enhancement()?;
             crossterm::execute!(io::stderr(), LeaveAlternateScreen, cursor::Show)?;
             crossterm::terminal::disable_raw_mode()?;
         }
@@ -261,6 +282,7 @@ impl Tui {
 ///
 /// Will error if there are any errors from crossterm
 pub fn restore() -> io::Result<()> {
+    pop_keyboard_enhancement()?;
     crossterm::execute!(io::stderr(), LeaveAlternateScreen, cursor::Show)?;
     crossterm::terminal::disable_raw_mode()?;
     crossterm::execute!(io::stderr(), DisableBracketedPaste)?;
@@ -268,6 +290,17 @@ pub fn restore() -> io::Result<()> {
     Ok(())
 }
 
+/// Whether key event types have been asked for, so they can be turned off again on exit.
+static KEYBOARD_ENHANCED: AtomicBool = AtomicBool::new(false);
+
+/// Stops the terminal reporting key event types, if `Tui::enter` asked it to.
+fn pop_keyboard_enhancement() -> io::Result<()> {
+    if KEYBOARD_ENHANCED.swap(false, Ordering::Relaxed) {
+        crossterm::execute!(io::stderr(), PopKeyboardEnhancementFlags)?;
+    }
+    Ok(())
+}
+
 impl Deref for Tui {
     type Target = ratatui::Terminal<Backend<io::Stderr>>;
 
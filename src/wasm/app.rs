@@ -4,116 +4,212 @@
 use std::{cell::RefCell, io, rc::Rc};
 
 use crate::prelude::Instant;
-use serde::de::Error;
 
-use ratzilla::{
-    DomBackend, WebRenderer,
-    event::{KeyCode, KeyEvent},
-};
+use ratzilla::{DomBackend, WebRenderer, event::KeyEvent};
 
-use web_sys::wasm_bindgen::JsValue;
+use web_sys::wasm_bindgen::{JsCast, closure::Closure};
+use web_sys::{AddEventListenerOptions, KeyboardEvent, TouchEvent};
 
-use crate::common::{Goto, TICK_RATE};
+use crate::common::TICK_RATE;
 
-use ratzilla::ratatui::{
-    Frame, Terminal,
-    layout::{Constraint, Layout},
-    style::Style,
-    symbols::border,
-    text::Text,
-    widgets::{Block, List, ListItem, ListState},
-};
+use ratzilla::ratatui::{Frame, Terminal, layout::Rect, style::Stylize, text::Line};
 
+use crate::common::app::{AppCore, Platform};
+use crate::common::apperror::AppError;
 use crate::common::game::Game;
+use crate::common::mainmenu::MenuChoice;
+use crate::common::savestore::{MAIN_SLOT, SaveStore};
+use crate::common::settings::setting::Theme;
 use crate::common::sound::SoundWrangler;
+use crate::common::toasts::ToastSeverity;
 use crate::common::upgrades::upgrade::PlayerState;
-use crate::common::utils::{center_horizontal, center_vertical};
+use crate::wasm::capture::CaptureSaver;
+use crate::wasm::params::LaunchParams;
+use crate::wasm::pwa::{is_online, register_service_worker, render_offline_indicator};
+use crate::wasm::save::IndexedDbStore;
+use crate::wasm::savestatus::SaveStatus;
+use crate::wasm::touch::{TouchControls, get_touch_cell};
+
+thread_local! {
+    static SAVE_STORE: IndexedDbStore = IndexedDbStore::open();
+}
 
-/// Saves the player's progress to local storage.
+/// Starts saving the player's progress to the browser's save store. How it went is picked up
+/// later with [`poll_saves`].
 ///
 /// # Errors
 ///
-/// Errors if cannot save to localstorage
-pub fn save_progress(player_state: &PlayerState) -> Result<(), JsValue> {
-    let window = web_sys::window();
+/// Errors with `AppError::Save` if the progress can't be serialized, or can't be stored anywhere
+#[tracing::instrument(skip_all, err)]
+pub fn save_progress(player_state: &PlayerState) -> Result<(), AppError> {
+    SAVE_STORE
+        .with(|store| store.save(MAIN_SLOT, player_state))
+        .map_err(|err| AppError::Save(err.to_string()))
+}
+
+/// Loads the player's progress from the browser's save store.
+///
+/// # Errors
+///
+/// Errors with `AppError::Load` if there is no save, or it can't be read
+#[tracing::instrument(skip_all)]
+pub fn load_progress() -> Result<PlayerState, AppError> {
+    SAVE_STORE
+        .with(|store| store.load(MAIN_SLOT))
+        .map_err(|err| AppError::Load(err.to_string()))
+}
 
-    let mut out = Ok(());
+/// Checks if there is saved progress to continue from.
+///
+/// # Errors
+///
+/// Errors with `AppError::Load` if there is a save but it's damaged
+pub fn check_save() -> Result<bool, AppError> {
+    SAVE_STORE
+        .with(|store| store.has_save(MAIN_SLOT))
+        .map_err(|err| AppError::Load(err.to_string()))
+}
 
-    let value: String = serde_json::to_string(player_state)
-        .map_err(|_| JsValue::from_str("Failed to serialize player state"))?;
+/// Takes how each save of the player's progress has gone since the last poll.
+fn poll_saves() -> Vec<Result<(), AppError>> {
+    SAVE_STORE
+        .with(IndexedDbStore::poll)
+        .into_iter()
+        .filter(|(slot, _)| slot == MAIN_SLOT)
+        .map(|(_, result)| result.map_err(|err| AppError::Save(err.to_string())))
+        .collect()
+}
 
-    if let Some(window) = window {
-        let local_storage = window
-            .local_storage()
-            .map_err(|_| JsValue::from_str("Failed to access local storage"))?;
+/// Starts saving any screenshot or clip the game has captured, and tells the player once each
+/// one has downloaded.
+fn save_captures(game: &mut Game, captures: &mut CaptureSaver) {
+    let started = game.take_capture().map(|output| captures.save(output));
 
-        if let Some(storage) = local_storage {
-            out = storage
-                .set_item("player_state", &value)
-                .map_err(|_| JsValue::from_str("Failed to save player state"));
+    match started.or_else(|| captures.tick().map(|result| result.map(Some))) {
+        Some(Ok(Some(name))) => {
+            game.notify_capture(format!("downloaded {name}"), ToastSeverity::Success);
+        }
+        Some(Err(err)) => {
+            game.notify_capture(format!("couldn't save: {err}"), ToastSeverity::Warning);
         }
+        Some(Ok(None)) | None => {}
     }
+}
 
-    out
+/// Where the browser saves progress, how the page set up runs, and how saving is going.
+struct WebPlatform {
+    /// How the page's URL set up runs, for shared challenge links.
+    launch: LaunchParams,
+    /// Whether the latest progress has been saved, and when to try again if it hasn't.
+    saves: SaveStatus,
+    /// Whether the save is damaged, so it isn't checked again until it's replaced.
+    save_damaged: bool,
 }
 
-/// Loads the player's progress from local storage.
-///
-/// # Errors
-///
-/// Errors if cannot save to localstorage
-pub fn load_progress() -> Result<PlayerState, serde_json::Error> {
-    let window = web_sys::window();
-
-    let mut value = String::new();
-
-    if let Some(window) = window {
-        let local_storage = window
-            .local_storage()
-            .map_err(|_| serde_json::Error::custom("oops!"))?;
-
-        if let Some(storage) = local_storage {
-            let out = storage
-                .get_item("player_state")
-                .map_err(|_| serde_json::Error::custom("local storage no exist"))?;
-            value = out.unwrap_or(String::new());
+impl Platform for WebPlatform {
+    fn save(&mut self, player_state: &PlayerState) -> Result<(), AppError> {
+        save_progress(player_state)
+    }
+
+    fn load(&mut self) -> Result<PlayerState, AppError> {
+        load_progress()
+    }
+
+    fn check_save(&mut self) -> Result<bool, AppError> {
+        let result = check_save();
+        self.save_damaged = result.is_err();
+        result
+    }
+
+    /// Sets the game up the way the page's URL asked for.
+    fn prepare_game(&mut self, player_state: &mut PlayerState) -> Option<u64> {
+        if let Some(theme) = self.launch.theme {
+            player_state.settings.theme = theme;
+        }
+        if let Some(challenge) = &self.launch.challenge {
+            player_state.accept_challenge(challenge.clone());
         }
+
+        self.launch.seed
     }
 
-    let i: PlayerState = serde_json::from_str(&value)?;
+    /// Saves that couldn't be sent off are tried again later. The rest are confirmed once the
+    /// browser reports back, in [`WebPlatform::record_saves`].
+    fn on_saved(&mut self, result: Result<(), AppError>) -> Result<(), AppError> {
+        if let Err(err) = result {
+            self.saves.record_failure(&err);
+        }
+        Ok(())
+    }
+
+    fn is_save_failing(&self) -> bool {
+        self.saves.is_failing()
+    }
 
-    Ok(i)
+    /// The save store may finish reading in a save after the menu first appears.
+    fn should_recheck_save(&self) -> bool {
+        !self.save_damaged
+    }
+
+    fn get_menu_theme(&self) -> Theme {
+        self.launch.theme.unwrap_or_default()
+    }
 }
 
-/// The main application struct, which manages the game's state and views.
+impl WebPlatform {
+    /// Confirms the saves the browser has finished storing with a toast, and schedules another
+    /// try for any it couldn't.
+    fn record_saves(&mut self) {
+        for result in poll_saves() {
+            match result {
+                Ok(()) => {
+                    self.save_damaged = false;
+                    self.saves.record_success();
+                }
+                Err(err) => self.saves.record_failure(&err),
+            }
+        }
+    }
+}
+
+/// The main application struct, which runs the page and passes its events on to the app.
 pub struct App {
-    game: Option<Game>,
-    player_state: Option<PlayerState>,
-    current_selection: ListState,
-    sound_wrangler: Option<Rc<RefCell<SoundWrangler>>>,
+    core: AppCore<WebPlatform>,
     last_frame: Instant,
+    /// When the game last updated a frame, to hold it to the player's frame limit.
+    last_game_frame: Instant,
+    /// The canvas size at the last draw, to notice when the browser window is resized.
+    last_area: Rect,
     pub tick_rate: f64,
-    save_exists: bool,
+    /// Whether the browser has a network connection, to show when the game is running offline.
+    online: bool,
+    /// The on-screen D-pad for playing by touch.
+    touch: TouchControls,
+    captures: CaptureSaver,
 }
 
 impl App {
-    /// Creates a new `App` instance.
+    /// Creates a new `App` instance, with runs set up by the page's URL parameters.
     #[must_use]
-    pub fn new() -> Self {
-        let mut out = Self {
-            game: None,
-            player_state: None,
-            current_selection: ListState::default(),
-            sound_wrangler: None,
-            last_frame: Instant::now(),
-            tick_rate: TICK_RATE,
-            save_exists: load_progress().is_ok(),
-        };
-        web_sys::console::log_1(&"Hello WASM!".into());
+    pub fn new(launch: LaunchParams) -> Self {
+        tracing::info!(version = env!("CARGO_PKG_VERSION"), "game started");
 
-        out.current_selection.select_first();
+        let platform = WebPlatform {
+            launch,
+            saves: SaveStatus::default(),
+            save_damaged: false,
+        };
 
-        out
+        Self {
+            core: AppCore::new(platform, vec![MenuChoice::NewGame, MenuChoice::Continue]),
+            last_frame: Instant::now(),
+            last_game_frame: Instant::now(),
+            last_area: Rect::default(),
+            tick_rate: TICK_RATE,
+            online: is_online(),
+            touch: TouchControls::default(),
+            captures: CaptureSaver::new(),
+        }
     }
 
     /// Runs the main application loop.
@@ -127,6 +223,12 @@ impl App {
 
         let tick_delay = std::time::Duration::from_secs_f64(1.0 / this.borrow().tick_rate);
 
+        Self::listen_for_visibility(this);
+        Self::listen_for_key_release(this);
+        Self::listen_for_connection(this);
+        Self::listen_for_touch(this);
+        register_service_worker();
+
         let self_ref_key = this.clone();
         let self_ref_draw = self_ref_key.clone();
 
@@ -144,6 +246,11 @@ impl App {
             let maybe_reference = self_ref_draw.try_borrow_mut();
 
             if let Ok(mut reference) = maybe_reference {
+                if f.area() != reference.last_area {
+                    reference.last_area = f.area();
+                    reference.resize(f.area());
+                }
+
                 let last_frame = reference.last_frame;
 
                 if Instant::now().duration_since(last_frame) >= tick_delay {
@@ -159,119 +266,215 @@ impl App {
         Ok(())
     }
 
-    /// Handles key events.
-    pub fn handle_key_event(&mut self, key_event: &KeyEvent) {
-        if self.sound_wrangler.is_none() {
-            self.sound_wrangler = Some(Rc::new(RefCell::new(SoundWrangler::default())));
-        }
-        if let Some(game) = &mut self.game {
-            game.handle_key_event(key_event);
-        } else {
-            match key_event.code {
-                KeyCode::Char('s') | KeyCode::Down => self.select_next(),
-                KeyCode::Char('w') | KeyCode::Up => self.select_prev(),
-                KeyCode::Enter => self.confirm_curr(),
-                _ => {}
+    /// Pauses the game whenever the page is hidden and resumes it once the page is shown again,
+    /// so runs don't carry on in a background tab.
+    fn listen_for_visibility(this: &Rc<RefCell<Self>>) {
+        let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+            return;
+        };
+
+        let self_ref = this.clone();
+        let visibility_document = document.clone();
+        let on_change = Closure::<dyn FnMut()>::new(move || {
+            if let Ok(mut reference) = self_ref.try_borrow_mut()
+                && let Some(game) = reference.core.get_game_mut()
+            {
+                game.set_visible(!visibility_document.hidden());
             }
-        }
+        });
+
+        document
+            .add_event_listener_with_callback(
+                "visibilitychange",
+                on_change.as_ref().unchecked_ref(),
+            )
+            .unwrap_or(());
+
+        // The listener lives for as long as the page does.
+        on_change.forget();
     }
 
-    /// Selects the next item in the menu.
-    fn select_next(&mut self) {
-        self.current_selection.select_next();
-        if !self.save_exists && self.current_selection.selected().unwrap_or(0) == 1 {
-            self.select_prev();
-        }
+    /// Passes key releases on to the game, which ratzilla doesn't report, so held movement keys
+    /// stop as soon as they're let go of.
+    fn listen_for_key_release(this: &Rc<RefCell<Self>>) {
+        let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+            return;
+        };
+
+        let self_ref = this.clone();
+        let on_release = Closure::<dyn FnMut(KeyboardEvent)>::new(move |event: KeyboardEvent| {
+            if let Ok(mut reference) = self_ref.try_borrow_mut()
+                && let Some(game) = reference.core.get_game_mut()
+            {
+                game.handle_key_release(&KeyEvent::from(event));
+            }
+        });
+
+        document
+            .add_event_listener_with_callback("keyup", on_release.as_ref().unchecked_ref())
+            .unwrap_or(());
+
+        // The listener lives for as long as the page does.
+        on_release.forget();
     }
 
-    /// Selects the previous item in the menu.
-    fn select_prev(&mut self) {
-        self.current_selection.select_previous();
+    /// Keeps track of whether the browser is online, for the offline indicator.
+    fn listen_for_connection(this: &Rc<RefCell<Self>>) {
+        let Some(window) = web_sys::window() else {
+            return;
+        };
+
+        let self_ref = this.clone();
+        let on_change = Closure::<dyn FnMut()>::new(move || {
+            if let Ok(mut reference) = self_ref.try_borrow_mut() {
+                reference.online = is_online();
+            }
+        });
+
+        for event in ["online", "offline"] {
+            window
+                .add_event_listener_with_callback(event, on_change.as_ref().unchecked_ref())
+                .unwrap_or(());
+        }
+
+        // The listener lives for as long as the page does.
+        on_change.forget();
     }
 
-    /// Confirms the current selection in the menu.
-    fn confirm_curr(&mut self) {
-        match self.current_selection.selected() {
-            Some(0) => {
-                self.player_state = Some(PlayerState::default());
-                self.game = Some(Game::new(
-                    self.player_state.clone().unwrap(),
-                    self.sound_wrangler.clone().unwrap_or_default(),
-                ));
+    /// Turns touches into key events, so the game can be played on phones.
+    fn listen_for_touch(this: &Rc<RefCell<Self>>) {
+        let Some(document) = web_sys::window().and_then(|window| window.document()) else {
+            return;
+        };
+
+        let self_ref = this.clone();
+        let on_start = Closure::<dyn FnMut(TouchEvent)>::new(move |event: TouchEvent| {
+            // stops the page from scrolling or zooming under the game
+            event.prevent_default();
+
+            let Some(touch) = event.changed_touches().get(0) else {
+                return;
+            };
+
+            if let Ok(mut reference) = self_ref.try_borrow_mut() {
+                let area = reference.last_area;
+                let (column, row) = get_touch_cell(area, touch.client_x(), touch.client_y());
+                let key_event = reference.touch.press(area, column, row);
+                reference.handle_key_event(&key_event);
             }
-            Some(1) => {
-                self.player_state = Some(load_progress().unwrap_or_default());
-                self.game = Some(Game::new(
-                    self.player_state.clone().unwrap(),
-                    self.sound_wrangler.clone().unwrap_or_default(),
-                ));
+        });
+
+        let self_ref = this.clone();
+        let on_end = Closure::<dyn FnMut()>::new(move || {
+            if let Ok(mut reference) = self_ref.try_borrow_mut()
+                && let Some(key_event) = reference.touch.release()
+                && let Some(game) = reference.core.get_game_mut()
+            {
+                game.handle_key_release(&key_event);
             }
-            _ => {}
+        });
+
+        // the listener has to be active to be allowed to prevent scrolling
+        let options = AddEventListenerOptions::new();
+        options.set_passive(false);
+
+        document
+            .add_event_listener_with_callback_and_add_event_listener_options(
+                "touchstart",
+                on_start.as_ref().unchecked_ref(),
+                &options,
+            )
+            .unwrap_or(());
+
+        for event in ["touchend", "touchcancel"] {
+            document
+                .add_event_listener_with_callback(event, on_end.as_ref().unchecked_ref())
+                .unwrap_or(());
         }
+
+        // The listeners live for as long as the page does.
+        on_start.forget();
+        on_end.forget();
     }
 
-    /// Renders the UI for the current view.
-    fn ui(&mut self, frame: &mut Frame) {
-        if let Some(ref mut game) = self.game {
-            game.render(frame);
-        } else {
-            self.render_menu(frame);
+    /// Lays the current view out again for the new canvas size.
+    fn resize(&mut self, area: Rect) {
+        if let Some(game) = self.core.get_game_mut() {
+            game.resize(area.width, area.height);
         }
     }
 
-    /// Called on each game tick.
-    fn on_tick(&mut self) {
-        if let Some(game) = &mut self.game {
-            game.on_tick();
-            if game.get_goto().clone() == Goto::Menu {
-                self.player_state = Some(game.get_player_state());
-                save_progress(self.player_state.as_ref().unwrap()).unwrap();
-                self.save_exists = load_progress().is_ok();
-                self.game = None;
-            }
+    /// Handles key events. Sound can only start once the player has pressed something.
+    pub fn handle_key_event(&mut self, key_event: &KeyEvent) {
+        if self.core.sound_wrangler.is_none() {
+            self.core.sound_wrangler = Some(Rc::new(RefCell::new(SoundWrangler::default())));
         }
+
+        self.core.handle_key_event(key_event);
     }
 
-    /// Called on each frame.
-    fn on_frame(&mut self) {
-        if let Some(game) = &mut self.game {
-            game.on_frame();
+    /// Renders the UI for the current view.
+    fn ui(&mut self, frame: &mut Frame) {
+        let footer = self
+            .core
+            .platform
+            .launch
+            .get_description()
+            .map(|description| Line::from(description.light_yellow()));
+        self.core.render(frame, footer);
+
+        if self.core.is_holding_start() {
+            self.core.platform.saves.render_unsaved_warning(frame);
+            self.core.render_held_help(frame);
+        }
+
+        self.touch.render(frame);
+
+        if !self.online {
+            render_offline_indicator(frame);
         }
+
+        self.core.platform.saves.render(frame);
     }
 
-    /// Renders the main menu.
-    pub fn render_menu(&mut self, frame: &mut Frame) {
-        let block = Block::bordered().border_set(border::DOUBLE);
+    /// Called on each game tick.
+    fn on_tick(&mut self) {
+        if let Some(key_event) = self.touch.take_repeat() {
+            self.handle_key_event(&key_event);
+        }
 
-        let [top, bottom] = Layout::vertical([Constraint::Percentage(25), Constraint::Fill(1)])
-            .areas(block.inner(frame.area()));
+        self.core.platform.record_saves();
 
-        let title_area = center_vertical(top, 1);
+        // failed saves keep being tried in the background, whatever is on screen
+        if self.core.platform.saves.is_retry_due() {
+            self.core.save();
+        }
 
-        let title = Text::from("Dispair").centered();
+        self.core.on_tick();
 
-        let options_area = center_vertical(center_horizontal(bottom, 12), 3);
+        if let Some(game) = self.core.get_game_mut() {
+            save_captures(game, &mut self.captures);
+        }
+    }
 
-        let options = List::new(vec![
-            ListItem::from("New Game"),
-            ListItem::from("Continue").style(if !self.save_exists {
-                Style::default().dark_gray()
-            } else {
-                Style::default()
-            }),
-        ])
-        .highlight_symbol("> ")
-        .highlight_style(Style::new().bold());
+    /// Called on each frame.
+    fn on_frame(&mut self) {
+        let Some(game) = self.core.get_game() else {
+            return;
+        };
 
-        frame.render_widget(block, frame.area());
+        // the browser draws on every animation frame, so the frame limit is kept here
+        let frame_delay = std::time::Duration::from_secs_f64(1.0 / game.get_frame_limit());
 
-        frame.render_widget(title, title_area);
-        frame.render_stateful_widget(options, options_area, &mut self.current_selection);
+        if self.last_game_frame.elapsed() >= frame_delay {
+            self.last_game_frame = Instant::now();
+            self.core.on_frame();
+        }
     }
 }
 
 impl Default for App {
     fn default() -> Self {
-        Self::new()
+        Self::new(LaunchParams::default())
     }
 }
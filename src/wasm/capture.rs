@@ -0,0 +1,92 @@
+//! This module saves screenshots and clips for the web build by downloading them. The browser has
+//! no threads to spare, so clips are encoded on the main thread a frame per tick instead, one clip
+//! at a time.
+
+use std::collections::VecDeque;
+
+use web_sys::{
+    Blob, BlobPropertyBag, HtmlAnchorElement, Url,
+    js_sys::{Array, Uint8Array},
+    wasm_bindgen::{JsCast, JsValue},
+};
+
+use crate::common::capture::{CaptureOutput, ClipEncoder};
+
+/// Downloads captures, encoding clips over several ticks.
+#[derive(Default)]
+pub struct CaptureSaver {
+    /// The clips waiting to be encoded, and the names to download them as. The first is the one
+    /// being encoded.
+    clips: VecDeque<(String, ClipEncoder)>,
+}
+
+impl CaptureSaver {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts saving `output`. Screenshots download straight away, and clips once they're
+    /// encoded, after any clips saved before them.
+    ///
+    /// # Errors
+    ///
+    /// Errors if the screenshot couldn't be downloaded.
+    pub fn save(&mut self, output: CaptureOutput) -> Result<Option<String>, String> {
+        match output {
+            CaptureOutput::Screenshot { name, contents } => {
+                download(&name, contents.as_bytes(), "text/plain")?;
+                Ok(Some(name))
+            }
+            CaptureOutput::Clip { name, encoder } => {
+                self.clips.push_back((name, encoder));
+                Ok(None)
+            }
+        }
+    }
+
+    /// Encodes the next frame of the clip in progress, downloading it once it's done. Returns the
+    /// clip's name, or why it failed, once it's finished.
+    pub fn tick(&mut self) -> Option<Result<String, String>> {
+        let (_, encoder) = self.clips.front_mut()?;
+        if encoder.step() {
+            return None;
+        }
+
+        let (name, encoder) = self.clips.pop_front()?;
+        Some(
+            encoder
+                .finish()
+                .and_then(|gif| download(&name, &gif, "image/gif"))
+                .map(|()| name),
+        )
+    }
+}
+
+/// Has the browser download `contents` as a file called `name`.
+fn download(name: &str, contents: &[u8], mime_type: &str) -> Result<(), String> {
+    let parts = Array::of1(&Uint8Array::from(contents));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime_type);
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &options)
+        .map_err(|err| describe(&err))?;
+    let url = Url::create_object_url_with_blob(&blob).map_err(|err| describe(&err))?;
+
+    let anchor = web_sys::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.create_element("a").ok())
+        .and_then(|element| element.dyn_into::<HtmlAnchorElement>().ok())
+        .ok_or("couldn't start the download")?;
+    anchor.set_href(&url);
+    anchor.set_download(name);
+    anchor.click();
+
+    // the URL is left for the browser to free with the page, as revoking it straight away can
+    // cancel the download
+    Ok(())
+}
+
+fn describe(err: &JsValue) -> String {
+    err.as_string()
+        .unwrap_or_else(|| "couldn't start the download".into())
+}
@@ -0,0 +1,17 @@
+//! This module sets up logging for the web build, which sends logs to the browser's console.
+
+use tracing::Level;
+use tracing_wasm::WASMLayerConfigBuilder;
+
+/// Starts sending logs to the browser's console. Debug builds log in more detail.
+pub fn init_logging() {
+    let level = if cfg!(debug_assertions) {
+        Level::DEBUG
+    } else {
+        Level::INFO
+    };
+
+    tracing_wasm::set_as_global_default_with_config(
+        WASMLayerConfigBuilder::new().set_max_level(level).build(),
+    );
+}
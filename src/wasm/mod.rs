@@ -2,3 +2,14 @@
 //! It includes the main application logic that runs in the browser.
 
 pub mod app;
+pub mod capture;
+pub mod logging;
+pub mod params;
+pub mod pwa;
+#[cfg(feature = "leaderboard")]
+pub mod request;
+pub mod save;
+pub mod savestatus;
+#[cfg(feature = "netplay")]
+pub mod socket;
+pub mod touch;
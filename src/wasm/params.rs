@@ -0,0 +1,105 @@
+//! This module reads the web build's URL query parameters, which can set up a run ahead of time
+//! so challenges can be shared as links, e.g. `?seed=1234&theme=mono`, `?mode=daily` or
+//! `?challenge=c1-4d2-0-1-3-2-x`.
+
+use crate::common::challenge::Challenge;
+use crate::common::settings::setting::Theme;
+use crate::prelude::SystemTime;
+use strum::IntoEnumIterator;
+
+/// How a run was set up from the page's URL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeMode {
+    /// A run on the map built from a given seed.
+    Seeded,
+    /// Today's run, on the same map for everyone until midnight UTC.
+    Daily,
+}
+
+/// Run settings picked up from the page's URL.
+#[derive(Debug, Clone, Default)]
+pub struct LaunchParams {
+    /// The seed new runs are played on, if the link sets up a challenge.
+    pub seed: Option<u64>,
+    pub mode: Option<ChallengeMode>,
+    pub theme: Option<Theme>,
+    /// The challenge code the link shares, which also sets the seed.
+    pub challenge: Option<Challenge>,
+}
+
+impl LaunchParams {
+    const SECS_PER_DAY: u64 = 60 * 60 * 24;
+
+    /// Reads the parameters from the current page's URL. Unknown or malformed parameters are
+    /// ignored.
+    #[must_use]
+    pub fn from_url() -> Self {
+        let search = web_sys::window()
+            .and_then(|window| window.location().search().ok())
+            .unwrap_or_default();
+
+        Self::parse(&search)
+    }
+
+    /// Parses a query string like `?seed=1234&mode=daily&theme=mono`. A challenge code takes
+    /// priority over the daily mode, which takes priority over a given seed.
+    #[must_use]
+    pub fn parse(query: &str) -> Self {
+        let mut params = Self::default();
+
+        for pair in query.trim_start_matches('?').split('&') {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+
+            match key {
+                "seed" => {
+                    if let Ok(seed) = value.parse() {
+                        params.seed = Some(seed);
+                    }
+                }
+                "mode" if value == "daily" => params.mode = Some(ChallengeMode::Daily),
+                "challenge" => params.challenge = Challenge::decode(value),
+                "theme" => {
+                    params.theme = Theme::iter().find(|theme| <&str>::from(*theme) == value);
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(challenge) = &params.challenge {
+            params.seed = Some(challenge.seed);
+            params.mode = Some(ChallengeMode::Seeded);
+        } else if params.mode == Some(ChallengeMode::Daily) {
+            params.seed = Some(Self::get_daily_seed());
+        } else if params.seed.is_some() {
+            params.mode = Some(ChallengeMode::Seeded);
+        }
+
+        params
+    }
+
+    /// Returns a line describing the challenge, for the main menu.
+    #[must_use]
+    pub fn get_description(&self) -> Option<String> {
+        if let Some(challenge) = &self.challenge {
+            return Some(format!("challenge {}", challenge.encode()));
+        }
+
+        match (self.mode?, self.seed?) {
+            (ChallengeMode::Daily, _) => Some("daily challenge".to_string()),
+            (ChallengeMode::Seeded, seed) => Some(format!("challenge seed {seed}")),
+        }
+    }
+
+    /// Works out today's seed from the number of days since the Unix epoch.
+    fn get_daily_seed() -> u64 {
+        let days = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() / Self::SECS_PER_DAY)
+            .unwrap_or_default();
+
+        // spread neighbouring days far apart so their maps don't look alike
+        days.wrapping_mul(0x9E37_79B9_7F4A_7C15)
+    }
+}
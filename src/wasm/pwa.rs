@@ -0,0 +1,41 @@
+//! This module wires the web version up as an installable, offline-capable app. The service
+//! worker itself is generated at build time by `pwa/generate-sw.sh`.
+
+use ratzilla::ratatui::{Frame, layout::Rect, style::Stylize, text::Line};
+use web_sys::js_sys::Reflect;
+
+/// Where the generated service worker is served from. The crate version is added so each
+/// release is fetched fresh rather than from the browser's HTTP cache.
+const SERVICE_WORKER_URL: &str = concat!("./sw.js?v=", env!("CARGO_PKG_VERSION"));
+
+/// Registers the service worker that caches the game for offline play. Does nothing where
+/// service workers aren't available, such as pages not served over HTTPS.
+pub fn register_service_worker() {
+    let Some(window) = web_sys::window() else {
+        return;
+    };
+    let navigator = window.navigator();
+
+    if !Reflect::has(&navigator, &"serviceWorker".into()).unwrap_or(false) {
+        return;
+    }
+
+    // the registration finishes in the background, and failing just means no offline play
+    let _ = navigator.service_worker().register(SERVICE_WORKER_URL);
+}
+
+/// Checks if the browser thinks it has a network connection.
+#[must_use]
+pub fn is_online() -> bool {
+    web_sys::window().is_none_or(|window| window.navigator().on_line())
+}
+
+/// Draws a small marker in the top right corner to show the game is running offline.
+pub fn render_offline_indicator(frame: &mut Frame) {
+    let label = " offline ";
+    let width = (label.len() as u16).min(frame.area().width);
+    let x = frame.area().right().saturating_sub(width + 1);
+    let area = Rect::new(x, frame.area().y, width, 1);
+
+    frame.render_widget(Line::from(label.black().on_yellow()), area);
+}
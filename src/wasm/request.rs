@@ -0,0 +1,89 @@
+//! This module sends HTTP requests for the web build, using the browser's `fetch`.
+
+use std::{cell::RefCell, rc::Rc};
+
+use web_sys::{
+    Request, RequestInit, Response,
+    js_sys::Promise,
+    wasm_bindgen::{JsCast, JsValue, closure::Closure},
+};
+
+/// Called with the response body, or an error message, once a request finishes.
+pub type OnResponse = Box<dyn FnOnce(Result<String, String>) + Send>;
+
+type PendingResponse = Rc<RefCell<Option<OnResponse>>>;
+
+/// Sends an HTTP request with an optional JSON body without blocking the game loop.
+// takes `url` by value to match the terminal version
+#[allow(clippy::needless_pass_by_value)]
+pub fn send_request(method: &'static str, url: String, body: Option<String>, on_done: OnResponse) {
+    let on_done: PendingResponse = Rc::new(RefCell::new(Some(on_done)));
+
+    let promise = match start_fetch(method, &url, body) {
+        Ok(promise) => promise,
+        Err(err) => {
+            finish(&on_done, Err(describe(&err)));
+            return;
+        }
+    };
+
+    let text_done = on_done.clone();
+    let on_text = Closure::<dyn FnMut(JsValue)>::new(move |text: JsValue| {
+        finish(&text_done, Ok(text.as_string().unwrap_or_default()));
+    });
+
+    let response_done = on_done.clone();
+    let on_response = Closure::<dyn FnMut(JsValue)>::new(move |response: JsValue| {
+        let Ok(response) = response.dyn_into::<Response>() else {
+            finish(&response_done, Err("Not a response".into()));
+            return;
+        };
+
+        if !response.ok() {
+            finish(&response_done, Err(format!("HTTP {}", response.status())));
+            return;
+        }
+
+        match response.text() {
+            Ok(text) => {
+                let _ = text.then(&on_text);
+            }
+            Err(err) => finish(&response_done, Err(describe(&err))),
+        }
+    });
+
+    let on_error = Closure::<dyn FnMut(JsValue)>::new(move |err: JsValue| {
+        finish(&on_done, Err(describe(&err)));
+    });
+
+    let _ = promise.then2(&on_response, &on_error);
+
+    // The callbacks must live until the browser calls them.
+    on_response.forget();
+    on_error.forget();
+}
+
+fn start_fetch(method: &str, url: &str, body: Option<String>) -> Result<Promise, JsValue> {
+    let init = RequestInit::new();
+    init.set_method(method);
+    if let Some(body) = body {
+        init.set_body(&JsValue::from_str(&body));
+    }
+
+    let request = Request::new_with_str_and_init(url, &init)?;
+    request.headers().set("Content-Type", "application/json")?;
+
+    Ok(web_sys::window()
+        .ok_or_else(|| JsValue::from_str("No window"))?
+        .fetch_with_request(&request))
+}
+
+fn finish(on_done: &PendingResponse, result: Result<String, String>) {
+    if let Some(on_done) = on_done.borrow_mut().take() {
+        on_done(result);
+    }
+}
+
+fn describe(err: &JsValue) -> String {
+    err.as_string().unwrap_or_else(|| "Request failed".into())
+}
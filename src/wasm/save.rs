@@ -0,0 +1,400 @@
+//! This module provides the web build's save backends. Progress is kept in `IndexedDB`, which
+//! has far more room than `localStorage`. `localStorage` is still used as a fallback wherever
+//! `IndexedDB` isn't available, and any saves left there by older builds are moved across on
+//! startup. Each save is stamped with when it was made, so the newest copy of a slot wins
+//! wherever it was kept.
+
+use std::{cell::RefCell, collections::HashMap, rc::Rc, sync::mpsc};
+
+use serde::{Deserialize, Serialize};
+use web_sys::{
+    IdbDatabase, IdbOpenDbRequest, IdbTransactionMode, Storage,
+    js_sys::{Array, Date},
+    wasm_bindgen::{JsCast, JsValue, closure::Closure},
+};
+
+use crate::common::savestore::{MAIN_SLOT, SaveError, SaveStore, read_save, serialize_save};
+use crate::common::upgrades::upgrade::PlayerState;
+
+/// Where localStorage has always kept the main slot, so saves from older builds are still found.
+const SAVE_KEY: &str = "player_state";
+/// Goes in front of the other slots' names to make their localStorage keys, to tell them apart
+/// from anything else the page keeps there.
+const SLOT_KEY_PREFIX: &str = "dispair/saves/";
+const DB_NAME: &str = "dispair";
+const DB_VERSION: u32 = 1;
+const STORE_NAME: &str = "saves";
+
+/// A serialized save, stamped with when it was made.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+struct StampedSave {
+    /// When the save was made, in milliseconds since the Unix epoch. Older builds didn't stamp
+    /// their saves, so those count as older than any that were.
+    saved_at: u64,
+    /// The save's JSON.
+    save: String,
+}
+
+impl StampedSave {
+    fn new(save: String) -> Self {
+        Self {
+            saved_at: Date::now() as u64,
+            save,
+        }
+    }
+
+    /// Reads a stored save, which older builds kept without a stamp.
+    fn parse(raw: String) -> Self {
+        serde_json::from_str(&raw).unwrap_or(Self {
+            saved_at: 0,
+            save: raw,
+        })
+    }
+
+    fn to_raw(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| self.save.clone())
+    }
+}
+
+/// Saves progress to the browser's localStorage.
+#[derive(Clone, Copy, Default)]
+pub struct LocalStorageStore;
+
+impl LocalStorageStore {
+    fn storage() -> Result<Storage, SaveError> {
+        web_sys::window()
+            .ok_or_else(|| SaveError::Storage("there's no window".to_string()))?
+            .local_storage()
+            .ok()
+            .flatten()
+            .ok_or_else(|| SaveError::Storage("local storage isn't available".to_string()))
+    }
+
+    fn get_key(slot: &str) -> String {
+        if slot == MAIN_SLOT {
+            SAVE_KEY.to_string()
+        } else {
+            format!("{SLOT_KEY_PREFIX}{slot}")
+        }
+    }
+
+    /// Returns the save in `slot`, if there is one.
+    fn get_stamped(slot: &str) -> Option<StampedSave> {
+        Self::storage()
+            .ok()?
+            .get_item(&Self::get_key(slot))
+            .ok()?
+            .map(StampedSave::parse)
+    }
+
+    /// Stores an already serialized save in `slot`.
+    fn set_stamped(slot: &str, save: &StampedSave) -> Result<(), SaveError> {
+        Self::storage()?
+            .set_item(&Self::get_key(slot), &save.to_raw())
+            .map_err(|_| SaveError::Storage("local storage is full".to_string()))
+    }
+
+    /// Returns every save, by slot.
+    fn get_all(self) -> HashMap<String, StampedSave> {
+        self.list_slots()
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|slot| Some((slot.clone(), Self::get_stamped(&slot)?)))
+            .collect()
+    }
+}
+
+impl SaveStore for LocalStorageStore {
+    fn save(&self, slot: &str, player_state: &PlayerState) -> Result<(), SaveError> {
+        Self::set_stamped(slot, &StampedSave::new(serialize_save(player_state)?))
+    }
+
+    fn load(&self, slot: &str) -> Result<PlayerState, SaveError> {
+        read_save(&Self::get_stamped(slot).ok_or(SaveError::Missing)?.save)
+    }
+
+    fn list_slots(&self) -> Result<Vec<String>, SaveError> {
+        let storage = Self::storage()?;
+        let length = storage
+            .length()
+            .map_err(|_| SaveError::Storage("local storage couldn't be read".to_string()))?;
+
+        Ok((0..length)
+            .filter_map(|index| storage.key(index).ok().flatten())
+            .filter_map(|key| {
+                if key == SAVE_KEY {
+                    Some(MAIN_SLOT.to_string())
+                } else {
+                    key.strip_prefix(SLOT_KEY_PREFIX).map(str::to_string)
+                }
+            })
+            .collect())
+    }
+
+    fn delete(&self, slot: &str) -> Result<(), SaveError> {
+        Self::storage()?
+            .remove_item(&Self::get_key(slot))
+            .map_err(|_| SaveError::Storage("local storage couldn't be written".to_string()))
+    }
+}
+
+/// Saves progress to `IndexedDB`, keyed by slot.
+///
+/// `IndexedDB` can only be used asynchronously, so the latest saves are also kept in memory: loads
+/// are answered from there, and writes are sent off in the background, with how each went picked
+/// up with [`IndexedDbStore::poll`]. Until the database has opened, saves go to localStorage
+/// instead and are moved across once it is ready.
+pub struct IndexedDbStore {
+    db: Rc<RefCell<Option<IdbDatabase>>>,
+    cached: Rc<RefCell<HashMap<String, StampedSave>>>,
+    fallback: LocalStorageStore,
+    finished: mpsc::Sender<(String, Result<(), SaveError>)>,
+    events: mpsc::Receiver<(String, Result<(), SaveError>)>,
+}
+
+impl IndexedDbStore {
+    /// Creates the store and starts opening the database in the background.
+    #[must_use]
+    pub fn open() -> Self {
+        let fallback = LocalStorageStore;
+        let (finished, events) = mpsc::channel();
+
+        let store = Self {
+            db: Rc::new(RefCell::new(None)),
+            cached: Rc::new(RefCell::new(fallback.get_all())),
+            fallback,
+            finished,
+            events,
+        };
+
+        if let Err(err) = store.start_open() {
+            web_sys::console::warn_2(&"IndexedDB unavailable, using localStorage:".into(), &err);
+        }
+
+        store
+    }
+
+    /// Takes how each save has gone since the last poll, by slot. A save only counts as made
+    /// once the browser has stored it.
+    #[must_use]
+    pub fn poll(&self) -> Vec<(String, Result<(), SaveError>)> {
+        self.events.try_iter().collect()
+    }
+
+    fn start_open(&self) -> Result<(), JsValue> {
+        let request = web_sys::window()
+            .ok_or_else(|| JsValue::from_str("No window"))?
+            .indexed_db()?
+            .ok_or_else(|| JsValue::from_str("IndexedDB unavailable"))?
+            .open_with_u32(DB_NAME, DB_VERSION)?;
+
+        let upgrade_request = request.clone();
+        let on_upgrade = Closure::<dyn FnMut()>::new(move || {
+            if let Some(db) = Self::get_db(&upgrade_request) {
+                let _ = db.create_object_store(STORE_NAME);
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade.as_ref().unchecked_ref()));
+        on_upgrade.forget();
+
+        let success_request = request.clone();
+        let db_ref = self.db.clone();
+        let cached = self.cached.clone();
+        let fallback = self.fallback;
+        let on_success = Closure::<dyn FnMut()>::new(move || {
+            let Some(db) = Self::get_db(&success_request) else {
+                return;
+            };
+
+            if let Err(err) = Self::read_in(&db, &cached, fallback) {
+                web_sys::console::warn_2(&"Failed to read save from IndexedDB:".into(), &err);
+            }
+
+            *db_ref.borrow_mut() = Some(db);
+        });
+        request.set_onsuccess(Some(on_success.as_ref().unchecked_ref()));
+        on_success.forget();
+
+        Ok(())
+    }
+
+    fn get_db(request: &IdbOpenDbRequest) -> Option<IdbDatabase> {
+        request.result().ok()?.dyn_into::<IdbDatabase>().ok()
+    }
+
+    /// Reads the saves out of the database into memory, keeping whichever copy of each slot was
+    /// saved last. Saves in localStorage were either written before the database opened or left
+    /// by an older build, so once the database has been read they're moved into it.
+    fn read_in(
+        db: &IdbDatabase,
+        cached: &Rc<RefCell<HashMap<String, StampedSave>>>,
+        fallback: LocalStorageStore,
+    ) -> Result<(), JsValue> {
+        let store = db
+            .transaction_with_str(STORE_NAME)?
+            .object_store(STORE_NAME)?;
+        let keys_request = store.get_all_keys()?;
+        let request = store.get_all()?;
+
+        let result_request = request.clone();
+        let db = db.clone();
+        let cached = cached.clone();
+        let on_success = Closure::<dyn FnMut()>::new(move || {
+            let (Ok(keys), Ok(values)) = (keys_request.result(), result_request.result()) else {
+                return;
+            };
+
+            let mut cached = cached.borrow_mut();
+            for (key, value) in Array::from(&keys).iter().zip(Array::from(&values).iter()) {
+                if let (Some(slot), Some(value)) = (key.as_string(), value.as_string()) {
+                    keep_newest(&mut cached, slot, StampedSave::parse(value));
+                }
+            }
+
+            for slot in fallback.list_slots().unwrap_or_default() {
+                let Some(newest) = cached.get(&slot).cloned() else {
+                    continue;
+                };
+
+                let moved = slot.clone();
+                let left = LocalStorageStore::get_stamped(&slot);
+                let on_finished = move |result: Result<(), SaveError>| {
+                    // anything saved to localStorage since is left to be moved next time
+                    if result.is_ok() && LocalStorageStore::get_stamped(&moved) == left {
+                        let _ = fallback.delete(&moved);
+                    }
+                };
+                if let Err(err) = Self::put(&db, &slot, &newest, on_finished) {
+                    web_sys::console::warn_2(&"Failed to move save to IndexedDB:".into(), &err);
+                }
+            }
+        });
+        request.set_onsuccess(Some(on_success.as_ref().unchecked_ref()));
+        on_success.forget();
+
+        Ok(())
+    }
+
+    /// Writes a save to `slot` in the database, calling `on_finished` once it has been stored or
+    /// has failed to be.
+    fn put(
+        db: &IdbDatabase,
+        slot: &str,
+        save: &StampedSave,
+        on_finished: impl FnOnce(Result<(), SaveError>) + 'static,
+    ) -> Result<(), JsValue> {
+        let transaction =
+            db.transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)?;
+        transaction
+            .object_store(STORE_NAME)?
+            .put_with_key(&JsValue::from_str(&save.to_raw()), &JsValue::from_str(slot))?;
+
+        // a failed write fires both error and abort, so only the first to fire is passed on
+        let on_finished = Rc::new(RefCell::new(Some(on_finished)));
+        let finish_with = |result: Result<(), SaveError>| {
+            let on_finished = on_finished.clone();
+            Closure::<dyn FnMut()>::new(move || {
+                if let Some(on_finished) = on_finished.borrow_mut().take() {
+                    on_finished(result.clone());
+                }
+            })
+        };
+
+        let on_complete = finish_with(Ok(()));
+        let on_error = finish_with(Err(SaveError::Storage(
+            "the browser couldn't store the save".to_string(),
+        )));
+        let on_abort = finish_with(Err(SaveError::Storage(
+            "storing the save was cancelled".to_string(),
+        )));
+        transaction.set_oncomplete(Some(on_complete.as_ref().unchecked_ref()));
+        transaction.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        transaction.set_onabort(Some(on_abort.as_ref().unchecked_ref()));
+        on_complete.forget();
+        on_error.forget();
+        on_abort.forget();
+
+        Ok(())
+    }
+
+    /// Removes `slot` from the database.
+    fn remove(db: &IdbDatabase, slot: &str) -> Result<(), JsValue> {
+        db.transaction_with_str_and_mode(STORE_NAME, IdbTransactionMode::Readwrite)?
+            .object_store(STORE_NAME)?
+            .delete(&JsValue::from_str(slot))?;
+
+        Ok(())
+    }
+}
+
+impl SaveStore for IndexedDbStore {
+    /// Sends the save off, returning an error only if it couldn't be. Whether it was stored is
+    /// reported later, through [`IndexedDbStore::poll`]. A save the database fails to store is
+    /// kept in localStorage instead, if there's room.
+    fn save(&self, slot: &str, player_state: &PlayerState) -> Result<(), SaveError> {
+        let save = StampedSave::new(serialize_save(player_state)?);
+
+        self.cached
+            .borrow_mut()
+            .insert(slot.to_string(), save.clone());
+
+        if let Some(db) = self.db.borrow().as_ref() {
+            let finished = self.finished.clone();
+            let (saved_slot, saved) = (slot.to_string(), save.clone());
+            let on_finished = move |result: Result<(), SaveError>| {
+                let result =
+                    result.or_else(|_| LocalStorageStore::set_stamped(&saved_slot, &saved));
+                let _ = finished.send((saved_slot, result));
+            };
+
+            if Self::put(db, slot, &save, on_finished).is_ok() {
+                return Ok(());
+            }
+        }
+
+        LocalStorageStore::set_stamped(slot, &save)?;
+        let _ = self.finished.send((slot.to_string(), Ok(())));
+        Ok(())
+    }
+
+    fn load(&self, slot: &str) -> Result<PlayerState, SaveError> {
+        let value = self
+            .cached
+            .borrow()
+            .get(slot)
+            .map(|stamped| stamped.save.clone())
+            .ok_or(SaveError::Missing)?;
+
+        read_save(&value)
+    }
+
+    fn list_slots(&self) -> Result<Vec<String>, SaveError> {
+        Ok(self.cached.borrow().keys().cloned().collect())
+    }
+
+    /// A save deleted before the database has opened is only removed from localStorage.
+    fn delete(&self, slot: &str) -> Result<(), SaveError> {
+        self.cached.borrow_mut().remove(slot);
+        self.fallback.delete(slot)?;
+
+        match self.db.borrow().as_ref() {
+            Some(db) => Self::remove(db, slot).map_err(|err| {
+                SaveError::Storage(
+                    err.as_string()
+                        .unwrap_or_else(|| "the save couldn't be deleted".to_string()),
+                )
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Keeps `save` in `slot` unless what's there was saved later.
+fn keep_newest(cached: &mut HashMap<String, StampedSave>, slot: String, save: StampedSave) {
+    match cached.get(&slot) {
+        Some(kept) if kept.saved_at >= save.saved_at => {}
+        _ => {
+            cached.insert(slot, save);
+        }
+    }
+}
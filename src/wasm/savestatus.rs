@@ -0,0 +1,156 @@
+//! This module keeps track of whether the player's latest progress has been saved in the browser.
+//! Each save is confirmed with a toast, and failed saves are tried again with a growing delay,
+//! rather than interrupting the player with the error screen.
+
+use std::time::Duration;
+
+use ratzilla::ratatui::{
+    Frame,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph, Wrap},
+};
+
+use crate::common::apperror::AppError;
+use crate::common::keybinds::KeyContext;
+use crate::common::popups::popup_area;
+use crate::prelude::Instant;
+
+/// How long a toast stays on screen.
+const TOAST_DURATION: Duration = Duration::from_secs(2);
+/// The delay before the first retry, doubled after each one that fails.
+const FIRST_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// The longest delay between retries.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// A short message about the last save, shown briefly in the corner of the screen.
+struct Toast {
+    message: String,
+    failed: bool,
+    shown_at: Instant,
+}
+
+/// Whether the latest progress has been saved, and when to try again if it hasn't.
+#[derive(Default)]
+pub struct SaveStatus {
+    toast: Option<Toast>,
+    /// How many saves in a row have failed.
+    failures: u32,
+    /// When to try saving again, while the latest progress isn't saved.
+    next_retry: Option<Instant>,
+}
+
+impl SaveStatus {
+    /// Confirms a successful save, and stops any retries.
+    pub fn record_success(&mut self) {
+        if self.failures > 0 {
+            tracing::info!(failures = self.failures, "save recovered");
+        }
+
+        self.failures = 0;
+        self.next_retry = None;
+        self.show_toast("progress saved", false);
+    }
+
+    /// Reports a failed save and schedules the next try, backing off after each failure.
+    pub fn record_failure(&mut self, err: &AppError) {
+        let delay = FIRST_RETRY_DELAY
+            .saturating_mul(2u32.saturating_pow(self.failures))
+            .min(MAX_RETRY_DELAY);
+
+        self.failures = self.failures.saturating_add(1);
+        self.next_retry = Some(Instant::now() + delay);
+
+        tracing::warn!(%err, failures = self.failures, ?delay, "save failed, retrying");
+        self.show_toast(
+            format!("save failed, retrying in {}s", delay.as_secs()),
+            true,
+        );
+    }
+
+    /// Checks whether the latest progress still hasn't been saved.
+    #[must_use]
+    pub fn is_failing(&self) -> bool {
+        self.next_retry.is_some()
+    }
+
+    /// Checks whether it's time to try saving again.
+    #[must_use]
+    pub fn is_retry_due(&self) -> bool {
+        self.next_retry
+            .is_some_and(|next_retry| Instant::now() >= next_retry)
+    }
+
+    fn show_toast(&mut self, message: impl Into<String>, failed: bool) {
+        self.toast = Some(Toast {
+            message: message.into(),
+            failed,
+            shown_at: Instant::now(),
+        });
+    }
+
+    /// Draws the toast for the last save in the top right corner, below the offline indicator,
+    /// until it's been up long enough.
+    pub fn render(&mut self, frame: &mut Frame) {
+        if self
+            .toast
+            .as_ref()
+            .is_some_and(|toast| toast.shown_at.elapsed() >= TOAST_DURATION)
+        {
+            self.toast = None;
+        }
+
+        let Some(toast) = &self.toast else {
+            return;
+        };
+
+        let label = format!(" {} ", toast.message);
+        let width = (label.len() as u16).min(frame.area().width);
+        let x = frame.area().right().saturating_sub(width + 1);
+        let area = Rect::new(x, frame.area().y + 1, width, 1).intersection(frame.area());
+
+        let line = if toast.failed {
+            Line::from(label.white().on_red())
+        } else {
+            Line::from(label.black().on_green())
+        };
+
+        frame.render_widget(line, area);
+    }
+
+    /// Draws the warning shown when a run is about to start while the last one isn't saved.
+    pub fn render_unsaved_warning(&self, frame: &mut Frame) {
+        let area = popup_area(frame.area(), 50, 30);
+
+        let retry_in = self.next_retry.map_or(0, |next_retry| {
+            next_retry
+                .saturating_duration_since(Instant::now())
+                .as_secs()
+        });
+
+        let warning = Paragraph::new(vec![
+            Line::from("your last run hasn't been saved yet"),
+            Line::from(""),
+            Line::from(format!(
+                "trying again in {retry_in}s, the run will start once it's saved"
+            ))
+            .dark_gray(),
+        ])
+        .wrap(Wrap { trim: true })
+        .centered()
+        .block(
+            Block::bordered()
+                .border_set(border::DOUBLE)
+                .title(Line::from(" not saved ").centered())
+                .title_bottom(
+                    Line::from(format!(" {} ", KeyContext::HeldStart.get_hint())).centered(),
+                )
+                .yellow(),
+        );
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(warning, area);
+    }
+}
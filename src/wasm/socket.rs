@@ -0,0 +1,116 @@
+//! This module connects to the co-op relay for the web build, using the browser's `WebSocket`.
+
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+
+use web_sys::{
+    CloseEvent, MessageEvent, WebSocket,
+    wasm_bindgen::{JsCast, JsValue, closure::Closure},
+};
+
+use crate::common::netplay::SocketEvent;
+
+type Events = Rc<RefCell<VecDeque<SocketEvent>>>;
+
+/// A WebSocket connection, sending and receiving text without blocking the game loop. The
+/// connection closes when this is dropped.
+pub struct Socket {
+    connection: Option<WebSocket>,
+    events: Events,
+    // The callbacks must live as long as the browser can call them.
+    _callbacks: Vec<Closure<dyn FnMut(JsValue)>>,
+}
+
+impl Socket {
+    /// Starts connecting to `url`. [`SocketEvent::Open`] arrives once connected.
+    #[must_use]
+    pub fn connect(url: &str) -> Self {
+        let events: Events = Rc::new(RefCell::new(VecDeque::new()));
+
+        let socket = match WebSocket::new(url) {
+            Ok(socket) => socket,
+            Err(err) => {
+                events
+                    .borrow_mut()
+                    .push_back(SocketEvent::Closed(describe(&err)));
+                return Self {
+                    connection: None,
+                    events,
+                    _callbacks: vec![],
+                };
+            }
+        };
+
+        let open_events = events.clone();
+        let on_open = Closure::<dyn FnMut(JsValue)>::new(move |_| {
+            open_events.borrow_mut().push_back(SocketEvent::Open);
+        });
+
+        let message_events = events.clone();
+        let on_message = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            if let Some(text) = event
+                .dyn_into::<MessageEvent>()
+                .ok()
+                .and_then(|event| event.data().as_string())
+            {
+                message_events
+                    .borrow_mut()
+                    .push_back(SocketEvent::Message(text));
+            }
+        });
+
+        let close_events = events.clone();
+        let on_close = Closure::<dyn FnMut(JsValue)>::new(move |event: JsValue| {
+            let reason = event
+                .dyn_into::<CloseEvent>()
+                .ok()
+                .map(|event| event.reason())
+                .filter(|reason| !reason.is_empty())
+                .unwrap_or_else(|| "the connection closed".into());
+            close_events
+                .borrow_mut()
+                .push_back(SocketEvent::Closed(reason));
+        });
+
+        socket.set_onopen(Some(on_open.as_ref().unchecked_ref()));
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+
+        Self {
+            connection: Some(socket),
+            events,
+            _callbacks: vec![on_open, on_message, on_close],
+        }
+    }
+
+    /// Sends `text` once connected. Text sent after the connection closes is dropped.
+    // takes `text` by value to match the terminal version
+    #[allow(clippy::needless_pass_by_value)]
+    pub fn send(&self, text: String) {
+        if let Some(socket) = &self.connection {
+            let _ = socket.send_with_str(&text);
+        }
+    }
+
+    /// Takes everything that has happened on the connection since the last poll.
+    #[must_use]
+    pub fn poll(&self) -> Vec<SocketEvent> {
+        self.events.borrow_mut().drain(..).collect()
+    }
+}
+
+impl Drop for Socket {
+    fn drop(&mut self) {
+        if let Some(socket) = &self.connection {
+            // the callbacks are about to be dropped, so the browser mustn't call them
+            socket.set_onopen(None);
+            socket.set_onmessage(None);
+            socket.set_onclose(None);
+            let _ = socket.close();
+        }
+    }
+}
+
+fn describe(err: &JsValue) -> String {
+    err.as_string()
+        .unwrap_or_else(|| "couldn't open the connection".into())
+}
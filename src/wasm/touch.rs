@@ -0,0 +1,209 @@
+//! This module provides touch controls for the web build, so it can be played on phones. Touches
+//! are turned into the same key events the keyboard sends: a D-pad in the corner of the screen
+//! sends movement and menu keys, and a tap anywhere else confirms.
+
+use ratzilla::event::{KeyCode, KeyEvent};
+use ratzilla::ratatui::{
+    Frame,
+    layout::{Position, Rect},
+    style::Style,
+    symbols::border,
+    text::Line,
+    widgets::{Block, Clear, Paragraph},
+};
+
+use crate::prelude::{Duration, Instant};
+
+/// A button on the on-screen D-pad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TouchButton {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+}
+
+impl TouchButton {
+    /// The buttons and where they sit on the D-pad, as (column, row) in a 3x3 grid.
+    const LAYOUT: [(TouchButton, u16, u16); 6] = [
+        (TouchButton::Up, 1, 0),
+        (TouchButton::Left, 0, 1),
+        (TouchButton::Confirm, 1, 1),
+        (TouchButton::Right, 2, 1),
+        (TouchButton::Back, 0, 2),
+        (TouchButton::Down, 1, 2),
+    ];
+
+    fn get_key(self) -> KeyCode {
+        match self {
+            TouchButton::Up => KeyCode::Char('w'),
+            TouchButton::Down => KeyCode::Char('s'),
+            TouchButton::Left => KeyCode::Char('a'),
+            TouchButton::Right => KeyCode::Char('d'),
+            TouchButton::Confirm => KeyCode::Enter,
+            TouchButton::Back => KeyCode::Esc,
+        }
+    }
+
+    fn get_label(self) -> &'static str {
+        match self {
+            TouchButton::Up => "▲",
+            TouchButton::Down => "▼",
+            TouchButton::Left => "◀",
+            TouchButton::Right => "▶",
+            TouchButton::Confirm => "OK",
+            TouchButton::Back => "ESC",
+        }
+    }
+
+    /// Whether holding the button keeps sending its key, like holding down a movement key.
+    fn repeats(self) -> bool {
+        matches!(
+            self,
+            TouchButton::Up | TouchButton::Down | TouchButton::Left | TouchButton::Right
+        )
+    }
+}
+
+/// The state of the touch controls: whether they're shown, and which button is held down.
+#[derive(Debug, Clone, Default)]
+pub struct TouchControls {
+    /// Whether the D-pad is drawn. It appears after the first touch, so keyboard players never
+    /// see it.
+    visible: bool,
+    /// The button being held, and when its key was last sent.
+    held: Option<(TouchButton, Instant)>,
+}
+
+impl TouchControls {
+    const BUTTON_WIDTH: u16 = 7;
+    const BUTTON_HEIGHT: u16 = 3;
+    /// How often a held button sends its key again.
+    const REPEAT_INTERVAL: Duration = Duration::from_millis(150);
+
+    /// Handles a touch at the given cell of the screen, returning the key it stands for.
+    pub fn press(&mut self, area: Rect, column: u16, row: u16) -> KeyEvent {
+        self.visible = true;
+
+        let button = Self::get_button_at(area, column, row);
+
+        self.held = button
+            .filter(|button| button.repeats())
+            .map(|button| (button, Instant::now()));
+
+        to_key_event(button.map_or(KeyCode::Enter, TouchButton::get_key))
+    }
+
+    /// Lets go of any held button, returning its key so the game can be told it was released.
+    pub fn release(&mut self) -> Option<KeyEvent> {
+        self.held
+            .take()
+            .map(|(button, _)| to_key_event(button.get_key()))
+    }
+
+    /// Returns the held button's key again once it has been held long enough to repeat.
+    pub fn take_repeat(&mut self) -> Option<KeyEvent> {
+        let (button, last_sent) = self.held.as_mut()?;
+
+        if last_sent.elapsed() < Self::REPEAT_INTERVAL {
+            return None;
+        }
+
+        *last_sent = Instant::now();
+        Some(to_key_event(button.get_key()))
+    }
+
+    /// Draws the D-pad in the bottom left corner of the screen, once touch has been used.
+    pub fn render(&self, frame: &mut Frame) {
+        if !self.visible {
+            return;
+        }
+
+        for (button, button_area) in Self::get_button_areas(frame.area()) {
+            let style = if self.held.is_some_and(|(held, _)| held == button) {
+                Style::new().bold()
+            } else {
+                Style::new().dark_gray()
+            };
+
+            let widget = Paragraph::new(Line::from(button.get_label()).centered())
+                .style(style)
+                .block(Block::bordered().border_set(border::ROUNDED));
+
+            frame.render_widget(Clear, button_area);
+            frame.render_widget(widget, button_area);
+        }
+    }
+
+    /// Finds which D-pad button, if any, covers the given cell.
+    fn get_button_at(area: Rect, column: u16, row: u16) -> Option<TouchButton> {
+        let position = Position::new(column, row);
+
+        Self::get_button_areas(area)
+            .into_iter()
+            .find(|(_, button_area)| button_area.contains(position))
+            .map(|(button, _)| button)
+    }
+
+    /// Lays out the D-pad buttons in the bottom left corner of the area.
+    fn get_button_areas(area: Rect) -> Vec<(TouchButton, Rect)> {
+        let pad_height = Self::BUTTON_HEIGHT * 3;
+        let top = area.bottom().saturating_sub(pad_height + 1).max(area.y);
+        let left = area.x + 1;
+
+        TouchButton::LAYOUT
+            .iter()
+            .map(|&(button, column, row)| {
+                let button_area = Rect::new(
+                    left + column * Self::BUTTON_WIDTH,
+                    top + row * Self::BUTTON_HEIGHT,
+                    Self::BUTTON_WIDTH,
+                    Self::BUTTON_HEIGHT,
+                )
+                .intersection(area);
+
+                (button, button_area)
+            })
+            .collect()
+    }
+}
+
+/// Converts a browser touch position, in pixels, to the cell of the screen it lands on.
+#[must_use]
+pub fn get_touch_cell(area: Rect, client_x: i32, client_y: i32) -> (u16, u16) {
+    let Some(window) = web_sys::window() else {
+        return (0, 0);
+    };
+
+    let window_width = window
+        .inner_width()
+        .ok()
+        .and_then(|width| width.as_f64())
+        .unwrap_or(1.)
+        .max(1.);
+    let window_height = window
+        .inner_height()
+        .ok()
+        .and_then(|height| height.as_f64())
+        .unwrap_or(1.)
+        .max(1.);
+
+    let column = f64::from(client_x) / window_width * f64::from(area.width);
+    let row = f64::from(client_y) / window_height * f64::from(area.height);
+
+    (
+        (column as u16).min(area.width.saturating_sub(1)),
+        (row as u16).min(area.height.saturating_sub(1)),
+    )
+}
+
+fn to_key_event(code: KeyCode) -> KeyEvent {
+    KeyEvent {
+        code,
+        ctrl: false,
+        alt: false,
+        shift: false,
+    }
+}